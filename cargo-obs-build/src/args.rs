@@ -1,3 +1,4 @@
+use cargo_obs_build::LinuxObsSource;
 use clap::Parser;
 use std::path::PathBuf;
 
@@ -58,6 +59,23 @@ pub struct BuildArgs {
     /// If .pdb files should be removed from the final output, this reduces size significantly
     #[arg(long, default_value_t = false)]
     pub remove_pdbs: bool,
+
+    /// Where the OBS libraries are taken from when targeting Linux: auto, deb, rpm, flatpak or source
+    #[arg(long, default_value = "auto")]
+    pub linux_source: LinuxObsSource,
+
+    /// The lock file that records the release tag and asset hashes, defaults to `obs-build.lock`
+    #[arg(long)]
+    pub lock_file: Option<PathBuf>,
+
+    /// Fail instead of updating the lock file if the tag or the downloaded assets do not match it
+    #[arg(long, default_value_t = false)]
+    pub locked: bool,
+
+    /// Only keep these plugins and their data, e.g. `--plugins win-capture,obs-ffmpeg,obs-x264`.
+    /// Defaults to the `libobs-plugins` array in the Cargo.toml metadata or all plugins.
+    #[arg(long, value_delimiter = ',')]
+    pub plugins: Option<Vec<String>>,
 }
 
 #[cfg(target_os = "linux")]
@@ -24,16 +24,19 @@ use log::{debug, info};
 use log::{error, trace};
 use sha2::{Digest, Sha256};
 
-use crate::git::ReleaseInfo;
+use crate::{git::ReleaseInfo, lockfile::ObsBuildLock};
 
 const DEFAULT_REQ_TIMEOUT: u64 = 60 * 60;
 
 /// Downloads OBS binaries and optionally debug symbols.
+/// The hashes of the downloads are checked against and recorded in the lock.
 /// Returns (main_binary_path, Option<debug_symbols_path>).
 pub fn download_binaries(
     build_dir: &Path,
     info: &ReleaseInfo,
     strip_debug: bool,
+    lock: &mut ObsBuildLock,
+    locked: bool,
 ) -> anyhow::Result<(PathBuf, Option<PathBuf>)> {
     // Determine platform-specific search criteria based on TARGET platform
     // Priority: OBS_BUILD_TARGET > CARGO_CFG_TARGET_OS > host platform
@@ -113,9 +116,11 @@ pub fn download_binaries(
         error!("No checksum found for {}", name);
     }
 
+    lock.check_asset(name, &hash, locked)?;
+
     // Download debug symbols if not stripping
     let debug_symbols_path = if !strip_debug {
-        download_debug_symbols(build_dir, info, &target_os, arch_name)?
+        download_debug_symbols(build_dir, info, &target_os, arch_name, lock, locked)?
     } else {
         None
     };
@@ -129,6 +134,8 @@ fn download_debug_symbols(
     info: &ReleaseInfo,
     target_os: &str,
     arch_name: &str,
+    lock: &mut ObsBuildLock,
+    locked: bool,
 ) -> anyhow::Result<Option<PathBuf>> {
     // Determine debug symbols file pattern based on platform
     let (debug_marker, debug_extension, output_filename) = if target_os == "macos" {
@@ -177,6 +184,8 @@ fn download_debug_symbols(
         error!("No checksum found for debug symbols: {}", name);
     }
 
+    lock.check_asset(name, &hash, locked)?;
+
     Ok(Some(download_path))
 }
 
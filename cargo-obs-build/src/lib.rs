@@ -18,15 +18,20 @@ use tar::Archive;
 use xz2::read::XzDecoder;
 use zip::ZipArchive;
 
+pub use linux::LinuxObsSource;
+pub use lockfile::{ObsBuildLock, DEFAULT_LOCK_FILE};
 pub use metadata::get_meta_info;
 
 mod download;
 mod git;
 mod lib_version;
+mod linux;
 mod lock;
+mod lockfile;
 #[cfg(target_os = "macos")]
 mod macos;
 mod metadata;
+mod plugins;
 mod util;
 
 /// Check if we're running in a CI environment
@@ -110,6 +115,22 @@ pub struct ObsBuildConfig {
 
     /// If set, PDBs will be deleted after extraction to save space, saving disk space.
     pub remove_pdbs: bool,
+
+    /// Where the OBS libraries are taken from when targeting Linux
+    pub linux_source: LinuxObsSource,
+
+    /// The lock file that records the release tag and asset hashes, defaults to [`DEFAULT_LOCK_FILE`]
+    pub lock_file: Option<PathBuf>,
+
+    /// When set, the build fails instead of updating the lock file if the tag or the hash of
+    /// a downloaded asset does not match it
+    pub locked: bool,
+
+    /// The plugins that should be kept in the output directory, e.g. `win-capture`, `obs-ffmpeg`
+    /// and `obs-x264`. All other plugins and their data are removed. If this is set to None,
+    /// it defaults to the `libobs-plugins` array in the `Cargo.toml` metadata and keeps all
+    /// plugins if that is not set either.
+    pub plugins: Option<Vec<String>>,
 }
 
 impl Default for ObsBuildConfig {
@@ -124,6 +145,10 @@ impl Default for ObsBuildConfig {
             tag: None,
             skip_compatibility_check: false,
             remove_pdbs: false,
+            linux_source: LinuxObsSource::default(),
+            lock_file: None,
+            locked: false,
+            plugins: None,
         }
     }
 }
@@ -144,7 +169,8 @@ impl Default for ObsBuildConfig {
 /// ```
 ///
 /// This is equivalent to calling `build_obs_binaries()` with default configuration
-/// and the out_dir set to `$OUT_DIR/../../obs-binaries`.
+/// and the out_dir set to `$OUT_DIR/../../obs-binaries`. Set the `OBS_BUILD_LOCKED`
+/// environment variable to build in locked mode.
 pub fn install() -> anyhow::Result<()> {
     use std::env;
 
@@ -158,8 +184,10 @@ pub fn install() -> anyhow::Result<()> {
         .and_then(|p| p.parent())
         .ok_or_else(|| anyhow::anyhow!("Failed to determine target directory from OUT_DIR"))?;
 
+    println!("cargo:rerun-if-env-changed=OBS_BUILD_LOCKED");
     let config = ObsBuildConfig {
         out_dir: target_dir.to_path_buf(),
+        locked: env::var("OBS_BUILD_LOCKED").is_ok_and(|v| v != "0"),
         ..Default::default()
     };
 
@@ -183,20 +211,16 @@ pub fn install() -> anyhow::Result<()> {
 /// - Caching to avoid re-downloads
 /// - Locking to prevent concurrent builds
 /// - Copying binaries to the target directory
+/// - Pinning the release tag and asset hashes in the lock file
+///
+/// When targeting Linux, the binaries are taken from the distribution packages or built
+/// from source, see [`LinuxObsSource`].
 pub fn build_obs_binaries(config: ObsBuildConfig) -> anyhow::Result<()> {
-    //TODO For build scripts, we should actually check the TARGET env var instead of just erroring out on linux, but I don't think anyone will be cross-compiling
-
     // Check if we are targeting Linux (not host Linux, but target Linux)
-    // We allow running on Linux if targeting Windows/macOS (cross-compilation)
     let target_os = std::env::var("OBS_BUILD_TARGET_OS")
         .or_else(|_| std::env::var("CARGO_CFG_TARGET_OS"))
         .unwrap_or_else(|_| std::env::consts::OS.to_string());
 
-    if target_os == "linux" {
-        // The case for the "install" subcommand is handled before calling this function
-        return Err(anyhow::anyhow!("Building OBS Studio from source is required on Linux. You can install binaries by running `cargo-obs-build install` separately before building your project."));
-    }
-
     let ObsBuildConfig {
         mut cache_dir,
         repo_id,
@@ -207,14 +231,35 @@ pub fn build_obs_binaries(config: ObsBuildConfig) -> anyhow::Result<()> {
         override_zip,
         skip_compatibility_check,
         remove_pdbs,
+        linux_source,
+        lock_file,
+        locked,
+        mut plugins,
     } = config;
 
     // Get metadata which may update cache_dir and tag
     metadata::get_meta_info(&mut cache_dir, &mut tag)?;
     let cache_dir = cache_dir.unwrap_or_else(|| PathBuf::from("obs-build"));
+    if plugins.is_none() {
+        plugins = metadata::get_meta_plugins()?;
+    }
+
+    let lock_file = lock_file.unwrap_or_else(|| PathBuf::from(DEFAULT_LOCK_FILE));
+    let existing_lock = ObsBuildLock::read(&lock_file)?;
+    if locked && existing_lock.is_none() {
+        bail!(
+            "The lock file {} does not exist, build once without --locked to create it",
+            lock_file.display()
+        );
+    }
 
     let mut obs_ver = None;
     let repo_id = repo_id.unwrap_or_else(|| "obsproject/obs-studio".to_string());
+    if let (None, Some(lock)) = (&tag, &existing_lock) {
+        info!("Using OBS Studio {} from {}", lock.tag, lock_file.display());
+        tag = Some(lock.tag.clone());
+    }
+
     if tag.is_none() {
         obs_ver = Some(get_lib_obs_version()?);
         let (major, minor, patch) = obs_ver.as_ref().unwrap();
@@ -264,6 +309,18 @@ pub fn build_obs_binaries(config: ObsBuildConfig) -> anyhow::Result<()> {
         tag
     };
 
+    let mut lock = match &existing_lock {
+        Some(lock) if lock.tag == tag && lock.repository == repo_id => lock.clone(),
+        Some(lock) if locked => bail!(
+            "The lock file pins {} {}, but {} {} was requested. Refusing to continue because of --locked",
+            lock.repository,
+            lock.tag,
+            repo_id,
+            tag
+        ),
+        _ => ObsBuildLock::new(&repo_id, &tag),
+    };
+
     if !skip_compatibility_check {
         let (major, minor, patch) = if let Some(v) = obs_ver {
             v
@@ -313,25 +370,54 @@ pub fn build_obs_binaries(config: ObsBuildConfig) -> anyhow::Result<()> {
     }
 
     let build_out = repo_dir.join("build_out");
-    let lock_file = cache_dir.join(format!("{}.lock", tag));
+    let build_lock_file = cache_dir.join(format!("{}.lock", tag));
     let success_file = repo_dir.join(".success");
 
-    wait_for_lock(&lock_file)?;
+    wait_for_lock(&build_lock_file)?;
 
     if !success_file.is_file() || rebuild {
-        let lock = acquire_lock(&lock_file)?;
+        let build_lock = acquire_lock(&build_lock_file)?;
         if repo_exists || rebuild {
             debug!("Cleaning up old build...");
             delete_all_except(&repo_dir, None)?;
         }
 
-        debug!("Fetching {} version of OBS Studio...", tag);
+        if target_os == "linux" {
+            if override_zip.is_some() {
+                warn!("Override zips are not supported on Linux and will be ignored");
+            }
 
-        let release = fetch_release(&repo_id, &Some(tag.clone()), &cache_dir)?;
-        build_obs(release, &build_out, browser, remove_pdbs, override_zip)?;
+            linux::build_obs_linux(
+                linux_source,
+                &repo_id,
+                &tag,
+                &repo_dir.join("work"),
+                &build_out,
+                browser,
+            )?;
+            clean_up_files(&build_out, remove_pdbs, browser)?;
+        } else {
+            debug!("Fetching {} version of OBS Studio...", tag);
+
+            let release = fetch_release(&repo_id, &Some(tag.clone()), &cache_dir)?;
+            build_obs(
+                release,
+                &build_out,
+                browser,
+                remove_pdbs,
+                override_zip,
+                &mut lock,
+                locked,
+            )?;
+        }
 
         File::create(&success_file)?;
-        drop(lock);
+        drop(build_lock);
+    }
+
+    if existing_lock.as_ref() != Some(&lock) {
+        info!("Writing lock file {}", lock_file.display());
+        lock.write(&lock_file)?;
     }
 
     info!(
@@ -345,6 +431,17 @@ pub fn build_obs_binaries(config: ObsBuildConfig) -> anyhow::Result<()> {
     #[cfg(target_os = "macos")]
     macos::setup_macos_files(&target_out_dir)?;
 
+    if let Some(plugins) = &plugins {
+        plugins::trim_plugins(&target_out_dir, plugins)?;
+    }
+
+    // Only count what has been installed, the output directory also contains the build of the user
+    let runtime_size: u64 = fs::read_dir(&build_out)?
+        .filter_map(|e| e.ok())
+        .map(|e| plugins::size_of(&target_out_dir.join(e.file_name())))
+        .sum();
+    info!("OBS runtime size: {}", plugins::format_size(runtime_size));
+
     info!("Done!");
 
     Ok(())
@@ -384,13 +481,15 @@ fn build_obs(
     include_browser: bool,
     remove_pdbs: bool,
     override_zip: Option<PathBuf>,
+    lock: &mut ObsBuildLock,
+    locked: bool,
 ) -> anyhow::Result<()> {
     fs::create_dir_all(build_out)?;
 
     let (obs_path, debug_symbols_path) = if let Some(e) = override_zip {
         (e, None)
     } else {
-        download_binaries(build_out, &release, remove_pdbs)?
+        download_binaries(build_out, &release, remove_pdbs, lock, locked)?
     };
 
     info!("Extracting OBS Studio binaries...");
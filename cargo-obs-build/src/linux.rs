@@ -0,0 +1,445 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process::Command,
+    str::FromStr,
+};
+
+use anyhow::{anyhow, bail};
+use log::{debug, info, warn};
+use walkdir::WalkDir;
+
+use crate::util::copy_to_dir;
+
+/// The id of the official OBS Studio flatpak
+const FLATPAK_ID: &str = "com.obsproject.Studio";
+
+/// Where the OBS libraries are taken from when targeting Linux
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LinuxObsSource {
+    /// Uses the package manager of the system (apt, then dnf), falls back to building from source
+    #[default]
+    Auto,
+    /// Downloads the `.deb` packages with `apt-get download`, no root required
+    Deb,
+    /// Downloads the `.rpm` packages with `dnf download`, no root required
+    Rpm,
+    /// Copies the libraries from an installed OBS Studio flatpak. These are linked against
+    /// the flatpak runtime, so they may not load on every system.
+    Flatpak,
+    /// Clones the given tag and builds libobs and its plugins without the frontend
+    Source,
+}
+
+impl FromStr for LinuxObsSource {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "auto" => Ok(Self::Auto),
+            "deb" => Ok(Self::Deb),
+            "rpm" => Ok(Self::Rpm),
+            "flatpak" => Ok(Self::Flatpak),
+            "source" => Ok(Self::Source),
+            _ => Err(format!(
+                "Unknown Linux source '{}', expected one of auto, deb, rpm, flatpak or source",
+                s
+            )),
+        }
+    }
+}
+
+fn command_exists(name: &str) -> bool {
+    Command::new(name).arg("--version").output().is_ok()
+}
+
+fn run(command: &mut Command) -> anyhow::Result<()> {
+    debug!("Running {:?}", command);
+    let status = command
+        .status()
+        .map_err(|e| anyhow!("Failed to run {:?}: {}", command.get_program(), e))?;
+
+    if !status.success() {
+        bail!("{:?} failed with {}", command.get_program(), status);
+    }
+
+    Ok(())
+}
+
+/// Assembles the OBS libraries, plugins and data in `build_out`, using the same layout
+/// as on Windows: the libraries next to the executable, the plugins in `obs-plugins` and
+/// the data in `data/libobs` and `data/obs-plugins`.
+///
+/// `work_dir` is used for downloads and the source build and removed afterwards.
+pub fn build_obs_linux(
+    source: LinuxObsSource,
+    repo_id: &str,
+    tag: &str,
+    work_dir: &Path,
+    build_out: &Path,
+    include_browser: bool,
+) -> anyhow::Result<()> {
+    let source = match source {
+        LinuxObsSource::Auto if command_exists("apt-get") => LinuxObsSource::Deb,
+        LinuxObsSource::Auto if command_exists("dnf") => LinuxObsSource::Rpm,
+        LinuxObsSource::Auto => LinuxObsSource::Source,
+        source => source,
+    };
+
+    info!("Assembling OBS Studio {} for Linux from {:?}", tag, source);
+    fs::create_dir_all(work_dir)?;
+
+    let (root, version) = match source {
+        LinuxObsSource::Deb => extract_debs(work_dir)?,
+        LinuxObsSource::Rpm => extract_rpms(work_dir)?,
+        LinuxObsSource::Flatpak => flatpak_files()?,
+        LinuxObsSource::Source | LinuxObsSource::Auto => (
+            build_from_source(repo_id, tag, work_dir, include_browser)?,
+            None,
+        ),
+    };
+
+    if let Some(version) = version {
+        check_package_version(&version, tag);
+    }
+
+    assemble(&root, build_out)?;
+
+    if let Err(e) = fs::remove_dir_all(work_dir) {
+        warn!("Could not remove {}: {}", work_dir.display(), e);
+    }
+
+    Ok(())
+}
+
+/// Returns the version of a `.deb` or `.rpm` package from its file name,
+/// e.g. `obs-studio_1%3a30.0.2+dfsg-3_amd64.deb` or `obs-studio-30.1.2-1.fc40.x86_64.rpm`.
+fn package_version(file_name: &str) -> Option<String> {
+    let version = if let Some(name) = file_name.strip_suffix(".deb") {
+        name.split('_').nth(1)?
+    } else {
+        let name = file_name.strip_suffix(".rpm")?;
+        let mut parts = name.rsplitn(3, '-');
+        parts.next()?;
+        parts.next()?
+    };
+
+    // apt escapes the colon of the epoch in file names
+    let version = version
+        .split_once(':')
+        .or_else(|| version.split_once("%3a"))
+        .map_or(version, |(_, v)| v);
+    let version: String = version
+        .chars()
+        .take_while(|c| c.is_ascii_digit() || *c == '.')
+        .collect();
+
+    (!version.is_empty()).then_some(version)
+}
+
+fn check_package_version(version: &str, tag: &str) {
+    let major_minor = |v: &str| {
+        v.trim_start_matches('v')
+            .split('.')
+            .take(2)
+            .collect::<Vec<_>>()
+            .join(".")
+    };
+
+    if major_minor(version) != major_minor(tag) {
+        warn!(
+            "The packaged OBS Studio {} does not match the expected version {}, use the `source` Linux source to build the matching version",
+            version, tag
+        );
+    } else {
+        info!("Using packaged OBS Studio {}", version);
+    }
+}
+
+/// Downloads the packages one by one, as the names differ between distributions and
+/// `apt-get download` / `dnf download` fail on the first unknown package.
+fn download_packages(
+    download_dir: &Path,
+    required: &str,
+    optional: &[&str],
+    download: impl Fn(&str) -> Command,
+) -> anyhow::Result<()> {
+    fs::create_dir_all(download_dir)?;
+    run(&mut download(required))
+        .map_err(|e| anyhow!("Failed to download the {} package: {}", required, e))?;
+
+    for package in optional {
+        if let Err(e) = run(&mut download(package)) {
+            debug!("Skipping optional package {}: {}", package, e);
+        }
+    }
+
+    Ok(())
+}
+
+fn packages_in(dir: &Path, extension: &str) -> anyhow::Result<Vec<PathBuf>> {
+    let mut packages = fs::read_dir(dir)?
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.extension().is_some_and(|e| e == extension))
+        .collect::<Vec<_>>();
+
+    packages.sort();
+    Ok(packages)
+}
+
+fn extract_debs(work_dir: &Path) -> anyhow::Result<(PathBuf, Option<String>)> {
+    let download_dir = work_dir.join("packages");
+    let root = work_dir.join("root");
+
+    // The OBS PPA ships everything in `obs-studio`, Debian and Ubuntu split libobs and the plugins
+    download_packages(
+        &download_dir,
+        "obs-studio",
+        &["libobs0t64", "libobs0", "obs-plugins", "libobs-dev"],
+        |package| {
+            let mut command = Command::new("apt-get");
+            command
+                .arg("download")
+                .arg(package)
+                .current_dir(&download_dir);
+            command
+        },
+    )?;
+
+    let mut version = None;
+    for package in packages_in(&download_dir, "deb")? {
+        let file_name = package.file_name().unwrap_or_default().to_string_lossy();
+        if file_name.starts_with("obs-studio_") {
+            version = package_version(&file_name);
+        }
+
+        run(Command::new("dpkg-deb").arg("-x").arg(&package).arg(&root))?;
+    }
+
+    Ok((root, version))
+}
+
+fn extract_rpms(work_dir: &Path) -> anyhow::Result<(PathBuf, Option<String>)> {
+    let download_dir = work_dir.join("packages");
+    let root = work_dir.join("root");
+
+    download_packages(
+        &download_dir,
+        "obs-studio",
+        &["obs-studio-libs", "obs-studio-plugin-x264"],
+        |package| {
+            let mut command = Command::new("dnf");
+            command
+                .arg("download")
+                .arg("--destdir")
+                .arg(&download_dir)
+                .arg(package);
+            command
+        },
+    )?;
+
+    fs::create_dir_all(&root)?;
+    let mut version = None;
+    for package in packages_in(&download_dir, "rpm")? {
+        let file_name = package.file_name().unwrap_or_default().to_string_lossy();
+        if file_name.starts_with("obs-studio-") && version.is_none() {
+            version = package_version(&file_name);
+        }
+
+        run(Command::new("sh")
+            .arg("-c")
+            .arg("rpm2cpio \"$0\" | cpio -idm --quiet")
+            .arg(&package)
+            .current_dir(&root))?;
+    }
+
+    Ok((root, version))
+}
+
+fn flatpak_files() -> anyhow::Result<(PathBuf, Option<String>)> {
+    let output = Command::new("flatpak")
+        .arg("info")
+        .arg("--show-location")
+        .arg(FLATPAK_ID)
+        .output()
+        .map_err(|e| anyhow!("Failed to run flatpak: {}", e))?;
+
+    if !output.status.success() {
+        bail!(
+            "The OBS Studio flatpak is not installed, install it with `flatpak install flathub {}`",
+            FLATPAK_ID
+        );
+    }
+
+    let location = String::from_utf8_lossy(&output.stdout).trim().to_string();
+
+    let version = Command::new("flatpak")
+        .arg("info")
+        .arg("--show-metadata")
+        .arg(FLATPAK_ID)
+        .output()
+        .ok()
+        .and_then(|o| {
+            String::from_utf8_lossy(&o.stdout)
+                .lines()
+                .find_map(|l| l.strip_prefix("version=").map(|v| v.to_string()))
+        });
+
+    warn!("The libraries of the flatpak are linked against its runtime and may not load on every system");
+    Ok((PathBuf::from(location).join("files"), version))
+}
+
+fn build_from_source(
+    repo_id: &str,
+    tag: &str,
+    work_dir: &Path,
+    include_browser: bool,
+) -> anyhow::Result<PathBuf> {
+    let source_dir = work_dir.join("obs-studio");
+    let build_dir = work_dir.join("build");
+    let prefix = work_dir.join("install");
+
+    if !source_dir.is_dir() {
+        info!("Cloning {} at {}...", repo_id, tag);
+        run(Command::new("git")
+            .arg("clone")
+            .arg("--recursive")
+            .arg("--depth")
+            .arg("1")
+            .arg("--branch")
+            .arg(tag)
+            .arg(format!("https://github.com/{}.git", repo_id))
+            .arg(&source_dir))?;
+    }
+
+    let on_off = |enabled: bool| if enabled { "ON" } else { "OFF" };
+
+    info!("Configuring OBS Studio, the dependencies of `cargo-obs-build install` are required...");
+    let mut configure = Command::new("cmake");
+    configure
+        .arg("-S")
+        .arg(&source_dir)
+        .arg("-B")
+        .arg(&build_dir)
+        .arg("-G")
+        .arg("Ninja")
+        .arg("-DCMAKE_BUILD_TYPE=Release")
+        .arg(format!("-DCMAKE_INSTALL_PREFIX={}", prefix.display()))
+        .arg("-DCMAKE_INSTALL_LIBDIR=lib")
+        .arg("-DCMAKE_INSTALL_RPATH=$ORIGIN")
+        .arg("-DOBS_COMPILE_DEPRECATION_AS_WARNING=ON")
+        .arg("-DENABLE_UI=OFF")
+        .arg("-DENABLE_FRONTEND=OFF")
+        .arg("-DENABLE_SCRIPTING=OFF")
+        .arg("-DENABLE_WEBSOCKET=OFF")
+        .arg("-DENABLE_WEBRTC=OFF")
+        .arg("-DENABLE_AJA=OFF")
+        .arg("-DENABLE_VST=OFF")
+        .arg(format!("-DENABLE_BROWSER={}", on_off(include_browser)));
+
+    if include_browser {
+        match env::var("CEF_ROOT_DIR") {
+            Ok(cef) => {
+                configure.arg(format!("-DCEF_ROOT_DIR={}", cef));
+            }
+            Err(_) => warn!("CEF_ROOT_DIR is not set, the browser source may fail to configure"),
+        }
+    }
+
+    run(&mut configure)?;
+
+    info!("Building OBS Studio...");
+    run(Command::new("cmake")
+        .arg("--build")
+        .arg(&build_dir)
+        .arg("--parallel"))?;
+    run(Command::new("cmake").arg("--install").arg(&build_dir))?;
+
+    Ok(prefix)
+}
+
+/// Copies the libraries, plugins, helper binaries and data from an installation prefix
+/// (`usr/` of a package, the flatpak `files/` or the source build prefix) into `build_out`.
+fn assemble(root: &Path, build_out: &Path) -> anyhow::Result<()> {
+    let lib_dir = WalkDir::new(root)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .find(|e| {
+            let name = e.file_name().to_string_lossy();
+            name.starts_with("libobs.so")
+                && !e
+                    .path()
+                    .components()
+                    .any(|c| c.as_os_str() == "obs-plugins")
+        })
+        .and_then(|e| e.path().parent().map(|p| p.to_path_buf()))
+        .ok_or_else(|| anyhow!("No libobs.so found in {}", root.display()))?;
+
+    let data_dir = [root.join("usr/share/obs"), root.join("share/obs")]
+        .into_iter()
+        .find(|p| p.is_dir())
+        .ok_or_else(|| anyhow!("No OBS data found in {}", root.display()))?;
+
+    fs::create_dir_all(build_out)?;
+    debug!("Copying libraries from {}", lib_dir.display());
+    for entry in fs::read_dir(&lib_dir)? {
+        let path = entry?.path();
+        let name = path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
+        if name.starts_with("libobs") && name.contains(".so") {
+            copy_file(&path, &build_out.join(&name))?;
+        }
+    }
+
+    // The linker needs the unversioned name, which only the development packages contain
+    let unversioned = build_out.join("libobs.so");
+    if !unversioned.exists() {
+        let versioned = fs::read_dir(build_out)?
+            .filter_map(|e| e.ok().map(|e| e.file_name()))
+            .find(|n| n.to_string_lossy().starts_with("libobs.so."))
+            .ok_or_else(|| anyhow!("No versioned libobs.so found"))?;
+
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(versioned, &unversioned)?;
+        #[cfg(not(unix))]
+        fs::copy(build_out.join(versioned), &unversioned)?;
+    }
+
+    let plugins = lib_dir.join("obs-plugins");
+    if plugins.is_dir() {
+        fs::create_dir_all(build_out.join("obs-plugins"))?;
+        copy_to_dir(&plugins, &build_out.join("obs-plugins"), None)?;
+    }
+
+    for bin_dir in [root.join("usr/bin"), root.join("bin")] {
+        let helper = bin_dir.join("obs-ffmpeg-mux");
+        if helper.is_file() {
+            copy_file(&helper, &build_out.join("obs-ffmpeg-mux"))?;
+        }
+    }
+
+    for data in ["libobs", "obs-plugins"] {
+        let src = data_dir.join(data);
+        if src.is_dir() {
+            let dst = build_out.join("data").join(data);
+            fs::create_dir_all(&dst)?;
+            copy_to_dir(&src, &dst, None)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Copies a file and its permissions, resolving symlinks as packages link
+/// `libobs.so.30` to `libobs.so.30.0.2`
+fn copy_file(src: &Path, dst: &Path) -> anyhow::Result<()> {
+    if dst.exists() {
+        fs::remove_file(dst)?;
+    }
+
+    fs::copy(src, dst)?;
+    Ok(())
+}
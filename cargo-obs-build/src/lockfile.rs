@@ -0,0 +1,138 @@
+use std::{collections::BTreeMap, fs, path::Path};
+
+use anyhow::{anyhow, bail, Context};
+use log::{info, warn};
+use toml::{Table, Value};
+
+/// The default name of the lock file, relative to the directory of the `Cargo.toml`
+pub const DEFAULT_LOCK_FILE: &str = "obs-build.lock";
+
+const LOCK_FILE_VERSION: i64 = 1;
+
+/// Records the release tag and the sha256 hashes of the downloaded assets, so later builds
+/// use the exact same binaries. Commit this file to get reproducible builds.
+///
+/// Assets of every platform the project has been built on are kept, so the same lock file
+/// can be used on Windows and macOS.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ObsBuildLock {
+    pub repository: String,
+    pub tag: String,
+    /// Maps asset names to their lowercase sha256 hash
+    pub assets: BTreeMap<String, String>,
+}
+
+impl ObsBuildLock {
+    pub fn new(repository: &str, tag: &str) -> Self {
+        Self {
+            repository: repository.to_string(),
+            tag: tag.to_string(),
+            assets: BTreeMap::new(),
+        }
+    }
+
+    /// Reads the lock file, `None` if it does not exist.
+    pub fn read(path: &Path) -> anyhow::Result<Option<Self>> {
+        if !path.is_file() {
+            return Ok(None);
+        }
+
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Reading lock file {}", path.display()))?;
+
+        Self::parse(&content)
+            .map(Some)
+            .with_context(|| format!("Parsing lock file {}", path.display()))
+    }
+
+    pub fn parse(content: &str) -> anyhow::Result<Self> {
+        let table: Table = toml::from_str(content)?;
+
+        let version = table.get("version").and_then(|v| v.as_integer());
+        if version != Some(LOCK_FILE_VERSION) {
+            bail!(
+                "Unsupported lock file version {:?}, expected {}",
+                version,
+                LOCK_FILE_VERSION
+            );
+        }
+
+        let read_str = |key: &str| {
+            table
+                .get(key)
+                .and_then(|v| v.as_str())
+                .map(|v| v.to_string())
+                .ok_or_else(|| anyhow!("Missing `{}` in lock file", key))
+        };
+
+        let mut assets = BTreeMap::new();
+        if let Some(table) = table.get("assets").and_then(|v| v.as_table()) {
+            for (name, hash) in table {
+                let hash = hash
+                    .as_str()
+                    .ok_or_else(|| anyhow!("The hash of asset {} is not a string", name))?;
+
+                assets.insert(name.clone(), hash.to_lowercase());
+            }
+        }
+
+        Ok(Self {
+            repository: read_str("repository")?,
+            tag: read_str("tag")?,
+            assets,
+        })
+    }
+
+    pub fn write(&self, path: &Path) -> anyhow::Result<()> {
+        let mut assets = Table::new();
+        for (name, hash) in &self.assets {
+            assets.insert(name.clone(), Value::String(hash.clone()));
+        }
+
+        let mut table = Table::new();
+        table.insert("version".to_string(), Value::Integer(LOCK_FILE_VERSION));
+        table.insert(
+            "repository".to_string(),
+            Value::String(self.repository.clone()),
+        );
+        table.insert("tag".to_string(), Value::String(self.tag.clone()));
+        table.insert("assets".to_string(), Value::Table(assets));
+
+        let content = format!(
+            "# This file is generated by cargo-obs-build, do not edit it by hand.\n{}",
+            table
+        );
+
+        fs::write(path, content).with_context(|| format!("Writing lock file {}", path.display()))
+    }
+
+    /// Checks the hash of a downloaded asset against the lock. Unknown assets and changed
+    /// hashes are recorded, unless `locked` is set, in which case they are an error.
+    pub fn check_asset(&mut self, name: &str, hash: &str, locked: bool) -> anyhow::Result<()> {
+        let hash = hash.to_lowercase();
+        match self.assets.get(name) {
+            Some(locked_hash) if *locked_hash == hash => {
+                info!("{} matches the lock file", name);
+                return Ok(());
+            }
+            Some(locked_hash) if locked => bail!(
+                "The sha256 of {} changed from {} to {}, refusing to continue because of --locked",
+                name,
+                locked_hash,
+                hash
+            ),
+            None if locked => bail!(
+                "{} is not in the lock file, refusing to continue because of --locked. Build once without --locked to record it.",
+                name
+            ),
+            Some(locked_hash) => warn!(
+                "The sha256 of {} changed from {} to {}, updating the lock file",
+                name, locked_hash, hash
+            ),
+            None => info!("Adding {} to the lock file", name),
+        }
+
+        self.assets.insert(name.to_string(), hash);
+        Ok(())
+    }
+}
@@ -68,6 +68,10 @@ fn main() -> anyhow::Result<()> {
                 browser: args.browser,
                 skip_compatibility_check: args.skip_compatibility_check,
                 remove_pdbs: args.remove_pdbs,
+                linux_source: args.linux_source,
+                lock_file: args.lock_file,
+                locked: args.locked,
+                plugins: args.plugins,
             };
 
             build_obs_binaries(config)?;
@@ -71,6 +71,30 @@ pub fn get_meta_info(
     Ok(())
 }
 
+/// Reads the `libobs-plugins` array, the plugins that should be kept in the OBS runtime.
+pub fn get_meta_plugins() -> anyhow::Result<Option<Vec<String>>> {
+    let Some(meta) = get_main_meta()? else {
+        return Ok(None);
+    };
+
+    let Some(plugins) = meta.get("libobs-plugins") else {
+        return Ok(None);
+    };
+
+    let plugins = plugins
+        .as_array()
+        .ok_or_else(|| anyhow!("`libobs-plugins` in Cargo.toml must be an array of strings"))?
+        .iter()
+        .map(|p| {
+            p.as_str().map(|p| p.to_string()).ok_or_else(|| {
+                anyhow!("`libobs-plugins` in Cargo.toml must be an array of strings")
+            })
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    Ok(Some(plugins))
+}
+
 pub fn fetch_latest_release_tag(repo_id: &str, cache_dir: &Path) -> anyhow::Result<String> {
     let release = fetch_release(repo_id, &None, cache_dir)?;
     Ok(release.tag)
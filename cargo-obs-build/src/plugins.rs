@@ -0,0 +1,122 @@
+use std::{
+    collections::BTreeSet,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use log::{debug, info, warn};
+use walkdir::WalkDir;
+
+/// Returns the size of a file or of all files in a directory in bytes.
+pub fn size_of(path: &Path) -> u64 {
+    WalkDir::new(path)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter_map(|e| e.metadata().ok())
+        .filter(|m| m.is_file())
+        .map(|m| m.len())
+        .sum()
+}
+
+pub fn format_size(bytes: u64) -> String {
+    format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+}
+
+/// The module a file in `obs-plugins` belongs to, e.g. `obs-x264` for `obs-x264.dll`,
+/// `obs-x264.pdb`, `obs-x264.so` and `obs-x264.plugin`.
+fn module_name(path: &Path) -> Option<String> {
+    let extension = path.extension()?.to_string_lossy().to_lowercase();
+    if !["dll", "pdb", "so", "plugin"].contains(&extension.as_str()) {
+        return None;
+    }
+
+    Some(path.file_stem()?.to_string_lossy().to_string())
+}
+
+/// Lists the module binaries in `obs-plugins`, including the `64bit` directory on Windows.
+fn module_binaries(plugin_dir: &Path) -> Vec<(String, PathBuf)> {
+    let mut binaries = vec![];
+    let Ok(entries) = fs::read_dir(plugin_dir) else {
+        return binaries;
+    };
+
+    for path in entries.filter_map(|e| e.ok().map(|e| e.path())) {
+        let is_bundle = path.extension().is_some_and(|e| e == "plugin");
+        if path.is_dir() && !is_bundle {
+            binaries.extend(module_binaries(&path));
+        } else if let Some(name) = module_name(&path) {
+            binaries.push((name, path));
+        }
+    }
+
+    binaries
+}
+
+fn remove(path: &Path) -> anyhow::Result<u64> {
+    let size = size_of(path);
+    debug!("Removing plugin file {}", path.display());
+    if path.is_dir() {
+        fs::remove_dir_all(path)?;
+    } else {
+        fs::remove_file(path)?;
+    }
+
+    Ok(size)
+}
+
+/// Removes every plugin that is not in `keep` from the OBS runtime in `out_dir`,
+/// together with its data directory. Returns the number of bytes removed.
+///
+/// Only files that belong to a module are removed. A module is a `.plugin` bundle or a
+/// binary that has a directory in `data/obs-plugins`, so support libraries like the
+/// CEF files of the browser source stay in place.
+pub fn trim_plugins(out_dir: &Path, keep: &[String]) -> anyhow::Result<u64> {
+    let plugin_dir = out_dir.join("obs-plugins");
+    let data_dir = out_dir.join("data").join("obs-plugins");
+
+    let data_modules = fs::read_dir(&data_dir)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .filter(|e| e.path().is_dir())
+                .map(|e| e.file_name().to_string_lossy().to_string())
+                .collect::<BTreeSet<_>>()
+        })
+        .unwrap_or_default();
+
+    let binaries = module_binaries(&plugin_dir);
+    let mut modules = data_modules.clone();
+    modules.extend(
+        binaries
+            .iter()
+            .filter(|(_, path)| path.extension().is_some_and(|e| e == "plugin"))
+            .map(|(name, _)| name.clone()),
+    );
+
+    for name in keep {
+        if !modules.contains(name) {
+            warn!("The plugin {} is not part of the OBS build", name);
+        }
+    }
+
+    let mut removed = 0;
+    for (name, path) in binaries {
+        if modules.contains(&name) && !keep.contains(&name) && path.exists() {
+            removed += remove(&path)?;
+        }
+    }
+
+    for name in data_modules {
+        if !keep.contains(&name) {
+            removed += remove(&data_dir.join(&name))?;
+        }
+    }
+
+    info!(
+        "Kept the plugins {}, removed {} of other plugins",
+        keep.join(", "),
+        format_size(removed)
+    );
+
+    Ok(removed)
+}
@@ -1,5 +1,7 @@
 use libobs_simple::output::simple::ObsContextSimpleExt;
-use libobs_simple::sources::linux::pipewire::PipeWireScreenCaptureSourceBuilder;
+use libobs_simple::sources::linux::pipewire::{
+    FileRestoreTokenStore, PipeWireScreenCaptureSourceBuilder, RestoreTokenStore,
+};
 use libobs_simple::wrapper::{
     context::ObsContext,
     data::output::ObsOutputTrait,
@@ -8,7 +10,6 @@ use libobs_simple::wrapper::{
     sources::ObsSourceBuilder,
     utils::{ObsPath, StartupInfo},
 };
-use std::fs;
 
 #[derive(Debug)]
 pub struct NoLogger {}
@@ -18,11 +19,8 @@ impl ObsLogger for NoLogger {
 
 pub fn main() -> anyhow::Result<()> {
     println!("Starting Linux XComposite Window Capture Example...");
-    let restore_token_path = std::env::current_exe()
-        .unwrap()
-        .parent()
-        .unwrap()
-        .join("window_restore_token.txt");
+    let restore_token_store =
+        FileRestoreTokenStore::next_to_executable("window_restore_token.txt")?;
 
     // Start the OBS context
     let startup_info = StartupInfo::default()
@@ -35,10 +33,10 @@ pub fn main() -> anyhow::Result<()> {
     let mut window_capture_builder = context
         .source_builder::<PipeWireScreenCaptureSourceBuilder, _>("PipeWire Screen Capture")?;
 
-    if let Ok(restore_token) = fs::read_to_string(&restore_token_path) {
+    if let Some(restore_token) = restore_token_store.load()? {
         println!(
             "Using restore token from file({}): {}",
-            &restore_token_path.display(),
+            restore_token_store.path().display(),
             restore_token
         );
         window_capture_builder = window_capture_builder.set_restore_token(restore_token);
@@ -46,6 +44,11 @@ pub fn main() -> anyhow::Result<()> {
 
     let window_capture = window_capture_builder.add_to_scene(&mut scene)?;
 
+    // Saves the restore token as soon as the user selected a window
+    let restore_token_watcher = window_capture
+        .inner_source()
+        .watch_restore_token(Some(Box::new(restore_token_store.clone())))?;
+
     // Register the source
     scene.set_to_channel(0)?;
 
@@ -67,13 +70,14 @@ pub fn main() -> anyhow::Result<()> {
     // Stop recording
     output.stop()?;
     println!("Recording stopped. Output saved to {:?}", obs_path);
-    let restore_token = window_capture.inner_source().get_restore_token()?;
+    let restore_token = restore_token_watcher.token();
     println!("Restore Token: {:?}. You can use this when creating a source so the exact same window is captured again", restore_token);
 
-    if let Some(restore_token) = restore_token {
-        // Save the restore token to a file
-        fs::write(&restore_token_path, restore_token)?;
-        println!("Restore token saved to {}", restore_token_path.display());
+    if restore_token.is_some() {
+        println!(
+            "Restore token saved to {}",
+            restore_token_store.path().display()
+        );
     }
 
     Ok(())
@@ -18,13 +18,13 @@ use libobs_wrapper::scenes::SceneItemTrait;
 #[cfg(target_os = "linux")]
 use libobs_wrapper::utils::NixDisplay;
 
-#[cfg(windows)]
-use libobs_simple::sources::ObsObjectUpdater;
 #[cfg(windows)]
 use libobs_simple::sources::windows::{
     GameCaptureSourceBuilder, MonitorCaptureSourceBuilder, ObsDisplayCaptureMethod,
     ObsGameCaptureMode, WindowSearchMode,
 };
+#[cfg(windows)]
+use libobs_simple::sources::ObsObjectUpdater;
 use libobs_wrapper::data::video::ObsVideoInfoBuilder;
 use libobs_wrapper::display::{
     ObsDisplayCreationData, ObsDisplayRef, ObsWindowHandle, ShowHideTrait, WindowPositionTrait,
@@ -184,7 +184,7 @@ impl ObsInner {
 
             println!("Listening for updates");
             while !thread_exit.load(Ordering::Relaxed) {
-                if x.try_recv().is_ok() {
+                if let Ok(Some(_)) = x.try_recv() {
                     println!("Monitor Source has been updated!");
                 }
 
@@ -0,0 +1,137 @@
+use std::path::{Path, PathBuf};
+
+use libobs::{LIBOBS_API_MAJOR_VER, LIBOBS_API_MINOR_VER};
+use semver::Version;
+use sha2::{Digest, Sha256};
+use tokio::io::AsyncReadExt;
+
+use crate::error::ObsBootstrapError;
+
+/// An OBS archive that has been downloaded before and can be extracted without going online.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ObsCachedBuild {
+    pub version: String,
+    pub path: PathBuf,
+}
+
+/// The extension of the archives OBS is distributed in on this platform
+pub(crate) fn archive_extension() -> &'static str {
+    if cfg!(target_os = "macos") {
+        "dmg"
+    } else {
+        "7z"
+    }
+}
+
+/// Archives are named `obs_<version>_<sha256>.<extension>`, so the hash of a cached archive
+/// can be verified without going online. The hash is omitted if the release has none (macOS).
+pub(crate) fn cache_file_name(version: &str, hash: Option<&[u8]>, extension: &str) -> String {
+    match hash {
+        Some(hash) => format!("obs_{}_{}.{}", version, hex::encode(hash), extension),
+        None => format!("obs_{}.{}", version, extension),
+    }
+}
+
+/// Parses the version and hash of a file name created by `cache_file_name`.
+pub(crate) fn parse_cache_file_name(
+    file_name: &str,
+    extension: &str,
+) -> Option<(Version, Option<Vec<u8>>)> {
+    let stem = file_name
+        .strip_prefix("obs_")?
+        .strip_suffix(extension)?
+        .strip_suffix('.')?;
+
+    let (version, hash) = match stem.split_once('_') {
+        Some((version, hash)) => (version, Some(hex::decode(hash).ok()?)),
+        None => (stem, None),
+    };
+
+    Some((Version::parse(version).ok()?, hash))
+}
+
+pub(crate) async fn hash_file(path: &Path) -> Result<Vec<u8>, ObsBootstrapError> {
+    let mut file = tokio::fs::File::open(path)
+        .await
+        .map_err(|e| ObsBootstrapError::IoError("Opening cached archive", e))?;
+
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0; 64 * 1024];
+    loop {
+        let read = file
+            .read(&mut buffer)
+            .await
+            .map_err(|e| ObsBootstrapError::IoError("Reading cached archive", e))?;
+        if read == 0 {
+            break;
+        }
+
+        hasher.update(&buffer[..read]);
+    }
+
+    Ok(hasher.finalize().to_vec())
+}
+
+/// Returns true if the archive exists and matches the hash in its name.
+pub(crate) async fn verify_cached(path: &Path, hash: Option<&[u8]>) -> bool {
+    if !path.is_file() {
+        return false;
+    }
+
+    match hash {
+        Some(hash) => match hash_file(path).await {
+            Ok(local_hash) => local_hash == hash,
+            Err(e) => {
+                log::warn!("Failed to hash cached archive {}: {}", path.display(), e);
+                false
+            }
+        },
+        None => true,
+    }
+}
+
+/// Returns the newest cached archive whose major and minor version match the libobs version
+/// this crate has been built for, after verifying its hash.
+pub(crate) async fn find_compatible(
+    cache_dir: &Path,
+) -> Result<Option<ObsCachedBuild>, ObsBootstrapError> {
+    let mut entries = match tokio::fs::read_dir(cache_dir).await {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(ObsBootstrapError::IoError("Reading cache directory", e)),
+    };
+
+    let mut candidates = vec![];
+    while let Some(entry) = entries
+        .next_entry()
+        .await
+        .map_err(|e| ObsBootstrapError::IoError("Reading cache directory entry", e))?
+    {
+        let file_name = entry.file_name();
+        let Some((version, hash)) =
+            parse_cache_file_name(&file_name.to_string_lossy(), archive_extension())
+        else {
+            continue;
+        };
+
+        if version.major == LIBOBS_API_MAJOR_VER as u64
+            && version.minor == LIBOBS_API_MINOR_VER as u64
+        {
+            candidates.push((version, hash, entry.path()));
+        }
+    }
+
+    candidates.sort_by(|a, b| b.0.cmp(&a.0));
+    for (version, hash, path) in candidates {
+        if verify_cached(&path, hash.as_deref()).await {
+            return Ok(Some(ObsCachedBuild {
+                version: version.to_string(),
+                path,
+            }));
+        }
+
+        log::warn!("Ignoring corrupted cached archive {}", path.display());
+    }
+
+    Ok(None)
+}
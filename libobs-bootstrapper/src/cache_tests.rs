@@ -0,0 +1,36 @@
+#[cfg(test)]
+mod tests {
+    use semver::Version;
+
+    use crate::cache::{cache_file_name, parse_cache_file_name};
+
+    #[test]
+    fn test_cache_file_name_roundtrip() {
+        let hash = [0xab, 0xcd, 0xef];
+        let name = cache_file_name("32.0.4", Some(&hash), "7z");
+        assert_eq!(name, "obs_32.0.4_abcdef.7z");
+
+        let (version, parsed_hash) = parse_cache_file_name(&name, "7z").unwrap();
+        assert_eq!(version, Version::new(32, 0, 4));
+        assert_eq!(parsed_hash, Some(hash.to_vec()));
+    }
+
+    #[test]
+    fn test_cache_file_name_without_hash() {
+        let name = cache_file_name("32.0.4", None, "dmg");
+        assert_eq!(name, "obs_32.0.4.dmg");
+
+        let (version, parsed_hash) = parse_cache_file_name(&name, "dmg").unwrap();
+        assert_eq!(version, Version::new(32, 0, 4));
+        assert_eq!(parsed_hash, None);
+    }
+
+    #[test]
+    fn test_parse_cache_file_name_rejects_other_files() {
+        assert!(parse_cache_file_name("obs_32.0.4_abcdef.7z.part", "7z").is_none());
+        assert!(parse_cache_file_name("obs_32.0.4_abcdef.dmg", "7z").is_none());
+        assert!(parse_cache_file_name("obs_invalid_abcdef.7z", "7z").is_none());
+        assert!(parse_cache_file_name("obs_32.0.4_nothex.7z", "7z").is_none());
+        assert!(parse_cache_file_name("other.7z", "7z").is_none());
+    }
+}
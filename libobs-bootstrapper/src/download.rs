@@ -1,16 +1,23 @@
-use std::{env::temp_dir, path::PathBuf};
+use std::{path::PathBuf, pin::Pin};
 
 use async_stream::stream;
 use futures_core::Stream;
 use futures_util::StreamExt;
 use libobs::{LIBOBS_API_MAJOR_VER, LIBOBS_API_MINOR_VER};
+use reqwest::{StatusCode, header::RANGE};
 use semver::Version;
-use sha2::{Digest, Sha256};
-use tokio::{fs::File, io::AsyncWriteExt};
-use uuid::Uuid;
+use tokio::{fs::OpenOptions, io::AsyncWriteExt};
 
 use super::{LIBRARY_OBS_VERSION, github_types};
-use crate::error::ObsBootstrapError;
+use crate::{
+    ObsBootstrapperOptions,
+    cache::{self, archive_extension},
+    error::ObsBootstrapError,
+    options::GITHUB_API_URL,
+};
+
+/// How often an interrupted download is resumed before giving up
+const MAX_DOWNLOAD_ATTEMPTS: u32 = 3;
 
 pub enum DownloadStatus {
     Error(ObsBootstrapError),
@@ -18,20 +25,85 @@ pub enum DownloadStatus {
     Done(PathBuf),
 }
 
-pub(crate) async fn download_obs(
-    _repo: &str,
-) -> Result<impl Stream<Item = DownloadStatus>, ObsBootstrapError> {
-    // Fetch latest OBS release
-    let client = reqwest::ClientBuilder::new()
-        .user_agent("libobs-rs")
+type DownloadStream = Pin<Box<dyn Stream<Item = DownloadStatus> + Send>>;
+
+/// The archive of the newest OBS release that is compatible with this crate
+struct ObsRelease {
+    version: String,
+    archive: AssetRequest,
+    hash: Option<AssetRequest>,
+}
+
+/// Where and how an asset of a release is downloaded
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct AssetRequest {
+    pub(crate) url: String,
+    pub(crate) headers: Vec<(String, String)>,
+}
+
+impl AssetRequest {
+    /// Assets are downloaded from GitHub directly, unless a custom API URL or API headers are
+    /// set. Then they are downloaded through the asset endpoint of that API with the headers,
+    /// so a mirror or an authenticated network is used for the archives as well.
+    pub(crate) fn new(
+        options: &ObsBootstrapperOptions,
+        asset_id: i64,
+        browser_download_url: &str,
+    ) -> Self {
+        if options.api_url == GITHUB_API_URL && options.api_headers.is_empty() {
+            return Self {
+                url: browser_download_url.to_string(),
+                headers: Vec::new(),
+            };
+        }
+
+        let mut headers = options.api_headers.clone();
+        headers.push(("Accept".to_string(), "application/octet-stream".to_string()));
+
+        Self {
+            url: format!(
+                "{}/repos/{}/releases/assets/{}",
+                options.api_url, options.repository, asset_id
+            ),
+            headers,
+        }
+    }
+
+    fn request(&self, client: &reqwest::Client) -> reqwest::RequestBuilder {
+        self.headers
+            .iter()
+            .fold(client.get(&self.url), |request, (name, value)| {
+                request.header(name.as_str(), value.as_str())
+            })
+    }
+}
+
+fn build_client(options: &ObsBootstrapperOptions) -> Result<reqwest::Client, ObsBootstrapError> {
+    let mut builder = reqwest::ClientBuilder::new().user_agent("libobs-rs");
+    if let Some(proxy) = &options.proxy {
+        let proxy = reqwest::Proxy::all(proxy)
+            .map_err(|e| ObsBootstrapError::DownloadError("Parsing the proxy URL", e))?;
+        builder = builder.proxy(proxy);
+    }
+
+    builder
         .build()
-        .map_err(|e| ObsBootstrapError::DownloadError("Building the reqwest client", e))?;
+        .map_err(|e| ObsBootstrapError::DownloadError("Building the reqwest client", e))
+}
 
+async fn find_release(
+    client: &reqwest::Client,
+    options: &ObsBootstrapperOptions,
+) -> Result<ObsRelease, ObsBootstrapError> {
     #[cfg(not(feature = "__mock_github_responses"))]
-    let releases_url = format!("https://api.github.com/repos/{}/releases", _repo);
+    let releases_url = format!("{}/repos/{}/releases", options.api_url, options.repository);
     #[cfg(not(feature = "__mock_github_responses"))]
-    let releases: github_types::Root = client
-        .get(&releases_url)
+    let releases: github_types::Root = options
+        .api_headers
+        .iter()
+        .fold(client.get(&releases_url), |request, (name, value)| {
+            request.header(name.as_str(), value.as_str())
+        })
         .send()
         .await
         .map_err(|e| ObsBootstrapError::DownloadError("Sending Github API request", e))?
@@ -41,6 +113,7 @@ pub(crate) async fn download_obs(
 
     #[cfg(feature = "__mock_github_responses")]
     let releases: github_types::Root = {
+        let _ = client;
         println!("-- WARNING --");
         println!("Using mock GitHub responses! This is only for testing purposes.");
         println!("-- WARNING --");
@@ -71,13 +144,13 @@ pub(crate) async fn download_obs(
         if version.major == LIBOBS_API_MAJOR_VER as u64
             && version.minor == LIBOBS_API_MINOR_VER as u64
         {
-            possible_versions.push(release);
+            possible_versions.push((version, release));
         }
     }
 
-    let latest_version = possible_versions
+    let (version, latest_version) = possible_versions
         .iter()
-        .max_by_key(|r| &r.published_at)
+        .max_by_key(|(_, r)| &r.published_at)
         .ok_or_else(|| {
             ObsBootstrapError::InvalidFormatError(format!(
                 "Finding a matching obs version for {}",
@@ -102,21 +175,20 @@ pub(crate) async fn download_obs(
         (".7z".to_string(), "7z")
     };
 
-    let archive_url = latest_version
+    let archive = latest_version
         .assets
         .iter()
         .find(|a| a.name.contains(&asset_extension) && !a.name.contains("dSYM"))
+        .map(|a| AssetRequest::new(options, a.id, &a.browser_download_url))
         .ok_or_else(|| {
             ObsBootstrapError::InvalidFormatError(format!(
                 "Finding {} asset with pattern: {}",
                 file_extension, asset_extension
             ))
-        })?
-        .browser_download_url
-        .clone();
+        })?;
 
     // Hash verification is optional for macOS (DMG has built-in verification)
-    let hash_url = if cfg!(target_os = "macos") {
+    let hash = if cfg!(target_os = "macos") {
         None
     } else {
         Some(
@@ -124,82 +196,231 @@ pub(crate) async fn download_obs(
                 .assets
                 .iter()
                 .find(|a| a.name.ends_with(".sha256"))
+                .map(|a| AssetRequest::new(options, a.id, &a.browser_download_url))
                 .ok_or_else(|| {
                     ObsBootstrapError::InvalidFormatError("Finding sha256 asset".to_string())
-                })?
-                .browser_download_url
-                .clone(),
+                })?,
         )
     };
 
-    let res = client
-        .get(archive_url)
+    Ok(ObsRelease {
+        version: version.to_string(),
+        archive,
+        hash,
+    })
+}
+
+async fn fetch_hash(
+    client: &reqwest::Client,
+    hash: &AssetRequest,
+) -> Result<Vec<u8>, ObsBootstrapError> {
+    let remote_hash = hash
+        .request(client)
         .send()
         .await
-        .map_err(|e| ObsBootstrapError::DownloadError("Sending archive request", e))?;
-    let length = res.content_length().unwrap_or(0);
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| ObsBootstrapError::DownloadError("Fetching hash", e))?
+        .text()
+        .await
+        .map_err(|e| ObsBootstrapError::DownloadError("Reading hash", e))?;
+
+    hex::decode(remote_hash.trim())
+        .map_err(|e| ObsBootstrapError::InvalidFormatError(e.to_string()))
+}
+
+fn done(path: PathBuf) -> DownloadStream {
+    Box::pin(stream! {
+        yield DownloadStatus::Done(path);
+    })
+}
+
+/// Downloads the newest compatible OBS archive into the cache directory of the options.
+///
+/// Archives that have been downloaded before are not downloaded again and interrupted downloads
+/// are resumed with HTTP range requests. If GitHub can not be reached, the newest compatible
+/// cached archive is used instead.
+pub(crate) async fn download_obs(
+    options: &ObsBootstrapperOptions,
+) -> Result<DownloadStream, ObsBootstrapError> {
+    // Fetch latest OBS release
+    let client = build_client(options)?;
 
-    let mut bytes_stream = res.bytes_stream();
+    let cache_dir = options.cache_dir.clone();
+    let release = match find_release(&client, options).await {
+        Ok(release) => release,
+        Err(e) => {
+            if let Some(cached) = cache::find_compatible(&cache_dir).await? {
+                log::warn!(
+                    "Could not fetch OBS releases ({}), using cached OBS {}",
+                    e,
+                    cached.version
+                );
+                return Ok(done(cached.path));
+            }
 
-    let path =
-        PathBuf::new()
-            .join(temp_dir())
-            .join(format!("{}.{}", Uuid::new_v4(), file_extension));
-    let mut tmp_file = File::create_new(&path)
+            return Err(e);
+        }
+    };
+
+    let remote_hash = match &release.hash {
+        Some(hash) => Some(fetch_hash(&client, hash).await?),
+        None => None,
+    };
+
+    let file_name = cache::cache_file_name(
+        &release.version,
+        remote_hash.as_deref(),
+        archive_extension(),
+    );
+    let path = cache_dir.join(&file_name);
+    if cache::verify_cached(&path, remote_hash.as_deref()).await {
+        log::info!("Using cached OBS {} at {}", release.version, path.display());
+        return Ok(done(path));
+    }
+
+    tokio::fs::create_dir_all(&cache_dir)
         .await
-        .map_err(|e| ObsBootstrapError::IoError("Creating temporary file", e))?;
+        .map_err(|e| ObsBootstrapError::IoError("Creating cache directory", e))?;
+
+    let part_path = cache_dir.join(format!("{}.part", file_name));
+    Ok(download_archive(
+        client,
+        release.archive,
+        part_path,
+        path,
+        remote_hash,
+    ))
+}
 
-    let mut curr_len = 0;
-    let mut hasher = Sha256::new();
-    Ok(stream! {
+/// Downloads the archive into `part_path`, verifies it against the hash and moves it to `path`.
+pub(crate) fn download_archive(
+    client: reqwest::Client,
+    archive: AssetRequest,
+    part_path: PathBuf,
+    path: PathBuf,
+    remote_hash: Option<Vec<u8>>,
+) -> DownloadStream {
+    Box::pin(stream! {
         yield DownloadStatus::Progress(0.0, "Downloading OBS".to_string());
-        while let Some(chunk) = bytes_stream.next().await {
-            let chunk = chunk.map_err(|e| ObsBootstrapError::DownloadError("Receiving chunk of archive data", e));
-            if let Err(e) = chunk {
-                yield DownloadStatus::Error(e);
-                return;
+
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+
+            let offset = tokio::fs::metadata(&part_path).await.map(|m| m.len()).unwrap_or(0);
+            let mut request = archive.request(&client);
+            if offset > 0 {
+                log::info!("Resuming download of OBS at {} bytes", offset);
+                request = request.header(RANGE, format!("bytes={}-", offset));
             }
 
-            let chunk = chunk.unwrap();
-            hasher.update(&chunk);
-            let r = tmp_file.write_all(&chunk).await.map_err(|e| ObsBootstrapError::IoError("Writing to temporary file", e));
-            if let Err(e) = r {
-                yield DownloadStatus::Error(e);
-                return;
+            let res = request.send().await;
+            if offset > 0 && matches!(&res, Ok(r) if r.status() == StatusCode::RANGE_NOT_SATISFIABLE) {
+                // There is nothing after the offset, so the partial download is either complete,
+                // e.g. because the previous run stopped before moving it into the cache, or broken
+                if remote_hash.is_some() && cache::verify_cached(&part_path, remote_hash.as_deref()).await {
+                    log::info!("Partial download of OBS is already complete");
+                    break;
+                }
+
+                log::warn!("Partial download of OBS can not be resumed, restarting");
+                let r = tokio::fs::remove_file(&part_path).await.map_err(|e| ObsBootstrapError::IoError("Removing partial download", e));
+                if let Err(e) = r {
+                    yield DownloadStatus::Error(e);
+                    return;
+                }
+
+                continue;
             }
 
-            curr_len = std::cmp::min(curr_len + chunk.len() as u64, length);
-            yield DownloadStatus::Progress(curr_len as  f32 / length as f32, "Downloading OBS".to_string());
-        }
+            let res = res.and_then(|r| r.error_for_status());
+            let res = match res {
+                Ok(res) => res,
+                Err(e) if attempt < MAX_DOWNLOAD_ATTEMPTS => {
+                    log::warn!("Failed to request OBS archive, retrying: {}", e);
+                    continue;
+                }
+                Err(e) => {
+                    yield DownloadStatus::Error(ObsBootstrapError::DownloadError("Sending archive request", e));
+                    return;
+                }
+            };
 
-        // Hash verification (only for non-macOS platforms)
-        if let Some(hash_url) = hash_url {
-            // Getting remote hash
-            let remote_hash = client.get(hash_url).send().await.map_err(|e| ObsBootstrapError::DownloadError("Fetching hash", e));
-            if let Err(e) = remote_hash {
-                yield DownloadStatus::Error(e);
-                return;
+            // Servers that do not support range requests send the whole archive again
+            let resumed = offset > 0 && res.status() == StatusCode::PARTIAL_CONTENT;
+            let mut curr_len = if resumed { offset } else { 0 };
+            let length = res.content_length().map(|l| l + curr_len).unwrap_or(0);
+
+            let part_file = OpenOptions::new()
+                .create(true)
+                .write(true)
+                .append(resumed)
+                .truncate(!resumed)
+                .open(&part_path)
+                .await
+                .map_err(|e| ObsBootstrapError::IoError("Opening partial download", e));
+            let mut part_file = match part_file {
+                Ok(file) => file,
+                Err(e) => {
+                    yield DownloadStatus::Error(e);
+                    return;
+                }
+            };
+
+            let mut bytes_stream = res.bytes_stream();
+            let mut failure = None;
+            while let Some(chunk) = bytes_stream.next().await {
+                let chunk = match chunk {
+                    Ok(chunk) => chunk,
+                    Err(e) => {
+                        failure = Some(e);
+                        break;
+                    }
+                };
+
+                let r = part_file.write_all(&chunk).await.map_err(|e| ObsBootstrapError::IoError("Writing to partial download", e));
+                if let Err(e) = r {
+                    yield DownloadStatus::Error(e);
+                    return;
+                }
+
+                curr_len = std::cmp::min(curr_len + chunk.len() as u64, length);
+                yield DownloadStatus::Progress(curr_len as f32 / length as f32, "Downloading OBS".to_string());
             }
 
-            let remote_hash = remote_hash.unwrap().text().await.map_err(|e| ObsBootstrapError::DownloadError("Reading hash", e));
-            if let Err(e) = remote_hash {
+            let r = part_file.flush().await.map_err(|e| ObsBootstrapError::IoError("Flushing partial download", e));
+            if let Err(e) = r {
                 yield DownloadStatus::Error(e);
                 return;
             }
 
-            let remote_hash = remote_hash.unwrap();
-            let remote_hash = hex::decode(remote_hash.trim()).map_err(|e| ObsBootstrapError::InvalidFormatError(e.to_string()));
-            if let Err(e) = remote_hash {
-                yield DownloadStatus::Error(e);
-                return;
+            match failure {
+                None => break,
+                Some(e) if attempt < MAX_DOWNLOAD_ATTEMPTS => {
+                    log::warn!("Download of OBS was interrupted, resuming: {}", e);
+                }
+                Some(e) => {
+                    yield DownloadStatus::Error(ObsBootstrapError::DownloadError("Receiving chunk of archive data", e));
+                    return;
+                }
             }
+        }
 
-            let remote_hash = remote_hash.unwrap();
+        // Hash verification (only for non-macOS platforms)
+        if let Some(remote_hash) = &remote_hash {
+            let local_hash = cache::hash_file(&part_path).await;
+            let local_hash = match local_hash {
+                Ok(hash) => hash,
+                Err(e) => {
+                    yield DownloadStatus::Error(e);
+                    return;
+                }
+            };
 
-            // Calculating local hash
-            let local_hash = hasher.finalize();
-            if local_hash.to_vec() != remote_hash {
-            yield DownloadStatus::Error(ObsBootstrapError::HashMismatchError);
+            if &local_hash != remote_hash {
+                // The partial download is corrupted, so the next attempt has to start from scratch
+                let _ = tokio::fs::remove_file(&part_path).await;
+                yield DownloadStatus::Error(ObsBootstrapError::HashMismatchError);
                 return;
             }
 
@@ -208,6 +429,12 @@ pub(crate) async fn download_obs(
             log::info!("Skipping hash verification for macOS DMG (has built-in verification)");
         }
 
+        let r = tokio::fs::rename(&part_path, &path).await.map_err(|e| ObsBootstrapError::IoError("Moving download into the cache", e));
+        if let Err(e) = r {
+            yield DownloadStatus::Error(e);
+            return;
+        }
+
         yield DownloadStatus::Done(path);
     })
 }
@@ -0,0 +1,186 @@
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{Read, Write},
+        net::TcpListener,
+        path::PathBuf,
+    };
+
+    use futures_util::StreamExt;
+    use sha2::{Digest, Sha256};
+
+    use crate::{
+        ObsBootstrapperOptions,
+        download::{AssetRequest, DownloadStatus, download_archive},
+    };
+
+    const ARCHIVE: &[u8] = b"not really an OBS archive";
+
+    /// Serves `ARCHIVE` and answers every range request with 416, like a server does for a
+    /// range that starts at the end of the file. Requests without the `required_header`
+    /// (lowercase `name: value`) are answered with 401.
+    fn serve_archive(required_header: Option<&'static str>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(mut stream) = stream else {
+                    continue;
+                };
+
+                let mut request = Vec::new();
+                let mut buffer = [0; 1024];
+                while !request.ends_with(b"\r\n\r\n") {
+                    match stream.read(&mut buffer) {
+                        Ok(0) | Err(_) => break,
+                        Ok(read) => request.extend_from_slice(&buffer[..read]),
+                    }
+                }
+
+                let request = String::from_utf8_lossy(&request).to_ascii_lowercase();
+                let authorized = required_header
+                    .is_none_or(|header| request.contains(&format!("\r\n{}", header)));
+                let response = if !authorized {
+                    "HTTP/1.1 401 Unauthorized\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                        .as_bytes()
+                        .to_vec()
+                } else if request.contains("\r\nrange:") {
+                    format!(
+                        "HTTP/1.1 416 Range Not Satisfiable\r\nContent-Range: bytes */{}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                        ARCHIVE.len()
+                    )
+                    .into_bytes()
+                } else {
+                    let mut response = format!(
+                        "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                        ARCHIVE.len()
+                    )
+                    .into_bytes();
+                    response.extend_from_slice(ARCHIVE);
+                    response
+                };
+                let _ = stream.write_all(&response);
+            }
+        });
+
+        format!("http://{}/obs.7z", addr)
+    }
+
+    fn paths(name: &str) -> (PathBuf, PathBuf) {
+        let dir =
+            std::env::temp_dir().join(format!("libobs-download-{}-{}", name, uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        (dir.join("obs.7z.part"), dir.join("obs.7z"))
+    }
+
+    async fn run(part_path: &PathBuf, path: &PathBuf) -> Result<PathBuf, String> {
+        let archive = AssetRequest {
+            url: serve_archive(None),
+            headers: Vec::new(),
+        };
+        run_request(archive, part_path, path).await
+    }
+
+    async fn run_request(
+        archive: AssetRequest,
+        part_path: &PathBuf,
+        path: &PathBuf,
+    ) -> Result<PathBuf, String> {
+        let hash = Sha256::digest(ARCHIVE).to_vec();
+        let mut stream = download_archive(
+            reqwest::Client::new(),
+            archive,
+            part_path.clone(),
+            path.clone(),
+            Some(hash),
+        );
+
+        while let Some(status) = stream.next().await {
+            match status {
+                DownloadStatus::Error(e) => return Err(e.to_string()),
+                DownloadStatus::Progress(_, _) => {}
+                DownloadStatus::Done(path) => return Ok(path),
+            }
+        }
+
+        Err("Download ended without a result".to_string())
+    }
+
+    #[tokio::test]
+    async fn test_complete_part_is_moved_into_place() {
+        let (part_path, path) = paths("complete");
+        std::fs::write(&part_path, ARCHIVE).unwrap();
+
+        let done = run(&part_path, &path).await.unwrap();
+        assert_eq!(done, path);
+        assert_eq!(std::fs::read(&path).unwrap(), ARCHIVE);
+        assert!(!part_path.exists());
+
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_broken_part_is_downloaded_again() {
+        let (part_path, path) = paths("broken");
+        let mut broken = ARCHIVE.to_vec();
+        broken.extend_from_slice(b"trailing garbage");
+        std::fs::write(&part_path, broken).unwrap();
+
+        let done = run(&part_path, &path).await.unwrap();
+        assert_eq!(done, path);
+        assert_eq!(std::fs::read(&path).unwrap(), ARCHIVE);
+        assert!(!part_path.exists());
+
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_archive_is_downloaded_with_the_api_headers() {
+        let url = serve_archive(Some("authorization: bearer secret"));
+
+        let (part_path, path) = paths("unauthorized");
+        let archive = AssetRequest {
+            url: url.clone(),
+            headers: Vec::new(),
+        };
+        assert!(run_request(archive, &part_path, &path).await.is_err());
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+
+        let (part_path, path) = paths("authorized");
+        let archive = AssetRequest {
+            url,
+            headers: vec![("Authorization".to_string(), "Bearer secret".to_string())],
+        };
+        assert_eq!(run_request(archive, &part_path, &path).await.unwrap(), path);
+        assert_eq!(std::fs::read(&path).unwrap(), ARCHIVE);
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_asset_requests_follow_the_api_options() {
+        let github = ObsBootstrapperOptions::new();
+        assert_eq!(
+            AssetRequest::new(&github, 42, "https://github.com/obs.7z"),
+            AssetRequest {
+                url: "https://github.com/obs.7z".to_string(),
+                headers: Vec::new(),
+            }
+        );
+
+        let mirror = ObsBootstrapperOptions::new()
+            .set_repository("corp/obs")
+            .set_api_url("https://mirror.corp/api/v3")
+            .set_github_token("secret");
+        assert_eq!(
+            AssetRequest::new(&mirror, 42, "https://github.com/obs.7z"),
+            AssetRequest {
+                url: "https://mirror.corp/api/v3/repos/corp/obs/releases/assets/42".to_string(),
+                headers: vec![
+                    ("Authorization".to_string(), "Bearer secret".to_string()),
+                    ("Accept".to_string(), "application/octet-stream".to_string()),
+                ],
+            }
+        );
+    }
+}
@@ -18,6 +18,7 @@ use libobs::{LIBOBS_API_MAJOR_VER, LIBOBS_API_MINOR_VER, LIBOBS_API_PATCH_VER};
 #[cfg(not(target_os = "macos"))]
 use tokio::{fs::File, io::AsyncWriteExt, process::Command};
 
+mod cache;
 #[cfg_attr(coverage_nightly, coverage(off))]
 mod download;
 mod error;
@@ -27,16 +28,25 @@ mod extract;
 mod github_types;
 mod options;
 pub mod status_handler;
+mod upgrade;
 mod version;
 
+#[cfg(test)]
+mod cache_tests;
+#[cfg(test)]
+mod download_tests;
 #[cfg(test)]
 mod options_tests;
 #[cfg(test)]
+mod upgrade_tests;
+#[cfg(test)]
 mod version_tests;
 
+pub use cache::ObsCachedBuild;
 pub use error::ObsBootstrapError;
 
 pub use options::ObsBootstrapperOptions;
+pub use upgrade::ObsUpgrade;
 
 use crate::status_handler::{ObsBootstrapConsoleHandler, ObsBootstrapStatusHandler};
 
@@ -116,28 +126,12 @@ fn get_obs_dll_path() -> Result<PathBuf, ObsBootstrapError> {
     }
 }
 
-pub(crate) fn bootstrap(
-    options: &ObsBootstrapperOptions,
-) -> Result<Option<impl Stream<Item = BootstrapStatus>>, ObsBootstrapError> {
-    let repo = options.repository.to_string();
-
-    log::trace!("Checking for update...");
-    let update = if options.update {
-        ObsBootstrapper::is_update_available()?
-    } else {
-        ObsBootstrapper::is_valid_installation()?
-    };
-
-    if !update {
-        log::debug!("No update needed.");
-        return Ok(None);
-    }
-
-    #[allow(unused_variables)]
-    let options = options.clone();
-    Ok(Some(stream! {
-        log::debug!("Downloading OBS from {}", repo);
-        let download_stream = download::download_obs(&repo).await;
+/// Downloads and verifies the newest compatible OBS archive and extracts it into the
+/// `obs_new` directory next to the executable.
+fn stage(options: ObsBootstrapperOptions) -> impl Stream<Item = BootstrapStatus> {
+    stream! {
+        log::debug!("Downloading OBS from {}", options.repository);
+        let download_stream = download::download_obs(&options).await;
         if let Err(err) = download_stream {
             yield BootstrapStatus::Error(err);
             return;
@@ -190,6 +184,80 @@ pub(crate) fn bootstrap(
                 }
             }
         }
+    }
+}
+
+/// The directory the executable and the OBS runtime are in
+fn get_install_dir() -> Result<PathBuf, ObsBootstrapError> {
+    let executable =
+        env::current_exe().map_err(|e| ObsBootstrapError::IoError("Getting current exe", e))?;
+
+    executable.parent().map(|p| p.to_path_buf()).ok_or_else(|| {
+        ObsBootstrapError::IoError(
+            "Failed to get parent directory",
+            std::io::Error::from(std::io::ErrorKind::InvalidInput),
+        )
+    })
+}
+
+fn handle_status<E: Send + Sync + 'static + std::error::Error>(
+    handler: &mut Box<dyn ObsBootstrapStatusHandler<Error = E>>,
+    status: BootstrapStatus,
+) -> Result<Option<ObsBootstrapperResult>, ObsBootstrapError> {
+    match status {
+        BootstrapStatus::Downloading(progress, message) => {
+            handler
+                .handle_downloading(progress, message)
+                .map_err(|e| ObsBootstrapError::Abort(Box::new(e)))?;
+        }
+        BootstrapStatus::Extracting(progress, message) => {
+            handler
+                .handle_extraction(progress, message)
+                .map_err(|e| ObsBootstrapError::Abort(Box::new(e)))?;
+        }
+        BootstrapStatus::Error(err) => {
+            return Err(err);
+        }
+        BootstrapStatus::RestartRequired => {
+            return Ok(Some(ObsBootstrapperResult::Restart));
+        }
+        BootstrapStatus::Done => {
+            return Ok(Some(ObsBootstrapperResult::None));
+        }
+    }
+
+    Ok(None)
+}
+
+pub(crate) fn bootstrap(
+    options: &ObsBootstrapperOptions,
+) -> Result<Option<impl Stream<Item = BootstrapStatus>>, ObsBootstrapError> {
+    ObsUpgrade::recover(&get_install_dir()?)?;
+
+    log::trace!("Checking for update...");
+    let update = if options.update {
+        ObsBootstrapper::is_update_available()?
+    } else {
+        ObsBootstrapper::is_valid_installation()?
+    };
+
+    if !update {
+        log::debug!("No update needed.");
+        return Ok(None);
+    }
+
+    let options = options.clone();
+    Ok(Some(stream! {
+        let stage_stream = stage(options.clone());
+        pin_mut!(stage_stream);
+
+        while let Some(item) = stage_stream.next().await {
+            let failed = matches!(item, BootstrapStatus::Error(_));
+            yield item;
+            if failed {
+                return;
+            }
+        }
 
         // Platform-specific post-extraction handling
         #[cfg(target_os = "macos")]
@@ -398,6 +466,18 @@ impl ObsBootstrapper {
         version::should_update(&installed)
     }
 
+    /// Returns the newest downloaded OBS archive in the cache directory of the options that
+    /// is compatible with this crate, without going online. The archive is hashed to detect
+    /// corrupted downloads, which may take a moment.
+    ///
+    /// The bootstrapper extracts a cached archive instead of downloading it again and
+    /// falls back to the newest compatible one if GitHub can not be reached.
+    pub async fn cached_build(
+        options: &options::ObsBootstrapperOptions,
+    ) -> Result<Option<ObsCachedBuild>, ObsBootstrapError> {
+        cache::find_compatible(&options.cache_dir).await
+    }
+
     /// Bootstraps OBS using the provided options and a default console status
     /// handler.
     ///
@@ -474,30 +554,73 @@ impl ObsBootstrapper {
 
             log::trace!("Waiting for bootstrapper to finish");
             while let Some(item) = stream.next().await {
-                match item {
-                    BootstrapStatus::Downloading(progress, message) => {
-                        handler
-                            .handle_downloading(progress, message)
-                            .map_err(|e| ObsBootstrapError::Abort(Box::new(e)))?;
-                    }
-                    BootstrapStatus::Extracting(progress, message) => {
-                        handler
-                            .handle_extraction(progress, message)
-                            .map_err(|e| ObsBootstrapError::Abort(Box::new(e)))?;
-                    }
-                    BootstrapStatus::Error(err) => {
-                        return Err(err);
-                    }
-                    BootstrapStatus::RestartRequired => {
-                        return Ok(ObsBootstrapperResult::Restart);
-                    }
-                    BootstrapStatus::Done => {
-                        return Ok(ObsBootstrapperResult::None);
-                    }
+                if let Some(result) = handle_status(&mut handler, item)? {
+                    return Ok(result);
                 }
             }
         }
 
         Ok(ObsBootstrapperResult::None)
     }
+
+    /// Downloads the newest compatible OBS runtime next to the current one while the
+    /// application keeps running, using a default console status handler.
+    ///
+    /// See `upgrade_with_handler` for details.
+    pub async fn upgrade(
+        options: &options::ObsBootstrapperOptions,
+    ) -> Result<Option<ObsUpgrade>, ObsBootstrapError> {
+        ObsBootstrapper::upgrade_with_handler(
+            options,
+            Box::new(ObsBootstrapConsoleHandler::default()),
+        )
+        .await
+    }
+
+    /// Downloads the newest compatible OBS runtime, verifies its hash and extracts it into
+    /// the `obs_new` directory next to the current one, without touching the current runtime
+    /// or spawning the updater.
+    ///
+    /// Once the `ObsContext` has been shut down, call [`ObsUpgrade::apply`] to swap the
+    /// runtimes. If the swap fails, the current runtime is restored.
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(None)` when the installed version is up-to-date.
+    /// - `Ok(Some(upgrade))` when the new runtime is ready to be applied.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err(ObsBootstrapError)` when the installed version could not be determined,
+    /// the download or extraction failed or the handler returned an error.
+    pub async fn upgrade_with_handler<E: Send + Sync + 'static + std::error::Error>(
+        options: &options::ObsBootstrapperOptions,
+        mut handler: Box<dyn ObsBootstrapStatusHandler<Error = E>>,
+    ) -> Result<Option<ObsUpgrade>, ObsBootstrapError> {
+        let install_dir = get_install_dir()?;
+        ObsUpgrade::recover(&install_dir)?;
+
+        if !ObsBootstrapper::is_update_available()? {
+            log::debug!("No upgrade needed.");
+            return Ok(None);
+        }
+
+        let upgrade = ObsUpgrade::new(&install_dir);
+
+        // Files of an earlier, unfinished attempt must not end up in the new runtime
+        if upgrade.new_dir().exists() {
+            tokio::fs::remove_dir_all(upgrade.new_dir())
+                .await
+                .map_err(|e| ObsBootstrapError::IoError("Removing old obs_new directory", e))?;
+        }
+
+        let stream = stage(options.clone());
+        pin_mut!(stream);
+
+        while let Some(item) = stream.next().await {
+            handle_status(&mut handler, item)?;
+        }
+
+        Ok(Some(upgrade))
+    }
 }
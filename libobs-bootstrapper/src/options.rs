@@ -1,3 +1,5 @@
+use std::path::{Path, PathBuf};
+
 // Platform-specific default repos
 #[cfg(target_os = "macos")]
 pub const GITHUB_REPO: &str = "obsproject/obs-studio";
@@ -5,11 +7,38 @@ pub const GITHUB_REPO: &str = "obsproject/obs-studio";
 #[cfg(not(target_os = "macos"))]
 pub const GITHUB_REPO: &str = "sshcrack/libobs-builds";
 
-#[derive(Debug, Clone)]
+pub const GITHUB_API_URL: &str = "https://api.github.com";
+
+#[derive(Clone)]
 pub struct ObsBootstrapperOptions {
     pub(crate) repository: String,
     pub(crate) update: bool,
     pub(crate) restart_after_update: bool,
+    pub(crate) cache_dir: PathBuf,
+    pub(crate) api_url: String,
+    pub(crate) proxy: Option<String>,
+    pub(crate) api_headers: Vec<(String, String)>,
+}
+
+impl std::fmt::Debug for ObsBootstrapperOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Header values are left out, as they usually contain access tokens
+        let api_headers = self
+            .api_headers
+            .iter()
+            .map(|(name, _)| name)
+            .collect::<Vec<_>>();
+
+        f.debug_struct("ObsBootstrapperOptions")
+            .field("repository", &self.repository)
+            .field("update", &self.update)
+            .field("restart_after_update", &self.restart_after_update)
+            .field("cache_dir", &self.cache_dir)
+            .field("api_url", &self.api_url)
+            .field("proxy", &self.proxy)
+            .field("api_headers", &api_headers)
+            .finish()
+    }
 }
 
 impl ObsBootstrapperOptions {
@@ -18,6 +47,10 @@ impl ObsBootstrapperOptions {
             repository: GITHUB_REPO.to_string(),
             update: true,
             restart_after_update: true,
+            cache_dir: std::env::temp_dir().join("libobs-bootstrapper"),
+            api_url: GITHUB_API_URL.to_string(),
+            proxy: None,
+            api_headers: Vec::new(),
         }
     }
 
@@ -37,6 +70,58 @@ impl ObsBootstrapperOptions {
         self
     }
 
+    /// Sets the directory downloaded archives are kept in, so they are not downloaded again.
+    /// Interrupted downloads are resumed from there as well. Defaults to a directory in the temp dir.
+    pub fn set_cache_dir<P: Into<PathBuf>>(mut self, cache_dir: P) -> Self {
+        self.cache_dir = cache_dir.into();
+        self
+    }
+
+    pub fn get_cache_dir(&self) -> &Path {
+        &self.cache_dir
+    }
+
+    /// Sets the base URL of the GitHub API the releases are fetched from, e.g. a GitHub Enterprise
+    /// instance or a mirror that serves the same responses. Defaults to [`GITHUB_API_URL`].
+    ///
+    /// If a custom API URL is set, the archives are downloaded through the release asset
+    /// endpoint of this API as well (`/repos/{repository}/releases/assets/{id}`), so a mirror
+    /// has to serve the assets too.
+    pub fn set_api_url(mut self, api_url: &str) -> Self {
+        self.api_url = api_url.trim_end_matches('/').to_string();
+        self
+    }
+
+    pub fn get_api_url(&self) -> &str {
+        &self.api_url
+    }
+
+    /// Sends every request through the given HTTP(S) or SOCKS5 proxy, e.g. `http://proxy.corp:8080`
+    /// or `socks5://proxy.corp:1080` (`socks5h://` resolves host names through the proxy).
+    /// Without a proxy, the proxy of the system (e.g. `HTTPS_PROXY`) is used.
+    pub fn set_proxy(mut self, proxy: &str) -> Self {
+        self.proxy = Some(proxy.to_string());
+        self
+    }
+
+    pub fn get_proxy(&self) -> Option<&str> {
+        self.proxy.as_deref()
+    }
+
+    /// Adds a header to the requests to the GitHub API. If any header is set, the archives are
+    /// downloaded through the release asset endpoint of the API with these headers as well,
+    /// instead of the public download URLs of GitHub.
+    pub fn add_api_header(mut self, name: &str, value: &str) -> Self {
+        self.api_headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Authenticates the requests to the GitHub API with the given token,
+    /// which avoids the rate limit for unauthenticated requests.
+    pub fn set_github_token(self, token: &str) -> Self {
+        self.add_api_header("Authorization", &format!("Bearer {}", token))
+    }
+
     /// Disables the automatic restart of the application after the update is applied.
     pub fn set_no_restart(mut self) -> Self {
         self.restart_after_update = false;
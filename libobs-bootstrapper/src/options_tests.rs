@@ -1,11 +1,16 @@
 #[cfg(test)]
 mod tests {
-    use crate::{ObsBootstrapperOptions, options::GITHUB_REPO};
+    use crate::{
+        ObsBootstrapperOptions,
+        options::{GITHUB_API_URL, GITHUB_REPO},
+    };
 
     #[test]
     fn test_default_options() {
         let options = ObsBootstrapperOptions::new();
         assert_eq!(options.get_repository(), GITHUB_REPO);
+        assert_eq!(options.get_api_url(), GITHUB_API_URL);
+        assert_eq!(options.get_proxy(), None);
         assert!(options.update);
         assert!(options.restart_after_update);
     }
@@ -34,6 +39,31 @@ mod tests {
         assert!(!options.restart_after_update);
     }
 
+    #[test]
+    fn test_set_cache_dir() {
+        let options = ObsBootstrapperOptions::new().set_cache_dir("/tmp/obs-cache");
+        assert_eq!(
+            options.get_cache_dir(),
+            std::path::Path::new("/tmp/obs-cache")
+        );
+    }
+
+    #[test]
+    fn test_network_options() {
+        let options = ObsBootstrapperOptions::new()
+            .set_api_url("https://github.corp/api/v3/")
+            .set_proxy("http://proxy.corp:8080")
+            .set_github_token("secret");
+
+        assert_eq!(options.get_api_url(), "https://github.corp/api/v3");
+        assert_eq!(options.get_proxy(), Some("http://proxy.corp:8080"));
+        assert_eq!(
+            options.api_headers,
+            vec![("Authorization".to_string(), "Bearer secret".to_string())]
+        );
+        assert!(!format!("{:?}", options).contains("secret"));
+    }
+
     #[test]
     fn test_chaining() {
         let options = ObsBootstrapperOptions::new()
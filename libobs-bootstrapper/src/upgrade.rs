@@ -0,0 +1,361 @@
+use std::{
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use crate::error::ObsBootstrapError;
+
+/// Directories of the installation that belong to OBS as a whole, even if the new runtime
+/// does not ship them anymore
+const OBS_OWNED_DIRS: &[&str] = &["obs-plugins", "data"];
+
+/// Lists the top-level entries of the installed runtime, so entries the next runtime does not
+/// ship anymore (e.g. removed core libraries) are moved out of the way by the next upgrade
+const MANIFEST_NAME: &str = "obs_runtime.manifest";
+
+/// A new OBS runtime that has been downloaded, verified and extracted next to the
+/// current one, created by `ObsBootstrapper::upgrade`.
+///
+/// Nothing in the current installation is touched until [`ObsUpgrade::apply`] is called.
+#[derive(Debug, Clone)]
+pub struct ObsUpgrade {
+    install_dir: PathBuf,
+    new_dir: PathBuf,
+    backup_dir: PathBuf,
+    journal_path: PathBuf,
+    /// Makes moving this entry fail, to test the rollback
+    #[cfg(test)]
+    fail_on: Option<PathBuf>,
+}
+
+/// A top-level entry of the installation that is moved by the upgrade
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum JournalEntry {
+    /// An entry of the current runtime that is moved into the backup directory
+    Backup(PathBuf),
+    /// An entry of the new runtime that is moved into the installation
+    Install(PathBuf),
+}
+
+impl ObsUpgrade {
+    pub(crate) fn new(install_dir: &Path) -> Self {
+        Self {
+            install_dir: install_dir.to_path_buf(),
+            new_dir: install_dir.join("obs_new"),
+            backup_dir: install_dir.join("obs_backup"),
+            journal_path: install_dir.join("obs_upgrade.journal"),
+            #[cfg(test)]
+            fail_on: None,
+        }
+    }
+
+    #[cfg(test)]
+    pub(crate) fn fail_on(mut self, name: &str) -> Self {
+        self.fail_on = Some(PathBuf::from(name));
+        self
+    }
+
+    /// The directory the new runtime has been extracted to
+    pub fn new_dir(&self) -> &Path {
+        &self.new_dir
+    }
+
+    pub fn install_dir(&self) -> &Path {
+        &self.install_dir
+    }
+
+    /// Replaces the current OBS runtime with the new one.
+    ///
+    /// The `ObsContext` must have been shut down (see `ObsContext::shutdown`) before calling
+    /// this. The whole current runtime is moved into a backup directory first: the `obs-plugins`
+    /// and `data` directories, every top-level entry the new runtime ships and every entry the
+    /// current runtime has been installed with. Files the new version does not ship anymore
+    /// can't be picked up by the new libobs this way. The files are moved instead of being
+    /// deleted, as libraries that are still mapped into the process can be renamed but not
+    /// removed on Windows. The new runtime is loaded the next time the application starts.
+    ///
+    /// Before anything is moved, the planned moves are written to a journal in the installation
+    /// directory, which is only removed once the new runtime is in place. If anything can not be
+    /// moved, every change is undone and the current runtime stays in place. If the process
+    /// dies halfway, the bootstrapper undoes the swap on the next start, see [`Self::recover`].
+    /// Returns the error that caused the rollback.
+    pub fn apply(self) -> Result<(), ObsBootstrapError> {
+        if !self.new_dir.is_dir() {
+            return Err(ObsBootstrapError::InvalidState);
+        }
+
+        // An interrupted upgrade must be rolled back before its backup is removed
+        self.rollback_journal()?;
+
+        // A backup of a previous upgrade may still be there if its libraries were in use
+        if self.backup_dir.exists() {
+            fs::remove_dir_all(&self.backup_dir)
+                .map_err(|e| ObsBootstrapError::IoError("Removing old OBS backup", e))?;
+        }
+
+        let mut new_entries = self
+            .top_level_entries(&self.new_dir)
+            .map_err(|e| ObsBootstrapError::IoError("Listing new OBS files", e))?;
+        new_entries.retain(|name| name != Path::new(MANIFEST_NAME));
+
+        // The manifest is swapped like every other entry, so it always describes the installed runtime
+        write_atomically(
+            &self.new_dir.join(MANIFEST_NAME),
+            &new_entries
+                .iter()
+                .map(|name| format!("{}\n", name.to_string_lossy()))
+                .collect::<String>(),
+        )?;
+        new_entries.push(PathBuf::from(MANIFEST_NAME));
+
+        let mut owned = OBS_OWNED_DIRS
+            .iter()
+            .map(PathBuf::from)
+            .chain(new_entries.iter().cloned())
+            .chain(self.read_manifest()?)
+            .filter(|name| self.install_dir.join(name).symlink_metadata().is_ok())
+            .collect::<Vec<_>>();
+        owned.sort();
+        owned.dedup();
+
+        let entries = owned
+            .into_iter()
+            .map(JournalEntry::Backup)
+            .chain(new_entries.into_iter().map(JournalEntry::Install))
+            .collect::<Vec<_>>();
+
+        self.write_journal(&entries)?;
+
+        for entry in &entries {
+            if let Err(e) = self.move_entry(entry) {
+                log::error!(
+                    "Failed to move {:?}, rolling back the OBS upgrade: {}",
+                    entry,
+                    e
+                );
+
+                if let Err(rollback_error) = self.rollback_journal() {
+                    return Err(ObsBootstrapError::GeneralError(format!(
+                        "Upgrading OBS failed ({}) and the rollback failed as well ({}), the previous files are in {}",
+                        e,
+                        rollback_error,
+                        self.backup_dir.display()
+                    )));
+                }
+
+                return Err(e);
+            }
+        }
+
+        // The new runtime is in place, removing the journal commits the upgrade
+        fs::remove_file(&self.journal_path)
+            .map_err(|e| ObsBootstrapError::IoError("Removing OBS upgrade journal", e))?;
+
+        if let Err(e) = fs::remove_dir_all(&self.new_dir) {
+            log::warn!("Could not remove {}: {}", self.new_dir.display(), e);
+        }
+
+        // Fails on Windows while the old libraries are still loaded, the next upgrade removes it
+        if let Err(e) = fs::remove_dir_all(&self.backup_dir) {
+            log::debug!("Could not remove the OBS backup yet: {}", e);
+        }
+
+        log::info!("OBS has been upgraded");
+        Ok(())
+    }
+
+    /// Undoes an upgrade of the installation in `install_dir` that was interrupted while
+    /// [`Self::apply`] swapped the runtime, e.g. because the process crashed. The whole
+    /// previous runtime is restored from the backup.
+    ///
+    /// This is called by the bootstrapper before it checks the installed version.
+    /// Returns whether an interrupted upgrade has been rolled back.
+    pub fn recover(install_dir: &Path) -> Result<bool, ObsBootstrapError> {
+        ObsUpgrade::new(install_dir).rollback_journal()
+    }
+
+    /// Moves an entry of the current runtime into the backup directory or an entry of the new
+    /// runtime into the installation.
+    fn move_entry(&self, entry: &JournalEntry) -> Result<(), ObsBootstrapError> {
+        #[cfg(test)]
+        self.injected_failure(entry)?;
+
+        match entry {
+            JournalEntry::Backup(name) => {
+                fs::create_dir_all(&self.backup_dir)
+                    .map_err(|e| ObsBootstrapError::IoError("Creating OBS backup directory", e))?;
+
+                fs::rename(self.install_dir.join(name), self.backup_dir.join(name))
+                    .map_err(|e| ObsBootstrapError::IoError("Moving OBS file into the backup", e))
+            }
+            JournalEntry::Install(name) => {
+                fs::rename(self.new_dir.join(name), self.install_dir.join(name))
+                    .map_err(|e| ObsBootstrapError::IoError("Moving new OBS file", e))
+            }
+        }
+    }
+
+    #[cfg(test)]
+    fn injected_failure(&self, entry: &JournalEntry) -> Result<(), ObsBootstrapError> {
+        if let (Some(fail_on), JournalEntry::Install(name)) = (&self.fail_on, entry)
+            && fail_on == name
+        {
+            return Err(ObsBootstrapError::IoError(
+                "Moving new OBS file",
+                io::Error::other("Injected failure"),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Restores the previous runtime if there is a journal. Each entry is restored depending on
+    /// how far it has been moved, so this can be repeated if it fails halfway.
+    fn rollback_journal(&self) -> Result<bool, ObsBootstrapError> {
+        let Some(entries) = self.read_journal()? else {
+            return Ok(false);
+        };
+
+        log::warn!(
+            "Rolling back an unfinished OBS upgrade in {}",
+            self.install_dir.display()
+        );
+
+        // The new runtime is moved out first, so the backup can be restored into its place
+        for entry in entries.iter().rev() {
+            match entry {
+                JournalEntry::Install(name) => {
+                    let source = self.new_dir.join(name);
+                    let destination = self.install_dir.join(name);
+                    if source.symlink_metadata().is_err() && destination.symlink_metadata().is_ok()
+                    {
+                        fs::create_dir_all(&self.new_dir)
+                            .map_err(|e| ObsBootstrapError::IoError("Creating OBS directory", e))?;
+
+                        fs::rename(&destination, &source).map_err(|e| {
+                            ObsBootstrapError::IoError("Moving new OBS file back", e)
+                        })?;
+                    }
+                }
+                JournalEntry::Backup(name) => {
+                    let backup = self.backup_dir.join(name);
+                    let destination = self.install_dir.join(name);
+                    if destination.symlink_metadata().is_err() && backup.symlink_metadata().is_ok()
+                    {
+                        fs::rename(&backup, &destination)
+                            .map_err(|e| ObsBootstrapError::IoError("Restoring OBS file", e))?;
+                    }
+                }
+            }
+        }
+
+        fs::remove_file(&self.journal_path)
+            .map_err(|e| ObsBootstrapError::IoError("Removing OBS upgrade journal", e))?;
+
+        log::info!("Rolled back the OBS upgrade");
+        Ok(true)
+    }
+
+    /// Returns the names of the entries in the directory, without the files of the upgrade itself.
+    fn top_level_entries(&self, dir: &Path) -> io::Result<Vec<PathBuf>> {
+        let mut entries = vec![];
+        for entry in fs::read_dir(dir)? {
+            let name = PathBuf::from(entry?.file_name());
+            if !self.is_reserved(&name) {
+                entries.push(name);
+            }
+        }
+
+        entries.sort();
+        Ok(entries)
+    }
+
+    /// Whether the entry of the installation belongs to the upgrade instead of the runtime
+    fn is_reserved(&self, name: &Path) -> bool {
+        [&self.new_dir, &self.backup_dir, &self.journal_path]
+            .iter()
+            .any(|path| path.file_name() == Some(name.as_os_str()))
+    }
+
+    /// Reads the top-level entries the current runtime has been installed with
+    fn read_manifest(&self) -> Result<Vec<PathBuf>, ObsBootstrapError> {
+        let content = match fs::read_to_string(self.install_dir.join(MANIFEST_NAME)) {
+            Ok(content) => content,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+            Err(e) => {
+                return Err(ObsBootstrapError::IoError(
+                    "Reading OBS runtime manifest",
+                    e,
+                ));
+            }
+        };
+
+        Ok(content
+            .lines()
+            .map(PathBuf::from)
+            .filter(|name| is_plain_name(name) && !self.is_reserved(name))
+            .collect())
+    }
+
+    /// Writes the journal to a temporary file first, so it is either complete or missing.
+    fn write_journal(&self, entries: &[JournalEntry]) -> Result<(), ObsBootstrapError> {
+        let content = entries
+            .iter()
+            .map(|entry| match entry {
+                JournalEntry::Backup(name) => format!("backup\t{}\n", name.to_string_lossy()),
+                JournalEntry::Install(name) => format!("install\t{}\n", name.to_string_lossy()),
+            })
+            .collect::<String>();
+
+        write_atomically(&self.journal_path, &content)
+    }
+
+    fn read_journal(&self) -> Result<Option<Vec<JournalEntry>>, ObsBootstrapError> {
+        let content = match fs::read_to_string(&self.journal_path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(ObsBootstrapError::IoError("Reading OBS upgrade journal", e)),
+        };
+
+        content
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(|line| match line.split_once('\t') {
+                Some(("backup", name)) if is_plain_name(Path::new(name)) => {
+                    Ok(JournalEntry::Backup(PathBuf::from(name)))
+                }
+                Some(("install", name)) if is_plain_name(Path::new(name)) => {
+                    Ok(JournalEntry::Install(PathBuf::from(name)))
+                }
+                _ => Err(ObsBootstrapError::InvalidFormatError(format!(
+                    "Invalid line in OBS upgrade journal: {}",
+                    line
+                ))),
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map(Some)
+    }
+}
+
+/// Whether the path is a single file or directory name, so it can't point outside the installation
+fn is_plain_name(name: &Path) -> bool {
+    let mut components = name.components();
+    matches!(
+        (components.next(), components.next()),
+        (Some(std::path::Component::Normal(_)), None)
+    )
+}
+
+/// Writes to a temporary file first and moves it into place, so the file is either complete or missing.
+fn write_atomically(path: &Path, content: &str) -> Result<(), ObsBootstrapError> {
+    let temp_path = path.with_extension("tmp");
+    let mut file = fs::File::create(&temp_path)
+        .map_err(|e| ObsBootstrapError::IoError("Creating OBS upgrade file", e))?;
+    file.write_all(content.as_bytes())
+        .and_then(|_| file.sync_all())
+        .map_err(|e| ObsBootstrapError::IoError("Writing OBS upgrade file", e))?;
+
+    fs::rename(&temp_path, path)
+        .map_err(|e| ObsBootstrapError::IoError("Moving OBS upgrade file", e))
+}
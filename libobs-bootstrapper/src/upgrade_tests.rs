@@ -0,0 +1,143 @@
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::PathBuf};
+
+    use crate::upgrade::ObsUpgrade;
+
+    fn install_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("libobs-upgrade-{}-{}", name, uuid::Uuid::new_v4()));
+        fs::create_dir_all(dir.join("obs_new/obs-plugins/64bit")).unwrap();
+        fs::create_dir_all(dir.join("obs-plugins/64bit")).unwrap();
+
+        fs::write(dir.join("obs.dll"), "old").unwrap();
+        fs::write(dir.join("obs-plugins/64bit/obs-x264.dll"), "old").unwrap();
+        fs::write(dir.join("app.exe"), "app").unwrap();
+
+        fs::write(dir.join("obs_new/obs.dll"), "new").unwrap();
+        fs::write(dir.join("obs_new/obs-plugins/64bit/obs-x264.dll"), "new").unwrap();
+        fs::write(dir.join("obs_new/obs-plugins/64bit/obs-ffmpeg.dll"), "new").unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_apply_swaps_runtime() {
+        let dir = install_dir("apply");
+        ObsUpgrade::new(&dir).apply().unwrap();
+
+        assert_eq!(fs::read_to_string(dir.join("obs.dll")).unwrap(), "new");
+        assert_eq!(
+            fs::read_to_string(dir.join("obs-plugins/64bit/obs-x264.dll")).unwrap(),
+            "new"
+        );
+        assert_eq!(
+            fs::read_to_string(dir.join("obs-plugins/64bit/obs-ffmpeg.dll")).unwrap(),
+            "new"
+        );
+        assert_eq!(fs::read_to_string(dir.join("app.exe")).unwrap(), "app");
+        assert!(!dir.join("obs_new").exists());
+        assert!(!dir.join("obs_backup").exists());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_apply_removes_files_the_new_runtime_does_not_ship() {
+        let dir = install_dir("stale");
+        fs::write(dir.join("obs-plugins/64bit/removed-plugin.dll"), "old").unwrap();
+        fs::create_dir_all(dir.join("data/removed-plugin")).unwrap();
+        fs::write(dir.join("data/removed-plugin/locale.ini"), "old").unwrap();
+        ObsUpgrade::new(&dir).apply().unwrap();
+
+        assert!(!dir.join("obs-plugins/64bit/removed-plugin.dll").exists());
+        assert!(!dir.join("data").exists());
+        assert_eq!(fs::read_to_string(dir.join("app.exe")).unwrap(), "app");
+
+        // A core library the next runtime does not ship anymore is moved out by its upgrade
+        fs::create_dir_all(dir.join("obs_new/obs-plugins")).unwrap();
+        fs::write(dir.join("obs_new/libobs.dll"), "newer").unwrap();
+        ObsUpgrade::new(&dir).apply().unwrap();
+
+        assert!(!dir.join("obs.dll").exists());
+        assert!(!dir.join("obs-plugins/64bit").exists());
+        assert_eq!(fs::read_to_string(dir.join("libobs.dll")).unwrap(), "newer");
+        assert_eq!(fs::read_to_string(dir.join("app.exe")).unwrap(), "app");
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_apply_rolls_back_on_failure() {
+        let dir = install_dir("rollback");
+        fs::write(dir.join("obs-plugins/64bit/removed-plugin.dll"), "old").unwrap();
+        fs::write(dir.join("obs_new/zz.dll"), "new").unwrap();
+
+        // obs-plugins and obs.dll are in place when moving zz.dll fails
+        assert!(ObsUpgrade::new(&dir).fail_on("zz.dll").apply().is_err());
+
+        assert_eq!(fs::read_to_string(dir.join("obs.dll")).unwrap(), "old");
+        assert_eq!(
+            fs::read_to_string(dir.join("obs-plugins/64bit/obs-x264.dll")).unwrap(),
+            "old"
+        );
+        assert_eq!(
+            fs::read_to_string(dir.join("obs-plugins/64bit/removed-plugin.dll")).unwrap(),
+            "old"
+        );
+        assert!(!dir.join("obs-plugins/64bit/obs-ffmpeg.dll").exists());
+        assert!(!dir.join("zz.dll").exists());
+        assert_eq!(fs::read_to_string(dir.join("app.exe")).unwrap(), "app");
+        assert_eq!(
+            fs::read_to_string(dir.join("obs_new/obs.dll")).unwrap(),
+            "new"
+        );
+        assert!(!dir.join("obs_upgrade.journal").exists());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_recover_interrupted_apply() {
+        let dir = install_dir("recover");
+        // The process died after the current runtime has been moved into the backup and
+        // obs.dll has been moved into place, but before the new obs-plugins directory
+        fs::write(
+            dir.join("obs_upgrade.journal"),
+            "backup\tobs-plugins\nbackup\tobs.dll\ninstall\tobs-plugins\ninstall\tobs.dll\n",
+        )
+        .unwrap();
+        fs::create_dir_all(dir.join("obs_backup")).unwrap();
+        fs::rename(dir.join("obs-plugins"), dir.join("obs_backup/obs-plugins")).unwrap();
+        fs::rename(dir.join("obs.dll"), dir.join("obs_backup/obs.dll")).unwrap();
+        fs::rename(dir.join("obs_new/obs.dll"), dir.join("obs.dll")).unwrap();
+
+        assert!(ObsUpgrade::recover(&dir).unwrap());
+
+        assert_eq!(fs::read_to_string(dir.join("obs.dll")).unwrap(), "old");
+        assert_eq!(
+            fs::read_to_string(dir.join("obs-plugins/64bit/obs-x264.dll")).unwrap(),
+            "old"
+        );
+        assert!(!dir.join("obs-plugins/64bit/obs-ffmpeg.dll").exists());
+        assert_eq!(
+            fs::read_to_string(dir.join("obs_new/obs.dll")).unwrap(),
+            "new"
+        );
+        assert!(!dir.join("obs_upgrade.journal").exists());
+
+        // Nothing is left to recover and the upgrade can be applied again
+        assert!(!ObsUpgrade::recover(&dir).unwrap());
+        ObsUpgrade::new(&dir).apply().unwrap();
+        assert_eq!(fs::read_to_string(dir.join("obs.dll")).unwrap(), "new");
+        assert!(!dir.join("obs_upgrade.journal").exists());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_apply_without_new_runtime() {
+        let dir =
+            std::env::temp_dir().join(format!("libobs-upgrade-missing-{}", uuid::Uuid::new_v4()));
+        assert!(ObsUpgrade::new(&dir).apply().is_err());
+    }
+}
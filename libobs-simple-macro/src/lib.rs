@@ -108,7 +108,7 @@ pub fn obs_object_updater(attr: TokenStream, item: TokenStream) -> TokenStream {
             }
 
             fn get_id() -> libobs_wrapper::utils::ObsString {
-                #id_value.into()
+                libobs_wrapper::obs_string!(#id_value)
             }
 
             fn update(self) -> Result<(), libobs_wrapper::utils::ObsError> {
@@ -291,7 +291,7 @@ pub fn obs_object_builder(attr: TokenStream, item: TokenStream) -> TokenStream {
             }
 
             fn get_id() -> libobs_wrapper::utils::ObsString {
-                #id_value.into()
+                libobs_wrapper::obs_string!(#id_value)
             }
 
             fn object_build(self) -> Result<libobs_wrapper::utils::ObjectInfo, libobs_wrapper::utils::ObsError> {
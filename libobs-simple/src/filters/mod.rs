@@ -0,0 +1,9 @@
+//! Ready-made filter chains for common use cases.
+//!
+//! Filters are created as `ObsFilterRef`s and applied to the given sources, they stay
+//! attached for as long as the source (or one of its clones) is alive.
+
+mod webcam;
+#[cfg(test)]
+mod webcam_tests;
+pub use webcam::*;
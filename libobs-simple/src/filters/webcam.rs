@@ -0,0 +1,299 @@
+use libobs_wrapper::{
+    data::{object::ObsObjectTrait, ObsData},
+    run_with_obs,
+    runtime::ObsRuntime,
+    sources::{ObsFilterRef, ObsSourceTrait},
+};
+
+use crate::error::ObsSimpleError;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// Key color used by the chroma key filter
+pub enum ObsChromaKeyColor {
+    Green,
+    Blue,
+    Magenta,
+}
+
+impl ObsChromaKeyColor {
+    fn as_str(&self) -> &'static str {
+        match self {
+            ObsChromaKeyColor::Green => "green",
+            ObsChromaKeyColor::Blue => "blue",
+            ObsChromaKeyColor::Magenta => "magenta",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// Noise suppression method used for the linked microphone
+pub enum ObsNoiseSuppressMethod {
+    /// Speex, low CPU usage but lower quality
+    Speex,
+    /// RNNoise, good quality at a moderate CPU usage
+    RNNoise,
+    /// NVIDIA Broadcast noise removal, requires a supported NVIDIA GPU and the NVIDIA Audio
+    /// Effects SDK
+    Nvidia,
+}
+
+impl ObsNoiseSuppressMethod {
+    fn as_str(&self) -> &'static str {
+        match self {
+            ObsNoiseSuppressMethod::Speex => "speex",
+            ObsNoiseSuppressMethod::RNNoise => "rnnoise",
+            ObsNoiseSuppressMethod::Nvidia => "denoiser",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+/// Configuration of the standard webcam filter chain applied by `apply_webcam_preset`.
+///
+/// Every step can be disabled by setting it to `None`. The defaults crop to 16:9, apply a
+/// subtle color correction and sharpen, and use RNNoise on the microphone.
+pub struct WebcamFilterPreset {
+    /// Crops the camera to the given aspect ratio (width, height), centered.
+    pub crop_to_aspect: Option<(u32, u32)>,
+    /// Keys out the given background color.
+    pub chroma_key: Option<ObsChromaKeyColor>,
+    /// Color correction applied to the camera.
+    pub color_correction: Option<WebcamColorCorrection>,
+    /// Sharpness in the range 0.0 - 1.0.
+    pub sharpen: Option<f64>,
+    /// Noise suppression applied to the microphone, if one is given.
+    pub noise_suppression: Option<ObsNoiseSuppressMethod>,
+}
+
+impl Default for WebcamFilterPreset {
+    fn default() -> Self {
+        Self {
+            crop_to_aspect: Some((16, 9)),
+            chroma_key: None,
+            color_correction: Some(WebcamColorCorrection::default()),
+            sharpen: Some(0.08),
+            noise_suppression: Some(ObsNoiseSuppressMethod::RNNoise),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+/// Settings of the color correction filter, see the OBS "Color Correction" filter for ranges.
+pub struct WebcamColorCorrection {
+    /// Gamma in the range -3.0 - 3.0
+    pub gamma: f64,
+    /// Contrast in the range -4.0 - 4.0
+    pub contrast: f64,
+    /// Brightness in the range -1.0 - 1.0
+    pub brightness: f64,
+    /// Saturation in the range -1.0 - 5.0
+    pub saturation: f64,
+}
+
+impl Default for WebcamColorCorrection {
+    fn default() -> Self {
+        Self {
+            gamma: 0.0,
+            contrast: 0.1,
+            brightness: 0.02,
+            saturation: 0.1,
+        }
+    }
+}
+
+/// The filters created by `apply_webcam_preset`, in the order they were applied.
+#[derive(Debug, Clone)]
+pub struct WebcamFilterChain {
+    pub video_filters: Vec<ObsFilterRef>,
+    pub audio_filters: Vec<ObsFilterRef>,
+}
+
+/// Applies the webcam filter chain described by `preset` to `camera` and, if given, the
+/// noise suppression to `mic`.
+///
+/// The crop is computed from the current size of the camera, so the camera should already be
+/// active (e.g. added to a scene with a device selected). If the camera does not report a size
+/// yet, the crop step is skipped and a warning is logged.
+///
+/// If a filter can not be created or applied, the filters that have already been applied are
+/// removed again and the error is returned.
+pub fn apply_webcam_preset(
+    camera: &dyn ObsSourceTrait,
+    mic: Option<&dyn ObsSourceTrait>,
+    preset: &WebcamFilterPreset,
+) -> Result<WebcamFilterChain, ObsSimpleError> {
+    let runtime = camera.runtime().clone();
+    let camera_name = camera.name().to_string();
+    let mut video_filters = Vec::new();
+
+    if let Some((aspect_w, aspect_h)) = preset.crop_to_aspect {
+        let camera_ptr = camera.as_ptr();
+        let (width, height) = run_with_obs!(runtime, (camera_ptr), move |token| unsafe {
+            // Safety: The source pointer is valid because we are using a smart pointer
+            let source = camera_ptr.get_ptr(token);
+            (
+                libobs::obs_source_get_width(source),
+                libobs::obs_source_get_height(source),
+            )
+        })?;
+
+        match aspect_crop(width, height, aspect_w, aspect_h) {
+            Some((crop_x, crop_y)) => {
+                let mut settings = ObsData::new(runtime.clone())?;
+                settings
+                    .bulk_update()
+                    .set_int("left", crop_x)
+                    .set_int("right", crop_x)
+                    .set_int("top", crop_y)
+                    .set_int("bottom", crop_y)
+                    .set_bool("relative", true)
+                    .apply()?;
+
+                video_filters.push(create_filter(
+                    "crop_filter",
+                    format!("{} Crop", camera_name),
+                    settings,
+                    &runtime,
+                )?);
+            }
+            None => log::warn!(
+                "Could not crop {} to {}:{}, source size is {}x{}",
+                camera_name,
+                aspect_w,
+                aspect_h,
+                width,
+                height
+            ),
+        }
+    }
+
+    if let Some(color) = preset.chroma_key {
+        let mut settings = ObsData::new(runtime.clone())?;
+        settings
+            .bulk_update()
+            .set_string("key_color_type", color.as_str())
+            .apply()?;
+
+        video_filters.push(create_filter(
+            "chroma_key_filter_v2",
+            format!("{} Chroma Key", camera_name),
+            settings,
+            &runtime,
+        )?);
+    }
+
+    if let Some(correction) = preset.color_correction {
+        let mut settings = ObsData::new(runtime.clone())?;
+        settings
+            .bulk_update()
+            .set_double("gamma", correction.gamma)
+            .set_double("contrast", correction.contrast)
+            .set_double("brightness", correction.brightness)
+            .set_double("saturation", correction.saturation)
+            .apply()?;
+
+        video_filters.push(create_filter(
+            "color_filter_v2",
+            format!("{} Color Correction", camera_name),
+            settings,
+            &runtime,
+        )?);
+    }
+
+    if let Some(sharpness) = preset.sharpen {
+        let mut settings = ObsData::new(runtime.clone())?;
+        settings
+            .bulk_update()
+            .set_double("sharpness", sharpness.clamp(0.0, 1.0))
+            .apply()?;
+
+        video_filters.push(create_filter(
+            "sharpness_filter_v2",
+            format!("{} Sharpen", camera_name),
+            settings,
+            &runtime,
+        )?);
+    }
+
+    let mut audio_filters = Vec::new();
+    if let (Some(mic), Some(method)) = (mic, preset.noise_suppression) {
+        let mut settings = ObsData::new(runtime.clone())?;
+        settings
+            .bulk_update()
+            .set_string("method", method.as_str())
+            .apply()?;
+
+        audio_filters.push(create_filter(
+            "noise_suppress_filter_v2",
+            format!("{} Noise Suppression", mic.name()),
+            settings,
+            &runtime,
+        )?);
+    }
+
+    // Either the whole chain is applied or none of it
+    let targets = video_filters.iter().map(|filter| (camera, filter)).chain(
+        mic.into_iter()
+            .flat_map(|mic| audio_filters.iter().map(move |f| (mic, f))),
+    );
+
+    let mut applied: Vec<(&dyn ObsSourceTrait, &ObsFilterRef)> = Vec::new();
+    for (source, filter) in targets {
+        if let Err(e) = source.apply_filter(filter) {
+            for (source, filter) in applied.into_iter().rev() {
+                if let Err(remove_error) = source.remove_filter(filter) {
+                    log::warn!(
+                        "Failed to remove filter {} after applying the webcam preset failed: {:?}",
+                        filter.name(),
+                        remove_error
+                    );
+                }
+            }
+
+            return Err(e.into());
+        }
+
+        applied.push((source, filter));
+    }
+
+    Ok(WebcamFilterChain {
+        video_filters,
+        audio_filters,
+    })
+}
+
+fn create_filter(
+    id: &str,
+    name: String,
+    settings: ObsData,
+    runtime: &ObsRuntime,
+) -> Result<ObsFilterRef, ObsSimpleError> {
+    let runtime = runtime.clone();
+    let filter = ObsFilterRef::new(id, name, Some(settings.into_immutable()), None, runtime)?;
+
+    Ok(filter)
+}
+
+/// Returns the horizontal and vertical crop (per side) needed to center-crop
+/// a `width`x`height` frame to the given aspect ratio.
+pub(super) fn aspect_crop(
+    width: u32,
+    height: u32,
+    aspect_w: u32,
+    aspect_h: u32,
+) -> Option<(i64, i64)> {
+    if width == 0 || height == 0 || aspect_w == 0 || aspect_h == 0 {
+        return None;
+    }
+
+    let (width, height) = (width as i64, height as i64);
+    let (aspect_w, aspect_h) = (aspect_w as i64, aspect_h as i64);
+
+    if width * aspect_h > height * aspect_w {
+        let target_width = height * aspect_w / aspect_h;
+        Some(((width - target_width) / 2, 0))
+    } else {
+        let target_height = width * aspect_h / aspect_w;
+        Some((0, (height - target_height) / 2))
+    }
+}
@@ -0,0 +1,34 @@
+#[cfg(test)]
+mod tests {
+    use super::super::webcam::aspect_crop;
+
+    #[test]
+    fn test_aspect_crop_wider_source() {
+        // 4:3 cropped to 1:1 removes the sides
+        assert_eq!(aspect_crop(1440, 1080, 1, 1), Some((180, 0)));
+        // Ultra wide cropped to 16:9
+        assert_eq!(aspect_crop(2560, 1080, 16, 9), Some((320, 0)));
+    }
+
+    #[test]
+    fn test_aspect_crop_taller_source() {
+        // 4:3 cropped to 16:9 removes top and bottom
+        assert_eq!(aspect_crop(1440, 1080, 16, 9), Some((0, 135)));
+        // Portrait cropped to 1:1
+        assert_eq!(aspect_crop(1080, 1920, 1, 1), Some((0, 420)));
+    }
+
+    #[test]
+    fn test_aspect_crop_matching_aspect() {
+        assert_eq!(aspect_crop(1920, 1080, 16, 9), Some((0, 0)));
+        assert_eq!(aspect_crop(1280, 720, 16, 9), Some((0, 0)));
+    }
+
+    #[test]
+    fn test_aspect_crop_invalid_sizes() {
+        assert_eq!(aspect_crop(0, 1080, 16, 9), None);
+        assert_eq!(aspect_crop(1920, 0, 16, 9), None);
+        assert_eq!(aspect_crop(1920, 1080, 0, 9), None);
+        assert_eq!(aspect_crop(1920, 1080, 16, 0), None);
+    }
+}
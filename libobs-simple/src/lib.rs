@@ -2,8 +2,12 @@
 //! A simplified interface for recording and streaming with libobs
 
 pub mod error;
+pub mod filters;
 pub mod output;
 pub mod sources;
+#[cfg(feature = "test-harness")]
+#[cfg_attr(doc, doc(cfg(feature = "test-harness")))]
+pub mod testing;
 
 pub use error::ObsSimpleError;
 pub use libobs_wrapper as wrapper;
@@ -43,10 +43,13 @@ use libobs_wrapper::{
         ObsData, ObsDataGetters, ObsDataSetters,
     },
     encoders::{ObsAudioEncoderType, ObsContextEncoders, ObsVideoEncoderType},
-    utils::{AudioEncoderInfo, ObsError, ObsPath, ObsString, OutputInfo, VideoEncoderInfo},
+    enums::ObsScaleType,
+    utils::{AudioEncoderInfo, ObsError, ObsPath, ObsString, OutputInfo},
 };
 
-use super::simple::{AudioEncoder, HardwareCodec, HardwarePreset, VideoEncoder, X264Preset};
+use super::simple::{
+    create_video_encoder, AudioEncoder, HardwareCodec, HardwarePreset, VideoEncoder, X264Preset,
+};
 
 /// Settings for replay buffer output
 #[derive(Debug)]
@@ -68,6 +71,9 @@ pub struct ReplayBufferSettings {
     audio_bitrate: u32,
     directory: ObsPath,
     custom_encoder_settings: Option<String>,
+    /// Resolution the video is encoded at, `None` to use the output resolution
+    scaled_size: Option<(u32, u32)>,
+    gpu_scale_type: ObsScaleType,
 }
 
 impl ReplayBufferSettings {
@@ -137,6 +143,20 @@ impl ReplayBufferSettings {
         self
     }
 
+    /// Encodes the video at the given resolution instead of the output resolution of the
+    /// video context, e.g. to record at 1080p while the canvas is 1440p.
+    pub fn with_scaled_size(mut self, width: u32, height: u32) -> Self {
+        self.scaled_size = Some((width, height));
+        self
+    }
+
+    /// Sets the filter used to scale the video on the GPU if a scaled size is set.
+    /// `ObsScaleType::Disable` scales on the CPU instead. Defaults to bicubic.
+    pub fn with_gpu_scale_type(mut self, scale_type: ObsScaleType) -> Self {
+        self.gpu_scale_type = scale_type;
+        self
+    }
+
     /// Sets the audio encoder.
     pub fn with_audio_encoder(mut self, encoder: AudioEncoder) -> Self {
         self.audio_encoder = encoder;
@@ -191,6 +211,8 @@ impl ReplayBufferBuilder {
                 video_encoder: VideoEncoder::X264(X264Preset::VeryFast),
                 audio_encoder: AudioEncoder::AAC,
                 custom_encoder_settings: None,
+                scaled_size: None,
+                gpu_scale_type: ObsScaleType::Bicubic,
             },
             context,
         }
@@ -256,8 +278,27 @@ impl ReplayBufferBuilder {
         self
     }
 
+    /// Encodes the video at the given resolution instead of the output resolution.
+    pub fn scaled_size(mut self, width: u32, height: u32) -> Self {
+        self.settings.scaled_size = Some((width, height));
+        self
+    }
+
+    /// Sets the filter used to scale the video on the GPU.
+    pub fn gpu_scale_type(mut self, scale_type: ObsScaleType) -> Self {
+        self.settings.gpu_scale_type = scale_type;
+        self
+    }
+
     /// Builds and returns the configured replay buffer output.
-    pub fn build(mut self) -> Result<ObsReplayBufferOutputRef, ObsError> {
+    ///
+    /// The output and its encoders are created in a single dispatch onto the OBS thread.
+    pub fn build(self) -> Result<ObsReplayBufferOutputRef, ObsError> {
+        let runtime = self.context.runtime().clone();
+        runtime.batch(move |_runtime| self.build_inner())?
+    }
+
+    fn build_inner(mut self) -> Result<ObsReplayBufferOutputRef, ObsError> {
         if self.settings.max_size_mb <= 0 {
             return Err(ObsError::InvalidOperation(
                 "max_size_mb must be greater than 0".into(),
@@ -296,18 +337,18 @@ impl ReplayBufferBuilder {
 
         // Create and configure video encoder (with hardware fallback)
         let video_encoder_type = self.select_video_encoder_type(&self.settings.video_encoder)?;
-        let mut video_settings = self.context.data()?;
-
-        self.configure_video_encoder(&mut video_settings)?;
-
-        let video_encoder_info = VideoEncoderInfo::new(
+        let video_encoder = create_video_encoder(
+            &self.context,
+            &mut output,
+            &self.settings.video_encoder,
             video_encoder_type,
             format!("{}_video_encoder", self.settings.name),
-            Some(video_settings),
-            None,
-        );
-
-        output.create_and_set_video_encoder(video_encoder_info)?;
+            |settings, encoder| self.configure_video_encoder(settings, encoder),
+        )?;
+        if let Some((width, height)) = self.settings.scaled_size {
+            video_encoder.set_gpu_scale_type(self.settings.gpu_scale_type)?;
+            video_encoder.set_scaled_size(width, height)?;
+        }
 
         // Create and configure audio encoder
         let audio_encoder_type = match &self.settings.audio_encoder {
@@ -397,13 +438,17 @@ impl ReplayBufferBuilder {
         }
     }
 
-    fn configure_video_encoder(&self, settings: &mut ObsData) -> Result<(), ObsError> {
+    fn configure_video_encoder(
+        &self,
+        settings: &mut ObsData,
+        encoder: &VideoEncoder,
+    ) -> Result<(), ObsError> {
         // Set rate control to CBR
         settings.set_string("rate_control", "CBR")?;
         settings.set_int("bitrate", self.settings.video_bitrate as i64)?;
 
         // Set preset if available
-        if let Some(preset) = self.get_encoder_preset(&self.settings.video_encoder) {
+        if let Some(preset) = self.get_encoder_preset(encoder) {
             settings.set_string("preset", preset)?;
         }
 
@@ -35,13 +35,18 @@
 //! }
 //! ```
 
+use std::sync::Arc;
+
 use libobs_wrapper::{
     context::ObsContext,
     data::{
         output::{ObsOutputRef, ObsOutputTrait},
         ObsData, ObsDataSetters,
     },
-    encoders::{ObsAudioEncoderType, ObsContextEncoders, ObsVideoEncoderType},
+    encoders::{
+        video::ObsVideoEncoder, ObsAudioEncoderType, ObsContextEncoders, ObsVideoEncoderType,
+    },
+    enums::ObsScaleType,
     utils::{AudioEncoderInfo, ObsError, ObsPath, ObsString, OutputInfo, VideoEncoderInfo},
 };
 
@@ -100,6 +105,15 @@ impl HardwarePreset {
             HardwarePreset::Quality => "quality",
         }
     }
+
+    /// The x264 preset that is used if the hardware encoder can not be created.
+    pub fn x264_fallback(&self) -> X264Preset {
+        match self {
+            HardwarePreset::Speed => X264Preset::VeryFast,
+            HardwarePreset::Balanced => X264Preset::Faster,
+            HardwarePreset::Quality => X264Preset::Fast,
+        }
+    }
 }
 
 /// Video encoder configuration
@@ -159,6 +173,62 @@ pub enum OutputFormat {
     MpegTs,
 }
 
+/// Creates the video encoder of an output and sets it.
+///
+/// A hardware encoder can be listed, but still fail to be created (e.g. if the session limit of
+/// the GPU is reached). In that case an x264 encoder is created instead, `configure` is called
+/// again with its settings, so it gets the same rate control, bitrate and custom settings and
+/// a preset that matches the hardware preset.
+pub(crate) fn create_video_encoder<T: ObsOutputTrait>(
+    context: &ObsContext,
+    output: &mut T,
+    encoder: &VideoEncoder,
+    encoder_type: ObsVideoEncoderType,
+    name: String,
+    configure: impl Fn(&mut ObsData, &VideoEncoder) -> Result<(), ObsError>,
+) -> Result<Arc<ObsVideoEncoder>, ObsError> {
+    let mut video_settings = context.data()?;
+    configure(&mut video_settings, encoder)?;
+
+    let info = VideoEncoderInfo::new(
+        encoder_type.clone(),
+        name.clone(),
+        Some(video_settings),
+        None,
+    );
+
+    let e = match output.create_and_set_video_encoder(info) {
+        Ok(video_encoder) => return Ok(video_encoder),
+        Err(e) => e,
+    };
+
+    let VideoEncoder::Hardware { preset, .. } = encoder else {
+        return Err(e);
+    };
+    if !e.is_recoverable() || encoder_type == ObsVideoEncoderType::OBS_X264 {
+        return Err(e);
+    }
+
+    log::warn!(
+        "Failed to create video encoder {:?}, falling back to x264: {}",
+        encoder_type,
+        e
+    );
+
+    let mut video_settings = context.data()?;
+    configure(
+        &mut video_settings,
+        &VideoEncoder::X264(preset.x264_fallback()),
+    )?;
+
+    output.create_and_set_video_encoder(VideoEncoderInfo::new(
+        ObsVideoEncoderType::OBS_X264,
+        name,
+        Some(video_settings),
+        None,
+    ))
+}
+
 /// Unified output settings
 #[derive(Debug)]
 pub struct OutputSettings {
@@ -171,6 +241,9 @@ pub struct OutputSettings {
     path: ObsPath,
     format: OutputFormat,
     custom_muxer_settings: Option<String>,
+    /// Resolution the video is encoded at, `None` to use the output resolution
+    scaled_size: Option<(u32, u32)>,
+    gpu_scale_type: ObsScaleType,
 }
 
 impl OutputSettings {
@@ -229,6 +302,20 @@ impl OutputSettings {
         self
     }
 
+    /// Encodes the video at the given resolution instead of the output resolution of the
+    /// video context, e.g. to record at 1080p while the canvas is 1440p.
+    pub fn with_scaled_size(mut self, width: u32, height: u32) -> Self {
+        self.scaled_size = Some((width, height));
+        self
+    }
+
+    /// Sets the filter used to scale the video on the GPU if a scaled size is set.
+    /// `ObsScaleType::Disable` scales on the CPU instead. Defaults to bicubic.
+    pub fn with_gpu_scale_type(mut self, scale_type: ObsScaleType) -> Self {
+        self.gpu_scale_type = scale_type;
+        self
+    }
+
     /// Sets the audio encoder.
     pub fn with_audio_encoder(mut self, encoder: AudioEncoder) -> Self {
         self.audio_encoder = encoder;
@@ -278,6 +365,8 @@ impl SimpleOutputBuilder {
                 format: OutputFormat::default(),
                 custom_muxer_settings: None,
                 name: name.into(),
+                scaled_size: None,
+                gpu_scale_type: ObsScaleType::Bicubic,
             },
             context,
         }
@@ -325,8 +414,27 @@ impl SimpleOutputBuilder {
         self
     }
 
+    /// Encodes the video at the given resolution instead of the output resolution.
+    pub fn scaled_size(mut self, width: u32, height: u32) -> Self {
+        self.settings.scaled_size = Some((width, height));
+        self
+    }
+
+    /// Sets the filter used to scale the video on the GPU.
+    pub fn gpu_scale_type(mut self, scale_type: ObsScaleType) -> Self {
+        self.settings.gpu_scale_type = scale_type;
+        self
+    }
+
     /// Builds and returns the configured output.
-    pub fn build(mut self) -> Result<ObsOutputRef, ObsError> {
+    ///
+    /// The output and its encoders are created in a single dispatch onto the OBS thread.
+    pub fn build(self) -> Result<ObsOutputRef, ObsError> {
+        let runtime = self.context.runtime().clone();
+        runtime.batch(move |_runtime| self.build_inner())?
+    }
+
+    fn build_inner(mut self) -> Result<ObsOutputRef, ObsError> {
         // Determine the output type based on format
         let output_id = match self.settings.format {
             OutputFormat::HybridMP4 => "mp4_output",
@@ -354,18 +462,18 @@ impl SimpleOutputBuilder {
 
         // Create and configure video encoder (with hardware fallback)
         let video_encoder_type = self.select_video_encoder_type(&self.settings.video_encoder)?;
-        let mut video_settings = self.context.data()?;
-
-        self.configure_video_encoder(&mut video_settings)?;
-
-        let video_encoder_info = VideoEncoderInfo::new(
+        let video_encoder = create_video_encoder(
+            &self.context,
+            &mut output,
+            &self.settings.video_encoder,
             video_encoder_type,
             format!("{}_video_encoder", self.settings.name),
-            Some(video_settings),
-            None,
-        );
-
-        output.create_and_set_video_encoder(video_encoder_info)?;
+            |settings, encoder| self.configure_video_encoder(settings, encoder),
+        )?;
+        if let Some((width, height)) = self.settings.scaled_size {
+            video_encoder.set_gpu_scale_type(self.settings.gpu_scale_type)?;
+            video_encoder.set_scaled_size(width, height)?;
+        }
 
         // Create and configure audio encoder
         let audio_encoder_type = match &self.settings.audio_encoder {
@@ -456,13 +564,17 @@ impl SimpleOutputBuilder {
         }
     }
 
-    fn configure_video_encoder(&self, settings: &mut ObsData) -> Result<(), ObsError> {
+    fn configure_video_encoder(
+        &self,
+        settings: &mut ObsData,
+        encoder: &VideoEncoder,
+    ) -> Result<(), ObsError> {
         // Set rate control to CBR
         settings.set_string("rate_control", "CBR")?;
         settings.set_int("bitrate", self.settings.video_bitrate as i64)?;
 
         // Set preset if available
-        if let Some(preset) = self.get_encoder_preset(&self.settings.video_encoder) {
+        if let Some(preset) = self.get_encoder_preset(encoder) {
             settings.set_string("preset", preset)?;
         }
 
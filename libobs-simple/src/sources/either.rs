@@ -77,6 +77,13 @@ where
         }
     }
 
+    fn set_name(&self, new_name: &str) -> Result<(), ObsError> {
+        match self {
+            ObsEitherSource::Left(a) => a.set_name(new_name),
+            ObsEitherSource::Right(b) => b.set_name(new_name),
+        }
+    }
+
     fn update_settings(&self, settings: ObsData) -> Result<(), ObsError> {
         match self {
             ObsEitherSource::Left(a) => a.update_settings(settings),
@@ -117,6 +124,13 @@ where
             ObsEitherSource::Right(b) => b.apply_filter(filter),
         }
     }
+
+    fn remove_filter(&self, filter: &ObsFilterRef) -> Result<(), ObsError> {
+        match self {
+            ObsEitherSource::Left(a) => a.remove_filter(filter),
+            ObsEitherSource::Right(b) => b.remove_filter(filter),
+        }
+    }
 }
 
 pub enum ObsEither<A, B> {
@@ -1,5 +1,6 @@
 use libobs_wrapper::{
     data::ObsObjectBuilder,
+    obs_string,
     runtime::ObsRuntime,
     sources::{ObsSourceBuilder, ObsSourceRef},
     utils::{ObjectInfo, ObsError, ObsString, PlatformType},
@@ -7,36 +8,66 @@ use libobs_wrapper::{
 
 use crate::sources::{
     linux::{
-        pipewire::{ObsPipeWireSourceRef, PipeWireScreenCaptureSourceBuilder},
+        pipewire::{ObsPipeWireSourceRef, PipeWireScreenCaptureSourceBuilder, RestoreTokenStore},
         X11CaptureSourceBuilder,
     },
     ObsEither, ObsEitherSource,
 };
 
+/// Selects the source a `LinuxGeneralScreenCaptureBuilder` captures the screen with.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CaptureBackend {
+    /// XSHM on X11 and PipeWire on Wayland
+    #[default]
+    Auto,
+    /// The `xshm_input` source, which also works for Xwayland if the portal is broken
+    Xshm,
+    /// The PipeWire screen capture through the desktop portal
+    PipeWire,
+}
+
 pub struct LinuxGeneralScreenCaptureBuilder {
     underlying_builder: ObsEither<X11CaptureSourceBuilder, PipeWireScreenCaptureSourceBuilder>,
 }
 
-impl ObsObjectBuilder for LinuxGeneralScreenCaptureBuilder {
-    fn new<T: Into<ObsString> + Send + Sync>(name: T, runtime: ObsRuntime) -> Result<Self, ObsError>
-    where
-        Self: Sized,
-    {
-        let platform = runtime.get_platform()?;
-        let underlying_builder = match platform {
-            PlatformType::X11 => ObsEither::Left(X11CaptureSourceBuilder::new(name, runtime)?),
-            PlatformType::Wayland => {
+impl LinuxGeneralScreenCaptureBuilder {
+    /// Creates the builder with the given backend instead of detecting it from the display server.
+    pub fn new_with_backend<T: Into<ObsString> + Send + Sync>(
+        name: T,
+        runtime: ObsRuntime,
+        backend: CaptureBackend,
+    ) -> Result<Self, ObsError> {
+        let backend = match backend {
+            CaptureBackend::Auto => match runtime.get_platform()? {
+                PlatformType::X11 => CaptureBackend::Xshm,
+                PlatformType::Wayland => CaptureBackend::PipeWire,
+                PlatformType::Invalid => {
+                    return Err(ObsError::PlatformInitError(
+                        "No platform could be found to create the source on.".to_string(),
+                    ))
+                }
+            },
+            backend => backend,
+        };
+
+        let underlying_builder = match backend {
+            CaptureBackend::PipeWire => {
                 ObsEither::Right(PipeWireScreenCaptureSourceBuilder::new(name, runtime)?)
             }
-            PlatformType::Invalid => {
-                return Err(ObsError::PlatformInitError(
-                    "No platform could be found to create the source on.".to_string(),
-                ))
-            }
+            _ => ObsEither::Left(X11CaptureSourceBuilder::new(name, runtime)?),
         };
 
         Ok(Self { underlying_builder })
     }
+}
+
+impl ObsObjectBuilder for LinuxGeneralScreenCaptureBuilder {
+    fn new<T: Into<ObsString> + Send + Sync>(name: T, runtime: ObsRuntime) -> Result<Self, ObsError>
+    where
+        Self: Sized,
+    {
+        Self::new_with_backend(name, runtime, CaptureBackend::Auto)
+    }
 
     fn runtime(&self) -> &ObsRuntime {
         match &self.underlying_builder {
@@ -91,7 +122,7 @@ impl ObsObjectBuilder for LinuxGeneralScreenCaptureBuilder {
     }
 
     fn get_id() -> ObsString {
-        ObsString::from("linux_general_screen_capture")
+        obs_string!("linux_general_screen_capture")
     }
 }
 
@@ -133,6 +164,18 @@ impl LinuxGeneralScreenCaptureBuilder {
         self
     }
 
+    /// Sets the restore token that has been saved in the store, if there is one.
+    /// # Display Server
+    /// PipeWire only
+    pub fn load_restore_token(mut self, store: &dyn RestoreTokenStore) -> Result<Self, ObsError> {
+        self.underlying_builder = match self.underlying_builder {
+            ObsEither::Left(builder) => ObsEither::Left(builder),
+            ObsEither::Right(builder) => ObsEither::Right(builder.load_restore_token(store)?),
+        };
+
+        Ok(self)
+    }
+
     /// # Display Server
     /// All supported display servers
     pub fn set_show_cursor(mut self, show: bool) -> Self {
@@ -228,6 +271,14 @@ impl LinuxGeneralScreenCaptureBuilder {
         self
     }
 
+    /// Returns the backend that is used, never `CaptureBackend::Auto`.
+    pub fn backend(&self) -> CaptureBackend {
+        match &self.underlying_builder {
+            ObsEither::Left(_) => CaptureBackend::Xshm,
+            ObsEither::Right(_) => CaptureBackend::PipeWire,
+        }
+    }
+
     pub fn capture_type_name(&self) -> PlatformType {
         match &self.underlying_builder {
             ObsEither::Left(_) => PlatformType::X11,
@@ -1,6 +1,8 @@
 use libobs_simple_macro::obs_object_builder;
 
-use crate::sources::linux::pipewire::{impl_pipewire_source_builder, ObsPipeWireSourceType};
+use crate::sources::linux::pipewire::{
+    impl_pipewire_source_builder, impl_restore_token_store, ObsPipeWireSourceType,
+};
 
 #[obs_object_builder("pipewire-desktop-capture-source")]
 pub struct PipeWireDesktopCaptureSourceBuilder {
@@ -17,3 +19,5 @@ impl_pipewire_source_builder!(
     PipeWireDesktopCaptureSourceBuilder,
     ObsPipeWireSourceType::DesktopCapture
 );
+
+impl_restore_token_store!(PipeWireDesktopCaptureSourceBuilder);
@@ -13,8 +13,23 @@ pub use window::*;
 mod restore_updater;
 pub use restore_updater::*;
 
+mod restore_token;
+pub(crate) use restore_token::impl_restore_token_store;
+#[cfg(feature = "enable_runtime")]
+pub use restore_token::ObsRestoreTokenWatcher;
+pub use restore_token::{FileRestoreTokenStore, RestoreTokenStore};
+
+#[cfg(feature = "enable_runtime")]
+mod session;
+#[cfg(feature = "enable_runtime")]
+pub use session::*;
+
 use libobs_wrapper::{
-    data::{object::ObsObjectTrait, ObsDataGetters},
+    data::{
+        object::ObsObjectTrait,
+        properties::{ObsProperty, ObsPropertyObject},
+        ObsDataGetters,
+    },
     run_with_obs,
     sources::ObsSourceRef,
     utils::ObsError,
@@ -52,14 +67,15 @@ impl ObsPipeWireSourceRef {
 
     /// Gets the restore token used for reconnecting to previous sessions for `pipewire-desktop-capture-source` and `pipewire-window-capture-source` sources.
     ///
-    /// As of right now, there is no callback or signal to notify when the token has been set, you have to call this method to get the restore token.
+    /// There is no signal to notify when the token has been set, use [`ObsPipeWireSourceRef::watch_restore_token`]
+    /// to get notified instead of calling this method at the right time.
     ///
     /// The restore token will most probably be of `Some(String)` after the user has selected a screen or window to capture.
     pub fn get_restore_token(&self) -> Result<Option<String>, ObsError> {
         let source_ptr = self.as_ptr();
-        run_with_obs!(self.runtime(), (source_ptr), move || unsafe {
+        run_with_obs!(self.runtime(), (source_ptr), move |token| unsafe {
             // Safety: Safe because we are using a smart pointer
-            libobs::obs_source_save(source_ptr.get_ptr());
+            libobs::obs_source_save(source_ptr.get_ptr(token));
         })?;
 
         let settings = self.settings()?;
@@ -67,6 +83,47 @@ impl ObsPipeWireSourceRef {
         Ok(token)
     }
 
+    /// Polls the restore token in the background and emits it every time it changes,
+    /// which happens after the user selected a screen or window and every time a session is restored.
+    ///
+    /// Every new token is saved to the `store`, if one is given.
+    #[cfg(feature = "enable_runtime")]
+    pub fn watch_restore_token(
+        &self,
+        store: Option<Box<dyn RestoreTokenStore>>,
+    ) -> Result<ObsRestoreTokenWatcher, ObsError> {
+        ObsRestoreTokenWatcher::new(self.clone(), store)
+    }
+
+    /// Watches the desktop portal session of this source, so applications can offer to
+    /// [`reprompt`](ObsPipeWireSourceRef::reprompt) if the user cancelled the dialog.
+    #[cfg(feature = "enable_runtime")]
+    pub fn watch_session(&self) -> Result<ObsPipeWireSessionWatcher, ObsError> {
+        ObsPipeWireSessionWatcher::new(self.clone())
+    }
+
+    /// Opens the portal dialog again, e.g. after the user cancelled it. This restarts the
+    /// session, so the source shows nothing until the user selected something again.
+    ///
+    /// Camera sources are not supported.
+    pub fn reprompt(&self) -> Result<(), ObsError> {
+        if self.source_type == ObsPipeWireSourceType::CameraCapture {
+            return Err(ObsError::InvalidOperation(
+                "PipeWire camera sources can not be reprompted".to_string(),
+            ));
+        }
+
+        match self.source.get_properties()?.get("Reload") {
+            Some(ObsProperty::Button(reload)) => {
+                reload.click(&self.source)?;
+                Ok(())
+            }
+            _ => Err(ObsError::InvalidOperation(
+                "The PipeWire source has no reload button".to_string(),
+            )),
+        }
+    }
+
     pub fn create_updater<'a>(
         &'a mut self,
     ) -> Result<ObsPipeWireGeneralUpdater<'a>, libobs_wrapper::utils::ObsError> {
@@ -0,0 +1,185 @@
+use std::{
+    io::ErrorKind,
+    path::{Path, PathBuf},
+};
+
+use libobs_wrapper::utils::ObsError;
+
+/// Persists the restore token of a PipeWire source, so the portal restores the
+/// selection of the user on the next start instead of asking again.
+///
+/// The portal hands out a new token every time a session is restored, so the token
+/// has to be saved again whenever it changes, see `ObsPipeWireSourceRef::watch_restore_token`.
+pub trait RestoreTokenStore: Send + Sync {
+    /// Returns the saved token, `None` if no token has been saved yet.
+    fn load(&self) -> Result<Option<String>, ObsError>;
+
+    fn save(&self, token: &str) -> Result<(), ObsError>;
+}
+
+/// Saves the restore token in a plain text file.
+#[derive(Debug, Clone)]
+pub struct FileRestoreTokenStore {
+    path: PathBuf,
+}
+
+impl FileRestoreTokenStore {
+    pub fn new<P: Into<PathBuf>>(path: P) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Saves the token in the given file next to the current executable.
+    pub fn next_to_executable(file_name: &str) -> Result<Self, ObsError> {
+        let exe = std::env::current_exe().map_err(|e| ObsError::IoError(e.into()))?;
+        let dir = exe.parent().ok_or_else(|| {
+            ObsError::InvalidOperation("The executable has no parent directory".to_string())
+        })?;
+
+        Ok(Self::new(dir.join(file_name)))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl RestoreTokenStore for FileRestoreTokenStore {
+    fn load(&self) -> Result<Option<String>, ObsError> {
+        match std::fs::read_to_string(&self.path) {
+            Ok(token) => {
+                let token = token.trim();
+                Ok((!token.is_empty()).then(|| token.to_string()))
+            }
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(ObsError::IoError(e.into())),
+        }
+    }
+
+    fn save(&self, token: &str) -> Result<(), ObsError> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| ObsError::IoError(e.into()))?;
+        }
+
+        std::fs::write(&self.path, token).map_err(|e| ObsError::IoError(e.into()))
+    }
+}
+
+#[cfg(feature = "enable_runtime")]
+mod watcher {
+    use std::{
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc,
+        },
+        thread::{self, JoinHandle},
+        time::Duration,
+    };
+
+    use libobs_wrapper::utils::ObsError;
+    use tokio::sync::watch;
+
+    use super::RestoreTokenStore;
+    use crate::sources::linux::pipewire::ObsPipeWireSourceRef;
+
+    /// How often the restore token of the source is read
+    const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+    /// Watches the restore token of a PipeWire source and saves it whenever it changes,
+    /// created by `ObsPipeWireSourceRef::watch_restore_token`.
+    ///
+    /// The watcher keeps the source alive and stops once it is dropped.
+    #[derive(Debug)]
+    pub struct ObsRestoreTokenWatcher {
+        stopped: Arc<AtomicBool>,
+        receiver: watch::Receiver<Option<String>>,
+        thread: Option<JoinHandle<()>>,
+    }
+
+    impl ObsRestoreTokenWatcher {
+        pub(crate) fn new(
+            source: ObsPipeWireSourceRef,
+            store: Option<Box<dyn RestoreTokenStore>>,
+        ) -> Result<Self, ObsError> {
+            let initial = source.get_restore_token()?.filter(|t| !t.is_empty());
+            let (sender, receiver) = watch::channel(initial);
+
+            let stopped = Arc::new(AtomicBool::new(false));
+            let thread_stopped = stopped.clone();
+            let thread = thread::Builder::new()
+                .name("obs-pipewire-restore-token".to_string())
+                .spawn(move || {
+                    while !thread_stopped.load(Ordering::SeqCst) {
+                        thread::sleep(POLL_INTERVAL);
+
+                        let token = match source.get_restore_token() {
+                            Ok(token) => token.filter(|t| !t.is_empty()),
+                            Err(e) => {
+                                log::warn!("Failed to read the PipeWire restore token: {}", e);
+                                continue;
+                            }
+                        };
+
+                        if token.is_none() || *sender.borrow() == token {
+                            continue;
+                        }
+
+                        if let (Some(store), Some(token)) = (&store, &token) {
+                            if let Err(e) = store.save(token) {
+                                log::warn!("Failed to save the PipeWire restore token: {}", e);
+                            }
+                        }
+
+                        sender.send_replace(token);
+                    }
+                })
+                .map_err(|e| ObsError::IoError(e.into()))?;
+
+            Ok(Self {
+                stopped,
+                receiver,
+                thread: Some(thread),
+            })
+        }
+
+        /// Returns the latest restore token, `None` if the user has not selected anything yet.
+        pub fn token(&self) -> Option<String> {
+            self.receiver.borrow().clone()
+        }
+
+        /// Returns a receiver that is notified every time the restore token changes.
+        pub fn subscribe(&self) -> watch::Receiver<Option<String>> {
+            self.receiver.clone()
+        }
+    }
+
+    impl Drop for ObsRestoreTokenWatcher {
+        fn drop(&mut self) {
+            self.stopped.store(true, Ordering::SeqCst);
+            if let Some(thread) = self.thread.take() {
+                let _ = thread.join();
+            }
+        }
+    }
+}
+
+#[cfg(feature = "enable_runtime")]
+pub use watcher::ObsRestoreTokenWatcher;
+
+macro_rules! impl_restore_token_store {
+    ($struct_name: ident) => {
+        impl $struct_name {
+            /// Sets the restore token that has been saved in the store, if there is one.
+            pub fn load_restore_token(
+                self,
+                store: &dyn crate::sources::linux::pipewire::RestoreTokenStore,
+            ) -> Result<Self, libobs_wrapper::utils::ObsError> {
+                Ok(match store.load()? {
+                    Some(token) => self.set_restore_token(token),
+                    None => self,
+                })
+            }
+        }
+    };
+}
+
+pub(crate) use impl_restore_token_store;
@@ -1,7 +1,8 @@
 use libobs_wrapper::{
     data::{object::ObsObjectTrait, ObsData, ObsDataUpdater, ObsObjectUpdater},
+    obs_string,
     runtime::ObsRuntime,
-    utils::{ObsError, ObsString},
+    utils::ObsError,
 };
 
 use crate::sources::linux::pipewire::ObsPipeWireSourceRef;
@@ -16,7 +17,7 @@ impl<'a> ObsObjectUpdater<'a, *mut libobs::obs_source> for ObsPipeWireGeneralUpd
     type ToUpdate = ObsPipeWireSourceRef;
 
     fn get_id() -> libobs_wrapper::utils::ObsString {
-        ObsString::new("pipewire-restore-token-updater")
+        obs_string!("pipewire-restore-token-updater")
     }
 
     fn create_update(
@@ -1,4 +1,6 @@
-use crate::sources::linux::pipewire::{impl_pipewire_source_builder, ObsPipeWireSourceType};
+use crate::sources::linux::pipewire::{
+    impl_pipewire_source_builder, impl_restore_token_store, ObsPipeWireSourceType,
+};
 use libobs_simple_macro::obs_object_builder;
 
 #[obs_object_builder("pipewire-screen-capture-source")]
@@ -17,3 +19,5 @@ impl_pipewire_source_builder!(
     PipeWireScreenCaptureSourceBuilder,
     ObsPipeWireSourceType::ScreenCapture
 );
+
+impl_restore_token_store!(PipeWireScreenCaptureSourceBuilder);
@@ -0,0 +1,152 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+use libobs_wrapper::{
+    data::object::ObsObjectTrait,
+    logger::{log_history_position, log_history_since},
+    run_with_obs,
+    utils::ObsError,
+};
+use tokio::sync::watch;
+
+use crate::sources::linux::pipewire::ObsPipeWireSourceRef;
+
+/// How often the state of the portal session is checked
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Lines the PipeWire plugin logs when the portal session could not be started
+const FAILURE_MESSAGES: [&str; 3] = [
+    "denied or cancelled by user",
+    "Error creating screencast session",
+    "Error selecting screencast source",
+];
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+/// The state of the desktop portal session of a PipeWire source
+pub enum ObsPipeWireSessionState {
+    /// The portal dialog is open or the stream has not delivered a frame yet
+    Pending,
+    Streaming,
+    /// The user cancelled the portal dialog or the session could not be created,
+    /// contains the message the plugin logged. See `ObsPipeWireSourceRef::reprompt`.
+    Failed(String),
+}
+
+/// Returns the failure message if the line reports a failed portal session.
+fn session_failure(line: &str) -> Option<&str> {
+    if !line.contains("[pipewire]") {
+        return None;
+    }
+
+    FAILURE_MESSAGES
+        .iter()
+        .any(|message| line.contains(message))
+        .then_some(line)
+}
+
+/// Watches the portal session of a PipeWire source, created by `ObsPipeWireSourceRef::watch_session`.
+///
+/// The plugin does not tell which source a log line belongs to, so a cancelled portal dialog
+/// is reported by every watcher whose source is not streaming yet.
+///
+/// The watcher keeps the source alive and stops once it is dropped.
+#[derive(Debug)]
+pub struct ObsPipeWireSessionWatcher {
+    stopped: Arc<AtomicBool>,
+    sender: Arc<watch::Sender<ObsPipeWireSessionState>>,
+    receiver: watch::Receiver<ObsPipeWireSessionState>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl ObsPipeWireSessionWatcher {
+    pub(crate) fn new(source: ObsPipeWireSourceRef) -> Result<Self, ObsError> {
+        let (sender, receiver) = watch::channel(ObsPipeWireSessionState::Pending);
+        let sender = Arc::new(sender);
+        let thread_sender = sender.clone();
+
+        let stopped = Arc::new(AtomicBool::new(false));
+        let thread_stopped = stopped.clone();
+        let mut position = log_history_position();
+        let thread = thread::Builder::new()
+            .name("obs-pipewire-session".to_string())
+            .spawn(move || {
+                while !thread_stopped.load(Ordering::SeqCst) {
+                    thread::sleep(POLL_INTERVAL);
+
+                    let lines = log_history_since(position);
+                    position = log_history_position();
+
+                    let source_ptr = source.as_ptr();
+                    let width =
+                        run_with_obs!(source.runtime(), (source_ptr), move |token| unsafe {
+                            // Safety: The pointer is valid because of the smart pointer
+                            libobs::obs_source_get_width(source_ptr.get_ptr(token))
+                        });
+
+                    let width = match width {
+                        Ok(width) => width,
+                        Err(e) => {
+                            log::warn!("Failed to read the size of the PipeWire source: {}", e);
+                            continue;
+                        }
+                    };
+
+                    let current = thread_sender.borrow().clone();
+                    let failure = lines.iter().find_map(|(_, line)| session_failure(line));
+                    let state = match failure {
+                        Some(message) if width == 0 => {
+                            ObsPipeWireSessionState::Failed(message.to_string())
+                        }
+                        _ if width > 0 => ObsPipeWireSessionState::Streaming,
+                        // The stream has been torn down, e.g. because the portal dialog was reopened
+                        _ if current == ObsPipeWireSessionState::Streaming => {
+                            ObsPipeWireSessionState::Pending
+                        }
+                        _ => current.clone(),
+                    };
+
+                    if state != current {
+                        thread_sender.send_replace(state);
+                    }
+                }
+            })
+            .map_err(|e| ObsError::IoError(e.into()))?;
+
+        Ok(Self {
+            stopped,
+            sender,
+            receiver,
+            thread: Some(thread),
+        })
+    }
+
+    pub fn state(&self) -> ObsPipeWireSessionState {
+        self.receiver.borrow().clone()
+    }
+
+    /// Returns a receiver that is notified every time the state of the session changes.
+    pub fn subscribe(&self) -> watch::Receiver<ObsPipeWireSessionState> {
+        self.receiver.clone()
+    }
+
+    /// Marks the session as pending again, call this after `ObsPipeWireSourceRef::reprompt`
+    /// so a failure of the new dialog is reported as a change.
+    pub fn reset(&self) {
+        self.sender.send_replace(ObsPipeWireSessionState::Pending);
+    }
+}
+
+impl Drop for ObsPipeWireSessionWatcher {
+    fn drop(&mut self) {
+        self.stopped.store(true, Ordering::SeqCst);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
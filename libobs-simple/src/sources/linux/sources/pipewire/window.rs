@@ -1,6 +1,8 @@
 use libobs_simple_macro::obs_object_builder;
 
-use crate::sources::linux::pipewire::{impl_pipewire_source_builder, ObsPipeWireSourceType};
+use crate::sources::linux::pipewire::{
+    impl_pipewire_source_builder, impl_restore_token_store, ObsPipeWireSourceType,
+};
 
 #[obs_object_builder("pipewire-window-capture-source")]
 pub struct PipeWireWindowCaptureSourceBuilder {
@@ -17,3 +19,5 @@ impl_pipewire_source_builder!(
     PipeWireWindowCaptureSourceBuilder,
     ObsPipeWireSourceType::WindowCapture
 );
+
+impl_restore_token_store!(PipeWireWindowCaptureSourceBuilder);
@@ -62,3 +62,6 @@ define_object_manager!(
 );
 
 impl_default_builder!(X11CaptureSourceBuilder);
+
+/// The builder of the `xshm_input` source, which is what OBS calls its X11 screen capture.
+pub type XshmInputSourceBuilder = X11CaptureSourceBuilder;
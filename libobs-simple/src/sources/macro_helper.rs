@@ -97,9 +97,9 @@ macro_rules! impl_custom_source {
         $($(#[$attr:meta])* $signal_name: literal: { $($inner_def:tt)* }),* $(,)*
     ]) => {
         paste::paste! {
-            libobs_wrapper::impl_signal_manager!(|ptr: libobs_wrapper::unsafe_send::SmartPointerSendable<*mut libobs::obs_source>| unsafe {
+            libobs_wrapper::impl_signal_manager!(|ptr: libobs_wrapper::unsafe_send::SmartPointerSendable<*mut libobs::obs_source>, token: libobs_wrapper::unsafe_send::ObsThreadToken| unsafe {
                     // Safety: This is a smart pointer, so it is fine
-                    libobs::obs_source_get_signal_handler(ptr.get_ptr())
+                    libobs::obs_source_get_signal_handler(ptr.get_ptr(token))
                 }, [<$new_source_struct Signals>] for *mut libobs::obs_source, [
             $($(#[$attr])* $signal_name: { $($inner_def)* }),*
             ]);
@@ -6,6 +6,8 @@
 //! - **Linux**: X11 screen capture, XComposite window capture, V4L2 camera, ALSA/PulseAudio/JACK audio, PipeWire
 //! - **macOS**: Screen capture (via ScreenCaptureKit)
 //!
+//! It also provides a timecode overlay to burn the wall-clock or recording time into the video.
+//!
 //! You can find examples [here](https://github.com/libobs-rs/libobs-rs/tree/main/examples).
 
 #[cfg(any(windows, doc))]
@@ -22,6 +24,8 @@ pub mod macos;
 
 mod either;
 mod macro_helper;
+mod timecode;
 
 pub use either::*;
 pub use libobs_wrapper::{data::ObsObjectUpdater, sources::ObsSourceBuilder};
+pub use timecode::{TimecodeMode, TimecodeOverlay, TimecodeOverlayBuilder};
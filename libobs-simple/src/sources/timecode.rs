@@ -0,0 +1,330 @@
+//! A text source that burns the current timecode into the video, to sync multiple cameras or
+//! to find audio/video drift in recordings.
+
+use std::{
+    ffi::CString,
+    sync::{
+        atomic::{AtomicI32, Ordering},
+        Arc,
+    },
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use libobs_wrapper::{
+    context::ObsContext,
+    data::{object::ObsObjectTrait, output::ObsOutputRef, ObsData},
+    graphics::ObsTickCallback,
+    run_with_obs,
+    scenes::{ObsSceneItemRef, ObsSceneRef, SceneItemExtSceneTrait},
+    sources::ObsSourceRef,
+    unsafe_send::Sendable,
+    utils::{ObsError, ObsString},
+};
+
+#[cfg(windows)]
+const TEXT_SOURCE_ID: &str = "text_gdiplus_v3";
+#[cfg(not(windows))]
+const TEXT_SOURCE_ID: &str = "text_ft2_source_v2";
+
+#[cfg(windows)]
+const DEFAULT_FONT_FACE: &str = "Consolas";
+#[cfg(target_os = "macos")]
+const DEFAULT_FONT_FACE: &str = "Menlo";
+#[cfg(not(any(windows, target_os = "macos")))]
+const DEFAULT_FONT_FACE: &str = "Monospace";
+
+/// What the timecode overlay counts.
+#[derive(Debug, Clone)]
+pub enum TimecodeMode {
+    /// The time of day with the frame of the current second, in UTC unless the overlay has a
+    /// UTC offset, see `TimecodeOverlayBuilder::utc_offset`
+    WallClock,
+    /// The frames the output has rendered since it was started, the output is kept alive by the overlay
+    Output(ObsOutputRef),
+}
+
+/// A text source showing a `HH:MM:SS:FF` timecode, which is refreshed on every tick of the graphics thread.
+///
+/// The timecode stops updating once this overlay is dropped, the source itself stays alive as long
+/// as a scene uses it.
+#[derive(Debug)]
+pub struct TimecodeOverlay {
+    source: ObsSourceRef,
+    utc_offset: Arc<AtomicI32>,
+    _tick: ObsTickCallback,
+}
+
+impl TimecodeOverlay {
+    pub fn source(&self) -> &ObsSourceRef {
+        &self.source
+    }
+
+    /// The offset from UTC in minutes the wall clock of this overlay is shown in.
+    pub fn utc_offset(&self) -> i32 {
+        self.utc_offset.load(Ordering::Relaxed)
+    }
+
+    /// Changes the time zone of the wall clock of this overlay, e.g. to show the local time
+    /// of another region. This only affects this overlay, other overlays keep their offset.
+    pub fn set_utc_offset(&self, offset_minutes: i32) {
+        self.utc_offset.store(offset_minutes, Ordering::Relaxed);
+    }
+
+    /// Adds the text source to the scene.
+    pub fn add_to_scene(
+        &self,
+        scene: &mut ObsSceneRef,
+    ) -> Result<ObsSceneItemRef<ObsSourceRef>, ObsError> {
+        scene.add_source(self.source.clone())
+    }
+}
+
+/// Creates a [`TimecodeOverlay`].
+///
+/// # Example
+///
+/// ```no_run
+/// use libobs_simple::sources::TimecodeOverlayBuilder;
+/// use libobs_wrapper::{context::ObsContext, utils::StartupInfo};
+///
+/// let mut context = ObsContext::new(StartupInfo::default()).unwrap();
+/// let mut scene = context.scene("main", Some(0)).unwrap();
+///
+/// let overlay = TimecodeOverlayBuilder::new(context.clone(), "timecode")
+///     .font_size(64)
+///     .build()
+///     .unwrap();
+/// overlay.add_to_scene(&mut scene).unwrap();
+/// ```
+#[derive(Debug)]
+pub struct TimecodeOverlayBuilder {
+    context: ObsContext,
+    name: ObsString,
+    mode: TimecodeMode,
+    font_face: String,
+    font_size: u32,
+    utc_offset: i32,
+}
+
+impl TimecodeOverlayBuilder {
+    /// Creates a builder for a wall-clock timecode in a monospace font.
+    pub fn new<T: Into<ObsString>>(context: ObsContext, name: T) -> Self {
+        Self {
+            context,
+            name: name.into(),
+            mode: TimecodeMode::WallClock,
+            font_face: DEFAULT_FONT_FACE.to_string(),
+            font_size: 48,
+            utc_offset: 0,
+        }
+    }
+
+    /// Sets what the timecode counts.
+    pub fn mode(mut self, mode: TimecodeMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Counts the frames of the output instead of the wall-clock time.
+    pub fn output(self, output: &ObsOutputRef) -> Self {
+        self.mode(TimecodeMode::Output(output.clone()))
+    }
+
+    pub fn font_face<T: Into<String>>(mut self, face: T) -> Self {
+        self.font_face = face.into();
+        self
+    }
+
+    pub fn font_size(mut self, size: u32) -> Self {
+        self.font_size = size;
+        self
+    }
+
+    /// Shows the wall clock in the time zone with the given offset from UTC in minutes,
+    /// e.g. `120` for UTC+02:00. The offset is kept per overlay, so overlays for different
+    /// regions can be shown at once. Has no effect if the frames of an output are counted.
+    pub fn utc_offset(mut self, offset_minutes: i32) -> Self {
+        self.utc_offset = offset_minutes;
+        self
+    }
+
+    /// Creates the text source and starts updating it.
+    pub fn build(self) -> Result<TimecodeOverlay, ObsError> {
+        let settings = ObsData::from_json(
+            &format!(
+                r#"{{"font":{{"face":"{}","size":{}}}}}"#,
+                escape_json(&self.font_face),
+                self.font_size
+            ),
+            self.context.runtime().clone(),
+        )?;
+
+        let source = ObsSourceRef::new(
+            TEXT_SOURCE_ID,
+            self.name,
+            Some(settings.into_immutable()),
+            None,
+            self.context.runtime().clone(),
+        )?;
+
+        let source_ptr = source.as_ptr();
+        let output_ptr = match &self.mode {
+            TimecodeMode::WallClock => None,
+            TimecodeMode::Output(output) => Some(output.as_ptr()),
+        };
+
+        // The tick runs on the graphics thread, so the raw pointers are read once up front.
+        // The clones moved into the callback keep them valid.
+        let (raw_source, raw_output) = run_with_obs!(
+            self.context.runtime(),
+            (source_ptr, output_ptr),
+            move |token| {
+                (
+                    Sendable(source_ptr.get_ptr(token)),
+                    output_ptr.map(|ptr| Sendable(ptr.get_ptr(token))),
+                )
+            }
+        )?;
+
+        let utc_offset = Arc::new(AtomicI32::new(self.utc_offset));
+        let mut updater = TimecodeUpdater {
+            source: raw_source,
+            output: raw_output,
+            utc_offset: utc_offset.clone(),
+            last_text: String::new(),
+            _keep_alive: (source.clone(), self.mode),
+        };
+        let tick = self
+            .context
+            .add_tick_callback(move |_seconds| updater.tick())?;
+
+        Ok(TimecodeOverlay {
+            source,
+            utc_offset,
+            _tick: tick,
+        })
+    }
+}
+
+struct TimecodeUpdater {
+    source: Sendable<*mut libobs::obs_source_t>,
+    output: Option<Sendable<*mut libobs::obs_output_t>>,
+    utc_offset: Arc<AtomicI32>,
+    last_text: String,
+    _keep_alive: (ObsSourceRef, TimecodeMode),
+}
+
+impl TimecodeUpdater {
+    #[allow(unknown_lints)]
+    #[allow(ensure_obs_call_in_runtime)]
+    fn tick(&mut self) {
+        let (fps_num, fps_den) = unsafe {
+            // Safety: The video info is a plain struct that libobs fills in
+            let mut ovi = std::mem::zeroed::<libobs::obs_video_info>();
+            if !libobs::obs_get_video_info(&mut ovi) {
+                return;
+            }
+
+            (ovi.fps_num, ovi.fps_den)
+        };
+
+        let text = match &self.output {
+            Some(output) => {
+                let frames = unsafe {
+                    // Safety: The output is kept alive by the clone in `_keep_alive`
+                    libobs::obs_output_get_total_frames(output.0)
+                };
+                format_frames(frames.max(0) as u64, fps_num, fps_den)
+            }
+            None => {
+                let now = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap_or_default();
+                format_wall_clock(
+                    now.as_secs(),
+                    now.subsec_nanos(),
+                    self.utc_offset.load(Ordering::Relaxed),
+                    fps_num,
+                    fps_den,
+                )
+            }
+        };
+
+        if text == self.last_text {
+            return;
+        }
+
+        let Ok(c_text) = CString::new(text.as_str()) else {
+            return;
+        };
+
+        unsafe {
+            // Safety: The source is kept alive by the clone in `_keep_alive`, the data is released after the update
+            let data = libobs::obs_data_create();
+            libobs::obs_data_set_string(data, c"text".as_ptr(), c_text.as_ptr());
+            libobs::obs_source_update(self.source.0, data);
+            libobs::obs_data_release(data);
+        }
+
+        self.last_text = text;
+    }
+}
+
+/// Returns the whole number of frames per second the timecode counts with, e.g. 30 for 29.97.
+fn timecode_fps(fps_num: u32, fps_den: u32) -> u64 {
+    if fps_den == 0 {
+        return 1;
+    }
+
+    (fps_num as u64).div_ceil(fps_den as u64).max(1)
+}
+
+/// Formats the frame count as non-drop-frame timecode.
+fn format_frames(frames: u64, fps_num: u32, fps_den: u32) -> String {
+    let fps = timecode_fps(fps_num, fps_den);
+    let seconds = frames / fps;
+
+    format!(
+        "{:02}:{:02}:{:02}:{:02}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60,
+        frames % fps
+    )
+}
+
+/// Formats the time of day of a unix timestamp in the time zone with the given UTC offset,
+/// with the frame of the current second.
+fn format_wall_clock(
+    unix_seconds: u64,
+    subsec_nanos: u32,
+    utc_offset_minutes: i32,
+    fps_num: u32,
+    fps_den: u32,
+) -> String {
+    let fps = timecode_fps(fps_num, fps_den);
+    let seconds = (unix_seconds as i64 + utc_offset_minutes as i64 * 60).rem_euclid(86400) as u64;
+    let frame = (subsec_nanos as u64 * fps / 1_000_000_000).min(fps - 1);
+
+    format!(
+        "{:02}:{:02}:{:02}:{:02}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60,
+        frame
+    )
+}
+
+fn escape_json(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
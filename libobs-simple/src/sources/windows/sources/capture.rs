@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use num_derive::{FromPrimitive, ToPrimitive};
 
 #[repr(i32)]
@@ -49,3 +51,31 @@ pub enum ObsHookRate {
     Fast = libobs::hook_rate_HOOK_RATE_FAST,
     Fastest = libobs::hook_rate_HOOK_RATE_FASTEST,
 }
+
+impl ObsHookRate {
+    const ALL: [ObsHookRate; 4] = [
+        ObsHookRate::Slow,
+        ObsHookRate::Normal,
+        ObsHookRate::Fast,
+        ObsHookRate::Fastest,
+    ];
+
+    /// The interval in which the game capture source retries to hook into the game
+    /// (the plugin waits 2 seconds, scaled by the hook rate).
+    pub fn retry_interval(&self) -> Duration {
+        match self {
+            ObsHookRate::Slow => Duration::from_secs(4),
+            ObsHookRate::Normal => Duration::from_secs(2),
+            ObsHookRate::Fast => Duration::from_secs(1),
+            ObsHookRate::Fastest => Duration::from_millis(200),
+        }
+    }
+
+    /// Returns the hook rate whose retry interval is the closest to the given one.
+    pub fn from_retry_interval(interval: Duration) -> Self {
+        Self::ALL
+            .into_iter()
+            .min_by_key(|rate| rate.retry_interval().abs_diff(interval))
+            .unwrap_or(ObsHookRate::Normal)
+    }
+}
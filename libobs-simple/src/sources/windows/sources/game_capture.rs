@@ -108,11 +108,12 @@ define_object_manager!(
         #[obs_property(type_t = "bool")]
         capture_overlays: bool,
 
-        /// Use anti-cheat compatibility hook
+        /// Use the anti-cheat compatibility hook, which injects the graphics hook in a way
+        /// that is tolerated by most anti-cheat software. Enabled by default in OBS.
         #[obs_property(type_t = "bool")]
         anti_cheat_hook: bool,
 
-        /// Hook rate (Ranging from slow to fastest)
+        /// Hook rate (Ranging from slow to fastest), see `set_retry_interval`
         #[obs_property(type_t = "enum")]
         hook_rate: ObsHookRate,
 
@@ -156,10 +157,41 @@ impl GameCaptureSourceBuilder {
     pub fn set_window(self, window: &WindowInfo) -> Self {
         self.set_window_raw(window.obs_id.as_str())
     }
+
+    /// Returns a window of the process with the given id that can be captured by this source,
+    /// or `None` if the process has no such window.
+    pub fn find_window_by_pid(pid: u32) -> Result<Option<WindowInfo>, ObsSimpleError> {
+        Ok(Self::get_windows(WindowSearchMode::IncludeMinimized)?
+            .into_iter()
+            .find(|window| window.pid == pid))
+    }
+
+    /// Captures the window of the process with the given id.
+    ///
+    /// The window is only resolved once. OBS matches the window by its title, class and executable
+    /// afterwards (see `set_priority`), so the game is captured again after a restart with a new process id.
+    pub fn set_process_id(self, pid: u32) -> Result<Self, ObsSimpleError> {
+        let window = Self::find_window_by_pid(pid)?.ok_or_else(|| {
+            ObsError::InvalidOperation(format!(
+                "Process {} has no window that can be captured",
+                pid
+            ))
+        })?;
+
+        Ok(self
+            .set_capture_mode(ObsGameCaptureMode::CaptureSpecificWindow)
+            .set_window(&window))
+    }
 }
 
 #[obs_object_impl]
 impl GameCaptureSource {
+    /// Sets how often the source retries to hook into the game. The plugin only supports
+    /// the intervals of `ObsHookRate`, so the closest one is used.
+    pub fn set_retry_interval(self, interval: std::time::Duration) -> Self {
+        self.set_hook_rate(ObsHookRate::from_retry_interval(interval))
+    }
+
     pub fn set_capture_audio(mut self, capture_audio: bool) -> Result<Self, ObsSimpleError> {
         use crate::sources::windows::audio_capture_available;
 
@@ -2,8 +2,13 @@ use std::ffi::CStr;
 
 pub mod window_capture;
 use libobs_wrapper::{
-    impl_signal_manager, run_with_obs, runtime::ObsRuntime, sources::ObsSourceTrait,
-    unsafe_send::SmartPointerSendable, utils::ObsError,
+    data::object::ObsObjectTrait,
+    impl_signal_manager, run_with_obs,
+    runtime::ObsRuntime,
+    signals::ObsSignalReceiver,
+    sources::ObsSourceTrait,
+    unsafe_send::{ObsThreadToken, Sendable, SmartPointerSendable},
+    utils::{ObsCalldataExt, ObsError},
 };
 pub use window_capture::{
     WindowCaptureSource, WindowCaptureSourceBuilder, WindowCaptureSourceUpdater,
@@ -19,7 +24,9 @@ pub use game_capture::{
 };
 
 pub mod monitor_capture;
-pub use monitor_capture::{MonitorCaptureSourceBuilder, MonitorCaptureSourceUpdater};
+pub use monitor_capture::{
+    MonitorCaptureSourceBuilder, MonitorCaptureSourceUpdater, ObsMonitorInfo,
+};
 
 #[cfg(feature = "window-list")]
 pub use libobs_window_helper::{WindowInfo, WindowSearchMode};
@@ -35,9 +42,9 @@ pub(super) fn audio_capture_available(runtime: &ObsRuntime) -> Result<bool, ObsE
     })
 }
 
-impl_signal_manager!(|ptr: SmartPointerSendable<*mut libobs::obs_source>| unsafe {
+impl_signal_manager!(|ptr: SmartPointerSendable<*mut libobs::obs_source>, token: ObsThreadToken| unsafe {
     // Safety: We are using a smart pointer, so it is fine
-    libobs::obs_source_get_signal_handler(ptr.get_ptr())
+    libobs::obs_source_get_signal_handler(ptr.get_ptr(token))
 }, ObsHookableSourceSignals for *mut libobs::obs_source, [
     "hooked": {struct HookedSignal {
         title: String,
@@ -54,8 +61,57 @@ impl_signal_manager!(|ptr: SmartPointerSendable<*mut libobs::obs_source>| unsafe
     }},
 ]);
 
-pub trait ObsHookableSourceTrait {
+/// The window a game or window capture is currently hooked into.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ObsHookedWindow {
+    pub title: String,
+    pub class: String,
+    pub executable: String,
+}
+
+pub trait ObsHookableSourceTrait: ObsSourceTrait {
     fn source_specific_signals(&self) -> std::sync::Arc<ObsHookableSourceSignals>;
+
+    /// Subscribes to the `hooked` signal, which is emitted once the capture hooked into its target window.
+    fn on_hooked(&self) -> Result<ObsSignalReceiver<HookedSignal>, ObsError> {
+        self.source_specific_signals().on_hooked()
+    }
+
+    /// Subscribes to the `unhooked` signal, which is emitted once the capture lost its target window,
+    /// e.g. because the game was closed.
+    fn on_unhooked(&self) -> Result<ObsSignalReceiver<UnhookedSignal>, ObsError> {
+        self.source_specific_signals().on_unhooked()
+    }
+
+    /// Returns the window the capture is currently hooked into, `None` if it hasn't hooked yet.
+    ///
+    /// Use this together with `on_hooked` / `on_unhooked`, as the capture might have
+    /// hooked before you subscribed to the signals.
+    fn hooked_window(&self) -> Result<Option<ObsHookedWindow>, ObsError> {
+        let source_ptr = self.as_ptr();
+        let proc_handler = run_with_obs!(self.runtime(), (source_ptr), move |token| unsafe {
+            // Safety: The source is valid because of the smart pointer
+            Sendable(libobs::obs_source_get_proc_handler(
+                source_ptr.get_ptr(token),
+            ))
+        })?;
+
+        let mut calldata = unsafe {
+            // Safety: The proc handler is owned by the source, which is kept alive by `self`
+            self.runtime()
+                .call_proc_handler(&proc_handler, "get_hooked")?
+        };
+
+        if !calldata.get_bool("hooked")? {
+            return Ok(None);
+        }
+
+        Ok(Some(ObsHookedWindow {
+            title: calldata.get_string("title")?,
+            class: calldata.get_string("class")?,
+            executable: calldata.get_string("executable")?,
+        }))
+    }
 }
 
 impl<
@@ -21,11 +21,83 @@ use libobs_wrapper::{
     utils::ObsError,
 };
 use num_traits::ToPrimitive;
+use std::collections::HashMap;
+use std::mem::MaybeUninit;
+use windows::core::{Interface, PCWSTR};
+use windows::Win32::Foundation::HWND;
+use windows::Win32::Graphics::Dxgi::Common::DXGI_COLOR_SPACE_RGB_FULL_G2084_NONE_P2020;
+use windows::Win32::Graphics::Dxgi::{
+    CreateDXGIFactory1, IDXGIFactory1, IDXGIOutput, IDXGIOutput6,
+};
+use windows::Win32::Graphics::Gdi::{
+    EnumDisplayDevicesW, GetMonitorInfoW, MonitorFromWindow, DISPLAY_DEVICEW, HMONITOR,
+    MONITORINFO, MONITORINFOEXW, MONITOR_DEFAULTTONEAREST,
+};
 use windows::Win32::UI::HiDpi::{
     GetAwarenessFromDpiAwarenessContext, GetThreadDpiAwarenessContext,
     SetProcessDpiAwarenessContext, DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2,
     DPI_AWARENESS_UNAWARE,
 };
+use windows::Win32::UI::WindowsAndMessaging::EDD_GET_DEVICE_INTERFACE_NAME;
+
+/// A monitor with its geometry normalized for OBS, see `MonitorCaptureSourceBuilder::get_monitor_infos`.
+///
+/// Positions and sizes are in physical pixels, regardless of the DPI awareness of the process.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ObsMonitorInfo {
+    /// The device interface path OBS uses as `monitor_id`, e.g. `\\?\DISPLAY#...`
+    pub device_id: String,
+    /// The GDI device name, e.g. `\\.\DISPLAY1`
+    pub device_name: String,
+    pub friendly_name: String,
+    /// The position of the monitor on the virtual desktop
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub refresh_rate: f32,
+    pub rotation: f32,
+    /// The scale factor set in the Windows display settings, e.g. `1.5` for 150%
+    pub scale_factor: f32,
+    /// Whether the monitor currently outputs HDR (PQ / BT.2020)
+    pub hdr: bool,
+    pub is_primary: bool,
+}
+
+impl ObsMonitorInfo {
+    /// Returns the monitor with the given handle.
+    pub fn from_hmonitor(monitor: HMONITOR) -> Result<Option<Self>, ObsSimpleError> {
+        let mut info = MONITORINFOEXW::default();
+        info.monitorInfo.cbSize = std::mem::size_of::<MONITORINFOEXW>() as u32;
+
+        let success = unsafe {
+            // Safety: The struct is large enough as cbSize is set to the size of MONITORINFOEXW
+            GetMonitorInfoW(
+                monitor,
+                &mut info as *mut MONITORINFOEXW as *mut MONITORINFO,
+            )
+        };
+        if !success.as_bool() {
+            return Ok(None);
+        }
+
+        let device_name = wide_to_string(&info.szDevice);
+        Ok(get_monitor_infos()?
+            .into_iter()
+            .find(|m| m.device_name == device_name))
+    }
+
+    /// Returns the monitor the given window is displayed on (or the nearest one if the window is off screen),
+    /// e.g. to capture the display under a window.
+    pub fn from_window(window: HWND) -> Result<Option<Self>, ObsSimpleError> {
+        let monitor = unsafe {
+            // Safety: An invalid window handle returns the primary monitor.
+            MonitorFromWindow(window, MONITOR_DEFAULTTONEAREST)
+        };
+
+        Self::from_hmonitor(monitor)
+    }
+}
 
 // Usage example
 define_object_manager!(
@@ -48,6 +120,9 @@ define_object_manager!(
         force_sdr: bool,
 
         capture_method: Option<ObsDisplayCaptureMethod>,
+        monitor_device: Option<String>,
+        wgc_on_cross_adapter: bool,
+        cross_adapter_checked: bool,
     }
 );
 
@@ -62,9 +137,200 @@ impl MonitorCaptureSource {
             .collect())
     }
 
-    pub fn set_monitor(self, monitor: &Sendable<DisplayInfo>) -> Self {
+    pub fn set_monitor(mut self, monitor: &Sendable<DisplayInfo>) -> Self {
+        self.monitor_device = Some(monitor.0.name.clone());
         self.set_monitor_id_raw(monitor.0.name.as_str())
     }
+
+    /// Gets all available monitors with their device id, geometry, scale factor and HDR status
+    pub fn get_monitor_infos() -> Result<Vec<ObsMonitorInfo>, ObsSimpleError> {
+        get_monitor_infos()
+    }
+
+    pub fn set_monitor_info(mut self, monitor: &ObsMonitorInfo) -> Self {
+        self.monitor_device = Some(monitor.device_name.clone());
+        self.set_monitor_id_raw(monitor.device_id.as_str())
+    }
+}
+
+impl MonitorCaptureSource {
+    /// Returns true if the monitor is connected to another GPU than the one OBS renders on.
+    /// DXGI capture of such a monitor requires a cross-adapter copy, which is slow and may result in a black screen.
+    /// WGC handles this scenario better.
+    pub fn is_cross_adapter(
+        monitor: &Sendable<DisplayInfo>,
+        runtime: &ObsRuntime,
+    ) -> Result<bool, ObsSimpleError> {
+        Ok(check_cross_adapter(&monitor.0.name, runtime)?.is_some())
+    }
+}
+
+fn wide_to_string(wide: &[u16]) -> String {
+    let len = wide.iter().position(|c| *c == 0).unwrap_or(wide.len());
+    String::from_utf16_lossy(&wide[..len])
+}
+
+/// Returns every DXGI output with the index of its adapter and its device name (e.g. `\\.\DISPLAY1`).
+fn enum_dxgi_outputs() -> Result<Vec<(u32, IDXGIOutput, String)>, ObsError> {
+    let factory: IDXGIFactory1 = unsafe {
+        // Safety: Creating a DXGI factory has no preconditions.
+        CreateDXGIFactory1()
+    }
+    .map_err(|e| ObsError::NativeError(e.into()))?;
+
+    let mut outputs = Vec::new();
+    let mut adapter_index = 0;
+    while let Ok(adapter) = unsafe {
+        // Safety: The factory is valid, an error is returned once there are no adapters left.
+        factory.EnumAdapters1(adapter_index)
+    } {
+        let mut output_index = 0;
+        while let Ok(output) = unsafe {
+            // Safety: The adapter is valid, an error is returned once there are no outputs left.
+            adapter.EnumOutputs(output_index)
+        } {
+            let desc = unsafe {
+                // Safety: The output is valid.
+                output.GetDesc()
+            }
+            .map_err(|e| ObsError::NativeError(e.into()))?;
+
+            outputs.push((adapter_index, output, wide_to_string(&desc.DeviceName)));
+            output_index += 1;
+        }
+
+        adapter_index += 1;
+    }
+
+    Ok(outputs)
+}
+
+/// Returns the index of the GPU adapter the monitor with the given device name (e.g. `\\.\DISPLAY1`) is connected to,
+/// or `None` if no adapter has an output with that name.
+pub fn get_monitor_adapter_index(device_name: &str) -> Result<Option<u32>, ObsError> {
+    Ok(enum_dxgi_outputs()?
+        .into_iter()
+        .find(|(_, _, name)| name == device_name)
+        .map(|(adapter_index, _, _)| adapter_index))
+}
+
+/// Returns whether each DXGI output outputs HDR, keyed by the device name.
+fn get_hdr_states() -> Result<HashMap<String, bool>, ObsError> {
+    Ok(enum_dxgi_outputs()?
+        .into_iter()
+        .map(|(_, output, name)| {
+            // Outputs of older systems don't support IDXGIOutput6, those can't output HDR
+            let hdr = output
+                .cast::<IDXGIOutput6>()
+                .and_then(|output| unsafe {
+                    // Safety: The output is valid.
+                    output.GetDesc1()
+                })
+                .map(|desc| desc.ColorSpace == DXGI_COLOR_SPACE_RGB_FULL_G2084_NONE_P2020)
+                .unwrap_or(false);
+
+            (name, hdr)
+        })
+        .collect())
+}
+
+/// Returns the device interface path of the monitor with the given device name, which OBS uses as `monitor_id`.
+fn get_monitor_device_id(device_name: &str) -> Option<String> {
+    let device_name_w = device_name
+        .encode_utf16()
+        .chain(std::iter::once(0))
+        .collect::<Vec<_>>();
+
+    let mut device = DISPLAY_DEVICEW {
+        cb: std::mem::size_of::<DISPLAY_DEVICEW>() as u32,
+        ..Default::default()
+    };
+
+    let success = unsafe {
+        // Safety: The name is null terminated and the size of the struct is set.
+        EnumDisplayDevicesW(
+            PCWSTR(device_name_w.as_ptr()),
+            0,
+            &mut device,
+            EDD_GET_DEVICE_INTERFACE_NAME,
+        )
+    };
+
+    if !success.as_bool() {
+        return None;
+    }
+
+    let device_id = wide_to_string(&device.DeviceID);
+    if device_id.is_empty() {
+        None
+    } else {
+        Some(device_id)
+    }
+}
+
+fn get_monitor_infos() -> Result<Vec<ObsMonitorInfo>, ObsSimpleError> {
+    let hdr_states = get_hdr_states()?;
+
+    Ok(DisplayInfo::all()
+        .map_err(ObsSimpleError::DisplayInfoError)?
+        .into_iter()
+        .map(|display| ObsMonitorInfo {
+            // Fall back to the device name, which is what `set_monitor` uses as well
+            device_id: get_monitor_device_id(&display.name).unwrap_or_else(|| display.name.clone()),
+            hdr: hdr_states.get(&display.name).copied().unwrap_or(false),
+            device_name: display.name,
+            friendly_name: display.friendly_name,
+            x: display.x,
+            y: display.y,
+            width: display.width,
+            height: display.height,
+            refresh_rate: display.frequency,
+            rotation: display.rotation,
+            scale_factor: display.scale_factor,
+            is_primary: display.is_primary,
+        })
+        .collect())
+}
+
+/// Returns the index of the GPU adapter OBS renders on.
+fn get_renderer_adapter_index(runtime: &ObsRuntime) -> Result<u32, ObsError> {
+    run_with_obs!(runtime, (), move || {
+        let mut ovi = MaybeUninit::<libobs::obs_video_info>::uninit();
+        let was_ok = unsafe {
+            // Safety: The pointer is valid, the struct is only read if the call succeeded.
+            libobs::obs_get_video_info(ovi.as_mut_ptr())
+        };
+
+        if !was_ok {
+            return Err(ObsError::InvalidOperation(
+                "Video is not initialized, can not get the renderer adapter".into(),
+            ));
+        }
+
+        let ovi = unsafe {
+            // Safety: was_ok checked that the video info was properly initialized
+            ovi.assume_init()
+        };
+
+        Ok(ovi.adapter)
+    })?
+}
+
+/// Returns the adapters of the monitor and the renderer if they differ.
+fn check_cross_adapter(
+    device_name: &str,
+    runtime: &ObsRuntime,
+) -> Result<Option<(u32, u32)>, ObsError> {
+    let Some(monitor_adapter) = get_monitor_adapter_index(device_name)? else {
+        return Ok(None);
+    };
+
+    let renderer_adapter = get_renderer_adapter_index(runtime)?;
+    if monitor_adapter == renderer_adapter {
+        Ok(None)
+    } else {
+        Ok(Some((monitor_adapter, renderer_adapter)))
+    }
 }
 
 fn is_thread_dpi_unaware(runtime: &ObsRuntime) -> Result<bool, ObsError> {
@@ -117,16 +383,69 @@ impl MonitorCaptureSourceBuilder {
 
         self
     }
+
+    /// If enabled, the capture method is switched to WGC when the monitor is connected
+    /// to another GPU than the one OBS renders on (see `MonitorCaptureSource::is_cross_adapter`).
+    /// A warning is logged for this scenario regardless.
+    pub fn set_wgc_on_cross_adapter(mut self, enabled: bool) -> Self {
+        self.wgc_on_cross_adapter = enabled;
+
+        self
+    }
+
+    /// Warns if the selected monitor is connected to another GPU than the renderer
+    /// and switches to WGC if requested. Returns true if the capture method was switched.
+    fn check_cross_adapter(&mut self) -> Result<bool, ObsError> {
+        if self.cross_adapter_checked {
+            return Ok(false);
+        }
+        self.cross_adapter_checked = true;
+
+        // Only monitors selected with `set_monitor` can be checked, as the settings are not applied yet
+        let Some(monitor_id) = self.monitor_device.clone() else {
+            return Ok(false);
+        };
+
+        let Some((monitor_adapter, renderer_adapter)) =
+            check_cross_adapter(&monitor_id, self.runtime())?
+        else {
+            return Ok(false);
+        };
+
+        log::warn!(
+            "Monitor {} is connected to adapter {}, but OBS renders on adapter {}. DXGI capture requires a slow cross-adapter copy and may show a black screen.",
+            monitor_id,
+            monitor_adapter,
+            renderer_adapter
+        );
+
+        if self.wgc_on_cross_adapter
+            && self.capture_method != Some(ObsDisplayCaptureMethod::MethodWgc)
+        {
+            log::warn!("Switching capture method of monitor {} to WGC", monitor_id);
+            self.capture_method = Some(ObsDisplayCaptureMethod::MethodWgc);
+            return Ok(true);
+        }
+
+        Ok(false)
+    }
 }
 
 pub type GeneralSourceRef = Arc<Box<dyn ObsSourceTrait>>;
 impl ObsSourceBuilder for MonitorCaptureSourceBuilder {
     type T = MonitorCaptureSource;
 
-    fn build(self) -> Result<Self::T, ObsError>
+    fn build(mut self) -> Result<Self::T, ObsError>
     where
         Self: Sized,
     {
+        if self.check_cross_adapter()? {
+            self.get_settings_updater().set_int_ref(
+                "method",
+                ObsDisplayCaptureMethod::MethodWgc.to_i32().unwrap() as i64,
+            );
+        }
+
         if self.capture_method == Some(ObsDisplayCaptureMethod::MethodDXGI) {
             set_dpi_awareness_if_needed(self.runtime())?;
         }
@@ -142,6 +461,8 @@ impl ObsSourceBuilder for MonitorCaptureSourceBuilder {
     where
         Self: Sized,
     {
+        self.check_cross_adapter()?;
+
         // Because of a black screen bug, we need to set the method to WGC first and then update
         self.get_settings_updater().set_int_ref(
             "method",
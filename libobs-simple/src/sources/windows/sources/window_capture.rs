@@ -100,6 +100,18 @@ impl WindowCaptureSource {
     }
 }
 
+#[cfg(feature = "window-list")]
+impl WindowCaptureSource {
+    /// Switches the capture to another window. The window is replaced in a single settings update
+    /// on the OBS thread, so the source never captures a mix of the old and new window settings.
+    pub fn retarget(
+        &mut self,
+        window: &libobs_wrapper::unsafe_send::Sendable<WindowInfo>,
+    ) -> Result<(), ObsError> {
+        self.create_updater()?.set_window(window).update()
+    }
+}
+
 impl<'a> WindowCaptureSourceUpdater<'a> {
     pub fn set_capture_method(mut self, method: ObsWindowCaptureMethod) -> Self {
         self.get_settings_updater()
@@ -119,6 +131,14 @@ impl WindowCaptureSourceBuilder {
 
 #[obs_object_impl]
 impl WindowCaptureSource {
+    /// Keeps capturing the window when its title changes (e.g. when switching browser tabs).
+    ///
+    /// OBS then looks for a window of the same executable and only uses the title
+    /// to choose between multiple windows of that executable.
+    pub fn ignore_title_changes(self) -> Self {
+        self.set_priority(ObsWindowPriority::Executable)
+    }
+
     pub fn set_capture_audio(mut self, capture_audio: bool) -> Result<Self, ObsSimpleError> {
         use crate::sources::windows::audio_capture_available;
 
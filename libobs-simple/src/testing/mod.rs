@@ -0,0 +1,11 @@
+//! Sources and outputs for headless integration tests, enabled by the `test-harness` feature.
+//!
+//! [`TestPatternSourceBuilder`] renders a synthetic pattern instead of capturing a screen or a
+//! camera, and [`NullOutputBuilder`] encodes like a recording but discards the packets, so scenes,
+//! signals and encoders can be tested in CI without capture permissions or writing files.
+
+mod null_output;
+mod pattern;
+
+pub use null_output::{NullOutputBuilder, NULL_OUTPUT_ID};
+pub use pattern::{TestPattern, TestPatternSource, TestPatternSourceBuilder};
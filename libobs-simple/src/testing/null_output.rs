@@ -0,0 +1,240 @@
+//! An output that encodes like a recording, but discards every packet instead of writing it.
+
+use std::{
+    ffi::{c_char, c_void, CStr, CString},
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use libobs_wrapper::{
+    context::ObsContext,
+    data::output::{ObsOutputRef, ObsOutputTrait},
+    encoders::{ObsAudioEncoderType, ObsVideoEncoderType},
+    run_with_obs,
+    utils::{AudioEncoderInfo, ObsError, ObsString, OutputInfo, VideoEncoderInfo},
+};
+
+/// The id the null output type is registered with
+pub const NULL_OUTPUT_ID: &str = "libobs_simple_null_output";
+const NULL_OUTPUT_NAME: &CStr = c"Null Output";
+
+struct NullOutput {
+    output: *mut libobs::obs_output_t,
+    total_bytes: AtomicU64,
+}
+
+#[allow(unknown_lints)]
+#[allow(ensure_obs_call_in_runtime)]
+/// # Safety
+/// Only called by OBS.
+unsafe extern "C" fn get_name(_type_data: *mut c_void) -> *const c_char {
+    NULL_OUTPUT_NAME.as_ptr()
+}
+
+#[allow(unknown_lints)]
+#[allow(ensure_obs_call_in_runtime)]
+/// # Safety
+/// Only called by OBS with the output that is created.
+unsafe extern "C" fn create(
+    _settings: *mut libobs::obs_data_t,
+    output: *mut libobs::obs_output_t,
+) -> *mut c_void {
+    Box::into_raw(Box::new(NullOutput {
+        output,
+        total_bytes: AtomicU64::new(0),
+    })) as *mut c_void
+}
+
+#[allow(unknown_lints)]
+#[allow(ensure_obs_call_in_runtime)]
+/// # Safety
+/// Only called by OBS once with the data returned by `create`, which isn't used afterwards.
+unsafe extern "C" fn destroy(data: *mut c_void) {
+    drop(Box::from_raw(data as *mut NullOutput));
+}
+
+#[allow(unknown_lints)]
+#[allow(ensure_obs_call_in_runtime)]
+/// # Safety
+/// Only called by OBS with the data returned by `create`.
+unsafe extern "C" fn start(data: *mut c_void) -> bool {
+    let null_output = &*(data as *const NullOutput);
+    null_output.total_bytes.store(0, Ordering::Relaxed);
+
+    // Safety: The output is valid while its data exists
+    libobs::obs_output_can_begin_data_capture(null_output.output, 0)
+        && libobs::obs_output_initialize_encoders(null_output.output, 0)
+        && libobs::obs_output_begin_data_capture(null_output.output, 0)
+}
+
+#[allow(unknown_lints)]
+#[allow(ensure_obs_call_in_runtime)]
+/// # Safety
+/// Only called by OBS with the data returned by `create`.
+unsafe extern "C" fn stop(data: *mut c_void, _ts: u64) {
+    let null_output = &*(data as *const NullOutput);
+
+    // Safety: The output is valid while its data exists
+    libobs::obs_output_end_data_capture(null_output.output);
+}
+
+#[allow(unknown_lints)]
+#[allow(ensure_obs_call_in_runtime)]
+/// # Safety
+/// Only called by OBS with the data returned by `create` and a packet that is valid during the call.
+unsafe extern "C" fn encoded_packet(data: *mut c_void, packet: *mut libobs::encoder_packet) {
+    if packet.is_null() {
+        return;
+    }
+
+    let null_output = &*(data as *const NullOutput);
+    null_output
+        .total_bytes
+        .fetch_add((*packet).size as u64, Ordering::Relaxed);
+}
+
+#[allow(unknown_lints)]
+#[allow(ensure_obs_call_in_runtime)]
+/// # Safety
+/// Only called by OBS with the data returned by `create`.
+unsafe extern "C" fn get_total_bytes(data: *mut c_void) -> u64 {
+    let null_output = &*(data as *const NullOutput);
+    null_output.total_bytes.load(Ordering::Relaxed)
+}
+
+/// Registers the null output type, if it is not registered yet. Output types can't be
+/// unregistered, so it stays available until OBS is shut down.
+fn register_null_output(context: &ObsContext) -> Result<(), ObsError> {
+    let id = CString::new(NULL_OUTPUT_ID).map_err(|_| ObsError::StringConversionError)?;
+
+    run_with_obs!(context.runtime(), move || unsafe {
+        // Safety: We are on the runtime thread and the id is a valid C string
+        if !libobs::obs_output_get_display_name(id.as_ptr()).is_null() {
+            return;
+        }
+
+        // Safety: Every field of the info is either a pointer or an optional callback, so zero is a valid value
+        let mut info = std::mem::zeroed::<libobs::obs_output_info>();
+
+        // OBS keeps the id for as long as the type is registered, which is forever
+        info.id = id.into_raw();
+        info.flags = libobs::OBS_OUTPUT_AV | libobs::OBS_OUTPUT_ENCODED;
+        info.get_name = Some(get_name);
+        info.create = Some(create);
+        info.destroy = Some(destroy);
+        info.start = Some(start);
+        info.stop = Some(stop);
+        info.encoded_packet = Some(encoded_packet);
+        info.get_total_bytes = Some(get_total_bytes);
+
+        // Safety: OBS copies the info, the callbacks are static functions
+        libobs::obs_register_output_s(&info, std::mem::size_of::<libobs::obs_output_info>());
+    })
+}
+
+/// Creates an output that encodes the video and audio like a recording, but discards the
+/// encoded packets, so encoders and the render pipeline can be tested without writing files.
+///
+/// The frame counters of the output (`ObsOutputTrait::frame_counts`) work as usual and the
+/// discarded bytes are reported as the total bytes of the output.
+///
+/// # Example
+///
+/// ```no_run
+/// use libobs_simple::testing::NullOutputBuilder;
+/// use libobs_wrapper::{context::ObsContext, data::output::ObsOutputTrait, utils::StartupInfo};
+///
+/// let context = ObsContext::new(StartupInfo::default()).unwrap();
+/// let mut output = NullOutputBuilder::new(context, "null").build().unwrap();
+///
+/// output.start().unwrap();
+/// std::thread::sleep(std::time::Duration::from_secs(1));
+/// output.stop().unwrap();
+/// ```
+#[derive(Debug)]
+pub struct NullOutputBuilder {
+    context: ObsContext,
+    name: ObsString,
+    video_encoder: ObsVideoEncoderType,
+    audio_encoder: ObsAudioEncoderType,
+    video_bitrate: u32,
+    audio_bitrate: u32,
+}
+
+impl NullOutputBuilder {
+    /// Creates a builder that encodes with x264 and AAC, which don't need a GPU.
+    pub fn new<T: Into<ObsString>>(context: ObsContext, name: T) -> Self {
+        Self {
+            context,
+            name: name.into(),
+            video_encoder: ObsVideoEncoderType::OBS_X264,
+            audio_encoder: ObsAudioEncoderType::FFMPEG_AAC,
+            video_bitrate: 2500,
+            audio_bitrate: 160,
+        }
+    }
+
+    /// Sets the video encoder.
+    pub fn video_encoder(mut self, encoder: ObsVideoEncoderType) -> Self {
+        self.video_encoder = encoder;
+        self
+    }
+
+    /// Sets the audio encoder.
+    pub fn audio_encoder(mut self, encoder: ObsAudioEncoderType) -> Self {
+        self.audio_encoder = encoder;
+        self
+    }
+
+    /// Sets the video bitrate in Kbps.
+    pub fn video_bitrate(mut self, bitrate: u32) -> Self {
+        self.video_bitrate = bitrate;
+        self
+    }
+
+    /// Sets the audio bitrate in Kbps.
+    pub fn audio_bitrate(mut self, bitrate: u32) -> Self {
+        self.audio_bitrate = bitrate;
+        self
+    }
+
+    /// Builds the output and its encoders.
+    pub fn build(mut self) -> Result<ObsOutputRef, ObsError> {
+        register_null_output(&self.context)?;
+
+        let output_info = OutputInfo::new(NULL_OUTPUT_ID, self.name.clone(), None, None);
+        let mut output = self.context.output(output_info)?;
+
+        let mut video_settings = self.context.data()?;
+        video_settings
+            .bulk_update()
+            .set_string("rate_control", "CBR")
+            .set_int("bitrate", self.video_bitrate as i64)
+            .apply()?;
+
+        output.create_and_set_video_encoder(VideoEncoderInfo::new(
+            self.video_encoder,
+            format!("{}_video_encoder", self.name),
+            Some(video_settings),
+            None,
+        ))?;
+
+        let mut audio_settings = self.context.data()?;
+        audio_settings
+            .bulk_update()
+            .set_string("rate_control", "CBR")
+            .set_int("bitrate", self.audio_bitrate as i64)
+            .apply()?;
+
+        output.create_and_set_audio_encoder(
+            AudioEncoderInfo::new(
+                self.audio_encoder,
+                format!("{}_audio_encoder", self.name),
+                Some(audio_settings),
+                None,
+            ),
+            0,
+        )?;
+
+        Ok(output)
+    }
+}
@@ -0,0 +1,227 @@
+//! A synthetic video source that draws a test pattern, so scenes can be rendered without
+//! capture devices or permissions.
+
+use libobs_wrapper::{
+    context::ObsContext,
+    graphics::GraphicsContext,
+    scenes::{ObsSceneItemRef, ObsSceneRef, SceneItemExtSceneTrait},
+    sources::{CustomSourceDef, CustomSourceSettings, ObsSourceRef},
+    utils::{ObsError, ObsString},
+};
+
+/// SMPTE color bars at 75% intensity, as `0xAARRGGBB`
+const COLOR_BARS: [u32; 7] = [
+    0xFFC0C0C0, 0xFFC0C000, 0xFF00C0C0, 0xFF00C000, 0xFFC000C0, 0xFFC00000, 0xFF0000C0,
+];
+const BOX_BACKGROUND: u32 = 0xFF202020;
+const BOX_COLOR: u32 = 0xFFFFFFFF;
+
+/// The pattern drawn by a test pattern source.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TestPattern {
+    /// Static vertical color bars
+    #[default]
+    ColorBars,
+    /// A white box bouncing across a dark background, so consecutive frames differ
+    MovingBox,
+}
+
+impl TestPattern {
+    fn from_i64(value: i64) -> Self {
+        match value {
+            1 => TestPattern::MovingBox,
+            _ => TestPattern::ColorBars,
+        }
+    }
+
+    fn to_i64(self) -> i64 {
+        match self {
+            TestPattern::ColorBars => 0,
+            TestPattern::MovingBox => 1,
+        }
+    }
+}
+
+/// The source type registered by [`TestPatternSourceBuilder`].
+pub struct TestPatternSource {
+    pattern: TestPattern,
+    width: u32,
+    height: u32,
+    /// Top left corner of the moving box
+    box_position: (f32, f32),
+    /// Pixels per second the moving box travels
+    box_velocity: (f32, f32),
+}
+
+impl TestPatternSource {
+    fn box_size(&self) -> f32 {
+        (self.width.min(self.height) / 4).max(1) as f32
+    }
+}
+
+impl CustomSourceDef for TestPatternSource {
+    const ID: &'static str = "libobs_simple_test_pattern";
+    const NAME: &'static str = "Test Pattern";
+
+    fn create(settings: &CustomSourceSettings) -> Self {
+        let mut source = Self {
+            pattern: TestPattern::default(),
+            width: 0,
+            height: 0,
+            box_position: (0.0, 0.0),
+            box_velocity: (0.0, 0.0),
+        };
+        source.update(settings);
+
+        source
+    }
+
+    fn update(&mut self, settings: &CustomSourceSettings) {
+        self.pattern = TestPattern::from_i64(settings.get_int("pattern").unwrap_or_default());
+        self.width = settings
+            .get_int("width")
+            .unwrap_or(1920)
+            .clamp(1, u32::MAX as i64) as u32;
+        self.height = settings
+            .get_int("height")
+            .unwrap_or(1080)
+            .clamp(1, u32::MAX as i64) as u32;
+
+        // Crosses the width in two seconds
+        let speed = self.width as f32 / 2.0;
+        self.box_velocity = (speed, speed * 0.75);
+        self.box_position = (0.0, 0.0);
+    }
+
+    fn video_tick(&mut self, seconds: f32) {
+        if self.pattern != TestPattern::MovingBox {
+            return;
+        }
+
+        let size = self.box_size();
+        let bounds = (self.width as f32 - size, self.height as f32 - size);
+
+        let (x, y) = &mut self.box_position;
+        let (dx, dy) = &mut self.box_velocity;
+        for (position, velocity, max) in [(x, dx, bounds.0), (y, dy, bounds.1)] {
+            *position += *velocity * seconds;
+            if *position <= 0.0 || *position >= max {
+                *position = position.clamp(0.0, max.max(0.0));
+                *velocity = -*velocity;
+            }
+        }
+    }
+
+    fn video_render(&mut self, ctx: &mut GraphicsContext) {
+        let (width, height) = (self.width as f32, self.height as f32);
+
+        match self.pattern {
+            TestPattern::ColorBars => {
+                let bar_width = width / COLOR_BARS.len() as f32;
+                for (index, color) in COLOR_BARS.iter().enumerate() {
+                    ctx.fill_rect(index as f32 * bar_width, 0.0, bar_width, height, *color);
+                }
+            }
+            TestPattern::MovingBox => {
+                let size = self.box_size();
+                let (x, y) = self.box_position;
+
+                ctx.fill_rect(0.0, 0.0, width, height, BOX_BACKGROUND);
+                ctx.fill_rect(x, y, size, size, BOX_COLOR);
+            }
+        }
+    }
+
+    fn get_width(&self) -> u32 {
+        self.width
+    }
+
+    fn get_height(&self) -> u32 {
+        self.height
+    }
+}
+
+/// Creates a [`TestPatternSource`], which is registered with OBS on first use.
+///
+/// # Example
+///
+/// ```no_run
+/// use libobs_simple::testing::{TestPattern, TestPatternSourceBuilder};
+/// use libobs_wrapper::{context::ObsContext, utils::StartupInfo};
+///
+/// let mut context = ObsContext::new(StartupInfo::default()).unwrap();
+/// let mut scene = context.scene("main", Some(0)).unwrap();
+///
+/// TestPatternSourceBuilder::new(context.clone(), "pattern")
+///     .pattern(TestPattern::MovingBox)
+///     .size(1280, 720)
+///     .add_to_scene(&mut scene)
+///     .unwrap();
+/// ```
+#[derive(Debug)]
+pub struct TestPatternSourceBuilder {
+    context: ObsContext,
+    name: ObsString,
+    pattern: TestPattern,
+    width: u32,
+    height: u32,
+}
+
+impl TestPatternSourceBuilder {
+    /// Creates a builder for color bars with a size of 1920x1080.
+    pub fn new<T: Into<ObsString>>(context: ObsContext, name: T) -> Self {
+        Self {
+            context,
+            name: name.into(),
+            pattern: TestPattern::default(),
+            width: 1920,
+            height: 1080,
+        }
+    }
+
+    /// Sets the pattern that is drawn.
+    pub fn pattern(mut self, pattern: TestPattern) -> Self {
+        self.pattern = pattern;
+        self
+    }
+
+    /// Sets the size of the source in pixels.
+    pub fn size(mut self, width: u32, height: u32) -> Self {
+        self.width = width;
+        self.height = height;
+        self
+    }
+
+    /// Builds the source.
+    pub fn build(self) -> Result<ObsSourceRef, ObsError> {
+        match self.context.register_source::<TestPatternSource>() {
+            Ok(()) | Err(ObsError::NameAlreadyInUse(_)) => {}
+            Err(e) => return Err(e),
+        }
+
+        let mut settings = self.context.data()?;
+        settings
+            .bulk_update()
+            .set_int("pattern", self.pattern.to_i64())
+            .set_int("width", self.width as i64)
+            .set_int("height", self.height as i64)
+            .apply()?;
+
+        ObsSourceRef::new(
+            TestPatternSource::ID,
+            self.name,
+            Some(settings.into_immutable()),
+            None,
+            self.context.runtime().clone(),
+        )
+    }
+
+    /// Builds the source and adds it to the scene.
+    pub fn add_to_scene(
+        self,
+        scene: &mut ObsSceneRef,
+    ) -> Result<ObsSceneItemRef<ObsSourceRef>, ObsError> {
+        let source = self.build()?;
+        scene.add_source(source)
+    }
+}
@@ -90,23 +90,13 @@ impl ObsInner {
 
         #[cfg(target_os = "linux")]
         let monitor_item = {
-            use std::path::PathBuf;
-
-            let restore_token_path = std::env::current_exe()
-                .unwrap()
-                .parent()
-                .unwrap()
-                .join(PathBuf::from("pipewire_restore_token.txt"));
-            let restore_token = if restore_token_path.exists() {
-                Some(std::fs::read_to_string(&restore_token_path).unwrap())
-            } else {
-                None
-            };
+            use libobs_simple::sources::linux::pipewire::FileRestoreTokenStore;
 
+            let store = FileRestoreTokenStore::next_to_executable("pipewire_restore_token.txt")?;
             context
                 .source_builder::<LinuxGeneralScreenCaptureBuilder, _>("Monitor capture")
                 .unwrap()
-                .set_restore_token(&restore_token.unwrap_or_default())
+                .load_restore_token(&store)?
                 .add_to_scene(&mut scene)?
         };
 
@@ -216,14 +206,14 @@ impl ApplicationHandler for App {
 
         #[cfg(target_os = "linux")]
         if let ObsEitherSource::Right(pipewire) = inner._source.inner_source() {
-            if let Ok(Some(token)) = pipewire.get_restore_token() {
-                let restore_token_path = std::env::current_exe()
-                    .unwrap()
-                    .parent()
-                    .unwrap()
-                    .join(std::path::PathBuf::from("pipewire_restore_token.txt"));
+            use libobs_simple::sources::linux::pipewire::{
+                FileRestoreTokenStore, RestoreTokenStore,
+            };
 
-                std::fs::write(restore_token_path, token).unwrap();
+            if let Ok(Some(token)) = pipewire.get_restore_token() {
+                FileRestoreTokenStore::next_to_executable("pipewire_restore_token.txt")
+                    .and_then(|store| store.save(&token))
+                    .unwrap();
             }
         }
     }
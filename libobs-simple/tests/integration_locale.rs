@@ -0,0 +1,18 @@
+use libobs_wrapper::{context::ObsContext, utils::StartupInfo};
+
+/// Integration test: The startup locale is used by libobs and can be changed at runtime
+#[test]
+pub fn test_global_locale() {
+    let _ = env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("debug"))
+        .is_test(true)
+        .try_init();
+
+    let startup_info = StartupInfo::default().set_locale("de-DE");
+    assert_eq!(startup_info.get_locale(), "de-DE");
+
+    let context = ObsContext::new(startup_info).unwrap();
+    assert_eq!(context.locale().unwrap(), "de-DE");
+
+    context.set_locale("fr-FR").unwrap();
+    assert_eq!(context.locale().unwrap(), "fr-FR");
+}
@@ -0,0 +1,24 @@
+use libobs_wrapper::{context::ObsContext, utils::StartupInfo};
+
+/// Integration test: Loading a module that is already loaded returns the loaded module
+#[test]
+pub fn test_load_module_twice() {
+    let _ = env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("debug"))
+        .is_test(true)
+        .try_init();
+
+    let context = ObsContext::new(StartupInfo::default()).unwrap();
+    let modules = context.modules();
+
+    let first = modules.load_module("obs-ffmpeg").unwrap();
+    let second = modules.load_module("obs-ffmpeg").unwrap();
+    assert_eq!(first, second);
+    assert_eq!(first.module, "obs-ffmpeg");
+
+    let loaded = modules.loaded_modules().unwrap();
+    assert_eq!(
+        loaded.iter().filter(|m| m.module == "obs-ffmpeg").count(),
+        1,
+        "The module has been loaded twice"
+    );
+}
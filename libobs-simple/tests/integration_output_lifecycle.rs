@@ -108,3 +108,39 @@ pub fn test_output_different_settings() {
     let info2 = OutputInfo::new("ffmpeg_muxer", "configured_output", Some(settings2), None);
     assert!(context.output(info2).is_ok());
 }
+
+/// Integration test: Shutting down while another clone is alive leaves the clone untouched
+#[test]
+pub fn test_shutdown_with_live_clone() {
+    let _ = env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("debug"))
+        .is_test(true)
+        .try_init();
+
+    let mut context = ObsContext::new(StartupInfo::default()).unwrap();
+    let scene = context.scene("shutdown_scene", Some(0)).unwrap();
+
+    let mut settings = context.data().unwrap();
+    settings
+        .set_string("path", ObsString::new("shutdown_test.mp4"))
+        .unwrap();
+    let info = OutputInfo::new("ffmpeg_muxer", "shutdown_output", Some(settings), None);
+    context.output(info).unwrap();
+
+    let mut clone = context.clone();
+    let errors = context.shutdown().unwrap_err();
+    assert_eq!(
+        errors.len(),
+        1,
+        "Expected only the clone error: {:?}",
+        errors
+    );
+
+    // Nothing has been torn down, so the clone still sees its scenes and outputs
+    let found = clone.get_scene("shutdown_scene").unwrap();
+    assert!(found.is_some(), "The scene of the clone has been dropped");
+    assert!(clone.get_output("shutdown_output").unwrap().is_some());
+    scene.set_to_channel(0).unwrap();
+
+    drop(scene);
+    clone.shutdown().unwrap();
+}
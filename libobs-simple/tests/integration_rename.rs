@@ -0,0 +1,78 @@
+use libobs_wrapper::{
+    context::ObsContext,
+    data::object::ObsObjectTrait,
+    encoders::audio::ObsAudioEncoder,
+    sources::ObsSourceRef,
+    utils::{AudioEncoderInfo, ObsError, StartupInfo},
+};
+
+/// Integration test: Renaming a source is seen by every wrapper of it and fails if the name is taken
+#[test]
+pub fn test_source_rename() {
+    let _ = env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("debug"))
+        .is_test(true)
+        .try_init();
+
+    let context = ObsContext::new(StartupInfo::default()).unwrap();
+    let runtime = context.runtime().clone();
+
+    let first =
+        ObsSourceRef::new("color_source", "rename_first", None, None, runtime.clone()).unwrap();
+    let second =
+        ObsSourceRef::new("color_source", "rename_second", None, None, runtime.clone()).unwrap();
+
+    first.set_name("rename_renamed").unwrap();
+    assert_eq!(first.name().to_string(), "rename_renamed");
+
+    // Renaming to the current name is not a collision
+    first.set_name("rename_renamed").unwrap();
+
+    let result = second.set_name("rename_renamed");
+    assert!(
+        matches!(result, Err(ObsError::NameAlreadyInUse(_))),
+        "Expected NameAlreadyInUse, got {:?}",
+        result
+    );
+    assert_eq!(second.name().to_string(), "rename_second");
+
+    // A separate wrapper has its own cached name, it has to pick up the rename anyway
+    let existing = ObsSourceRef::new_from_existing("rename_second", runtime)
+        .unwrap()
+        .expect("The source should exist");
+    existing.set_name("rename_outside").unwrap();
+    assert_eq!(second.name().to_string(), "rename_outside");
+}
+
+/// Integration test: Renaming an encoder fails if another encoder already has the name
+#[test]
+pub fn test_encoder_rename() {
+    let _ = env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("debug"))
+        .is_test(true)
+        .try_init();
+
+    let context = ObsContext::new(StartupInfo::default()).unwrap();
+
+    let first = ObsAudioEncoder::new_from_info(
+        AudioEncoderInfo::new("ffmpeg_aac", "rename_audio_first", None, None),
+        0,
+        context.runtime().clone(),
+    )
+    .unwrap();
+    let second = ObsAudioEncoder::new_from_info(
+        AudioEncoderInfo::new("ffmpeg_aac", "rename_audio_second", None, None),
+        0,
+        context.runtime().clone(),
+    )
+    .unwrap();
+
+    first.set_name("rename_audio_renamed").unwrap();
+    assert_eq!(first.name().to_string(), "rename_audio_renamed");
+
+    let result = second.set_name("rename_audio_renamed");
+    assert!(
+        matches!(result, Err(ObsError::NameAlreadyInUse(_))),
+        "Expected NameAlreadyInUse, got {:?}",
+        result
+    );
+    assert_eq!(second.name().to_string(), "rename_audio_second");
+}
@@ -0,0 +1,237 @@
+#![cfg(feature = "test-harness")]
+
+mod common;
+
+use std::time::{Duration, Instant};
+
+use libobs_simple::testing::{NullOutputBuilder, TestPattern, TestPatternSourceBuilder};
+use libobs_wrapper::{
+    context::ObsContext,
+    data::{output::ObsOutputTrait, video::ObsVideoInfo},
+    graphics::{ObsFrameHash, ObsThumbnailConfig},
+    utils::StartupInfo,
+};
+
+/// Integration test: Test pattern sources can be created more than once
+#[test]
+pub fn test_pattern_source() {
+    let _ = env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("debug"))
+        .is_test(true)
+        .try_init();
+
+    let mut context = ObsContext::new(StartupInfo::default()).unwrap();
+    let mut scene = context.scene("pattern_scene", Some(0)).unwrap();
+
+    let bars = TestPatternSourceBuilder::new(context.clone(), "bars")
+        .size(640, 360)
+        .add_to_scene(&mut scene);
+    assert!(bars.is_ok(), "Failed to create color bars");
+
+    // The source type is already registered now, which must not fail
+    let moving_box = TestPatternSourceBuilder::new(context.clone(), "moving_box")
+        .pattern(TestPattern::MovingBox)
+        .size(320, 240)
+        .add_to_scene(&mut scene);
+    assert!(moving_box.is_ok(), "Failed to create moving box");
+}
+
+/// Integration test: Null outputs encode frames without writing them anywhere
+#[test]
+pub fn test_null_output() {
+    let _ = env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("debug"))
+        .is_test(true)
+        .try_init();
+
+    let mut context = ObsContext::new(StartupInfo::default()).unwrap();
+    let mut scene = context.scene("null_output_scene", Some(0)).unwrap();
+
+    TestPatternSourceBuilder::new(context.clone(), "pattern")
+        .pattern(TestPattern::MovingBox)
+        .add_to_scene(&mut scene)
+        .unwrap();
+
+    let mut output = NullOutputBuilder::new(context.clone(), "null_output")
+        .build()
+        .unwrap();
+
+    output.start().unwrap();
+    std::thread::sleep(Duration::from_secs(2));
+    let (total, _dropped) = output.frame_counts().unwrap();
+    output.stop().unwrap();
+
+    assert!(total > 0, "The null output did not receive any frames");
+}
+
+/// Integration test: The rendered test pattern can be validated by its frame hash
+#[test]
+pub fn test_frame_hash() {
+    let _ = env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("debug"))
+        .is_test(true)
+        .try_init();
+
+    let mut context = ObsContext::new(StartupInfo::default()).unwrap();
+    let mut scene = context.scene("hash_scene", Some(0)).unwrap();
+
+    TestPatternSourceBuilder::new(context.clone(), "hash_bars")
+        .add_to_scene(&mut scene)
+        .unwrap();
+
+    std::thread::sleep(Duration::from_millis(500));
+
+    let first = context.hash_current_frame().unwrap();
+    assert_ne!(first.value(), 0, "Expected the color bars to be rendered");
+
+    std::thread::sleep(Duration::from_millis(100));
+    context
+        .hash_current_frame()
+        .unwrap()
+        .assert_similar(&first, 5);
+}
+
+/// Integration test: Outputs that stop in time are not force stopped
+#[test]
+pub fn test_stop_with_force() {
+    let _ = env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("debug"))
+        .is_test(true)
+        .try_init();
+
+    let mut context = ObsContext::new(StartupInfo::default()).unwrap();
+    let mut scene = context.scene("stop_scene", Some(0)).unwrap();
+
+    TestPatternSourceBuilder::new(context.clone(), "stop_pattern")
+        .add_to_scene(&mut scene)
+        .unwrap();
+
+    let mut output = NullOutputBuilder::new(context.clone(), "stop_null_output")
+        .build()
+        .unwrap();
+
+    output.start().unwrap();
+    std::thread::sleep(Duration::from_secs(1));
+
+    let forced = output.stop_with_force(Duration::from_secs(5)).unwrap();
+    assert!(!forced, "Null output should stop without being forced");
+
+    // Stopping an inactive output is not an error
+    assert!(!output.stop_with_force(Duration::from_secs(5)).unwrap());
+}
+
+/// Integration test: Thumbnails of a source are rendered without a display
+#[test]
+pub fn test_source_thumbnails() {
+    let _ = env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("debug"))
+        .is_test(true)
+        .try_init();
+
+    let context = ObsContext::new(StartupInfo::default()).unwrap();
+    let source = TestPatternSourceBuilder::new(context.clone(), "thumbnail_bars")
+        .size(640, 360)
+        .build()
+        .unwrap();
+
+    let thumbnailer = context
+        .source_thumbnailer(ObsThumbnailConfig::default())
+        .unwrap();
+    thumbnailer.add_source(&source).unwrap();
+    let mut thumbnails = thumbnailer.subscribe();
+
+    let deadline = Instant::now() + Duration::from_secs(5);
+    let thumbnail = loop {
+        match thumbnails.try_recv() {
+            Ok(thumbnail) => break thumbnail,
+            Err(_) if Instant::now() < deadline => std::thread::sleep(Duration::from_millis(50)),
+            Err(e) => panic!("No thumbnail has been rendered: {:?}", e),
+        }
+    };
+
+    assert_eq!(thumbnail.source, "thumbnail_bars");
+    assert_eq!((thumbnail.width, thumbnail.height), (160, 90));
+    assert_eq!(thumbnail.pixels.len(), 160 * 90 * 4);
+
+    let hash =
+        ObsFrameHash::from_rgba(&thumbnail.pixels, thumbnail.width, thumbnail.height).unwrap();
+    assert_ne!(hash.value(), 0, "Expected the color bars in the thumbnail");
+
+    assert!(thumbnailer.remove_source("thumbnail_bars").unwrap());
+}
+
+/// Integration test: Idle rendering never resets the video an existing output was created with
+#[test]
+pub fn test_idle_output_restart() {
+    let _ = env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("debug"))
+        .is_test(true)
+        .try_init();
+
+    let startup_info = StartupInfo::default().set_idle_frame_rate(1, 1);
+    let mut context = ObsContext::new(startup_info).unwrap();
+    assert!(
+        context.is_rendering_idle(),
+        "Expected the video to be idle on startup"
+    );
+
+    let mut scene = context.scene("idle_scene", Some(0)).unwrap();
+    TestPatternSourceBuilder::new(context.clone(), "idle_pattern")
+        .add_to_scene(&mut scene)
+        .unwrap();
+
+    let mut output = NullOutputBuilder::new(context.clone(), "idle_null_output")
+        .build()
+        .unwrap();
+    assert!(
+        !context.is_rendering_idle(),
+        "Creating an output has to wake the video"
+    );
+
+    output.start().unwrap();
+    std::thread::sleep(Duration::from_secs(1));
+    output.stop().unwrap();
+
+    // The stopped output still uses the video, so it must not be reset
+    assert!(!context.idle_rendering().unwrap());
+    assert!(!context.is_rendering_idle());
+
+    output.start().unwrap();
+    std::thread::sleep(Duration::from_secs(1));
+    let (total, _dropped) = output.frame_counts().unwrap();
+    output.stop().unwrap();
+
+    assert!(total > 0, "The restarted output did not receive any frames");
+}
+
+/// Integration test: A view can't be removed while an output encodes it
+#[test]
+pub fn test_view_remove_while_encoding() {
+    let _ = env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("debug"))
+        .is_test(true)
+        .try_init();
+
+    let mut context = ObsContext::new(StartupInfo::default()).unwrap();
+    let mut scene = context.scene("view_scene", None).unwrap();
+    TestPatternSourceBuilder::new(context.clone(), "view_pattern")
+        .add_to_scene(&mut scene)
+        .unwrap();
+
+    let view = context.view().unwrap();
+    view.set_scene(0, &scene).unwrap();
+    view.add(&ObsVideoInfo::default()).unwrap();
+
+    let mut output = NullOutputBuilder::new(context.clone(), "view_null_output")
+        .build()
+        .unwrap();
+    output.set_view(Some(view.clone())).unwrap();
+
+    output.start().unwrap();
+    std::thread::sleep(Duration::from_millis(500));
+    assert!(
+        view.remove().is_err(),
+        "The view must not be removed while the output encodes it"
+    );
+    assert!(view.is_added().unwrap());
+
+    output.stop().unwrap();
+    view.remove().unwrap();
+    assert!(!view.is_added().unwrap());
+
+    // The stopped output can't start without the video of the view
+    assert!(output.start().is_err());
+}
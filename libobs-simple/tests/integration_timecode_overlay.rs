@@ -0,0 +1,78 @@
+mod common;
+
+use std::time::Duration;
+
+use libobs_simple::sources::TimecodeOverlayBuilder;
+use libobs_wrapper::{
+    context::ObsContext,
+    data::{object::ObsObjectTrait, ObsDataGetters},
+    utils::StartupInfo,
+};
+
+/// Integration test: A wall-clock timecode overlay keeps rendering until it is dropped
+#[test]
+pub fn test_timecode_overlay() {
+    let _ = env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("debug"))
+        .is_test(true)
+        .try_init();
+
+    let mut context = ObsContext::new(StartupInfo::default()).unwrap();
+    let mut scene = context.scene("timecode_scene", Some(0)).unwrap();
+
+    let overlay = TimecodeOverlayBuilder::new(context.clone(), "timecode")
+        .font_size(32)
+        .build();
+    assert!(overlay.is_ok(), "Failed to create timecode overlay");
+
+    let overlay = overlay.unwrap();
+    assert!(
+        overlay.add_to_scene(&mut scene).is_ok(),
+        "Failed to add timecode overlay to scene"
+    );
+
+    std::thread::sleep(Duration::from_millis(500));
+    drop(overlay);
+}
+
+/// Integration test: Wall-clock overlays keep their own UTC offset
+#[test]
+pub fn test_timecode_overlay_utc_offset() {
+    let _ = env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("debug"))
+        .is_test(true)
+        .try_init();
+
+    let mut context = ObsContext::new(StartupInfo::default()).unwrap();
+    let mut scene = context.scene("timecode_offset_scene", Some(0)).unwrap();
+
+    let utc = TimecodeOverlayBuilder::new(context.clone(), "timecode_utc")
+        .build()
+        .unwrap();
+    let tokyo = TimecodeOverlayBuilder::new(context.clone(), "timecode_tokyo")
+        .utc_offset(9 * 60)
+        .build()
+        .unwrap();
+    utc.add_to_scene(&mut scene).unwrap();
+    tokyo.add_to_scene(&mut scene).unwrap();
+
+    assert_eq!(utc.utc_offset(), 0);
+    assert_eq!(tokyo.utc_offset(), 9 * 60);
+
+    tokyo.set_utc_offset(-5 * 60);
+    assert_eq!(tokyo.utc_offset(), -5 * 60);
+    assert_eq!(utc.utc_offset(), 0, "The offset of another overlay changed");
+
+    std::thread::sleep(Duration::from_millis(500));
+
+    let text = |overlay: &libobs_simple::sources::TimecodeOverlay| {
+        overlay
+            .source()
+            .settings()
+            .unwrap()
+            .get_string("text")
+            .unwrap()
+            .unwrap_or_default()
+    };
+    let (utc_text, tokyo_text) = (text(&utc), text(&tokyo));
+    assert!(!utc_text.is_empty(), "The overlay has not been updated");
+    assert_ne!(utc_text, tokyo_text, "Both overlays show the same time");
+}
@@ -0,0 +1,277 @@
+//! Extracts the icon and a thumbnail of a window as RGBA buffers, e.g. to show previews in a window picker.
+use std::ffi::c_void;
+
+use windows::{
+    core::Error,
+    Win32::{
+        Foundation::{HWND, LPARAM, RECT, WPARAM},
+        Graphics::Gdi::{
+            CreateCompatibleBitmap, CreateCompatibleDC, DeleteDC, DeleteObject, GetDC, GetDIBits,
+            GetObjectW, ReleaseDC, SelectObject, SetStretchBltMode, StretchBlt, BITMAP, BITMAPINFO,
+            BITMAPINFOHEADER, BI_RGB, DIB_RGB_COLORS, HALFTONE, HBITMAP, HDC, SRCCOPY,
+        },
+        Storage::Xps::{PrintWindow, PRINT_WINDOW_FLAGS},
+        UI::WindowsAndMessaging::{
+            GetClassLongPtrW, GetIconInfo, GetWindowRect, SendMessageTimeoutW, GCLP_HICON, HICON,
+            ICONINFO, ICON_BIG, SMTO_ABORTIFHUNG, WM_GETICON,
+        },
+    },
+};
+
+use crate::error::WindowHelperError;
+
+/// Also renders windows that use DirectComposition (e.g. browsers), which would be black otherwise.
+/// This flag is missing in the windows metadata.
+const PW_RENDERFULLCONTENT: PRINT_WINDOW_FLAGS = PRINT_WINDOW_FLAGS(2);
+
+/// How long to wait for a window to return its icon, so hung windows don't block the caller.
+const ICON_TIMEOUT_MS: u32 = 100;
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// An image of a window. The pixels are stored row by row from the top as RGBA with 8 bits per channel.
+pub struct WindowImage {
+    pub width: u32,
+    pub height: u32,
+    pub data: Vec<u8>,
+}
+
+/// Reads the pixels of the given bitmap as RGBA. The bitmap must not be selected into a device context.
+///
+/// # Safety
+/// The device context and bitmap must be valid and the bitmap must have the given size.
+unsafe fn read_bitmap(
+    hdc: HDC,
+    bitmap: HBITMAP,
+    width: i32,
+    height: i32,
+) -> Result<Vec<u8>, WindowHelperError> {
+    let mut info = BITMAPINFO {
+        bmiHeader: BITMAPINFOHEADER {
+            biSize: std::mem::size_of::<BITMAPINFOHEADER>() as u32,
+            biWidth: width,
+            // A negative height requests the rows from the top
+            biHeight: -height,
+            biPlanes: 1,
+            biBitCount: 32,
+            biCompression: BI_RGB.0,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    let mut data = vec![0u8; width as usize * height as usize * 4];
+    let lines = GetDIBits(
+        hdc,
+        bitmap,
+        0,
+        height as u32,
+        Some(data.as_mut_ptr() as *mut c_void),
+        &mut info,
+        DIB_RGB_COLORS,
+    );
+
+    if lines == 0 {
+        return Err(Error::from_thread().into());
+    }
+
+    // GDI stores the pixels as BGRA
+    for pixel in data.chunks_exact_mut(4) {
+        pixel.swap(0, 2);
+    }
+
+    Ok(data)
+}
+
+fn set_opaque(data: &mut [u8]) {
+    for pixel in data.chunks_exact_mut(4) {
+        pixel[3] = 255;
+    }
+}
+
+fn icon_to_image(icon: HICON) -> Result<WindowImage, WindowHelperError> {
+    let mut icon_info = ICONINFO::default();
+    // Safety: The icon handle has been returned by the window and the struct is valid to be written to.
+    unsafe { GetIconInfo(icon, &mut icon_info)? };
+
+    let result = color_bitmap_to_image(icon_info.hbmColor);
+
+    // Safety: GetIconInfo creates copies of the bitmaps, which are owned by the caller.
+    unsafe {
+        let _ = DeleteObject(icon_info.hbmColor.into());
+        let _ = DeleteObject(icon_info.hbmMask.into());
+    }
+
+    let mut image = result?;
+
+    // Icons without an alpha channel are fully opaque
+    if image.data.chunks_exact(4).all(|pixel| pixel[3] == 0) {
+        set_opaque(&mut image.data);
+    }
+
+    Ok(image)
+}
+
+fn color_bitmap_to_image(bitmap: HBITMAP) -> Result<WindowImage, WindowHelperError> {
+    if bitmap.is_invalid() {
+        return Err(WindowHelperError::InvalidState(
+            "Monochrome icons are not supported".to_string(),
+        ));
+    }
+
+    let mut bitmap_info = BITMAP::default();
+    // Safety: The bitmap is valid and the size of the struct is passed.
+    let size = unsafe {
+        GetObjectW(
+            bitmap.into(),
+            std::mem::size_of::<BITMAP>() as i32,
+            Some(&mut bitmap_info as *mut BITMAP as *mut c_void),
+        )
+    };
+
+    if size == 0 {
+        return Err(Error::from_thread().into());
+    }
+
+    let width = bitmap_info.bmWidth;
+    let height = bitmap_info.bmHeight;
+
+    // Safety: The device context is deleted right after the bitmap has been read.
+    let data = unsafe {
+        let hdc = CreateCompatibleDC(None);
+        let data = read_bitmap(hdc, bitmap, width, height);
+        let _ = DeleteDC(hdc);
+
+        data?
+    };
+
+    Ok(WindowImage {
+        width: width as u32,
+        height: height as u32,
+        data,
+    })
+}
+
+/// Retrieves the (large) icon of the given window.
+///
+/// # Returns
+///
+/// Returns `None` if neither the window nor its class has an icon.
+pub fn get_window_icon(handle: HWND) -> Result<Option<WindowImage>, WindowHelperError> {
+    let mut icon = 0usize;
+    // Safety: `handle` is a valid HWND, the timeout makes sure hung windows don't block.
+    unsafe {
+        SendMessageTimeoutW(
+            handle,
+            WM_GETICON,
+            WPARAM(ICON_BIG as usize),
+            LPARAM(0),
+            SMTO_ABORTIFHUNG,
+            ICON_TIMEOUT_MS,
+            Some(&mut icon),
+        )
+    };
+
+    if icon == 0 {
+        // Safety: `handle` is a valid HWND, 0 is returned if the class has no icon.
+        icon = unsafe { GetClassLongPtrW(handle, GCLP_HICON) };
+    }
+
+    if icon == 0 {
+        return Ok(None);
+    }
+
+    icon_to_image(HICON(icon as *mut c_void)).map(Some)
+}
+
+/// Renders a thumbnail of the given window using `PrintWindow`.
+///
+/// # Arguments
+///
+/// * `handle` - The handle to the window.
+/// * `max_width` / `max_height` - The maximum size of the thumbnail, the aspect ratio of the window is kept.
+///
+/// # Errors
+///
+/// Returns an error if the window has no size (e.g. is minimized) or could not be rendered.
+pub fn get_window_thumbnail(
+    handle: HWND,
+    max_width: u32,
+    max_height: u32,
+) -> Result<WindowImage, WindowHelperError> {
+    let mut rect = RECT::default();
+    // Safety: `handle` is a valid HWND and the rect is valid to be written to.
+    unsafe { GetWindowRect(handle, &mut rect)? };
+
+    let width = rect.right - rect.left;
+    let height = rect.bottom - rect.top;
+    if width <= 0 || height <= 0 {
+        return Err(WindowHelperError::InvalidState(
+            "Window has no size".to_string(),
+        ));
+    }
+
+    let scale = (max_width as f64 / width as f64)
+        .min(max_height as f64 / height as f64)
+        .min(1.0);
+    let thumb_width = ((width as f64 * scale).round() as i32).max(1);
+    let thumb_height = ((height as f64 * scale).round() as i32).max(1);
+
+    // Safety: Every GDI object created here is deselected and deleted before returning,
+    // the bitmaps are only read after they have been deselected.
+    let mut data = unsafe {
+        let screen_dc = GetDC(None);
+        let window_dc = CreateCompatibleDC(Some(screen_dc));
+        let thumb_dc = CreateCompatibleDC(Some(screen_dc));
+        let window_bitmap = CreateCompatibleBitmap(screen_dc, width, height);
+        let thumb_bitmap = CreateCompatibleBitmap(screen_dc, thumb_width, thumb_height);
+
+        let old_window_bitmap = SelectObject(window_dc, window_bitmap.into());
+        let old_thumb_bitmap = SelectObject(thumb_dc, thumb_bitmap.into());
+
+        let rendered = PrintWindow(handle, window_dc, PW_RENDERFULLCONTENT).as_bool()
+            && SetStretchBltMode(thumb_dc, HALFTONE) != 0
+            && StretchBlt(
+                thumb_dc,
+                0,
+                0,
+                thumb_width,
+                thumb_height,
+                Some(window_dc),
+                0,
+                0,
+                width,
+                height,
+                SRCCOPY,
+            )
+            .as_bool();
+
+        let error = Error::from_thread();
+        SelectObject(window_dc, old_window_bitmap);
+        SelectObject(thumb_dc, old_thumb_bitmap);
+
+        let data = if rendered {
+            read_bitmap(thumb_dc, thumb_bitmap, thumb_width, thumb_height)
+        } else {
+            Err(error.into())
+        };
+
+        let _ = DeleteObject(window_bitmap.into());
+        let _ = DeleteObject(thumb_bitmap.into());
+        let _ = DeleteDC(window_dc);
+        let _ = DeleteDC(thumb_dc);
+        ReleaseDC(None, screen_dc);
+
+        data?
+    };
+
+    // PrintWindow does not fill the alpha channel
+    set_opaque(&mut data);
+
+    Ok(WindowImage {
+        width: thumb_width as u32,
+        height: thumb_height as u32,
+        data,
+    })
+}
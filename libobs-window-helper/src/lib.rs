@@ -16,6 +16,9 @@ pub use error::WindowHelperError;
 mod game;
 #[cfg_attr(coverage_nightly, coverage(off))]
 #[cfg(windows)]
+mod image;
+#[cfg_attr(coverage_nightly, coverage(off))]
+#[cfg(windows)]
 mod monitor;
 #[cfg(windows)]
 mod util;
@@ -34,6 +37,8 @@ pub use game::*;
 #[cfg(windows)]
 pub use helper::*;
 #[cfg(windows)]
+pub use image::{get_window_icon, get_window_thumbnail, WindowImage};
+#[cfg(windows)]
 use win_iterator::{first_window, next_window};
 #[cfg(windows)]
 use windows::Win32::{Foundation::HWND, System::Console::GetConsoleWindow};
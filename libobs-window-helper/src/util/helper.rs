@@ -44,6 +44,23 @@ pub struct WindowInfo {
     pub is_game: bool,
 }
 
+#[cfg(all(not(feature = "serde"), not(feature = "specta")))]
+impl WindowInfo {
+    /// Retrieves the icon of this window, see `get_window_icon`.
+    pub fn icon(&self) -> Result<Option<crate::WindowImage>, WindowHelperError> {
+        crate::get_window_icon(self.handle)
+    }
+
+    /// Renders a thumbnail of this window that fits into the given size, see `get_window_thumbnail`.
+    pub fn thumbnail(
+        &self,
+        max_width: u32,
+        max_height: u32,
+    ) -> Result<crate::WindowImage, WindowHelperError> {
+        crate::get_window_thumbnail(self.handle, max_width, max_height)
+    }
+}
+
 fn encode_string(s: &str) -> String {
     s.replace("#", "#22").replace(":", "#3A")
 }
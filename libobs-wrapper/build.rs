@@ -1,6 +1,9 @@
 fn main() {
     let target_os = std::env::var("CARGO_CFG_TARGET_OS").unwrap_or_default();
-    if target_os != "windows" && target_os != "macos" {
+    if target_os == "macos" {
+        println!("cargo:rustc-link-lib=objc");
+        println!("cargo:rustc-link-lib=framework=AppKit");
+    } else if target_os != "windows" {
         println!("cargo:rustc-link-lib=X11");
         println!("cargo:rustc-link-lib=wayland-client");
     }
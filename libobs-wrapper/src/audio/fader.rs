@@ -0,0 +1,316 @@
+use std::{
+    ffi::c_void,
+    sync::{Arc, RwLock},
+};
+
+use libobs::obs_fader_t;
+use tokio::sync::broadcast;
+
+use crate::{
+    enums::{ObsFaderType, OsEnumType},
+    impl_obs_drop, run_with_obs,
+    runtime::ObsRuntime,
+    sources::ObsSourceTrait,
+    unsafe_send::{Sendable, SmartPointerSendable},
+    utils::{ObsDropGuard, ObsError},
+};
+
+const LOG_OFFSET_DB: f32 = 6.0;
+const LOG_RANGE_DB: f32 = 96.0;
+// -log10(LOG_OFFSET_DB)
+const LOG_OFFSET_VAL: f32 = -0.778_151_3;
+// -log10(LOG_RANGE_DB + LOG_OFFSET_DB)
+const LOG_RANGE_VAL: f32 = -2.008_600_2;
+
+/// Converts a linear multiplier to dB, `0.0` is `-inf` dB.
+pub fn mul_to_db(mul: f32) -> f32 {
+    if mul <= 0.0 {
+        f32::NEG_INFINITY
+    } else {
+        20.0 * mul.log10()
+    }
+}
+
+/// Converts dB to a linear multiplier, `-inf` dB is `0.0`.
+pub fn db_to_mul(db: f32) -> f32 {
+    if db.is_finite() {
+        10f32.powf(db / 20.0)
+    } else {
+        0.0
+    }
+}
+
+impl ObsFaderType {
+    /// Converts a fader deflection (`0.0` - `1.0`) to dB, using the same curves as libobs.
+    pub fn deflection_to_db(&self, deflection: f32) -> f32 {
+        if deflection >= 1.0 {
+            return 0.0;
+        }
+        if deflection <= 0.0 {
+            return f32::NEG_INFINITY;
+        }
+
+        match self {
+            ObsFaderType::Cubic => mul_to_db(deflection.powi(3)),
+            ObsFaderType::IEC => iec_def_to_db(deflection),
+            ObsFaderType::Log => {
+                -(LOG_RANGE_DB + LOG_OFFSET_DB)
+                    * ((LOG_RANGE_DB + LOG_OFFSET_DB) / LOG_OFFSET_DB).powf(-deflection)
+                    + LOG_OFFSET_DB
+            }
+        }
+    }
+
+    /// Converts dB to a fader deflection (`0.0` - `1.0`), using the same curves as libobs.
+    pub fn db_to_deflection(&self, db: f32) -> f32 {
+        if db >= 0.0 {
+            return 1.0;
+        }
+        if db == f32::NEG_INFINITY {
+            return 0.0;
+        }
+
+        match self {
+            ObsFaderType::Cubic => db_to_mul(db).cbrt(),
+            ObsFaderType::IEC => iec_db_to_def(db),
+            ObsFaderType::Log => {
+                if db <= -LOG_RANGE_DB {
+                    return 0.0;
+                }
+
+                (-(-db + LOG_OFFSET_DB).log10() - LOG_RANGE_VAL) / (LOG_OFFSET_VAL - LOG_RANGE_VAL)
+            }
+        }
+    }
+}
+
+fn iec_def_to_db(def: f32) -> f32 {
+    if def >= 0.75 {
+        (def - 1.0) / 0.25 * 9.0
+    } else if def >= 0.5 {
+        (def - 0.75) / 0.25 * 11.0 - 9.0
+    } else if def >= 0.3 {
+        (def - 0.5) / 0.2 * 10.0 - 20.0
+    } else if def >= 0.15 {
+        (def - 0.3) / 0.15 * 10.0 - 30.0
+    } else if def >= 0.075 {
+        (def - 0.15) / 0.075 * 10.0 - 40.0
+    } else if def >= 0.025 {
+        (def - 0.075) / 0.05 * 10.0 - 50.0
+    } else if def >= 0.001 {
+        (def - 0.025) / 0.025 * 90.0 - 60.0
+    } else {
+        f32::NEG_INFINITY
+    }
+}
+
+fn iec_db_to_def(db: f32) -> f32 {
+    if db >= -9.0 {
+        (db + 9.0) / 9.0 * 0.25 + 0.75
+    } else if db >= -20.0 {
+        (db + 20.0) / 11.0 * 0.25 + 0.5
+    } else if db >= -30.0 {
+        (db + 30.0) / 10.0 * 0.2 + 0.3
+    } else if db >= -40.0 {
+        (db + 40.0) / 10.0 * 0.15 + 0.15
+    } else if db >= -50.0 {
+        (db + 50.0) / 10.0 * 0.075 + 0.075
+    } else if db >= -60.0 {
+        (db + 60.0) / 10.0 * 0.05 + 0.025
+    } else if db >= -114.0 {
+        (db + 150.0) / 90.0 * 0.025
+    } else {
+        0.0
+    }
+}
+
+unsafe extern "C" fn fader_changed(param: *mut c_void, db: f32) {
+    let sender = &*(param as *const broadcast::Sender<f32>);
+
+    // Sending only fails if there are no receivers
+    let _ = sender.send(db);
+}
+
+/// A volume fader, which maps slider positions (deflection) to dB values like the
+/// volume sliders of OBS Studio.
+///
+/// When attached to a source, the fader controls the volume of that source and is kept in sync
+/// if the volume is changed elsewhere. The fader is detached and destroyed once every clone
+/// of this struct is dropped.
+#[derive(Debug, Clone)]
+pub struct ObsFader {
+    fader: SmartPointerSendable<*mut obs_fader_t>,
+    fader_type: ObsFaderType,
+    sender: broadcast::Sender<f32>,
+    /// Keeps the attached source alive
+    attached_source: Arc<RwLock<Option<SmartPointerSendable<*mut libobs::obs_source_t>>>>,
+    runtime: ObsRuntime,
+}
+
+impl ObsFader {
+    pub fn new(fader_type: ObsFaderType, runtime: ObsRuntime) -> Result<Self, ObsError> {
+        let (sender, _) = broadcast::channel(16);
+        let callback_sender = sender.clone();
+
+        let (fader, callback_data) = run_with_obs!(runtime, (callback_sender), move || {
+            let fader = unsafe {
+                // Safety: We are in the runtime
+                libobs::obs_fader_create(fader_type as OsEnumType)
+            };
+
+            if fader.is_null() {
+                return Err(ObsError::NullPointer(None).in_function("obs_fader_create"));
+            }
+
+            let callback_data = Box::into_raw(Box::new(callback_sender));
+            unsafe {
+                // Safety: The fader was just created, the callback data is freed after the callback has been removed in the drop guard.
+                libobs::obs_fader_add_callback(
+                    fader,
+                    Some(fader_changed),
+                    callback_data as *mut c_void,
+                );
+            }
+
+            Ok((Sendable(fader), Sendable(callback_data)))
+        })??;
+
+        let drop_guard = _ObsFaderDropGuard {
+            fader: fader.clone(),
+            callback_data,
+            runtime: runtime.clone(),
+        };
+
+        Ok(Self {
+            fader: SmartPointerSendable::new(fader.0, Arc::new(drop_guard)),
+            fader_type,
+            sender,
+            attached_source: Arc::new(RwLock::new(None)),
+            runtime,
+        })
+    }
+
+    pub fn fader_type(&self) -> ObsFaderType {
+        self.fader_type
+    }
+
+    /// Attaches this fader to the given source, replacing a previously attached source.
+    pub fn attach_source<T: ObsSourceTrait>(&self, source: &T) -> Result<(), ObsError> {
+        let mut attached = self.attached_source.write().map_err(|_| {
+            ObsError::LockError("Failed to acquire write lock on attached source".into())
+        })?;
+
+        let fader = self.as_ptr();
+        let source_ptr = source.as_ptr();
+        let success = run_with_obs!(self.runtime, (fader, source_ptr), move |token| unsafe {
+            // Safety: Both pointers are valid because we are using smart pointers
+            libobs::obs_fader_attach_source(fader.get_ptr(token), source_ptr.get_ptr(token))
+        })?;
+
+        if !success {
+            return Err(ObsError::InvalidOperation(
+                "Failed to attach the fader to the source".into(),
+            ));
+        }
+
+        *attached = Some(source.as_ptr());
+        Ok(())
+    }
+
+    /// Detaches the fader from its source, the volume of the source is left as is.
+    pub fn detach_source(&self) -> Result<(), ObsError> {
+        let mut attached = self.attached_source.write().map_err(|_| {
+            ObsError::LockError("Failed to acquire write lock on attached source".into())
+        })?;
+
+        let fader = self.as_ptr();
+        run_with_obs!(self.runtime, (fader), move |token| unsafe {
+            // Safety: The pointer is valid because we are using a smart pointer
+            libobs::obs_fader_detach_source(fader.get_ptr(token));
+        })?;
+
+        *attached = None;
+        Ok(())
+    }
+
+    /// Subscribes to volume changes of this fader in dB, including changes made to the
+    /// volume of the attached source from somewhere else.
+    pub fn subscribe(&self) -> broadcast::Receiver<f32> {
+        self.sender.subscribe()
+    }
+
+    /// Sets the volume in dB. Returns `false` if the value had to be clamped.
+    pub fn set_db(&self, db: f32) -> Result<bool, ObsError> {
+        let fader = self.as_ptr();
+        run_with_obs!(self.runtime, (fader), move |token| unsafe {
+            // Safety: The pointer is valid because we are using a smart pointer
+            libobs::obs_fader_set_db(fader.get_ptr(token), db)
+        })
+    }
+
+    pub fn db(&self) -> Result<f32, ObsError> {
+        let fader = self.as_ptr();
+        run_with_obs!(self.runtime, (fader), move |token| unsafe {
+            // Safety: The pointer is valid because we are using a smart pointer
+            libobs::obs_fader_get_db(fader.get_ptr(token))
+        })
+    }
+
+    /// Sets the volume from a slider position, usually in the range `0.0` - `1.0`.
+    /// Returns `false` if the value had to be clamped.
+    pub fn set_deflection(&self, deflection: f32) -> Result<bool, ObsError> {
+        let fader = self.as_ptr();
+        run_with_obs!(self.runtime, (fader), move |token| unsafe {
+            // Safety: The pointer is valid because we are using a smart pointer
+            libobs::obs_fader_set_deflection(fader.get_ptr(token), deflection)
+        })
+    }
+
+    pub fn deflection(&self) -> Result<f32, ObsError> {
+        let fader = self.as_ptr();
+        run_with_obs!(self.runtime, (fader), move |token| unsafe {
+            // Safety: The pointer is valid because we are using a smart pointer
+            libobs::obs_fader_get_deflection(fader.get_ptr(token))
+        })
+    }
+
+    /// Sets the volume as a linear multiplier. Returns `false` if the value had to be clamped.
+    pub fn set_mul(&self, mul: f32) -> Result<bool, ObsError> {
+        let fader = self.as_ptr();
+        run_with_obs!(self.runtime, (fader), move |token| unsafe {
+            // Safety: The pointer is valid because we are using a smart pointer
+            libobs::obs_fader_set_mul(fader.get_ptr(token), mul)
+        })
+    }
+
+    pub fn mul(&self) -> Result<f32, ObsError> {
+        let fader = self.as_ptr();
+        run_with_obs!(self.runtime, (fader), move |token| unsafe {
+            // Safety: The pointer is valid because we are using a smart pointer
+            libobs::obs_fader_get_mul(fader.get_ptr(token))
+        })
+    }
+
+    pub fn as_ptr(&self) -> SmartPointerSendable<*mut obs_fader_t> {
+        self.fader.clone()
+    }
+}
+
+#[derive(Debug)]
+struct _ObsFaderDropGuard {
+    fader: Sendable<*mut obs_fader_t>,
+    callback_data: Sendable<*mut broadcast::Sender<f32>>,
+    runtime: ObsRuntime,
+}
+
+impl ObsDropGuard for _ObsFaderDropGuard {}
+
+impl_obs_drop!(_ObsFaderDropGuard, (fader, callback_data), move || unsafe {
+    // Safety: The fader is valid until it is destroyed here, the callback is removed
+    // before its data is freed, so it can't be accessed afterwards.
+    libobs::obs_fader_remove_callback(fader.0, Some(fader_changed), callback_data.0 as *mut c_void);
+    libobs::obs_fader_detach_source(fader.0);
+    libobs::obs_fader_destroy(fader.0);
+
+    drop(Box::from_raw(callback_data.0));
+});
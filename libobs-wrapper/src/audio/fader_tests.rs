@@ -0,0 +1,41 @@
+#[cfg(test)]
+mod tests {
+    use super::super::{db_to_mul, mul_to_db};
+    use crate::enums::ObsFaderType;
+
+    #[test]
+    fn test_mul_db_conversion() {
+        assert_eq!(mul_to_db(1.0), 0.0);
+        assert_eq!(mul_to_db(0.0), f32::NEG_INFINITY);
+        assert!((mul_to_db(0.5) + 6.0206).abs() < 1e-3);
+        assert!((db_to_mul(-6.0206) - 0.5).abs() < 1e-4);
+        assert_eq!(db_to_mul(f32::NEG_INFINITY), 0.0);
+    }
+
+    #[test]
+    fn test_fader_curves_round_trip() {
+        for fader_type in [ObsFaderType::Cubic, ObsFaderType::IEC, ObsFaderType::Log] {
+            assert_eq!(fader_type.deflection_to_db(1.0), 0.0);
+            assert_eq!(fader_type.deflection_to_db(0.0), f32::NEG_INFINITY);
+            assert_eq!(fader_type.db_to_deflection(0.0), 1.0);
+            assert_eq!(fader_type.db_to_deflection(f32::NEG_INFINITY), 0.0);
+
+            for deflection in [0.1, 0.25, 0.5, 0.75, 0.9] {
+                let db = fader_type.deflection_to_db(deflection);
+                let back = fader_type.db_to_deflection(db);
+                assert!(
+                    (back - deflection).abs() < 1e-4,
+                    "{:?}: {} -> {} dB -> {}",
+                    fader_type,
+                    deflection,
+                    db,
+                    back
+                );
+            }
+        }
+
+        // The IEC fader has fixed segments
+        assert_eq!(ObsFaderType::IEC.deflection_to_db(0.75), -9.0);
+        assert_eq!(ObsFaderType::IEC.deflection_to_db(0.5), -20.0);
+    }
+}
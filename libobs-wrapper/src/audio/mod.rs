@@ -0,0 +1,13 @@
+//! Audio level metering and volume control of sources.
+//!
+//! Use an [`ObsVolmeter`] to get the peak and magnitude levels of a source, for example
+//! to display VU meters in your UI, and an [`ObsFader`] to build volume sliders that
+//! behave like the ones in OBS Studio.
+
+mod fader;
+#[cfg(test)]
+mod fader_tests;
+pub use fader::*;
+
+mod volmeter;
+pub use volmeter::*;
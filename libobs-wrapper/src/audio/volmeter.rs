@@ -0,0 +1,193 @@
+use std::ffi::c_void;
+
+use libobs::obs_volmeter_t;
+use tokio::sync::broadcast;
+
+use crate::{
+    enums::{ObsFaderType, ObsPeakMeterType, OsEnumType},
+    impl_obs_drop, run_with_obs,
+    runtime::ObsRuntime,
+    sources::ObsSourceTrait,
+    unsafe_send::{Sendable, SmartPointerSendable},
+    utils::{ObsDropGuard, ObsError},
+};
+
+/// Audio levels of a source in dBFS, one value per audio channel.
+///
+/// Silence is reported as `f32::NEG_INFINITY`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ObsVolmeterLevels {
+    /// The RMS magnitude of each channel, after the volume of the source has been applied
+    pub magnitude: Vec<f32>,
+    /// The peak of each channel, after the volume of the source has been applied
+    pub peak: Vec<f32>,
+    /// The peak of each channel, before the volume of the source has been applied
+    pub input_peak: Vec<f32>,
+}
+
+struct VolmeterCallbackData {
+    volmeter: *mut obs_volmeter_t,
+    sender: broadcast::Sender<ObsVolmeterLevels>,
+}
+
+unsafe extern "C" fn volmeter_updated(
+    param: *mut c_void,
+    magnitude: *const f32,
+    peak: *const f32,
+    input_peak: *const f32,
+) {
+    let data = &*(param as *const VolmeterCallbackData);
+    if data.sender.receiver_count() == 0 {
+        return;
+    }
+
+    let channels = libobs::obs_volmeter_get_nr_channels(data.volmeter)
+        .clamp(0, libobs::MAX_AUDIO_CHANNELS as i32) as usize;
+
+    let levels = ObsVolmeterLevels {
+        magnitude: std::slice::from_raw_parts(magnitude, channels).to_vec(),
+        peak: std::slice::from_raw_parts(peak, channels).to_vec(),
+        input_peak: std::slice::from_raw_parts(input_peak, channels).to_vec(),
+    };
+
+    // Sending only fails if every receiver has been dropped in the meantime
+    let _ = data.sender.send(levels);
+}
+
+/// A volume meter attached to a source.
+///
+/// The levels are sent over a tokio broadcast channel every time the source outputs audio
+/// (usually every ~20ms). The meter is detached from the source and destroyed once
+/// every clone of this struct is dropped.
+#[derive(Debug, Clone)]
+pub struct ObsVolmeter {
+    volmeter: SmartPointerSendable<*mut obs_volmeter_t>,
+    sender: broadcast::Sender<ObsVolmeterLevels>,
+    runtime: ObsRuntime,
+}
+
+impl ObsVolmeter {
+    /// Creates a new volume meter and attaches it to the given source.
+    pub fn new<T: ObsSourceTrait>(source: &T, fader_type: ObsFaderType) -> Result<Self, ObsError> {
+        let runtime = source.runtime().clone();
+        let source_ptr = source.as_ptr();
+
+        let (sender, _) = broadcast::channel(16);
+        let callback_sender = sender.clone();
+
+        let (volmeter, callback_data) =
+            run_with_obs!(runtime, (source_ptr, callback_sender), move |token| {
+                let volmeter = unsafe {
+                    // Safety: We are in the runtime
+                    libobs::obs_volmeter_create(fader_type as OsEnumType)
+                };
+
+                if volmeter.is_null() {
+                    return Err(ObsError::NullPointer(None).in_function("obs_volmeter_create"));
+                }
+
+                let callback_data = Box::into_raw(Box::new(VolmeterCallbackData {
+                    volmeter,
+                    sender: callback_sender,
+                }));
+
+                unsafe {
+                    // Safety: The volmeter was just created, the source is valid because of the smart pointer
+                    // and the callback data is freed after the callback has been removed in the drop guard.
+                    libobs::obs_volmeter_add_callback(
+                        volmeter,
+                        Some(volmeter_updated),
+                        callback_data as *mut c_void,
+                    );
+
+                    if !libobs::obs_volmeter_attach_source(volmeter, source_ptr.get_ptr(token)) {
+                        libobs::obs_volmeter_remove_callback(
+                            volmeter,
+                            Some(volmeter_updated),
+                            callback_data as *mut c_void,
+                        );
+                        libobs::obs_volmeter_destroy(volmeter);
+                        drop(Box::from_raw(callback_data));
+
+                        return Err(ObsError::InvalidOperation(
+                            "Failed to attach the volume meter to the source".into(),
+                        ));
+                    }
+                }
+
+                Ok((Sendable(volmeter), Sendable(callback_data)))
+            })??;
+
+        let drop_guard = _ObsVolmeterDropGuard {
+            volmeter: volmeter.clone(),
+            callback_data,
+            _source: source_ptr,
+            runtime: runtime.clone(),
+        };
+
+        Ok(Self {
+            volmeter: SmartPointerSendable::new(volmeter.0, std::sync::Arc::new(drop_guard)),
+            sender,
+            runtime,
+        })
+    }
+
+    /// Subscribes to the level updates of this meter.
+    pub fn subscribe(&self) -> broadcast::Receiver<ObsVolmeterLevels> {
+        self.sender.subscribe()
+    }
+
+    /// Sets whether sample or true peaks are measured, defaults to `ObsPeakMeterType::SamplePeak`.
+    pub fn set_peak_meter_type(&self, peak_meter_type: ObsPeakMeterType) -> Result<(), ObsError> {
+        let volmeter = self.as_ptr();
+        run_with_obs!(self.runtime, (volmeter), move |token| unsafe {
+            // Safety: The pointer is valid because we are using a smart pointer
+            libobs::obs_volmeter_set_peak_meter_type(
+                volmeter.get_ptr(token),
+                peak_meter_type as OsEnumType,
+            );
+        })
+    }
+
+    /// Returns the number of audio channels the meter reports levels for.
+    pub fn channel_count(&self) -> Result<usize, ObsError> {
+        let volmeter = self.as_ptr();
+        run_with_obs!(self.runtime, (volmeter), move |token| unsafe {
+            // Safety: The pointer is valid because we are using a smart pointer
+            libobs::obs_volmeter_get_nr_channels(volmeter.get_ptr(token)).max(0) as usize
+        })
+    }
+
+    pub fn as_ptr(&self) -> SmartPointerSendable<*mut obs_volmeter_t> {
+        self.volmeter.clone()
+    }
+}
+
+#[derive(Debug)]
+struct _ObsVolmeterDropGuard {
+    volmeter: Sendable<*mut obs_volmeter_t>,
+    callback_data: Sendable<*mut VolmeterCallbackData>,
+    /// Keeps the source alive as long as the meter is attached to it
+    _source: SmartPointerSendable<*mut libobs::obs_source_t>,
+    runtime: ObsRuntime,
+}
+
+impl ObsDropGuard for _ObsVolmeterDropGuard {}
+
+impl_obs_drop!(
+    _ObsVolmeterDropGuard,
+    (volmeter, callback_data),
+    move || unsafe {
+        // Safety: The volmeter is valid until it is destroyed here, the callback is removed
+        // before its data is freed, so it can't be accessed afterwards.
+        libobs::obs_volmeter_remove_callback(
+            volmeter.0,
+            Some(volmeter_updated),
+            callback_data.0 as *mut c_void,
+        );
+        libobs::obs_volmeter_detach_source(volmeter.0);
+        libobs::obs_volmeter_destroy(volmeter.0);
+
+        drop(Box::from_raw(callback_data.0));
+    }
+);
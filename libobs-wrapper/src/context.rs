@@ -41,27 +41,48 @@ use std::{
     ffi::CStr,
     sync::{Arc, Mutex, RwLock},
     thread::ThreadId,
+    time::Duration,
 };
 
+use crate::studio::StudioModeController;
 #[cfg(target_os = "linux")]
 use crate::utils::initialization::PlatformType;
+use crate::view::ObsViewRef;
 use crate::{
-    data::{
-        object::ObsObjectTrait,
-        output::{ObsOutputTrait, ObsOutputTraitSealed, ObsReplayBufferOutputRef},
-    },
-    display::{ObsDisplayCreationData, ObsDisplayRef},
-};
-use crate::{
-    data::{output::ObsOutputRef, video::ObsVideoInfo, ObsData},
+    data::{audio::ObsAudioInfo, output::ObsOutputRef, video::ObsVideoInfo, ObsData},
     enums::{ObsLogLevel, ObsResetVideoStatus},
     logger::LOGGER,
     run_with_obs,
     runtime::ObsRuntime,
     scenes::ObsSceneRef,
-    sources::{ObsFilterRef, ObsSourceBuilder},
+    signals::ObsEventBus,
+    sources::{
+        register_custom_source, CustomSourceDef, ObsBufferSource, ObsFilterRef, ObsSourceBuilder,
+        ObsSourceRef,
+    },
     unsafe_send::Sendable,
-    utils::{FilterInfo, ObsError, ObsModules, ObsString, OutputInfo, StartupInfo},
+    utils::{
+        enumerate_encoders, enumerate_outputs, enumerate_services, idle::ObsIdleVideo,
+        settings_to_data, FilterInfo, ObsEncoderSummary, ObsError, ObsLeakEntry, ObsLeakKind,
+        ObsLeakReport, ObsModules, ObsOutputSummary, ObsProfile, ObsServiceSummary, ObsString,
+        ObsVersionInfo, OutputInfo, StartupInfo,
+    },
+};
+use crate::{
+    data::{
+        object::ObsObjectTrait,
+        output::{stop_output, ObsOutputTrait, ObsOutputTraitSealed, ObsReplayBufferOutputRef},
+    },
+    display::{ObsDisplayCreationData, ObsDisplayRef},
+    encoders::video::ObsVideoEncoder,
+    graphics::{
+        GraphicsContext, ObsFrameHash, ObsMainRenderCallback, ObsSourceThumbnailer,
+        ObsThumbnailConfig, ObsTickCallback,
+    },
+    stats::{
+        ObsAvSyncProbe, ObsAvSyncProbeConfig, ObsEncoderMitigation, ObsEncoderWatchdog,
+        ObsFrameDropAlarm, ObsFrameStats, ObsStatsMonitor,
+    },
 };
 use getters0::Getters;
 use libobs::{audio_output, video_output};
@@ -108,6 +129,10 @@ pub struct ObsContext {
     #[skip_getter]
     _obs_modules: Arc<ObsModules>,
 
+    /// Lowers the frame rate while nothing uses the video, see `StartupInfo::set_idle_frame_rate`
+    #[skip_getter]
+    idle: ObsIdleVideo,
+
     /// This struct must be the last element which makes sure
     /// that everything else has been freed already before the runtime
     /// shuts down
@@ -191,8 +216,17 @@ impl ObsContext {
             None
         };
 
+        let idle = ObsIdleVideo::default();
+        if let Some((fps_num, fps_den)) = info.idle_frame_rate {
+            idle.enable(&info.obs_video_info, fps_num, fps_den);
+            if let Err(e) = idle.try_idle(&runtime, false) {
+                log::warn!("Failed to lower the frame rate on startup: {:?}", e);
+            }
+        }
+
         Ok(Self {
             _obs_modules: Arc::new(obs_modules),
+            idle,
             displays: Default::default(),
             outputs: Default::default(),
             scenes: Default::default(),
@@ -227,6 +261,63 @@ impl ObsContext {
         }
     }
 
+    /// Returns the version of the loaded libobs together with the features it supports,
+    /// e.g. whether AV1 encoders or a virtual camera are available.
+    pub fn version(&self) -> Result<ObsVersionInfo, ObsError> {
+        run_with_obs!(self.runtime, move || unsafe {
+            // Safety: We are in the runtime
+            ObsVersionInfo::collect()
+        })
+    }
+
+    /// Returns the global locale of libobs.
+    pub fn locale(&self) -> Result<String, ObsError> {
+        run_with_obs!(self.runtime, move || {
+            let locale = unsafe {
+                // Safety: We are in the runtime, the locale is a globally allocated string
+                libobs::obs_get_locale()
+            };
+
+            if locale.is_null() {
+                return String::new();
+            }
+
+            unsafe {
+                // Safety: We did a null check
+                CStr::from_ptr(locale).to_string_lossy().into_owned()
+            }
+        })
+    }
+
+    /// Changes the global locale of libobs. Modules are notified about the new locale,
+    /// properties fetched afterwards are translated with it.
+    ///
+    /// libobs has no locale per source, the text and browser sources of OBS always use this one.
+    /// Overlays that need their own time zone can set it on the overlay itself, see
+    /// `TimecodeOverlayBuilder::utc_offset` in `libobs-simple`.
+    pub fn set_locale(&self, locale: &str) -> Result<(), ObsError> {
+        let locale_str = ObsString::new(locale);
+        run_with_obs!(self.runtime, (locale_str), move || unsafe {
+            // Safety: We are in the runtime and the string is kept alive during the call
+            libobs::obs_set_locale(locale_str.as_ptr().0);
+        })?;
+
+        self.startup_info
+            .write()
+            .map_err(|_| {
+                ObsError::LockError("Failed to acquire write lock on startup info".into())
+            })?
+            .locale = locale.to_string();
+
+        Ok(())
+    }
+
+    /// Returns the loaded modules. Use this to get the resolved plugin paths
+    /// and the diagnostics of modules that are missing data or failed to load.
+    pub fn modules(&self) -> &ObsModules {
+        &self._obs_modules
+    }
+
     pub fn log(&self, level: ObsLogLevel, msg: &str) {
         let mut log = LOGGER.lock().unwrap();
         log.log(level, msg.to_string());
@@ -247,6 +338,8 @@ impl ObsContext {
     /// without destroying the entire OBS context. Trying
     /// so will result in an error.
     pub fn reset_video(&mut self, ovi: ObsVideoInfo) -> Result<(), ObsError> {
+        ovi.validate()?;
+
         // You cannot change the graphics module without
         // completely destroying the entire OBS context.
         if self
@@ -262,17 +355,7 @@ impl ObsContext {
             return Err(ObsError::ResetVideoFailureGraphicsModule);
         }
 
-        let has_active_outputs = {
-            self.outputs
-                .read()
-                .map_err(|_| {
-                    ObsError::LockError("Failed to acquire read lock on outputs".to_string())
-                })?
-                .iter()
-                .any(|output| output.is_active().unwrap_or_default())
-        };
-
-        if has_active_outputs {
+        if self.has_active_outputs()? {
             return Err(ObsError::ResetVideoFailureOutputActive);
         }
 
@@ -296,6 +379,14 @@ impl ObsContext {
         };
 
         if reset_video_status == ObsResetVideoStatus::Success {
+            self.idle.video_reset(&ovi);
+            if let Err(e) = self.idle.try_idle(&self.runtime, self.has_outputs()?) {
+                log::warn!(
+                    "Failed to lower the frame rate after resetting the video: {:?}",
+                    e
+                );
+            }
+
             self.startup_info
                 .write()
                 .map_err(|_| {
@@ -309,6 +400,206 @@ impl ObsContext {
         }
     }
 
+    /// Resets the OBS audio context, e.g. to change the sample rate
+    /// or the speaker layout after startup.
+    ///
+    /// Every output has to be stopped before the audio can be reset,
+    /// otherwise `ObsError::ResetAudioFailureOutputActive` is returned.
+    pub fn reset_audio(&mut self, oai: ObsAudioInfo) -> Result<(), ObsError> {
+        if self.has_active_outputs()? {
+            return Err(ObsError::ResetAudioFailureOutputActive);
+        }
+
+        let audio_ptr = oai.as_ptr();
+        let success = run_with_obs!(self.runtime, (audio_ptr), move || unsafe {
+            // Safety: OAI is still in scope, so the pointer is valid as well.
+            libobs::obs_reset_audio2(audio_ptr.0)
+        })?;
+
+        if !success {
+            return Err(ObsError::ResetAudioFailure);
+        }
+
+        self.startup_info
+            .write()
+            .map_err(|_| {
+                ObsError::LockError("Failed to acquire write lock on startup info".to_string())
+            })?
+            .obs_audio_info = oai;
+
+        Ok(())
+    }
+
+    /// Applies the video and audio settings of the profile, see [`ObsProfile`].
+    ///
+    /// The video and audio are only reset if their settings changed, so every output has
+    /// to be stopped if they do. The encoders and output settings of the profile are used
+    /// by `Self::profile_output`.
+    ///
+    /// The profile is either applied as a whole or not at all: if the audio can not be reset,
+    /// the video is reset to its previous settings again.
+    pub fn apply_profile(&mut self, profile: &ObsProfile) -> Result<(), ObsError> {
+        let oai = profile.audio.to_audio_info()?;
+        let (ovi, previous_ovi, audio_changed) = {
+            let startup_info = self.startup_info.read().map_err(|_| {
+                ObsError::LockError("Failed to acquire read lock on startup info".to_string())
+            })?;
+
+            let current_ovi = &startup_info.obs_video_info;
+            let (ovi, previous_ovi) = if profile.video.matches(current_ovi) {
+                (None, None)
+            } else {
+                let ovi = profile.video.to_video_info(current_ovi)?;
+                ovi.validate()?;
+
+                let previous_ovi = current_ovi
+                    .with_frame_rate(current_ovi.get_fps_num(), current_ovi.get_fps_den());
+                (Some(ovi), Some(previous_ovi))
+            };
+
+            let current_oai = &startup_info.obs_audio_info;
+            let audio_changed = current_oai.samples_per_second() != oai.samples_per_second()
+                || current_oai.speakers() != oai.speakers()
+                || current_oai.max_buffering_ms() != oai.max_buffering_ms()
+                || current_oai.fixed_buffering() != oai.fixed_buffering();
+
+            (ovi, previous_ovi, audio_changed)
+        };
+
+        if (ovi.is_some() || audio_changed) && self.has_active_outputs()? {
+            return Err(if ovi.is_some() {
+                ObsError::ResetVideoFailureOutputActive
+            } else {
+                ObsError::ResetAudioFailureOutputActive
+            });
+        }
+
+        if let Some(ovi) = ovi {
+            self.reset_video(ovi)?;
+        }
+
+        if audio_changed {
+            if let Err(e) = self.reset_audio(oai) {
+                if let Some(previous_ovi) = previous_ovi {
+                    if let Err(restore_error) = self.reset_video(previous_ovi) {
+                        log::error!(
+                            "Failed to restore the video after applying profile {} failed: {:?}",
+                            profile.name,
+                            restore_error
+                        );
+                    }
+                }
+
+                return Err(e);
+            }
+        }
+
+        log::debug!("Applied profile {}", profile.name);
+        Ok(())
+    }
+
+    /// Creates an output with the settings of the profile and sets its video and audio
+    /// encoders, if the profile has any. The audio encoder uses the first mixer.
+    pub fn profile_output(
+        &mut self,
+        profile: &ObsProfile,
+        output_id: &str,
+        name: &str,
+    ) -> Result<ObsOutputRef, ObsError> {
+        let settings = settings_to_data(&profile.output_settings, &self.runtime)?;
+        let mut output = self.output(OutputInfo::new(output_id, name, settings, None))?;
+
+        if let Some(encoder) = &profile.video_encoder {
+            let info = encoder.to_info(format!("{}_video_encoder", name), &self.runtime)?;
+            output.create_and_set_video_encoder(info)?;
+        }
+
+        if let Some(encoder) = &profile.audio_encoder {
+            let info = encoder.to_info(format!("{}_audio_encoder", name), &self.runtime)?;
+            output.create_and_set_audio_encoder(info, 0)?;
+        }
+
+        Ok(output)
+    }
+
+    /// Checks whether the audio encoder with the given id (e.g. `ffmpeg_aac`) can encode
+    /// the speaker layout of the current audio settings, see `ObsAudioInfo::validate_codec`.
+    pub fn validate_audio_encoder(&self, encoder_id: &str) -> Result<(), ObsError> {
+        let id = ObsString::new(encoder_id);
+        let codec = run_with_obs!(self.runtime, (id), move || unsafe {
+            // Safety: The id is valid for the duration of this call and the codec is a static string of the encoder
+            let codec = libobs::obs_get_encoder_codec(id.as_ptr().0);
+            if codec.is_null() {
+                None
+            } else {
+                Some(CStr::from_ptr(codec).to_string_lossy().into_owned())
+            }
+        })?
+        .ok_or_else(|| ObsError::InvalidOperation(format!("Unknown encoder {}", encoder_id)))?;
+
+        self.startup_info
+            .read()
+            .map_err(|_| {
+                ObsError::LockError("Failed to acquire read lock on startup info".to_string())
+            })?
+            .obs_audio_info
+            .validate_codec(&codec)
+    }
+
+    /// Checks whether the video encoder with the given id (e.g. `obs_x264`) can encode
+    /// the output format of the current video settings, see `ObsVideoInfo::validate_codec`.
+    pub fn validate_video_encoder(&self, encoder_id: &str) -> Result<(), ObsError> {
+        let id = ObsString::new(encoder_id);
+        let codec = run_with_obs!(self.runtime, (id), move || unsafe {
+            // Safety: The id is valid for the duration of this call and the codec is a static string of the encoder
+            let codec = libobs::obs_get_encoder_codec(id.as_ptr().0);
+            if codec.is_null() {
+                None
+            } else {
+                Some(CStr::from_ptr(codec).to_string_lossy().into_owned())
+            }
+        })?
+        .ok_or_else(|| ObsError::InvalidOperation(format!("Unknown encoder {}", encoder_id)))?;
+
+        self.startup_info
+            .read()
+            .map_err(|_| {
+                ObsError::LockError("Failed to acquire read lock on startup info".to_string())
+            })?
+            .obs_video_info
+            .validate_codec(&codec)
+    }
+
+    /// Returns true if any output of this context, or any output created
+    /// directly through libobs, is currently active.
+    fn has_active_outputs(&self) -> Result<bool, ObsError> {
+        let has_active_outputs = self
+            .outputs
+            .read()
+            .map_err(|_| ObsError::LockError("Failed to acquire read lock on outputs".to_string()))?
+            .iter()
+            .any(|output| output.is_active().unwrap_or_default());
+
+        if has_active_outputs {
+            return Ok(true);
+        }
+
+        run_with_obs!(self.runtime, move || unsafe {
+            // Safety: We are in the runtime, this just reads the global state of the video output
+            libobs::obs_video_active()
+        })
+    }
+
+    /// Returns true if this context has created any output. Outputs keep the video
+    /// they were created with, so the video can't be lowered to the idle frame rate anymore.
+    fn has_outputs(&self) -> Result<bool, ObsError> {
+        Ok(!self
+            .outputs
+            .read()
+            .map_err(|_| ObsError::LockError("Failed to acquire read lock on outputs".to_string()))?
+            .is_empty())
+    }
+
     /// Returns a pointer to the video output.
     ///
     /// # Safety
@@ -341,6 +632,9 @@ impl ObsContext {
         &mut self,
         info: OutputInfo,
     ) -> Result<ObsReplayBufferOutputRef, ObsError> {
+        self.ensure_unique_output_name(&info.name)?;
+        // The output keeps the video it is created with
+        self.idle.wake(&self.runtime)?;
         let output = ObsReplayBufferOutputRef::new(info, self.runtime.clone());
 
         match output {
@@ -360,6 +654,9 @@ impl ObsContext {
     }
 
     pub fn output(&mut self, info: OutputInfo) -> Result<ObsOutputRef, ObsError> {
+        self.ensure_unique_output_name(&info.name)?;
+        // The output keeps the video it is created with
+        self.idle.wake(&self.runtime)?;
         let output = ObsOutputRef::new(info, self.runtime.clone());
 
         match output {
@@ -379,6 +676,11 @@ impl ObsContext {
     }
 
     pub fn obs_filter(&mut self, info: FilterInfo) -> Result<ObsFilterRef, ObsError> {
+        let name = info.name.to_string();
+        if self.get_filter(&name)?.is_some() {
+            return Err(ObsError::NameAlreadyInUse(name));
+        }
+
         let filter = ObsFilterRef::new(
             info.id,
             info.name,
@@ -499,8 +801,11 @@ impl ObsContext {
             }
         }
 
+        // The display has to render at the real frame rate
+        self.idle.wake(&self.runtime)?;
         let display = ObsDisplayRef::new(data, self.runtime.clone())
             .map_err(|e| ObsError::DisplayCreationError(e.to_string()))?;
+        self.idle.display_added();
 
         let id = display.id();
         self.displays
@@ -518,13 +823,21 @@ impl ObsContext {
     }
 
     pub fn remove_display_by_id(&mut self, id: usize) -> Result<(), ObsError> {
-        self.displays
+        let removed = self
+            .displays
             .write()
             .map_err(|_| {
                 ObsError::LockError("Failed to acquire write lock on displays".to_string())
             })?
             .remove(&id);
 
+        if let Some(display) = removed {
+            // The display has to be destroyed before the frame rate can be lowered
+            drop(display);
+            self.idle.display_removed();
+            self.idle.try_idle(&self.runtime, self.has_outputs()?)?;
+        }
+
         Ok(())
     }
 
@@ -556,6 +869,15 @@ impl ObsContext {
         Ok(o)
     }
 
+    fn ensure_unique_output_name(&mut self, name: &ObsString) -> Result<(), ObsError> {
+        let name = name.to_string();
+        if self.get_output(&name)?.is_some() {
+            return Err(ObsError::NameAlreadyInUse(name));
+        }
+
+        Ok(())
+    }
+
     pub fn update_output(&mut self, name: &str, settings: ObsData) -> Result<(), ObsError> {
         match self
             .outputs
@@ -600,7 +922,12 @@ impl ObsContext {
         name: T,
         channel: Option<u32>,
     ) -> Result<ObsSceneRef, ObsError> {
-        let scene = ObsSceneRef::new(name.into(), self.runtime.clone())?;
+        let name: ObsString = name.into();
+        if self.get_scene(&name.to_string())?.is_some() {
+            return Err(ObsError::NameAlreadyInUse(name.to_string()));
+        }
+
+        let scene = ObsSceneRef::new(name, self.runtime.clone())?;
 
         let tmp = scene.clone();
         self.scenes
@@ -633,6 +960,351 @@ impl ObsContext {
         T::new(name.into(), self.runtime.clone())
     }
 
+    /// Registers a source type that is implemented in Rust, see [`CustomSourceDef`].
+    ///
+    /// Sources of this type are created with `T::ID` as id. Registering the same id twice fails
+    /// with `ObsError::NameAlreadyInUse`, including ids of source types registered by plugins.
+    pub fn register_source<T: CustomSourceDef>(&self) -> Result<(), ObsError> {
+        register_custom_source::<T>(&self.runtime)
+    }
+
+    /// Creates a source that outputs video frames and audio pushed from Rust,
+    /// see [`ObsBufferSource`].
+    pub fn buffer_source<K: Into<ObsString> + Send + Sync>(
+        &self,
+        name: K,
+    ) -> Result<ObsBufferSource, ObsError> {
+        ObsBufferSource::new(name, self.runtime.clone())
+    }
+
+    /// Returns the source with the given name, including sources that have not been created by
+    /// this wrapper, e.g. by a loaded scene collection. See [`ObsSourceRef::new_from_existing`].
+    pub fn get_source_by_name(&self, name: &str) -> Result<Option<ObsSourceRef>, ObsError> {
+        ObsSourceRef::new_from_existing(name, self.runtime.clone())
+    }
+
+    /// Returns every public source that exists in OBS, see [`ObsSourceRef::existing_sources`].
+    pub fn sources(&self) -> Result<Vec<ObsSourceRef>, ObsError> {
+        ObsSourceRef::existing_sources(self.runtime.clone())
+    }
+
+    /// Returns every output that exists in OBS, including outputs that have not been created by this wrapper.
+    pub fn enumerate_outputs(&self) -> Result<Vec<ObsOutputSummary>, ObsError> {
+        run_with_obs!(self.runtime, move || unsafe {
+            // Safety: We are in the runtime
+            enumerate_outputs()
+        })
+    }
+
+    /// Returns every encoder that exists in OBS, including encoders that have not been created by this wrapper.
+    pub fn enumerate_encoders(&self) -> Result<Vec<ObsEncoderSummary>, ObsError> {
+        run_with_obs!(self.runtime, move || unsafe {
+            // Safety: We are in the runtime
+            enumerate_encoders()
+        })
+    }
+
+    /// Returns every service that exists in OBS.
+    pub fn enumerate_services(&self) -> Result<Vec<ObsServiceSummary>, ObsError> {
+        run_with_obs!(self.runtime, move || unsafe {
+            // Safety: We are in the runtime
+            enumerate_services()
+        })
+    }
+
+    /// Lists the scenes and filters that are only kept alive by this context and the sources
+    /// that are kept for a scene they have been removed from outside of the wrapper.
+    ///
+    /// This is meant as a diagnostic to find scene and source lifetime bugs, see [`ObsLeakReport`].
+    pub fn leak_report(&self) -> Result<ObsLeakReport, ObsError> {
+        let mut report = ObsLeakReport::default();
+
+        let scenes = self
+            .scenes
+            .read()
+            .map_err(|_| ObsError::LockError("Failed to acquire read lock on scenes".to_string()))?
+            .clone();
+
+        for scene in scenes.iter() {
+            let scene_name = scene.name().to_string();
+            // One handle is held by the context and one by the `scenes` clone above
+            if scene.handle_count() <= 2 && !scene.is_showing()? {
+                report.entries.push(ObsLeakEntry {
+                    name: scene_name.clone(),
+                    kind: ObsLeakKind::UnusedScene,
+                });
+            }
+
+            for source in scene.detached_sources()? {
+                report.entries.push(ObsLeakEntry {
+                    name: source,
+                    kind: ObsLeakKind::DetachedSource {
+                        scene: scene_name.clone(),
+                    },
+                });
+            }
+        }
+
+        let filters = self.filters.read().map_err(|_| {
+            ObsError::LockError("Failed to acquire read lock on filters".to_string())
+        })?;
+
+        for filter in filters.iter() {
+            if filter.handle_count() == 1 {
+                report.entries.push(ObsLeakEntry {
+                    name: filter.name().to_string(),
+                    kind: ObsLeakKind::UnusedFilter,
+                });
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Tears down this context in a fixed order instead of relying on the drop order of its fields:
+    ///
+    /// 1. Active outputs are stopped, waiting for their stop signal
+    /// 2. Displays are released
+    /// 3. Every output channel is cleared, so no scene is shown anymore
+    /// 4. Filters, scenes and outputs are dropped, which disconnects their signals
+    /// 5. OBS shuts down once the last handle to the runtime is gone
+    ///
+    /// Every step runs even if a previous one failed, all errors are returned in the order they occurred.
+    /// OBS can only shut down once no clone of this context and no source, output, etc. is alive anymore,
+    /// so if other clones of the context still exist, nothing is torn down and an `InvalidOperation`
+    /// error is returned. The other clones keep working in that case.
+    pub fn shutdown(self) -> Result<(), Vec<ObsError>> {
+        if Arc::strong_count(&self.startup_info) > 1 {
+            return Err(vec![ObsError::InvalidOperation(
+                "Other clones of the context are still alive, drop them before shutting down"
+                    .to_string(),
+            )]);
+        }
+
+        let mut errors = Vec::new();
+        self.idle.disable();
+
+        let outputs = self
+            .outputs
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone();
+
+        for output in outputs.iter() {
+            match output.is_active() {
+                Ok(true) => {
+                    if let Err(e) = stop_output(output.as_ref().as_ref()) {
+                        errors.push(e);
+                    }
+                }
+                Ok(false) => {}
+                Err(e) => errors.push(e),
+            }
+        }
+        drop(outputs);
+
+        let displays = std::mem::take(
+            &mut *self
+                .displays
+                .write()
+                .unwrap_or_else(|poisoned| poisoned.into_inner()),
+        );
+        drop(displays);
+
+        let cleared = run_with_obs!(self.runtime, move || unsafe {
+            // Safety: We are in the runtime, clearing a channel just releases the source shown on it
+            for i in 0..libobs::MAX_CHANNELS {
+                libobs::obs_set_output_source(i, std::ptr::null_mut());
+            }
+        });
+        if let Err(e) = cleared {
+            errors.push(e);
+        }
+
+        let filters = std::mem::take(
+            &mut *self
+                .filters
+                .write()
+                .unwrap_or_else(|poisoned| poisoned.into_inner()),
+        );
+        drop(filters);
+
+        let scenes = std::mem::take(
+            &mut *self
+                .scenes
+                .write()
+                .unwrap_or_else(|poisoned| poisoned.into_inner()),
+        );
+        drop(scenes);
+
+        let outputs = std::mem::take(
+            &mut *self
+                .outputs
+                .write()
+                .unwrap_or_else(|poisoned| poisoned.into_inner()),
+        );
+        drop(outputs);
+
+        drop(self);
+
+        #[cfg(feature = "debug_pointers")]
+        crate::unsafe_send::debug::report_live_pointers();
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Enables studio mode on the given output channel, see [`StudioModeController`].
+    pub fn studio_mode<T: Into<ObsString> + Send + Sync>(
+        &self,
+        transition_id: T,
+        channel: u32,
+    ) -> Result<StudioModeController, ObsError> {
+        StudioModeController::new(transition_id, channel, self.runtime.clone())
+    }
+
+    /// Creates a new view with its own output channels, see [`ObsViewRef`].
+    ///
+    /// The view is removed once it is dropped and no output is using it anymore.
+    /// The video keeps its real frame rate while the view is alive, see `StartupInfo::set_idle_frame_rate`.
+    pub fn view(&self) -> Result<ObsViewRef, ObsError> {
+        self.idle.wake(&self.runtime)?;
+        let view = ObsViewRef::new(self.runtime.clone())?;
+        self.idle.view_added(view.liveness());
+
+        Ok(view)
+    }
+
+    /// Restores the frame rate of the video if it has been lowered, see
+    /// `StartupInfo::set_idle_frame_rate`. Displays, outputs and views do this automatically,
+    /// call this before rendering something else, e.g. a screenshot of a source.
+    pub fn wake_rendering(&self) -> Result<(), ObsError> {
+        self.idle.wake(&self.runtime)
+    }
+
+    /// Lowers the frame rate of the video if this context has no display, output or view,
+    /// see `StartupInfo::set_idle_frame_rate`. Returns whether the video is idle afterwards,
+    /// which is always `false` if no idle frame rate has been set.
+    pub fn idle_rendering(&self) -> Result<bool, ObsError> {
+        self.idle.try_idle(&self.runtime, self.has_outputs()?)
+    }
+
+    /// Returns whether the video currently runs at the idle frame rate.
+    pub fn is_rendering_idle(&self) -> bool {
+        self.idle.is_idle()
+    }
+
+    /// Renders the last frame of the main output to a staging surface and returns its perceptual hash,
+    /// e.g. to check in tests that the expected scene is rendered with `ObsFrameHash::assert_similar`.
+    ///
+    /// Fails if no frame has been rendered yet.
+    pub fn hash_current_frame(&self) -> Result<ObsFrameHash, ObsError> {
+        ObsFrameHash::hash_main_texture(&self.runtime)
+    }
+
+    /// Starts an [`ObsSourceThumbnailer`], which renders the sources added to it offscreen
+    /// at a small size, e.g. for source previews without a display per source.
+    pub fn source_thumbnailer(
+        &self,
+        config: ObsThumbnailConfig,
+    ) -> Result<ObsSourceThumbnailer, ObsError> {
+        ObsSourceThumbnailer::new(self.runtime.clone(), config)
+    }
+
+    /// Returns a snapshot of the global frame counters (rendered, lagged, encoded and skipped frames).
+    pub fn frame_stats(&self) -> Result<ObsFrameStats, ObsError> {
+        ObsFrameStats::read(&self.runtime)
+    }
+
+    /// Starts an [`ObsAvSyncProbe`], which measures the offset between audio and video
+    /// with a flashing and beeping source.
+    pub fn av_sync_probe<K: Into<ObsString> + Send + Sync>(
+        &self,
+        name: K,
+        config: ObsAvSyncProbeConfig,
+    ) -> Result<ObsAvSyncProbe, ObsError> {
+        // Measuring at the idle frame rate would make the flashes land on fewer frames
+        self.idle.wake(&self.runtime)?;
+        ObsAvSyncProbe::new(name, config, self.runtime.clone())
+    }
+
+    /// Creates an [`ObsEventBus`], which combines the events of every source, scene and output of this context
+    /// into a single stream. Outputs that are created afterwards have to be added with `ObsEventBus::watch_output`.
+    pub fn event_bus(&self) -> Result<ObsEventBus, ObsError> {
+        let bus = ObsEventBus::new(self.runtime.clone())?;
+        let outputs = self
+            .outputs
+            .read()
+            .map_err(|_| ObsError::LockError("Failed to acquire read lock on outputs".to_string()))?
+            .clone();
+
+        for output in outputs.iter() {
+            bus.watch_output(output.as_ref().as_ref())?;
+        }
+
+        Ok(bus)
+    }
+
+    /// Runs the closure on every tick of the graphics thread with the seconds elapsed since the last tick,
+    /// until the returned guard is dropped. Use this for per-frame logic like animations or watchdogs.
+    pub fn add_tick_callback<F>(&self, callback: F) -> Result<ObsTickCallback, ObsError>
+    where
+        F: FnMut(f32) + Send + 'static,
+    {
+        ObsTickCallback::new(self.runtime.clone(), callback)
+    }
+
+    /// Runs the closure every frame after the scene has been rendered to the main texture, until the
+    /// returned guard is dropped. Everything drawn with the `GraphicsContext` is part of the encoded video.
+    pub fn add_main_render_callback<F>(
+        &self,
+        callback: F,
+    ) -> Result<ObsMainRenderCallback, ObsError>
+    where
+        F: FnMut(&mut GraphicsContext) + Send + 'static,
+    {
+        ObsMainRenderCallback::new(self.runtime.clone(), callback)
+    }
+
+    /// Returns a snapshot of the profiler of libobs, with the timings of the render loop,
+    /// the encoders and the ticks of every source.
+    #[cfg(feature = "profiler")]
+    pub fn profiler_snapshot(&self) -> Result<crate::stats::ObsProfilerSnapshot, ObsError> {
+        crate::stats::ObsProfilerSnapshot::take(&self.runtime)
+    }
+
+    /// Starts an `ObsStatsMonitor` that checks the given alarms every `poll_interval`.
+    /// Subscribe to the returned monitor to get notified when too many frames are dropped.
+    pub fn stats_monitor(
+        &self,
+        alarms: Vec<ObsFrameDropAlarm>,
+        poll_interval: Duration,
+    ) -> Result<ObsStatsMonitor, ObsError> {
+        ObsStatsMonitor::new(self.runtime.clone(), alarms, poll_interval)
+    }
+
+    /// Starts an `ObsEncoderWatchdog` that applies the given mitigations to `encoder` in order
+    /// whenever `alarm` is exceeded, e.g. lowering the bitrate when more than 5% of the frames
+    /// are skipped over 10 seconds.
+    pub fn encoder_watchdog(
+        &self,
+        encoder: Arc<ObsVideoEncoder>,
+        alarm: ObsFrameDropAlarm,
+        mitigations: Vec<ObsEncoderMitigation>,
+        poll_interval: Duration,
+    ) -> Result<ObsEncoderWatchdog, ObsError> {
+        ObsEncoderWatchdog::new(
+            self.runtime.clone(),
+            encoder,
+            alarm,
+            mitigations,
+            poll_interval,
+        )
+    }
+
     /// Gets the current video frame time in nanoseconds.
     ///
     /// This returns the timestamp from OBS's internal monotonic clock (`os_gettime_ns()`),
@@ -1,8 +1,9 @@
 use arboard::Clipboard;
 use dialog::{Choice, DialogBox};
 
-use super::ObsCrashHandler;
+use super::{ObsCrashHandler, ObsCrashReport};
 
+#[derive(Debug)]
 pub struct DialogCrashHandler {
     _private: (),
 }
@@ -20,8 +21,9 @@ impl DialogCrashHandler {
 }
 
 impl ObsCrashHandler for DialogCrashHandler {
-    fn handle_crash(&self, message: String) {
-        eprintln!("OBS crashed: {}", message);
+    fn handle_crash(&self, report: ObsCrashReport) {
+        let message = report.to_string();
+        eprintln!("{}", message);
         let res =
             dialog::Question::new("OBS has crashed. Do you want to copy the error to clipboard?")
                 .title("OBS Crash Handler")
@@ -0,0 +1,46 @@
+use std::{fs, path::PathBuf, time::SystemTime};
+
+use super::{ObsCrashHandler, ObsCrashReport};
+
+/// Writes every crash report to a new file in the given directory (`obs-crash-<unix timestamp>.txt`)
+/// and prints it to stderr, so crashes of end users can be diagnosed later.
+#[derive(Debug)]
+pub struct FileCrashHandler {
+    directory: PathBuf,
+}
+
+impl FileCrashHandler {
+    pub fn new(directory: impl Into<PathBuf>) -> Self {
+        Self {
+            directory: directory.into(),
+        }
+    }
+
+    pub fn directory(&self) -> &PathBuf {
+        &self.directory
+    }
+}
+
+impl ObsCrashHandler for FileCrashHandler {
+    fn handle_crash(&self, report: ObsCrashReport) {
+        let report_text = report.to_string();
+        eprintln!("{}", report_text);
+
+        let timestamp = report
+            .timestamp
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        let path = self.directory.join(format!("obs-crash-{}.txt", timestamp));
+
+        if let Err(e) = fs::create_dir_all(&self.directory) {
+            eprintln!("Failed to create crash report directory: {e:?}");
+            return;
+        }
+
+        match fs::write(&path, report_text) {
+            Ok(()) => eprintln!("Crash report written to {}", path.display()),
+            Err(e) => eprintln!("Failed to write crash report: {e:?}"),
+        }
+    }
+}
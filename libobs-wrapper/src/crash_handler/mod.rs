@@ -2,13 +2,26 @@
 //! By default this will handle crashes just by printing them out to console, if the `dialog-crash-handler` feature is disabled.
 //! If you want to implement your own crash handler, make sure that you do the least amount of work possible and access as few global variables as you can,
 //! as it is quite unstable if libobs has crashed.
-use std::{ffi::c_void, sync::Mutex};
+use std::{ffi::c_void, fmt::Debug, sync::Mutex};
 
 use lazy_static::lazy_static;
 
 #[cfg(feature = "dialog_crash_handler")]
 pub mod dialog;
 
+mod file;
+pub use file::FileCrashHandler;
+
+pub(crate) mod report;
+#[cfg(test)]
+mod report_tests;
+pub use report::{ObsCrashReport, ObsCrashReportOutput};
+
+pub(crate) mod session;
+#[cfg(test)]
+mod session_tests;
+pub use session::ObsCrashedSession;
+
 /// Trait for handling OBS crashes.
 /// This is called whenever OBS encounters a fatal error and crashes.
 /// Implementors can define custom behavior for crash handling,
@@ -16,12 +29,18 @@ pub mod dialog;
 ///
 /// **MAKE SURE** that the `handle_crash` function does the least amount of work possible,
 /// as it is called in a crash context where many resources may be unavailable.
-pub trait ObsCrashHandler: Send {
-    /// Handles an OBS crash with the given message.
+/// Use `StartupInfo::set_crash_handler` to replace the default crash handler.
+pub trait ObsCrashHandler
+where
+    Self: Send + Debug,
+{
+    /// Handles an OBS crash with the given report. Its `Display` implementation
+    /// formats the whole report, including the loaded modules and active outputs.
     /// YOU MUST MAKE SURE that this function does the least amount of work possible!
-    fn handle_crash(&self, message: String);
+    fn handle_crash(&self, report: ObsCrashReport);
 }
 
+#[derive(Debug)]
 pub struct ConsoleCrashHandler {
     _private: (),
 }
@@ -38,11 +57,11 @@ impl ConsoleCrashHandler {
     }
 }
 impl ObsCrashHandler for ConsoleCrashHandler {
-    fn handle_crash(&self, message: String) {
+    fn handle_crash(&self, report: ObsCrashReport) {
         #[cfg(not(feature = "logging_crash_handler"))]
-        eprintln!("OBS crashed: {}", message);
+        eprintln!("{}", report);
         #[cfg(feature = "logging_crash_handler")]
-        log::error!("OBS crashed: {}", message);
+        log::error!("{}", report);
     }
 }
 
@@ -75,5 +94,16 @@ pub(crate) unsafe extern "C" fn main_crash_handler<V>(
     }
 
     let res = res.unwrap();
-    CRASH_HANDLER.lock().unwrap().handle_crash(res);
+    session::mark_crashed();
+    CRASH_HANDLER
+        .lock()
+        .unwrap()
+        .handle_crash(report::build_report(res));
+}
+
+/// Replaces the crash handler, called on startup if a crash handler was set in the `StartupInfo`.
+pub(crate) fn set_crash_handler(handler: Box<dyn ObsCrashHandler>) {
+    *CRASH_HANDLER
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = handler;
 }
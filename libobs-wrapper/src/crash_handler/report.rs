@@ -0,0 +1,136 @@
+//! Keeps a snapshot of the state of the OBS context, so the crash handler can report it without
+//! calling into libobs, which may be in an inconsistent state after a crash.
+use std::{
+    fmt::{self, Display},
+    sync::Mutex,
+    time::SystemTime,
+};
+
+use lazy_static::lazy_static;
+
+lazy_static! {
+    static ref CRASH_STATE: Mutex<CrashState> = Mutex::new(CrashState::default());
+}
+
+#[derive(Debug, Default)]
+struct CrashState {
+    obs_version: Option<String>,
+    modules: Vec<String>,
+    active_outputs: Vec<ObsCrashReportOutput>,
+}
+
+/// An output that was active when OBS crashed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ObsCrashReportOutput {
+    pub name: String,
+    pub id: String,
+    /// The ids of the video and audio encoders of the output
+    pub encoders: Vec<String>,
+}
+
+/// Everything that is known about a crash, passed to `ObsCrashHandler::handle_crash`.
+#[derive(Debug, Clone)]
+pub struct ObsCrashReport {
+    /// The message libobs crashed with
+    pub message: String,
+    /// The version of libobs, `None` if OBS crashed before it was started
+    pub obs_version: Option<String>,
+    /// The modules that were loaded on startup
+    pub modules: Vec<String>,
+    /// The outputs that were started and not stopped yet
+    pub active_outputs: Vec<ObsCrashReportOutput>,
+    pub timestamp: SystemTime,
+}
+
+impl Display for ObsCrashReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let timestamp = self
+            .timestamp
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+
+        writeln!(f, "OBS crashed: {}", self.message)?;
+        writeln!(f)?;
+        writeln!(f, "Timestamp (unix): {}", timestamp)?;
+        writeln!(
+            f,
+            "OBS version: {}",
+            self.obs_version.as_deref().unwrap_or("unknown")
+        )?;
+
+        writeln!(f)?;
+        writeln!(f, "Loaded modules:")?;
+        for module in &self.modules {
+            writeln!(f, "  {}", module)?;
+        }
+
+        writeln!(f)?;
+        writeln!(f, "Active outputs:")?;
+        for output in &self.active_outputs {
+            writeln!(
+                f,
+                "  {} ({}), encoders: {}",
+                output.name,
+                output.id,
+                output.encoders.join(", ")
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Builds the report for a crash. Called from the crash handler, so this never blocks
+/// and only reports the message if the state is locked.
+pub(crate) fn build_report(message: String) -> ObsCrashReport {
+    let mut report = ObsCrashReport {
+        message,
+        obs_version: None,
+        modules: Vec::new(),
+        active_outputs: Vec::new(),
+        timestamp: SystemTime::now(),
+    };
+
+    if let Ok(state) = CRASH_STATE.try_lock() {
+        report.obs_version = state.obs_version.clone();
+        report.modules = state.modules.clone();
+        report.active_outputs = state.active_outputs.clone();
+    }
+
+    report
+}
+
+fn with_state<F: FnOnce(&mut CrashState)>(f: F) {
+    let mut state = CRASH_STATE
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    f(&mut state);
+}
+
+/// Records the version and modules of OBS once startup is complete.
+pub(crate) fn set_startup_state(obs_version: String, modules: Vec<String>) {
+    with_state(|state| {
+        *state = CrashState {
+            obs_version: Some(obs_version),
+            modules,
+            active_outputs: Vec::new(),
+        }
+    });
+}
+
+pub(crate) fn output_started(output: ObsCrashReportOutput) {
+    with_state(|state| {
+        state.active_outputs.retain(|o| o.name != output.name);
+        state.active_outputs.push(output);
+    });
+}
+
+pub(crate) fn output_stopped(name: &str) {
+    with_state(|state| state.active_outputs.retain(|o| o.name != name));
+}
+
+/// Clears the state on shutdown.
+pub(crate) fn clear_state() {
+    with_state(|state| *state = CrashState::default());
+}
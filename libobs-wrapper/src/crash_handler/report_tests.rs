@@ -0,0 +1,28 @@
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime};
+
+    use super::super::report::{ObsCrashReport, ObsCrashReportOutput};
+
+    #[test]
+    fn test_report_display() {
+        let report = ObsCrashReport {
+            message: "Access violation".into(),
+            obs_version: Some("32.0.1".into()),
+            modules: vec!["obs-ffmpeg".into(), "obs-x264".into()],
+            active_outputs: vec![ObsCrashReportOutput {
+                name: "recording".into(),
+                id: "ffmpeg_muxer".into(),
+                encoders: vec!["obs_x264".into(), "ffmpeg_aac".into()],
+            }],
+            timestamp: SystemTime::UNIX_EPOCH + Duration::from_secs(42),
+        };
+
+        let text = report.to_string();
+        assert!(text.starts_with("OBS crashed: Access violation\n"));
+        assert!(text.contains("Timestamp (unix): 42\n"));
+        assert!(text.contains("OBS version: 32.0.1\n"));
+        assert!(text.contains("  obs-x264\n"));
+        assert!(text.contains("  recording (ffmpeg_muxer), encoders: obs_x264, ffmpeg_aac\n"));
+    }
+}
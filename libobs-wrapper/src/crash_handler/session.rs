@@ -0,0 +1,136 @@
+//! Keeps track of the state of the current OBS session on disk, so a crash can be detected on the next startup.
+//!
+//! The session file is written while the modules are loading and once startup is complete,
+//! marked as crashed by the crash handler and removed on a clean shutdown.
+//! If the file still exists on the next startup, the previous session did not shut down cleanly.
+use std::{
+    fs::{self, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use lazy_static::lazy_static;
+
+const LOADING_MODULES: &str = "loading_modules";
+const RUNNING: &str = "running";
+pub(super) const CRASHED: &str = "crashed";
+const MODULE_PREFIX: &str = "module=";
+
+lazy_static! {
+    static ref SESSION_STATE_PATH: Mutex<Option<PathBuf>> = Mutex::new(None);
+}
+
+/// A previous OBS session that did not shut down cleanly.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ObsCrashedSession {
+    /// Whether the session crashed while the modules were still loading.
+    pub crashed_while_loading_modules: bool,
+    /// Whether the crash handler was invoked. If this is false, the process was terminated
+    /// without going through the crash handler (e.g. it was killed).
+    pub crash_handler_invoked: bool,
+    /// The third-party modules that were loading or active when the session crashed.
+    pub modules: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SessionPhase {
+    LoadingModules,
+    Running,
+}
+
+/// Returns the default location of the session state file.
+pub(crate) fn default_session_state_path() -> PathBuf {
+    std::env::temp_dir().join("libobs-wrapper-session")
+}
+
+/// Reads the state of the previous session, returns `None` if it shut down cleanly.
+pub(crate) fn read_previous_session(path: &Path) -> Option<ObsCrashedSession> {
+    let content = fs::read_to_string(path).ok()?;
+    Some(parse_session(&content))
+}
+
+/// Writes the current phase and the active third-party modules to the session state file.
+pub(crate) fn write_session(
+    path: &Path,
+    phase: SessionPhase,
+    modules: &[String],
+) -> std::io::Result<()> {
+    fs::write(path, format_session(phase, modules))?;
+
+    *SESSION_STATE_PATH
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(path.to_path_buf());
+
+    Ok(())
+}
+
+/// Marks the current session as crashed. Called from the crash handler, so this does as little work as possible.
+pub(crate) fn mark_crashed() {
+    let Ok(path) = SESSION_STATE_PATH.try_lock() else {
+        return;
+    };
+
+    if let Some(path) = path.as_ref() {
+        if let Ok(mut file) = OpenOptions::new().append(true).open(path) {
+            let _ = writeln!(file, "{}", CRASHED);
+        }
+    }
+}
+
+/// Removes the session state file, which marks the session as cleanly shut down.
+pub(crate) fn end_session() {
+    let path = SESSION_STATE_PATH
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .take();
+
+    if let Some(path) = path {
+        if let Err(e) = fs::remove_file(&path) {
+            log::warn!(
+                "Failed to remove session state file {}: {}",
+                path.display(),
+                e
+            );
+        }
+    }
+}
+
+pub(super) fn format_session(phase: SessionPhase, modules: &[String]) -> String {
+    let mut content = match phase {
+        SessionPhase::LoadingModules => LOADING_MODULES,
+        SessionPhase::Running => RUNNING,
+    }
+    .to_string();
+    content.push('\n');
+
+    for module in modules {
+        content.push_str(MODULE_PREFIX);
+        content.push_str(module);
+        content.push('\n');
+    }
+
+    content
+}
+
+pub(super) fn parse_session(content: &str) -> ObsCrashedSession {
+    let mut session = ObsCrashedSession {
+        crashed_while_loading_modules: false,
+        crash_handler_invoked: false,
+        modules: Vec::new(),
+    };
+
+    for line in content.lines().map(str::trim) {
+        match line {
+            LOADING_MODULES => session.crashed_while_loading_modules = true,
+            CRASHED => session.crash_handler_invoked = true,
+            _ => {
+                if let Some(module) = line.strip_prefix(MODULE_PREFIX) {
+                    session.modules.push(module.to_string());
+                }
+            }
+        }
+    }
+
+    session
+}
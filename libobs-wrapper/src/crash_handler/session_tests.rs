@@ -0,0 +1,30 @@
+#[cfg(test)]
+mod tests {
+    use super::super::session::{format_session, parse_session, SessionPhase, CRASHED};
+
+    #[test]
+    fn test_session_roundtrip() {
+        let modules = vec![
+            "obs-shaderfilter".to_string(),
+            "move-transition".to_string(),
+        ];
+        let mut content = format_session(SessionPhase::Running, &modules);
+        content.push_str(CRASHED);
+        content.push('\n');
+
+        let session = parse_session(&content);
+        assert!(!session.crashed_while_loading_modules);
+        assert!(session.crash_handler_invoked);
+        assert_eq!(session.modules, modules);
+    }
+
+    #[test]
+    fn test_session_crashed_while_loading() {
+        let content = format_session(SessionPhase::LoadingModules, &["obs-shaderfilter".into()]);
+
+        let session = parse_session(&content);
+        assert!(session.crashed_while_loading_modules);
+        assert!(!session.crash_handler_invoked);
+        assert_eq!(session.modules, vec!["obs-shaderfilter".to_string()]);
+    }
+}
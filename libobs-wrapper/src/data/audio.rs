@@ -3,8 +3,12 @@ use libobs::obs_audio_info2;
 use crate::{
     enums::{ObsSamplesPerSecond, ObsSpeakerLayout},
     unsafe_send::Sendable,
+    utils::ObsError,
 };
 
+/// The maximum audio buffering libobs supports, larger values are clamped by libobs.
+pub const MAX_AUDIO_BUFFERING_MS: u32 = 960;
+
 /// Information passed to libobs when attempting to
 /// reset the audio context using `obs_reset_audio2`.
 #[repr(C)]
@@ -38,6 +42,58 @@ impl ObsAudioInfo {
         Self::new(samples_per_second, speakers, 20, true)
     }
 
+    pub fn samples_per_second(&self) -> ObsSamplesPerSecond {
+        self.samples_per_sec
+    }
+
+    pub fn speakers(&self) -> ObsSpeakerLayout {
+        self.speakers
+    }
+
+    pub fn max_buffering_ms(&self) -> u32 {
+        self.max_buffering_ms
+    }
+
+    pub fn fixed_buffering(&self) -> bool {
+        self.fixed_buffering
+    }
+
+    /// Returns the number of audio channels of the speaker layout.
+    pub fn channels(&self) -> u32 {
+        match self.speakers {
+            ObsSpeakerLayout::Unknown => 0,
+            ObsSpeakerLayout::Mono => 1,
+            ObsSpeakerLayout::Stereo => 2,
+            ObsSpeakerLayout::S2Point1 => 3,
+            ObsSpeakerLayout::S4Point0 => 4,
+            ObsSpeakerLayout::S4Point1 => 5,
+            ObsSpeakerLayout::S5Point1 => 6,
+            ObsSpeakerLayout::S7Point1 => 8,
+        }
+    }
+
+    /// Checks whether audio encoders of the given codec (e.g. `aac` or `opus`, see
+    /// `ObsContext::validate_audio_encoder`) can encode the speaker layout of this audio info.
+    pub fn validate_codec(&self, codec: &str) -> Result<(), ObsError> {
+        let max_channels = match codec {
+            "mp3" => 2,
+            "ac3" | "eac3" => 6,
+            _ => 8,
+        };
+
+        if self.channels() > max_channels {
+            return Err(ObsError::InvalidOperation(format!(
+                "The {} codec supports at most {} audio channels, but the speaker layout {:?} has {}",
+                codec,
+                max_channels,
+                self.speakers,
+                self.channels()
+            )));
+        }
+
+        Ok(())
+    }
+
     pub fn as_ptr(&self) -> Sendable<*const obs_audio_info2> {
         Sendable(self as *const Self as *const obs_audio_info2)
     }
@@ -53,3 +109,94 @@ impl Default for ObsAudioInfo {
         }
     }
 }
+
+/// A structure intended to help make
+/// creating new `ObsAudioInfo` structs
+/// easier for starting or resetting the
+/// OBS audio context.
+#[derive(Clone, Debug)]
+pub struct ObsAudioInfoBuilder {
+    samples_per_sec: ObsSamplesPerSecond,
+    speakers: ObsSpeakerLayout,
+    max_buffering_ms: u32,
+    fixed_buffering: bool,
+}
+
+impl ObsAudioInfoBuilder {
+    /// Creates a new `ObsAudioInfoBuilder` with the
+    /// same defaults as `ObsAudioInfo::default`.
+    pub fn new() -> Self {
+        let info = ObsAudioInfo::default();
+        Self {
+            samples_per_sec: info.samples_per_sec,
+            speakers: info.speakers,
+            max_buffering_ms: info.max_buffering_ms,
+            fixed_buffering: info.fixed_buffering,
+        }
+    }
+
+    /// Consumes the `ObsAudioInfoBuilder`
+    /// to create an `ObsAudioInfo`.
+    ///
+    /// Fails if the speaker layout is unknown or the
+    /// buffering exceeds `MAX_AUDIO_BUFFERING_MS`.
+    pub fn build(self) -> Result<ObsAudioInfo, ObsError> {
+        if self.speakers == ObsSpeakerLayout::Unknown {
+            return Err(ObsError::InvalidOperation(
+                "The speaker layout of the audio info must be known".to_string(),
+            ));
+        }
+
+        if self.max_buffering_ms > MAX_AUDIO_BUFFERING_MS {
+            return Err(ObsError::InvalidOperation(format!(
+                "The audio buffering must not exceed {} ms, got {} ms",
+                MAX_AUDIO_BUFFERING_MS, self.max_buffering_ms
+            )));
+        }
+
+        Ok(ObsAudioInfo::new(
+            self.samples_per_sec,
+            self.speakers,
+            self.max_buffering_ms,
+            self.fixed_buffering,
+        ))
+    }
+
+    /// Sets the sample rate of the audio.
+    pub fn samples_per_second(mut self, value: ObsSamplesPerSecond) -> Self {
+        self.samples_per_sec = value;
+        self
+    }
+
+    /// Sets the speaker layout, which
+    /// determines the number of channels.
+    pub fn speakers(mut self, value: ObsSpeakerLayout) -> Self {
+        self.speakers = value;
+        self
+    }
+
+    /// Sets the maximum amount of audio
+    /// libobs buffers to compensate for
+    /// sources with delayed audio.
+    /// Zero uses the default of libobs.
+    pub fn max_buffering_ms(mut self, value: u32) -> Self {
+        self.max_buffering_ms = value;
+        self
+    }
+
+    /// Sets whether the buffering is fixed to
+    /// `max_buffering_ms` instead of growing
+    /// dynamically up to it, which keeps the
+    /// latency constant.
+    pub fn fixed_buffering(mut self, value: bool) -> Self {
+        self.fixed_buffering = value;
+        self
+    }
+}
+
+#[cfg_attr(coverage_nightly, coverage(off))]
+impl Default for ObsAudioInfoBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
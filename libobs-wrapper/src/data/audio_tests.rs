@@ -0,0 +1,30 @@
+#[cfg(test)]
+mod tests {
+    use super::super::audio::{ObsAudioInfoBuilder, MAX_AUDIO_BUFFERING_MS};
+    use crate::enums::{ObsSamplesPerSecond, ObsSpeakerLayout};
+
+    #[test]
+    fn test_audio_info_builder_validation() {
+        let info = ObsAudioInfoBuilder::new()
+            .samples_per_second(ObsSamplesPerSecond::F48000)
+            .speakers(ObsSpeakerLayout::S5Point1)
+            .max_buffering_ms(100)
+            .build()
+            .unwrap();
+
+        assert_eq!(info.samples_per_second(), ObsSamplesPerSecond::F48000);
+        assert_eq!(info.channels(), 6);
+        assert!(info.validate_codec("aac").is_ok());
+        assert!(info.validate_codec("ac3").is_ok());
+        assert!(info.validate_codec("mp3").is_err());
+
+        assert!(ObsAudioInfoBuilder::new()
+            .speakers(ObsSpeakerLayout::Unknown)
+            .build()
+            .is_err());
+        assert!(ObsAudioInfoBuilder::new()
+            .max_buffering_ms(MAX_AUDIO_BUFFERING_MS + 1)
+            .build()
+            .is_err());
+    }
+}
@@ -57,10 +57,10 @@ impl ImmutableObsData {
 
     pub fn to_mutable(&self) -> Result<ObsData, ObsError> {
         let ptr = self.ptr.clone();
-        let json = run_with_obs!(self.runtime, (ptr), move || {
+        let json = run_with_obs!(self.runtime, (ptr), move |token| {
             let json_ptr = unsafe {
                 // Safety: We are making sure by using a SmartPointer, that this pointer is valid during the call.
-                libobs::obs_data_get_json(ptr.get_ptr())
+                libobs::obs_data_get_json(ptr.get_ptr(token))
             };
 
             if json_ptr.is_null() {
@@ -74,7 +74,7 @@ impl ImmutableObsData {
                 CStr::from_ptr(json_ptr)
             }
             .to_str()
-            .map_err(|_| ObsError::JsonParseError)?
+            .map_err(|e| ObsError::JsonParseError(Some(e.into())))?
             .to_string();
 
             Ok(json)
@@ -0,0 +1,430 @@
+//! Diffing and migrating settings, e.g. source settings that are persisted by an application
+//! and have to be loaded again after OBS or a plugin renamed some of its keys.
+use std::{collections::BTreeMap, ffi::CStr};
+
+use libobs::{obs_data_item_t, obs_data_t};
+
+use crate::{
+    data::{ImmutableObsData, ObsData, ObsDataPointers},
+    run_with_obs,
+    runtime::ObsRuntime,
+    unsafe_send::SmartPointerSendable,
+    utils::{ObsError, ObsString},
+};
+
+/// A value stored in `ObsData`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ObsDataValue {
+    String(String),
+    Int(i64),
+    Double(f64),
+    Bool(bool),
+    /// The JSON of a nested object
+    Object(String),
+    /// The JSON of every object in the array
+    Array(Vec<String>),
+}
+
+/// A difference between two `ObsData` objects, see [`ObsData::diff`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum SettingChange {
+    Added {
+        key: String,
+        value: ObsDataValue,
+    },
+    Removed {
+        key: String,
+        value: ObsDataValue,
+    },
+    Changed {
+        key: String,
+        from: ObsDataValue,
+        to: ObsDataValue,
+    },
+}
+
+impl SettingChange {
+    pub fn key(&self) -> &str {
+        match self {
+            SettingChange::Added { key, .. }
+            | SettingChange::Removed { key, .. }
+            | SettingChange::Changed { key, .. } => key,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum SettingMigrationRule {
+    /// Moves the value of `from` to `to`. If `to` is set already, the value of `from` is dropped.
+    Rename {
+        from: String,
+        to: String,
+    },
+    Remove(String),
+    /// Replaces the value of `key` with the result of `transform`, `None` removes the key.
+    /// Keys that are not set are skipped.
+    Transform {
+        key: String,
+        transform: fn(ObsDataValue) -> Option<ObsDataValue>,
+    },
+}
+
+/// A rule that is applied when migrating settings to `version` or a later version,
+/// see [`ImmutableObsData::migrate`].
+#[derive(Debug, Clone)]
+pub struct SettingMigration {
+    pub version: u32,
+    pub rule: SettingMigrationRule,
+}
+
+impl SettingMigration {
+    pub fn rename(version: u32, from: impl Into<String>, to: impl Into<String>) -> Self {
+        Self {
+            version,
+            rule: SettingMigrationRule::Rename {
+                from: from.into(),
+                to: to.into(),
+            },
+        }
+    }
+
+    pub fn remove(version: u32, key: impl Into<String>) -> Self {
+        Self {
+            version,
+            rule: SettingMigrationRule::Remove(key.into()),
+        }
+    }
+
+    pub fn transform(
+        version: u32,
+        key: impl Into<String>,
+        transform: fn(ObsDataValue) -> Option<ObsDataValue>,
+    ) -> Self {
+        Self {
+            version,
+            rule: SettingMigrationRule::Transform {
+                key: key.into(),
+                transform,
+            },
+        }
+    }
+}
+
+impl ObsData {
+    /// Lists the changes from this data to `other`. Only values that have been set are
+    /// compared, default values are ignored. Keys are sorted alphabetically.
+    pub fn diff(&self, other: &impl ObsDataPointers) -> Result<Vec<SettingChange>, ObsError> {
+        diff(self, other)
+    }
+}
+
+impl ImmutableObsData {
+    /// Lists the changes from this data to `other`, see [`ObsData::diff`].
+    pub fn diff(&self, other: &impl ObsDataPointers) -> Result<Vec<SettingChange>, ObsError> {
+        diff(self, other)
+    }
+
+    /// Returns a copy of this data with every rule applied whose version is greater than
+    /// `from_version` and at most `to_version`, in the order of their versions.
+    ///
+    /// Use [`ImmutableObsData::diff`] on the result to see what has been changed.
+    pub fn migrate(
+        &self,
+        from_version: u32,
+        to_version: u32,
+        rules: &[SettingMigration],
+    ) -> Result<ImmutableObsData, ObsError> {
+        if from_version > to_version {
+            return Err(ObsError::InvalidOperation(format!(
+                "Can not migrate settings from version {} back to {}",
+                from_version, to_version
+            )));
+        }
+
+        let data = self.to_mutable()?;
+        let runtime = data.runtime.clone();
+        let data_ptr = data.as_ptr();
+
+        for migration in applicable_rules(from_version, to_version, rules) {
+            match &migration.rule {
+                SettingMigrationRule::Rename { from, to } => {
+                    let from = ObsString::from(from.as_str());
+                    let to = ObsString::from(to.as_str());
+                    run_with_obs!(runtime, (data_ptr, from, to), move |token| unsafe {
+                        // Safety: We are in the runtime and the pointer is valid because of the smart pointer
+                        if let Some(value) = read_value(data_ptr.get_ptr(token), &from) {
+                            if read_value(data_ptr.get_ptr(token), &to).is_none() {
+                                write_value(data_ptr.get_ptr(token), &to, &value)?;
+                            }
+
+                            libobs::obs_data_erase(data_ptr.get_ptr(token), from.as_ptr().0);
+                        }
+
+                        Ok(())
+                    })??;
+                }
+                SettingMigrationRule::Remove(key) => {
+                    let key = ObsString::from(key.as_str());
+                    run_with_obs!(runtime, (data_ptr, key), move |token| unsafe {
+                        // Safety: We are in the runtime and the pointer is valid because of the smart pointer
+                        libobs::obs_data_erase(data_ptr.get_ptr(token), key.as_ptr().0);
+                    })?;
+                }
+                SettingMigrationRule::Transform { key, transform } => {
+                    let key = ObsString::from(key.as_str());
+                    let Some(value) = get_value(&runtime, &data_ptr, &key)? else {
+                        continue;
+                    };
+
+                    // Called outside of the runtime, so the transform can not block it
+                    let value = transform(value);
+                    run_with_obs!(runtime, (data_ptr, key), move |token| unsafe {
+                        // Safety: We are in the runtime and the pointer is valid because of the smart pointer
+                        libobs::obs_data_erase(data_ptr.get_ptr(token), key.as_ptr().0);
+                        match &value {
+                            Some(value) => write_value(data_ptr.get_ptr(token), &key, value),
+                            None => Ok(()),
+                        }
+                    })??;
+                }
+            }
+        }
+
+        Ok(data.into_immutable())
+    }
+}
+
+fn diff(
+    data: &impl ObsDataPointers,
+    other: &impl ObsDataPointers,
+) -> Result<Vec<SettingChange>, ObsError> {
+    let old = get_values(data.runtime(), &data.as_ptr())?;
+    let mut new = get_values(other.runtime(), &other.as_ptr())?;
+
+    let mut changes = vec![];
+    for (key, from) in old {
+        match new.remove(&key) {
+            Some(to) if to == from => {}
+            Some(to) => changes.push(SettingChange::Changed { key, from, to }),
+            None => changes.push(SettingChange::Removed { key, value: from }),
+        }
+    }
+
+    changes.extend(
+        new.into_iter()
+            .map(|(key, value)| SettingChange::Added { key, value }),
+    );
+    changes.sort_by(|a, b| a.key().cmp(b.key()));
+
+    Ok(changes)
+}
+
+pub(super) fn applicable_rules(
+    from_version: u32,
+    to_version: u32,
+    rules: &[SettingMigration],
+) -> Vec<&SettingMigration> {
+    let mut rules = rules
+        .iter()
+        .filter(|r| r.version > from_version && r.version <= to_version)
+        .collect::<Vec<_>>();
+
+    // Stable, so rules of the same version keep their order
+    rules.sort_by_key(|r| r.version);
+    rules
+}
+
+fn get_values(
+    runtime: &ObsRuntime,
+    data_ptr: &SmartPointerSendable<*mut obs_data_t>,
+) -> Result<BTreeMap<String, ObsDataValue>, ObsError> {
+    let data_ptr = data_ptr.clone();
+    run_with_obs!(runtime, (data_ptr), move |token| unsafe {
+        // Safety: We are in the runtime and the pointer is valid because of the smart pointer
+        let mut values = BTreeMap::new();
+        let mut item = libobs::obs_data_first(data_ptr.get_ptr(token));
+        while !item.is_null() {
+            if libobs::obs_data_item_has_user_value(item) {
+                let name = CStr::from_ptr(libobs::obs_data_item_get_name(item))
+                    .to_string_lossy()
+                    .to_string();
+
+                if let Some(value) = item_value(item) {
+                    values.insert(name, value);
+                }
+            }
+
+            // Releases the current item and stores the next one or null
+            libobs::obs_data_item_next(&mut item);
+        }
+
+        values
+    })
+}
+
+fn get_value(
+    runtime: &ObsRuntime,
+    data_ptr: &SmartPointerSendable<*mut obs_data_t>,
+    key: &ObsString,
+) -> Result<Option<ObsDataValue>, ObsError> {
+    let data_ptr = data_ptr.clone();
+    let key = key.clone();
+    run_with_obs!(runtime, (data_ptr, key), move |token| unsafe {
+        // Safety: We are in the runtime and the pointer is valid because of the smart pointer
+        read_value(data_ptr.get_ptr(token), &key)
+    })
+}
+
+/// Reads the value of `key`, `None` if it has no user value.
+///
+/// # Safety
+/// This function must be called on the OBS runtime with a valid data pointer.
+#[allow(unknown_lints)]
+#[allow(ensure_obs_call_in_runtime)]
+unsafe fn read_value(data: *mut obs_data_t, key: &ObsString) -> Option<ObsDataValue> {
+    let mut item = libobs::obs_data_item_byname(data, key.as_ptr().0);
+    if item.is_null() {
+        return None;
+    }
+
+    let value = if libobs::obs_data_item_has_user_value(item) {
+        item_value(item)
+    } else {
+        None
+    };
+
+    libobs::obs_data_item_release(&mut item);
+    value
+}
+
+/// # Safety
+/// This function must be called on the OBS runtime with a valid item pointer.
+#[allow(unknown_lints)]
+#[allow(ensure_obs_call_in_runtime)]
+unsafe fn item_value(item: *mut obs_data_item_t) -> Option<ObsDataValue> {
+    match libobs::obs_data_item_gettype(item) {
+        libobs::obs_data_type_OBS_DATA_STRING => {
+            let value = libobs::obs_data_item_get_string(item);
+            if value.is_null() {
+                return None;
+            }
+
+            Some(ObsDataValue::String(
+                CStr::from_ptr(value).to_string_lossy().to_string(),
+            ))
+        }
+        libobs::obs_data_type_OBS_DATA_NUMBER => {
+            if libobs::obs_data_item_numtype(item) == libobs::obs_data_number_type_OBS_DATA_NUM_INT
+            {
+                Some(ObsDataValue::Int(libobs::obs_data_item_get_int(item)))
+            } else {
+                Some(ObsDataValue::Double(libobs::obs_data_item_get_double(item)))
+            }
+        }
+        libobs::obs_data_type_OBS_DATA_BOOLEAN => {
+            Some(ObsDataValue::Bool(libobs::obs_data_item_get_bool(item)))
+        }
+        libobs::obs_data_type_OBS_DATA_OBJECT => {
+            let obj = libobs::obs_data_item_get_obj(item);
+            if obj.is_null() {
+                return None;
+            }
+
+            let json = data_json(obj);
+            libobs::obs_data_release(obj);
+            Some(ObsDataValue::Object(json))
+        }
+        libobs::obs_data_type_OBS_DATA_ARRAY => {
+            let array = libobs::obs_data_item_get_array(item);
+            if array.is_null() {
+                return None;
+            }
+
+            let mut objects = vec![];
+            for i in 0..libobs::obs_data_array_count(array) {
+                let obj = libobs::obs_data_array_item(array, i);
+                if !obj.is_null() {
+                    objects.push(data_json(obj));
+                    libobs::obs_data_release(obj);
+                }
+            }
+
+            libobs::obs_data_array_release(array);
+            Some(ObsDataValue::Array(objects))
+        }
+        _ => None,
+    }
+}
+
+/// # Safety
+/// This function must be called on the OBS runtime with a valid data pointer.
+#[allow(unknown_lints)]
+#[allow(ensure_obs_call_in_runtime)]
+unsafe fn data_json(data: *mut obs_data_t) -> String {
+    let json = libobs::obs_data_get_json(data);
+    if json.is_null() {
+        return "{}".to_string();
+    }
+
+    CStr::from_ptr(json).to_string_lossy().to_string()
+}
+
+/// # Safety
+/// This function must be called on the OBS runtime with a valid data pointer.
+#[allow(unknown_lints)]
+#[allow(ensure_obs_call_in_runtime)]
+unsafe fn write_value(
+    data: *mut obs_data_t,
+    key: &ObsString,
+    value: &ObsDataValue,
+) -> Result<(), ObsError> {
+    let key = key.as_ptr().0;
+    match value {
+        ObsDataValue::String(value) => {
+            let value = ObsString::from(value.as_str());
+            libobs::obs_data_set_string(data, key, value.as_ptr().0);
+        }
+        ObsDataValue::Int(value) => libobs::obs_data_set_int(data, key, *value),
+        ObsDataValue::Double(value) => libobs::obs_data_set_double(data, key, *value),
+        ObsDataValue::Bool(value) => libobs::obs_data_set_bool(data, key, *value),
+        ObsDataValue::Object(json) => {
+            let obj = data_from_json(json)?;
+            libobs::obs_data_set_obj(data, key, obj);
+            libobs::obs_data_release(obj);
+        }
+        ObsDataValue::Array(objects) => {
+            let array = libobs::obs_data_array_create();
+            for json in objects {
+                let obj = match data_from_json(json) {
+                    Ok(obj) => obj,
+                    Err(e) => {
+                        libobs::obs_data_array_release(array);
+                        return Err(e);
+                    }
+                };
+
+                libobs::obs_data_array_push_back(array, obj);
+                libobs::obs_data_release(obj);
+            }
+
+            libobs::obs_data_set_array(data, key, array);
+            libobs::obs_data_array_release(array);
+        }
+    }
+
+    Ok(())
+}
+
+/// # Safety
+/// This function must be called on the OBS runtime.
+#[allow(unknown_lints)]
+#[allow(ensure_obs_call_in_runtime)]
+unsafe fn data_from_json(json: &str) -> Result<*mut obs_data_t, ObsError> {
+    let json = ObsString::from(json);
+    let obj = libobs::obs_data_create_from_json(json.as_ptr().0);
+    if obj.is_null() {
+        return Err(ObsError::JsonParseError(None));
+    }
+
+    Ok(obj)
+}
@@ -0,0 +1,26 @@
+#[cfg(test)]
+mod tests {
+    use super::super::migration::{applicable_rules, SettingMigration, SettingMigrationRule};
+
+    #[test]
+    fn rules_are_selected_by_version() {
+        let rules = [
+            SettingMigration::remove(3, "c"),
+            SettingMigration::rename(2, "a", "b"),
+            SettingMigration::remove(1, "old"),
+            SettingMigration::rename(2, "b", "d"),
+        ];
+
+        let keys = applicable_rules(1, 3, &rules)
+            .into_iter()
+            .map(|r| match &r.rule {
+                SettingMigrationRule::Rename { from, to } => format!("{}->{}", from, to),
+                SettingMigrationRule::Remove(key) => format!("-{}", key),
+                SettingMigrationRule::Transform { key, .. } => key.clone(),
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(keys, ["a->b", "b->d", "-c"]);
+        assert!(applicable_rules(3, 3, &rules).is_empty());
+    }
+}
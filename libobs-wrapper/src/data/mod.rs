@@ -17,16 +17,27 @@ use crate::{
     utils::{ObsDropGuard, ObsError},
 };
 pub use immutable::ImmutableObsData;
+use updater::apply_changes;
 
 pub mod audio;
+#[cfg(test)]
+mod audio_tests;
 mod immutable;
 mod lib_support;
+mod migration;
+#[cfg(test)]
+mod migration_tests;
+pub use migration::*;
 pub mod object;
 pub mod output;
 pub mod properties;
 pub mod video;
+#[cfg(test)]
+mod video_tests;
 pub use lib_support::*;
 mod updater;
+#[cfg(test)]
+mod updater_tests;
 pub use updater::*;
 mod traits;
 pub use traits::*;
@@ -72,15 +83,33 @@ impl ObsData {
             Sendable(libobs::obs_data_create())
         })?;
 
+        Ok(Self::from_created(obs_data, runtime))
+    }
+
+    /// Creates a new `ObsData` holding the given values, with a single call to the runtime.
+    /// The [`obs_data!`](crate::obs_data) macro is a shorthand for this.
+    pub fn from_changes(
+        runtime: ObsRuntime,
+        changes: Vec<ObsDataChange>,
+    ) -> Result<Self, ObsError> {
+        let obs_data = run_with_obs!(runtime, move || unsafe {
+            // Safety: We are in the runtime and the data was just created, so it is valid.
+            let data = libobs::obs_data_create();
+            apply_changes(data, changes);
+            Sendable(data)
+        })?;
+
+        Ok(Self::from_created(obs_data, runtime))
+    }
+
+    /// Takes ownership of a newly created `obs_data_t`.
+    fn from_created(obs_data: Sendable<*mut libobs::obs_data_t>, runtime: ObsRuntime) -> Self {
         let drop_guard = Arc::new(_ObsDataDropGuard {
             data_ptr: obs_data.clone(),
             runtime: runtime.clone(),
         });
-        let ptr = SmartPointerSendable::new(obs_data.0, drop_guard.clone());
-        Ok(ObsData {
-            ptr,
-            runtime: runtime.clone(),
-        })
+        let ptr = SmartPointerSendable::new(obs_data.0, drop_guard);
+        ObsData { ptr, runtime }
     }
 
     pub fn bulk_update(&mut self) -> ObsDataUpdater {
@@ -88,7 +117,7 @@ impl ObsData {
     }
 
     pub fn from_json(json: &str, runtime: ObsRuntime) -> Result<Self, ObsError> {
-        let cstr = CString::new(json).map_err(|_| ObsError::JsonParseError)?;
+        let cstr = CString::new(json).map_err(|e| ObsError::JsonParseError(Some(e.into())))?;
 
         let raw_ptr = run_with_obs!(runtime, (cstr), move || unsafe {
             // Safety: We made sure that the cstr pointer is valid during the call.
@@ -96,7 +125,7 @@ impl ObsData {
         })?;
 
         if raw_ptr.0.is_null() {
-            return Err(ObsError::JsonParseError);
+            return Err(ObsError::JsonParseError(None));
         }
 
         let drop_guard = Arc::new(_ObsDataDropGuard {
@@ -138,3 +167,29 @@ impl Clone for ObsData {
         Self::from_json(json.as_str(), self.runtime.clone()).unwrap()
     }
 }
+
+/// Creates an [`ObsData`] holding the given values, with a single call to the runtime.
+///
+/// Values can be strings, integers, floats and bools, see [`IntoObsDataChange`].
+///
+/// ```no_run
+/// # use libobs_wrapper::{context::ObsContext, obs_data, utils::ObsError};
+/// # fn example(context: &ObsContext) -> Result<(), ObsError> {
+/// let settings = obs_data!(context.runtime(); {
+///     "url" => "https://example.com",
+///     "width" => 1920,
+///     "height" => 1080,
+///     "reroute_audio" => true,
+/// })?;
+/// # Ok(())
+/// # }
+/// ```
+#[macro_export]
+macro_rules! obs_data {
+    ($runtime:expr; { $($key:expr => $value:expr),* $(,)? }) => {
+        $crate::data::ObsData::from_changes(
+            ::std::clone::Clone::clone($runtime),
+            ::std::vec![$($crate::data::ObsDataChange::new($key, $value)),*],
+        )
+    };
+}
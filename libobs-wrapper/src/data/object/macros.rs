@@ -5,10 +5,10 @@ macro_rules! inner_fn_update_settings {
         let obs_ptr = $self.as_ptr();
         let runtime = $self.runtime().clone();
 
-        run_with_obs!(runtime, (obs_ptr, settings_ptr), move || {
+        run_with_obs!(runtime, (obs_ptr, settings_ptr), move |token| {
             // SAFETY: Both the obs_ptr and the settings_ptr are a SmartPointer, so neither can't be dropped.
             unsafe {
-                $update_fn(obs_ptr.get_ptr(), settings_ptr.get_ptr())
+                $update_fn(obs_ptr.get_ptr(token), settings_ptr.get_ptr(token))
             }
         })?;
 
@@ -44,6 +44,10 @@ macro_rules! forward_obs_object_impl {
                 self.$var_name.name()
             }
 
+            fn set_name(&self, new_name: &str) -> Result<(), $crate::utils::ObsError> {
+                self.$var_name.set_name(new_name)
+            }
+
             fn id(&self) -> $crate::utils::ObsString {
                 self.$var_name.id()
             }
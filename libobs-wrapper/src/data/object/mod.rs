@@ -57,6 +57,11 @@ pub trait ObsObjectTrait<K: Clone>: ObsObjectClone<K> + ObsObjectTraitPrivate {
     fn id(&self) -> ObsString;
     fn name(&self) -> ObsString;
 
+    /// Renames this object. Every clone of this object will return the new name afterwards.
+    ///
+    /// Fails if the name is already used by another object of the same kind or if the object can't be renamed.
+    fn set_name(&self, new_name: &str) -> Result<(), ObsError>;
+
     /// Updates the settings of this output. Fails if active.
     fn update_settings(&self, settings: ObsData) -> Result<(), ObsError>;
 
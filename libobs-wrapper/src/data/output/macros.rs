@@ -16,6 +16,19 @@ macro_rules! forward_obs_output_impl {
                 self.$var_name.video_encoder()
             }
 
+            fn indexed_video_encoders(
+                &self,
+            ) -> &std::sync::Arc<
+                std::sync::RwLock<
+                    std::collections::HashMap<
+                        usize,
+                        std::sync::Arc<$crate::data::output::ObsVideoEncoder>,
+                    >,
+                >,
+            > {
+                self.$var_name.indexed_video_encoders()
+            }
+
             fn audio_encoders(
                 &self,
             ) -> &std::sync::Arc<
@@ -28,6 +41,10 @@ macro_rules! forward_obs_output_impl {
             > {
                 self.$var_name.audio_encoders()
             }
+
+            fn view(&self) -> &std::sync::Arc<std::sync::RwLock<Option<$crate::view::ObsViewRef>>> {
+                self.$var_name.view()
+            }
         }
 
         impl $struct_name {
@@ -7,8 +7,9 @@ use crate::data::object::{inner_fn_update_settings, ObsObjectTrait, ObsObjectTra
 use crate::data::ImmutableObsData;
 use crate::data::ObsDataPointers;
 use crate::runtime::ObsRuntime;
-use crate::unsafe_send::{Sendable, SmartPointerSendable};
+use crate::unsafe_send::{ObsThreadToken, Sendable, SmartPointerSendable};
 use crate::utils::{ObsDropGuard, OutputInfo};
+use crate::view::ObsViewRef;
 use crate::{impl_obs_drop, impl_signal_manager, run_with_obs};
 
 use crate::{
@@ -20,6 +21,8 @@ use super::ObsData;
 
 pub(crate) mod macros;
 mod traits;
+#[cfg(test)]
+mod traits_tests;
 pub use traits::*;
 
 mod replay_buffer;
@@ -66,9 +69,15 @@ pub struct ObsOutputRef {
     /// Video encoders attached to this output
     curr_video_encoder: Arc<RwLock<Option<Arc<ObsVideoEncoder>>>>,
 
+    /// Video encoders attached at index 1 and above
+    indexed_video_encoders: Arc<RwLock<HashMap<usize, Arc<ObsVideoEncoder>>>>,
+
     /// Audio encoders attached to this output
     audio_encoders: Arc<RwLock<HashMap<usize, Arc<ObsAudioEncoder>>>>,
 
+    /// The view the video encoder renders, the main canvas if `None`
+    view: Arc<RwLock<Option<ObsViewRef>>>,
+
     /// The type identifier of this output
     id: ObsString,
 
@@ -96,14 +105,14 @@ impl ObsOutputTraitSealed for ObsOutputRef {
         let output = run_with_obs!(
             runtime,
             (id, name, settings_ptr, hotkey_data_ptr),
-            move || {
+            move |token| {
                 let settings_raw_ptr = match settings_ptr {
-                    Some(s) => s.get_ptr(),
+                    Some(s) => s.get_ptr(token),
                     None => ptr::null_mut(),
                 };
 
                 let hotkey_data_raw_ptr = match hotkey_data_ptr {
-                    Some(h) => h.get_ptr(),
+                    Some(h) => h.get_ptr(token),
                     None => ptr::null_mut(),
                 };
 
@@ -121,7 +130,7 @@ impl ObsOutputTraitSealed for ObsOutputRef {
                 };
 
                 if output.is_null() {
-                    return Err(ObsError::NullPointer(None));
+                    return Err(ObsError::NullPointer(None).in_function("obs_output_create"));
                 }
 
                 Ok(Sendable(output))
@@ -137,14 +146,14 @@ impl ObsOutputTraitSealed for ObsOutputRef {
         );
 
         // We are getting the settings from OBS because OBS will have updated it with default values.
-        let new_settings_ptr = run_with_obs!(runtime, (output), move || {
+        let new_settings_ptr = run_with_obs!(runtime, (output), move |token| {
             let new_settings_ptr = unsafe {
                 // Safety: At this point, the output can't be released because we are using a SmartPointer.
-                libobs::obs_output_get_settings(output.get_ptr())
+                libobs::obs_output_get_settings(output.get_ptr(token))
             };
 
             if new_settings_ptr.is_null() {
-                return Err(ObsError::NullPointer(None));
+                return Err(ObsError::NullPointer(None).in_function("obs_output_get_settings"));
             }
 
             Ok(Sendable(new_settings_ptr))
@@ -165,7 +174,9 @@ impl ObsOutputTraitSealed for ObsOutputRef {
             hotkey_data: Arc::new(RwLock::new(hotkey_data)),
 
             curr_video_encoder: Arc::new(RwLock::new(None)),
+            indexed_video_encoders: Arc::new(RwLock::new(HashMap::new())),
             audio_encoders: Arc::new(RwLock::new(HashMap::new())),
+            view: Arc::new(RwLock::new(None)),
 
             output: output.clone(),
             id,
@@ -209,6 +220,13 @@ impl ObsObjectTrait<*mut libobs::obs_output> for ObsOutputRef {
         self.id.clone()
     }
 
+    /// libobs does not support renaming outputs, so this always returns an error.
+    fn set_name(&self, _new_name: &str) -> Result<(), ObsError> {
+        Err(ObsError::InvalidOperation(
+            "Outputs can not be renamed".to_string(),
+        ))
+    }
+
     fn runtime(&self) -> &ObsRuntime {
         &self.runtime
     }
@@ -252,14 +270,22 @@ impl ObsOutputTrait for ObsOutputRef {
         &self.curr_video_encoder
     }
 
+    fn indexed_video_encoders(&self) -> &Arc<RwLock<HashMap<usize, Arc<ObsVideoEncoder>>>> {
+        &self.indexed_video_encoders
+    }
+
     fn audio_encoders(&self) -> &Arc<RwLock<HashMap<usize, Arc<ObsAudioEncoder>>>> {
         &self.audio_encoders
     }
+
+    fn view(&self) -> &Arc<RwLock<Option<ObsViewRef>>> {
+        &self.view
+    }
 }
 
-impl_signal_manager!(|ptr: SmartPointerSendable<*mut libobs::obs_output>| unsafe {
+impl_signal_manager!(|ptr: SmartPointerSendable<*mut libobs::obs_output>, token: ObsThreadToken| unsafe {
     // Safety: We are using a smart pointer, so it is fine
-    libobs::obs_output_get_signal_handler(ptr.get_ptr())
+    libobs::obs_output_get_signal_handler(ptr.get_ptr(token))
 }, ObsOutputSignals for *mut libobs::obs_output, [
     "start": {},
     "stop": {code: crate::enums::ObsOutputStopSignal},
@@ -4,19 +4,28 @@
 //! A replay buffer is a special type of output that continuously records
 //! the last N seconds of content, allowing the user to save this buffer on demand. This must be configured. More documentation soon.
 use std::{
+    ffi::{c_char, CStr},
+    future::Future,
     path::{Path, PathBuf},
+    ptr,
     sync::Arc,
+    time::{Duration, Instant},
 };
 
+use libobs::{calldata_t, obs_output};
+use tokio::sync::broadcast;
+
 use crate::{
     data::{
         object::ObsObjectTrait,
-        output::{ObsOutputRef, ObsOutputTraitSealed},
+        output::{ObsOutputRef, ObsOutputTrait, ObsOutputTraitSealed},
     },
-    forward_obs_object_impl, forward_obs_output_impl, impl_signal_manager, run_with_obs,
+    forward_obs_object_impl, forward_obs_output_impl, impl_signal_manager, obs_string,
+    run_with_obs,
     runtime::ObsRuntime,
-    unsafe_send::{Sendable, SmartPointerSendable},
-    utils::{ObsCalldataExt, ObsError, ObsString, OutputInfo},
+    signals::{ObsRawSignalConnection, ObsSignalReceiver},
+    unsafe_send::{ObsThreadToken, SmartPointerSendable},
+    utils::{calldata_free, ObsError, OutputInfo},
 };
 
 #[derive(Debug, Clone)]
@@ -29,19 +38,74 @@ use crate::{
 /// raw media to the required format before sending/storing.
 pub struct ObsReplayBufferOutputRef {
     /// Disconnect signals first
+    _saved_connection: Arc<ObsRawSignalConnection>,
     replay_signal_manager: Arc<ObsReplayOutputSignals>,
+    saved_paths: broadcast::Sender<PathBuf>,
 
     output: ObsOutputRef,
 }
 
+/// How long `ObsReplayBufferOutputRef::save_buffer` waits for the replay to be written.
+pub const DEFAULT_REPLAY_SAVE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Reads the path of the last saved replay using the `get_last_replay` proc of the output.
+///
+/// # Safety
+/// The output pointer must be valid.
+unsafe fn last_replay_path(output: *mut obs_output) -> Option<PathBuf> {
+    let proc_handler = libobs::obs_output_get_proc_handler(output);
+    if proc_handler.is_null() {
+        return None;
+    }
+
+    let mut calldata: calldata_t = std::mem::zeroed();
+    let mut path = None;
+    if libobs::proc_handler_call(proc_handler, c"get_last_replay".as_ptr(), &mut calldata) {
+        let mut path_ptr: *const c_char = ptr::null();
+        if libobs::calldata_get_string(&calldata, c"path".as_ptr(), &mut path_ptr)
+            && !path_ptr.is_null()
+        {
+            path = CStr::from_ptr(path_ptr)
+                .to_str()
+                .ok()
+                .filter(|p| !p.is_empty())
+                .map(PathBuf::from);
+        }
+    }
+
+    calldata_free(&mut calldata);
+    path
+}
+
 impl ObsOutputTraitSealed for ObsReplayBufferOutputRef {
     fn new(mut output: OutputInfo, runtime: ObsRuntime) -> Result<Self, ObsError> {
-        output.id = ObsString::new("replay_buffer");
+        output.id = obs_string!("replay_buffer");
         let output = ObsOutputRef::new(output, runtime.clone())?;
 
-        let replay_signal_manager = ObsReplayOutputSignals::new(&output.as_ptr(), runtime)?;
+        let replay_signal_manager = ObsReplayOutputSignals::new(&output.as_ptr(), runtime.clone())?;
+
+        let (saved_paths, _) = broadcast::channel(16);
+        let output_ptr = output.as_ptr();
+        let sender = saved_paths.clone();
+        let saved_connection = replay_signal_manager.connect_raw("saved", move |_calldata| {
+            #[allow(unknown_lints)]
+            #[allow(ensure_obs_call_in_runtime)]
+            // Safety: The connection keeps the output alive and the signal is emitted by the output
+            // itself, proc handlers of libobs can be called from any thread.
+            let path = unsafe { last_replay_path(output_ptr.get_ptr_unchecked()) };
+            match path {
+                Some(path) => {
+                    // Sending only fails if there are no receivers, which is fine
+                    let _ = sender.send(path);
+                }
+                None => log::warn!("Replay buffer was saved, but the path could not be read"),
+            }
+        })?;
+
         Ok(Self {
+            _saved_connection: Arc::new(saved_connection),
             replay_signal_manager: Arc::new(replay_signal_manager),
+            saved_paths,
             output,
         })
     }
@@ -50,10 +114,10 @@ impl ObsOutputTraitSealed for ObsReplayBufferOutputRef {
 forward_obs_object_impl!(ObsReplayBufferOutputRef, output, *mut libobs::obs_output);
 forward_obs_output_impl!(ObsReplayBufferOutputRef, output);
 
-impl_signal_manager!(|ptr: SmartPointerSendable<*mut libobs::obs_output>| {
+impl_signal_manager!(|ptr: SmartPointerSendable<*mut libobs::obs_output>, token: ObsThreadToken| {
     unsafe {
         // Safety: Again, it carries a reference of the drop guard so we must have a valid pointer
-        libobs::obs_output_get_signal_handler(ptr.get_ptr())
+        libobs::obs_output_get_signal_handler(ptr.get_ptr(token))
     }
 }, ObsReplayOutputSignals for *mut libobs::obs_output, [
     "saved": {}
@@ -63,63 +127,160 @@ impl ObsReplayBufferOutputRef {
     pub fn replay_signals(&self) -> &Arc<ObsReplayOutputSignals> {
         &self.replay_signal_manager
     }
-    /// Saves the current replay buffer content to disk.
-    ///
-    /// # Implementation Details
-    /// This method:
-    /// 1. Accesses the OBS procedure handler for the output
-    /// 2. Calls the "save" procedure to trigger saving the replay
-    /// 3. Calls the "get_last_replay" procedure to retrieve the saved file path
-    /// 4. Extracts the path string from the calldata and returns it
+
+    /// Subscribes to the paths of saved replays. A path is sent every time the replay buffer
+    /// finished writing a file, no matter if the save was triggered by this crate or a hotkey.
+    pub fn on_saved(&self) -> broadcast::Receiver<PathBuf> {
+        self.saved_paths.subscribe()
+    }
+
+    /// Returns the path of the last saved replay, `None` if no replay has been saved yet.
+    pub fn last_replay_path(&self) -> Result<Option<Box<Path>>, ObsError> {
+        let output_ptr = self.as_ptr();
+        let path = run_with_obs!(self.runtime().clone(), (output_ptr), move |token| unsafe {
+            // Safety: The output is valid because of the smart pointer
+            last_replay_path(output_ptr.get_ptr(token))
+        })?;
+
+        Ok(path.map(PathBuf::into_boxed_path))
+    }
+
+    /// Triggers the "save" procedure of the replay buffer.
+    fn trigger_save(&self) -> Result<(), ObsError> {
+        let output_ptr = self.as_ptr();
+        run_with_obs!(self.runtime().clone(), (output_ptr), move |token| {
+            unsafe {
+                // Safety: The output is valid because of the smart pointer
+                let ph = libobs::obs_output_get_proc_handler(output_ptr.get_ptr(token));
+                if ph.is_null() {
+                    return Err(ObsError::OutputSaveBufferFailure(
+                        "Failed to get proc handler.".to_string(),
+                    ));
+                }
+
+                let mut calldata: calldata_t = std::mem::zeroed();
+                let ok = libobs::proc_handler_call(ph, c"save".as_ptr(), &mut calldata);
+                calldata_free(&mut calldata);
+
+                if !ok {
+                    return Err(ObsError::OutputSaveBufferFailure(
+                        "Failed to call the save procedure.".to_string(),
+                    ));
+                }
+            }
+
+            Ok(())
+        })?
+    }
+
+    /// Checks that the replay buffer is active and triggers a save. Returns receivers for
+    /// the "saved" and "stop" signals, which are subscribed before the save is triggered.
+    #[allow(clippy::type_complexity)]
+    fn start_save(
+        &self,
+    ) -> Result<
+        (
+            ObsSignalReceiver<impl Clone + Send + 'static>,
+            ObsSignalReceiver<impl Clone + Send + 'static>,
+        ),
+        ObsError,
+    > {
+        if !self.is_active()? {
+            return Err(ObsError::OutputSaveBufferFailure(
+                "The replay buffer is not active.".to_string(),
+            ));
+        }
+
+        // Subscribe before saving, so the signals can't be emitted before we are listening
+        let saved = self.replay_signals().on_saved()?;
+        let stopped = self.signals().on_stop()?;
+        self.trigger_save()?;
+
+        Ok((saved, stopped))
+    }
+
+    /// Blocks until the replay has been saved, the replay buffer stopped or the timeout elapsed.
+    fn wait_for_save<S: Clone, T: Clone>(
+        &self,
+        mut saved: ObsSignalReceiver<S>,
+        mut stopped: ObsSignalReceiver<T>,
+        timeout: Duration,
+    ) -> Result<Box<Path>, ObsError> {
+        log::trace!("Waiting for 'saved' signal from replay buffer output...");
+        let deadline = Instant::now() + timeout;
+        loop {
+            // A lagged receiver still means that a replay has been saved
+            if saved.try_recv()?.is_some() {
+                return self.last_replay_path()?.ok_or_else(|| {
+                    ObsError::OutputSaveBufferFailure(
+                        "The replay has been saved, but its path could not be read.".to_string(),
+                    )
+                });
+            }
+
+            if stopped.try_recv()?.is_some() {
+                return Err(ObsError::OutputSaveBufferFailure(
+                    "The replay buffer stopped before the replay was saved.".to_string(),
+                ));
+            }
+
+            let now = Instant::now();
+            if now >= deadline {
+                return Err(ObsError::OutputSaveBufferFailure(format!(
+                    "The replay was not saved within {:?}.",
+                    timeout
+                )));
+            }
+
+            std::thread::sleep((deadline - now).min(Duration::from_millis(10)));
+        }
+    }
+
+    /// Saves the current replay buffer content to disk and blocks until the file is fully written,
+    /// at most for `DEFAULT_REPLAY_SAVE_TIMEOUT`.
     ///
     /// # Returns
     /// * `Ok(Box<Path>)` - The path to the saved replay file
-    /// * `Err(ObsError)` - Various errors that might occur during the saving process:
-    ///   - Failure to get procedure handler
-    ///   - Failure to call "save" procedure
-    ///   - Failure to call "get_last_replay" procedure
-    ///   - Failure to extract the path from calldata
+    /// * `Err(ObsError)` - If the replay buffer is not active, the save procedure could not be called,
+    ///   the replay buffer stopped before the file was written or the timeout elapsed
     pub fn save_buffer(&self) -> Result<Box<Path>, ObsError> {
+        self.save_buffer_timeout(DEFAULT_REPLAY_SAVE_TIMEOUT)
+    }
+
+    /// Like `Self::save_buffer`, but waits at most `timeout` for the file to be written.
+    pub fn save_buffer_timeout(&self, timeout: Duration) -> Result<Box<Path>, ObsError> {
         log::trace!("Saving replay buffer...");
-        let output_ptr = self.as_ptr();
+        let (saved, stopped) = self.start_save()?;
+        self.wait_for_save(saved, stopped, timeout)
+    }
 
-        log::trace!("Getting procedure handler for replay buffer output...");
-        let proc_handler = run_with_obs!(self.runtime().clone(), (output_ptr), move || {
-            // Safety: At this point, output_ptr MUST be a valid pointer as we haven't released the output yet.
-            let ph = unsafe { libobs::obs_output_get_proc_handler(output_ptr.get_ptr()) };
-            if ph.is_null() {
-                return Err(ObsError::OutputSaveBufferFailure(
-                    "Failed to get proc handler.".to_string(),
-                ));
-            }
-            Ok(Sendable(ph))
-        })??;
+    /// Saves the current replay buffer content to disk. The returned future resolves
+    /// with the path of the replay once the file is fully written, with the same errors
+    /// and timeout as `Self::save_buffer`.
+    ///
+    /// The save is triggered immediately, even if the future is never awaited.
+    pub fn save_buffer_async(
+        &self,
+    ) -> Result<impl Future<Output = Result<Box<Path>, ObsError>> + Send + 'static, ObsError> {
+        let (saved, stopped) = self.start_save()?;
 
-        log::trace!("Calling 'save' procedure on replay buffer output...");
-        // Safety: we know that the proc handler is valid because we got it from OBS earlier
-        unsafe { self.runtime().call_proc_handler(&proc_handler, "save")? };
+        // The signals are raced on a separate thread, as the runtime of the caller is unknown
+        let (sender, receiver) = tokio::sync::oneshot::channel();
+        let output = self.clone();
+        std::thread::Builder::new()
+            .name("obs-replay-save".to_string())
+            .spawn(move || {
+                let _ =
+                    sender.send(output.wait_for_save(saved, stopped, DEFAULT_REPLAY_SAVE_TIMEOUT));
+            })
+            .map_err(|e| ObsError::IoError(e.into()))?;
 
-        log::trace!("Waiting for 'saved' signal from replay buffer output...");
-        self.replay_signals()
-            .on_saved()?
-            .blocking_recv()
-            .map_err(|_e| {
+        Ok(async move {
+            receiver.await.map_err(|_e| {
                 ObsError::OutputSaveBufferFailure(
                     "Failed to receive saved replay buffer path.".to_string(),
                 )
-            })?;
-
-        log::trace!("Retrieving last replay path from replay buffer output...");
-        // Safety: We know that the proc handler is valid because we got it from OBS earlier
-        let mut calldata = unsafe {
-            self.runtime()
-                .call_proc_handler(&proc_handler, "get_last_replay")?
-        };
-
-        log::trace!("Extracting path from calldata...");
-        let path = calldata.get_string("path")?;
-        let path = PathBuf::from(path);
-
-        Ok(path.into_boxed_path())
+            })?
+        })
     }
 }
@@ -1,18 +1,26 @@
 use std::{
     collections::HashMap,
-    ffi::CStr,
+    ffi::{c_void, CStr},
     fmt::Debug,
     sync::{Arc, RwLock},
+    time::Duration,
 };
 
+use libobs::calldata_t;
+
 use crate::{
+    crash_handler::{report as crash_report, ObsCrashReportOutput},
     data::object::ObsObjectTrait,
     encoders::{audio::ObsAudioEncoder, video::ObsVideoEncoder},
     enums::ObsOutputStopSignal,
+    logger::{attach_logs_since, log_history_position},
     macros::trait_with_optional_send_sync,
     run_with_obs,
     runtime::ObsRuntime,
-    utils::{AudioEncoderInfo, ObsError, OutputInfo, VideoEncoderInfo},
+    stats::{ObsStreamHealthMonitor, ObsStreamHealthThresholds},
+    unsafe_send::Sendable,
+    utils::{calldata_free, AudioEncoderInfo, ObsError, ObsString, OutputInfo, VideoEncoderInfo},
+    view::ObsViewRef,
 };
 
 use super::ObsOutputSignals;
@@ -38,7 +46,38 @@ pub trait ObsOutputTrait: ObsOutputTraitSealed + ObsObjectTrait<*mut libobs::obs
     fn signals(&self) -> &Arc<ObsOutputSignals>;
 
     fn video_encoder(&self) -> &Arc<RwLock<Option<Arc<ObsVideoEncoder>>>>;
+    /// Video encoders at index 1 and above, see `set_video_encoder_indexed`.
+    fn indexed_video_encoders(&self) -> &Arc<RwLock<HashMap<usize, Arc<ObsVideoEncoder>>>>;
     fn audio_encoders(&self) -> &Arc<RwLock<HashMap<usize, Arc<ObsAudioEncoder>>>>;
+    fn view(&self) -> &Arc<RwLock<Option<ObsViewRef>>>;
+
+    /// Returns the view this output encodes, `None` if it encodes the main canvas.
+    fn get_current_view(&self) -> Result<Option<ObsViewRef>, ObsError> {
+        let curr = self
+            .view()
+            .read()
+            .map_err(|e| ObsError::LockError(e.to_string()))?;
+
+        Ok(curr.clone())
+    }
+
+    /// Makes the video encoder of this output encode the given view instead of the
+    /// main canvas, `None` switches back to the main canvas. The view has to be added
+    /// (see `ObsViewRef::add`) before the output is started.
+    ///
+    /// Fails if the output is active.
+    fn set_view(&mut self, view: Option<ObsViewRef>) -> Result<(), ObsError> {
+        if self.is_active()? {
+            return Err(ObsError::OutputAlreadyActive);
+        }
+
+        *self
+            .view()
+            .write()
+            .map_err(|e| ObsError::LockError(e.to_string()))? = view;
+
+        Ok(())
+    }
 
     /// Returns the current video encoder attached to this output, if any.
     fn get_current_video_encoder(&self) -> Result<Option<Arc<ObsVideoEncoder>>, ObsError> {
@@ -79,10 +118,13 @@ pub trait ObsOutputTrait: ObsOutputTraitSealed + ObsObjectTrait<*mut libobs::obs
         let encoder_ptr = encoder.as_ptr();
         let runtime = self.runtime().clone();
 
-        run_with_obs!(runtime, (output_ptr, encoder_ptr), move || {
+        run_with_obs!(runtime, (output_ptr, encoder_ptr), move |token| {
             unsafe {
                 // Safety: This is safe because we are only using smart pointers.
-                libobs::obs_output_set_video_encoder(output_ptr.get_ptr(), encoder_ptr.get_ptr());
+                libobs::obs_output_set_video_encoder(
+                    output_ptr.get_ptr(token),
+                    encoder_ptr.get_ptr(token),
+                );
             }
         })?;
 
@@ -94,6 +136,106 @@ pub trait ObsOutputTrait: ObsOutputTraitSealed + ObsObjectTrait<*mut libobs::obs
         Ok(())
     }
 
+    /// Attaches a video encoder at the given index. Outputs that support multiple video tracks
+    /// (e.g. `ffmpeg_output` or `ffmpeg_mpegts_muxer`) write one track per encoder, index `0` is the
+    /// same encoder as set by `set_video_encoder`.
+    ///
+    /// Fails if the output is active, the index is out of range or the output does not support
+    /// multiple video encoders.
+    fn set_video_encoder_indexed(
+        &mut self,
+        encoder: Arc<ObsVideoEncoder>,
+        idx: usize,
+    ) -> Result<(), ObsError> {
+        if idx == 0 {
+            return self.set_video_encoder(encoder);
+        }
+
+        if self.is_active()? {
+            return Err(ObsError::OutputAlreadyActive);
+        }
+
+        if idx >= libobs::MAX_OUTPUT_VIDEO_ENCODERS as usize {
+            return Err(ObsError::InvalidOperation(format!(
+                "Video encoder index {} is out of range, there are only {} slots",
+                idx,
+                libobs::MAX_OUTPUT_VIDEO_ENCODERS
+            )));
+        }
+
+        let output_ptr = self.as_ptr();
+        let encoder_ptr = encoder.as_ptr();
+        let runtime = self.runtime().clone();
+        run_with_obs!(runtime, (output_ptr, encoder_ptr), move |token| {
+            unsafe {
+                // Safety: This is safe because we are only using smart pointers.
+                let flags = libobs::obs_output_get_flags(output_ptr.get_ptr(token));
+                if flags & libobs::OBS_OUTPUT_MULTI_TRACK_VIDEO == 0 {
+                    return Err(ObsError::InvalidOperation(
+                        "The output does not support multiple video encoders".into(),
+                    ));
+                }
+
+                libobs::obs_output_set_video_encoder2(
+                    output_ptr.get_ptr(token),
+                    encoder_ptr.get_ptr(token),
+                    idx,
+                );
+            }
+
+            Ok(())
+        })??;
+
+        self.indexed_video_encoders()
+            .write()
+            .map_err(|e| ObsError::LockError(e.to_string()))?
+            .insert(idx, encoder);
+
+        Ok(())
+    }
+
+    /// Returns the video encoder attached at the given index, if any.
+    fn get_video_encoder_indexed(
+        &self,
+        idx: usize,
+    ) -> Result<Option<Arc<ObsVideoEncoder>>, ObsError> {
+        if idx == 0 {
+            return self.get_current_video_encoder();
+        }
+
+        Ok(self
+            .indexed_video_encoders()
+            .read()
+            .map_err(|e| ObsError::LockError(e.to_string()))?
+            .get(&idx)
+            .cloned())
+    }
+
+    /// Sets the audio tracks a raw (non-encoded) multi-track output receives, bit `n` of the
+    /// mask enables mixer `n`. Encoded outputs use the mixer index of their audio encoders instead.
+    ///
+    /// Fails if the output is active.
+    fn set_audio_mixers(&self, mask: usize) -> Result<(), ObsError> {
+        if self.is_active()? {
+            return Err(ObsError::OutputAlreadyActive);
+        }
+
+        let output_ptr = self.as_ptr();
+        run_with_obs!(self.runtime(), (output_ptr), move |token| unsafe {
+            // Safety: output_ptr is valid because of SmartPointer
+            libobs::obs_output_set_mixers(output_ptr.get_ptr(token), mask);
+        })
+    }
+
+    /// Returns the audio track mask of this output, see `set_audio_mixers`.
+    fn audio_mixers(&self) -> Result<usize, ObsError> {
+        let output_ptr = self.as_ptr();
+        run_with_obs!(self.runtime(), (output_ptr), move |token| unsafe {
+            // Safety: output_ptr is valid because of SmartPointer
+            libobs::obs_output_get_mixers(output_ptr.get_ptr(token))
+        })
+    }
+
     /// Creates and attaches a new audio encoder for the given mixer index. Fails if output active.
     fn create_and_set_audio_encoder(
         &mut self,
@@ -124,12 +266,12 @@ pub trait ObsOutputTrait: ObsOutputTraitSealed + ObsObjectTrait<*mut libobs::obs
         let encoder_ptr = encoder.as_ptr();
         let output_ptr = self.as_ptr();
         let runtime = self.runtime().clone();
-        run_with_obs!(runtime, (output_ptr, encoder_ptr), move || {
+        run_with_obs!(runtime, (output_ptr, encoder_ptr), move |token| {
             unsafe {
                 // Safety: This is safe because we are only using smart pointers.
                 libobs::obs_output_set_audio_encoder(
-                    output_ptr.get_ptr(),
-                    encoder_ptr.get_ptr(),
+                    output_ptr.get_ptr(token),
+                    encoder_ptr.get_ptr(token),
                     mixer_idx,
                 );
             }
@@ -150,12 +292,26 @@ pub trait ObsOutputTrait: ObsOutputTraitSealed + ObsObjectTrait<*mut libobs::obs
             return Err(ObsError::OutputAlreadyActive);
         }
 
-        let vid_encoder_ptr = self
+        let mut vid_encoders = self
             .video_encoder()
             .read()
             .map_err(|e| ObsError::LockError(e.to_string()))?
-            .as_ref()
-            .map(|enc| enc.as_ptr());
+            .clone()
+            .into_iter()
+            .collect::<Vec<_>>();
+
+        vid_encoders.extend(
+            self.indexed_video_encoders()
+                .read()
+                .map_err(|e| ObsError::LockError(e.to_string()))?
+                .values()
+                .cloned(),
+        );
+
+        let vid_encoder_pointers = vid_encoders
+            .iter()
+            .map(|enc| enc.as_ptr())
+            .collect::<Vec<_>>();
 
         let audio_encoder_pointers = self
             .audio_encoders()
@@ -165,26 +321,52 @@ pub trait ObsOutputTrait: ObsOutputTraitSealed + ObsObjectTrait<*mut libobs::obs
             .map(|enc| enc.as_ptr())
             .collect::<Vec<_>>();
 
+        let view = self.get_current_view()?;
+        // Holding the lock on the video of the view prevents it from being removed during the start
+        let view_video_guard = view.as_ref().map(|view| view.video_lock()).transpose()?;
+        let view_video = match (&view, &view_video_guard) {
+            (Some(view), Some(video)) => {
+                let video = (**video).clone().ok_or_else(|| {
+                    ObsError::InvalidOperation(
+                        "The view of this output has not been added yet".into(),
+                    )
+                })?;
+
+                // The view can't be removed while these encoders are active
+                view.register_encoders(&vid_encoders)?;
+                Some(video)
+            }
+            _ => None,
+        };
+
         let output_ptr = self.as_ptr();
         let runtime = self.runtime().clone();
+        let log_position = log_history_position();
         let res = run_with_obs!(
             runtime,
-            (output_ptr, vid_encoder_ptr, audio_encoder_pointers),
-            move || {
-                if let Some(vid_encoder_ptr) = vid_encoder_ptr {
+            (
+                output_ptr,
+                vid_encoder_pointers,
+                audio_encoder_pointers,
+                view_video
+            ),
+            move |token| {
+                for vid_encoder_ptr in vid_encoder_pointers {
                     unsafe {
-                        // Safety: vid_encoder_ptr is valid because of SmartPointer
-                        libobs::obs_encoder_set_video(
-                            vid_encoder_ptr.get_ptr(),
-                            libobs::obs_get_video(),
-                        );
+                        // Safety: vid_encoder_ptr is valid because of SmartPointer, the video
+                        // of the view is valid as long as the view has not been removed
+                        let video = match view_video {
+                            Some(Sendable(video)) => video,
+                            None => libobs::obs_get_video(),
+                        };
+                        libobs::obs_encoder_set_video(vid_encoder_ptr.get_ptr(token), video);
                     }
                 }
                 for audio_encoder_ptr in audio_encoder_pointers {
                     unsafe {
                         // Safety: audio_encoder_ptr is valid because of SmartPointer
                         libobs::obs_encoder_set_audio(
-                            audio_encoder_ptr.get_ptr(),
+                            audio_encoder_ptr.get_ptr(token),
                             libobs::obs_get_audio(),
                         );
                     }
@@ -192,20 +374,21 @@ pub trait ObsOutputTrait: ObsOutputTraitSealed + ObsObjectTrait<*mut libobs::obs
 
                 unsafe {
                     // Safety: output_ptr is valid because of SmartPointer
-                    libobs::obs_output_start(output_ptr.get_ptr())
+                    libobs::obs_output_start(output_ptr.get_ptr(token))
                 }
             }
         )?;
 
         if res {
+            crash_report::output_started(crash_report_output(self)?);
             return Ok(());
         }
 
         let runtime = self.runtime().clone();
-        let err = run_with_obs!(runtime, (output_ptr), move || {
+        let err = run_with_obs!(runtime, (output_ptr), move |token| {
             let err = unsafe {
                 // Safety: The output pointer must be valid because of SmartPointer
-                libobs::obs_output_get_last_error(output_ptr.get_ptr())
+                libobs::obs_output_get_last_error(output_ptr.get_ptr(token))
             };
 
             if err.is_null() {
@@ -218,7 +401,11 @@ pub trait ObsOutputTrait: ObsOutputTraitSealed + ObsObjectTrait<*mut libobs::obs
             err
         })?;
 
-        Err(ObsError::OutputStartFailure(Some(err)))
+        // The last error is often generic, the actual cause is usually logged by the output or its encoders
+        Err(attach_logs_since(
+            ObsError::OutputStartFailure(Some(err)).in_function("obs_output_start"),
+            log_position,
+        ))
     }
 
     fn set_paused(&self, should_pause: bool) -> Result<(), ObsError> {
@@ -237,23 +424,23 @@ pub trait ObsOutputTrait: ObsOutputTraitSealed + ObsObjectTrait<*mut libobs::obs
             self.signals().on_unpause()?
         };
 
-        let res = run_with_obs!(runtime, (output_ptr), move || {
+        let res = run_with_obs!(runtime, (output_ptr), move |token| {
             unsafe {
                 // Safety: output_ptr is valid because of SmartPointer
-                libobs::obs_output_pause(output_ptr.get_ptr(), should_pause)
+                libobs::obs_output_pause(output_ptr.get_ptr(token), should_pause)
             }
         })?;
 
         if res {
-            rx.blocking_recv().map_err(|_| ObsError::NoSenderError)?;
+            rx.blocking_recv_signal()?;
 
             Ok(())
         } else {
             let runtime = self.runtime().clone();
-            let err = run_with_obs!(runtime, (output_ptr), move || {
+            let err = run_with_obs!(runtime, (output_ptr), move |token| {
                 let err = unsafe {
                     // Safety: output_ptr is valid because of SmartPointer
-                    libobs::obs_output_get_last_error(output_ptr.get_ptr())
+                    libobs::obs_output_get_last_error(output_ptr.get_ptr(token))
                 };
 
                 if err.is_null() {
@@ -281,57 +468,356 @@ pub trait ObsOutputTrait: ObsOutputTraitSealed + ObsObjectTrait<*mut libobs::obs
 
     /// Stops the output and waits for stop and deactivate signals.
     fn stop(&mut self) -> Result<(), ObsError> {
+        stop_output(self)
+    }
+
+    /// Stops the output like `stop`, but force stops it if the stop signal doesn't arrive
+    /// within `timeout`, e.g. because an encoder is stuck. Force stopping skips the remaining
+    /// packets, but the recording is still finalized.
+    ///
+    /// Returns whether the output had to be force stopped. Unlike `stop`, an output that is
+    /// not active is not an error.
+    fn stop_with_force(&mut self, timeout: Duration) -> Result<bool, ObsError> {
+        stop_output_with_force(self, timeout)
+    }
+
+    /// Returns whether the output is currently active.
+    fn is_active(&self) -> Result<bool, ObsError> {
         let output_ptr = self.as_ptr();
         let runtime = self.runtime().clone();
-        let output_active = run_with_obs!(runtime, (output_ptr), move || {
+        let output_active = run_with_obs!(runtime, (output_ptr), move |token| {
             unsafe {
                 // Safety: output_ptr is valid because of SmartPointer
-                libobs::obs_output_active(output_ptr.get_ptr())
+                libobs::obs_output_active(output_ptr.get_ptr(token))
             }
         })?;
 
-        if !output_active {
-            return Err(ObsError::OutputStopFailure(Some(
-                "Output is not active.".to_string(),
-            )));
-        }
+        Ok(output_active)
+    }
+
+    /// Returns whether the output is currently paused.
+    fn is_paused(&self) -> Result<bool, ObsError> {
+        let output_ptr = self.as_ptr();
+        let runtime = self.runtime().clone();
+        run_with_obs!(runtime, (output_ptr), move |token| {
+            unsafe {
+                // Safety: output_ptr is valid because of SmartPointer
+                libobs::obs_output_paused(output_ptr.get_ptr(token))
+            }
+        })
+    }
+
+    /// Returns the total time the output has been paused for since it was started.
+    fn pause_offset(&self) -> Result<Duration, ObsError> {
+        let output_ptr = self.as_ptr();
+        let runtime = self.runtime().clone();
+        let offset = run_with_obs!(runtime, (output_ptr), move |token| {
+            unsafe {
+                // Safety: output_ptr is valid because of SmartPointer
+                libobs::obs_output_get_pause_offset(output_ptr.get_ptr(token))
+            }
+        })?;
 
-        let mut rx = self.signals().on_stop()?;
-        let mut rx_deactivate = self.signals().on_deactivate()?;
+        Ok(Duration::from_nanos(offset))
+    }
 
+    /// Returns the number of frames the output has received and dropped since it was started.
+    fn frame_counts(&self) -> Result<(u32, u32), ObsError> {
+        let output_ptr = self.as_ptr();
         let runtime = self.runtime().clone();
-        run_with_obs!(runtime, (output_ptr), move || {
+        let (total, dropped) = run_with_obs!(runtime, (output_ptr), move |token| {
             unsafe {
                 // Safety: output_ptr is valid because of SmartPointer
-                libobs::obs_output_stop(output_ptr.get_ptr())
+                (
+                    libobs::obs_output_get_total_frames(output_ptr.get_ptr(token)),
+                    libobs::obs_output_get_frames_dropped(output_ptr.get_ptr(token)),
+                )
             }
         })?;
 
-        let signal = rx.blocking_recv().map_err(|_| ObsError::NoSenderError)?;
+        Ok((total.max(0) as u32, dropped.max(0) as u32))
+    }
 
-        log::trace!("Received stop signal: {:?}", signal);
-        if signal != ObsOutputStopSignal::Success {
-            return Err(ObsError::OutputStopFailure(Some(signal.to_string())));
-        }
+    /// Returns the congestion of the connection of a streaming output, from 0.0 (none) to 1.0.
+    /// Outputs that do not send data over the network always report 0.0.
+    fn congestion(&self) -> Result<f32, ObsError> {
+        let output_ptr = self.as_ptr();
+        let runtime = self.runtime().clone();
+        run_with_obs!(runtime, (output_ptr), move |token| {
+            unsafe {
+                // Safety: output_ptr is valid because of SmartPointer
+                libobs::obs_output_get_congestion(output_ptr.get_ptr(token))
+            }
+        })
+    }
+
+    /// Enables the bandwidth test mode of the service of this output. Services that support it
+    /// (e.g. Twitch) discard the stream, so the connection can be tested without going live.
+    ///
+    /// Takes effect the next time the output is started.
+    fn enable_bandwidth_test(&self, enabled: bool) -> Result<(), ObsError> {
+        let output_ptr = self.as_ptr();
+        let runtime = self.runtime().clone();
+        let has_service = run_with_obs!(runtime, (output_ptr), move |token| {
+            unsafe {
+                // Safety: output_ptr is valid because of SmartPointer, the service is checked
+                // for null and the settings are released after they were applied.
+                let service = libobs::obs_output_get_service(output_ptr.get_ptr(token));
+                if service.is_null() {
+                    return false;
+                }
 
-        rx_deactivate
-            .blocking_recv()
-            .map_err(|_| ObsError::NoSenderError)?;
+                let settings = libobs::obs_data_create();
+                libobs::obs_data_set_bool(settings, c"bwtest".as_ptr(), enabled);
+                libobs::obs_service_update(service, settings);
+                libobs::obs_data_release(settings);
+
+                true
+            }
+        })?;
+
+        if !has_service {
+            return Err(ObsError::InvalidOperation(
+                "The output has no service to enable the bandwidth test for".to_string(),
+            ));
+        }
 
         Ok(())
     }
 
-    /// Returns whether the output is currently active.
-    fn is_active(&self) -> Result<bool, ObsError> {
+    /// Reports when the stream of this output becomes unstable, see [`ObsStreamHealthMonitor`].
+    fn monitor_health(
+        &self,
+        thresholds: ObsStreamHealthThresholds,
+        poll_interval: Duration,
+    ) -> Result<ObsStreamHealthMonitor, ObsError> {
+        ObsStreamHealthMonitor::new(self, thresholds, poll_interval)
+    }
+
+    /// Returns the duration of the recorded video, derived from the frame count and the frame rate of the output.
+    ///
+    /// Frames are not sent to the output while it is paused, so unlike the wall clock time since
+    /// the output has been started, this duration excludes the paused time and is suited for recording timers.
+    fn recording_duration(&self) -> Result<Duration, ObsError> {
         let output_ptr = self.as_ptr();
         let runtime = self.runtime().clone();
-        let output_active = run_with_obs!(runtime, (output_ptr), move || {
+        let (frames, fps) = run_with_obs!(runtime, (output_ptr), move |token| {
             unsafe {
-                // Safety: output_ptr is valid because of SmartPointer
-                libobs::obs_output_active(output_ptr.get_ptr())
+                // Safety: output_ptr is valid because of SmartPointer, the video output is
+                // checked for null before its frame rate is read.
+                let frames = libobs::obs_output_get_total_frames(output_ptr.get_ptr(token));
+                let video = libobs::obs_output_video(output_ptr.get_ptr(token));
+                let fps = if video.is_null() {
+                    0.0
+                } else {
+                    libobs::video_output_get_frame_rate(video)
+                };
+
+                (frames, fps)
             }
         })?;
 
-        Ok(output_active)
+        Ok(frames_to_duration(frames.max(0) as u64, fps))
     }
+
+    /// Adds a chapter marker with the given name at the current position of the recording,
+    /// like the "Add Chapter Marker" hotkey of OBS.
+    ///
+    /// Only recording outputs that support chapters (e.g. the hybrid MP4 output) provide the
+    /// `add_chapter` procedure, for any other output this returns an error.
+    fn add_chapter_marker(&self, name: &str) -> Result<(), ObsError> {
+        let name = ObsString::new(name);
+        let output_ptr = self.as_ptr();
+        let runtime = self.runtime().clone();
+        let added = run_with_obs!(runtime, (output_ptr, name), move |token| {
+            unsafe {
+                // Safety: output_ptr is valid because of SmartPointer, the proc handler is checked
+                // for null and the calldata is freed after the call.
+                let ph = libobs::obs_output_get_proc_handler(output_ptr.get_ptr(token));
+                if ph.is_null() {
+                    return false;
+                }
+
+                let name = name.as_ptr().0;
+                let mut calldata: calldata_t = std::mem::zeroed();
+                libobs::calldata_set_data(
+                    &mut calldata,
+                    c"chapter_name".as_ptr(),
+                    name as *const c_void,
+                    CStr::from_ptr(name).to_bytes_with_nul().len(),
+                );
+                let ok = libobs::proc_handler_call(ph, c"add_chapter".as_ptr(), &mut calldata);
+                calldata_free(&mut calldata);
+
+                ok
+            }
+        })?;
+
+        if !added {
+            return Err(ObsError::InvalidOperation(format!(
+                "Output {} does not support chapter markers",
+                self.id()
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+pub(super) fn frames_to_duration(frames: u64, fps: f64) -> Duration {
+    if !fps.is_finite() || fps <= 0.0 {
+        return Duration::ZERO;
+    }
+
+    Duration::from_secs_f64(frames as f64 / fps)
+}
+
+/// Stops the output and waits for stop and deactivate signals.
+///
+/// Only needs a shared reference, so outputs that are stored behind an `Arc` can be stopped as well.
+pub(crate) fn stop_output<T: ObsOutputTrait + ?Sized>(output: &T) -> Result<(), ObsError> {
+    let output_ptr = output.as_ptr();
+    let runtime = output.runtime().clone();
+    let output_active = run_with_obs!(runtime, (output_ptr), move |token| {
+        unsafe {
+            // Safety: output_ptr is valid because of SmartPointer
+            libobs::obs_output_active(output_ptr.get_ptr(token))
+        }
+    })?;
+
+    if !output_active {
+        return Err(ObsError::OutputStopFailure(Some(
+            "Output is not active.".to_string(),
+        )));
+    }
+
+    let mut rx = output.signals().on_stop()?;
+    let mut rx_deactivate = output.signals().on_deactivate()?;
+
+    let runtime = output.runtime().clone();
+    run_with_obs!(runtime, (output_ptr), move |token| {
+        unsafe {
+            // Safety: output_ptr is valid because of SmartPointer
+            libobs::obs_output_stop(output_ptr.get_ptr(token))
+        }
+    })?;
+    crash_report::output_stopped(&output.name().to_string());
+
+    let signal = rx.blocking_recv_signal()?;
+
+    log::trace!("Received stop signal: {:?}", signal);
+    if signal != ObsOutputStopSignal::Success {
+        return Err(ObsError::OutputStopFailure(Some(signal.to_string())));
+    }
+
+    rx_deactivate.blocking_recv_signal()?;
+
+    Ok(())
+}
+
+pub(crate) fn stop_output_with_force<T: ObsOutputTrait + ?Sized>(
+    output: &T,
+    timeout: Duration,
+) -> Result<bool, ObsError> {
+    let output_ptr = output.as_ptr();
+    let runtime = output.runtime().clone();
+
+    let mut rx = output.signals().on_stop()?;
+    let mut rx_deactivate = output.signals().on_deactivate()?;
+
+    let output_active = run_with_obs!(runtime, (output_ptr), move |token| {
+        unsafe {
+            // Safety: output_ptr is valid because of SmartPointer
+            let output = output_ptr.get_ptr(token);
+            let active = libobs::obs_output_active(output);
+            if active {
+                libobs::obs_output_stop(output);
+            }
+
+            active
+        }
+    })?;
+
+    if !output_active {
+        return Ok(false);
+    }
+    crash_report::output_stopped(&output.name().to_string());
+
+    let mut forced = false;
+    let signal = match rx.blocking_recv_signal_timeout(timeout)? {
+        Some(signal) => signal,
+        None => {
+            log::warn!(
+                "Output {} did not stop within {:?}, force stopping it",
+                output.name(),
+                timeout
+            );
+
+            forced = true;
+            run_with_obs!(runtime, (output_ptr), move |token| {
+                unsafe {
+                    // Safety: output_ptr is valid because of SmartPointer
+                    libobs::obs_output_force_stop(output_ptr.get_ptr(token))
+                }
+            })?;
+
+            rx.blocking_recv_signal_timeout(timeout)?.ok_or_else(|| {
+                ObsError::OutputStopFailure(Some(
+                    "Output did not stop after it was force stopped".to_string(),
+                ))
+            })?
+        }
+    };
+
+    log::trace!("Received stop signal: {:?}", signal);
+    if !forced && signal != ObsOutputStopSignal::Success {
+        return Err(ObsError::OutputStopFailure(Some(signal.to_string())));
+    }
+
+    if rx_deactivate
+        .blocking_recv_signal_timeout(timeout)?
+        .is_none()
+    {
+        return Err(ObsError::OutputStopFailure(Some(
+            "Output did not deactivate after it stopped".to_string(),
+        )));
+    }
+
+    Ok(forced)
+}
+
+/// Describes this output and its encoders for crash reports.
+fn crash_report_output<T: ObsOutputTrait + ?Sized>(
+    output: &T,
+) -> Result<ObsCrashReportOutput, ObsError> {
+    let mut encoders = output
+        .video_encoder()
+        .read()
+        .map_err(|e| ObsError::LockError(e.to_string()))?
+        .iter()
+        .map(|enc| enc.id().to_string())
+        .collect::<Vec<_>>();
+
+    encoders.extend(
+        output
+            .indexed_video_encoders()
+            .read()
+            .map_err(|e| ObsError::LockError(e.to_string()))?
+            .values()
+            .map(|enc| enc.id().to_string()),
+    );
+    encoders.extend(
+        output
+            .audio_encoders()
+            .read()
+            .map_err(|e| ObsError::LockError(e.to_string()))?
+            .values()
+            .map(|enc| enc.id().to_string()),
+    );
+
+    Ok(ObsCrashReportOutput {
+        name: output.name().to_string(),
+        id: output.id().to_string(),
+        encoders,
+    })
 }
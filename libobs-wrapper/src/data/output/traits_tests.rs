@@ -0,0 +1,16 @@
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::super::traits::frames_to_duration;
+
+    #[test]
+    fn test_frames_to_duration() {
+        assert_eq!(frames_to_duration(120, 60.0), Duration::from_secs(2));
+        assert_eq!(
+            frames_to_duration(30_000, 30_000.0 / 1001.0),
+            Duration::from_secs_f64(1001.0)
+        );
+        assert_eq!(frames_to_duration(120, 0.0), Duration::ZERO);
+    }
+}
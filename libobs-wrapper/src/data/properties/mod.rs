@@ -8,6 +8,8 @@ mod enums;
 mod macros;
 pub mod prop_impl;
 pub mod types;
+mod watch;
+mod writer;
 
 use std::{collections::HashMap, ffi::CStr};
 
@@ -16,8 +18,11 @@ use macros::*;
 
 pub use enums::*;
 use types::*;
+pub use watch::ObsPropertiesWatcher;
+pub use writer::PropertyValueWriter;
 
 use crate::{
+    data::{ObsData, ObsDataPointers},
     impl_obs_drop, run_with_obs,
     runtime::ObsRuntime,
     unsafe_send::{Sendable, SmartPointerSendable},
@@ -86,6 +91,12 @@ pub trait ObsPropertyObjectPrivate {
         id: T,
         runtime: ObsRuntime,
     ) -> Result<SmartPointerSendable<*mut libobs::obs_properties_t>, ObsError>;
+
+    /// Returns the raw pointer of the OBS object the properties belong to.
+    /// This is `None` if the properties are not bound to an existing object (e.g. for builders).
+    fn get_object_ptr_raw(&self) -> Option<Sendable<*mut std::os::raw::c_void>> {
+        None
+    }
 }
 
 pub(crate) fn property_ptr_to_struct(
@@ -93,11 +104,11 @@ pub(crate) fn property_ptr_to_struct(
     runtime: ObsRuntime,
 ) -> Result<HashMap<String, ObsProperty>, ObsError> {
     let runtime_clone = runtime.clone();
-    run_with_obs!(runtime, (properties_raw, runtime_clone), move || {
+    run_with_obs!(runtime, (properties_raw, runtime_clone), move |token| {
         let mut result = HashMap::new();
         let mut property = unsafe {
             // Safety: Safe because of smart pointer
-            libobs::obs_properties_first(properties_raw.get_ptr())
+            libobs::obs_properties_first(properties_raw.get_ptr(token))
         };
         while !property.is_null() {
             let name = unsafe { libobs::obs_property_name(property) };
@@ -157,10 +168,96 @@ pub(crate) fn property_ptr_to_struct(
     })
 }
 
+/// Properties returned by `ObsPropertyObject::refresh_on_change`.
+#[derive(Debug, Clone)]
+pub struct ObsRefreshedProperties {
+    /// Whether the modified callback of the changed property requested a refresh,
+    /// meaning that dependent properties may have been added, removed or changed.
+    /// This is always `true` if no specific property was given.
+    pub needs_refresh: bool,
+    /// The properties after the modified callbacks have been run.
+    pub properties: HashMap<String, ObsProperty>,
+}
+
+/// Runs the modified callbacks of the given properties with the given settings.
+/// If `changed` is set, only the callback of that property is invoked, otherwise every callback is invoked
+/// by using `obs_properties_apply_settings`.
+pub(crate) fn apply_settings_to_properties(
+    properties_raw: &SmartPointerSendable<*mut obs_properties>,
+    settings: &mut ObsData,
+    changed: Option<ObsString>,
+) -> Result<bool, ObsError> {
+    let properties_raw = properties_raw.clone();
+    let settings_ptr = settings.as_ptr();
+
+    run_with_obs!(
+        settings.runtime(),
+        (properties_raw, settings_ptr, changed),
+        move |token| {
+            let Some(changed) = changed else {
+                unsafe {
+                    // Safety: Both pointers are valid because of smart pointers
+                    libobs::obs_properties_apply_settings(
+                        properties_raw.get_ptr(token),
+                        settings_ptr.get_ptr(token),
+                    );
+                }
+
+                return Ok(true);
+            };
+
+            let property = unsafe {
+                // Safety: The properties pointer is valid because of the smart pointer and the name is kept in scope
+                libobs::obs_properties_get(properties_raw.get_ptr(token), changed.as_ptr().0)
+            };
+
+            if property.is_null() {
+                return Err(ObsError::InvalidOperation(format!(
+                    "Property {} does not exist",
+                    changed
+                )));
+            }
+
+            let needs_refresh = unsafe {
+                // Safety: The property belongs to the properties which are kept alive by the smart pointer
+                libobs::obs_property_modified(property, settings_ptr.get_ptr(token))
+            };
+
+            Ok(needs_refresh)
+        }
+    )?
+}
+
 /// This trait is implemented for all obs objects that can have properties
 pub trait ObsPropertyObject: ObsPropertyObjectPrivate {
     /// Returns the properties of the object
     fn get_properties(&self) -> Result<HashMap<String, ObsProperty>, ObsError>;
+
+    /// Returns the properties of the object after running their modified callbacks with the given settings.
+    ///
+    /// Some properties change depending on the value of other properties (e.g. selecting a device reveals its resolutions),
+    /// so call this whenever a property was modified in your settings UI and re-render with the returned properties.
+    /// The modified callbacks may update `settings` as well.
+    ///
+    /// # Arguments
+    /// * `settings` - The settings the user is currently editing (not yet applied to the object)
+    /// * `changed` - The name of the property that was modified. If `None`, the callbacks of every property are run.
+    fn refresh_on_change(
+        &self,
+        settings: &mut ObsData,
+        changed: Option<&str>,
+    ) -> Result<ObsRefreshedProperties, ObsError> {
+        let properties_raw = self.get_properties_raw()?;
+        let needs_refresh =
+            apply_settings_to_properties(&properties_raw, settings, changed.map(ObsString::new))?;
+
+        let properties = property_ptr_to_struct(properties_raw, settings.runtime().clone())?;
+        Ok(ObsRefreshedProperties {
+            needs_refresh,
+            properties,
+        })
+    }
+
     fn get_properties_by_source_id<T: Into<ObsString> + Sync + Send>(
         id: T,
         runtime: &ObsRuntime,
@@ -2,7 +2,7 @@ use std::{collections::HashMap, sync::Arc};
 
 use crate::{
     data::{object::ObsObjectTrait, output::ObsOutputRef, properties::_ObsPropertiesDropGuard},
-    run_with_obs,
+    run_with_obs_background,
     runtime::ObsRuntime,
     sources::ObsSourceTrait,
     unsafe_send::{Sendable, SmartPointerSendable},
@@ -19,17 +19,24 @@ impl<K: ObsSourceTrait> ObsPropertyObject for K {
 }
 
 impl<K: ObsSourceTrait> ObsPropertyObjectPrivate for K {
+    fn get_object_ptr_raw(&self) -> Option<Sendable<*mut std::os::raw::c_void>> {
+        // Safety: The pointer is only passed to libobs in the runtime
+        Some(Sendable(
+            unsafe { self.as_ptr().get_ptr_unchecked() } as *mut _
+        ))
+    }
+
     fn get_properties_raw(
         &self,
     ) -> Result<SmartPointerSendable<*mut libobs::obs_properties_t>, ObsError> {
         let source_ptr = self.as_ptr();
         let runtime = self.runtime().clone();
 
-        let raw_ptr = run_with_obs!(runtime, (source_ptr), move || {
+        let raw_ptr = run_with_obs_background!(runtime, (source_ptr), move |token| {
             let source_ptr = source_ptr;
             let property_ptr = unsafe {
                 // Safety: Safe because of smart pointer
-                libobs::obs_source_properties(source_ptr.get_ptr())
+                libobs::obs_source_properties(source_ptr.get_ptr(token))
             };
 
             if property_ptr.is_null() {
@@ -52,7 +59,7 @@ impl<K: ObsSourceTrait> ObsPropertyObjectPrivate for K {
         runtime: ObsRuntime,
     ) -> Result<SmartPointerSendable<*mut libobs::obs_properties_t>, ObsError> {
         let id: ObsString = id.into();
-        let raw_ptr = run_with_obs!(runtime, (id), move || {
+        let raw_ptr = run_with_obs_background!(runtime, (id), move || {
             let id_ptr = id.as_ptr();
             let property_ptr = unsafe {
                 // Safety: Safe because of smart pointer
@@ -84,14 +91,21 @@ impl ObsPropertyObject for ObsOutputRef {
 }
 
 impl ObsPropertyObjectPrivate for ObsOutputRef {
+    fn get_object_ptr_raw(&self) -> Option<Sendable<*mut std::os::raw::c_void>> {
+        // Safety: The pointer is only passed to libobs in the runtime
+        Some(Sendable(
+            unsafe { self.as_ptr().get_ptr_unchecked() } as *mut _
+        ))
+    }
+
     fn get_properties_raw(
         &self,
     ) -> Result<SmartPointerSendable<*mut libobs::obs_properties_t>, ObsError> {
         let output_ptr = self.as_ptr().clone();
-        let ptr = run_with_obs!(self.runtime(), (output_ptr), move || {
+        let ptr = run_with_obs_background!(self.runtime(), (output_ptr), move |token| {
             let property_ptr = unsafe {
                 // Safety: Safe because of smart pointer
-                libobs::obs_output_properties(output_ptr.get_ptr())
+                libobs::obs_output_properties(output_ptr.get_ptr(token))
             };
 
             if property_ptr.is_null() {
@@ -114,7 +128,7 @@ impl ObsPropertyObjectPrivate for ObsOutputRef {
         runtime: ObsRuntime,
     ) -> Result<SmartPointerSendable<*mut libobs::obs_properties_t>, ObsError> {
         let id: ObsString = id.into();
-        let ptr = run_with_obs!(runtime, (id), move || {
+        let ptr = run_with_obs_background!(runtime, (id), move || {
             let id_ptr = id.as_ptr();
             let property_ptr = unsafe {
                 // Safety: Safe because of smart pointer
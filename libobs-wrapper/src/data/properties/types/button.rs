@@ -1,8 +1,16 @@
 use getters0::Getters;
 
 use crate::{
-    data::properties::{get_enum, get_opt_str, macros::unsafe_is_of_type_result, ObsButtonType},
+    data::{
+        object::ObsObjectTrait,
+        properties::{
+            get_enum, get_opt_str, macros::unsafe_is_of_type_result, property_ptr_to_struct,
+            ObsButtonType, ObsPropertyObject, ObsRefreshedProperties,
+        },
+    },
     run_with_obs,
+    unsafe_send::Sendable,
+    utils::{ObsError, ObsString},
 };
 
 use super::PropertyCreationInfo;
@@ -16,6 +24,63 @@ pub struct ObsButtonProperty {
     url: Option<String>,
 }
 
+impl ObsButtonProperty {
+    /// Clicks this button on the given object, which runs the callback of the button on the OBS thread.
+    ///
+    /// Buttons are commonly used by plugins to trigger actions like refreshing a device list,
+    /// which is why the properties of the object are returned again after the callback ran.
+    /// `needs_refresh` is set if the callback reported that the properties have changed.
+    pub fn click<K: Clone, T: ObsPropertyObject + ObsObjectTrait<K>>(
+        &self,
+        object: &T,
+    ) -> Result<ObsRefreshedProperties, ObsError> {
+        let object_ptr = object.get_object_ptr_raw().ok_or_else(|| {
+            ObsError::InvalidOperation(format!(
+                "Button {} can not be clicked without an object",
+                self.name
+            ))
+        })?;
+
+        let properties_raw = object.get_properties_raw()?;
+        let name = ObsString::new(&self.name);
+
+        let properties_clone = properties_raw.clone();
+        let needs_refresh = run_with_obs!(
+            object.runtime(),
+            (properties_clone, object_ptr, name),
+            move |token| {
+                let property = unsafe {
+                    // Safety: The properties pointer is valid because of the smart pointer and the name is kept in scope
+                    libobs::obs_properties_get(properties_clone.get_ptr(token), name.as_ptr().0)
+                };
+
+                if property.is_null() {
+                    return Err(ObsError::InvalidOperation(format!(
+                        "Button {} does not exist",
+                        name
+                    )));
+                }
+
+                let pointer = Sendable(property);
+                unsafe_is_of_type_result!(Button, pointer)?;
+
+                let needs_refresh = unsafe {
+                    // Safety: The property is a valid button and the object is kept alive by the caller for the duration of this call.
+                    libobs::obs_property_button_clicked(pointer.0, object_ptr.0)
+                };
+
+                Ok(needs_refresh)
+            }
+        )??;
+
+        let properties = property_ptr_to_struct(properties_raw, object.runtime().clone())?;
+        Ok(ObsRefreshedProperties {
+            needs_refresh,
+            properties,
+        })
+    }
+}
+
 impl TryFrom<PropertyCreationInfo> for ObsButtonProperty {
     type Error = crate::utils::ObsError;
 
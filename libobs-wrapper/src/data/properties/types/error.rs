@@ -0,0 +1,88 @@
+use std::fmt::{self, Display};
+
+use super::ObsListItemValue;
+use crate::data::properties::ObsComboFormat;
+
+/// Describes why a value was not accepted by a property.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ObsPropertyValueError {
+    /// The value does not have the type of the property
+    TypeMismatch {
+        property: String,
+        value: ObsListItemValue,
+    },
+    /// The value does not match the format of a list property
+    FormatMismatch {
+        property: String,
+        value: ObsListItemValue,
+        format: ObsComboFormat,
+    },
+    /// The value is not one of the items of a list property
+    NotAnItem {
+        property: String,
+        value: ObsListItemValue,
+    },
+    /// The value matches an item of a list property, but that item is disabled
+    DisabledItem { property: String, item: String },
+    /// The value is outside of the `min`/`max` range of a number property
+    OutOfRange {
+        property: String,
+        value: f64,
+        min: f64,
+        max: f64,
+    },
+}
+
+impl ObsPropertyValueError {
+    /// The name of the property that rejected the value.
+    pub fn property(&self) -> &str {
+        match self {
+            ObsPropertyValueError::TypeMismatch { property, .. }
+            | ObsPropertyValueError::FormatMismatch { property, .. }
+            | ObsPropertyValueError::NotAnItem { property, .. }
+            | ObsPropertyValueError::DisabledItem { property, .. }
+            | ObsPropertyValueError::OutOfRange { property, .. } => property,
+        }
+    }
+}
+
+impl Display for ObsPropertyValueError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ObsPropertyValueError::TypeMismatch { property, value } => write!(
+                f,
+                "Value {:?} does not match the type of property {}",
+                value, property
+            ),
+            ObsPropertyValueError::FormatMismatch {
+                property,
+                value,
+                format,
+            } => write!(
+                f,
+                "Value {:?} does not match the format {:?} of list property {}",
+                value, format, property
+            ),
+            ObsPropertyValueError::NotAnItem { property, value } => write!(
+                f,
+                "Value {:?} is not an item of list property {}",
+                value, property
+            ),
+            ObsPropertyValueError::DisabledItem { property, item } => {
+                write!(f, "Item {} of list property {} is disabled", item, property)
+            }
+            ObsPropertyValueError::OutOfRange {
+                property,
+                value,
+                min,
+                max,
+            } => write!(
+                f,
+                "Value {} is out of range {}..={} of property {}",
+                value, min, max, property
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ObsPropertyValueError {}
@@ -0,0 +1,28 @@
+#[cfg(test)]
+mod tests {
+    use super::super::{error::ObsPropertyValueError, ObsListItemValue};
+
+    #[test]
+    fn test_display_matches_property_errors() {
+        let error = ObsPropertyValueError::OutOfRange {
+            property: "bitrate".into(),
+            value: 200000.0,
+            min: 50.0,
+            max: 100000.0,
+        };
+        assert_eq!(error.property(), "bitrate");
+        assert_eq!(
+            error.to_string(),
+            "Value 200000 is out of range 50..=100000 of property bitrate"
+        );
+
+        let error = ObsPropertyValueError::NotAnItem {
+            property: "preset".into(),
+            value: ObsListItemValue::String("p9".into()),
+        };
+        assert_eq!(
+            error.to_string(),
+            "Value String(\"p9\") is not an item of list property preset"
+        );
+    }
+}
@@ -1,7 +1,11 @@
-use super::PropertyCreationInfo;
+use super::{ObsPropertyValueError, PropertyCreationInfo};
 use crate::{
-    data::properties::{get_enum, unsafe_is_of_type_result, ObsComboFormat, ObsComboType},
+    data::{
+        properties::{get_enum, unsafe_is_of_type_result, ObsComboFormat, ObsComboType},
+        ObsDataGetters,
+    },
     run_with_obs,
+    utils::ObsError,
 };
 use getters0::Getters;
 use std::ffi::CStr;
@@ -24,7 +28,7 @@ pub struct ObsListItem {
     disabled: bool,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum ObsListItemValue {
     String(String),
     Int(i64),
@@ -43,6 +47,67 @@ impl ObsListItem {
     }
 }
 
+impl ObsListProperty {
+    /// Checks whether the given value can be written to this property.
+    /// The value has to match the format of the list and, unless the list is editable,
+    /// one of its enabled items.
+    pub fn validate_value(&self, value: &ObsListItemValue) -> Result<(), ObsError> {
+        self.check_value(value)
+            .map_err(|e| ObsError::InvalidOperation(e.to_string()))
+    }
+
+    /// Reads the value of this property from the given settings, using the format of the list.
+    /// Returns `None` if the settings contain no value for this property.
+    pub fn read_value<T: ObsDataGetters>(
+        &self,
+        settings: &T,
+    ) -> Result<Option<ObsListItemValue>, ObsError> {
+        let key = self.name.as_str();
+        Ok(match self.format {
+            ObsComboFormat::String => settings.get_string(key)?.map(ObsListItemValue::String),
+            ObsComboFormat::Int => settings.get_int(key)?.map(ObsListItemValue::Int),
+            ObsComboFormat::Float => settings.get_double(key)?.map(ObsListItemValue::Float),
+            ObsComboFormat::Bool => settings.get_bool(key)?.map(ObsListItemValue::Bool),
+            ObsComboFormat::Invalid => None,
+        })
+    }
+
+    /// Same as [`ObsListProperty::validate_value`], but returns why the value was rejected.
+    pub fn check_value(&self, value: &ObsListItemValue) -> Result<(), ObsPropertyValueError> {
+        let matches_format = matches!(
+            (self.format, value),
+            (ObsComboFormat::String, ObsListItemValue::String(_))
+                | (ObsComboFormat::Int, ObsListItemValue::Int(_))
+                | (ObsComboFormat::Float, ObsListItemValue::Float(_))
+                | (ObsComboFormat::Bool, ObsListItemValue::Bool(_))
+        );
+
+        if !matches_format {
+            return Err(ObsPropertyValueError::FormatMismatch {
+                property: self.name.clone(),
+                value: value.clone(),
+                format: self.format,
+            });
+        }
+
+        if self.list_type == ObsComboType::Editable {
+            return Ok(());
+        }
+
+        match self.items.iter().find(|item| &item.value == value) {
+            Some(item) if item.disabled => Err(ObsPropertyValueError::DisabledItem {
+                property: self.name.clone(),
+                item: item.name.clone(),
+            }),
+            Some(_) => Ok(()),
+            None => Err(ObsPropertyValueError::NotAnItem {
+                property: self.name.clone(),
+                value: value.clone(),
+            }),
+        }
+    }
+}
+
 impl TryFrom<PropertyCreationInfo> for ObsListProperty {
     type Error = crate::utils::ObsError;
 
@@ -4,12 +4,17 @@
 mod button;
 impl_general_property!(Color);
 mod editable_list;
+mod error;
+#[cfg(test)]
+mod error_tests;
 impl_general_property!(Font);
 impl_general_property!(FrameRate);
 impl_general_property!(Group);
 impl_general_property!(ColorAlpha);
 mod list;
 mod number;
+#[cfg(test)]
+mod number_tests;
 mod path;
 mod text;
 
@@ -42,6 +47,7 @@ use std::ffi::CStr;
 
 pub use button::*;
 pub use editable_list::*;
+pub use error::*;
 use libobs::obs_property;
 pub use list::*;
 pub use number::*;
@@ -1,6 +1,7 @@
 use getters0::Getters;
 
-use crate::data::properties::ObsNumberType;
+use super::ObsPropertyValueError;
+use crate::{data::properties::ObsNumberType, utils::ObsError};
 
 #[derive(Debug, Getters, Clone)]
 #[skip_new]
@@ -98,3 +99,93 @@ macro_rules! impl_from_property {
 
 impl_from_property!(i32, int);
 impl_from_property!(f64, float);
+
+impl ObsNumberProperty<i32> {
+    /// Clamps the given value to the `min`/`max` range of this property and snaps it to the nearest `step`.
+    pub fn clamp_value(&self, value: i64) -> i64 {
+        snap_int(value, self.min as i64, self.max as i64, self.step as i64)
+    }
+
+    /// Checks whether the given value is within the `min`/`max` range of this property.
+    pub fn validate_value(&self, value: i64) -> Result<(), ObsError> {
+        self.check_value(value)
+            .map_err(|e| ObsError::InvalidOperation(e.to_string()))
+    }
+
+    /// Same as `validate_value`, but returns why the value was rejected.
+    pub fn check_value(&self, value: i64) -> Result<(), ObsPropertyValueError> {
+        if value < self.min as i64 || value > self.max as i64 {
+            return Err(ObsPropertyValueError::OutOfRange {
+                property: self.name.clone(),
+                value: value as f64,
+                min: self.min as f64,
+                max: self.max as f64,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+impl ObsNumberProperty<f64> {
+    /// Clamps the given value to the `min`/`max` range of this property and snaps it to the nearest `step`.
+    pub fn clamp_value(&self, value: f64) -> f64 {
+        snap_float(value, self.min, self.max, self.step)
+    }
+
+    /// Checks whether the given value is within the `min`/`max` range of this property.
+    pub fn validate_value(&self, value: f64) -> Result<(), ObsError> {
+        self.check_value(value)
+            .map_err(|e| ObsError::InvalidOperation(e.to_string()))
+    }
+
+    /// Same as `validate_value`, but returns why the value was rejected.
+    pub fn check_value(&self, value: f64) -> Result<(), ObsPropertyValueError> {
+        if value < self.min || value > self.max {
+            return Err(ObsPropertyValueError::OutOfRange {
+                property: self.name.clone(),
+                value,
+                min: self.min,
+                max: self.max,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+pub(super) fn snap_int(value: i64, min: i64, max: i64, step: i64) -> i64 {
+    if min > max {
+        return value;
+    }
+
+    let value = value.clamp(min, max);
+    if step <= 0 {
+        return value;
+    }
+
+    let mut snapped = min + (value - min + step / 2) / step * step;
+    if snapped > max {
+        snapped -= step;
+    }
+
+    snapped
+}
+
+pub(super) fn snap_float(value: f64, min: f64, max: f64, step: f64) -> f64 {
+    if min > max || !min.is_finite() || !max.is_finite() {
+        return value;
+    }
+
+    let value = value.clamp(min, max);
+    if step <= 0.0 || !step.is_finite() {
+        return value;
+    }
+
+    let mut steps = ((value - min) / step).round();
+    if min + steps * step > max {
+        steps -= 1.0;
+    }
+
+    (min + steps * step).clamp(min, max)
+}
@@ -0,0 +1,23 @@
+#[cfg(test)]
+mod tests {
+    use super::super::number::{snap_float, snap_int};
+
+    #[test]
+    fn test_snap_int() {
+        assert_eq!(snap_int(150, 0, 100, 1), 100);
+        assert_eq!(snap_int(-5, 0, 100, 1), 0);
+        assert_eq!(snap_int(17, 0, 100, 5), 15);
+        assert_eq!(snap_int(18, 0, 100, 5), 20);
+        assert_eq!(snap_int(99, 0, 98, 4), 96);
+        assert_eq!(snap_int(7, 1, 10, 3), 7);
+        assert_eq!(snap_int(42, 0, 100, 0), 42);
+    }
+
+    #[test]
+    fn test_snap_float() {
+        assert_eq!(snap_float(1.5, 0.0, 1.0, 0.1), 1.0);
+        assert_eq!(snap_float(0.26, 0.0, 1.0, 0.25), 0.25);
+        assert_eq!(snap_float(0.9, 0.0, 0.8, 0.3), 0.6);
+        assert_eq!(snap_float(0.33, 0.0, 1.0, 0.0), 0.33);
+    }
+}
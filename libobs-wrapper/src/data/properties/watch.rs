@@ -0,0 +1,94 @@
+use std::{
+    collections::HashMap,
+    sync::mpsc::{self, RecvTimeoutError},
+    time::Duration,
+};
+
+use tokio::sync::broadcast;
+
+use crate::{
+    data::properties::{ObsProperty, ObsPropertyObject},
+    signals::ObsSignalEvent,
+    sources::ObsSourceTrait,
+    utils::ObsError,
+};
+
+/// Sends the refreshed properties of a source whenever the source emits `update_properties`,
+/// e.g. because a capture device was plugged in, so settings UIs can stay in sync.
+///
+/// Bursts of signals are debounced: the properties are queried once no further signal
+/// arrived for the debounce interval. The watcher keeps the source alive and stops once it is dropped.
+#[derive(Debug)]
+pub struct ObsPropertiesWatcher {
+    updates: broadcast::Sender<HashMap<String, ObsProperty>>,
+    _stop: mpsc::Sender<()>,
+}
+
+impl ObsPropertiesWatcher {
+    pub fn new<T>(source: &T, debounce: Duration) -> Result<Self, ObsError>
+    where
+        T: ObsSourceTrait + Clone + Send + 'static,
+    {
+        let mut signals = source.signals().on_update_properties()?;
+        let source = source.clone();
+
+        let (updates, _) = broadcast::channel(4);
+        let (stop_tx, stop_rx) = mpsc::channel::<()>();
+
+        let sender = updates.clone();
+        std::thread::Builder::new()
+            .name("libobs-wrapper-properties-watcher".to_string())
+            .spawn(move || {
+                let mut pending = false;
+
+                // The loop ends as soon as the watcher is dropped, as the stop sender disconnects.
+                while let Err(RecvTimeoutError::Timeout) = stop_rx.recv_timeout(debounce) {
+                    let mut received = false;
+                    loop {
+                        match signals.try_recv() {
+                            Ok(Some(ObsSignalEvent::Signal(_) | ObsSignalEvent::Lagged(_))) => {
+                                received = true
+                            }
+                            Ok(None) => break,
+                            Err(_) => return,
+                        }
+                    }
+
+                    // Wait until the burst of signals is over
+                    if received {
+                        pending = true;
+                        continue;
+                    }
+
+                    if !pending {
+                        continue;
+                    }
+
+                    pending = false;
+                    match source.get_properties() {
+                        // Sending only fails if there are no receivers, which is fine
+                        Ok(properties) => {
+                            let _ = sender.send(properties);
+                        }
+                        Err(e) => log::warn!("Failed to refresh properties: {:?}", e),
+                    }
+                }
+            })
+            .map_err(|e| {
+                ObsError::InvalidOperation(format!(
+                    "Failed to spawn properties watcher thread: {}",
+                    e
+                ))
+            })?;
+
+        Ok(Self {
+            updates,
+            _stop: stop_tx,
+        })
+    }
+
+    /// Subscribes to the refreshed properties.
+    pub fn subscribe(&self) -> broadcast::Receiver<HashMap<String, ObsProperty>> {
+        self.updates.subscribe()
+    }
+}
@@ -0,0 +1,172 @@
+use std::marker::PhantomData;
+
+use crate::{
+    data::{object::ObsObjectTrait, ObsData, ObsDataUpdater},
+    utils::{ObsError, ObsString},
+};
+
+use super::{ObsListItem, ObsListItemValue, ObsProperty, ObsPropertyObject};
+
+/// Writes values of a single property back to an OBS object.
+///
+/// The value is validated against the property description before it is written,
+/// numbers are clamped to the `min`/`max` range and snapped to the `step` of the property
+/// and list values have to match one of the enabled items of the list.
+/// Multiple values can be written before calling `apply()`, which updates the settings of the object.
+#[must_use = "The `apply()` method must be called to apply changes."]
+pub struct PropertyValueWriter<'a, K: Clone, T: ObsObjectTrait<K>> {
+    object: &'a T,
+    name: ObsString,
+    property: ObsProperty,
+    settings: ObsData,
+    updater: ObsDataUpdater,
+    _key: PhantomData<K>,
+}
+
+impl<'a, K: Clone, T: ObsObjectTrait<K>> PropertyValueWriter<'a, K, T> {
+    /// Creates a new writer for the property with the given name and description.
+    /// The description can be obtained by calling `get_properties` on the object.
+    pub fn new(
+        object: &'a T,
+        name: impl Into<ObsString>,
+        property: ObsProperty,
+    ) -> Result<Self, ObsError> {
+        let mut settings = ObsData::new(object.runtime().clone())?;
+        let updater = settings.bulk_update();
+
+        Ok(Self {
+            object,
+            name: name.into(),
+            property,
+            settings,
+            updater,
+            _key: PhantomData,
+        })
+    }
+
+    /// Looks up the property with the given name on the object and creates a new writer for it.
+    pub fn for_property(object: &'a T, name: &str) -> Result<Self, ObsError>
+    where
+        T: ObsPropertyObject,
+    {
+        let property = object.get_properties()?.remove(name).ok_or_else(|| {
+            ObsError::InvalidOperation(format!("Property {} does not exist", name))
+        })?;
+
+        Self::new(object, name, property)
+    }
+
+    pub fn property(&self) -> &ObsProperty {
+        &self.property
+    }
+
+    /// Writes a boolean value. Only valid for bool properties and lists with a bool format.
+    pub fn set_bool(&mut self, value: bool) -> Result<(), ObsError> {
+        match &self.property {
+            ObsProperty::Bool => {}
+            ObsProperty::List(list) => list.validate_value(&ObsListItemValue::Bool(value))?,
+            _ => return Err(self.type_mismatch("bool")),
+        }
+
+        self.updater.set_bool_ref(self.name.clone(), value);
+        Ok(())
+    }
+
+    /// Writes an integer value and returns the value that was actually written.
+    /// Values of int properties are clamped to the range and step of the property.
+    pub fn set_int(&mut self, value: i64) -> Result<i64, ObsError> {
+        let value = match &self.property {
+            ObsProperty::Int(number) => number.clamp_value(value),
+            ObsProperty::Color(_) | ObsProperty::ColorAlpha(_) => {
+                if u32::try_from(value).is_err() {
+                    return Err(ObsError::InvalidOperation(format!(
+                        "Color {} of property {} is out of range",
+                        value, self.name
+                    )));
+                }
+
+                value
+            }
+            ObsProperty::List(list) => {
+                list.validate_value(&ObsListItemValue::Int(value))?;
+                value
+            }
+            _ => return Err(self.type_mismatch("int")),
+        };
+
+        self.updater.set_int_ref(self.name.clone(), value);
+        Ok(value)
+    }
+
+    /// Writes a float value and returns the value that was actually written.
+    /// Values of float properties are clamped to the range and step of the property.
+    pub fn set_float(&mut self, value: f64) -> Result<f64, ObsError> {
+        if !value.is_finite() {
+            return Err(ObsError::InvalidOperation(format!(
+                "Value {} of property {} is not a finite number",
+                value, self.name
+            )));
+        }
+
+        let value = match &self.property {
+            ObsProperty::Float(number) => number.clamp_value(value),
+            ObsProperty::List(list) => {
+                list.validate_value(&ObsListItemValue::Float(value))?;
+                value
+            }
+            _ => return Err(self.type_mismatch("float")),
+        };
+
+        self.updater.set_double_ref(self.name.clone(), value);
+        Ok(value)
+    }
+
+    /// Writes a string value. Valid for text, path and lists with a string format.
+    pub fn set_string(&mut self, value: &str) -> Result<(), ObsError> {
+        match &self.property {
+            ObsProperty::Text(_) | ObsProperty::Path(_) => {}
+            ObsProperty::List(list) => {
+                list.validate_value(&ObsListItemValue::String(value.to_string()))?
+            }
+            _ => return Err(self.type_mismatch("string")),
+        }
+
+        self.updater.set_string_ref(self.name.clone(), value);
+        Ok(())
+    }
+
+    /// Selects the given item of a list property.
+    pub fn select_item(&mut self, item: &ObsListItem) -> Result<(), ObsError> {
+        match item.value() {
+            ObsListItemValue::String(value) => self.set_string(value),
+            ObsListItemValue::Int(value) => self.set_int(*value).map(|_| ()),
+            ObsListItemValue::Float(value) => self.set_float(*value).map(|_| ()),
+            ObsListItemValue::Bool(value) => self.set_bool(*value),
+            ObsListItemValue::Invalid => Err(ObsError::InvalidOperation(format!(
+                "Item {} of property {} has no valid value",
+                item.name(),
+                self.name
+            ))),
+        }
+    }
+
+    /// Applies all written values to the settings of the object.
+    pub fn apply(self) -> Result<(), ObsError> {
+        let PropertyValueWriter {
+            object,
+            settings,
+            updater,
+            ..
+        } = self;
+
+        updater.apply()?;
+        object.update_settings(settings)
+    }
+
+    fn type_mismatch(&self, value_type: &str) -> ObsError {
+        ObsError::InvalidOperation(format!(
+            "Can not write a {} value to property {} ({:?})",
+            value_type, self.name, self.property
+        ))
+    }
+}
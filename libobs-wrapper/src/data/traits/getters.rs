@@ -3,7 +3,7 @@ use std::ffi::CStr;
 use crate::{
     data::ObsDataPointers,
     run_with_obs,
-    unsafe_send::SmartPointerSendable,
+    unsafe_send::{ObsThreadToken, SmartPointerSendable},
     utils::{ObsError, ObsString},
 };
 
@@ -14,9 +14,10 @@ use crate::{
 unsafe fn has_value(
     data_ptr: SmartPointerSendable<*mut libobs::obs_data_t>,
     key: &ObsString,
+    token: ObsThreadToken,
 ) -> bool {
-    libobs::obs_data_has_user_value(data_ptr.get_ptr(), key.as_ptr().0)
-        || libobs::obs_data_has_default_value(data_ptr.get_ptr(), key.as_ptr().0)
+    libobs::obs_data_has_user_value(data_ptr.get_ptr(token), key.as_ptr().0)
+        || libobs::obs_data_has_default_value(data_ptr.get_ptr(token), key.as_ptr().0)
 }
 
 pub trait ObsDataGetters: ObsDataPointers {
@@ -27,16 +28,16 @@ pub trait ObsDataGetters: ObsDataPointers {
         let key = key.into();
         let data_ptr = self.as_ptr();
 
-        run_with_obs!(self.runtime(), (data_ptr, key), move || {
+        run_with_obs!(self.runtime(), (data_ptr, key), move |token| {
             let has_value = unsafe {
                 // Safety: We are running on the OBS runtime.
-                has_value(data_ptr.clone(), &key)
+                has_value(data_ptr.clone(), &key, token)
             };
 
             if has_value {
                 let result = unsafe {
                     // Safety: The pointer is valid because we are using a smart pointer
-                    libobs::obs_data_get_string(data_ptr.get_ptr(), key.as_ptr().0)
+                    libobs::obs_data_get_string(data_ptr.get_ptr(token), key.as_ptr().0)
                 };
 
                 if result.is_null() {
@@ -62,16 +63,16 @@ pub trait ObsDataGetters: ObsDataPointers {
         let key = key.into();
         let data_ptr = self.as_ptr();
 
-        run_with_obs!(self.runtime(), (data_ptr, key), move || {
+        run_with_obs!(self.runtime(), (data_ptr, key), move |token| {
             let has_value = unsafe {
                 // Safety: We are running on the OBS runtime.
-                has_value(data_ptr.clone(), &key)
+                has_value(data_ptr.clone(), &key, token)
             };
 
             if has_value {
                 Some(unsafe {
                     // Safety: The pointer is valid because we are using a smart pointer
-                    libobs::obs_data_get_int(data_ptr.get_ptr(), key.as_ptr().0)
+                    libobs::obs_data_get_int(data_ptr.get_ptr(token), key.as_ptr().0)
                 })
             } else {
                 None
@@ -83,16 +84,16 @@ pub trait ObsDataGetters: ObsDataPointers {
 
         let data_ptr = self.as_ptr();
 
-        run_with_obs!(self.runtime(), (data_ptr, key), move || {
+        run_with_obs!(self.runtime(), (data_ptr, key), move |token| {
             let has_value = unsafe {
                 // Safety: We are running on the OBS runtime.
-                has_value(data_ptr.clone(), &key)
+                has_value(data_ptr.clone(), &key, token)
             };
 
             if has_value {
                 Some(unsafe {
                     // Safety: The pointer is valid because we are using a smart pointer
-                    libobs::obs_data_get_bool(data_ptr.get_ptr(), key.as_ptr().0)
+                    libobs::obs_data_get_bool(data_ptr.get_ptr(token), key.as_ptr().0)
                 })
             } else {
                 None
@@ -106,16 +107,16 @@ pub trait ObsDataGetters: ObsDataPointers {
         let key = key.into();
         let data_ptr = self.as_ptr();
 
-        let result = run_with_obs!(self.runtime(), (key, data_ptr), move || {
+        let result = run_with_obs!(self.runtime(), (key, data_ptr), move |token| {
             let has_value = unsafe {
                 // Safety: We are running on the OBS runtime.
-                has_value(data_ptr.clone(), &key)
+                has_value(data_ptr.clone(), &key, token)
             };
 
             if has_value {
                 Some(unsafe {
                     // Safety: The pointer is valid because we are using a smart pointer
-                    libobs::obs_data_get_double(data_ptr.get_ptr(), key.as_ptr().0)
+                    libobs::obs_data_get_double(data_ptr.get_ptr(token), key.as_ptr().0)
                 })
             } else {
                 None
@@ -127,10 +128,10 @@ pub trait ObsDataGetters: ObsDataPointers {
 
     fn get_json(&self) -> Result<String, ObsError> {
         let data_ptr = self.as_ptr();
-        run_with_obs!(self.runtime(), (data_ptr), move || {
+        run_with_obs!(self.runtime(), (data_ptr), move |token| {
             let json_ptr = unsafe {
                 // Safety: The pointer is valid because we are using a smart pointer
-                libobs::obs_data_get_json(data_ptr.get_ptr())
+                libobs::obs_data_get_json(data_ptr.get_ptr(token))
             };
 
             if json_ptr.is_null() {
@@ -144,7 +145,7 @@ pub trait ObsDataGetters: ObsDataPointers {
                 CStr::from_ptr(json_ptr)
             }
             .to_str()
-            .map_err(|_| ObsError::JsonParseError)?
+            .map_err(|e| ObsError::JsonParseError(Some(e.into())))?
             .to_string();
 
             Ok(json)
@@ -17,10 +17,14 @@ pub trait ObsDataSetters: ObsDataPointers {
 
         let data_ptr = self.as_ptr();
 
-        run_with_obs!(self.runtime(), (data_ptr, key, value), move || {
+        run_with_obs!(self.runtime(), (data_ptr, key, value), move |token| {
             unsafe {
                 // Safety: The pointer is valid because we are using a smart pointer
-                libobs::obs_data_set_string(data_ptr.get_ptr(), key.as_ptr().0, value.as_ptr().0)
+                libobs::obs_data_set_string(
+                    data_ptr.get_ptr(token),
+                    key.as_ptr().0,
+                    value.as_ptr().0,
+                )
             }
         })?;
 
@@ -37,10 +41,10 @@ pub trait ObsDataSetters: ObsDataPointers {
         let key = key.into();
         let data_ptr = self.as_ptr();
 
-        run_with_obs!(self.runtime(), (key, data_ptr), move || {
+        run_with_obs!(self.runtime(), (key, data_ptr), move |token| {
             unsafe {
                 // Safety: The pointer is valid because we are using a smart pointer
-                libobs::obs_data_set_int(data_ptr.get_ptr(), key.as_ptr().0, value);
+                libobs::obs_data_set_int(data_ptr.get_ptr(token), key.as_ptr().0, value);
             }
         })?;
 
@@ -57,10 +61,10 @@ pub trait ObsDataSetters: ObsDataPointers {
         let key = key.into();
 
         let data_ptr = self.as_ptr();
-        run_with_obs!(self.runtime(), (key, data_ptr), move || {
+        run_with_obs!(self.runtime(), (key, data_ptr), move |token| {
             unsafe {
                 // Safety: The pointer is valid because we are using a smart pointer
-                libobs::obs_data_set_bool(data_ptr.get_ptr(), key.as_ptr().0, value);
+                libobs::obs_data_set_bool(data_ptr.get_ptr(token), key.as_ptr().0, value);
             }
         })?;
 
@@ -77,10 +81,10 @@ pub trait ObsDataSetters: ObsDataPointers {
         let key = key.into();
         let data_ptr = self.as_ptr();
 
-        run_with_obs!(self.runtime(), (key, data_ptr), move || {
+        run_with_obs!(self.runtime(), (key, data_ptr), move |token| {
             unsafe {
                 // Safety: The pointer is valid because we are using a smart pointer
-                libobs::obs_data_set_double(data_ptr.get_ptr(), key.as_ptr().0, value);
+                libobs::obs_data_set_double(data_ptr.get_ptr(token), key.as_ptr().0, value);
             }
         })?;
 
@@ -13,6 +13,64 @@ pub enum ObsDataChange {
     Double(ObsString, f64),
 }
 
+impl ObsDataChange {
+    pub fn new(key: impl Into<ObsString>, value: impl IntoObsDataChange) -> Self {
+        value.into_change(key.into())
+    }
+}
+
+/// A value that can be set in `ObsData` without naming its type,
+/// used by the [`obs_data!`](crate::obs_data) macro and `ObsDataUpdater::set`.
+pub trait IntoObsDataChange {
+    fn into_change(self, key: ObsString) -> ObsDataChange;
+}
+
+macro_rules! impl_into_change {
+    ($variant:ident, $target:ty, $($t:ty),*) => {
+        $(
+            impl IntoObsDataChange for $t {
+                fn into_change(self, key: ObsString) -> ObsDataChange {
+                    ObsDataChange::$variant(key, <$target>::from(self))
+                }
+            }
+        )*
+    };
+}
+
+impl_into_change!(String, ObsString, ObsString, &str, String);
+impl_into_change!(Int, i64, i64, i32, u32, i16, u16, i8, u8);
+impl_into_change!(Bool, bool, bool);
+impl_into_change!(Double, f64, f64, f32);
+
+impl IntoObsDataChange for &String {
+    fn into_change(self, key: ObsString) -> ObsDataChange {
+        ObsDataChange::String(key, self.as_str().into())
+    }
+}
+
+/// Applies the changes to the data.
+///
+/// # Safety
+/// Must be called on the runtime with a valid data pointer.
+#[allow(unknown_lints)]
+#[allow(ensure_obs_call_in_runtime)]
+pub(super) unsafe fn apply_changes(data: *mut libobs::obs_data_t, changes: Vec<ObsDataChange>) {
+    for change in changes {
+        match change {
+            ObsDataChange::String(key, value) => {
+                libobs::obs_data_set_string(data, key.as_ptr().0, value.as_ptr().0)
+            }
+            ObsDataChange::Int(key, value) => libobs::obs_data_set_int(data, key.as_ptr().0, value),
+            ObsDataChange::Bool(key, value) => {
+                libobs::obs_data_set_bool(data, key.as_ptr().0, value)
+            }
+            ObsDataChange::Double(key, value) => {
+                libobs::obs_data_set_double(data, key.as_ptr().0, value)
+            }
+        };
+    }
+}
+
 #[derive(Debug)]
 /// This struct is used to update the ObsData in bulk, rather than having to call the set_string
 /// methods all the time.
@@ -73,6 +131,26 @@ impl ObsDataUpdater {
         self
     }
 
+    pub fn set_double_ref(&mut self, key: impl Into<ObsString>, value: f64) {
+        let key = key.into();
+        self.changes.push(ObsDataChange::Double(key, value));
+    }
+
+    pub fn set_double(mut self, key: impl Into<ObsString>, value: f64) -> Self {
+        self.set_double_ref(key, value);
+        self
+    }
+
+    /// Sets a value of any type that implements [`IntoObsDataChange`].
+    pub fn set_ref(&mut self, key: impl Into<ObsString>, value: impl IntoObsDataChange) {
+        self.changes.push(ObsDataChange::new(key, value));
+    }
+
+    pub fn set(mut self, key: impl Into<ObsString>, value: impl IntoObsDataChange) -> Self {
+        self.set_ref(key, value);
+        self
+    }
+
     pub fn apply(self) -> Result<(), ObsError> {
         let ObsDataUpdater {
             changes,
@@ -81,27 +159,9 @@ impl ObsDataUpdater {
         } = self;
 
         let data_ptr = data_ptr.clone();
-        run_with_obs!(runtime, (data_ptr), move || unsafe {
+        run_with_obs!(runtime, (data_ptr), move |token| unsafe {
             // Safety: All pointers are held within the changes type and data_ptr is valid because we are using a SmartPointer.
-
-            for change in changes {
-                match change {
-                    ObsDataChange::String(key, value) => libobs::obs_data_set_string(
-                        data_ptr.get_ptr(),
-                        key.as_ptr().0,
-                        value.as_ptr().0,
-                    ),
-                    ObsDataChange::Int(key, value) => {
-                        libobs::obs_data_set_int(data_ptr.get_ptr(), key.as_ptr().0, value)
-                    }
-                    ObsDataChange::Bool(key, value) => {
-                        libobs::obs_data_set_bool(data_ptr.get_ptr(), key.as_ptr().0, value)
-                    }
-                    ObsDataChange::Double(key, value) => {
-                        libobs::obs_data_set_double(data_ptr.get_ptr(), key.as_ptr().0, value)
-                    }
-                };
-            }
+            apply_changes(data_ptr.get_ptr(token), changes);
         })
     }
 
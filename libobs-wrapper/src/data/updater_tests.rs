@@ -0,0 +1,21 @@
+#[cfg(test)]
+mod tests {
+    use super::super::updater::ObsDataChange;
+
+    #[test]
+    fn values_map_to_their_kind() {
+        assert!(matches!(
+            ObsDataChange::new("a", 5u32),
+            ObsDataChange::Int(_, 5)
+        ));
+        assert!(matches!(ObsDataChange::new("a", 0.5f32), ObsDataChange::Double(_, v) if v == 0.5));
+        assert!(matches!(
+            ObsDataChange::new("a", true),
+            ObsDataChange::Bool(_, true)
+        ));
+        assert!(matches!(
+            ObsDataChange::new("a", "value"),
+            ObsDataChange::String(_, v) if v == "value"
+        ));
+    }
+}
@@ -2,6 +2,7 @@ use std::{boxed::Box, fmt::Debug, pin::Pin};
 
 use display_info::DisplayInfo;
 use libobs::obs_video_info;
+use num_traits::FromPrimitive;
 
 #[cfg(target_os = "linux")]
 use crate::utils::linux::get_linux_opengl_lib_name;
@@ -10,7 +11,7 @@ use crate::{
         ObsColorspace, ObsGraphicsModule, ObsScaleType, ObsVideoFormat, ObsVideoRange, OsEnumType,
     },
     unsafe_send::Sendable,
-    utils::ObsString,
+    utils::{ObsError, ObsString},
 };
 
 #[derive(Clone, Debug)]
@@ -55,6 +56,9 @@ impl Debug for ObsVideoInfo {
             .field("base_height", &self.get_base_height())
             .field("output_width", &self.get_output_width())
             .field("output_height", &self.get_output_height())
+            .field("output_format", &self.get_output_format())
+            .field("colorspace", &self.get_colorspace())
+            .field("range", &self.get_range())
             .field("sdr_info", &self.get_sdr_info())
             .finish()
     }
@@ -105,6 +109,34 @@ impl ObsVideoInfo {
         &self.graphics_module
     }
 
+    /// Returns a copy of this video info with a different frame rate.
+    pub(crate) fn with_frame_rate(&self, fps_num: u32, fps_den: u32) -> Self {
+        let graphics_module = self.graphics_module.clone();
+
+        let mut ovi = *self.ovi.0;
+        ovi.fps_num = fps_num;
+        ovi.fps_den = fps_den;
+        // The copied string lives at a different address
+        ovi.graphics_module = graphics_module.as_ptr().0;
+
+        Self::new_with_sdr_info(ovi, graphics_module, self.sdr_info.clone())
+    }
+
+    /// Returns a copy with another canvas and frame rate, the sizes are `(width, height)` and the
+    /// frame rate is `(num, den)`.
+    pub(crate) fn with_canvas(
+        &self,
+        base_size: (u32, u32),
+        output_size: (u32, u32),
+        frame_rate: (u32, u32),
+    ) -> Self {
+        let mut copy = self.with_frame_rate(frame_rate.0, frame_rate.1);
+        (copy.ovi.0.base_width, copy.ovi.0.base_height) = base_size;
+        (copy.ovi.0.output_width, copy.ovi.0.output_height) = output_size;
+
+        copy
+    }
+
     pub fn get_fps_num(&self) -> u32 {
         self.ovi.0.fps_num
     }
@@ -132,6 +164,75 @@ impl ObsVideoInfo {
     pub fn get_sdr_info(&self) -> &ObsSdrVideoInfo {
         &self.sdr_info
     }
+
+    /// Returns `None` if libobs uses a format this wrapper does not know.
+    pub fn get_output_format(&self) -> Option<ObsVideoFormat> {
+        ObsVideoFormat::from_i64(self.ovi.0.output_format as i64)
+    }
+
+    pub fn get_colorspace(&self) -> Option<ObsColorspace> {
+        ObsColorspace::from_i64(self.ovi.0.colorspace as i64)
+    }
+
+    pub fn get_range(&self) -> Option<ObsVideoRange> {
+        ObsVideoRange::from_i64(self.ovi.0.range as i64)
+    }
+
+    /// Returns true if the colorspace is one of the Rec. 2100 (PQ or HLG) HDR colorspaces.
+    pub fn is_hdr(&self) -> bool {
+        matches!(
+            self.get_colorspace(),
+            Some(ObsColorspace::CS2100PQ | ObsColorspace::CS2100HLG)
+        )
+    }
+
+    /// Checks that the output format can carry the colorspace, HDR colorspaces
+    /// require a format with more than 8 bits per channel, e.g. `P010`.
+    ///
+    /// This is checked before the video context is reset.
+    pub fn validate(&self) -> Result<(), ObsError> {
+        let format = self.get_output_format();
+        if self.is_hdr() && !format.is_some_and(is_high_bit_depth) {
+            return Err(ObsError::InvalidOperation(format!(
+                "The HDR colorspace {:?} requires a 10 or 16 bit output format like P010, but the output format is {:?}",
+                self.get_colorspace(),
+                format
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Checks whether video encoders of the given codec (e.g. `h264` or `hevc`, see
+    /// `ObsContext::validate_video_encoder`) can encode the output format of this video info.
+    pub fn validate_codec(&self, codec: &str) -> Result<(), ObsError> {
+        let Some(format) = self.get_output_format() else {
+            return Ok(());
+        };
+
+        let supported = match format {
+            // 16 bit formats are only used for ProRes
+            ObsVideoFormat::P216 | ObsVideoFormat::P416 => codec == "prores",
+            ObsVideoFormat::P010 | ObsVideoFormat::I010 => codec != "h264",
+            _ => true,
+        };
+
+        if !supported {
+            return Err(ObsError::InvalidOperation(format!(
+                "The {} codec does not support the output format {:?}",
+                codec, format
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+fn is_high_bit_depth(format: ObsVideoFormat) -> bool {
+    matches!(
+        format,
+        ObsVideoFormat::P010 | ObsVideoFormat::I010 | ObsVideoFormat::P216 | ObsVideoFormat::P416
+    )
 }
 
 impl Default for ObsVideoInfo {
@@ -329,7 +430,8 @@ impl ObsVideoInfoBuilder {
         self
     }
 
-    /// Sets the video colorspace.
+    /// Sets the video colorspace. The HDR colorspaces `CS2100PQ` and `CS2100HLG`
+    /// require a high bit depth `output_format`, see `ObsVideoInfo::validate`.
     pub fn colorspace(mut self, value: ObsColorspace) -> Self {
         self.colorspace = value;
         self
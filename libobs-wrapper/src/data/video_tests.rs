@@ -0,0 +1,31 @@
+#[cfg(test)]
+mod tests {
+    use super::super::video::ObsVideoInfoBuilder;
+    use crate::enums::{ObsColorspace, ObsVideoFormat, ObsVideoRange};
+
+    #[test]
+    fn test_video_info_hdr_validation() {
+        let sdr = ObsVideoInfoBuilder::new().build();
+        assert!(!sdr.is_hdr());
+        assert!(sdr.validate().is_ok());
+        assert!(sdr.validate_codec("h264").is_ok());
+
+        let invalid_hdr = ObsVideoInfoBuilder::new()
+            .colorspace(ObsColorspace::CS2100PQ)
+            .build();
+        assert!(invalid_hdr.validate().is_err());
+
+        let hdr = ObsVideoInfoBuilder::new()
+            .output_format(ObsVideoFormat::P010)
+            .colorspace(ObsColorspace::CS2100HLG)
+            .range(ObsVideoRange::Partial)
+            .build();
+        assert!(hdr.is_hdr());
+        assert_eq!(hdr.get_output_format(), Some(ObsVideoFormat::P010));
+        assert_eq!(hdr.get_range(), Some(ObsVideoRange::Partial));
+        assert!(hdr.validate().is_ok());
+        assert!(hdr.validate_codec("hevc").is_ok());
+        assert!(hdr.validate_codec("av1").is_ok());
+        assert!(hdr.validate_codec("h264").is_err());
+    }
+}
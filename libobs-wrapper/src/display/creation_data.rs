@@ -2,7 +2,7 @@ use libobs::gs_init_data;
 
 use crate::{display::ObsWindowHandle, enums::OsEnumType};
 
-use super::{GsColorFormat, GsZstencilFormat};
+use super::{GsColorFormat, GsZstencilFormat, ObsDisplayScaleMode};
 
 pub type RawDisplayHandle = *mut ::std::os::raw::c_void;
 
@@ -20,6 +20,8 @@ pub struct ObsDisplayCreationData {
     pub(super) adapter: u32,
     pub(super) backbuffers: u32,
     pub(super) background_color: u32,
+    pub(super) enabled: bool,
+    pub(super) scale_mode: ObsDisplayScaleMode,
 }
 
 pub struct CloneableGsInitData(pub gs_init_data);
@@ -53,6 +55,8 @@ impl ObsDisplayCreationData {
             adapter: 0,
             backbuffers: 0,
             background_color: 0,
+            enabled: true,
+            scale_mode: ObsDisplayScaleMode::default(),
         }
     }
 
@@ -81,10 +85,30 @@ impl ObsDisplayCreationData {
         self
     }
 
+    /// Sets the background color from its RGB components, this is the color of the area
+    /// that is not covered by the canvas (see `set_scale_mode`).
+    pub fn set_background_rgb(self, r: u8, g: u8, b: u8) -> Self {
+        self.set_background_color(((r as u32) << 16) | ((g as u32) << 8) | (b as u32))
+    }
+
+    /// Whether the display should draw right after creation, defaults to `true`.
+    /// Use `MiscDisplayTrait::set_enabled` to toggle drawing later on.
+    pub fn set_enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+
+    /// Sets how the canvas is scaled inside of the display, defaults to `ObsDisplayScaleMode::Stretch`.
+    pub fn set_scale_mode(mut self, scale_mode: ObsDisplayScaleMode) -> Self {
+        self.scale_mode = scale_mode;
+        self
+    }
+
     /// If enabled, creating the display will result in a child window being created inside the provided window handle. The display is attached to that child window. This is on by default.
     ///
     /// ## Platform
-    /// This is only applicable on Windows.
+    /// This is applicable on Windows, Linux and macOS. On X11 the child is a subwindow, on Wayland a
+    /// subsurface of the given surface and on macOS a subview of the given `NSView`.
     pub fn set_create_child(mut self, should_create: bool) -> Self {
         self.create_child = should_create;
         self
@@ -0,0 +1,295 @@
+//! Forwards mouse and keyboard input to sources, which is needed to build an interactive preview.
+//!
+//! Input events are usually received in window coordinates, use `ObsDisplayRef::window_to_canvas`
+//! to convert them to the base canvas space before hit-testing scene items or forwarding them to sources.
+//! Coordinates of events sent to a source are relative to the top-left corner of that source.
+
+use std::{ffi::CString, mem::MaybeUninit};
+
+use bitflags::bitflags;
+use num_derive::{FromPrimitive, ToPrimitive};
+
+use crate::{
+    display::{
+        scaling::{display_viewport, DisplayView},
+        ObsDisplayRef, ObsDisplayScaleMode, DISPLAY_POSITIONS,
+    },
+    run_with_obs,
+    sources::ObsSourceTrait,
+    unsafe_send::Sendable,
+    utils::ObsError,
+};
+
+// The flags are signed on Windows
+#[allow(clippy::unnecessary_cast)]
+const fn flag(value: libobs::obs_interaction_flags) -> u32 {
+    value as u32
+}
+
+bitflags! {
+    /// Modifier keys and mouse buttons that are held down during an interaction.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+    pub struct ObsInteractionFlags: u32 {
+        const CAPS_KEY = flag(libobs::obs_interaction_flags_INTERACT_CAPS_KEY);
+        const SHIFT_KEY = flag(libobs::obs_interaction_flags_INTERACT_SHIFT_KEY);
+        const CONTROL_KEY = flag(libobs::obs_interaction_flags_INTERACT_CONTROL_KEY);
+        const ALT_KEY = flag(libobs::obs_interaction_flags_INTERACT_ALT_KEY);
+        const MOUSE_LEFT = flag(libobs::obs_interaction_flags_INTERACT_MOUSE_LEFT);
+        const MOUSE_MIDDLE = flag(libobs::obs_interaction_flags_INTERACT_MOUSE_MIDDLE);
+        const MOUSE_RIGHT = flag(libobs::obs_interaction_flags_INTERACT_MOUSE_RIGHT);
+        const COMMAND_KEY = flag(libobs::obs_interaction_flags_INTERACT_COMMAND_KEY);
+        const NUMLOCK_KEY = flag(libobs::obs_interaction_flags_INTERACT_NUMLOCK_KEY);
+        const IS_KEY_PAD = flag(libobs::obs_interaction_flags_INTERACT_IS_KEY_PAD);
+        const IS_LEFT = flag(libobs::obs_interaction_flags_INTERACT_IS_LEFT);
+        const IS_RIGHT = flag(libobs::obs_interaction_flags_INTERACT_IS_RIGHT);
+    }
+}
+
+#[cfg_attr(target_os = "windows", repr(i32))]
+#[cfg_attr(not(target_os = "windows"), repr(u32))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, FromPrimitive, ToPrimitive)]
+pub enum ObsMouseButton {
+    Left = libobs::obs_mouse_button_type_MOUSE_LEFT,
+    Middle = libobs::obs_mouse_button_type_MOUSE_MIDDLE,
+    Right = libobs::obs_mouse_button_type_MOUSE_RIGHT,
+}
+
+/// A mouse event, the position is relative to the source the event is sent to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ObsMouseEvent {
+    pub modifiers: ObsInteractionFlags,
+    pub x: i32,
+    pub y: i32,
+}
+
+impl ObsMouseEvent {
+    pub fn new(x: i32, y: i32, modifiers: ObsInteractionFlags) -> Self {
+        Self { modifiers, x, y }
+    }
+
+    fn as_raw(&self) -> libobs::obs_mouse_event {
+        libobs::obs_mouse_event {
+            modifiers: self.modifiers.bits(),
+            x: self.x,
+            y: self.y,
+        }
+    }
+}
+
+/// A keyboard event. The native fields are platform specific
+/// (e.g. the virtual key code on Windows) and are used by sources like the browser source.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ObsKeyEvent {
+    pub modifiers: ObsInteractionFlags,
+    /// The text that was typed, if any.
+    pub text: Option<String>,
+    pub native_modifiers: u32,
+    pub native_scancode: u32,
+    pub native_vkey: u32,
+}
+
+/// Sends mouse, keyboard and focus events to a source.
+/// Only sources with the `OBS_SOURCE_INTERACTION` output flag (e.g. browser sources) react to these events.
+pub trait ObsSourceInteraction {
+    fn send_mouse_click(
+        &self,
+        event: ObsMouseEvent,
+        button: ObsMouseButton,
+        mouse_up: bool,
+        click_count: u32,
+    ) -> Result<(), ObsError>;
+
+    /// Sends a mouse move event, set `mouse_leave` if the mouse left the source.
+    fn send_mouse_move(&self, event: ObsMouseEvent, mouse_leave: bool) -> Result<(), ObsError>;
+
+    fn send_mouse_wheel(
+        &self,
+        event: ObsMouseEvent,
+        x_delta: i32,
+        y_delta: i32,
+    ) -> Result<(), ObsError>;
+
+    fn send_key_click(&self, event: &ObsKeyEvent, key_up: bool) -> Result<(), ObsError>;
+
+    fn send_focus(&self, focus: bool) -> Result<(), ObsError>;
+}
+
+impl<T: ObsSourceTrait> ObsSourceInteraction for T {
+    fn send_mouse_click(
+        &self,
+        event: ObsMouseEvent,
+        button: ObsMouseButton,
+        mouse_up: bool,
+        click_count: u32,
+    ) -> Result<(), ObsError> {
+        let source_ptr = self.as_ptr();
+        let event = Sendable(event.as_raw());
+        run_with_obs!(self.runtime(), (source_ptr, event), move |token| unsafe {
+            // Safety: The source is valid because of the smart pointer and the event lives during the call
+            libobs::obs_source_send_mouse_click(
+                source_ptr.get_ptr(token),
+                &event.0,
+                button as i32,
+                mouse_up,
+                click_count,
+            );
+        })
+    }
+
+    fn send_mouse_move(&self, event: ObsMouseEvent, mouse_leave: bool) -> Result<(), ObsError> {
+        let source_ptr = self.as_ptr();
+        let event = Sendable(event.as_raw());
+        run_with_obs!(self.runtime(), (source_ptr, event), move |token| unsafe {
+            // Safety: The source is valid because of the smart pointer and the event lives during the call
+            libobs::obs_source_send_mouse_move(source_ptr.get_ptr(token), &event.0, mouse_leave);
+        })
+    }
+
+    fn send_mouse_wheel(
+        &self,
+        event: ObsMouseEvent,
+        x_delta: i32,
+        y_delta: i32,
+    ) -> Result<(), ObsError> {
+        let source_ptr = self.as_ptr();
+        let event = Sendable(event.as_raw());
+        run_with_obs!(self.runtime(), (source_ptr, event), move |token| unsafe {
+            // Safety: The source is valid because of the smart pointer and the event lives during the call
+            libobs::obs_source_send_mouse_wheel(
+                source_ptr.get_ptr(token),
+                &event.0,
+                x_delta,
+                y_delta,
+            );
+        })
+    }
+
+    fn send_key_click(&self, event: &ObsKeyEvent, key_up: bool) -> Result<(), ObsError> {
+        let text = match &event.text {
+            Some(text) => Some(CString::new(text.as_str()).map_err(|_| {
+                ObsError::InvalidOperation("Key event text contains a null byte".into())
+            })?),
+            None => None,
+        };
+
+        let source_ptr = self.as_ptr();
+        let event = Sendable(event.clone());
+        run_with_obs!(self.runtime(), (source_ptr, event, text), move |token| {
+            let raw_event = libobs::obs_key_event {
+                modifiers: event.0.modifiers.bits(),
+                text: text
+                    .as_ref()
+                    .map_or(std::ptr::null_mut(), |t| t.as_ptr() as *mut _),
+                native_modifiers: event.0.native_modifiers,
+                native_scancode: event.0.native_scancode,
+                native_vkey: event.0.native_vkey,
+            };
+
+            unsafe {
+                // Safety: The source is valid because of the smart pointer, the text is kept alive during the call
+                libobs::obs_source_send_key_click(source_ptr.get_ptr(token), &raw_event, key_up);
+            }
+        })
+    }
+
+    fn send_focus(&self, focus: bool) -> Result<(), ObsError> {
+        let source_ptr = self.as_ptr();
+        run_with_obs!(self.runtime(), (source_ptr), move |token| unsafe {
+            // Safety: The source is valid because of the smart pointer
+            libobs::obs_source_send_focus(source_ptr.get_ptr(token), focus);
+        })
+    }
+}
+
+impl ObsDisplayRef {
+    /// Returns the size of the display in pixels.
+    pub fn size(&self) -> Result<(u32, u32), ObsError> {
+        let display_ptr = self.as_ptr();
+        run_with_obs!(self.runtime, (display_ptr), move |token| {
+            let mut width = 0;
+            let mut height = 0;
+            unsafe {
+                // Safety: The pointer is valid because we are using a smart pointer
+                libobs::obs_display_size(display_ptr.get_ptr(token), &mut width, &mut height);
+            }
+
+            (width, height)
+        })
+    }
+
+    /// Converts a position in window coordinates (relative to the window the display was created in)
+    /// to the base canvas space, which is the coordinate space of scene items.
+    ///
+    /// Takes the zoom and pan of the display into account.
+    /// Returns `None` if the position is outside of the display.
+    pub fn window_to_canvas(&self, x: i32, y: i32) -> Result<Option<(f32, f32)>, ObsError> {
+        let (pos_x, pos_y) = DISPLAY_POSITIONS
+            .read()
+            .map_err(|_| {
+                ObsError::LockError("Failed to acquire read lock on display positions".into())
+            })?
+            .get(&self.id)
+            .cloned()
+            .unwrap_or((0, 0));
+
+        let (width, height) = self.size()?;
+        let (base_width, base_height) = get_base_size(self)?;
+
+        Ok(window_to_canvas(
+            self.scale_mode()?,
+            self.view()?,
+            (x - pos_x, y - pos_y),
+            (width, height),
+            (base_width, base_height),
+        ))
+    }
+}
+
+fn get_base_size(display: &ObsDisplayRef) -> Result<(u32, u32), ObsError> {
+    run_with_obs!(display.runtime, move || {
+        let mut ovi = MaybeUninit::<libobs::obs_video_info>::uninit();
+        let was_ok = unsafe {
+            // Safety: The pointer is valid, the struct is only read if the call succeeded.
+            libobs::obs_get_video_info(ovi.as_mut_ptr())
+        };
+
+        if !was_ok {
+            return Err(ObsError::InvalidOperation(
+                "Video is not initialized, can not get the canvas size".into(),
+            ));
+        }
+
+        let ovi = unsafe {
+            // Safety: was_ok checked that the video info was properly initialized
+            ovi.assume_init()
+        };
+
+        Ok((ovi.base_width, ovi.base_height))
+    })?
+}
+
+/// Maps a display position to the canvas viewport of the given scale mode.
+pub(super) fn window_to_canvas(
+    scale_mode: ObsDisplayScaleMode,
+    view: DisplayView,
+    (x, y): (i32, i32),
+    (width, height): (u32, u32),
+    (base_width, base_height): (u32, u32),
+) -> Option<(f32, f32)> {
+    if width == 0 || height == 0 || x < 0 || y < 0 || x as u32 >= width || y as u32 >= height {
+        return None;
+    }
+
+    let (view_x, view_y, view_width, view_height) =
+        display_viewport(scale_mode, view, (width, height), (base_width, base_height));
+    let (x, y) = (x - view_x, y - view_y);
+    if view_width <= 0 || view_height <= 0 || x < 0 || y < 0 || x >= view_width || y >= view_height
+    {
+        return None;
+    }
+
+    Some((
+        x as f32 * base_width as f32 / view_width as f32,
+        y as f32 * base_height as f32 / view_height as f32,
+    ))
+}
@@ -0,0 +1,51 @@
+#[cfg(test)]
+mod tests {
+    use super::super::{interaction, scaling::DisplayView, ObsDisplayScaleMode};
+
+    fn window_to_canvas(
+        scale_mode: ObsDisplayScaleMode,
+        pos: (i32, i32),
+        size: (u32, u32),
+        base: (u32, u32),
+    ) -> Option<(f32, f32)> {
+        interaction::window_to_canvas(scale_mode, DisplayView::default(), pos, size, base)
+    }
+
+    #[test]
+    fn test_window_to_canvas() {
+        let window_to_canvas =
+            |pos, size, base| window_to_canvas(ObsDisplayScaleMode::Stretch, pos, size, base);
+
+        assert_eq!(
+            window_to_canvas((480, 270), (960, 540), (1920, 1080)),
+            Some((960.0, 540.0))
+        );
+        assert_eq!(
+            window_to_canvas((0, 0), (960, 540), (1920, 1080)),
+            Some((0.0, 0.0))
+        );
+        assert_eq!(window_to_canvas((960, 0), (960, 540), (1920, 1080)), None);
+        assert_eq!(window_to_canvas((-1, 10), (960, 540), (1920, 1080)), None);
+        assert_eq!(window_to_canvas((10, 10), (0, 0), (1920, 1080)), None);
+    }
+
+    #[test]
+    fn test_window_to_canvas_letterboxed() {
+        let fit = ObsDisplayScaleMode::Fit;
+        // The canvas is 800x450 and centered vertically
+        assert_eq!(
+            window_to_canvas(fit, (400, 10), (800, 800), (1920, 1080)),
+            None
+        );
+        assert_eq!(
+            window_to_canvas(fit, (400, 400), (800, 800), (1920, 1080)),
+            Some((960.0, 540.0))
+        );
+
+        let canvas = ObsDisplayScaleMode::Canvas;
+        assert_eq!(
+            window_to_canvas(canvas, (0, 0), (960, 540), (1920, 1080)),
+            Some((480.0, 270.0))
+        );
+    }
+}
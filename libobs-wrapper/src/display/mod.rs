@@ -3,6 +3,12 @@
 
 mod creation_data;
 mod enums;
+pub mod interaction;
+#[cfg(test)]
+mod interaction_tests;
+mod scaling;
+#[cfg(test)]
+mod scaling_tests;
 //TODO
 mod window_manager;
 
@@ -11,7 +17,10 @@ pub use window_manager::{MiscDisplayTrait, ShowHideTrait, WindowPositionTrait};
 pub use creation_data::*;
 pub use enums::*;
 use libobs::obs_video_info;
+use scaling::DisplayView;
+pub use scaling::ObsDisplayScaleMode;
 
+use crate::graphics::GraphicsContext;
 use crate::unsafe_send::SmartPointerSendable;
 use crate::utils::{ObsDropGuard, ObsError};
 use crate::{impl_obs_drop, run_with_obs, runtime::ObsRuntime, unsafe_send::Sendable};
@@ -26,6 +35,12 @@ use std::{
 
 #[allow(dead_code)]
 static ID_COUNTER: AtomicUsize = AtomicUsize::new(1);
+static DRAW_CALLBACK_ID_COUNTER: AtomicUsize = AtomicUsize::new(1);
+
+/// A custom draw callback of a display, see `ObsDisplayRef::add_draw_callback`.
+pub type ObsDrawCallback = dyn Fn(&mut GraphicsContext) + Send + Sync + 'static;
+type DrawCallbackList = Vec<(usize, Arc<ObsDrawCallback>)>;
+
 #[derive(Debug, Clone)]
 /// You can use the `ObsContext` to create this struct. This struct is stored in the
 /// `ObsContext` itself and the display is removed if every instance of this struct is dropped
@@ -38,6 +53,10 @@ pub struct ObsDisplayRef {
 
     _pos_remove_guard: Arc<PosRemoveGuard>,
 
+    /// Declared before the child window handlers, so the display is destroyed before the
+    /// window it renders into.
+    display: SmartPointerSendable<*mut libobs::obs_display_t>,
+
     /// Keep for window, manager is accessed by render thread as well so Arc and RwLock
     ///
     /// This is mostly used on windows to handle the size and position of the child window.
@@ -46,14 +65,35 @@ pub struct ObsDisplayRef {
     child_window_handler:
         Option<Arc<RwLock<window_manager::windows::WindowsPreviewChildWindowHandler>>>,
 
+    /// The X11 subwindow or Wayland subsurface the display renders into.
+    #[cfg(target_os = "linux")]
+    child_window_handler:
+        Option<Arc<RwLock<window_manager::linux::LinuxPreviewChildWindowHandler>>>,
+
+    /// The `NSView` the display renders into.
+    #[cfg(target_os = "macos")]
+    child_window_handler:
+        Option<Arc<RwLock<window_manager::macos::MacOSPreviewChildWindowHandler>>>,
+
     /// Stored so the obs context is not dropped while this is alive
     runtime: ObsRuntime,
-    display: SmartPointerSendable<*mut libobs::obs_display_t>,
 }
 
 lazy_static! {
     pub(super) static ref DISPLAY_POSITIONS: Arc<RwLock<HashMap<usize, (i32, i32)>>> =
         Arc::new(RwLock::new(HashMap::new()));
+
+    /// How the canvas is scaled inside of each display, read by the render callback.
+    pub(super) static ref DISPLAY_SCALE_MODES: RwLock<HashMap<usize, ObsDisplayScaleMode>> =
+        RwLock::new(HashMap::new());
+
+    /// Zoom and pan of each display, read by the render callback.
+    pub(super) static ref DISPLAY_VIEWS: RwLock<HashMap<usize, DisplayView>> =
+        RwLock::new(HashMap::new());
+
+    /// Custom draw callbacks of each display, executed after the scene has been rendered.
+    static ref DISPLAY_DRAW_CALLBACKS: RwLock<HashMap<usize, DrawCallbackList>> =
+        RwLock::new(HashMap::new());
 }
 
 #[derive(Debug)]
@@ -65,6 +105,15 @@ impl Drop for PosRemoveGuard {
     fn drop(&mut self) {
         let mut map = DISPLAY_POSITIONS.write().unwrap();
         map.remove(&self.id);
+
+        let mut scale_modes = DISPLAY_SCALE_MODES.write().unwrap();
+        scale_modes.remove(&self.id);
+
+        let mut views = DISPLAY_VIEWS.write().unwrap();
+        views.remove(&self.id);
+
+        let mut callbacks = DISPLAY_DRAW_CALLBACKS.write().unwrap();
+        callbacks.remove(&self.id);
     }
 }
 
@@ -81,6 +130,18 @@ unsafe extern "C" fn render_display(data: *mut c_void, width: u32, height: u32)
         .cloned()
         .unwrap_or((0, 0));
 
+    let scale_mode = DISPLAY_SCALE_MODES
+        .read()
+        .ok()
+        .and_then(|modes| modes.get(&id).cloned())
+        .unwrap_or_default();
+
+    let view = DISPLAY_VIEWS
+        .read()
+        .ok()
+        .and_then(|views| views.get(&id).cloned())
+        .unwrap_or_default();
+
     let mut ovi = MaybeUninit::<obs_video_info>::uninit();
     let was_ok = libobs::obs_get_video_info(ovi.as_mut_ptr());
     if !was_ok {
@@ -104,11 +165,35 @@ unsafe extern "C" fn render_display(data: *mut c_void, width: u32, height: u32)
         -100.0f32,
         100.0f32,
     );
-    libobs::gs_set_viewport(pos.0, pos.1, width as i32, height as i32);
+    let (view_x, view_y, view_width, view_height) = scaling::display_viewport(
+        scale_mode,
+        view,
+        (width, height),
+        (ovi.base_width, ovi.base_height),
+    );
+    libobs::gs_set_viewport(pos.0 + view_x, pos.1 + view_y, view_width, view_height);
     //draw_backdrop(&s.buffers, ovi.base_width as f32, ovi.base_height as f32);
 
     libobs::obs_render_main_texture_src_color_only();
 
+    // Cloning the callbacks so they can add or remove callbacks without deadlocking
+    let callbacks = DISPLAY_DRAW_CALLBACKS
+        .read()
+        .ok()
+        .and_then(|callbacks| callbacks.get(&id).cloned())
+        .unwrap_or_default();
+
+    if !callbacks.is_empty() {
+        let mut ctx = GraphicsContext::new(width, height, ovi.base_width, ovi.base_height);
+        for (_, callback) in callbacks {
+            let result =
+                std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| callback(&mut ctx)));
+            if result.is_err() {
+                log::error!("Draw callback of display {} panicked", id);
+            }
+        }
+    }
+
     libobs::gs_projection_pop();
     libobs::gs_viewport_pop();
 }
@@ -203,11 +288,13 @@ impl ObsDisplayRef {
             y,
             background_color,
             create_child,
-            #[cfg(windows)]
+            enabled,
+            scale_mode,
+            #[cfg(any(windows, target_os = "linux", target_os = "macos"))]
             height,
-            #[cfg(windows)]
+            #[cfg(any(windows, target_os = "linux", target_os = "macos"))]
             width,
-            #[cfg(windows)]
+            #[cfg(any(windows, target_os = "linux", target_os = "macos"))]
             window_handle,
             ..
         } = data.clone();
@@ -230,7 +317,44 @@ impl ObsDisplayRef {
         #[cfg(windows)]
         let init_data = Sendable(data.build(child_handler.as_ref().map(|e| e.get_window_handle())));
 
-        #[cfg(not(windows))]
+        #[cfg(target_os = "linux")]
+        let child_handler = if create_child {
+            Some(
+                window_manager::linux::LinuxPreviewChildWindowHandler::new_child(
+                    window_handle.clone(),
+                    x,
+                    y,
+                    width,
+                    height,
+                    runtime.clone(),
+                )?,
+            )
+        } else {
+            None
+        };
+
+        #[cfg(target_os = "linux")]
+        let init_data = Sendable(data.build(child_handler.as_ref().map(|e| e.get_window_handle())));
+
+        #[cfg(target_os = "macos")]
+        let child_handler = if create_child {
+            Some(
+                window_manager::macos::MacOSPreviewChildWindowHandler::new_child(
+                    window_handle.clone(),
+                    x,
+                    y,
+                    width,
+                    height,
+                )?,
+            )
+        } else {
+            None
+        };
+
+        #[cfg(target_os = "macos")]
+        let init_data = Sendable(data.build(child_handler.as_ref().map(|e| e.get_window_handle())));
+
+        #[cfg(not(any(windows, target_os = "linux", target_os = "macos")))]
         let init_data = Sendable(data.build(None));
 
         log::trace!("Creating obs display...");
@@ -241,7 +365,7 @@ impl ObsDisplayRef {
             };
 
             if display_ptr.is_null() {
-                Err(ObsError::NullPointer(None))
+                Err(ObsError::NullPointer(None).in_function("obs_display_create"))
             } else {
                 Ok(Sendable(display_ptr))
             }
@@ -260,17 +384,22 @@ impl ObsDisplayRef {
             handler.set_display_handle(display.clone());
         }
 
-        let initial_pos = if create_child && cfg!(windows) {
-            (0, 0)
-        } else {
-            (x, y)
-        };
+        let initial_pos =
+            if create_child && cfg!(any(windows, target_os = "linux", target_os = "macos")) {
+                (0, 0)
+            } else {
+                (x, y)
+            };
 
         let id = ID_COUNTER.fetch_add(1, Ordering::Relaxed);
         DISPLAY_POSITIONS
             .write()
             .map_err(|e| ObsError::LockError(format!("{:?}", e)))?
             .insert(id, initial_pos);
+        DISPLAY_SCALE_MODES
+            .write()
+            .map_err(|e| ObsError::LockError(format!("{:?}", e)))?
+            .insert(id, scale_mode);
 
         let instance = Self {
             display: display.clone(),
@@ -280,19 +409,29 @@ impl ObsDisplayRef {
 
             #[cfg(windows)]
             child_window_handler: child_handler.map(|e| Arc::new(RwLock::new(e))),
+
+            #[cfg(target_os = "linux")]
+            child_window_handler: child_handler.map(|e| Arc::new(RwLock::new(e))),
+
+            #[cfg(target_os = "macos")]
+            child_window_handler: child_handler.map(|e| Arc::new(RwLock::new(e))),
         };
 
         log::trace!("Adding draw callback with display {:?}", instance.display);
 
         let display_ptr = instance.as_ptr();
-        run_with_obs!(runtime, (display_ptr), move || {
+        run_with_obs!(runtime, (display_ptr), move |token| {
             unsafe {
                 // Safety: The pointer is valid because we are using a smart pointer
                 libobs::obs_display_add_draw_callback(
-                    display_ptr.get_ptr(),
+                    display_ptr.get_ptr(token),
                     Some(render_display),
                     id as *mut c_void,
                 );
+
+                if !enabled {
+                    libobs::obs_display_set_enabled(display_ptr.get_ptr(token), false);
+                }
             }
         })?;
 
@@ -303,12 +442,132 @@ impl ObsDisplayRef {
         self.id
     }
 
+    /// Registers a closure that is executed on the graphics thread every time this display is rendered,
+    /// after the scene has been drawn. Use the given `GraphicsContext` to render overlays like
+    /// safe-area guides or selection outlines.
+    ///
+    /// Returns an id which can be used to remove the callback with `remove_draw_callback`.
+    /// Callbacks are removed automatically once the display is dropped.
+    pub fn add_draw_callback<F>(&self, callback: F) -> Result<usize, ObsError>
+    where
+        F: Fn(&mut GraphicsContext) + Send + Sync + 'static,
+    {
+        use std::sync::atomic::Ordering;
+
+        let callback_id = DRAW_CALLBACK_ID_COUNTER.fetch_add(1, Ordering::Relaxed);
+        DISPLAY_DRAW_CALLBACKS
+            .write()
+            .map_err(|_| {
+                ObsError::LockError("Failed to acquire write lock on draw callbacks".into())
+            })?
+            .entry(self.id)
+            .or_default()
+            .push((callback_id, Arc::new(callback)));
+
+        Ok(callback_id)
+    }
+
+    /// Removes a draw callback that was registered with `add_draw_callback`.
+    /// Returns `false` if there was no callback with the given id.
+    pub fn remove_draw_callback(&self, callback_id: usize) -> Result<bool, ObsError> {
+        let mut callbacks = DISPLAY_DRAW_CALLBACKS.write().map_err(|_| {
+            ObsError::LockError("Failed to acquire write lock on draw callbacks".into())
+        })?;
+
+        let Some(display_callbacks) = callbacks.get_mut(&self.id) else {
+            return Ok(false);
+        };
+
+        let len_before = display_callbacks.len();
+        display_callbacks.retain(|(id, _)| *id != callback_id);
+
+        Ok(display_callbacks.len() != len_before)
+    }
+
+    /// Changes how the canvas is scaled inside of this display, see `ObsDisplayScaleMode`.
+    pub fn set_scale_mode(&self, scale_mode: ObsDisplayScaleMode) -> Result<(), ObsError> {
+        DISPLAY_SCALE_MODES
+            .write()
+            .map_err(|_| ObsError::LockError("Failed to acquire write lock on scale modes".into()))?
+            .insert(self.id, scale_mode);
+
+        Ok(())
+    }
+
+    pub fn scale_mode(&self) -> Result<ObsDisplayScaleMode, ObsError> {
+        let scale_mode = DISPLAY_SCALE_MODES
+            .read()
+            .map_err(|_| ObsError::LockError("Failed to acquire read lock on scale modes".into()))?
+            .get(&self.id)
+            .cloned()
+            .unwrap_or_default();
+
+        Ok(scale_mode)
+    }
+
+    /// Zooms the canvas by `factor` on top of the scale mode, e.g. `2.0` shows the canvas twice
+    /// as large as `ObsDisplayScaleMode::Fit` would. The canvas is zoomed around the center of
+    /// the display. Only the preview is affected, the scene and the output stay untouched.
+    ///
+    /// Use `ObsDisplayScaleMode::Canvas` with a zoom of `1.0` for 1:1 pixel inspection.
+    pub fn set_zoom(&self, factor: f32) -> Result<(), ObsError> {
+        if !factor.is_finite() || factor <= 0.0 {
+            return Err(ObsError::InvalidOperation(format!(
+                "The zoom factor must be positive, got {}",
+                factor
+            )));
+        }
+
+        self.update_view(|view| view.zoom = factor)
+    }
+
+    pub fn zoom(&self) -> Result<f32, ObsError> {
+        Ok(self.view()?.zoom)
+    }
+
+    /// Moves the canvas so the point `(x, y)` in canvas pixels, relative to the center of
+    /// the canvas, is shown in the center of the display. `(0, 0)` centers the canvas again.
+    pub fn set_pan(&self, x: f32, y: f32) -> Result<(), ObsError> {
+        self.update_view(|view| view.pan = (x, y))
+    }
+
+    pub fn pan(&self) -> Result<(f32, f32), ObsError> {
+        Ok(self.view()?.pan)
+    }
+
+    /// Resets the zoom to `1.0` and the pan to `(0, 0)`.
+    pub fn reset_view(&self) -> Result<(), ObsError> {
+        self.update_view(|view| *view = DisplayView::default())
+    }
+
+    pub(super) fn view(&self) -> Result<DisplayView, ObsError> {
+        let view = DISPLAY_VIEWS
+            .read()
+            .map_err(|_| {
+                ObsError::LockError("Failed to acquire read lock on display views".into())
+            })?
+            .get(&self.id)
+            .cloned()
+            .unwrap_or_default();
+
+        Ok(view)
+    }
+
+    fn update_view(&self, update: impl FnOnce(&mut DisplayView)) -> Result<(), ObsError> {
+        let mut views = DISPLAY_VIEWS.write().map_err(|_| {
+            ObsError::LockError("Failed to acquire write lock on display views".into())
+        })?;
+
+        update(views.entry(self.id).or_default());
+        Ok(())
+    }
+
     pub fn update_color_space(&self) -> Result<(), ObsError> {
         let display_ptr = self.as_ptr();
-        run_with_obs!(self.runtime, (display_ptr), move || {
+        run_with_obs!(self.runtime, (display_ptr), move |token| {
             unsafe {
                 // Safety: The pointer is valid because we are using a smart pointer
-                libobs::obs_display_update_color_space(display_ptr.get_ptr())
+                libobs::obs_display_update_color_space(display_ptr.get_ptr(token))
             }
         })
     }
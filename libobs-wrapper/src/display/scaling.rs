@@ -0,0 +1,96 @@
+/// Describes how the canvas is placed inside of a display.
+///
+/// The area that is not covered by the canvas is filled with the background color of the display.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum ObsDisplayScaleMode {
+    /// Stretches the canvas over the whole display, ignoring the aspect ratio.
+    #[default]
+    Stretch,
+    /// Scales the canvas to the largest size that fits into the display while keeping
+    /// the aspect ratio, centered. This is what the OBS Studio preview does.
+    Fit,
+    /// Renders the canvas 1:1 without any scaling, centered. Parts of the canvas that
+    /// are larger than the display are cut off.
+    Canvas,
+}
+
+/// Zoom and pan of a display on top of its scale mode, see `ObsDisplayRef::set_zoom`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct DisplayView {
+    pub zoom: f32,
+    /// Offset of the canvas point in the center of the display, in canvas pixels
+    pub pan: (f32, f32),
+}
+
+impl Default for DisplayView {
+    fn default() -> Self {
+        Self {
+            zoom: 1.0,
+            pan: (0.0, 0.0),
+        }
+    }
+}
+
+/// Returns the viewport `(x, y, width, height)` of the canvas, relative to the top-left corner of the display.
+pub(crate) fn canvas_viewport(
+    mode: ObsDisplayScaleMode,
+    (width, height): (u32, u32),
+    (base_width, base_height): (u32, u32),
+) -> (i32, i32, i32, i32) {
+    let (view_width, view_height) = match mode {
+        ObsDisplayScaleMode::Stretch => return (0, 0, width as i32, height as i32),
+        ObsDisplayScaleMode::Canvas => (base_width as i32, base_height as i32),
+        ObsDisplayScaleMode::Fit => {
+            if base_width == 0 || base_height == 0 {
+                return (0, 0, width as i32, height as i32);
+            }
+
+            let scale = f64::min(
+                width as f64 / base_width as f64,
+                height as f64 / base_height as f64,
+            );
+
+            (
+                (base_width as f64 * scale).round() as i32,
+                (base_height as f64 * scale).round() as i32,
+            )
+        }
+    };
+
+    (
+        (width as i32 - view_width) / 2,
+        (height as i32 - view_height) / 2,
+        view_width,
+        view_height,
+    )
+}
+
+/// Applies the zoom and pan of the view to the viewport of [`canvas_viewport`].
+/// The canvas is zoomed around the center of the display.
+pub(crate) fn display_viewport(
+    mode: ObsDisplayScaleMode,
+    view: DisplayView,
+    size: (u32, u32),
+    (base_width, base_height): (u32, u32),
+) -> (i32, i32, i32, i32) {
+    let viewport = canvas_viewport(mode, size, (base_width, base_height));
+    if view == DisplayView::default() || base_width == 0 || base_height == 0 {
+        return viewport;
+    }
+
+    let (x, y, width, height) = viewport;
+    let center_x = x as f32 + width as f32 / 2.0;
+    let center_y = y as f32 + height as f32 / 2.0;
+
+    let width = width as f32 * view.zoom;
+    let height = height as f32 * view.zoom;
+    let pan_x = view.pan.0 * width / base_width as f32;
+    let pan_y = view.pan.1 * height / base_height as f32;
+
+    (
+        (center_x - width / 2.0 - pan_x).round() as i32,
+        (center_y - height / 2.0 - pan_y).round() as i32,
+        width.round() as i32,
+        height.round() as i32,
+    )
+}
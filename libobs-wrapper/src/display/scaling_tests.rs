@@ -0,0 +1,58 @@
+#[cfg(test)]
+mod tests {
+    use super::super::scaling::{
+        canvas_viewport, display_viewport, DisplayView, ObsDisplayScaleMode,
+    };
+
+    #[test]
+    fn test_canvas_viewport() {
+        let base = (1920, 1080);
+
+        assert_eq!(
+            canvas_viewport(ObsDisplayScaleMode::Stretch, (800, 800), base),
+            (0, 0, 800, 800)
+        );
+        assert_eq!(
+            canvas_viewport(ObsDisplayScaleMode::Fit, (800, 800), base),
+            (0, 175, 800, 450)
+        );
+        assert_eq!(
+            canvas_viewport(ObsDisplayScaleMode::Fit, (1000, 540), base),
+            (20, 0, 960, 540)
+        );
+        assert_eq!(
+            canvas_viewport(ObsDisplayScaleMode::Canvas, (960, 540), base),
+            (-480, -270, 1920, 1080)
+        );
+    }
+
+    #[test]
+    fn test_display_viewport() {
+        let base = (1920, 1080);
+        let fit = ObsDisplayScaleMode::Fit;
+
+        assert_eq!(
+            display_viewport(fit, DisplayView::default(), (800, 800), base),
+            (0, 175, 800, 450)
+        );
+
+        let zoomed = DisplayView {
+            zoom: 2.0,
+            pan: (0.0, 0.0),
+        };
+        assert_eq!(
+            display_viewport(fit, zoomed, (800, 800), base),
+            (-400, -50, 1600, 900)
+        );
+
+        // The canvas point (1440, 540) is in the center of the display
+        let panned = DisplayView {
+            zoom: 2.0,
+            pan: (480.0, 0.0),
+        };
+        assert_eq!(
+            display_viewport(fit, panned, (800, 800), base),
+            (-800, -50, 1600, 900)
+        );
+    }
+}
@@ -0,0 +1,562 @@
+//! Child windows for displays on Linux, so previews can be moved, resized and hidden inside
+//! of the application window just like on Windows.
+//!
+//! On X11 the display renders into a subwindow of the given window. On Wayland it renders into
+//! a desynchronized subsurface of the given surface, which has an empty input region so pointer
+//! events still reach the application.
+
+use std::{
+    ffi::CStr,
+    os::raw::{c_char, c_ulong, c_void},
+    ptr,
+};
+
+use crate::{
+    display::ObsWindowHandle,
+    run_with_obs,
+    runtime::ObsRuntime,
+    unsafe_send::Sendable,
+    utils::{
+        linux::{
+            wl_compositor_interface, wl_display_create_queue, wl_display_flush,
+            wl_display_roundtrip_queue, wl_event_queue_destroy, wl_proxy_add_listener,
+            wl_proxy_create_wrapper, wl_proxy_destroy, wl_proxy_get_display, wl_proxy_get_version,
+            wl_proxy_marshal_flags, wl_proxy_set_queue, wl_proxy_wrapper_destroy,
+            wl_region_interface, wl_registry_interface, wl_subcompositor_interface,
+            wl_subsurface_interface, wl_surface_interface, WlInterface, XCreateSimpleWindow,
+            XDestroyWindow, XFlush, XLowerWindow, XMapWindow, XMoveWindow, XRaiseWindow,
+            XResizeWindow, XUnmapWindow,
+        },
+        ObsError,
+    },
+};
+
+const WL_MARSHAL_FLAG_DESTROY: u32 = 1;
+
+// Request opcodes, see wayland.xml
+const WL_DISPLAY_GET_REGISTRY: u32 = 1;
+const WL_REGISTRY_BIND: u32 = 0;
+const WL_COMPOSITOR_CREATE_SURFACE: u32 = 0;
+const WL_COMPOSITOR_CREATE_REGION: u32 = 1;
+const WL_SUBCOMPOSITOR_DESTROY: u32 = 0;
+const WL_SUBCOMPOSITOR_GET_SUBSURFACE: u32 = 1;
+const WL_SURFACE_DESTROY: u32 = 0;
+const WL_SURFACE_ATTACH: u32 = 1;
+const WL_SURFACE_SET_INPUT_REGION: u32 = 5;
+const WL_SURFACE_COMMIT: u32 = 6;
+const WL_SUBSURFACE_DESTROY: u32 = 0;
+const WL_SUBSURFACE_SET_POSITION: u32 = 1;
+const WL_SUBSURFACE_PLACE_ABOVE: u32 = 2;
+const WL_SUBSURFACE_PLACE_BELOW: u32 = 3;
+const WL_SUBSURFACE_SET_DESYNC: u32 = 5;
+const WL_REGION_DESTROY: u32 = 0;
+
+#[derive(Debug)]
+enum ChildWindow {
+    X11 {
+        display: Sendable<*mut c_void>,
+        window: c_ulong,
+    },
+    Wayland(WaylandSubsurface),
+}
+
+#[derive(Debug)]
+pub(crate) struct LinuxPreviewChildWindowHandler {
+    child: ChildWindow,
+    window_handle: ObsWindowHandle,
+    runtime: ObsRuntime,
+
+    pub(in crate::display::window_manager) x: i32,
+    pub(in crate::display::window_manager) y: i32,
+
+    pub(in crate::display::window_manager) width: u32,
+    pub(in crate::display::window_manager) height: u32,
+
+    pub(in crate::display::window_manager) is_hidden: bool,
+    pub(in crate::display::window_manager) render_at_bottom: bool,
+}
+
+impl LinuxPreviewChildWindowHandler {
+    pub fn new_child(
+        parent: ObsWindowHandle,
+        x: i32,
+        y: i32,
+        width: u32,
+        height: u32,
+        runtime: ObsRuntime,
+    ) -> Result<Self, ObsError> {
+        log::trace!("Creating LinuxPreviewChildWindowHandler...");
+        let (child, window_handle) = if parent.is_wayland {
+            let surface = unsafe {
+                // Safety: The window handle of a Wayland display holds a valid wl_surface
+                WaylandSubsurface::new(parent.window.0.display, x, y)?
+            };
+
+            let window_handle = ObsWindowHandle {
+                window: Sendable(libobs::gs_window {
+                    display: surface.surface.0,
+                    id: 0,
+                }),
+                is_wayland: true,
+            };
+            (ChildWindow::Wayland(surface), window_handle)
+        } else {
+            let display = Sendable(parent.window.0.display);
+            let parent_id = parent.window.0.id as c_ulong;
+            let window = run_with_obs!(runtime, (display), move || unsafe {
+                // Safety: The display was opened for OBS and the parent is a window of it.
+                // Xlib is only used on the OBS thread, so the calls are not interleaved.
+                let window = XCreateSimpleWindow(
+                    display.0,
+                    parent_id,
+                    x,
+                    y,
+                    width.max(1),
+                    height.max(1),
+                    0,
+                    0,
+                    0,
+                );
+                if window != 0 {
+                    XMapWindow(display.0, window);
+                    XFlush(display.0);
+                }
+
+                window
+            })?;
+
+            if window == 0 {
+                return Err(ObsError::DisplayCreationError(
+                    "Failed to create X11 child window".to_string(),
+                ));
+            }
+
+            let window_handle = ObsWindowHandle {
+                window: Sendable(libobs::gs_window {
+                    display: display.0,
+                    id: window as u32,
+                }),
+                is_wayland: false,
+            };
+            (ChildWindow::X11 { display, window }, window_handle)
+        };
+
+        Ok(Self {
+            child,
+            window_handle,
+            runtime,
+            x,
+            y,
+            width,
+            height,
+            is_hidden: false,
+            render_at_bottom: false,
+        })
+    }
+
+    pub fn get_window_handle(&self) -> ObsWindowHandle {
+        self.window_handle.clone()
+    }
+
+    pub(in crate::display::window_manager) fn set_pos(
+        &mut self,
+        x: i32,
+        y: i32,
+    ) -> Result<(), ObsError> {
+        self.x = x;
+        self.y = y;
+
+        match &self.child {
+            ChildWindow::X11 { display, window } => {
+                let window = *window;
+                run_with_obs!(self.runtime, (display), move || unsafe {
+                    // Safety: The window is owned by this handler and is still alive
+                    XMoveWindow(display.0, window, x, y);
+                    XFlush(display.0);
+                })
+            }
+            ChildWindow::Wayland(surface) => {
+                unsafe {
+                    // Safety: The subsurface is owned by this handler and is still alive
+                    surface.set_position(x, y);
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Resizes the X11 window. Wayland surfaces take the size of the buffers OBS renders,
+    /// which follow `obs_display_resize`.
+    pub(in crate::display::window_manager) fn set_size(
+        &mut self,
+        width: u32,
+        height: u32,
+    ) -> Result<(), ObsError> {
+        self.width = width;
+        self.height = height;
+
+        if let ChildWindow::X11 { display, window } = &self.child {
+            let window = *window;
+            run_with_obs!(self.runtime, (display), move || unsafe {
+                // Safety: The window is owned by this handler and is still alive
+                XResizeWindow(display.0, window, width.max(1), height.max(1));
+                XFlush(display.0);
+            })?;
+        }
+
+        Ok(())
+    }
+
+    pub(in crate::display::window_manager) fn set_render_at_bottom(
+        &mut self,
+        render_at_bottom: bool,
+    ) -> Result<(), ObsError> {
+        self.render_at_bottom = render_at_bottom;
+
+        match &self.child {
+            ChildWindow::X11 { display, window } => {
+                let window = *window;
+                run_with_obs!(self.runtime, (display), move || unsafe {
+                    // Safety: The window is owned by this handler and is still alive
+                    if render_at_bottom {
+                        XLowerWindow(display.0, window);
+                    } else {
+                        XRaiseWindow(display.0, window);
+                    }
+                    XFlush(display.0);
+                })
+            }
+            ChildWindow::Wayland(surface) => {
+                unsafe {
+                    // Safety: The subsurface is owned by this handler and is still alive
+                    surface.set_render_at_bottom(render_at_bottom);
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Shows or hides the child window. On Wayland the display has to stop rendering as well,
+    /// as every rendered frame would attach a new buffer to the surface.
+    pub(in crate::display::window_manager) fn set_hidden(
+        &mut self,
+        hidden: bool,
+    ) -> Result<(), ObsError> {
+        self.is_hidden = hidden;
+
+        match &self.child {
+            ChildWindow::X11 { display, window } => {
+                let window = *window;
+                run_with_obs!(self.runtime, (display), move || unsafe {
+                    // Safety: The window is owned by this handler and is still alive
+                    if hidden {
+                        XUnmapWindow(display.0, window);
+                    } else {
+                        XMapWindow(display.0, window);
+                    }
+                    XFlush(display.0);
+                })
+            }
+            ChildWindow::Wayland(surface) => {
+                if hidden {
+                    unsafe {
+                        // Safety: The surface is owned by this handler and is still alive
+                        surface.unmap();
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+
+    pub(in crate::display::window_manager) fn is_wayland(&self) -> bool {
+        matches!(self.child, ChildWindow::Wayland(_))
+    }
+}
+
+impl Drop for LinuxPreviewChildWindowHandler {
+    fn drop(&mut self) {
+        log::trace!("Dropping LinuxPreviewChildWindowHandler...");
+        if let ChildWindow::X11 { display, window } = &self.child {
+            let window = *window;
+            let result = run_with_obs!(self.runtime, (display), move || unsafe {
+                // Safety: The window is owned by this handler and the display of OBS is
+                // destroyed before, as it is declared first in `ObsDisplayRef`
+                XDestroyWindow(display.0, window);
+                XFlush(display.0);
+            });
+
+            if let Err(e) = result {
+                log::error!("Failed to destroy X11 child window: {:?}", e);
+            }
+        }
+    }
+}
+
+/// A `wl_subsurface` with its own `wl_surface`, created on a private event queue so the
+/// event loop of the application is not disturbed.
+#[derive(Debug)]
+struct WaylandSubsurface {
+    display: Sendable<*mut c_void>,
+    queue: Sendable<*mut c_void>,
+    compositor: Sendable<*mut c_void>,
+    subcompositor: Sendable<*mut c_void>,
+    parent: Sendable<*mut c_void>,
+    surface: Sendable<*mut c_void>,
+    subsurface: Sendable<*mut c_void>,
+}
+
+#[derive(Default)]
+struct WaylandGlobals {
+    compositor: *mut c_void,
+    subcompositor: *mut c_void,
+}
+
+#[repr(C)]
+struct WlRegistryListener {
+    global: unsafe extern "C" fn(*mut c_void, *mut c_void, u32, *const c_char, u32),
+    global_remove: unsafe extern "C" fn(*mut c_void, *mut c_void, u32),
+}
+
+static REGISTRY_LISTENER: WlRegistryListener = WlRegistryListener {
+    global: registry_global,
+    global_remove: registry_global_remove,
+};
+
+/// # Safety
+/// Must only be called by libwayland with a `WaylandGlobals` as data.
+unsafe extern "C" fn registry_global(
+    data: *mut c_void,
+    registry: *mut c_void,
+    name: u32,
+    interface: *const c_char,
+    version: u32,
+) {
+    let globals = &mut *(data as *mut WaylandGlobals);
+    let (target, wl_interface, version) = match CStr::from_ptr(interface).to_bytes() {
+        b"wl_compositor" => (
+            &mut globals.compositor,
+            &wl_compositor_interface,
+            version.min(4),
+        ),
+        b"wl_subcompositor" => (&mut globals.subcompositor, &wl_subcompositor_interface, 1),
+        _ => return,
+    };
+
+    *target = wl_proxy_marshal_flags(
+        registry,
+        WL_REGISTRY_BIND,
+        wl_interface,
+        version,
+        0,
+        name,
+        interface,
+        version,
+        ptr::null_mut::<c_void>(),
+    );
+}
+
+unsafe extern "C" fn registry_global_remove(
+    _data: *mut c_void,
+    _registry: *mut c_void,
+    _name: u32,
+) {
+}
+
+/// Sends a request that creates a new object and takes no other arguments.
+///
+/// # Safety
+/// The proxy must be valid.
+unsafe fn create(proxy: *mut c_void, opcode: u32, interface: &WlInterface) -> *mut c_void {
+    wl_proxy_marshal_flags(
+        proxy,
+        opcode,
+        interface,
+        wl_proxy_get_version(proxy),
+        0,
+        ptr::null_mut::<c_void>(),
+    )
+}
+
+/// # Safety
+/// The proxy must be valid. It is invalid afterwards if `destroy` is set.
+unsafe fn request(proxy: *mut c_void, opcode: u32, destroy: bool) {
+    let flags = if destroy { WL_MARSHAL_FLAG_DESTROY } else { 0 };
+    wl_proxy_marshal_flags(
+        proxy,
+        opcode,
+        ptr::null(),
+        wl_proxy_get_version(proxy),
+        flags,
+    );
+}
+
+impl WaylandSubsurface {
+    /// # Safety
+    /// `parent` must be a valid `wl_surface`.
+    unsafe fn new(parent: *mut c_void, x: i32, y: i32) -> Result<Self, ObsError> {
+        let display = wl_proxy_get_display(parent).map_err(|e| {
+            ObsError::DisplayCreationError(format!(
+                "Could not get the Wayland display of the surface: {}",
+                e
+            ))
+        })?;
+
+        let queue = wl_display_create_queue(display);
+        let wrapper = wl_proxy_create_wrapper(display);
+        wl_proxy_set_queue(wrapper, queue);
+        let registry = create(wrapper, WL_DISPLAY_GET_REGISTRY, &wl_registry_interface);
+        wl_proxy_wrapper_destroy(wrapper);
+
+        let mut globals = WaylandGlobals::default();
+        wl_proxy_add_listener(
+            registry,
+            &REGISTRY_LISTENER as *const WlRegistryListener as *mut c_void,
+            &mut globals as *mut WaylandGlobals as *mut c_void,
+        );
+        let roundtrip = wl_display_roundtrip_queue(display, queue);
+        wl_proxy_destroy(registry);
+
+        if roundtrip < 0 || globals.compositor.is_null() || globals.subcompositor.is_null() {
+            if !globals.compositor.is_null() {
+                wl_proxy_destroy(globals.compositor);
+            }
+            if !globals.subcompositor.is_null() {
+                request(globals.subcompositor, WL_SUBCOMPOSITOR_DESTROY, true);
+            }
+            wl_event_queue_destroy(queue);
+
+            return Err(ObsError::DisplayCreationError(
+                "The Wayland compositor does not support subsurfaces".to_string(),
+            ));
+        }
+
+        let surface = create(
+            globals.compositor,
+            WL_COMPOSITOR_CREATE_SURFACE,
+            &wl_surface_interface,
+        );
+        let subsurface = wl_proxy_marshal_flags(
+            globals.subcompositor,
+            WL_SUBCOMPOSITOR_GET_SUBSURFACE,
+            &wl_subsurface_interface,
+            wl_proxy_get_version(globals.subcompositor),
+            0,
+            ptr::null_mut::<c_void>(),
+            surface,
+            parent,
+        );
+
+        // An empty input region lets pointer events through to the parent
+        let region = create(
+            globals.compositor,
+            WL_COMPOSITOR_CREATE_REGION,
+            &wl_region_interface,
+        );
+        wl_proxy_marshal_flags(
+            surface,
+            WL_SURFACE_SET_INPUT_REGION,
+            ptr::null(),
+            wl_proxy_get_version(surface),
+            0,
+            region,
+        );
+        request(region, WL_REGION_DESTROY, true);
+
+        // Frames rendered by OBS are shown right away instead of on the next parent commit
+        request(subsurface, WL_SUBSURFACE_SET_DESYNC, false);
+        request(surface, WL_SURFACE_COMMIT, false);
+
+        let instance = Self {
+            display: Sendable(display),
+            queue: Sendable(queue),
+            compositor: Sendable(globals.compositor),
+            subcompositor: Sendable(globals.subcompositor),
+            parent: Sendable(parent),
+            surface: Sendable(surface),
+            subsurface: Sendable(subsurface),
+        };
+        instance.set_position(x, y);
+
+        Ok(instance)
+    }
+
+    /// Changes of the subsurface are applied with the next commit of the parent, so the
+    /// parent is committed right away.
+    ///
+    /// # Safety
+    /// The parent surface must still be alive.
+    unsafe fn commit_parent(&self) {
+        request(self.parent.0, WL_SURFACE_COMMIT, false);
+        wl_display_flush(self.display.0);
+    }
+
+    /// # Safety
+    /// The parent surface must still be alive.
+    unsafe fn set_position(&self, x: i32, y: i32) {
+        wl_proxy_marshal_flags(
+            self.subsurface.0,
+            WL_SUBSURFACE_SET_POSITION,
+            ptr::null(),
+            wl_proxy_get_version(self.subsurface.0),
+            0,
+            x,
+            y,
+        );
+        self.commit_parent();
+    }
+
+    /// # Safety
+    /// The parent surface must still be alive.
+    unsafe fn set_render_at_bottom(&self, render_at_bottom: bool) {
+        let opcode = if render_at_bottom {
+            WL_SUBSURFACE_PLACE_BELOW
+        } else {
+            WL_SUBSURFACE_PLACE_ABOVE
+        };
+
+        wl_proxy_marshal_flags(
+            self.subsurface.0,
+            opcode,
+            ptr::null(),
+            wl_proxy_get_version(self.subsurface.0),
+            0,
+            self.parent.0,
+        );
+        self.commit_parent();
+    }
+
+    /// Removes the buffer of the surface, the next frame rendered by OBS maps it again.
+    ///
+    /// # Safety
+    /// The surface must still be alive.
+    unsafe fn unmap(&self) {
+        wl_proxy_marshal_flags(
+            self.surface.0,
+            WL_SURFACE_ATTACH,
+            ptr::null(),
+            wl_proxy_get_version(self.surface.0),
+            0,
+            ptr::null_mut::<c_void>(),
+            0i32,
+            0i32,
+        );
+        request(self.surface.0, WL_SURFACE_COMMIT, false);
+        wl_display_flush(self.display.0);
+    }
+}
+
+impl Drop for WaylandSubsurface {
+    fn drop(&mut self) {
+        unsafe {
+            // Safety: Every proxy is owned by this struct. The display of OBS that renders into
+            // the surface is destroyed before, as it is declared first in `ObsDisplayRef`.
+            request(self.subsurface.0, WL_SUBSURFACE_DESTROY, true);
+            request(self.surface.0, WL_SURFACE_DESTROY, true);
+            request(self.subcompositor.0, WL_SUBCOMPOSITOR_DESTROY, true);
+            wl_proxy_destroy(self.compositor.0);
+            wl_display_flush(self.display.0);
+            wl_event_queue_destroy(self.queue.0);
+        }
+    }
+}
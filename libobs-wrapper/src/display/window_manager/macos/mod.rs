@@ -0,0 +1,232 @@
+//! Child views for displays on macOS, so previews can be moved, resized and hidden inside
+//! of the application window just like on Windows.
+//!
+//! The display renders into a layer-backed `NSView` that is added as subview of the given
+//! view (e.g. the content view of a winit or tao window). The renderer of OBS sets up the layer
+//! of that view, which is a `CAMetalLayer` for Metal and an IOSurface backed layer for OpenGL.
+//!
+//! AppKit must only be used on the main thread, so every call is dispatched to it. Positions
+//! and sizes are in physical pixels like on the other platforms and converted to points.
+
+use std::os::raw::c_void;
+
+use crate::{
+    display::ObsWindowHandle,
+    unsafe_send::Sendable,
+    utils::{
+        macos::{
+            class, msg_send, msg_send_rect, run_on_main, CGFloat, Id, NSPoint, NSRect, NSSize,
+            ObjcBool, NO, YES,
+        },
+        ObsError,
+    },
+};
+
+const NS_WINDOW_ABOVE: isize = 1;
+const NS_WINDOW_BELOW: isize = -1;
+/// Keeps the distance to the top of the parent when it is not flipped
+const NS_VIEW_MIN_Y_MARGIN: usize = 8;
+
+#[derive(Debug)]
+pub(crate) struct MacOSPreviewChildWindowHandler {
+    parent: Sendable<Id>,
+    view: Sendable<Id>,
+    window_handle: ObsWindowHandle,
+
+    pub(in crate::display::window_manager) x: i32,
+    pub(in crate::display::window_manager) y: i32,
+
+    pub(in crate::display::window_manager) width: u32,
+    pub(in crate::display::window_manager) height: u32,
+
+    pub(in crate::display::window_manager) is_hidden: bool,
+    pub(in crate::display::window_manager) render_at_bottom: bool,
+}
+
+impl MacOSPreviewChildWindowHandler {
+    pub fn new_child(
+        parent: ObsWindowHandle,
+        x: i32,
+        y: i32,
+        width: u32,
+        height: u32,
+    ) -> Result<Self, ObsError> {
+        log::trace!("Creating MacOSPreviewChildWindowHandler...");
+        let parent = Sendable(parent.window.0.view as Id);
+        if parent.0.is_null() {
+            return Err(ObsError::NullPointer(Some(
+                "The view of the window handle is null".to_string(),
+            )));
+        }
+
+        let view = run_on_main(|| unsafe {
+            // Safety: The parent is a valid NSView as required by `ObsWindowHandle::new_from_cocoa`
+            // and we are on the main thread.
+            let frame = child_frame(parent.0, x, y, width, height);
+            let view: Id = msg_send![Id; class(c"NSView"), c"alloc"];
+            let view: Id = msg_send![Id; view, c"initWithFrame:", frame => NSRect];
+            if view.is_null() {
+                return Sendable(view);
+            }
+
+            msg_send![(); view, c"setWantsLayer:", YES => ObjcBool];
+            if msg_send![ObjcBool; parent.0, c"isFlipped"] == NO {
+                msg_send![(); view, c"setAutoresizingMask:", NS_VIEW_MIN_Y_MARGIN => usize];
+            }
+            msg_send![(); parent.0, c"addSubview:", view => Id];
+
+            Sendable(view)
+        });
+
+        if view.0.is_null() {
+            return Err(ObsError::DisplayCreationError(
+                "Failed to create NSView for the display".to_string(),
+            ));
+        }
+
+        let window_handle = ObsWindowHandle {
+            window: Sendable(libobs::gs_window {
+                view: view.0 as *mut _,
+            }),
+            is_wayland: false,
+        };
+
+        Ok(Self {
+            parent,
+            view,
+            window_handle,
+            x,
+            y,
+            width,
+            height,
+            is_hidden: false,
+            render_at_bottom: false,
+        })
+    }
+
+    pub fn get_window_handle(&self) -> ObsWindowHandle {
+        self.window_handle.clone()
+    }
+
+    fn update_frame(&self) {
+        let (parent, view) = (self.parent.clone(), self.view.clone());
+        let (x, y, width, height) = (self.x, self.y, self.width, self.height);
+        run_on_main(move || unsafe {
+            // Safety: Both views are alive as long as this handler exists
+            let frame = child_frame(parent.0, x, y, width, height);
+            msg_send![(); view.0, c"setFrame:", frame => NSRect];
+        });
+    }
+
+    pub(in crate::display::window_manager) fn set_pos(
+        &mut self,
+        x: i32,
+        y: i32,
+    ) -> Result<(), ObsError> {
+        self.x = x;
+        self.y = y;
+        self.update_frame();
+
+        Ok(())
+    }
+
+    pub(in crate::display::window_manager) fn set_size(
+        &mut self,
+        width: u32,
+        height: u32,
+    ) -> Result<(), ObsError> {
+        self.width = width;
+        self.height = height;
+        self.update_frame();
+
+        Ok(())
+    }
+
+    pub(in crate::display::window_manager) fn set_render_at_bottom(
+        &mut self,
+        render_at_bottom: bool,
+    ) -> Result<(), ObsError> {
+        self.render_at_bottom = render_at_bottom;
+
+        let (parent, view) = (self.parent.clone(), self.view.clone());
+        let position = if render_at_bottom {
+            NS_WINDOW_BELOW
+        } else {
+            NS_WINDOW_ABOVE
+        };
+        run_on_main(move || unsafe {
+            // Safety: Both views are alive as long as this handler exists. The view is retained
+            // by this handler, so removing it from the parent does not free it.
+            msg_send![(); view.0, c"removeFromSuperview"];
+            msg_send![
+                ();
+                parent.0,
+                c"addSubview:positioned:relativeTo:",
+                view.0 => Id,
+                position => isize,
+                std::ptr::null_mut::<c_void>() => Id,
+            ];
+        });
+
+        Ok(())
+    }
+
+    pub(in crate::display::window_manager) fn set_hidden(
+        &mut self,
+        hidden: bool,
+    ) -> Result<(), ObsError> {
+        self.is_hidden = hidden;
+
+        let view = self.view.clone();
+        let hidden = if hidden { YES } else { NO };
+        run_on_main(move || unsafe {
+            // Safety: The view is alive as long as this handler exists
+            msg_send![(); view.0, c"setHidden:", hidden => ObjcBool];
+        });
+
+        Ok(())
+    }
+}
+
+impl Drop for MacOSPreviewChildWindowHandler {
+    fn drop(&mut self) {
+        log::trace!("Dropping MacOSPreviewChildWindowHandler...");
+        let view = self.view.clone();
+        run_on_main(move || unsafe {
+            // Safety: The view is owned by this handler and the display of OBS is destroyed
+            // before, as it is declared first in `ObsDisplayRef`
+            msg_send![(); view.0, c"removeFromSuperview"];
+            msg_send![(); view.0, c"release"];
+        });
+    }
+}
+
+/// The frame of the child in the coordinates of the parent. `x`, `y` and the size are in
+/// pixels from the top left corner, the frame is in points and starts at the bottom left
+/// corner unless the parent is flipped.
+///
+/// # Safety
+/// The parent must be a valid NSView and this must be called on the main thread.
+unsafe fn child_frame(parent: Id, x: i32, y: i32, width: u32, height: u32) -> NSRect {
+    let window: Id = msg_send![Id; parent, c"window"];
+    let scale: CGFloat = if window.is_null() {
+        1.0
+    } else {
+        msg_send![CGFloat; window, c"backingScaleFactor"]
+    };
+
+    let width = width as CGFloat / scale;
+    let height = height as CGFloat / scale;
+    let x = x as CGFloat / scale;
+    let mut y = y as CGFloat / scale;
+
+    if msg_send![ObjcBool; parent, c"isFlipped"] == NO {
+        let bounds = msg_send_rect(parent, c"bounds");
+        y = bounds.size.height - y - height;
+    }
+
+    NSRect {
+        origin: NSPoint { x, y },
+        size: NSSize { width, height },
+    }
+}
@@ -6,10 +6,10 @@ use crate::utils::ObsError;
 impl MiscDisplayTrait for ObsDisplayRef {
     fn is_enabled(&self) -> Result<bool, ObsError> {
         let display_ptr = self.as_ptr();
-        run_with_obs!(self.runtime, (display_ptr), move || {
+        run_with_obs!(self.runtime, (display_ptr), move |token| {
             unsafe {
                 // Safety: The pointer is valid because we are using a smart pointer
-                libobs::obs_display_enabled(display_ptr.get_ptr())
+                libobs::obs_display_enabled(display_ptr.get_ptr(token))
             }
         })
     }
@@ -17,10 +17,10 @@ impl MiscDisplayTrait for ObsDisplayRef {
     fn set_enabled(&self, enabled: bool) -> Result<(), ObsError> {
         let display_ptr = self.as_ptr();
 
-        run_with_obs!(self.runtime, (display_ptr), move || {
+        run_with_obs!(self.runtime, (display_ptr), move |token| {
             unsafe {
                 // Safety: The pointer is valid because we are using a smart pointer
-                libobs::obs_display_set_enabled(display_ptr.get_ptr(), enabled)
+                libobs::obs_display_set_enabled(display_ptr.get_ptr(token), enabled)
             }
         })
     }
@@ -29,10 +29,10 @@ impl MiscDisplayTrait for ObsDisplayRef {
         let color: u32 = ((r as u32) << 16) | ((g as u32) << 8) | (b as u32);
         let display_ptr = self.as_ptr();
 
-        run_with_obs!(self.runtime, (display_ptr), move || {
+        run_with_obs!(self.runtime, (display_ptr), move |token| {
             unsafe {
                 // Safety: The pointer is valid because we are using a smart pointer and the color is literally just a number
-                libobs::obs_display_set_background_color(display_ptr.get_ptr(), color)
+                libobs::obs_display_set_background_color(display_ptr.get_ptr(token), color)
             }
         })
     }
@@ -1,6 +1,12 @@
 #[cfg(windows)]
 pub(crate) mod windows;
 
+#[cfg(target_os = "linux")]
+pub(crate) mod linux;
+
+#[cfg(target_os = "macos")]
+pub(crate) mod macos;
+
 mod traits;
 pub use traits::*;
 
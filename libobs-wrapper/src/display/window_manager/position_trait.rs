@@ -24,6 +24,15 @@ impl WindowPositionTrait for ObsDisplayRef {
 
             m.render_at_bottom = _render_at_bottom;
         }
+
+        #[cfg(any(target_os = "linux", target_os = "macos"))]
+        if let Some(m) = self.child_window_handler.as_ref() {
+            let mut m = m
+                .write()
+                .map_err(|e| ObsError::LockError(format!("{:?}", e)))?;
+
+            m.set_render_at_bottom(_render_at_bottom)?;
+        }
         Ok(())
     }
 
@@ -36,6 +45,14 @@ impl WindowPositionTrait for ObsDisplayRef {
             return Ok(m.render_at_bottom);
         }
 
+        #[cfg(any(target_os = "linux", target_os = "macos"))]
+        if let Some(m) = self.child_window_handler.as_ref() {
+            let m = m
+                .read()
+                .map_err(|e| ObsError::LockError(format!("{:?}", e)))?;
+            return Ok(m.render_at_bottom);
+        }
+
         Ok(false)
     }
 
@@ -76,7 +93,7 @@ impl WindowPositionTrait for ObsDisplayRef {
                     1_i32,
                     flags,
                 )
-                .map_err(|e| ObsError::DisplayCreationError(format!("{:?}", e)))?;
+                .map_err(|e| ObsError::NativeError(e.into()))?;
             }
 
             // Update color space when window position changes
@@ -85,6 +102,19 @@ impl WindowPositionTrait for ObsDisplayRef {
             return Ok(());
         }
 
+        #[cfg(any(target_os = "linux", target_os = "macos"))]
+        if let Some(m) = self.child_window_handler.as_ref() {
+            let mut m = m
+                .write()
+                .map_err(|e| ObsError::LockError(format!("{:?}", e)))?;
+
+            m.set_pos(x, y)?;
+
+            drop(m);
+            self.update_color_space()?;
+            return Ok(());
+        }
+
         *DISPLAY_POSITIONS
             .write()
             .map_err(|e| ObsError::LockError(format!("{:?}", e)))?
@@ -124,7 +154,7 @@ impl WindowPositionTrait for ObsDisplayRef {
                     height as i32,
                     SWP_NOCOPYBITS | SWP_NOACTIVATE | SWP_NOZORDER | SWP_SHOWWINDOW,
                 )
-                .map_err(|e| ObsError::DisplayCreationError(format!("{:?}", e)))?;
+                .map_err(|e| ObsError::NativeError(e.into()))?;
 
                 let _ = RedrawWindow(
                     Some(m.window_handle.get_hwnd()),
@@ -135,14 +165,23 @@ impl WindowPositionTrait for ObsDisplayRef {
             }
         }
 
+        #[cfg(any(target_os = "linux", target_os = "macos"))]
+        if let Some(m) = self.child_window_handler.as_ref() {
+            let mut m = m
+                .write()
+                .map_err(|e| ObsError::LockError(format!("{:?}", e)))?;
+
+            m.set_size(width, height)?;
+        }
+
         let pointer = self.as_ptr();
-        run_with_obs!(self.runtime, (pointer), move || {
+        run_with_obs!(self.runtime, (pointer), move |token| {
             unsafe {
                 // Safety: The pointer is valid because we are using a smart pointer
 
-                libobs::obs_display_resize(pointer.get_ptr(), width, height);
+                libobs::obs_display_resize(pointer.get_ptr(token), width, height);
                 // Update color space when window size changes
-                libobs::obs_display_update_color_space(pointer.get_ptr());
+                libobs::obs_display_update_color_space(pointer.get_ptr(token));
             }
         })
         .map_err(|e| ObsError::InvocationError(format!("{:?}", e)))?;
@@ -158,6 +197,14 @@ impl WindowPositionTrait for ObsDisplayRef {
             return Ok((m.x, m.y));
         }
 
+        #[cfg(any(target_os = "linux", target_os = "macos"))]
+        if let Some(m) = self.child_window_handler.as_ref() {
+            let m = m
+                .read()
+                .map_err(|e| ObsError::LockError(format!("{:?}", e)))?;
+            return Ok((m.x, m.y));
+        }
+
         let pos = DISPLAY_POSITIONS
             .read()
             .map_err(|e| ObsError::LockError(format!("{:?}", e)))?;
@@ -178,13 +225,21 @@ impl WindowPositionTrait for ObsDisplayRef {
             return Ok((m.width, m.height));
         }
 
+        #[cfg(any(target_os = "linux", target_os = "macos"))]
+        if let Some(m) = self.child_window_handler.as_ref() {
+            let m = m
+                .read()
+                .map_err(|e| ObsError::LockError(format!("{:?}", e)))?;
+            return Ok((m.width, m.height));
+        }
+
         let pointer = self.as_ptr();
-        let (width, height) = run_with_obs!(self.runtime, (pointer), move || {
+        let (width, height) = run_with_obs!(self.runtime, (pointer), move |token| {
             let mut w: u32 = 0;
             let mut h: u32 = 0;
             unsafe {
                 // Safety: The pointer is valid because we are using a smart pointer and because `w` and `h` are valid mutable references.
-                libobs::obs_display_size(pointer.get_ptr(), &mut w, &mut h);
+                libobs::obs_display_size(pointer.get_ptr(token), &mut w, &mut h);
             };
 
             (w, h)
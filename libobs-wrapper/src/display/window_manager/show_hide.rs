@@ -3,6 +3,8 @@ use std::sync::atomic::Ordering;
 #[cfg(windows)]
 use windows::Win32::UI::WindowsAndMessaging::{ShowWindow, SW_HIDE, SW_SHOWNA};
 
+#[cfg(target_os = "linux")]
+use crate::display::window_manager::MiscDisplayTrait;
 use crate::display::window_manager::ShowHideTrait;
 use crate::display::ObsDisplayRef;
 use crate::run_with_obs;
@@ -30,11 +32,34 @@ impl ShowHideTrait for ObsDisplayRef {
             return Ok(());
         }
 
+        #[cfg(target_os = "linux")]
+        if let Some(m) = &self.child_window_handler {
+            let mut m = m
+                .write()
+                .map_err(|e| ObsError::LockError(format!("{:?}", e)))?;
+
+            m.set_hidden(false)?;
+            if m.is_wayland() {
+                // The surface is mapped again by the next frame the display renders
+                drop(m);
+                self.set_enabled(true)?;
+            }
+            return Ok(());
+        }
+
+        #[cfg(target_os = "macos")]
+        if let Some(m) = &self.child_window_handler {
+            m.write()
+                .map_err(|e| ObsError::LockError(format!("{:?}", e)))?
+                .set_hidden(false)?;
+            return Ok(());
+        }
+
         let ptr = self.as_ptr();
-        run_with_obs!(self.runtime, (ptr), move || {
+        run_with_obs!(self.runtime, (ptr), move |token| {
             unsafe {
                 // Safety: The pointer is valid because we are using a smart pointer
-                libobs::obs_display_set_enabled(ptr.get_ptr(), true);
+                libobs::obs_display_set_enabled(ptr.get_ptr(token), true);
             }
         })?;
         Ok(())
@@ -57,11 +82,38 @@ impl ShowHideTrait for ObsDisplayRef {
             return Ok(());
         }
 
+        #[cfg(target_os = "linux")]
+        if let Some(handler) = &self.child_window_handler {
+            let is_wayland = handler
+                .read()
+                .map_err(|e| ObsError::LockError(format!("{:?}", e)))?
+                .is_wayland();
+
+            // Stop rendering first, otherwise the next frame would map the surface again
+            if is_wayland {
+                self.set_enabled(false)?;
+            }
+
+            handler
+                .write()
+                .map_err(|e| ObsError::LockError(format!("{:?}", e)))?
+                .set_hidden(true)?;
+            return Ok(());
+        }
+
+        #[cfg(target_os = "macos")]
+        if let Some(m) = &self.child_window_handler {
+            m.write()
+                .map_err(|e| ObsError::LockError(format!("{:?}", e)))?
+                .set_hidden(true)?;
+            return Ok(());
+        }
+
         let ptr = self.as_ptr();
-        run_with_obs!(self.runtime, (ptr), move || {
+        run_with_obs!(self.runtime, (ptr), move |token| {
             unsafe {
                 // Safety: The pointer is valid because we are using a smart pointer
-                libobs::obs_display_set_enabled(ptr.get_ptr(), false);
+                libobs::obs_display_set_enabled(ptr.get_ptr(token), false);
             }
         })?;
         Ok(())
@@ -77,11 +129,29 @@ impl ShowHideTrait for ObsDisplayRef {
             return Ok(!m.is_hidden.load(Ordering::Relaxed));
         }
 
+        #[cfg(target_os = "linux")]
+        if let Some(m) = &self.child_window_handler {
+            let m = m
+                .read()
+                .map_err(|e| ObsError::LockError(format!("{:?}", e)))?;
+
+            return Ok(!m.is_hidden);
+        }
+
+        #[cfg(target_os = "macos")]
+        if let Some(m) = &self.child_window_handler {
+            let m = m
+                .read()
+                .map_err(|e| ObsError::LockError(format!("{:?}", e)))?;
+
+            return Ok(!m.is_hidden);
+        }
+
         let ptr = self.as_ptr();
-        run_with_obs!(self.runtime, (ptr), move || {
+        run_with_obs!(self.runtime, (ptr), move |token| {
             let enabled = unsafe {
                 // Safety: The pointer is valid because we are using a smart pointer
-                libobs::obs_display_enabled(ptr.get_ptr())
+                libobs::obs_display_enabled(ptr.get_ptr(token))
             };
 
             Ok(enabled)
@@ -10,6 +10,7 @@ pub trait MiscDisplayTrait {
 
 pub trait WindowPositionTrait {
     /// If create_child is true, sets whether the window is rendered at the bottom of the Z order.
+    /// On Wayland this places the subsurface below or above its parent surface.
     ///
     /// Otherwise, this function has no effect.
     fn set_render_at_bottom(&self, render_at_bottom: bool) -> Result<(), ObsError>;
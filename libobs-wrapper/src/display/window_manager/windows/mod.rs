@@ -167,11 +167,11 @@ impl WindowsPreviewChildWindowHandler {
             // We have to have the whole window creation stuff here as well so the message loop functions
             let create = move || -> Result<AlwaysSendable<HWND>, ObsError> {
                 log::trace!("Registering class...");
-                try_register_class().map_err(|e| ObsError::DisplayCreationError(e.to_string()))?;
+                try_register_class().map_err(|e| ObsError::NativeError(e.into()))?;
                 let enabled = unsafe {
                     // Safety: Always safe
                     DwmIsCompositionEnabled()
-                        .map_err(|e| ObsError::DisplayCreationError(e.to_string()))?
+                        .map_err(|e| ObsError::NativeError(e.into()))?
                         .as_bool()
                 };
 
@@ -182,8 +182,7 @@ impl WindowsPreviewChildWindowHandler {
 
                 let instance = unsafe {
                     // Safety: This is being called during initialization, so the module handle should be valid.
-                    GetModuleHandleW(PCWSTR::null())
-                        .map_err(|e| ObsError::DisplayCreationError(e.to_string()))?
+                    GetModuleHandleW(PCWSTR::null()).map_err(|e| ObsError::NativeError(e.into()))?
                 };
 
                 let class_name = HSTRING::from("Win32DisplayClass");
@@ -215,7 +214,7 @@ impl WindowsPreviewChildWindowHandler {
                         Some(instance.into()),
                         None,
                     )
-                    .map_err(|e| ObsError::DisplayCreationError(e.to_string()))?
+                    .map_err(|e| ObsError::NativeError(e.into()))?
                 };
 
                 log::trace!("HWND is {:?}", window);
@@ -225,15 +224,14 @@ impl WindowsPreviewChildWindowHandler {
                         // Safety: The window handle is valid as it was just created. Therefore we can also set layered window attributes
 
                         SetLayeredWindowAttributes(window, COLORREF(0), 255, LWA_ALPHA)
-                            .map_err(|e| ObsError::DisplayCreationError(e.to_string()))?;
+                            .map_err(|e| ObsError::NativeError(e.into()))?;
                     }
                 }
 
                 log::trace!("Setting parent...");
                 unsafe {
                     // Safety: Both are valid window handles, so it is safe to set the parent.
-                    SetParent(window, Some(parent))
-                        .map_err(|e| ObsError::DisplayCreationError(e.to_string()))?;
+                    SetParent(window, Some(parent)).map_err(|e| ObsError::NativeError(e.into()))?;
                 }
 
                 log::trace!("Setting styles...");
@@ -319,10 +317,11 @@ impl WindowsPreviewChildWindowHandler {
         self.obs_display = Some(handle.clone());
         unsafe {
             // Safety: The window handle is valid because it was created and is owned by this struct.
+            // The display pointer is only stored, the message handler uses it on the OBS thread.
             SetWindowLongPtrW(
                 self.window_handle.get_hwnd(),
                 GWLP_USERDATA,
-                handle.get_ptr() as isize,
+                handle.get_ptr_unchecked() as isize,
             );
         }
     }
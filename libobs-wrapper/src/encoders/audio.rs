@@ -9,7 +9,8 @@ use crate::{
         object::{inner_fn_update_settings, ObsObjectTrait, ObsObjectTraitPrivate},
         ImmutableObsData, ObsDataPointers,
     },
-    encoders::{ObsEncoderTrait, _ObsEncoderDropGuard},
+    encoders::{encoder_name, set_encoder_name, ObsEncoderTrait, _ObsEncoderDropGuard},
+    logger::{attach_logs_since, log_history_position},
     run_with_obs,
     runtime::ObsRuntime,
     unsafe_send::{Sendable, SmartPointerSendable},
@@ -23,7 +24,7 @@ use crate::{
 /// added to the output
 pub struct ObsAudioEncoder {
     pub(crate) id: ObsString,
-    pub(crate) name: ObsString,
+    pub(crate) name: Arc<RwLock<ObsString>>,
     pub(crate) settings: Arc<RwLock<ImmutableObsData>>,
     pub(crate) hotkey_data: Arc<RwLock<ImmutableObsData>>,
     pub(crate) runtime: ObsRuntime,
@@ -47,17 +48,19 @@ impl ObsAudioEncoder {
         let settings_ptr = settings.as_ref().map(|s| s.as_ptr());
         let hotkey_data_ptr = hotkey_data.as_ref().map(|h| h.as_ptr());
 
+        // Encoders log why they couldn't be created (e.g. an unsupported GPU)
+        let log_position = log_history_position();
         let encoder = run_with_obs!(
             runtime,
             (id, name, settings_ptr, hotkey_data_ptr),
-            move || {
+            move |token| {
                 let settings_ptr_raw = match settings_ptr {
-                    Some(s) => s.get_ptr(),
+                    Some(s) => s.get_ptr(token),
                     None => ptr::null_mut(),
                 };
 
                 let hotkey_data_ptr_raw = match hotkey_data_ptr {
-                    Some(h) => h.get_ptr(),
+                    Some(h) => h.get_ptr(token),
                     None => ptr::null_mut(),
                 };
 
@@ -73,12 +76,13 @@ impl ObsAudioEncoder {
                 };
 
                 if ptr.is_null() {
-                    Err(ObsError::NullPointer(None))
+                    Err(ObsError::NullPointer(None).in_function("obs_audio_encoder_create"))
                 } else {
                     Ok(Sendable(ptr))
                 }
             }
-        )??;
+        )?
+        .map_err(|e| attach_logs_since(e, log_position))?;
 
         let encoder = SmartPointerSendable::new(
             encoder.0,
@@ -89,9 +93,9 @@ impl ObsAudioEncoder {
         );
 
         let settings = {
-            let settings_ptr = run_with_obs!(runtime, (encoder), move || unsafe {
+            let settings_ptr = run_with_obs!(runtime, (encoder), move |token| unsafe {
                 // Safety: We are using a smart pointer to ensure that the encoder pointer is valid
-                Sendable(libobs::obs_encoder_get_settings(encoder.get_ptr()))
+                Sendable(libobs::obs_encoder_get_settings(encoder.get_ptr(token)))
             })?;
 
             ImmutableObsData::from_raw_pointer(settings_ptr, runtime.clone())
@@ -105,13 +109,22 @@ impl ObsAudioEncoder {
         Ok(Arc::new(Self {
             encoder,
             id,
-            name,
+            name: Arc::new(RwLock::new(name)),
             settings: Arc::new(RwLock::new(settings)),
             hotkey_data: Arc::new(RwLock::new(hotkey_data)),
             runtime,
         }))
     }
 
+    /// Returns the sample rate the encoder encodes at. Zero if no audio is set yet.
+    pub fn sample_rate(&self) -> Result<u32, ObsError> {
+        let encoder_ptr = self.encoder.clone();
+        run_with_obs!(self.runtime, (encoder_ptr), move |token| unsafe {
+            // Safety: The pointer is valid because of the smart pointer
+            libobs::obs_encoder_get_sample_rate(encoder_ptr.get_ptr(token))
+        })
+    }
+
     /// This is only needed once for global audio context
     /// # Safety
     /// You must ensure that the `handler` pointer is valid and lives as long as this function call.
@@ -121,10 +134,10 @@ impl ObsAudioEncoder {
     ) -> Result<(), ObsError> {
         let encoder_ptr = self.encoder.clone();
 
-        run_with_obs!(self.runtime, (handler, encoder_ptr), move || {
+        run_with_obs!(self.runtime, (handler, encoder_ptr), move |token| {
             unsafe {
                 // Safety: Caller made sure that handler is valid and encoder_ptr is valid because of a SmartPointer
-                libobs::obs_encoder_set_audio(encoder_ptr.get_ptr(), handler.0)
+                libobs::obs_encoder_set_audio(encoder_ptr.get_ptr(token), handler.0)
             }
         })
     }
@@ -190,8 +203,16 @@ impl ObsObjectTrait<*mut libobs::obs_encoder> for ObsAudioEncoder {
         self.id.clone()
     }
 
+    /// Returns the current name of this encoder, including renames that were not done
+    /// through this crate.
     fn name(&self) -> ObsString {
-        self.name.clone()
+        encoder_name(&self.runtime, &self.encoder, &self.name)
+    }
+
+    /// Renames this encoder. Fails with `ObsError::NameAlreadyInUse` if another encoder
+    /// already has the name.
+    fn set_name(&self, new_name: &str) -> Result<(), ObsError> {
+        set_encoder_name(&self.runtime, &self.encoder, &self.name, new_name)
     }
 
     fn update_settings(&self, settings: crate::data::ObsData) -> Result<(), ObsError> {
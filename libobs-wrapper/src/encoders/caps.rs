@@ -0,0 +1,101 @@
+use std::ffi::{c_char, CStr};
+
+use bitflags::bitflags;
+
+use crate::enums::{ObsColorspace, ObsVideoFormat};
+
+bitflags! {
+    /// Capabilities an encoder advertises, see `ObsVideoEncoderBuilder::caps`.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+    pub struct ObsEncoderCaps: u32 {
+        const DEPRECATED = libobs::OBS_ENCODER_CAP_DEPRECATED;
+        /// The encoder can read frames directly from GPU textures (texture sharing)
+        const PASS_TEXTURE = libobs::OBS_ENCODER_CAP_PASS_TEXTURE;
+        /// The bitrate can be changed while the encoder is active
+        const DYN_BITRATE = libobs::OBS_ENCODER_CAP_DYN_BITRATE;
+        const INTERNAL = libobs::OBS_ENCODER_CAP_INTERNAL;
+        /// The encoder supports regions of interest
+        const ROI = libobs::OBS_ENCODER_CAP_ROI;
+        /// The encoder can scale frames itself instead of libobs
+        const SCALING = libobs::OBS_ENCODER_CAP_SCALING;
+    }
+}
+
+/// How an encoder can consume frames of a given video format.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ObsEncoderFormatSupport {
+    /// Frames are passed to the encoder as GPU textures, without a copy to system memory.
+    Texture,
+    /// Frames are downloaded to system memory before they are encoded.
+    Raw,
+    /// The encoder can not encode this format, the reason is given.
+    Unsupported(String),
+}
+
+impl ObsEncoderFormatSupport {
+    pub fn is_supported(&self) -> bool {
+        !matches!(self, ObsEncoderFormatSupport::Unsupported(_))
+    }
+}
+
+fn is_high_bit_depth(format: ObsVideoFormat) -> bool {
+    matches!(
+        format,
+        ObsVideoFormat::I010
+            | ObsVideoFormat::P010
+            | ObsVideoFormat::I210
+            | ObsVideoFormat::I412
+            | ObsVideoFormat::P216
+            | ObsVideoFormat::P416
+    )
+}
+
+/// Checks whether an encoder with the given codec and caps can encode the given video format.
+///
+/// This mirrors the checks OBS Studio does before starting an output: H.264 can not encode
+/// high bit depth formats, HDR needs a high bit depth format and texture sharing is only
+/// used for NV12 and P010.
+pub(super) fn format_support(
+    codec: Option<&str>,
+    caps: ObsEncoderCaps,
+    format: ObsVideoFormat,
+    colorspace: ObsColorspace,
+) -> ObsEncoderFormatSupport {
+    let high_bit_depth = is_high_bit_depth(format);
+    if high_bit_depth && codec == Some("h264") {
+        return ObsEncoderFormatSupport::Unsupported(format!(
+            "H.264 encoders can not encode the high bit depth format {:?}",
+            format
+        ));
+    }
+
+    let is_hdr = matches!(
+        colorspace,
+        ObsColorspace::CS2100PQ | ObsColorspace::CS2100HLG
+    );
+    if is_hdr && !high_bit_depth {
+        return ObsEncoderFormatSupport::Unsupported(format!(
+            "HDR colorspace {:?} requires a high bit depth format, but {:?} is used",
+            colorspace, format
+        ));
+    }
+
+    let texture_format = matches!(format, ObsVideoFormat::NV12 | ObsVideoFormat::P010);
+    if caps.contains(ObsEncoderCaps::PASS_TEXTURE) && texture_format {
+        ObsEncoderFormatSupport::Texture
+    } else {
+        ObsEncoderFormatSupport::Raw
+    }
+}
+
+/// Converts the codec returned by `obs_get_encoder_codec` to an owned string.
+///
+/// # Safety
+/// The pointer must be null or a valid C string.
+pub(super) unsafe fn codec_from_ptr(ptr: *const c_char) -> Option<String> {
+    if ptr.is_null() {
+        return None;
+    }
+
+    CStr::from_ptr(ptr).to_str().ok().map(|s| s.to_string())
+}
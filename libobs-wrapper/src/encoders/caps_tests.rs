@@ -0,0 +1,56 @@
+#[cfg(test)]
+mod tests {
+    use super::super::caps::{format_support, ObsEncoderCaps, ObsEncoderFormatSupport};
+    use crate::enums::{ObsColorspace, ObsVideoFormat};
+
+    #[test]
+    fn h264_rejects_high_bit_depth() {
+        let support = format_support(
+            Some("h264"),
+            ObsEncoderCaps::PASS_TEXTURE,
+            ObsVideoFormat::P010,
+            ObsColorspace::CS2100PQ,
+        );
+        assert!(!support.is_supported());
+
+        let support = format_support(
+            Some("hevc"),
+            ObsEncoderCaps::PASS_TEXTURE,
+            ObsVideoFormat::P010,
+            ObsColorspace::CS2100PQ,
+        );
+        assert_eq!(support, ObsEncoderFormatSupport::Texture);
+    }
+
+    #[test]
+    fn texture_sharing_depends_on_format() {
+        let caps = ObsEncoderCaps::PASS_TEXTURE | ObsEncoderCaps::DYN_BITRATE;
+        assert_eq!(
+            format_support(
+                Some("h264"),
+                caps,
+                ObsVideoFormat::NV12,
+                ObsColorspace::CS709
+            ),
+            ObsEncoderFormatSupport::Texture
+        );
+        assert_eq!(
+            format_support(
+                Some("h264"),
+                caps,
+                ObsVideoFormat::I444,
+                ObsColorspace::CS709
+            ),
+            ObsEncoderFormatSupport::Raw
+        );
+        assert_eq!(
+            format_support(
+                Some("h264"),
+                ObsEncoderCaps::empty(),
+                ObsVideoFormat::NV12,
+                ObsColorspace::CS709
+            ),
+            ObsEncoderFormatSupport::Raw
+        );
+    }
+}
@@ -31,7 +31,7 @@ macro_rules! encoder_enum {
             fn from(v: $name) -> ObsString {
                 #[allow(deprecated)]
                 return match v {
-                    $( $( $name::[<$variant:upper>] => ObsString::new(stringify!($variant)), )* )*
+                    $( $( $name::[<$variant:upper>] => $crate::obs_string!(stringify!($variant)), )* )*
                     $name::Other(e) => ObsString::new(&e),
                 };
             }
@@ -10,20 +10,95 @@ use crate::{
     enums::{ObsEncoderType, OsEnumType},
     impl_obs_drop, run_with_obs,
     runtime::ObsRuntime,
-    unsafe_send::Sendable,
-    utils::{ObsDropGuard, ObsError, ENCODER_HIDE_FLAGS},
+    unsafe_send::{Sendable, SmartPointerSendable},
+    utils::{ObsDropGuard, ObsError, ObsString, ENCODER_HIDE_FLAGS},
 };
-use std::{ffi::CStr, os::raw::c_char};
+use std::{ffi::CStr, os::raw::c_char, sync::RwLock};
 
 pub mod audio;
+mod caps;
+#[cfg(test)]
+mod caps_tests;
+pub use caps::{ObsEncoderCaps, ObsEncoderFormatSupport};
 mod enums;
 mod traits;
 pub use traits::*;
 mod property_helper;
 pub use property_helper::*;
+pub mod settings;
+#[cfg(test)]
+mod settings_tests;
 pub mod video;
 pub use enums::*;
 
+/// Returns the name libobs currently has for the encoder and stores it in `cached`, so renames
+/// that were not done through this crate are picked up. Falls back to the cached name if the
+/// runtime can not be reached.
+pub(crate) fn encoder_name(
+    runtime: &ObsRuntime,
+    encoder: &SmartPointerSendable<*mut libobs::obs_encoder>,
+    cached: &RwLock<ObsString>,
+) -> ObsString {
+    let encoder_ptr = encoder.clone();
+    let current = run_with_obs!(runtime, (encoder_ptr), move |token| unsafe {
+        // Safety: The encoder pointer is valid because of the smart pointer, the name is copied
+        // before the closure returns
+        optional_name(libobs::obs_encoder_get_name(encoder_ptr.get_ptr(token)))
+    });
+
+    let mut cached = cached
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    if let Ok(Some(current)) = current {
+        if cached.to_string() != current {
+            *cached = ObsString::new(current);
+        }
+    }
+
+    cached.clone()
+}
+
+/// Renames the encoder, fails if another encoder already uses the name.
+pub(crate) fn set_encoder_name(
+    runtime: &ObsRuntime,
+    encoder: &SmartPointerSendable<*mut libobs::obs_encoder>,
+    cached: &RwLock<ObsString>,
+    new_name: &str,
+) -> Result<(), ObsError> {
+    let new_name = ObsString::new(new_name);
+    let encoder_ptr = encoder.clone();
+    let name = run_with_obs!(runtime, (encoder_ptr, new_name), move |token| unsafe {
+        // Safety: The encoder pointer is valid because of the smart pointer and the name is kept in scope
+        let encoder = encoder_ptr.get_ptr(token);
+        let existing = libobs::obs_get_encoder_by_name(new_name.as_ptr().0);
+        if !existing.is_null() {
+            // obs_get_encoder_by_name added a reference, which we have to release again
+            libobs::obs_encoder_release(existing);
+            if existing != encoder {
+                return Err(ObsError::NameAlreadyInUse(new_name.to_string()));
+            }
+        }
+
+        libobs::obs_encoder_set_name(encoder, new_name.as_ptr().0);
+        Ok(optional_name(libobs::obs_encoder_get_name(encoder)))
+    })??;
+
+    *cached.write().map_err(|_| {
+        ObsError::LockError("Failed to acquire write lock on encoder name".into())
+    })? = ObsString::new(name.unwrap_or_default());
+
+    Ok(())
+}
+
+/// Safety: The pointer must be null or point to a valid C string
+unsafe fn optional_name(name: *const c_char) -> Option<String> {
+    if name.is_null() {
+        return None;
+    }
+
+    Some(CStr::from_ptr(name).to_string_lossy().into_owned())
+}
+
 pub trait ObsContextEncoders {
     fn best_video_encoder(&self) -> Result<ObsVideoEncoderBuilder, ObsError>;
 
@@ -1,25 +1,31 @@
-use std::{collections::HashMap, str::FromStr, sync::Arc};
+use std::{collections::HashMap, mem::MaybeUninit, str::FromStr, sync::Arc};
 
 use duplicate::duplicate_item;
+use num_traits::FromPrimitive;
 
 use crate::{
     context::ObsContext,
     data::{
         output::{ObsOutputRef, ObsOutputTrait},
         properties::{
-            ObsProperty, ObsPropertyObject, ObsPropertyObjectPrivate, _ObsPropertiesDropGuard,
+            types::ObsPropertyValueError, ObsProperty, ObsPropertyObject, ObsPropertyObjectPrivate, _ObsPropertiesDropGuard,
             property_ptr_to_struct,
         },
         ObsData,
     },
-    run_with_obs,
+    enums::{ObsColorspace, ObsVideoFormat},
+    run_with_obs, run_with_obs_background,
     runtime::ObsRuntime,
     unsafe_send::{Sendable, SmartPointerSendable},
     utils::{ObjectInfo, ObsError, ObsString},
 };
 
 use super::{
-    audio::ObsAudioEncoder, video::ObsVideoEncoder, ObsAudioEncoderType, ObsVideoEncoderType,
+    audio::ObsAudioEncoder,
+    caps::{codec_from_ptr, format_support, ObsEncoderCaps, ObsEncoderFormatSupport},
+    settings::{check_settings, validate_entries, write_entries, ObsVideoEncoderSettings},
+    video::ObsVideoEncoder,
+    ObsAudioEncoderType, ObsVideoEncoderType,
 };
 
 #[duplicate_item(
@@ -82,6 +88,25 @@ impl StructName {
     pub fn get_hotkey_data_mut(&mut self) -> Option<&mut ObsData> {
         self.hotkey_data.as_mut()
     }
+
+    /// Returns the capabilities the encoder advertises.
+    pub fn caps(&self) -> Result<ObsEncoderCaps, ObsError> {
+        let encoder_id: ObsString = self.encoder_id.clone().into();
+        run_with_obs!(self.runtime, (encoder_id), move || unsafe {
+            // Safety: The id is kept in scope and we are in the runtime
+            ObsEncoderCaps::from_bits_retain(libobs::obs_get_encoder_caps(encoder_id.as_ptr().0))
+        })
+    }
+
+    /// Returns the codec the encoder outputs (e.g. `h264`, `hevc`, `av1` or `aac`),
+    /// `None` if the encoder is not registered.
+    pub fn codec(&self) -> Result<Option<String>, ObsError> {
+        let encoder_id: ObsString = self.encoder_id.clone().into();
+        run_with_obs!(self.runtime, (encoder_id), move || unsafe {
+            // Safety: The id is kept in scope and libobs returns null or a static string
+            codec_from_ptr(libobs::obs_get_encoder_codec(encoder_id.as_ptr().0))
+        })
+    }
 }
 
 impl ObsAudioEncoderBuilder {
@@ -101,6 +126,92 @@ impl ObsAudioEncoderBuilder {
 }
 
 impl ObsVideoEncoderBuilder {
+    /// Returns whether the encoder can read frames directly from GPU textures.
+    /// Texture sharing is only used if the video format is NV12 or P010, see
+    /// [`ObsVideoEncoderBuilder::video_format_support`].
+    pub fn supports_texture_sharing(&self) -> Result<bool, ObsError> {
+        Ok(self.caps()?.contains(ObsEncoderCaps::PASS_TEXTURE))
+    }
+
+    /// Checks whether the encoder can encode the current video format and colorspace of
+    /// the context, and whether it would use texture sharing to do so.
+    pub fn video_format_support(&self) -> Result<ObsEncoderFormatSupport, ObsError> {
+        let (format, colorspace) = run_with_obs!(self.runtime, move || {
+            let mut ovi = MaybeUninit::<libobs::obs_video_info>::uninit();
+            let success = unsafe {
+                // Safety: We are providing a valid pointer to be filled
+                libobs::obs_get_video_info(ovi.as_mut_ptr())
+            };
+
+            if !success {
+                return Err(ObsError::NullPointer(Some(
+                    "Failed to get video info".to_string(),
+                )));
+            }
+
+            let ovi = unsafe {
+                // Safety: libobs filled the pointer and returned success
+                ovi.assume_init()
+            };
+            Ok((ovi.output_format, ovi.colorspace))
+        })??;
+
+        let format = ObsVideoFormat::from_i64(format as i64).ok_or_else(|| {
+            ObsError::Unexpected(format!("Unknown video format {}", format))
+        })?;
+        let colorspace = ObsColorspace::from_i64(colorspace as i64).ok_or_else(|| {
+            ObsError::Unexpected(format!("Unknown colorspace {}", colorspace))
+        })?;
+
+        Ok(format_support(
+            self.codec()?.as_deref(),
+            self.caps()?,
+            format,
+            colorspace,
+        ))
+    }
+
+    /// Writes typed settings (e.g. `NvencSettings`) into the settings of this builder.
+    ///
+    /// Fails if the settings are meant for another encoder family or a value is not accepted
+    /// by the properties of the encoder, e.g. a preset the installed plugin does not offer.
+    pub fn apply_settings<S: ObsVideoEncoderSettings>(
+        &mut self,
+        settings: &S,
+    ) -> Result<&mut Self, ObsError> {
+        if !settings.supports(&self.encoder_id) {
+            return Err(ObsError::InvalidOperation(format!(
+                "{:?} can not be applied to encoder {:?}",
+                settings, self.encoder_id
+            )));
+        }
+
+        let entries = settings.entries();
+        validate_entries(&self.get_properties()?, &entries)?;
+
+        let data = match self.settings.as_mut() {
+            Some(data) => data,
+            None => self.settings.insert(ObsData::new(self.runtime.clone())?),
+        };
+        write_entries(data, entries)?;
+
+        Ok(self)
+    }
+
+    /// Checks the values of the given settings against the properties the encoder advertises,
+    /// e.g. whether a preset is one of the items of the `preset` list or the bitrate is within
+    /// the range of the `bitrate` property.
+    ///
+    /// Encoders usually fall back to a default silently if they get a value they don't know,
+    /// use this before `set_to_output` to catch that. Returns every rejected value, an empty
+    /// list means the settings are valid. Values without a matching property are not checked.
+    pub fn validate_settings(
+        &self,
+        settings: &ObsData,
+    ) -> Result<Vec<ObsPropertyValueError>, ObsError> {
+        check_settings(&self.get_properties()?, settings)
+    }
+
     pub fn set_to_output(
         self,
         output: &mut ObsOutputRef,
@@ -136,7 +247,7 @@ impl ObsPropertyObjectPrivate for StructName {
     ) -> Result<SmartPointerSendable<*mut libobs::obs_properties_t>, ObsError> {
         let encoder_name: ObsString = self.encoder_id.clone().into();
 
-        let property_ptr = run_with_obs!(self.runtime, (encoder_name), move || {
+        let property_ptr = run_with_obs_background!(self.runtime, (encoder_name), move || {
             let encoder_name_ptr = encoder_name.as_ptr().0;
 
             let property_ptr = unsafe {
@@ -164,7 +275,7 @@ impl ObsPropertyObjectPrivate for StructName {
         runtime: ObsRuntime,
     ) -> Result<SmartPointerSendable<*mut libobs::obs_properties_t>, ObsError> {
         let id: ObsString = id.into();
-        let ptr = run_with_obs!(runtime, (id), move || {
+        let ptr = run_with_obs_background!(runtime, (id), move || {
             let id_ptr = id.as_ptr();
 
             let property_ptr = unsafe {
@@ -0,0 +1,484 @@
+//! Typed settings for the video encoder families shipped with OBS.
+//!
+//! Every encoder plugin uses its own settings keys and values, e.g. NVENC takes `p1` to `p7`
+//! as `preset` while x264 takes `veryfast`. The structs in this module write the correct keys,
+//! use them with [`ObsVideoEncoderBuilder::apply_settings`](super::ObsVideoEncoderBuilder::apply_settings).
+//! Fields that are `None` are not written, so the encoder keeps its default for them.
+
+use std::{collections::HashMap, fmt::Debug};
+
+use crate::{
+    data::{
+        properties::{
+            types::{ObsListItemValue, ObsPropertyValueError},
+            ObsProperty,
+        },
+        ObsData, ObsDataGetters, ObsDataSetters,
+    },
+    utils::ObsError,
+};
+
+use super::ObsVideoEncoderType;
+
+/// A single value of an encoder setting.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ObsEncoderSettingValue {
+    String(String),
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+}
+
+impl From<ObsEncoderSettingValue> for ObsListItemValue {
+    fn from(value: ObsEncoderSettingValue) -> Self {
+        match value {
+            ObsEncoderSettingValue::String(s) => ObsListItemValue::String(s),
+            ObsEncoderSettingValue::Int(i) => ObsListItemValue::Int(i),
+            ObsEncoderSettingValue::Float(f) => ObsListItemValue::Float(f),
+            ObsEncoderSettingValue::Bool(b) => ObsListItemValue::Bool(b),
+        }
+    }
+}
+
+/// Typed settings of a video encoder family.
+pub trait ObsVideoEncoderSettings: Debug {
+    /// Returns whether these settings can be applied to the given encoder.
+    fn supports(&self, encoder: &ObsVideoEncoderType) -> bool;
+
+    /// Returns the settings keys and their values, only containing the fields that are set.
+    fn entries(&self) -> Vec<(&'static str, ObsEncoderSettingValue)>;
+}
+
+macro_rules! settings_enum {
+    ($(#[$attr:meta])* $name:ident { $($(#[$variant_attr:meta])* $variant:ident => $value:literal,)* }) => {
+        $(#[$attr])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        pub enum $name {
+            $($(#[$variant_attr])* $variant,)*
+        }
+
+        impl $name {
+            pub fn as_str(&self) -> &'static str {
+                match self {
+                    $($name::$variant => $value,)*
+                }
+            }
+        }
+
+        impl From<$name> for ObsEncoderSettingValue {
+            fn from(value: $name) -> Self {
+                ObsEncoderSettingValue::String(value.as_str().to_string())
+            }
+        }
+    };
+}
+
+settings_enum!(
+    /// The rate control mode. Not every encoder supports every mode, which is
+    /// checked against the properties of the encoder when the settings are applied.
+    ObsEncoderRateControl {
+        /// Constant bitrate
+        Cbr => "CBR",
+        /// Variable bitrate
+        Vbr => "VBR",
+        /// Average bitrate
+        Abr => "ABR",
+        /// Constant rate factor (x264, VideoToolbox)
+        Crf => "CRF",
+        /// Constant QP
+        Cqp => "CQP",
+        /// Intelligent constant quality (QSV)
+        Icq => "ICQ",
+        /// Quality-defined variable bitrate (AMF)
+        Qvbr => "QVBR",
+        Lossless => "lossless",
+    }
+);
+
+settings_enum!(X264Preset {
+    UltraFast => "ultrafast",
+    SuperFast => "superfast",
+    VeryFast => "veryfast",
+    Faster => "faster",
+    Fast => "fast",
+    Medium => "medium",
+    Slow => "slow",
+    Slower => "slower",
+    VerySlow => "veryslow",
+    Placebo => "placebo",
+});
+
+settings_enum!(X264Profile {
+    Baseline => "baseline",
+    Main => "main",
+    High => "high",
+});
+
+settings_enum!(X264Tune {
+    Film => "film",
+    Animation => "animation",
+    Grain => "grain",
+    StillImage => "stillimage",
+    Psnr => "psnr",
+    Ssim => "ssim",
+    FastDecode => "fastdecode",
+    ZeroLatency => "zerolatency",
+});
+
+settings_enum!(
+    /// From fastest (`P1`) to slowest with the best quality (`P7`)
+    NvencPreset {
+        P1 => "p1",
+        P2 => "p2",
+        P3 => "p3",
+        P4 => "p4",
+        P5 => "p5",
+        P6 => "p6",
+        P7 => "p7",
+    }
+);
+
+settings_enum!(NvencTuning {
+    HighQuality => "hq",
+    LowLatency => "ll",
+    UltraLowLatency => "ull",
+});
+
+settings_enum!(NvencMultipass {
+    Disabled => "disabled",
+    QuarterResolution => "qres",
+    FullResolution => "fullres",
+});
+
+settings_enum!(AmfPreset {
+    Speed => "speed",
+    Balanced => "balanced",
+    Quality => "quality",
+    /// Only available for AV1
+    HighQuality => "highQuality",
+});
+
+settings_enum!(
+    /// From the best quality (`TU1`) to the fastest (`TU7`)
+    QsvTargetUsage {
+        TU1 => "TU1",
+        TU2 => "TU2",
+        TU3 => "TU3",
+        TU4 => "TU4",
+        TU5 => "TU5",
+        TU6 => "TU6",
+        TU7 => "TU7",
+    }
+);
+
+/// Collects the fields that are set into settings entries.
+macro_rules! entries {
+    ($($key:literal => $value:expr,)*) => {{
+        let mut entries = Vec::new();
+        $(
+            if let Some(value) = $value {
+                entries.push(($key, value));
+            }
+        )*
+        entries
+    }};
+}
+
+fn int<T: Into<i64>>(value: Option<T>) -> Option<ObsEncoderSettingValue> {
+    value.map(|v| ObsEncoderSettingValue::Int(v.into()))
+}
+
+fn bool(value: Option<bool>) -> Option<ObsEncoderSettingValue> {
+    value.map(ObsEncoderSettingValue::Bool)
+}
+
+fn string<T: Into<ObsEncoderSettingValue>>(value: Option<T>) -> Option<ObsEncoderSettingValue> {
+    value.map(Into::into)
+}
+
+/// Settings of the `obs_x264` encoder.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct X264Settings {
+    pub rate_control: Option<ObsEncoderRateControl>,
+    /// Bitrate in kbps
+    pub bitrate: Option<u32>,
+    pub crf: Option<u32>,
+    /// Keyframe interval in seconds, 0 is automatic
+    pub keyint_sec: Option<u32>,
+    pub preset: Option<X264Preset>,
+    pub profile: Option<X264Profile>,
+    pub tune: Option<X264Tune>,
+    /// Additional x264 options separated by spaces, e.g. `bframes=0`
+    pub x264opts: Option<String>,
+}
+
+impl ObsVideoEncoderSettings for X264Settings {
+    fn supports(&self, encoder: &ObsVideoEncoderType) -> bool {
+        *encoder == ObsVideoEncoderType::OBS_X264
+    }
+
+    fn entries(&self) -> Vec<(&'static str, ObsEncoderSettingValue)> {
+        entries! {
+            "rate_control" => string(self.rate_control),
+            "bitrate" => int(self.bitrate),
+            "crf" => int(self.crf),
+            "keyint_sec" => int(self.keyint_sec),
+            "preset" => string(self.preset),
+            "profile" => string(self.profile),
+            "tune" => string(self.tune),
+            "x264opts" => self.x264opts.clone().map(ObsEncoderSettingValue::String),
+        }
+    }
+}
+
+/// Settings of the NVENC encoders of the `obs-nvenc` plugin.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NvencSettings {
+    pub rate_control: Option<ObsEncoderRateControl>,
+    /// Bitrate in kbps
+    pub bitrate: Option<u32>,
+    /// Maximum bitrate in kbps, only used for VBR
+    pub max_bitrate: Option<u32>,
+    pub cqp: Option<u32>,
+    /// Keyframe interval in seconds, 0 is automatic
+    pub keyint_sec: Option<u32>,
+    pub preset: Option<NvencPreset>,
+    pub tuning: Option<NvencTuning>,
+    pub multipass: Option<NvencMultipass>,
+    pub lookahead: Option<bool>,
+    pub adaptive_quantization: Option<bool>,
+    pub bframes: Option<u32>,
+    /// Index of the GPU to encode on
+    pub gpu: Option<u32>,
+}
+
+impl ObsVideoEncoderSettings for NvencSettings {
+    fn supports(&self, encoder: &ObsVideoEncoderType) -> bool {
+        matches!(
+            encoder,
+            ObsVideoEncoderType::OBS_NVENC_H264_TEX
+                | ObsVideoEncoderType::OBS_NVENC_HEVC_TEX
+                | ObsVideoEncoderType::OBS_NVENC_AV1_TEX
+                | ObsVideoEncoderType::OBS_NVENC_H264_SOFT
+                | ObsVideoEncoderType::OBS_NVENC_HEVC_SOFT
+                | ObsVideoEncoderType::OBS_NVENC_AV1_SOFT
+        )
+    }
+
+    fn entries(&self) -> Vec<(&'static str, ObsEncoderSettingValue)> {
+        entries! {
+            "rate_control" => string(self.rate_control),
+            "bitrate" => int(self.bitrate),
+            "max_bitrate" => int(self.max_bitrate),
+            "cqp" => int(self.cqp),
+            "keyint_sec" => int(self.keyint_sec),
+            "preset" => string(self.preset),
+            "tune" => string(self.tuning),
+            "multipass" => string(self.multipass),
+            "lookahead" => bool(self.lookahead),
+            "adaptive_quantization" => bool(self.adaptive_quantization),
+            "bf" => int(self.bframes),
+            "gpu" => int(self.gpu),
+        }
+    }
+}
+
+/// Settings of the AMD AMF encoders.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AmfSettings {
+    pub rate_control: Option<ObsEncoderRateControl>,
+    /// Bitrate in kbps
+    pub bitrate: Option<u32>,
+    pub cqp: Option<u32>,
+    /// Keyframe interval in seconds, 0 is automatic
+    pub keyint_sec: Option<u32>,
+    pub preset: Option<AmfPreset>,
+    pub bframes: Option<u32>,
+}
+
+impl ObsVideoEncoderSettings for AmfSettings {
+    fn supports(&self, encoder: &ObsVideoEncoderType) -> bool {
+        matches!(
+            encoder,
+            ObsVideoEncoderType::H264_TEXTURE_AMF
+                | ObsVideoEncoderType::H265_TEXTURE_AMF
+                | ObsVideoEncoderType::AV1_TEXTURE_AMF
+        )
+    }
+
+    fn entries(&self) -> Vec<(&'static str, ObsEncoderSettingValue)> {
+        entries! {
+            "rate_control" => string(self.rate_control),
+            "bitrate" => int(self.bitrate),
+            "cqp" => int(self.cqp),
+            "keyint_sec" => int(self.keyint_sec),
+            "preset" => string(self.preset),
+            "bf" => int(self.bframes),
+        }
+    }
+}
+
+/// Settings of the Intel QuickSync encoders.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct QsvSettings {
+    pub rate_control: Option<ObsEncoderRateControl>,
+    /// Bitrate in kbps
+    pub bitrate: Option<u32>,
+    /// Maximum bitrate in kbps, only used for VBR
+    pub max_bitrate: Option<u32>,
+    pub cqp: Option<u32>,
+    /// Quality used for ICQ
+    pub icq_quality: Option<u32>,
+    /// Keyframe interval in seconds, 0 is automatic
+    pub keyint_sec: Option<u32>,
+    pub target_usage: Option<QsvTargetUsage>,
+    pub bframes: Option<u32>,
+}
+
+impl ObsVideoEncoderSettings for QsvSettings {
+    fn supports(&self, encoder: &ObsVideoEncoderType) -> bool {
+        matches!(
+            encoder,
+            ObsVideoEncoderType::OBS_QSV11
+                | ObsVideoEncoderType::OBS_QSV11_SOFT
+                | ObsVideoEncoderType::OBS_QSV11_V2
+                | ObsVideoEncoderType::OBS_QSV11_SOFT_V2
+                | ObsVideoEncoderType::OBS_QSV11_AV1
+                | ObsVideoEncoderType::OBS_QSV11_AV1_SOFT
+                | ObsVideoEncoderType::OBS_QSV11_HEVC
+                | ObsVideoEncoderType::OBS_QSV11_HEVC_SOFT
+        )
+    }
+
+    fn entries(&self) -> Vec<(&'static str, ObsEncoderSettingValue)> {
+        entries! {
+            "rate_control" => string(self.rate_control),
+            "bitrate" => int(self.bitrate),
+            "max_bitrate" => int(self.max_bitrate),
+            "cqp" => int(self.cqp),
+            "icq_quality" => int(self.icq_quality),
+            "keyint_sec" => int(self.keyint_sec),
+            "target_usage" => string(self.target_usage),
+            "bframes" => int(self.bframes),
+        }
+    }
+}
+
+/// Settings of the Apple VideoToolbox encoders on macOS.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AppleVtSettings {
+    pub rate_control: Option<ObsEncoderRateControl>,
+    /// Bitrate in kbps
+    pub bitrate: Option<u32>,
+    /// Quality used for CRF, from 0 to 100
+    pub quality: Option<u32>,
+    /// Keyframe interval in seconds, 0 is automatic
+    pub keyint_sec: Option<u32>,
+    pub bframes: Option<bool>,
+}
+
+impl ObsVideoEncoderSettings for AppleVtSettings {
+    fn supports(&self, encoder: &ObsVideoEncoderType) -> bool {
+        matches!(encoder, ObsVideoEncoderType::Other(id) if id.starts_with("com.apple.videotoolbox"))
+    }
+
+    fn entries(&self) -> Vec<(&'static str, ObsEncoderSettingValue)> {
+        entries! {
+            "rate_control" => string(self.rate_control),
+            "bitrate" => int(self.bitrate),
+            "quality" => int(self.quality),
+            "keyint_sec" => int(self.keyint_sec),
+            "bframes" => bool(self.bframes),
+        }
+    }
+}
+
+/// Checks a single value against the property with the same key.
+fn check_entry(
+    key: &str,
+    property: &ObsProperty,
+    value: &ObsEncoderSettingValue,
+) -> Result<(), ObsPropertyValueError> {
+    match (property, value) {
+        (ObsProperty::List(list), value) => list.check_value(&value.clone().into()),
+        (ObsProperty::Int(number), ObsEncoderSettingValue::Int(i)) => number.check_value(*i),
+        (ObsProperty::Float(number), ObsEncoderSettingValue::Float(f)) => number.check_value(*f),
+        (ObsProperty::Bool, ObsEncoderSettingValue::Bool(_))
+        | (ObsProperty::Text(_), ObsEncoderSettingValue::String(_)) => Ok(()),
+        (_, value) => Err(ObsPropertyValueError::TypeMismatch {
+            property: key.to_string(),
+            value: value.clone().into(),
+        }),
+    }
+}
+
+/// Checks the given entries against the properties of an encoder. Entries without a
+/// matching property are not checked, as the encoder might only show them for some modes.
+pub(super) fn validate_entries(
+    properties: &HashMap<String, ObsProperty>,
+    entries: &[(&'static str, ObsEncoderSettingValue)],
+) -> Result<(), ObsError> {
+    for (key, value) in entries {
+        let Some(property) = properties.get(*key) else {
+            log::debug!("Encoder has no property {}, skipping validation", key);
+            continue;
+        };
+
+        check_entry(key, property, value).map_err(|e| ObsError::InvalidOperation(e.to_string()))?;
+    }
+
+    Ok(())
+}
+
+/// Reads every value of the settings that has a list, int or float property and checks it
+/// against that property. Returns all rejected values, sorted by key.
+pub(super) fn check_settings<T: ObsDataGetters>(
+    properties: &HashMap<String, ObsProperty>,
+    settings: &T,
+) -> Result<Vec<ObsPropertyValueError>, ObsError> {
+    let mut keys = properties.keys().collect::<Vec<_>>();
+    keys.sort_unstable();
+
+    let mut errors = Vec::new();
+    for key in keys {
+        let property = &properties[key];
+        let value = match property {
+            ObsProperty::List(list) => match list.read_value(settings)? {
+                Some(value) => list.check_value(&value),
+                None => continue,
+            },
+            ObsProperty::Int(number) => match settings.get_int(key.as_str())? {
+                Some(value) => number.check_value(value),
+                None => continue,
+            },
+            ObsProperty::Float(number) => match settings.get_double(key.as_str())? {
+                Some(value) => number.check_value(value),
+                None => continue,
+            },
+            _ => continue,
+        };
+
+        if let Err(error) = value {
+            errors.push(error);
+        }
+    }
+
+    Ok(errors)
+}
+
+/// Writes the given entries into the settings.
+pub(super) fn write_entries(
+    settings: &mut ObsData,
+    entries: Vec<(&'static str, ObsEncoderSettingValue)>,
+) -> Result<(), ObsError> {
+    for (key, value) in entries {
+        match value {
+            ObsEncoderSettingValue::String(s) => settings.set_string(key, s)?,
+            ObsEncoderSettingValue::Int(i) => settings.set_int(key, i)?,
+            ObsEncoderSettingValue::Float(f) => settings.set_double(key, f)?,
+            ObsEncoderSettingValue::Bool(b) => settings.set_bool(key, b)?,
+        };
+    }
+
+    Ok(())
+}
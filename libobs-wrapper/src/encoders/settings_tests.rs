@@ -0,0 +1,44 @@
+#[cfg(test)]
+mod tests {
+    use super::super::{
+        settings::{
+            AppleVtSettings, NvencMultipass, NvencPreset, NvencSettings, ObsEncoderSettingValue,
+            ObsVideoEncoderSettings, X264Settings,
+        },
+        ObsVideoEncoderType,
+    };
+
+    #[test]
+    fn test_only_set_fields_are_written() {
+        let settings = NvencSettings {
+            bitrate: Some(6000),
+            preset: Some(NvencPreset::P5),
+            multipass: Some(NvencMultipass::QuarterResolution),
+            lookahead: Some(false),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            settings.entries(),
+            vec![
+                ("bitrate", ObsEncoderSettingValue::Int(6000)),
+                ("preset", ObsEncoderSettingValue::String("p5".into())),
+                ("multipass", ObsEncoderSettingValue::String("qres".into())),
+                ("lookahead", ObsEncoderSettingValue::Bool(false)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_supported_encoders() {
+        let x264 = X264Settings::default();
+        assert!(x264.supports(&ObsVideoEncoderType::OBS_X264));
+        assert!(!x264.supports(&ObsVideoEncoderType::OBS_NVENC_H264_TEX));
+
+        let vt = AppleVtSettings::default();
+        assert!(vt.supports(&ObsVideoEncoderType::Other(
+            "com.apple.videotoolbox.videoencoder.ave.avc".into()
+        )));
+        assert!(!vt.supports(&ObsVideoEncoderType::OBS_X264));
+    }
+}
@@ -2,13 +2,37 @@ use libobs::obs_encoder;
 
 use crate::{data::object::ObsObjectTrait, run_with_obs, utils::ObsError};
 
+use super::{caps::codec_from_ptr, ObsEncoderCaps};
+
 pub trait ObsEncoderTrait: ObsObjectTrait<*mut obs_encoder> {
     fn is_active(&self) -> Result<bool, ObsError> {
         let encoder_ptr = self.as_ptr();
 
-        run_with_obs!(self.runtime(), (encoder_ptr), move || {
+        run_with_obs!(self.runtime(), (encoder_ptr), move |token| {
+            // Safety: The pointer is valid because we are using a smart pointer
+            unsafe { libobs::obs_encoder_active(encoder_ptr.get_ptr(token)) }
+        })
+    }
+
+    /// Returns the capabilities of this encoder.
+    fn caps(&self) -> Result<ObsEncoderCaps, ObsError> {
+        let encoder_ptr = self.as_ptr();
+
+        run_with_obs!(self.runtime(), (encoder_ptr), move |token| {
             // Safety: The pointer is valid because we are using a smart pointer
-            unsafe { libobs::obs_encoder_active(encoder_ptr.get_ptr()) }
+            let caps = unsafe { libobs::obs_encoder_get_caps(encoder_ptr.get_ptr(token)) };
+            ObsEncoderCaps::from_bits_retain(caps)
+        })
+    }
+
+    /// Returns the codec this encoder outputs, e.g. `h264` or `aac`.
+    fn codec(&self) -> Result<Option<String>, ObsError> {
+        let encoder_ptr = self.as_ptr();
+
+        run_with_obs!(self.runtime(), (encoder_ptr), move |token| unsafe {
+            // Safety: The pointer is valid because we are using a smart pointer and
+            // libobs returns null or a string owned by the encoder info
+            codec_from_ptr(libobs::obs_encoder_get_codec(encoder_ptr.get_ptr(token)))
         })
     }
 }
@@ -1,4 +1,5 @@
 use libobs::{obs_encoder, video_output};
+use num_traits::FromPrimitive;
 use std::{
     ptr,
     sync::{Arc, RwLock},
@@ -9,7 +10,9 @@ use crate::{
         object::{inner_fn_update_settings, ObsObjectTrait, ObsObjectTraitPrivate},
         ImmutableObsData, ObsData, ObsDataPointers,
     },
-    encoders::{ObsEncoderTrait, _ObsEncoderDropGuard},
+    encoders::{encoder_name, set_encoder_name, ObsEncoderTrait, _ObsEncoderDropGuard},
+    enums::{ObsScaleType, OsEnumType},
+    logger::{attach_logs_since, log_history_position},
     run_with_obs,
     runtime::ObsRuntime,
     unsafe_send::{Sendable, SmartPointerSendable},
@@ -25,7 +28,7 @@ use crate::{
 /// If not attached to the output, it'll just remove itself again.
 pub struct ObsVideoEncoder {
     pub(crate) id: ObsString,
-    pub(crate) name: ObsString,
+    pub(crate) name: Arc<RwLock<ObsString>>,
     pub(crate) settings: Arc<RwLock<ImmutableObsData>>,
     pub(crate) hotkey_data: Arc<RwLock<ImmutableObsData>>,
     pub(crate) runtime: ObsRuntime,
@@ -48,17 +51,19 @@ impl ObsVideoEncoder {
         let settings_ptr = settings.as_ref().map(|s| s.as_ptr());
         let hotkey_data_ptr = hotkey_data.as_ref().map(|h| h.as_ptr());
 
+        // Encoders log why they couldn't be created (e.g. an unsupported GPU)
+        let log_position = log_history_position();
         let encoder_ptr = run_with_obs!(
             runtime,
             (id, name, hotkey_data_ptr, settings_ptr),
-            move || {
+            move |token| {
                 let settings_ptr_raw = match settings_ptr {
-                    Some(s) => s.get_ptr(),
+                    Some(s) => s.get_ptr(token),
                     None => ptr::null_mut(),
                 };
 
                 let hotkey_data_ptr_raw = match hotkey_data_ptr {
-                    Some(h) => h.get_ptr(),
+                    Some(h) => h.get_ptr(token),
                     None => ptr::null_mut(),
                 };
 
@@ -73,12 +78,13 @@ impl ObsVideoEncoder {
                 };
 
                 if ptr.is_null() {
-                    Err(ObsError::NullPointer(None))
+                    Err(ObsError::NullPointer(None).in_function("obs_video_encoder_create"))
                 } else {
                     Ok(Sendable(ptr))
                 }
             }
-        )??;
+        )?
+        .map_err(|e| attach_logs_since(e, log_position))?;
 
         let encoder_ptr = SmartPointerSendable::new(
             encoder_ptr.0,
@@ -94,10 +100,10 @@ impl ObsVideoEncoder {
         };
 
         let settings = {
-            let settings_ptr = run_with_obs!(runtime, (encoder_ptr), move || {
+            let settings_ptr = run_with_obs!(runtime, (encoder_ptr), move |token| {
                 let ptr = unsafe {
                     // Safety: encoder_ptr is valid because of the SmartPointer
-                    libobs::obs_encoder_get_settings(encoder_ptr.get_ptr())
+                    libobs::obs_encoder_get_settings(encoder_ptr.get_ptr(token))
                 };
 
                 Sendable(ptr)
@@ -108,13 +114,154 @@ impl ObsVideoEncoder {
         Ok(Arc::new(Self {
             encoder: encoder_ptr,
             id,
-            name,
+            name: Arc::new(RwLock::new(name)),
             settings: Arc::new(RwLock::new(settings)),
             hotkey_data: Arc::new(RwLock::new(hotkey_data)),
             runtime,
         }))
     }
 
+    /// Returns the width the encoder encodes at, which differs from the output width
+    /// of the video context if scaling is enabled. Zero if no video is set yet.
+    pub fn width(&self) -> Result<u32, ObsError> {
+        let encoder_ptr = self.as_ptr();
+        run_with_obs!(self.runtime, (encoder_ptr), move |token| unsafe {
+            // Safety: The pointer is valid because of the smart pointer
+            libobs::obs_encoder_get_width(encoder_ptr.get_ptr(token))
+        })
+    }
+
+    /// Returns the height the encoder encodes at, see [`ObsVideoEncoder::width`].
+    pub fn height(&self) -> Result<u32, ObsError> {
+        let encoder_ptr = self.as_ptr();
+        run_with_obs!(self.runtime, (encoder_ptr), move |token| unsafe {
+            // Safety: The pointer is valid because of the smart pointer
+            libobs::obs_encoder_get_height(encoder_ptr.get_ptr(token))
+        })
+    }
+
+    /// Returns whether frames are scaled before they are passed to this encoder.
+    pub fn scaling_enabled(&self) -> Result<bool, ObsError> {
+        let encoder_ptr = self.as_ptr();
+        run_with_obs!(self.runtime, (encoder_ptr), move |token| unsafe {
+            // Safety: The pointer is valid because of the smart pointer
+            libobs::obs_encoder_scaling_enabled(encoder_ptr.get_ptr(token))
+        })
+    }
+
+    /// Scales the frames to the given size before they are encoded, so the output can have a
+    /// different resolution than the canvas. Pass `0, 0` to encode at the output resolution
+    /// of the video context again.
+    ///
+    /// Frames are scaled on the CPU, unless a GPU scale type is set with
+    /// [`ObsVideoEncoder::set_gpu_scale_type`]. Fails if the encoder is active.
+    pub fn set_scaled_size(&self, width: u32, height: u32) -> Result<(), ObsError> {
+        if self.is_active()? {
+            return Err(ObsError::EncoderActive);
+        }
+
+        let encoder_ptr = self.as_ptr();
+        run_with_obs!(self.runtime, (encoder_ptr), move |token| unsafe {
+            // Safety: The pointer is valid because of the smart pointer
+            libobs::obs_encoder_set_scaled_size(encoder_ptr.get_ptr(token), width, height);
+        })
+    }
+
+    /// Scales the frames of this encoder on the GPU with the given filter, which is
+    /// faster and looks better than CPU scaling. `ObsScaleType::Disable` switches back to
+    /// CPU scaling. Fails if the encoder is active.
+    pub fn set_gpu_scale_type(&self, scale_type: ObsScaleType) -> Result<(), ObsError> {
+        if self.is_active()? {
+            return Err(ObsError::EncoderActive);
+        }
+
+        let encoder_ptr = self.as_ptr();
+        let scale_type = scale_type as OsEnumType;
+        run_with_obs!(self.runtime, (encoder_ptr), move |token| unsafe {
+            // Safety: The pointer is valid because of the smart pointer
+            libobs::obs_encoder_set_gpu_scale_type(encoder_ptr.get_ptr(token), scale_type);
+        })
+    }
+
+    /// Returns the GPU scale type of this encoder, `ObsScaleType::Disable` if frames
+    /// are scaled on the CPU.
+    pub fn gpu_scale_type(&self) -> Result<ObsScaleType, ObsError> {
+        let encoder_ptr = self.as_ptr();
+        let scale_type = run_with_obs!(self.runtime, (encoder_ptr), move |token| unsafe {
+            // Safety: The pointer is valid because of the smart pointer
+            libobs::obs_encoder_get_scale_type(encoder_ptr.get_ptr(token))
+        })?;
+
+        ObsScaleType::from_i64(scale_type as i64).ok_or_else(|| {
+            ObsError::Unexpected(format!("Unknown scale type {}", scale_type))
+        })
+    }
+
+    /// Only passes every `divisor`-th frame of the canvas to this encoder, e.g. a divisor of `2`
+    /// records a 30 fps proxy of a 60 fps canvas while another output streams at the full frame rate.
+    ///
+    /// A divisor of `1` encodes every frame again. Fails if the encoder is active or the divisor is zero.
+    pub fn set_frame_rate_divisor(&self, divisor: u32) -> Result<(), ObsError> {
+        if divisor == 0 {
+            return Err(ObsError::InvalidOperation(
+                "The frame rate divisor must be at least 1".to_string(),
+            ));
+        }
+
+        if self.is_active()? {
+            return Err(ObsError::EncoderActive);
+        }
+
+        let encoder_ptr = self.as_ptr();
+        let success = run_with_obs!(self.runtime, (encoder_ptr), move |token| unsafe {
+            // Safety: The pointer is valid because of the smart pointer
+            libobs::obs_encoder_set_frame_rate_divisor(encoder_ptr.get_ptr(token), divisor)
+        })?;
+
+        if !success {
+            return Err(ObsError::InvalidOperation(format!(
+                "Failed to set the frame rate divisor {} of the encoder",
+                divisor
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Returns the frame rate divisor of this encoder, `1` if every frame is encoded.
+    pub fn frame_rate_divisor(&self) -> Result<u32, ObsError> {
+        let encoder_ptr = self.as_ptr();
+        let divisor = run_with_obs!(self.runtime, (encoder_ptr), move |token| unsafe {
+            // Safety: The pointer is valid because of the smart pointer
+            libobs::obs_encoder_get_frame_rate_divisor(encoder_ptr.get_ptr(token))
+        })?;
+
+        // libobs reports 0 until a divisor has been set
+        Ok(divisor.max(1))
+    }
+
+    /// Updates the settings while the encoder is active, e.g. to lower the bitrate of a
+    /// running stream. The given settings are merged into the current ones.
+    ///
+    /// Encoders only apply what they can reconfigure on the fly, like the bitrate of encoders
+    /// with [`ObsEncoderCaps::DYN_BITRATE`](crate::encoders::ObsEncoderCaps::DYN_BITRATE).
+    pub fn update_active_settings(&self, settings: ObsData) -> Result<(), ObsError> {
+        let settings = settings.into_immutable();
+        let settings_ptr = settings.as_ptr();
+        let encoder_ptr = self.as_ptr();
+
+        let current = run_with_obs!(self.runtime, (encoder_ptr, settings_ptr), move |token| unsafe {
+            // Safety: Both pointers are valid because of the smart pointers
+            libobs::obs_encoder_update(encoder_ptr.get_ptr(token), settings_ptr.get_ptr(token));
+            Sendable(libobs::obs_encoder_get_settings(encoder_ptr.get_ptr(token)))
+        })?;
+
+        self.__internal_replace_settings(ImmutableObsData::from_raw_pointer(
+            current,
+            self.runtime.clone(),
+        ))
+    }
+
     /// This is only needed once for global video context
     /// # Safety
     /// The handler pointer must be a valid pointer to a video_output that lives as long as this function call.
@@ -123,10 +270,10 @@ impl ObsVideoEncoder {
         handler: Sendable<*mut video_output>,
     ) -> Result<(), ObsError> {
         let self_ptr = self.as_ptr();
-        run_with_obs!(self.runtime, (handler, self_ptr), move || {
+        run_with_obs!(self.runtime, (handler, self_ptr), move |token| {
             unsafe {
                 // Safety: Caller must make sure that the handler pointer is valid and the self pointer is a SmartPointer.
-                libobs::obs_encoder_set_video(self_ptr.get_ptr(), handler.0);
+                libobs::obs_encoder_set_video(self_ptr.get_ptr(token), handler.0);
             }
         })
     }
@@ -194,8 +341,16 @@ impl ObsObjectTrait<*mut libobs::obs_encoder> for ObsVideoEncoder {
         self.id.clone()
     }
 
+    /// Returns the current name of this encoder, including renames that were not done
+    /// through this crate.
     fn name(&self) -> ObsString {
-        self.name.clone()
+        encoder_name(&self.runtime, &self.encoder, &self.name)
+    }
+
+    /// Renames this encoder. Fails with `ObsError::NameAlreadyInUse` if another encoder
+    /// already has the name.
+    fn set_name(&self, new_name: &str) -> Result<(), ObsError> {
+        set_encoder_name(&self.runtime, &self.encoder, &self.name, new_name)
     }
 
     /// Updates the settings of this output. Fails if active.
@@ -140,6 +140,22 @@ pub enum ObsSpeakerLayout {
     Unknown = libobs::speaker_layout_SPEAKERS_UNKNOWN as OsEnumType,
 }
 
+#[cfg_attr(target_family = "windows", repr(i32))]
+#[cfg_attr(not(target_family = "windows"), repr(u32))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, FromPrimitive, ToPrimitive)]
+/// The sample format of raw audio, planar formats store every channel in its own plane.
+pub enum ObsAudioFormat {
+    Unknown = libobs::audio_format_AUDIO_FORMAT_UNKNOWN as OsEnumType,
+    U8Bit = libobs::audio_format_AUDIO_FORMAT_U8BIT as OsEnumType,
+    I16Bit = libobs::audio_format_AUDIO_FORMAT_16BIT as OsEnumType,
+    I32Bit = libobs::audio_format_AUDIO_FORMAT_32BIT as OsEnumType,
+    Float = libobs::audio_format_AUDIO_FORMAT_FLOAT as OsEnumType,
+    U8BitPlanar = libobs::audio_format_AUDIO_FORMAT_U8BIT_PLANAR as OsEnumType,
+    I16BitPlanar = libobs::audio_format_AUDIO_FORMAT_16BIT_PLANAR as OsEnumType,
+    I32BitPlanar = libobs::audio_format_AUDIO_FORMAT_32BIT_PLANAR as OsEnumType,
+    FloatPlanar = libobs::audio_format_AUDIO_FORMAT_FLOAT_PLANAR as OsEnumType,
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ObsOutputStopSignal {
     /// Successfully stopped
@@ -288,6 +304,81 @@ pub enum ObsBoundsType {
     MaxOnly = libobs::obs_bounds_type_OBS_BOUNDS_MAX_ONLY,
 }
 
+#[cfg_attr(target_os = "windows", repr(i32))]
+#[cfg_attr(not(target_os = "windows"), repr(u32))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, FromPrimitive, ToPrimitive)]
+/// Describes how a fader or volume meter maps deflection (0.0 - 1.0) to dB values.
+pub enum ObsFaderType {
+    /// A cubic fader, the default of OBS Studio
+    Cubic = libobs::obs_fader_type_OBS_FADER_CUBIC,
+    /// A fader compliant to IEC 60-268-18
+    IEC = libobs::obs_fader_type_OBS_FADER_IEC,
+    /// A logarithmic fader
+    Log = libobs::obs_fader_type_OBS_FADER_LOG,
+}
+
+#[cfg_attr(target_os = "windows", repr(i32))]
+#[cfg_attr(not(target_os = "windows"), repr(u32))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, FromPrimitive, ToPrimitive)]
+pub enum ObsPeakMeterType {
+    /// Measures the maximum of all samples
+    SamplePeak = libobs::obs_peak_meter_type_SAMPLE_PEAK_METER,
+    /// Measures the maximum of inter-samples (4x oversampling), more accurate but more expensive
+    TruePeak = libobs::obs_peak_meter_type_TRUE_PEAK_METER,
+}
+
+#[cfg_attr(target_os = "windows", repr(i32))]
+#[cfg_attr(not(target_os = "windows"), repr(u32))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, FromPrimitive, ToPrimitive)]
+/// Describes how interlaced video of a source (e.g. a capture card) is deinterlaced.
+/// The `2x` modes output both fields as separate frames, doubling the frame rate.
+pub enum ObsDeinterlaceMode {
+    Disable = libobs::obs_deinterlace_mode_OBS_DEINTERLACE_MODE_DISABLE as OsEnumType,
+    Discard = libobs::obs_deinterlace_mode_OBS_DEINTERLACE_MODE_DISCARD as OsEnumType,
+    Retro = libobs::obs_deinterlace_mode_OBS_DEINTERLACE_MODE_RETRO as OsEnumType,
+    Blend = libobs::obs_deinterlace_mode_OBS_DEINTERLACE_MODE_BLEND as OsEnumType,
+    Blend2x = libobs::obs_deinterlace_mode_OBS_DEINTERLACE_MODE_BLEND_2X as OsEnumType,
+    Linear = libobs::obs_deinterlace_mode_OBS_DEINTERLACE_MODE_LINEAR as OsEnumType,
+    Linear2x = libobs::obs_deinterlace_mode_OBS_DEINTERLACE_MODE_LINEAR_2X as OsEnumType,
+    Yadif = libobs::obs_deinterlace_mode_OBS_DEINTERLACE_MODE_YADIF as OsEnumType,
+    Yadif2x = libobs::obs_deinterlace_mode_OBS_DEINTERLACE_MODE_YADIF_2X as OsEnumType,
+}
+
+#[cfg_attr(target_os = "windows", repr(i32))]
+#[cfg_attr(not(target_os = "windows"), repr(u32))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, FromPrimitive, ToPrimitive)]
+/// Describes which field of interlaced video comes first.
+pub enum ObsDeinterlaceFieldOrder {
+    Top = libobs::obs_deinterlace_field_order_OBS_DEINTERLACE_FIELD_ORDER_TOP as OsEnumType,
+    Bottom = libobs::obs_deinterlace_field_order_OBS_DEINTERLACE_FIELD_ORDER_BOTTOM as OsEnumType,
+}
+
+#[cfg_attr(target_os = "windows", repr(i32))]
+#[cfg_attr(not(target_os = "windows"), repr(u32))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, FromPrimitive, ToPrimitive)]
+/// Describes how a scene item is blended with the items below it,
+/// e.g. `Additive` for light leaks or `Multiply` for vignettes.
+pub enum ObsBlendingType {
+    Normal = libobs::obs_blending_type_OBS_BLEND_NORMAL as OsEnumType,
+    Additive = libobs::obs_blending_type_OBS_BLEND_ADDITIVE as OsEnumType,
+    Subtract = libobs::obs_blending_type_OBS_BLEND_SUBTRACT as OsEnumType,
+    Screen = libobs::obs_blending_type_OBS_BLEND_SCREEN as OsEnumType,
+    Multiply = libobs::obs_blending_type_OBS_BLEND_MULTIPLY as OsEnumType,
+    Lighten = libobs::obs_blending_type_OBS_BLEND_LIGHTEN as OsEnumType,
+    Darken = libobs::obs_blending_type_OBS_BLEND_DARKEN as OsEnumType,
+}
+
+#[cfg_attr(target_os = "windows", repr(i32))]
+#[cfg_attr(not(target_os = "windows"), repr(u32))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, FromPrimitive, ToPrimitive)]
+/// Describes in which color space a scene item is blended.
+pub enum ObsBlendingMethod {
+    /// Blends in linear space if the source supports sRGB
+    Default = libobs::obs_blending_method_OBS_BLEND_METHOD_DEFAULT as OsEnumType,
+    /// Blends in gamma space, which matches the behavior of OBS before version 28
+    SrgbOff = libobs::obs_blending_method_OBS_BLEND_METHOD_SRGB_OFF as OsEnumType,
+}
+
 bitflags! {
     /// Represents a set of flags.
     #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -299,3 +390,48 @@ bitflags! {
         const CENTER = libobs::OBS_ALIGN_CENTER;
     }
 }
+
+bitflags! {
+    /// Flags that change the behavior of a source, see `ObsSourceTrait::set_flags`.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    pub struct ObsSourceFlags: u32 {
+        /// Downmixes the audio of the source to mono
+        const FORCE_MONO = libobs::OBS_SOURCE_FLAG_FORCE_MONO;
+    }
+}
+
+bitflags! {
+    /// Capabilities of a source type, see `ObsSourceTrait::output_flags`.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    pub struct ObsSourceOutputFlags: u32 {
+        /// The source renders video
+        const VIDEO = libobs::OBS_SOURCE_VIDEO;
+        /// The source outputs audio, so volume controls make sense
+        const AUDIO = libobs::OBS_SOURCE_AUDIO;
+        /// The source outputs frames on its own thread instead of rendering them
+        const ASYNC = libobs::OBS_SOURCE_ASYNC;
+        const ASYNC_VIDEO = libobs::OBS_SOURCE_ASYNC_VIDEO;
+        /// The source draws itself instead of using the default effect
+        const CUSTOM_DRAW = libobs::OBS_SOURCE_CUSTOM_DRAW;
+        /// The source handles mouse and keyboard input, see `ObsSourceInteraction`
+        const INTERACTION = libobs::OBS_SOURCE_INTERACTION;
+        /// The source contains other sources, e.g. a scene or a transition
+        const COMPOSITE = libobs::OBS_SOURCE_COMPOSITE;
+        /// The source should not be duplicated, e.g. because it captures a device
+        const DO_NOT_DUPLICATE = libobs::OBS_SOURCE_DO_NOT_DUPLICATE;
+        const DEPRECATED = libobs::OBS_SOURCE_DEPRECATED;
+        /// Monitoring the audio of the source would create a feedback loop
+        const DO_NOT_SELF_MONITOR = libobs::OBS_SOURCE_DO_NOT_SELF_MONITOR;
+        /// The source type is hidden from the user
+        const CAP_DISABLED = libobs::OBS_SOURCE_CAP_DISABLED;
+        const MONITOR_BY_DEFAULT = libobs::OBS_SOURCE_MONITOR_BY_DEFAULT;
+        const SUBMIX = libobs::OBS_SOURCE_SUBMIX;
+        /// The source supports media controls like play, pause and seek
+        const CONTROLLABLE_MEDIA = libobs::OBS_SOURCE_CONTROLLABLE_MEDIA;
+        const CEA_708 = libobs::OBS_SOURCE_CEA_708;
+        const SRGB = libobs::OBS_SOURCE_SRGB;
+        /// The source has no properties that should be shown to the user
+        const CAP_DONT_SHOW_PROPERTIES = libobs::OBS_SOURCE_CAP_DONT_SHOW_PROPERTIES;
+        const REQUIRES_CANVAS = libobs::OBS_SOURCE_REQUIRES_CANVAS;
+    }
+}
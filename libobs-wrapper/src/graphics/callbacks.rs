@@ -0,0 +1,142 @@
+//! Closures that are run by OBS every frame, see `ObsContext::add_tick_callback`
+//! and `ObsContext::add_main_render_callback`.
+use std::ffi::c_void;
+
+use crate::{
+    graphics::GraphicsContext,
+    impl_obs_drop, run_with_obs,
+    runtime::ObsRuntime,
+    unsafe_send::Sendable,
+    utils::{ObsDropGuard, ObsError},
+};
+
+type TickFn = dyn FnMut(f32) + Send + 'static;
+type RenderFn = dyn FnMut(&mut GraphicsContext) + Send + 'static;
+
+#[allow(unknown_lints)]
+#[allow(ensure_obs_call_in_runtime)]
+/// # Safety
+/// Only called by OBS on the graphics thread with the data registered in `ObsTickCallback::new`.
+unsafe extern "C" fn tick_callback(param: *mut c_void, seconds: f32) {
+    let callback = &mut *(param as *mut Box<TickFn>);
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| callback(seconds)));
+    if result.is_err() {
+        log::error!("Tick callback panicked");
+    }
+}
+
+#[allow(unknown_lints)]
+#[allow(ensure_obs_call_in_runtime)]
+/// # Safety
+/// Only called by OBS on the graphics thread while the main texture is rendered, with the data
+/// registered in `ObsMainRenderCallback::new`. The projection is set to the base size of the video.
+unsafe extern "C" fn main_render_callback(param: *mut c_void, cx: u32, cy: u32) {
+    let callback = &mut *(param as *mut Box<RenderFn>);
+    let mut ctx = GraphicsContext::new(cx, cy, cx, cy);
+
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| callback(&mut ctx)));
+    if result.is_err() {
+        log::error!("Main render callback panicked");
+    }
+}
+
+/// A closure that is run on every tick of the graphics thread, before the frame is rendered.
+///
+/// The closure gets the seconds that have elapsed since the last tick. It is removed once this guard is dropped.
+/// It must not block, as it delays the rendering of every frame.
+#[derive(Debug)]
+pub struct ObsTickCallback {
+    _guard: _ObsTickCallbackDropGuard,
+}
+
+impl ObsTickCallback {
+    pub(crate) fn new<F>(runtime: ObsRuntime, callback: F) -> Result<Self, ObsError>
+    where
+        F: FnMut(f32) + Send + 'static,
+    {
+        let callback: Box<Box<TickFn>> = Box::new(Box::new(callback));
+        let data = Sendable(Box::into_raw(callback));
+
+        let added = run_with_obs!(runtime, (data), move || unsafe {
+            // Safety: The data is freed after the callback has been removed in the drop guard.
+            libobs::obs_add_tick_callback(Some(tick_callback), data.0 as *mut c_void);
+        });
+
+        if let Err(e) = added {
+            unsafe {
+                // Safety: The callback has not been added, so the data is not used by OBS
+                drop(Box::from_raw(data.0));
+            }
+            return Err(e);
+        }
+
+        Ok(Self {
+            _guard: _ObsTickCallbackDropGuard { data, runtime },
+        })
+    }
+}
+
+#[derive(Debug)]
+struct _ObsTickCallbackDropGuard {
+    data: Sendable<*mut Box<TickFn>>,
+    runtime: ObsRuntime,
+}
+
+impl ObsDropGuard for _ObsTickCallbackDropGuard {}
+
+impl_obs_drop!(_ObsTickCallbackDropGuard, (data), move || unsafe {
+    // Safety: The callback is removed before its data is freed, so it can't be accessed afterwards.
+    libobs::obs_remove_tick_callback(Some(tick_callback), data.0 as *mut c_void);
+    drop(Box::from_raw(data.0));
+});
+
+/// A closure that draws on top of the main texture, after the scene has been rendered.
+///
+/// Unlike the draw callbacks of a display, everything drawn here is part of the video that is
+/// encoded by the outputs (e.g. watermarks). The `GraphicsContext` uses the base size of the video
+/// as display and canvas size. The closure is removed once this guard is dropped.
+#[derive(Debug)]
+pub struct ObsMainRenderCallback {
+    _guard: _ObsMainRenderCallbackDropGuard,
+}
+
+impl ObsMainRenderCallback {
+    pub(crate) fn new<F>(runtime: ObsRuntime, callback: F) -> Result<Self, ObsError>
+    where
+        F: FnMut(&mut GraphicsContext) + Send + 'static,
+    {
+        let callback: Box<Box<RenderFn>> = Box::new(Box::new(callback));
+        let data = Sendable(Box::into_raw(callback));
+
+        let added = run_with_obs!(runtime, (data), move || unsafe {
+            // Safety: The data is freed after the callback has been removed in the drop guard.
+            libobs::obs_add_main_render_callback(Some(main_render_callback), data.0 as *mut c_void);
+        });
+
+        if let Err(e) = added {
+            unsafe {
+                // Safety: The callback has not been added, so the data is not used by OBS
+                drop(Box::from_raw(data.0));
+            }
+            return Err(e);
+        }
+
+        Ok(Self {
+            _guard: _ObsMainRenderCallbackDropGuard { data, runtime },
+        })
+    }
+}
+
+#[derive(Debug)]
+struct _ObsMainRenderCallbackDropGuard {
+    data: Sendable<*mut Box<RenderFn>>,
+    runtime: ObsRuntime,
+}
+
+impl ObsDropGuard for _ObsMainRenderCallbackDropGuard {}
+
+impl_obs_drop!(_ObsMainRenderCallbackDropGuard, (data), move || unsafe {
+    // Safety: The callback is removed before its data is freed, so it can't be accessed afterwards.
+    libobs::obs_remove_main_render_callback(Some(main_render_callback), data.0 as *mut c_void);
+    drop(Box::from_raw(data.0));
+});
@@ -0,0 +1,211 @@
+use std::marker::PhantomData;
+
+use crate::graphics::{ObsTexRender, ObsTexture};
+
+/// Gives access to the graphics subsystem of OBS while a draw callback is running.
+///
+/// This struct can only be obtained inside a draw callback, which is executed on the graphics thread,
+/// so every method can safely call into the graphics subsystem.
+/// Coordinates are in canvas space (the base resolution of the video info), so overlays line up with the rendered scene
+/// regardless of the size of the display.
+/// Colors are given as `0xAARRGGBB`.
+pub struct GraphicsContext<'a> {
+    display_width: u32,
+    display_height: u32,
+    canvas_width: u32,
+    canvas_height: u32,
+    /// Ties the context to the draw callback and keeps it on the graphics thread
+    _graphics_thread: PhantomData<&'a *const ()>,
+}
+
+impl GraphicsContext<'_> {
+    /// # Safety
+    /// Must only be constructed on the graphics thread while the graphics context is entered,
+    /// with a projection matching the canvas size.
+    pub(crate) unsafe fn new(
+        display_width: u32,
+        display_height: u32,
+        canvas_width: u32,
+        canvas_height: u32,
+    ) -> Self {
+        Self {
+            display_width,
+            display_height,
+            canvas_width,
+            canvas_height,
+            _graphics_thread: PhantomData,
+        }
+    }
+
+    /// Returns the size of the display in pixels.
+    pub fn display_size(&self) -> (u32, u32) {
+        (self.display_width, self.display_height)
+    }
+
+    /// Returns the size of the canvas, which is the coordinate space used for drawing.
+    pub fn canvas_size(&self) -> (u32, u32) {
+        (self.canvas_width, self.canvas_height)
+    }
+
+    /// Draws a line from `(x1, y1)` to `(x2, y2)`.
+    pub fn draw_line(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, color: u32) {
+        self.draw_solid(libobs::gs_draw_mode_GS_LINES, &[(x1, y1), (x2, y2)], color);
+    }
+
+    /// Draws the outline of a rectangle, e.g. for selection outlines or safe-area guides.
+    pub fn draw_rect(&mut self, x: f32, y: f32, width: f32, height: f32, color: u32) {
+        self.draw_solid(
+            libobs::gs_draw_mode_GS_LINESTRIP,
+            &[
+                (x, y),
+                (x + width, y),
+                (x + width, y + height),
+                (x, y + height),
+                (x, y),
+            ],
+            color,
+        );
+    }
+
+    /// Draws a filled rectangle.
+    pub fn fill_rect(&mut self, x: f32, y: f32, width: f32, height: f32, color: u32) {
+        self.draw_solid(
+            libobs::gs_draw_mode_GS_TRISTRIP,
+            &[
+                (x, y),
+                (x + width, y),
+                (x, y + height),
+                (x + width, y + height),
+            ],
+            color,
+        );
+    }
+
+    /// Runs `f` with the given translation and scale applied to everything drawn inside of it.
+    pub fn with_transform<F: FnOnce(&mut Self)>(
+        &mut self,
+        translate_x: f32,
+        translate_y: f32,
+        scale_x: f32,
+        scale_y: f32,
+        f: F,
+    ) {
+        unsafe {
+            // Safety: We are on the graphics thread, see `new`
+            libobs::gs_matrix_push();
+            libobs::gs_matrix_translate3f(translate_x, translate_y, 0.0);
+            libobs::gs_matrix_scale3f(scale_x, scale_y, 1.0);
+        }
+
+        f(self);
+
+        unsafe {
+            // Safety: We are on the graphics thread and pushed the matrix above
+            libobs::gs_matrix_pop();
+        }
+    }
+
+    /// Draws the texture with its top left corner at `(x, y)`, scaled to the given size.
+    pub fn draw_texture(&mut self, texture: &ObsTexture, x: f32, y: f32, width: f32, height: f32) {
+        let (texture_width, texture_height) = texture.size();
+        unsafe {
+            // Safety: The texture is valid while borrowed
+            self.draw_sprite(
+                texture.as_ptr(),
+                texture_width,
+                texture_height,
+                x,
+                y,
+                width,
+                height,
+            );
+        }
+    }
+
+    /// Draws the last render of the texrender with its top left corner at `(x, y)`, scaled to the given size.
+    pub fn draw_texrender(
+        &mut self,
+        texrender: &ObsTexRender,
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+    ) {
+        let Some((texture_width, texture_height)) = texrender.size() else {
+            return;
+        };
+
+        unsafe {
+            // Safety: The texture is owned by the texrender, which is valid while borrowed
+            self.draw_sprite(
+                texrender.texture_ptr(),
+                texture_width,
+                texture_height,
+                x,
+                y,
+                width,
+                height,
+            );
+        }
+    }
+
+    /// # Safety
+    /// The texture must be valid and have the given size.
+    #[allow(clippy::too_many_arguments)]
+    unsafe fn draw_sprite(
+        &mut self,
+        texture: *mut libobs::gs_texture_t,
+        texture_width: u32,
+        texture_height: u32,
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+    ) {
+        if texture.is_null() || texture_width == 0 || texture_height == 0 {
+            return;
+        }
+
+        // Safety: We are on the graphics thread, see `new`. The effect is owned by OBS.
+        let effect = libobs::obs_get_base_effect(libobs::obs_base_effect_OBS_EFFECT_DEFAULT);
+        if effect.is_null() {
+            return;
+        }
+
+        let image = libobs::gs_effect_get_param_by_name(effect, c"image".as_ptr());
+        libobs::gs_effect_set_texture(image, texture);
+
+        self.with_transform(
+            x,
+            y,
+            width / texture_width as f32,
+            height / texture_height as f32,
+            |_| {
+                while libobs::gs_effect_loop(effect, c"Draw".as_ptr()) {
+                    libobs::gs_draw_sprite(texture, 0, 0, 0);
+                }
+            },
+        );
+    }
+
+    fn draw_solid(&mut self, mode: libobs::gs_draw_mode, vertices: &[(f32, f32)], color: u32) {
+        unsafe {
+            // Safety: We are on the graphics thread, see `new`. The effect is owned by OBS.
+            let solid = libobs::obs_get_base_effect(libobs::obs_base_effect_OBS_EFFECT_SOLID);
+            if solid.is_null() {
+                return;
+            }
+
+            let color_param = libobs::gs_effect_get_param_by_name(solid, c"color".as_ptr());
+            libobs::gs_effect_set_color(color_param, color);
+
+            while libobs::gs_effect_loop(solid, c"Solid".as_ptr()) {
+                libobs::gs_render_start(true);
+                for (x, y) in vertices {
+                    libobs::gs_vertex2f(*x, *y);
+                }
+                libobs::gs_render_stop(mode);
+            }
+        }
+    }
+}
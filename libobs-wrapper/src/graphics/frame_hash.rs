@@ -0,0 +1,156 @@
+//! Perceptual hashes of the rendered canvas, so tests can check that something is actually
+//! rendered without comparing exact pixels, which differ between GPUs and drivers.
+
+use std::fmt;
+
+use crate::{
+    graphics::{ObsGraphicsGuard, ObsStageSurface, ObsTexRender},
+    run_with_obs,
+    runtime::ObsRuntime,
+    utils::ObsError,
+};
+
+/// Number of columns the frame is scaled down to, one more than the bits of a row
+const HASH_COLUMNS: usize = 9;
+const HASH_ROWS: usize = 8;
+
+/// A difference hash (dHash) of a frame.
+///
+/// Every bit tells whether the brightness increases from one column to the next of the frame
+/// scaled down to 9x8 pixels. Similar frames have hashes with a small [`distance`](Self::distance),
+/// frames with a single color always hash to zero.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ObsFrameHash(u64);
+
+impl ObsFrameHash {
+    /// Hashes tightly packed RGBA pixels.
+    pub fn from_rgba(pixels: &[u8], width: u32, height: u32) -> Result<Self, ObsError> {
+        let (width, height) = (width as usize, height as usize);
+        if width == 0 || height == 0 || pixels.len() != width * height * 4 {
+            return Err(ObsError::InvalidOperation(format!(
+                "Expected {} bytes for a {}x{} RGBA frame, got {}",
+                width * height * 4,
+                width,
+                height,
+                pixels.len()
+            )));
+        }
+
+        let mut cells = [[0u32; HASH_COLUMNS]; HASH_ROWS];
+        for (cy, row) in cells.iter_mut().enumerate() {
+            let (y_start, y_end) = cell_range(cy, HASH_ROWS, height);
+            for (cx, cell) in row.iter_mut().enumerate() {
+                let (x_start, x_end) = cell_range(cx, HASH_COLUMNS, width);
+
+                let mut sum = 0u64;
+                for y in y_start..y_end {
+                    for x in x_start..x_end {
+                        let i = (y * width + x) * 4;
+                        sum += luma(pixels[i], pixels[i + 1], pixels[i + 2]) as u64;
+                    }
+                }
+
+                let count = ((y_end - y_start) * (x_end - x_start)) as u64;
+                *cell = (sum / count) as u32;
+            }
+        }
+
+        let mut hash = 0u64;
+        for row in cells.iter() {
+            for pair in row.windows(2) {
+                hash = (hash << 1) | (pair[0] < pair[1]) as u64;
+            }
+        }
+
+        Ok(Self(hash))
+    }
+
+    pub fn from_value(value: u64) -> Self {
+        Self(value)
+    }
+
+    pub fn value(&self) -> u64 {
+        self.0
+    }
+
+    /// Returns the number of bits that differ between both hashes, from 0 (same picture) to 64.
+    pub fn distance(&self, other: &ObsFrameHash) -> u32 {
+        (self.0 ^ other.0).count_ones()
+    }
+
+    /// Panics if the hash differs from the reference by more than `max_distance` bits.
+    ///
+    /// A distance of up to 5 is usually the same picture with different scaling or compression.
+    #[track_caller]
+    pub fn assert_similar(&self, reference: &ObsFrameHash, max_distance: u32) {
+        let distance = self.distance(reference);
+        assert!(
+            distance <= max_distance,
+            "Frame hash {} differs from the reference {} by {} bits, at most {} are allowed",
+            self,
+            reference,
+            distance,
+            max_distance
+        );
+    }
+
+    /// Renders the last frame of the main output to a staging surface and hashes it.
+    pub(crate) fn hash_main_texture(runtime: &ObsRuntime) -> Result<Self, ObsError> {
+        run_with_obs!(runtime, move || {
+            let mut ovi = std::mem::MaybeUninit::<libobs::obs_video_info>::uninit();
+            let ovi = unsafe {
+                // Safety: We are providing a valid pointer to be filled
+                if !libobs::obs_get_video_info(ovi.as_mut_ptr()) {
+                    return Err(ObsError::NullPointer(Some(
+                        "Failed to get video info".to_string(),
+                    )));
+                }
+
+                // Safety: libobs filled the pointer and returned success
+                ovi.assume_init()
+            };
+
+            let (width, height) = (ovi.output_width, ovi.output_height);
+            let gfx = ObsGraphicsGuard::enter();
+
+            let has_frame = unsafe {
+                // Safety: The graphics context is entered
+                !libobs::obs_get_main_texture().is_null()
+            };
+            if !has_frame {
+                return Err(ObsError::InvalidOperation(
+                    "No frame has been rendered yet".to_string(),
+                ));
+            }
+
+            let mut texrender = ObsTexRender::new(&gfx)?;
+            texrender.render(&gfx, width, height, |_ctx| unsafe {
+                // Safety: We are rendering to the texrender inside of the graphics context
+                libobs::obs_render_main_texture();
+            })?;
+
+            let mut surface = ObsStageSurface::new(&gfx, width, height)?;
+            let pixels = surface.read_texrender(&gfx, &texrender)?;
+
+            ObsFrameHash::from_rgba(&pixels, width, height)
+        })?
+    }
+}
+
+impl fmt::Display for ObsFrameHash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:016x}", self.0)
+    }
+}
+
+/// BT.601 luma of a pixel, 0 - 255
+fn luma(r: u8, g: u8, b: u8) -> u32 {
+    (r as u32 * 299 + g as u32 * 587 + b as u32 * 114) / 1000
+}
+
+/// Returns the pixels covered by the cell, at least one even if the frame is smaller than the grid.
+fn cell_range(cell: usize, cells: usize, len: usize) -> (usize, usize) {
+    let start = (cell * len / cells).min(len - 1);
+    let end = ((cell + 1) * len / cells).max(start + 1);
+    (start, end)
+}
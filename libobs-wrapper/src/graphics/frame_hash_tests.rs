@@ -0,0 +1,60 @@
+#[cfg(test)]
+mod tests {
+    use super::super::ObsFrameHash;
+
+    fn frame<F: Fn(u32, u32) -> [u8; 4]>(width: u32, height: u32, pixel: F) -> Vec<u8> {
+        let mut pixels = Vec::with_capacity((width * height * 4) as usize);
+        for y in 0..height {
+            for x in 0..width {
+                pixels.extend_from_slice(&pixel(x, y));
+            }
+        }
+
+        pixels
+    }
+
+    #[test]
+    fn test_solid_frame_hashes_to_zero() {
+        let pixels = frame(64, 36, |_, _| [200, 10, 10, 255]);
+        let hash = ObsFrameHash::from_rgba(&pixels, 64, 36).unwrap();
+        assert_eq!(hash.value(), 0);
+    }
+
+    #[test]
+    fn test_gradient_sets_every_bit() {
+        let pixels = frame(90, 40, |x, _| {
+            let v = (x * 255 / 89) as u8;
+            [v, v, v, 255]
+        });
+        let hash = ObsFrameHash::from_rgba(&pixels, 90, 40).unwrap();
+        assert_eq!(hash.value(), u64::MAX);
+    }
+
+    #[test]
+    fn test_scaled_frames_are_similar() {
+        let bars = |width: u32| {
+            move |x: u32, _| {
+                if (x * 7 / width).is_multiple_of(2) {
+                    [255, 255, 255, 255]
+                } else {
+                    [0, 0, 0, 255]
+                }
+            }
+        };
+
+        let small = ObsFrameHash::from_rgba(&frame(160, 90, bars(160)), 160, 90).unwrap();
+        let large = ObsFrameHash::from_rgba(&frame(640, 360, bars(640)), 640, 360).unwrap();
+        small.assert_similar(&large, 5);
+
+        let inverted = ObsFrameHash::from_value(!small.value());
+        assert_eq!(small.distance(&inverted), 64);
+    }
+
+    #[test]
+    fn test_tiny_frames_and_invalid_sizes() {
+        let pixels = frame(2, 2, |x, _| [x as u8 * 255, 0, 0, 255]);
+        assert!(ObsFrameHash::from_rgba(&pixels, 2, 2).is_ok());
+        assert!(ObsFrameHash::from_rgba(&pixels, 3, 2).is_err());
+        assert!(ObsFrameHash::from_rgba(&[], 0, 0).is_err());
+    }
+}
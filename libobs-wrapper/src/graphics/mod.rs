@@ -1,4 +1,28 @@
-//! Holds a Vec2 vector, mainly used for positioning the ObsDisplay or ObsSceneItems.
+//! Holds a Vec2 vector, mainly used for positioning the ObsDisplay or ObsSceneItems,
+//! the `GraphicsContext` used to draw custom overlays in display draw callbacks
+//! the callbacks OBS runs every frame, safe wrappers for textures, texture renderers and staging surfaces
+//! perceptual hashes of the rendered canvas and offscreen thumbnails of sources.
+
+mod callbacks;
+pub use callbacks::{ObsMainRenderCallback, ObsTickCallback};
+
+mod context;
+pub use context::GraphicsContext;
+
+mod frame_hash;
+#[cfg(test)]
+mod frame_hash_tests;
+pub use frame_hash::ObsFrameHash;
+
+mod texture;
+#[cfg(test)]
+mod texture_tests;
+pub use texture::{ObsGraphicsGuard, ObsStageSurface, ObsTexRender, ObsTexture};
+
+mod thumbnails;
+#[cfg(test)]
+mod thumbnails_tests;
+pub use thumbnails::{ObsSourceThumbnail, ObsSourceThumbnailer, ObsThumbnailConfig};
 
 mod vec2;
 pub use vec2::Vec2;
@@ -0,0 +1,381 @@
+//! Textures, texture renderers and staging surfaces of the graphics subsystem.
+//!
+//! Every call into the graphics subsystem requires the graphics context to be entered, which
+//! is proven by passing an `ObsGraphicsGuard`. The objects enter the graphics context on their own when
+//! they are dropped, so they can be stored and dropped anywhere.
+use std::marker::PhantomData;
+
+use crate::{graphics::GraphicsContext, unsafe_send::Sendable, utils::ObsError};
+
+/// Enters the graphics context of OBS and leaves it again once dropped.
+///
+/// While this guard is alive, the graphics thread can't render, so keep it short-lived.
+/// The graphics context can be entered multiple times on the same thread, e.g. inside a draw callback.
+/// The guard can't be sent to another thread, as the context has to be left on the thread it was entered on.
+#[derive(Debug)]
+pub struct ObsGraphicsGuard {
+    _not_send: PhantomData<*const ()>,
+}
+
+impl ObsGraphicsGuard {
+    #[allow(unknown_lints)]
+    #[allow(ensure_obs_call_in_runtime)]
+    pub fn enter() -> Self {
+        unsafe {
+            // Safety: Entering the graphics context is thread-safe, libobs locks the graphics mutex
+            libobs::obs_enter_graphics();
+        }
+
+        Self {
+            _not_send: PhantomData,
+        }
+    }
+}
+
+impl Drop for ObsGraphicsGuard {
+    #[allow(unknown_lints)]
+    #[allow(ensure_obs_call_in_runtime)]
+    fn drop(&mut self) {
+        unsafe {
+            // Safety: The context has been entered on this thread in `enter`
+            libobs::obs_leave_graphics();
+        }
+    }
+}
+
+/// Runs `f` inside of the graphics context, used to destroy graphics objects on drop.
+fn with_graphics<F: FnOnce()>(f: F) {
+    let _gfx = ObsGraphicsGuard::enter();
+    f();
+}
+
+/// Returns the number of bytes of an RGBA image with the given size.
+pub(super) fn rgba_len(width: u32, height: u32) -> usize {
+    width as usize * height as usize * 4
+}
+
+/// Copies the rows of a mapped RGBA surface into a tightly packed buffer.
+pub(super) fn copy_rows(data: &[u8], linesize: usize, width: u32, height: u32) -> Vec<u8> {
+    let row_len = width as usize * 4;
+    let mut pixels = Vec::with_capacity(rgba_len(width, height));
+    for row in 0..height as usize {
+        let start = row * linesize;
+        pixels.extend_from_slice(&data[start..start + row_len]);
+    }
+
+    pixels
+}
+
+/// A texture with RGBA pixels, e.g. an image drawn by a custom source or overlay.
+#[derive(Debug)]
+pub struct ObsTexture {
+    texture: Sendable<*mut libobs::gs_texture_t>,
+    width: u32,
+    height: u32,
+}
+
+impl ObsTexture {
+    /// Creates a texture from tightly packed RGBA pixels, `data` must hold exactly `width * height * 4` bytes.
+    ///
+    /// The texture is created as dynamic, so it can be updated with `update_rgba`.
+    pub fn from_rgba(
+        _gfx: &ObsGraphicsGuard,
+        width: u32,
+        height: u32,
+        data: &[u8],
+    ) -> Result<Self, ObsError> {
+        if width == 0 || height == 0 || data.len() != rgba_len(width, height) {
+            return Err(ObsError::InvalidOperation(format!(
+                "Expected {} bytes for a {}x{} RGBA texture, got {}",
+                rgba_len(width, height),
+                width,
+                height,
+                data.len()
+            )));
+        }
+
+        let mut levels = [data.as_ptr()];
+        let texture = unsafe {
+            // Safety: The graphics context is entered and the data has the size of a single RGBA level
+            libobs::gs_texture_create(
+                width,
+                height,
+                libobs::gs_color_format_GS_RGBA,
+                1,
+                levels.as_mut_ptr(),
+                libobs::GS_DYNAMIC,
+            )
+        };
+
+        if texture.is_null() {
+            return Err(ObsError::NullPointer(None).in_function("gs_texture_create"));
+        }
+
+        Ok(Self {
+            texture: Sendable(texture),
+            width,
+            height,
+        })
+    }
+
+    /// Replaces the pixels of the texture, `data` must have the same size the texture was created with.
+    pub fn update_rgba(&self, _gfx: &ObsGraphicsGuard, data: &[u8]) -> Result<(), ObsError> {
+        if data.len() != rgba_len(self.width, self.height) {
+            return Err(ObsError::InvalidOperation(format!(
+                "Expected {} bytes to update the texture, got {}",
+                rgba_len(self.width, self.height),
+                data.len()
+            )));
+        }
+
+        unsafe {
+            // Safety: The graphics context is entered and the texture is valid until dropped
+            libobs::gs_texture_set_image(self.texture.0, data.as_ptr(), self.width * 4, false);
+        }
+
+        Ok(())
+    }
+
+    pub fn size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    /// Returns the raw texture, which may only be used while the graphics context is entered.
+    ///
+    /// # Safety
+    /// The texture must not be used after this `ObsTexture` has been dropped.
+    pub unsafe fn as_ptr(&self) -> *mut libobs::gs_texture_t {
+        self.texture.0
+    }
+}
+
+impl Drop for ObsTexture {
+    fn drop(&mut self) {
+        let texture = self.texture.0;
+        with_graphics(|| unsafe {
+            // Safety: The texture is owned by us and not used afterwards
+            libobs::gs_texture_destroy(texture);
+        });
+    }
+}
+
+/// Renders into a texture instead of the screen, e.g. to compose an image or take a screenshot.
+#[derive(Debug)]
+pub struct ObsTexRender {
+    texrender: Sendable<*mut libobs::gs_texrender_t>,
+    size: Option<(u32, u32)>,
+}
+
+impl ObsTexRender {
+    pub fn new(_gfx: &ObsGraphicsGuard) -> Result<Self, ObsError> {
+        let texrender = unsafe {
+            // Safety: The graphics context is entered
+            libobs::gs_texrender_create(
+                libobs::gs_color_format_GS_RGBA,
+                libobs::gs_zstencil_format_GS_ZS_NONE,
+            )
+        };
+
+        if texrender.is_null() {
+            return Err(ObsError::NullPointer(None).in_function("gs_texrender_create"));
+        }
+
+        Ok(Self {
+            texrender: Sendable(texrender),
+            size: None,
+        })
+    }
+
+    /// Clears the texture to transparent and runs `f` to draw into it.
+    ///
+    /// The `GraphicsContext` uses the given size as display and canvas size, so `(0, 0)` is the top left corner of the texture.
+    /// Everything drawn before is discarded.
+    pub fn render<F: FnOnce(&mut GraphicsContext)>(
+        &mut self,
+        _gfx: &ObsGraphicsGuard,
+        width: u32,
+        height: u32,
+        f: F,
+    ) -> Result<(), ObsError> {
+        self.size = None;
+        let began = unsafe {
+            // Safety: The graphics context is entered and the texrender is valid until dropped
+            libobs::gs_texrender_reset(self.texrender.0);
+            libobs::gs_texrender_begin(self.texrender.0, width, height)
+        };
+
+        if !began {
+            return Err(ObsError::InvalidOperation(format!(
+                "Failed to begin rendering to a {}x{} texture",
+                width, height
+            ))
+            .in_function("gs_texrender_begin"));
+        }
+
+        unsafe {
+            // Safety: We are rendering to the texrender, which is ended below
+            let clear_color = std::mem::zeroed::<libobs::vec4>();
+            libobs::gs_clear(libobs::GS_CLEAR_COLOR, &clear_color, 0.0, 0);
+            libobs::gs_ortho(0.0, width as f32, 0.0, height as f32, -100.0, 100.0);
+            libobs::gs_blend_state_push();
+            libobs::gs_blend_function(
+                libobs::gs_blend_type_GS_BLEND_ONE,
+                libobs::gs_blend_type_GS_BLEND_INVSRCALPHA,
+            );
+        }
+
+        let mut ctx = unsafe {
+            // Safety: The graphics context is entered and the projection matches the size
+            GraphicsContext::new(width, height, width, height)
+        };
+        f(&mut ctx);
+
+        unsafe {
+            // Safety: The blend state has been pushed and the texrender has begun above
+            libobs::gs_blend_state_pop();
+            libobs::gs_texrender_end(self.texrender.0);
+        }
+
+        self.size = Some((width, height));
+        Ok(())
+    }
+
+    /// Returns the size of the last successful `render`, `None` if nothing has been rendered yet.
+    pub fn size(&self) -> Option<(u32, u32)> {
+        self.size
+    }
+
+    /// Returns the texture that has been rendered to, which is owned by the texrender.
+    ///
+    /// # Safety
+    /// The texture must not be used after this `ObsTexRender` has been dropped or rendered to again.
+    pub unsafe fn texture_ptr(&self) -> *mut libobs::gs_texture_t {
+        libobs::gs_texrender_get_texture(self.texrender.0)
+    }
+}
+
+impl Drop for ObsTexRender {
+    fn drop(&mut self) {
+        let texrender = self.texrender.0;
+        with_graphics(|| unsafe {
+            // Safety: The texrender is owned by us and not used afterwards
+            libobs::gs_texrender_destroy(texrender);
+        });
+    }
+}
+
+/// A surface that copies textures from the GPU, so their pixels can be read on the CPU.
+#[derive(Debug)]
+pub struct ObsStageSurface {
+    surface: Sendable<*mut libobs::gs_stagesurf_t>,
+    width: u32,
+    height: u32,
+}
+
+impl ObsStageSurface {
+    pub fn new(_gfx: &ObsGraphicsGuard, width: u32, height: u32) -> Result<Self, ObsError> {
+        let surface = unsafe {
+            // Safety: The graphics context is entered
+            libobs::gs_stagesurface_create(width, height, libobs::gs_color_format_GS_RGBA)
+        };
+
+        if surface.is_null() {
+            return Err(ObsError::NullPointer(None).in_function("gs_stagesurface_create"));
+        }
+
+        Ok(Self {
+            surface: Sendable(surface),
+            width,
+            height,
+        })
+    }
+
+    pub fn size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    /// Copies the last render of the texrender and returns its tightly packed RGBA pixels.
+    ///
+    /// The texrender must have been rendered with the size of this surface.
+    pub fn read_texrender(
+        &mut self,
+        gfx: &ObsGraphicsGuard,
+        texrender: &ObsTexRender,
+    ) -> Result<Vec<u8>, ObsError> {
+        if texrender.size() != Some((self.width, self.height)) {
+            return Err(ObsError::InvalidOperation(format!(
+                "Expected a texrender of {}x{}, got {:?}",
+                self.width,
+                self.height,
+                texrender.size()
+            )));
+        }
+
+        let texture = unsafe {
+            // Safety: The texture is only used in `read`, while the texrender is borrowed
+            texrender.texture_ptr()
+        };
+        self.read(gfx, texture)
+    }
+
+    /// Copies the texture and returns its tightly packed RGBA pixels.
+    ///
+    /// The texture must have the size of this surface.
+    pub fn read_texture(
+        &mut self,
+        gfx: &ObsGraphicsGuard,
+        texture: &ObsTexture,
+    ) -> Result<Vec<u8>, ObsError> {
+        if texture.size() != (self.width, self.height) {
+            return Err(ObsError::InvalidOperation(format!(
+                "Expected a texture of {}x{}, got {:?}",
+                self.width,
+                self.height,
+                texture.size()
+            )));
+        }
+
+        self.read(gfx, texture.texture.0)
+    }
+
+    fn read(
+        &mut self,
+        _gfx: &ObsGraphicsGuard,
+        texture: *mut libobs::gs_texture_t,
+    ) -> Result<Vec<u8>, ObsError> {
+        if texture.is_null() {
+            return Err(ObsError::NullPointer(None).in_function("gs_texrender_get_texture"));
+        }
+
+        unsafe {
+            // Safety: The graphics context is entered and both the texture and the surface are valid
+            libobs::gs_stage_texture(self.surface.0, texture);
+
+            let mut data = std::ptr::null_mut();
+            let mut linesize = 0;
+            if !libobs::gs_stagesurface_map(self.surface.0, &mut data, &mut linesize) {
+                return Err(ObsError::InvalidOperation(
+                    "Failed to map the stage surface".to_string(),
+                )
+                .in_function("gs_stagesurface_map"));
+            }
+
+            // Safety: The mapped memory holds `linesize` bytes for every row until it is unmapped
+            let mapped = std::slice::from_raw_parts(data, linesize as usize * self.height as usize);
+            let pixels = copy_rows(mapped, linesize as usize, self.width, self.height);
+            libobs::gs_stagesurface_unmap(self.surface.0);
+
+            Ok(pixels)
+        }
+    }
+}
+
+impl Drop for ObsStageSurface {
+    fn drop(&mut self) {
+        let surface = self.surface.0;
+        with_graphics(|| unsafe {
+            // Safety: The surface is owned by us and not used afterwards
+            libobs::gs_stagesurface_destroy(surface);
+        });
+    }
+}
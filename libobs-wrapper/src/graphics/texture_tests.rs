@@ -0,0 +1,19 @@
+#[cfg(test)]
+mod tests {
+    use super::super::texture::{copy_rows, rgba_len};
+
+    #[test]
+    fn test_copy_rows_removes_padding() {
+        // 2x2 pixels with a linesize of 12 bytes, so every row has 4 bytes of padding
+        let mut mapped = Vec::new();
+        mapped.extend_from_slice(&[1; 8]);
+        mapped.extend_from_slice(&[0; 4]);
+        mapped.extend_from_slice(&[2; 8]);
+        mapped.extend_from_slice(&[0; 4]);
+
+        let pixels = copy_rows(&mapped, 12, 2, 2);
+        assert_eq!(pixels.len(), rgba_len(2, 2));
+        assert_eq!(&pixels[..8], &[1; 8]);
+        assert_eq!(&pixels[8..], &[2; 8]);
+    }
+}
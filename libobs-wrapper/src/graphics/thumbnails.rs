@@ -0,0 +1,276 @@
+//! Renders sources offscreen at a small size, e.g. for a grid of source previews
+//! without creating a display for every source.
+
+use std::{
+    collections::HashMap,
+    sync::{
+        mpsc::{self, RecvTimeoutError},
+        Arc, Mutex, RwLock,
+    },
+    time::Duration,
+};
+
+use tokio::sync::broadcast;
+
+use crate::{
+    data::object::ObsObjectTrait,
+    graphics::{ObsGraphicsGuard, ObsStageSurface, ObsTexRender},
+    run_with_obs_background,
+    runtime::ObsRuntime,
+    sources::ObsSourceRef,
+    utils::ObsError,
+};
+
+/// Size and rate of the thumbnails of an [`ObsSourceThumbnailer`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ObsThumbnailConfig {
+    /// Thumbnails are scaled down to fit into this size, keeping the aspect ratio of the source
+    pub max_width: u32,
+    pub max_height: u32,
+    /// How often every source is rendered, per second
+    pub fps: f64,
+}
+
+impl Default for ObsThumbnailConfig {
+    fn default() -> Self {
+        Self {
+            max_width: 160,
+            max_height: 90,
+            fps: 2.0,
+        }
+    }
+}
+
+impl ObsThumbnailConfig {
+    pub(super) fn interval(&self) -> Result<Duration, ObsError> {
+        if !(self.fps.is_finite() && self.fps > 0.0) || self.max_width == 0 || self.max_height == 0
+        {
+            return Err(ObsError::InvalidOperation(format!(
+                "Invalid thumbnail config {:?}, the size and fps must be positive",
+                self
+            )));
+        }
+
+        Ok(Duration::from_secs_f64(1.0 / self.fps))
+    }
+}
+
+/// A rendered thumbnail of a source, sent by the [`ObsSourceThumbnailer`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ObsSourceThumbnail {
+    /// The name of the source
+    pub source: String,
+    pub width: u32,
+    pub height: u32,
+    /// Tightly packed RGBA pixels, shared between all subscribers
+    pub pixels: Arc<[u8]>,
+}
+
+/// Texture renderer and staging surface of a source, reused while its thumbnail size stays the same
+type ThumbnailTargets = HashMap<String, (ObsTexRender, ObsStageSurface)>;
+
+/// Periodically renders the added sources into CPU buffers at a small size and sends them
+/// to every subscriber.
+///
+/// Sources with a size of zero, e.g. captures that have not received a frame yet, are skipped.
+/// The added sources are kept alive until they are removed or the thumbnailer is dropped,
+/// which also stops rendering.
+#[derive(Debug)]
+pub struct ObsSourceThumbnailer {
+    sources: Arc<RwLock<Vec<ObsSourceRef>>>,
+    events: broadcast::Sender<ObsSourceThumbnail>,
+    _stop: mpsc::Sender<()>,
+}
+
+impl ObsSourceThumbnailer {
+    /// Starts rendering, sources are added with `Self::add_source`.
+    pub fn new(runtime: ObsRuntime, config: ObsThumbnailConfig) -> Result<Self, ObsError> {
+        let interval = config.interval()?;
+        let (events, _) = broadcast::channel(16);
+        let (stop_tx, stop_rx) = mpsc::channel::<()>();
+        let sources = Arc::new(RwLock::new(Vec::<ObsSourceRef>::new()));
+
+        let sender = events.clone();
+        let thread_sources = sources.clone();
+        std::thread::Builder::new()
+            .name("libobs-wrapper-thumbnails".to_string())
+            .spawn(move || {
+                let targets = Arc::new(Mutex::new(ThumbnailTargets::new()));
+
+                // The loop ends as soon as the thumbnailer is dropped, as the stop sender disconnects.
+                while let Err(RecvTimeoutError::Timeout) = stop_rx.recv_timeout(interval) {
+                    let sources = thread_sources
+                        .read()
+                        .map(|sources| {
+                            sources
+                                .iter()
+                                .map(|source| (source.name().to_string(), source.as_ptr()))
+                                .collect::<Vec<_>>()
+                        })
+                        .unwrap_or_default();
+
+                    if sources.is_empty() || sender.receiver_count() == 0 {
+                        continue;
+                    }
+
+                    let thumbnails =
+                        run_with_obs_background!(runtime, (sources, targets), move |token| {
+                            let mut targets = targets
+                                .lock()
+                                .unwrap_or_else(|poisoned| poisoned.into_inner());
+                            targets.retain(|name, _| sources.iter().any(|(n, _)| n == name));
+
+                            let gfx = ObsGraphicsGuard::enter();
+                            sources
+                                .iter()
+                                .filter_map(|(name, ptr)| {
+                                    let result = render_thumbnail(
+                                        &gfx,
+                                        &mut targets,
+                                        name,
+                                        ptr.get_ptr(token),
+                                        &config,
+                                    );
+
+                                    result.unwrap_or_else(|e| {
+                                        log::debug!(
+                                            "Failed to render thumbnail of {}: {:?}",
+                                            name,
+                                            e
+                                        );
+                                        None
+                                    })
+                                })
+                                .collect::<Vec<_>>()
+                        });
+
+                    let thumbnails = match thumbnails {
+                        Ok(thumbnails) => thumbnails,
+                        Err(e) => {
+                            log::debug!("Stopping thumbnailer, could not render: {:?}", e);
+                            break;
+                        }
+                    };
+
+                    for thumbnail in thumbnails {
+                        // Sending only fails if there are no receivers, which is fine
+                        let _ = sender.send(thumbnail);
+                    }
+                }
+            })
+            .map_err(|e| {
+                ObsError::InvalidOperation(format!("Failed to spawn thumbnail thread: {}", e))
+            })?;
+
+        Ok(Self {
+            sources,
+            events,
+            _stop: stop_tx,
+        })
+    }
+
+    /// Renders thumbnails of the source from now on, adding a source twice does nothing.
+    pub fn add_source(&self, source: &ObsSourceRef) -> Result<(), ObsError> {
+        let mut sources = self.sources.write().map_err(|_| {
+            ObsError::LockError("Failed to acquire write lock on thumbnail sources".to_string())
+        })?;
+
+        let name = source.name();
+        if !sources.iter().any(|s| s.name() == name) {
+            sources.push(source.clone());
+        }
+
+        Ok(())
+    }
+
+    /// Stops rendering thumbnails of the source with the given name. Returns whether it was added before.
+    pub fn remove_source(&self, name: &str) -> Result<bool, ObsError> {
+        let mut sources = self.sources.write().map_err(|_| {
+            ObsError::LockError("Failed to acquire write lock on thumbnail sources".to_string())
+        })?;
+
+        let len = sources.len();
+        sources.retain(|s| s.name() != name);
+        Ok(sources.len() != len)
+    }
+
+    /// Subscribes to the thumbnails of every added source. Nothing is rendered while there are no subscribers.
+    pub fn subscribe(&self) -> broadcast::Receiver<ObsSourceThumbnail> {
+        self.events.subscribe()
+    }
+}
+
+/// Renders the source into its texture renderer and reads it back. Returns `None` if the source has no size.
+fn render_thumbnail(
+    gfx: &ObsGraphicsGuard,
+    targets: &mut ThumbnailTargets,
+    name: &str,
+    source: *mut libobs::obs_source_t,
+    config: &ObsThumbnailConfig,
+) -> Result<Option<ObsSourceThumbnail>, ObsError> {
+    let (source_width, source_height) = unsafe {
+        // Safety: The source is kept alive by the thumbnailer and we are in the runtime
+        (
+            libobs::obs_source_get_width(source),
+            libobs::obs_source_get_height(source),
+        )
+    };
+
+    let Some((width, height)) = fit_size(
+        (source_width, source_height),
+        (config.max_width, config.max_height),
+    ) else {
+        return Ok(None);
+    };
+
+    let reusable = targets
+        .get(name)
+        .is_some_and(|(_, surface)| surface.size() == (width, height));
+    if !reusable {
+        let texrender = ObsTexRender::new(gfx)?;
+        let surface = ObsStageSurface::new(gfx, width, height)?;
+        targets.insert(name.to_string(), (texrender, surface));
+    }
+
+    let Some((texrender, surface)) = targets.get_mut(name) else {
+        return Ok(None);
+    };
+
+    texrender.render(gfx, width, height, |_ctx| unsafe {
+        // Safety: We are rendering to the texrender, the projection covers the whole source
+        libobs::gs_ortho(
+            0.0,
+            source_width as f32,
+            0.0,
+            source_height as f32,
+            -100.0,
+            100.0,
+        );
+        libobs::obs_source_video_render(source);
+    })?;
+
+    let pixels = surface.read_texrender(gfx, texrender)?;
+    Ok(Some(ObsSourceThumbnail {
+        source: name.to_string(),
+        width,
+        height,
+        pixels: pixels.into(),
+    }))
+}
+
+/// Scales the size down to fit into the bounds, keeping the aspect ratio. Sizes that already fit are kept.
+pub(super) fn fit_size(size: (u32, u32), bounds: (u32, u32)) -> Option<(u32, u32)> {
+    let (width, height) = size;
+    if width == 0 || height == 0 {
+        return None;
+    }
+
+    let scale = (bounds.0 as f64 / width as f64)
+        .min(bounds.1 as f64 / height as f64)
+        .min(1.0);
+
+    Some((
+        ((width as f64 * scale).round() as u32).max(1),
+        ((height as f64 * scale).round() as u32).max(1),
+    ))
+}
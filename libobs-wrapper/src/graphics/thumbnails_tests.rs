@@ -0,0 +1,31 @@
+#[cfg(test)]
+mod tests {
+    use super::super::thumbnails::{fit_size, ObsThumbnailConfig};
+
+    #[test]
+    fn test_fit_size_keeps_aspect_ratio() {
+        assert_eq!(fit_size((1920, 1080), (160, 90)), Some((160, 90)));
+        assert_eq!(fit_size((1080, 1920), (160, 90)), Some((51, 90)));
+        assert_eq!(fit_size((4000, 100), (160, 90)), Some((160, 4)));
+        assert_eq!(fit_size((100, 50), (160, 90)), Some((100, 50)));
+        assert_eq!(fit_size((10000, 1), (160, 90)), Some((160, 1)));
+        assert_eq!(fit_size((0, 1080), (160, 90)), None);
+    }
+
+    #[test]
+    fn test_invalid_config() {
+        assert!(ObsThumbnailConfig::default().interval().is_ok());
+
+        let zero_fps = ObsThumbnailConfig {
+            fps: 0.0,
+            ..Default::default()
+        };
+        assert!(zero_fps.interval().is_err());
+
+        let zero_size = ObsThumbnailConfig {
+            max_width: 0,
+            ..Default::default()
+        };
+        assert!(zero_size.interval().is_err());
+    }
+}
@@ -9,6 +9,7 @@
 
 #![cfg_attr(coverage_nightly, feature(coverage_attribute))]
 
+pub mod audio;
 pub mod context;
 pub mod crash_handler;
 pub mod data;
@@ -16,12 +17,21 @@ pub mod display;
 pub mod encoders;
 pub mod enums;
 pub mod logger;
+#[cfg(feature = "remote-control")]
+pub mod remote;
 pub mod runtime;
+#[cfg(all(test, feature = "enable_runtime"))]
+mod runtime_tests;
 pub mod scenes;
 pub mod signals;
 pub mod sources;
+pub mod stats;
+pub mod studio;
 pub mod unsafe_send;
+#[cfg(all(test, feature = "debug_pointers"))]
+mod unsafe_send_tests;
 pub mod utils;
+pub mod view;
 
 pub use libobs as sys;
 
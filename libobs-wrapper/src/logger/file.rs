@@ -1,40 +1,350 @@
-use std::{fs::File, path::Path};
+use std::{
+    fs::{self, File},
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+    sync::{mpsc, Arc, RwLock},
+    thread::{self, JoinHandle},
+};
 
-use chrono::Local;
+use chrono::{Local, NaiveDate};
 
 use super::ObsLogger;
-use crate::utils::ObsError;
+use crate::{enums::ObsLogLevel, utils::ObsError};
+
+const LOG_PREFIX: &str = "obs-";
+const LOG_EXTENSION: &str = "log";
+
+/// When a `FileLogger` created with `FileLogger::from_dir_with_rotation` starts a new log file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FileLogRotation {
+    /// Starts a new file once the current one exceeds this size in bytes
+    pub max_size: Option<u64>,
+    /// Starts a new file when the local date changes
+    pub daily: bool,
+    /// Deletes the oldest log files in the directory, so at most this many are kept (including the current one)
+    pub max_files: Option<usize>,
+}
+
+impl FileLogRotation {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn max_size(mut self, bytes: u64) -> Self {
+        self.max_size = Some(bytes);
+        self
+    }
+
+    pub fn daily(mut self) -> Self {
+        self.daily = true;
+        self
+    }
+
+    pub fn max_files(mut self, count: usize) -> Self {
+        self.max_files = Some(count);
+        self
+    }
+}
+
+/// A handle to a `FileLogger` that can still be used after the logger has been passed to the `StartupInfo`,
+/// e.g. to add the current log file to a bug report.
+#[derive(Debug, Clone)]
+pub struct FileLogHandle {
+    path: Arc<RwLock<PathBuf>>,
+}
+
+impl FileLogHandle {
+    /// Returns the path of the file that is currently written to.
+    pub fn current_path(&self) -> PathBuf {
+        self.path
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    }
+}
+
+enum FileLogMessage {
+    Line(ObsLogLevel, String),
+    Flush(mpsc::Sender<()>),
+}
+
+/// Owns the log file, all writes are done on the thread of the logger.
+struct FileLogWriter {
+    file: BufWriter<File>,
+    /// The directory new files are created in, `None` if the logger writes to a single file
+    dir: Option<PathBuf>,
+    rotation: FileLogRotation,
+    path: Arc<RwLock<PathBuf>>,
+    size: u64,
+    opened_on: NaiveDate,
+    names: LogFileNames,
+}
+
+impl FileLogWriter {
+    fn new(
+        path: PathBuf,
+        dir: Option<PathBuf>,
+        rotation: FileLogRotation,
+        names: LogFileNames,
+    ) -> Result<Self, ObsError> {
+        let file = File::create(&path).map_err(|e| ObsError::IoError(e.into()))?;
+        let writer = Self {
+            file: BufWriter::new(file),
+            dir,
+            rotation,
+            path: Arc::new(RwLock::new(path)),
+            size: 0,
+            opened_on: Local::now().date_naive(),
+            names,
+        };
+
+        writer.remove_old_files();
+        Ok(writer)
+    }
+
+    fn write(&mut self, level: ObsLogLevel, msg: &str) {
+        if self.should_rotate() {
+            if let Err(e) = self.rotate() {
+                eprintln!("Failed to rotate the OBS log file: {}", e);
+            }
+        }
+
+        let line = format!("[{:?}] {}\n", level, msg);
+        match self.file.write_all(line.as_bytes()) {
+            Ok(()) => self.size += line.len() as u64,
+            Err(e) => eprintln!("Failed to write to the OBS log file: {}", e),
+        }
+    }
+
+    fn should_rotate(&self) -> bool {
+        if self.dir.is_none() {
+            return false;
+        }
+
+        let exceeds_size = self
+            .rotation
+            .max_size
+            .is_some_and(|max_size| self.size >= max_size);
+        let new_day = self.rotation.daily && Local::now().date_naive() != self.opened_on;
+
+        exceeds_size || new_day
+    }
+
+    fn rotate(&mut self) -> Result<(), ObsError> {
+        let Some(dir) = &self.dir else {
+            return Ok(());
+        };
+
+        let path = self.names.next(dir);
+        let file = File::create(&path).map_err(|e| ObsError::IoError(e.into()))?;
+
+        let _ = self.file.flush();
+        self.file = BufWriter::new(file);
+        self.size = 0;
+        self.opened_on = Local::now().date_naive();
+        *self
+            .path
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = path;
+
+        self.remove_old_files();
+        Ok(())
+    }
+
+    /// Deletes the oldest log files of the directory until at most `max_files` are left.
+    fn remove_old_files(&self) {
+        let (Some(dir), Some(max_files)) = (&self.dir, self.rotation.max_files) else {
+            return;
+        };
+
+        let Ok(entries) = fs::read_dir(dir) else {
+            return;
+        };
+
+        let current = self.path.read().map(|p| p.clone()).ok();
+        let mut files = entries
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| is_log_file(path) && Some(path) != current.as_ref())
+            .collect::<Vec<_>>();
+
+        // The names start with the time they were created at, followed by a counter if
+        // there are multiple in the same second, so the stems are sorted by age
+        files.sort_by(|a, b| a.file_stem().cmp(&b.file_stem()));
+
+        let to_remove = (files.len() + 1).saturating_sub(max_files.max(1));
+        for path in files.into_iter().take(to_remove) {
+            if let Err(e) = fs::remove_file(&path) {
+                eprintln!(
+                    "Failed to remove old OBS log file {}: {}",
+                    path.display(),
+                    e
+                );
+            }
+        }
+    }
+
+    fn handle(&mut self, message: FileLogMessage) {
+        match message {
+            FileLogMessage::Line(level, msg) => self.write(level, &msg),
+            FileLogMessage::Flush(done) => {
+                let _ = self.file.flush();
+                let _ = done.send(());
+            }
+        }
+    }
+
+    fn run(mut self, receiver: mpsc::Receiver<FileLogMessage>) {
+        while let Ok(message) = receiver.recv() {
+            self.handle(message);
+            while let Ok(message) = receiver.try_recv() {
+                self.handle(message);
+            }
+
+            // Flush once no more lines are queued, so the file is up to date if the process crashes
+            let _ = self.file.flush();
+        }
+    }
+}
+
+fn is_log_file(path: &Path) -> bool {
+    let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+    name.starts_with(LOG_PREFIX) && path.extension().is_some_and(|ext| ext == LOG_EXTENSION)
+}
+
+/// Creates the names of the log files in a directory from the current time.
+#[derive(Debug, Default)]
+struct LogFileNames {
+    last_time: String,
+    counter: u32,
+}
+
+impl LogFileNames {
+    /// Returns a path in the directory formatted by the current time that does not exist yet.
+    ///
+    /// If multiple files are created in the same second, a counter is appended, which keeps them sorted by age
+    /// even if an older file of that second has been removed in the meantime.
+    fn next(&mut self, dir: &Path) -> PathBuf {
+        let time = Local::now().format("%Y-%m-%d-%H-%M-%S").to_string();
+        if time != self.last_time {
+            self.last_time = time;
+            self.counter = 0;
+        }
+
+        loop {
+            let path = if self.counter == 0 {
+                dir.join(format!(
+                    "{}{}.{}",
+                    LOG_PREFIX, self.last_time, LOG_EXTENSION
+                ))
+            } else {
+                dir.join(format!(
+                    "{}{}-{:03}.{}",
+                    LOG_PREFIX, self.last_time, self.counter, LOG_EXTENSION
+                ))
+            };
+
+            self.counter += 1;
+            if !path.exists() {
+                return path;
+            }
+        }
+    }
+}
 
 /// A logger that writes logs to a file
+///
+/// The lines are written on a separate thread, so slow disks don't block libobs.
+/// They are flushed whenever no more lines are queued and when the logger is dropped.
 #[derive(Debug)]
 pub struct FileLogger {
-    file: File,
+    sender: Option<mpsc::Sender<FileLogMessage>>,
+    handle: FileLogHandle,
+    writer: Option<JoinHandle<()>>,
 }
 
 impl FileLogger {
     /// Creates a new `FileLogger`, which writes to a log file formatted by the current time.
-    /// This does not implement any rotary logging or similar, so there'll be a log file for every time your ObsContext is being started up.
+    /// This does not rotate the logs, so there'll be a log file for every time your ObsContext is being started up.
+    /// Use `from_dir_with_rotation` to limit the size and number of log files.
     pub fn from_dir(dir: &Path) -> Result<Self, ObsError> {
-        let current_local = Local::now();
-        let custom_format = current_local.format("%Y-%m-%d-%H-%M-%S");
+        Self::from_dir_with_rotation(dir, FileLogRotation::default())
+    }
 
-        Ok(Self {
-            file: File::create(dir.join(format!("obs-{}.log", custom_format)))
-                .map_err(|e| ObsError::IoError(e.to_string()))?,
-        })
+    /// Creates a new `FileLogger`, which writes to log files formatted by the time they were created at
+    /// and starts a new file according to the given rotation.
+    pub fn from_dir_with_rotation(dir: &Path, rotation: FileLogRotation) -> Result<Self, ObsError> {
+        let mut names = LogFileNames::default();
+        let path = names.next(dir);
+        let writer = FileLogWriter::new(path, Some(dir.to_path_buf()), rotation, names)?;
+        Self::spawn(writer)
     }
 
     /// Creates a new `FileLogger` which will pipe the libobs output directly to the file given.
     pub fn from_file(file: &Path) -> Result<Self, ObsError> {
+        let writer = FileLogWriter::new(
+            file.to_path_buf(),
+            None,
+            FileLogRotation::default(),
+            LogFileNames::default(),
+        )?;
+        Self::spawn(writer)
+    }
+
+    fn spawn(writer: FileLogWriter) -> Result<Self, ObsError> {
+        let handle = FileLogHandle {
+            path: writer.path.clone(),
+        };
+
+        let (sender, receiver) = mpsc::channel();
+        let writer = thread::Builder::new()
+            .name("obs-file-logger".to_string())
+            .spawn(move || writer.run(receiver))
+            .map_err(|e| ObsError::IoError(e.into()))?;
+
         Ok(Self {
-            file: File::create(file).map_err(|e| ObsError::IoError(e.to_string()))?,
+            sender: Some(sender),
+            handle,
+            writer: Some(writer),
         })
     }
+
+    /// Returns a handle to query the current log file, which stays valid after the logger has been moved.
+    pub fn handle(&self) -> FileLogHandle {
+        self.handle.clone()
+    }
+
+    /// Returns the path of the file that is currently written to.
+    pub fn current_path(&self) -> PathBuf {
+        self.handle.current_path()
+    }
+
+    /// Blocks until every line that has been logged so far is written to the file.
+    pub fn flush(&self) {
+        let Some(sender) = &self.sender else {
+            return;
+        };
+
+        let (done, wait) = mpsc::channel();
+        if sender.send(FileLogMessage::Flush(done)).is_ok() {
+            let _ = wait.recv();
+        }
+    }
 }
 
 impl ObsLogger for FileLogger {
-    fn log(&mut self, level: crate::enums::ObsLogLevel, msg: String) {
-        use std::io::Write;
-        writeln!(self.file, "[{:?}] {}", level, msg).unwrap();
+    fn log(&mut self, level: ObsLogLevel, msg: String) {
+        if let Some(sender) = &self.sender {
+            let _ = sender.send(FileLogMessage::Line(level, msg));
+        }
+    }
+}
+
+impl Drop for FileLogger {
+    fn drop(&mut self) {
+        // Closing the channel lets the thread write the remaining lines and exit
+        self.sender.take();
+        if let Some(writer) = self.writer.take() {
+            let _ = writer.join();
+        }
     }
 }
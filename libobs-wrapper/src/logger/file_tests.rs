@@ -0,0 +1,39 @@
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::super::{FileLogRotation, FileLogger, ObsLogger};
+    use crate::enums::ObsLogLevel;
+
+    #[test]
+    fn test_rotation_keeps_max_files() {
+        let dir = std::env::temp_dir().join(format!("libobs-file-logger-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+
+        let rotation = FileLogRotation::new().max_size(16).max_files(2);
+        let logger = FileLogger::from_dir_with_rotation(&dir, rotation).unwrap();
+        let handle = logger.handle();
+        let mut logger: Box<dyn ObsLogger> = Box::new(logger);
+
+        for i in 0..4 {
+            logger.log(ObsLogLevel::Info, format!("line number {}", i));
+        }
+        drop(logger);
+
+        let mut files = fs::read_dir(&dir)
+            .unwrap()
+            .map(|e| e.unwrap().path())
+            .collect::<Vec<_>>();
+        files.sort_by(|a, b| a.file_stem().cmp(&b.file_stem()));
+
+        assert_eq!(files.len(), 2);
+        assert_eq!(files.last(), Some(&handle.current_path()));
+        assert_eq!(
+            fs::read_to_string(handle.current_path()).unwrap(),
+            "[Info] line number 3\n"
+        );
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
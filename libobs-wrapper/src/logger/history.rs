@@ -0,0 +1,83 @@
+use std::collections::VecDeque;
+
+use crate::{enums::ObsLogLevel, utils::ObsError};
+
+/// The number of log lines that are kept by default, see `StartupInfo::set_log_history_size`.
+pub const DEFAULT_LOG_HISTORY_SIZE: usize = 100;
+
+/// A ring buffer of the last log lines of libobs.
+///
+/// Every line has a position that increases by one per logged line, so the lines that were logged
+/// during a call can be retrieved afterwards, as long as they haven't been evicted yet.
+#[derive(Debug)]
+pub(crate) struct ObsLogHistory {
+    lines: VecDeque<(ObsLogLevel, String)>,
+    capacity: usize,
+    /// The position of the next line that is pushed
+    position: u64,
+}
+
+impl ObsLogHistory {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            lines: VecDeque::with_capacity(capacity),
+            capacity,
+            position: 0,
+        }
+    }
+
+    pub fn push(&mut self, level: ObsLogLevel, line: String) {
+        self.position += 1;
+        if self.capacity == 0 {
+            return;
+        }
+
+        if self.lines.len() == self.capacity {
+            self.lines.pop_front();
+        }
+
+        self.lines.push_back((level, line));
+    }
+
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        while self.lines.len() > capacity {
+            self.lines.pop_front();
+        }
+    }
+
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+
+    /// Returns up to `count` of the most recent lines, oldest first.
+    pub fn last(&self, count: usize) -> Vec<(ObsLogLevel, String)> {
+        let skip = self.lines.len().saturating_sub(count);
+        self.lines.iter().skip(skip).cloned().collect()
+    }
+
+    /// Returns the retained lines that were logged at or after the given position, oldest first.
+    pub fn since(&self, position: u64) -> Vec<(ObsLogLevel, String)> {
+        let count = self.position.saturating_sub(position);
+        self.last(count.try_into().unwrap_or(usize::MAX))
+    }
+}
+
+/// Formats the lines like the console logger does, one line per message.
+pub(crate) fn format_lines(lines: &[(ObsLogLevel, String)]) -> String {
+    lines
+        .iter()
+        .map(|(level, line)| format!("[{:?}] {}", level, line))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Attaches the lines libobs logged since the given position (see `log_history_position`) to the error.
+pub(crate) fn attach_logs_since(error: ObsError, position: u64) -> ObsError {
+    let lines = super::log_history_since(position);
+    if lines.is_empty() {
+        return error;
+    }
+
+    error.with_log_excerpt(format_lines(&lines))
+}
@@ -0,0 +1,31 @@
+#[cfg(test)]
+mod tests {
+    use super::super::history::{format_lines, ObsLogHistory};
+    use crate::enums::ObsLogLevel;
+
+    #[test]
+    fn test_history_evicts_and_tracks_position() {
+        let mut history = ObsLogHistory::new(2);
+        history.push(ObsLogLevel::Info, "a".into());
+        let position = history.position();
+        history.push(ObsLogLevel::Warning, "b".into());
+        history.push(ObsLogLevel::Error, "c".into());
+
+        assert_eq!(
+            history.last(5),
+            vec![
+                (ObsLogLevel::Warning, "b".to_string()),
+                (ObsLogLevel::Error, "c".to_string())
+            ]
+        );
+        assert_eq!(history.since(position).len(), 2);
+        assert_eq!(history.since(history.position()), vec![]);
+        assert_eq!(
+            format_lines(&history.last(1)),
+            format!("[{:?}] c", ObsLogLevel::Error)
+        );
+
+        history.set_capacity(1);
+        assert_eq!(history.last(5), vec![(ObsLogLevel::Error, "c".to_string())]);
+    }
+}
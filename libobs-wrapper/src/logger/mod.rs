@@ -3,8 +3,21 @@
 
 mod console;
 mod file;
+#[cfg(test)]
+mod file_tests;
+mod history;
+#[cfg(test)]
+mod history_tests;
+#[cfg(feature = "tracing")]
+mod tracing_logger;
+#[cfg(all(test, feature = "tracing"))]
+mod tracing_logger_tests;
 pub use console::ConsoleLogger;
-pub use file::FileLogger;
+pub use file::{FileLogHandle, FileLogRotation, FileLogger};
+pub(crate) use history::attach_logs_since;
+pub use history::DEFAULT_LOG_HISTORY_SIZE;
+#[cfg(feature = "tracing")]
+pub use tracing_logger::TracingLogger;
 
 use std::{fmt::Debug, os::raw::c_void, sync::Mutex};
 
@@ -13,10 +26,67 @@ use num_traits::FromPrimitive;
 use vsprintf::vsprintf;
 
 use crate::enums::ObsLogLevel;
+use history::ObsLogHistory;
 
 lazy_static! {
     /// We are using this as global variable because there can only be one obs context
     pub(crate) static ref LOGGER: Mutex<Box<dyn ObsLogger>> = Mutex::new(Box::new(ConsoleLogger::new()));
+
+    /// Messages logged by libobs while a capture is running, see `start_log_capture`
+    static ref CAPTURED_LOGS: Mutex<Option<Vec<(ObsLogLevel, String)>>> = Mutex::new(None);
+
+    /// The last lines logged by libobs, which are attached to errors like `ObsError::OutputStartFailure`
+    static ref LOG_HISTORY: Mutex<ObsLogHistory> = Mutex::new(ObsLogHistory::new(DEFAULT_LOG_HISTORY_SIZE));
+}
+
+/// Returns up to `count` of the most recent lines libobs logged, oldest first.
+///
+/// At most `StartupInfo::set_log_history_size` lines are kept.
+pub fn recent_log_lines(count: usize) -> Vec<(ObsLogLevel, String)> {
+    LOG_HISTORY
+        .lock()
+        .map(|history| history.last(count))
+        .unwrap_or_default()
+}
+
+pub(crate) fn set_log_history_size(size: usize) {
+    if let Ok(mut history) = LOG_HISTORY.lock() {
+        history.set_capacity(size);
+    }
+}
+
+/// Returns the position of the next logged line, which can be passed to `log_history_since`
+/// to get the lines that have been logged since.
+pub fn log_history_position() -> u64 {
+    LOG_HISTORY
+        .lock()
+        .map(|history| history.position())
+        .unwrap_or_default()
+}
+
+/// Returns the retained lines that were logged at or after the given position, oldest first.
+pub fn log_history_since(position: u64) -> Vec<(ObsLogLevel, String)> {
+    LOG_HISTORY
+        .lock()
+        .map(|history| history.since(position))
+        .unwrap_or_default()
+}
+
+/// Starts collecting every message libobs logs in addition to passing them to the logger,
+/// until `stop_log_capture` is called.
+pub(crate) fn start_log_capture() {
+    if let Ok(mut captured) = CAPTURED_LOGS.lock() {
+        captured.replace(Vec::new());
+    }
+}
+
+/// Stops collecting log messages and returns the messages that were logged since `start_log_capture`.
+pub(crate) fn stop_log_capture() -> Vec<(ObsLogLevel, String)> {
+    CAPTURED_LOGS
+        .lock()
+        .ok()
+        .and_then(|mut captured| captured.take())
+        .unwrap_or_default()
 }
 
 /// # Safety
@@ -41,9 +111,20 @@ pub(crate) unsafe extern "C" fn extern_log_callback<V>(
         return;
     }
 
+    let formatted = formatted.unwrap();
+    if let Ok(mut captured) = CAPTURED_LOGS.lock() {
+        if let Some(captured) = captured.as_mut() {
+            captured.push((level, formatted.clone()));
+        }
+    }
+
+    if let Ok(mut history) = LOG_HISTORY.lock() {
+        history.push(level, formatted.clone());
+    }
+
     let mut logger = LOGGER.lock().unwrap();
 
-    logger.log(level, formatted.unwrap());
+    logger.log(level, formatted);
 }
 
 pub trait ObsLogger
@@ -0,0 +1,40 @@
+use crate::enums::ObsLogLevel;
+
+use super::ObsLogger;
+
+/// A logger that emits the libobs output as `tracing` events with the target `libobs`.
+///
+/// Most modules prefix their messages with their name, e.g. `[obs-ffmpeg-mux]: ...` or
+/// `[x264 encoder: 'recording'] ...`, this prefix is recorded in the `module` field.
+#[derive(Debug, Default)]
+pub struct TracingLogger {
+    _private: (),
+}
+
+impl TracingLogger {
+    pub fn new() -> Self {
+        Self { _private: () }
+    }
+}
+
+/// Returns the name in the leading `[...]` of a message, without the name of the object.
+pub(super) fn module_of(msg: &str) -> &str {
+    let Some(rest) = msg.strip_prefix('[') else {
+        return "";
+    };
+
+    let end = rest.find([']', ':']).unwrap_or(0);
+    &rest[..end]
+}
+
+impl ObsLogger for TracingLogger {
+    fn log(&mut self, level: ObsLogLevel, msg: String) {
+        let module = module_of(&msg);
+        match level {
+            ObsLogLevel::Error => tracing::error!(target: "libobs", module, "{}", msg),
+            ObsLogLevel::Warning => tracing::warn!(target: "libobs", module, "{}", msg),
+            ObsLogLevel::Info => tracing::info!(target: "libobs", module, "{}", msg),
+            ObsLogLevel::Debug => tracing::debug!(target: "libobs", module, "{}", msg),
+        }
+    }
+}
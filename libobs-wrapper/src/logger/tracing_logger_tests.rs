@@ -0,0 +1,17 @@
+#[cfg(test)]
+mod tests {
+    use super::super::tracing_logger::module_of;
+
+    #[test]
+    fn test_module_of() {
+        assert_eq!(
+            module_of("[obs-ffmpeg-mux]: Output started"),
+            "obs-ffmpeg-mux"
+        );
+        assert_eq!(
+            module_of("[x264 encoder: 'recording'] preset: veryfast"),
+            "x264 encoder"
+        );
+        assert_eq!(module_of("Loading module: obs-x264.so"), "");
+    }
+}
@@ -1,61 +1,108 @@
 #[macro_export]
 macro_rules! run_with_obs_impl {
-    ($runtime:expr, $operation:expr) => {
-        $crate::run_with_obs_impl!($runtime, (), $operation)
+    // Operations taking an argument receive the `ObsThreadToken` of the OBS thread
+    (@operation $(move)? |$token:ident| $body:expr) => {
+        {
+            // Safety: Operations are only ever run on the OBS thread
+            let $token = unsafe { $crate::unsafe_send::ObsThreadToken::new_unchecked() };
+            let inner_obs_run = move || $body;
+            inner_obs_run()
+        }
     };
-    ($runtime:expr, ($($var:ident),* $(,)*), $operation:expr) => {
+    (@operation $operation:expr) => {
+        {
+            let inner_obs_run = {
+                $operation
+            };
+            inner_obs_run()
+        }
+    };
+    (PRIORITY $priority:expr, $runtime:expr, ($($var:ident),* $(,)*), $($operation:tt)+) => {
         {
             $(let $var = $var.clone();)*
-            $runtime.run_with_obs_result(move || {
+            $runtime.run_with_obs_priority($priority, move || {
                 $(let $var = $var;)*
-                let inner_obs_run = {
-                    //$(let $var = $var.0;)*
-                    $operation
-                };
-                return inner_obs_run()
+                $crate::run_with_obs_impl!(@operation $($operation)+)
             })
         }
     };
-    (SEPARATE_THREAD, $runtime:expr, ($($var:ident),* $(,)*), $operation:expr) => {
+    (SEPARATE_THREAD, $runtime:expr, ($($var:ident),* $(,)*), $($operation:tt)+) => {
         {
             $(let $var = $var.clone();)*
 
-            tokio::task::spawn_blocking(move || {
-                $runtime.run_with_obs_result(move || {
-                    $(let $var = $var;)*
-                    let e = {
-                        //$(let $var = $var.0;)*
-                        $operation
-                    };
-                    return e()
-                }).unwrap()
+            if $runtime.is_within_runtime() {
+                // Already on the OBS thread (e.g. in `ObsRuntime::batch`), there may be no tokio
+                // runtime to spawn on and nothing else could run the operation until we return
+                $(let $var = $var;)*
+                $crate::run_with_obs_impl!(@operation $($operation)+);
+            } else {
+                tokio::task::spawn_blocking(move || {
+                    $runtime.run_with_obs_result(move || {
+                        $(let $var = $var;)*
+                        $crate::run_with_obs_impl!(@operation $($operation)+)
+                    }).unwrap()
+                });
+            }
+        }
+    };
+    ($runtime:expr, ($($var:ident),* $(,)*), $($operation:tt)+) => {
+        {
+            $(let $var = $var.clone();)*
+            $runtime.run_with_obs_result(move || {
+                $(let $var = $var;)*
+                $crate::run_with_obs_impl!(@operation $($operation)+)
             })
         }
     };
+    ($runtime:expr, $($operation:tt)+) => {
+        $crate::run_with_obs_impl!($runtime, (), $($operation)+)
+    };
 }
 
+/// Runs the operation on the OBS thread and returns its result.
+///
+/// Variables listed in parentheses are cloned and moved into the operation. Operations that
+/// take an argument receive an `ObsThreadToken`, which is needed to read the pointers of
+/// `SmartPointerSendable`s, e.g. `run_with_obs!(runtime, (source), move |token| unsafe { ... })`.
 #[macro_export]
 macro_rules! run_with_obs {
-    ($runtime:expr, $operation:expr) => {
+    ($runtime:expr, ($($var:ident),* $(,)*), $($operation:tt)+) => {
         {
-            $crate::run_with_obs_impl!($runtime, $operation)
+            $crate::run_with_obs_impl!($runtime, ($($var),*), $($operation)+)
                 .map_err(|e| $crate::utils::ObsError::InvocationError(e.to_string()))
         }
     };
-    ($runtime:expr, ($($var:ident),* $(,)*), $operation:expr) => {
+    ($runtime:expr, $($operation:tt)+) => {
         {
-            $crate::run_with_obs_impl!($runtime, ($($var),*), $operation)
+            $crate::run_with_obs_impl!($runtime, $($operation)+)
                 .map_err(|e| $crate::utils::ObsError::InvocationError(e.to_string()))
         }
     };
 }
 
+/// Same as `run_with_obs!`, but the operation is queued in the background lane of the runtime,
+/// see `ObsTaskPriority::Background`.
 #[macro_export]
-macro_rules! impl_obs_drop {
-    ($struct_name: ident, $operation:expr) => {
-        $crate::impl_obs_drop!($struct_name, (), $operation);
+macro_rules! run_with_obs_background {
+    ($runtime:expr, ($($var:ident),* $(,)*), $($operation:tt)+) => {
+        {
+            $crate::run_with_obs_impl!(
+                PRIORITY $crate::runtime::ObsTaskPriority::Background,
+                $runtime,
+                ($($var),*),
+                $($operation)+
+            )
+            .map_err(|e| $crate::utils::ObsError::InvocationError(e.to_string()))
+        }
     };
-    ($struct_name: ident, ($($var:ident),* $(,)*), $operation:expr) => {
+    ($runtime:expr, $($operation:tt)+) => {
+        $crate::run_with_obs_background!($runtime, (), $($operation)+)
+    };
+}
+
+#[macro_export]
+macro_rules! impl_obs_drop {
+    ($struct_name: ident, ($($var:ident),* $(,)*), $($operation:tt)+) => {
         impl Drop for $struct_name {
             fn drop(&mut self) {
                 log::trace!("Dropping {}...", stringify!($struct_name));
@@ -68,7 +115,7 @@ macro_rules! impl_obs_drop {
                     not(feature="enable_runtime")
                 ))]
                 {
-                    let run_with_obs_result = $crate::run_with_obs!(self.runtime, ($($var),*), $operation);
+                    let run_with_obs_result = $crate::run_with_obs!(self.runtime, ($($var),*), $($operation)+);
                     if std::thread::panicking() {
                         return;
                     }
@@ -84,11 +131,14 @@ macro_rules! impl_obs_drop {
                 ))]
                 {
                     let __runtime = self.runtime.clone();
-                    $crate::run_with_obs_impl!(SEPARATE_THREAD, __runtime, ($($var),*), $operation);
+                    $crate::run_with_obs_impl!(SEPARATE_THREAD, __runtime, ($($var),*), $($operation)+);
                 }
             }
         }
     };
+    ($struct_name: ident, $($operation:tt)+) => {
+        $crate::impl_obs_drop!($struct_name, (), $($operation)+);
+    };
 }
 
 /// Implements PartialEq, Eq and Hash für a struct by comparing the inner pointer given by `as_ptr()`.
@@ -98,7 +148,7 @@ macro_rules! impl_eq_of_ptr {
             fn eq(&self, other: &Self) -> bool {
                 #[allow(unused_imports)]
                 use crate::data::object::ObsObjectTrait;
-                self.as_ptr().get_ptr() == other.as_ptr().get_ptr()
+                self.as_ptr().ptr_eq(&other.as_ptr())
             }
         }
 
@@ -108,7 +158,8 @@ macro_rules! impl_eq_of_ptr {
             fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
                 #[allow(unused_imports)]
                 use crate::data::object::ObsObjectTrait;
-                self.as_ptr().get_ptr().hash(state);
+                // Safety: The pointer is only hashed
+                unsafe { self.as_ptr().get_ptr_unchecked() }.hash(state);
             }
         }
     };
@@ -0,0 +1,333 @@
+//! A WebSocket server speaking the obs-websocket v5 protocol, so existing tooling
+//! (e.g. Stream Deck or Touch Portal) can control applications built on this crate.
+//!
+//! Only requests are supported, events are not sent to the clients.
+//! See [`AVAILABLE_REQUESTS`] for the request types that are implemented.
+//!
+//! ```no_run
+//! use libobs_wrapper::{context::ObsContext, remote::ObsRemoteControl, utils::StartupInfo};
+//!
+//! let context = ObsContext::new(StartupInfo::default()).unwrap();
+//! let remote = ObsRemoteControl::builder(context.clone())
+//!     .password("secret")
+//!     .start()
+//!     .unwrap();
+//!
+//! println!("Listening on {}", remote.local_addr());
+//! ```
+use std::{
+    io::ErrorKind,
+    net::{SocketAddr, TcpListener, TcpStream},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+use tungstenite::{
+    protocol::{frame::coding::CloseCode, CloseFrame},
+    Message, WebSocket,
+};
+
+use crate::{
+    context::ObsContext,
+    data::output::{ObsOutputRef, ObsReplayBufferOutputRef},
+    utils::ObsError,
+};
+
+mod protocol;
+#[cfg(test)]
+mod protocol_tests;
+mod requests;
+#[cfg(test)]
+mod requests_tests;
+
+pub use protocol::{close_code, OBS_WEBSOCKET_VERSION, RPC_VERSION};
+pub use requests::AVAILABLE_REQUESTS;
+
+use protocol::{RemoteSession, SessionReply};
+use requests::RemoteRequestHandler;
+
+/// The port obs-websocket listens on by default
+pub const DEFAULT_REMOTE_CONTROL_PORT: u16 = 4455;
+
+/// How often the threads of the server check whether it has been stopped
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// How long a client may take for the handshake and to receive a message
+const IO_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How long dropping the server waits for the connections to close
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Configures which address the server listens on and which outputs the requests control.
+#[derive(Debug)]
+pub struct ObsRemoteControlBuilder {
+    context: ObsContext,
+    address: SocketAddr,
+    password: Option<String>,
+    record: Option<ObsOutputRef>,
+    stream: Option<ObsOutputRef>,
+    replay_buffer: Option<ObsReplayBufferOutputRef>,
+}
+
+impl ObsRemoteControlBuilder {
+    /// Sets the address to listen on, which is `127.0.0.1:4455` by default.
+    pub fn address(mut self, address: SocketAddr) -> Self {
+        self.address = address;
+        self
+    }
+
+    /// Requires clients to authenticate with this password.
+    pub fn password<T: Into<String>>(mut self, password: T) -> Self {
+        self.password = Some(password.into());
+        self
+    }
+
+    /// The output that is controlled by the `*Record` requests
+    pub fn record_output(mut self, output: ObsOutputRef) -> Self {
+        self.record = Some(output);
+        self
+    }
+
+    /// The output that is controlled by the `*Stream` requests
+    pub fn stream_output(mut self, output: ObsOutputRef) -> Self {
+        self.stream = Some(output);
+        self
+    }
+
+    /// The output that is controlled by the `*ReplayBuffer` requests
+    pub fn replay_buffer(mut self, output: ObsReplayBufferOutputRef) -> Self {
+        self.replay_buffer = Some(output);
+        self
+    }
+
+    /// Binds the address and starts accepting clients on a background thread.
+    pub fn start(self) -> Result<ObsRemoteControl, ObsError> {
+        let listener = TcpListener::bind(self.address).map_err(|e| ObsError::IoError(e.into()))?;
+        let local_addr = listener
+            .local_addr()
+            .map_err(|e| ObsError::IoError(e.into()))?;
+        listener
+            .set_nonblocking(true)
+            .map_err(|e| ObsError::IoError(e.into()))?;
+
+        let server = Arc::new(RemoteServer {
+            handler: Mutex::new(RemoteRequestHandler {
+                context: self.context,
+                record: self.record,
+                stream: self.stream,
+                replay_buffer: self.replay_buffer,
+            }),
+            password: self.password,
+            stopped: AtomicBool::new(false),
+            connections: Mutex::new(Vec::new()),
+        });
+
+        let accept_server = server.clone();
+        let accept_thread = thread::Builder::new()
+            .name("obs-remote-control".to_string())
+            .spawn(move || accept_server.accept(listener))
+            .map_err(|e| ObsError::IoError(e.into()))?;
+
+        Ok(ObsRemoteControl {
+            local_addr,
+            server,
+            accept_thread: Some(accept_thread),
+        })
+    }
+}
+
+/// A running obs-websocket compatible server, which is stopped once dropped.
+///
+/// The server holds a clone of the `ObsContext`, so it has to be dropped before OBS can shut down.
+/// Requests of all clients are handled one after another, except for saving the replay buffer.
+/// Dropping the server waits a few seconds for the clients to disconnect.
+#[derive(Debug)]
+pub struct ObsRemoteControl {
+    local_addr: SocketAddr,
+    server: Arc<RemoteServer>,
+    accept_thread: Option<JoinHandle<()>>,
+}
+
+impl ObsRemoteControl {
+    pub fn builder(context: ObsContext) -> ObsRemoteControlBuilder {
+        ObsRemoteControlBuilder {
+            context,
+            address: SocketAddr::from(([127, 0, 0, 1], DEFAULT_REMOTE_CONTROL_PORT)),
+            password: None,
+            record: None,
+            stream: None,
+            replay_buffer: None,
+        }
+    }
+
+    /// Returns the address the server is listening on, e.g. to get the port if `0` was given.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+}
+
+impl Drop for ObsRemoteControl {
+    fn drop(&mut self) {
+        self.server.stopped.store(true, Ordering::SeqCst);
+        if let Some(accept_thread) = self.accept_thread.take() {
+            let _ = accept_thread.join();
+        }
+
+        let connections = std::mem::take(
+            &mut *self
+                .server
+                .connections
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner()),
+        );
+
+        // Connections notice the stop within `POLL_INTERVAL`, unless they are stuck on a request
+        let deadline = Instant::now() + SHUTDOWN_TIMEOUT;
+        for connection in connections {
+            while !connection.is_finished() && Instant::now() < deadline {
+                thread::sleep(POLL_INTERVAL / 10);
+            }
+
+            if connection.is_finished() {
+                let _ = connection.join();
+            } else {
+                log::warn!(
+                    "Remote control client did not disconnect within {:?}, detaching it",
+                    SHUTDOWN_TIMEOUT
+                );
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+struct RemoteServer {
+    handler: Mutex<RemoteRequestHandler>,
+    password: Option<String>,
+    stopped: AtomicBool,
+    connections: Mutex<Vec<JoinHandle<()>>>,
+}
+
+impl RemoteServer {
+    fn is_stopped(&self) -> bool {
+        self.stopped.load(Ordering::SeqCst)
+    }
+
+    fn accept(self: Arc<Self>, listener: TcpListener) {
+        while !self.is_stopped() {
+            match listener.accept() {
+                Ok((stream, address)) => {
+                    let server = self.clone();
+                    let connection = thread::Builder::new()
+                        .name("obs-remote-control-client".to_string())
+                        .spawn(move || {
+                            if let Err(e) = server.serve(stream) {
+                                log::debug!(
+                                    "Remote control client {} disconnected: {}",
+                                    address,
+                                    e
+                                );
+                            }
+                        });
+
+                    match connection {
+                        Ok(connection) => {
+                            let mut connections = self
+                                .connections
+                                .lock()
+                                .unwrap_or_else(|poisoned| poisoned.into_inner());
+                            connections.retain(|c| !c.is_finished());
+                            connections.push(connection);
+                        }
+                        Err(e) => {
+                            log::error!("Failed to spawn remote control client thread: {}", e)
+                        }
+                    }
+                }
+                Err(e) if e.kind() == ErrorKind::WouldBlock => thread::sleep(POLL_INTERVAL),
+                Err(e) => {
+                    log::error!("Failed to accept remote control client: {}", e);
+                    thread::sleep(POLL_INTERVAL);
+                }
+            }
+        }
+    }
+
+    fn serve(&self, stream: TcpStream) -> Result<(), Box<tungstenite::Error>> {
+        stream
+            .set_nonblocking(false)
+            .map_err(tungstenite::Error::Io)?;
+        // A client that never finishes the handshake or stops reading must not block the thread forever
+        stream
+            .set_read_timeout(Some(IO_TIMEOUT))
+            .map_err(tungstenite::Error::Io)?;
+        stream
+            .set_write_timeout(Some(IO_TIMEOUT))
+            .map_err(tungstenite::Error::Io)?;
+
+        let mut socket = tungstenite::accept(stream).map_err(|e| match e {
+            tungstenite::HandshakeError::Failure(e) => e,
+            tungstenite::HandshakeError::Interrupted(_) => {
+                tungstenite::Error::Io(ErrorKind::WouldBlock.into())
+            }
+        })?;
+
+        // Reads time out regularly, so the thread notices when the server is stopped
+        socket
+            .get_ref()
+            .set_read_timeout(Some(POLL_INTERVAL))
+            .map_err(tungstenite::Error::Io)?;
+
+        let mut session = RemoteSession::new(self.password.clone())
+            .map_err(|e| tungstenite::Error::Io(std::io::Error::other(e.to_string())))?;
+        socket.send(Message::text(session.hello()))?;
+
+        while !self.is_stopped() {
+            let message = match socket.read() {
+                Ok(message) => message,
+                Err(tungstenite::Error::Io(e))
+                    if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) =>
+                {
+                    continue;
+                }
+                Err(tungstenite::Error::ConnectionClosed) => return Ok(()),
+                Err(e) => return Err(e.into()),
+            };
+
+            // Pings are answered and closing handshakes are completed by tungstenite itself
+            let Message::Text(text) = message else {
+                continue;
+            };
+
+            let reply = session.handle(text.as_str(), |request_type, data| {
+                RemoteRequestHandler::handle_shared(&self.handler, request_type, data)
+            });
+
+            match reply {
+                SessionReply::Message(reply) => socket.send(Message::text(reply))?,
+                SessionReply::Close(code, reason) => {
+                    close(&mut socket, code, reason)?;
+                    return Ok(());
+                }
+            }
+        }
+
+        close(&mut socket, 1001, "Server stopped")
+    }
+}
+
+fn close(
+    socket: &mut WebSocket<TcpStream>,
+    code: u16,
+    reason: &'static str,
+) -> Result<(), Box<tungstenite::Error>> {
+    socket.close(Some(CloseFrame {
+        code: CloseCode::from(code),
+        reason: reason.into(),
+    }))?;
+    Ok(socket.flush()?)
+}
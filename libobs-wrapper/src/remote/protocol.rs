@@ -0,0 +1,271 @@
+//! The session handling of the obs-websocket v5 protocol, independent of the socket it runs on.
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+
+use crate::utils::ObsError;
+
+/// The version of obs-websocket whose request schema is implemented
+pub const OBS_WEBSOCKET_VERSION: &str = "5.0.0";
+pub const RPC_VERSION: u64 = 1;
+
+mod op {
+    pub const HELLO: u64 = 0;
+    pub const IDENTIFY: u64 = 1;
+    pub const IDENTIFIED: u64 = 2;
+    pub const REIDENTIFY: u64 = 3;
+    pub const REQUEST: u64 = 6;
+    pub const REQUEST_RESPONSE: u64 = 7;
+    pub const REQUEST_BATCH: u64 = 8;
+    pub const REQUEST_BATCH_RESPONSE: u64 = 9;
+}
+
+/// Close codes of obs-websocket, sent when the client violates the protocol.
+pub mod close_code {
+    pub const MESSAGE_DECODE_ERROR: u16 = 4002;
+    pub const MISSING_DATA_FIELD: u16 = 4003;
+    pub const UNKNOWN_OP_CODE: u16 = 4006;
+    pub const NOT_IDENTIFIED: u16 = 4007;
+    pub const ALREADY_IDENTIFIED: u16 = 4008;
+    pub const AUTHENTICATION_FAILED: u16 = 4009;
+    pub const UNSUPPORTED_RPC_VERSION: u16 = 4010;
+}
+
+/// The `code` of the `requestStatus` of a response.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u16)]
+pub enum RequestStatus {
+    Success = 100,
+    MissingRequestType = 203,
+    UnknownRequestType = 204,
+    MissingRequestField = 300,
+    InvalidRequestFieldType = 401,
+    OutputRunning = 500,
+    OutputNotRunning = 501,
+    OutputPaused = 502,
+    OutputNotPaused = 503,
+    OutputDisabled = 504,
+    ResourceNotFound = 600,
+    RequestProcessingFailed = 702,
+}
+
+/// A request that could not be processed, sent to the client with a failed `requestStatus`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestError {
+    pub status: RequestStatus,
+    pub comment: String,
+}
+
+impl RequestError {
+    pub fn new(status: RequestStatus, comment: impl Into<String>) -> Self {
+        Self {
+            status,
+            comment: comment.into(),
+        }
+    }
+}
+
+impl From<ObsError> for RequestError {
+    fn from(error: ObsError) -> Self {
+        Self::new(RequestStatus::RequestProcessingFailed, error.to_string())
+    }
+}
+
+/// The result of a request, `None` if the request has no response data.
+pub type RequestResult = Result<Option<Value>, RequestError>;
+
+/// What has to be sent to the client after a message has been handled.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SessionReply {
+    Message(String),
+    /// Closes the connection with the code and reason
+    Close(u16, &'static str),
+}
+
+/// Computes the `authentication` string a client has to send for the password, salt and challenge.
+pub fn authentication_string(password: &str, salt: &str, challenge: &str) -> String {
+    let secret = STANDARD.encode(Sha256::digest(format!("{}{}", password, salt)));
+    STANDARD.encode(Sha256::digest(format!("{}{}", secret, challenge)))
+}
+
+/// Returns 32 random bytes from the random number generator of the OS, used as salt and challenge.
+fn random_token() -> Result<String, getrandom::Error> {
+    let mut bytes = [0u8; 32];
+    getrandom::fill(&mut bytes)?;
+
+    Ok(STANDARD.encode(bytes))
+}
+
+/// Compares both strings in constant time, so the authentication can't be guessed by timing the responses.
+pub(super) fn constant_time_eq(a: &str, b: &str) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+
+    a.bytes()
+        .zip(b.bytes())
+        .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+        == 0
+}
+
+fn message(op: u64, d: Value) -> SessionReply {
+    SessionReply::Message(json!({ "op": op, "d": d }).to_string())
+}
+
+/// The state of a single client connection.
+#[derive(Debug)]
+pub struct RemoteSession {
+    /// Salt and challenge, if the server requires authentication
+    pub(super) authentication: Option<(String, String)>,
+    password: Option<String>,
+    identified: bool,
+}
+
+impl RemoteSession {
+    /// Fails if no salt and challenge could be generated for the password.
+    pub fn new(password: Option<String>) -> Result<Self, getrandom::Error> {
+        let authentication = match password {
+            Some(_) => Some((random_token()?, random_token()?)),
+            None => None,
+        };
+
+        Ok(Self {
+            authentication,
+            password,
+            identified: false,
+        })
+    }
+
+    /// The `Hello` message that is sent as soon as the client connected.
+    pub fn hello(&self) -> String {
+        let mut d = json!({
+            "obsWebSocketVersion": OBS_WEBSOCKET_VERSION,
+            "rpcVersion": RPC_VERSION,
+        });
+
+        if let Some((salt, challenge)) = &self.authentication {
+            d["authentication"] = json!({ "challenge": challenge, "salt": salt });
+        }
+
+        json!({ "op": op::HELLO, "d": d }).to_string()
+    }
+
+    /// Handles a text message of the client, requests are passed to `handle_request`.
+    pub fn handle<F>(&mut self, text: &str, mut handle_request: F) -> SessionReply
+    where
+        F: FnMut(&str, &Value) -> RequestResult,
+    {
+        let Ok(parsed) = serde_json::from_str::<Value>(text) else {
+            return SessionReply::Close(close_code::MESSAGE_DECODE_ERROR, "Invalid JSON");
+        };
+
+        let (Some(op), Some(d)) = (parsed["op"].as_u64(), parsed.get("d")) else {
+            return SessionReply::Close(close_code::MISSING_DATA_FIELD, "Missing op or d");
+        };
+
+        match op {
+            op::IDENTIFY => self.identify(d),
+            op::REIDENTIFY if self.identified => message_identified(),
+            op::REQUEST if self.identified => message(
+                op::REQUEST_RESPONSE,
+                request_response(d, &mut handle_request),
+            ),
+            op::REQUEST_BATCH if self.identified => {
+                let halt_on_failure = d["haltOnFailure"].as_bool().unwrap_or(false);
+                let mut results = Vec::new();
+                for request in d["requests"].as_array().into_iter().flatten() {
+                    let response = request_response(request, &mut handle_request);
+                    let failed = response["requestStatus"]["result"] != json!(true);
+                    results.push(response);
+
+                    if failed && halt_on_failure {
+                        break;
+                    }
+                }
+
+                message(
+                    op::REQUEST_BATCH_RESPONSE,
+                    json!({ "requestId": d["requestId"], "results": results }),
+                )
+            }
+            op::REIDENTIFY | op::REQUEST | op::REQUEST_BATCH => {
+                SessionReply::Close(close_code::NOT_IDENTIFIED, "Not identified")
+            }
+            _ => SessionReply::Close(close_code::UNKNOWN_OP_CODE, "Unknown op code"),
+        }
+    }
+
+    fn identify(&mut self, d: &Value) -> SessionReply {
+        if self.identified {
+            return SessionReply::Close(close_code::ALREADY_IDENTIFIED, "Already identified");
+        }
+
+        if d["rpcVersion"].as_u64() != Some(RPC_VERSION) {
+            return SessionReply::Close(
+                close_code::UNSUPPORTED_RPC_VERSION,
+                "Unsupported rpc version",
+            );
+        }
+
+        if let (Some(password), Some((salt, challenge))) = (&self.password, &self.authentication) {
+            let expected = authentication_string(password, salt, challenge);
+            let authentication = d["authentication"].as_str().unwrap_or_default();
+            if !constant_time_eq(authentication, &expected) {
+                return SessionReply::Close(
+                    close_code::AUTHENTICATION_FAILED,
+                    "Authentication failed",
+                );
+            }
+        }
+
+        self.identified = true;
+        message_identified()
+    }
+}
+
+fn message_identified() -> SessionReply {
+    message(
+        op::IDENTIFIED,
+        json!({ "negotiatedRpcVersion": RPC_VERSION }),
+    )
+}
+
+/// Runs a single request and returns the data of its `RequestResponse`.
+fn request_response<F>(request: &Value, handle_request: &mut F) -> Value
+where
+    F: FnMut(&str, &Value) -> RequestResult,
+{
+    let request_type = request["requestType"].as_str().unwrap_or_default();
+    let result = if request_type.is_empty() {
+        Err(RequestError::new(
+            RequestStatus::MissingRequestType,
+            "The request is missing the requestType",
+        ))
+    } else {
+        handle_request(
+            request_type,
+            request.get("requestData").unwrap_or(&Value::Null),
+        )
+    };
+
+    let mut response = json!({
+        "requestType": request_type,
+        "requestId": request["requestId"],
+    });
+
+    match result {
+        Ok(data) => {
+            response["requestStatus"] =
+                json!({ "result": true, "code": RequestStatus::Success as u16 });
+            if let Some(data) = data {
+                response["responseData"] = data;
+            }
+        }
+        Err(e) => {
+            response["requestStatus"] =
+                json!({ "result": false, "code": e.status as u16, "comment": e.comment });
+        }
+    }
+
+    response
+}
@@ -0,0 +1,104 @@
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+
+    use super::super::protocol::{
+        authentication_string, close_code, constant_time_eq, RemoteSession, RequestError,
+        RequestResult, RequestStatus, SessionReply, RPC_VERSION,
+    };
+
+    fn parse(reply: SessionReply) -> Value {
+        match reply {
+            SessionReply::Message(text) => serde_json::from_str(&text).unwrap(),
+            other => panic!("Expected a message, got {:?}", other),
+        }
+    }
+
+    fn handler(request_type: &str, data: &Value) -> RequestResult {
+        match request_type {
+            "GetVersion" => Ok(Some(json!({ "rpcVersion": RPC_VERSION }))),
+            "SetCurrentProgramScene" => match data["sceneName"].as_str() {
+                Some(_) => Ok(None),
+                None => Err(RequestError::new(
+                    RequestStatus::MissingRequestField,
+                    "sceneName",
+                )),
+            },
+            _ => Err(RequestError::new(
+                RequestStatus::UnknownRequestType,
+                request_type,
+            )),
+        }
+    }
+
+    #[test]
+    fn test_session_requires_authentication() {
+        let mut session = RemoteSession::new(Some("secret".to_string())).unwrap();
+        let hello: Value = serde_json::from_str(&session.hello()).unwrap();
+        let salt = hello["d"]["authentication"]["salt"].as_str().unwrap();
+        let challenge = hello["d"]["authentication"]["challenge"].as_str().unwrap();
+
+        let request = json!({ "op": 6, "d": { "requestType": "GetVersion", "requestId": "1" } });
+        assert_eq!(
+            session.handle(&request.to_string(), handler),
+            SessionReply::Close(close_code::NOT_IDENTIFIED, "Not identified")
+        );
+
+        let wrong = json!({ "op": 1, "d": { "rpcVersion": 1, "authentication": "wrong" } });
+        assert_eq!(
+            session.handle(&wrong.to_string(), handler),
+            SessionReply::Close(close_code::AUTHENTICATION_FAILED, "Authentication failed")
+        );
+
+        let auth = authentication_string("secret", salt, challenge);
+        let identify = json!({ "op": 1, "d": { "rpcVersion": 1, "authentication": auth } });
+        let identified = parse(session.handle(&identify.to_string(), handler));
+        assert_eq!(identified["op"], json!(2));
+
+        let response = parse(session.handle(&request.to_string(), handler));
+        assert_eq!(response["op"], json!(7));
+        assert_eq!(response["d"]["requestId"], json!("1"));
+        assert_eq!(response["d"]["requestStatus"]["code"], json!(100));
+        assert_eq!(response["d"]["responseData"]["rpcVersion"], json!(1));
+    }
+
+    #[test]
+    fn test_sessions_use_different_challenges() {
+        let first = RemoteSession::new(Some("secret".to_string())).unwrap();
+        let second = RemoteSession::new(Some("secret".to_string())).unwrap();
+
+        let (salt, challenge) = first.authentication.clone().unwrap();
+        assert_ne!(salt, challenge);
+        assert_ne!(first.authentication, second.authentication);
+    }
+
+    #[test]
+    fn test_constant_time_eq() {
+        assert!(constant_time_eq("abc", "abc"));
+        assert!(!constant_time_eq("abc", "abd"));
+        assert!(!constant_time_eq("abc", "ab"));
+    }
+
+    #[test]
+    fn test_batch_halts_on_failure() {
+        let mut session = RemoteSession::new(None).unwrap();
+        let identify = json!({ "op": 1, "d": { "rpcVersion": 1 } });
+        parse(session.handle(&identify.to_string(), handler));
+
+        let batch = json!({ "op": 8, "d": {
+            "requestId": "batch",
+            "haltOnFailure": true,
+            "requests": [
+                { "requestType": "SetCurrentProgramScene", "requestData": {} },
+                { "requestType": "GetVersion" },
+            ],
+        }});
+
+        let response = parse(session.handle(&batch.to_string(), handler));
+        let results = response["d"]["results"].as_array().unwrap();
+        assert_eq!(response["op"], json!(9));
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0]["requestStatus"]["code"], json!(300));
+        assert_eq!(results[0]["requestStatus"]["comment"], json!("sceneName"));
+    }
+}
@@ -0,0 +1,402 @@
+//! The obs-websocket requests that are supported, implemented with the context, scene and output APIs.
+use std::{ffi::CStr, sync::Mutex, time::Duration};
+
+use serde_json::{json, Value};
+
+use crate::{
+    context::ObsContext,
+    data::object::ObsObjectTrait,
+    data::output::{ObsOutputRef, ObsOutputTrait, ObsReplayBufferOutputRef},
+    run_with_obs,
+    runtime::ObsRuntime,
+    sources::ObsSourceTrait,
+    utils::ObsError,
+};
+
+use super::protocol::{
+    RequestError, RequestResult, RequestStatus, OBS_WEBSOCKET_VERSION, RPC_VERSION,
+};
+
+/// Every request type `RemoteRequestHandler::handle` understands.
+pub const AVAILABLE_REQUESTS: &[&str] = &[
+    "GetVersion",
+    "GetSceneList",
+    "GetCurrentProgramScene",
+    "SetCurrentProgramScene",
+    "GetInputList",
+    "GetInputMute",
+    "SetInputMute",
+    "ToggleInputMute",
+    "GetRecordStatus",
+    "StartRecord",
+    "StopRecord",
+    "ToggleRecord",
+    "PauseRecord",
+    "ResumeRecord",
+    "ToggleRecordPause",
+    "GetStreamStatus",
+    "StartStream",
+    "StopStream",
+    "ToggleStream",
+    "GetReplayBufferStatus",
+    "StartReplayBuffer",
+    "StopReplayBuffer",
+    "ToggleReplayBuffer",
+    "SaveReplayBuffer",
+];
+
+/// How long `SaveReplayBuffer` waits for the replay to be written
+const SAVE_REPLAY_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Handles the requests of every connection of an `ObsRemoteControl`.
+#[derive(Debug)]
+pub(crate) struct RemoteRequestHandler {
+    pub context: ObsContext,
+    pub record: Option<ObsOutputRef>,
+    pub stream: Option<ObsOutputRef>,
+    pub replay_buffer: Option<ObsReplayBufferOutputRef>,
+}
+
+pub(super) fn field_str<'a>(data: &'a Value, field: &str) -> Result<&'a str, RequestError> {
+    data[field]
+        .as_str()
+        .ok_or_else(|| missing_field(data, field))
+}
+
+pub(super) fn field_bool(data: &Value, field: &str) -> Result<bool, RequestError> {
+    data[field]
+        .as_bool()
+        .ok_or_else(|| missing_field(data, field))
+}
+
+fn missing_field(data: &Value, field: &str) -> RequestError {
+    if data.get(field).is_some() {
+        RequestError::new(
+            RequestStatus::InvalidRequestFieldType,
+            format!("The field {} has the wrong type", field),
+        )
+    } else {
+        RequestError::new(
+            RequestStatus::MissingRequestField,
+            format!("The field {} is missing", field),
+        )
+    }
+}
+
+fn configured<'a, T>(output: &'a mut Option<T>, name: &str) -> Result<&'a mut T, RequestError> {
+    output.as_mut().ok_or_else(|| {
+        RequestError::new(
+            RequestStatus::OutputDisabled,
+            format!("No {} output has been configured", name),
+        )
+    })
+}
+
+/// Formats the duration like obs-websocket does, e.g. `01:02:03.456`.
+pub(super) fn format_timecode(duration: Duration) -> String {
+    let millis = duration.as_millis();
+    format!(
+        "{:02}:{:02}:{:02}.{:03}",
+        millis / 3_600_000,
+        millis / 60_000 % 60,
+        millis / 1000 % 60,
+        millis % 1000
+    )
+}
+
+/// Returns the name of the scene on the first output channel. If studio mode put a transition
+/// on the channel, the scene that is currently shown by the transition is returned.
+fn current_program_scene(runtime: &ObsRuntime) -> Result<Option<String>, ObsError> {
+    run_with_obs!(runtime, move || unsafe {
+        // Safety: We are on the runtime thread and release every reference we get
+        let channel_source = libobs::obs_get_output_source(0);
+        if channel_source.is_null() {
+            return None;
+        }
+
+        let scene = if libobs::obs_source_get_type(channel_source)
+            == libobs::obs_source_type_OBS_SOURCE_TYPE_TRANSITION
+        {
+            let active = libobs::obs_transition_get_active_source(channel_source);
+            libobs::obs_source_release(channel_source);
+            active
+        } else {
+            channel_source
+        };
+
+        if scene.is_null() {
+            return None;
+        }
+
+        let name = libobs::obs_source_get_name(scene);
+        let name = (!name.is_null()).then(|| CStr::from_ptr(name).to_string_lossy().into_owned());
+        libobs::obs_source_release(scene);
+
+        name
+    })
+}
+
+fn output_status<T: ObsOutputTrait + ?Sized>(output: &T) -> Result<Value, ObsError> {
+    let duration = output.recording_duration()?;
+    let (total_frames, skipped_frames) = output.frame_counts()?;
+
+    Ok(json!({
+        "outputActive": output.is_active()?,
+        "outputTimecode": format_timecode(duration),
+        "outputDuration": duration.as_millis() as u64,
+        "outputTotalFrames": total_frames,
+        "outputSkippedFrames": skipped_frames,
+    }))
+}
+
+fn start_output<T: ObsOutputTrait + ?Sized>(output: &T) -> Result<(), RequestError> {
+    if output.is_active()? {
+        return Err(RequestError::new(
+            RequestStatus::OutputRunning,
+            "The output is already running",
+        ));
+    }
+
+    Ok(output.start()?)
+}
+
+fn stop_output<T: ObsOutputTrait + ?Sized>(output: &mut T) -> Result<(), RequestError> {
+    if !output.is_active()? {
+        return Err(RequestError::new(
+            RequestStatus::OutputNotRunning,
+            "The output is not running",
+        ));
+    }
+
+    Ok(output.stop()?)
+}
+
+/// Starts or stops the output and returns whether it is active afterwards.
+fn toggle_output<T: ObsOutputTrait + ?Sized>(output: &mut T) -> Result<bool, RequestError> {
+    if output.is_active()? {
+        output.stop()?;
+        Ok(false)
+    } else {
+        output.start()?;
+        Ok(true)
+    }
+}
+
+fn set_record_paused(record: &ObsOutputRef, paused: bool) -> Result<(), RequestError> {
+    if !record.is_active()? {
+        return Err(RequestError::new(
+            RequestStatus::OutputNotRunning,
+            "The recording is not running",
+        ));
+    }
+
+    match (record.is_paused()?, paused) {
+        (true, true) => Err(RequestError::new(
+            RequestStatus::OutputPaused,
+            "The recording is already paused",
+        )),
+        (false, false) => Err(RequestError::new(
+            RequestStatus::OutputNotPaused,
+            "The recording is not paused",
+        )),
+        _ => Ok(record.set_paused(paused)?),
+    }
+}
+
+fn save_replay_buffer(mut replay_buffer: Option<ObsReplayBufferOutputRef>) -> RequestResult {
+    let replay_buffer = configured(&mut replay_buffer, "replay buffer")?;
+    if !replay_buffer.is_active()? {
+        return Err(RequestError::new(
+            RequestStatus::OutputNotRunning,
+            "The replay buffer is not running",
+        ));
+    }
+
+    replay_buffer.save_buffer_timeout(SAVE_REPLAY_TIMEOUT)?;
+    Ok(None)
+}
+
+impl RemoteRequestHandler {
+    /// Handles a request of one of the connections. Requests that wait for OBS to finish,
+    /// like saving the replay buffer, don't hold the lock meanwhile, so other connections
+    /// aren't blocked.
+    pub fn handle_shared(handler: &Mutex<Self>, request_type: &str, data: &Value) -> RequestResult {
+        let lock = || {
+            handler
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+        };
+        match request_type {
+            "SaveReplayBuffer" => {
+                let replay_buffer = lock().replay_buffer.clone();
+                save_replay_buffer(replay_buffer)
+            }
+            _ => lock().handle(request_type, data),
+        }
+    }
+
+    pub fn handle(&mut self, request_type: &str, data: &Value) -> RequestResult {
+        match request_type {
+            "GetVersion" => Ok(Some(json!({
+                "obsVersion": self.context.get_version()?,
+                "obsWebSocketVersion": OBS_WEBSOCKET_VERSION,
+                "rpcVersion": RPC_VERSION,
+                "availableRequests": AVAILABLE_REQUESTS,
+                "supportedImageFormats": [],
+                "platform": std::env::consts::OS,
+                "platformDescription": "",
+            }))),
+            "GetSceneList" => {
+                let scenes = self
+                    .context
+                    .scenes()
+                    .read()
+                    .map_err(|_| {
+                        ObsError::LockError("Failed to acquire read lock on scenes".to_string())
+                    })?
+                    .iter()
+                    .enumerate()
+                    .map(|(index, scene)| {
+                        json!({ "sceneName": scene.name().to_string(), "sceneIndex": index })
+                    })
+                    .collect::<Vec<_>>();
+
+                Ok(Some(json!({
+                    "currentProgramSceneName": current_program_scene(self.context.runtime())?,
+                    "currentPreviewSceneName": null,
+                    "scenes": scenes,
+                })))
+            }
+            "GetCurrentProgramScene" => {
+                let name = current_program_scene(self.context.runtime())?;
+                Ok(Some(
+                    json!({ "currentProgramSceneName": name, "sceneName": name }),
+                ))
+            }
+            "SetCurrentProgramScene" => {
+                let name = field_str(data, "sceneName")?;
+                let scene = self.context.get_scene(name)?.ok_or_else(|| {
+                    RequestError::new(
+                        RequestStatus::ResourceNotFound,
+                        format!("No scene with the name {} exists", name),
+                    )
+                })?;
+
+                scene.set_to_channel(0)?;
+                Ok(None)
+            }
+            "GetInputList" => {
+                let inputs = self
+                    .context
+                    .sources()?
+                    .iter()
+                    .map(|source| {
+                        json!({
+                            "inputName": source.name().to_string(),
+                            "inputKind": source.id().to_string(),
+                        })
+                    })
+                    .collect::<Vec<_>>();
+
+                Ok(Some(json!({ "inputs": inputs })))
+            }
+            "GetInputMute" | "SetInputMute" | "ToggleInputMute" => {
+                let name = field_str(data, "inputName")?;
+                let input = self.context.get_source_by_name(name)?.ok_or_else(|| {
+                    RequestError::new(
+                        RequestStatus::ResourceNotFound,
+                        format!("No input with the name {} exists", name),
+                    )
+                })?;
+
+                match request_type {
+                    "GetInputMute" => Ok(Some(json!({ "inputMuted": input.muted()? }))),
+                    "SetInputMute" => {
+                        input.set_muted(field_bool(data, "inputMuted")?)?;
+                        Ok(None)
+                    }
+                    _ => {
+                        let muted = !input.muted()?;
+                        input.set_muted(muted)?;
+                        Ok(Some(json!({ "inputMuted": muted })))
+                    }
+                }
+            }
+            "GetRecordStatus" => {
+                let record = configured(&mut self.record, "record")?;
+                let mut status = output_status(record)?;
+                status["outputPaused"] = json!(record.is_paused()?);
+
+                Ok(Some(status))
+            }
+            "StartRecord" => {
+                start_output(configured(&mut self.record, "record")?)?;
+                Ok(None)
+            }
+            "StopRecord" => {
+                stop_output(configured(&mut self.record, "record")?)?;
+                Ok(None)
+            }
+            "ToggleRecord" => {
+                let active = toggle_output(configured(&mut self.record, "record")?)?;
+                Ok(Some(json!({ "outputActive": active })))
+            }
+            "PauseRecord" => {
+                set_record_paused(configured(&mut self.record, "record")?, true)?;
+                Ok(None)
+            }
+            "ResumeRecord" => {
+                set_record_paused(configured(&mut self.record, "record")?, false)?;
+                Ok(None)
+            }
+            "ToggleRecordPause" => {
+                let record = configured(&mut self.record, "record")?;
+                let paused = !record.is_paused()?;
+                set_record_paused(record, paused)?;
+
+                Ok(Some(json!({ "outputPaused": paused })))
+            }
+            "GetStreamStatus" => {
+                let stream = configured(&mut self.stream, "stream")?;
+                let mut status = output_status(stream)?;
+                status["outputReconnecting"] = json!(false);
+
+                Ok(Some(status))
+            }
+            "StartStream" => {
+                start_output(configured(&mut self.stream, "stream")?)?;
+                Ok(None)
+            }
+            "StopStream" => {
+                stop_output(configured(&mut self.stream, "stream")?)?;
+                Ok(None)
+            }
+            "ToggleStream" => {
+                let active = toggle_output(configured(&mut self.stream, "stream")?)?;
+                Ok(Some(json!({ "outputActive": active })))
+            }
+            "GetReplayBufferStatus" => {
+                let replay_buffer = configured(&mut self.replay_buffer, "replay buffer")?;
+                Ok(Some(json!({ "outputActive": replay_buffer.is_active()? })))
+            }
+            "StartReplayBuffer" => {
+                start_output(configured(&mut self.replay_buffer, "replay buffer")?)?;
+                Ok(None)
+            }
+            "StopReplayBuffer" => {
+                stop_output(configured(&mut self.replay_buffer, "replay buffer")?)?;
+                Ok(None)
+            }
+            "ToggleReplayBuffer" => {
+                let replay_buffer = configured(&mut self.replay_buffer, "replay buffer")?;
+                let active = toggle_output(replay_buffer)?;
+                Ok(Some(json!({ "outputActive": active })))
+            }
+            "SaveReplayBuffer" => save_replay_buffer(self.replay_buffer.clone()),
+            _ => Err(RequestError::new(
+                RequestStatus::UnknownRequestType,
+                format!("The request type {} is not supported", request_type),
+            )),
+        }
+    }
+}
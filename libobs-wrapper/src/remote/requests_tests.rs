@@ -0,0 +1,33 @@
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use serde_json::json;
+
+    use super::super::{
+        protocol::RequestStatus,
+        requests::{field_bool, field_str, format_timecode},
+    };
+
+    #[test]
+    fn test_format_timecode() {
+        assert_eq!(format_timecode(Duration::ZERO), "00:00:00.000");
+        assert_eq!(
+            format_timecode(Duration::from_millis(3_723_456)),
+            "01:02:03.456"
+        );
+    }
+
+    #[test]
+    fn test_missing_field() {
+        let data = json!({ "sceneName": 3 });
+        assert_eq!(
+            field_str(&data, "sceneName").unwrap_err().status,
+            RequestStatus::InvalidRequestFieldType
+        );
+        assert_eq!(
+            field_bool(&data, "inputMuted").unwrap_err().status,
+            RequestStatus::MissingRequestField
+        );
+    }
+}
@@ -14,6 +14,8 @@
 //!
 //! The runtime locking APIs:
 //! - By default all operations are synchronous
+//! - Operations are queued as render-critical or background, see `ObsTaskPriority`
+//! - Operations started on the OBS thread itself are executed inline
 //!
 //! # Example
 //!
@@ -42,26 +44,156 @@ use std::sync::Arc;
 use std::{ptr, thread};
 
 use crate::context::ObsContext;
-use crate::crash_handler::main_crash_handler;
+use crate::crash_handler::session::{self, SessionPhase};
+use crate::crash_handler::{self, main_crash_handler};
 use crate::enums::{ObsLogLevel, ObsResetVideoStatus};
-use crate::logger::{extern_log_callback, internal_log_global, LOGGER};
+use crate::logger::{extern_log_callback, internal_log_global, set_log_history_size, LOGGER};
 #[cfg(target_os = "linux")]
 use crate::run_with_obs;
 use crate::utils::initialization::{platform_specific_setup, PlatformSpecificGuard};
-use crate::utils::{ObsError, ObsModules, ObsString};
+use crate::utils::{
+    find_third_party_modules, loaded_module_names, loaded_third_party_modules, ObsError,
+    ObsModules, ObsString,
+};
 use crate::{context::OBS_THREAD_ID, utils::StartupInfo};
 
 #[cfg(feature = "enable_runtime")]
 use crate::unsafe_send::Sendable;
+#[cfg(feature = "enable_runtime")]
+use std::collections::VecDeque;
 use std::fmt::Debug;
 #[cfg(feature = "enable_runtime")]
 use std::sync::atomic::{AtomicUsize, Ordering};
 #[cfg(feature = "enable_runtime")]
-use std::sync::mpsc::{channel, Sender};
-#[cfg(feature = "enable_runtime")]
-use std::sync::Mutex;
+use std::sync::{Condvar, Mutex};
 #[cfg(feature = "enable_runtime")]
 use std::thread::JoinHandle;
+use std::time::Duration;
+#[cfg(feature = "enable_runtime")]
+use std::{panic::Location, time::Instant};
+
+#[cfg(feature = "enable_runtime")]
+lazy_static::lazy_static! {
+    /// The OBS thread of a context that has been dropped without waiting for its shutdown
+    /// (`no_blocking_drops`). A new context waits for it before starting up.
+    static ref SHUTTING_DOWN_THREAD: Mutex<Option<JoinHandle<()>>> = Mutex::new(None);
+}
+
+/// What the runtime does if the OBS thread has not shut down within the timeout of the
+/// `ObsShutdownWatchdog`. The stuck operation is logged as an error in any case.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ObsShutdownTimeoutAction {
+    /// Keeps waiting for the OBS thread to shut down.
+    #[default]
+    Wait,
+    /// Stops waiting and leaves the OBS thread running, so the drop of the context returns.
+    /// OBS is never shut down in this case, so no new context can be created in this process.
+    Detach,
+    /// Aborts the process.
+    Abort,
+}
+
+/// Limits how long dropping the `ObsContext` waits for the OBS thread to shut down,
+/// e.g. if a plugin deadlocks. Set with `StartupInfo::set_shutdown_watchdog`.
+///
+/// This is only used with the `enable_runtime` feature and if drops are blocking.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ObsShutdownWatchdog {
+    timeout: Duration,
+    action: ObsShutdownTimeoutAction,
+}
+
+impl ObsShutdownWatchdog {
+    pub fn new(timeout: Duration, action: ObsShutdownTimeoutAction) -> Self {
+        Self { timeout, action }
+    }
+
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    pub fn action(&self) -> ObsShutdownTimeoutAction {
+        self.action
+    }
+}
+
+/// Where an operation that is executed on the OBS thread comes from,
+/// so the shutdown watchdog can report which one is stuck.
+#[cfg(feature = "enable_runtime")]
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ObsCommandInfo {
+    /// The type name of the closure, which contains the function it was created in
+    closure: &'static str,
+    location: &'static Location<'static>,
+}
+
+#[cfg(feature = "enable_runtime")]
+impl ObsCommandInfo {
+    #[track_caller]
+    pub(crate) fn new<F>() -> Self {
+        Self {
+            closure: any::type_name::<F>(),
+            location: Location::caller(),
+        }
+    }
+}
+
+#[cfg(feature = "enable_runtime")]
+impl std::fmt::Display for ObsCommandInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} ({}:{})",
+            self.closure,
+            self.location.file(),
+            self.location.line()
+        )
+    }
+}
+
+/// Wraps the operation in a span (with the span of the caller as parent) that records how long the
+/// operation waited in the queue and how long it was executed on the OBS thread.
+#[cfg(all(feature = "enable_runtime", feature = "tracing"))]
+#[track_caller]
+fn traced<F, T>(priority: ObsTaskPriority, operation: F) -> impl FnOnce() -> T + Send + 'static
+where
+    F: FnOnce() -> T + Send + 'static,
+{
+    let info = ObsCommandInfo::new::<F>();
+    let span = tracing::trace_span!(
+        target: "libobs_wrapper::runtime",
+        "run_with_obs",
+        closure = info.closure,
+        location = %info.location,
+        ?priority,
+        queue_wait_us = tracing::field::Empty,
+        execution_us = tracing::field::Empty,
+    );
+    let queued_at = Instant::now();
+
+    move || {
+        span.record("queue_wait_us", queued_at.elapsed().as_micros() as u64);
+        let _entered = span.enter();
+
+        let started_at = Instant::now();
+        let result = operation();
+        span.record("execution_us", started_at.elapsed().as_micros() as u64);
+
+        result
+    }
+}
+
+/// The operation the OBS thread is currently executing and when it was started
+#[cfg(feature = "enable_runtime")]
+pub(crate) type RunningCommand = Arc<Mutex<Option<(ObsCommandInfo, Instant)>>>;
+
+#[cfg(feature = "enable_runtime")]
+pub(crate) fn set_running_command(running: &RunningCommand, command: Option<ObsCommandInfo>) {
+    *running
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) =
+        command.map(|command| (command, Instant::now()));
+}
 
 /// Command type for operations to perform on the OBS thread
 #[cfg(feature = "enable_runtime")]
@@ -70,11 +202,131 @@ enum ObsCommand {
     Execute(
         Box<dyn FnOnce() -> Box<dyn any::Any + Send> + Send>,
         Option<oneshot::Sender<Box<dyn any::Any + Send>>>,
+        ObsCommandInfo,
     ),
     /// Signal the OBS thread to terminate
     Terminate,
 }
 
+/// The lane an operation is queued in for the OBS thread.
+///
+/// Queued render-critical operations are always executed before background operations,
+/// so long running queries do not delay operations the video and outputs are waiting for.
+/// Operations of the same lane are executed in the order they were queued.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ObsTaskPriority {
+    /// Used by `run_with_obs!`, e.g. for updating sources or starting outputs.
+    #[default]
+    RenderCritical,
+    /// Operations that may take long and are not time-critical, e.g. property queries.
+    Background,
+}
+
+#[cfg(feature = "enable_runtime")]
+#[derive(Default)]
+struct ObsCommandLanes {
+    render_critical: VecDeque<ObsCommand>,
+    background: VecDeque<ObsCommand>,
+    /// Set once the OBS thread has exited, no more commands are accepted then
+    closed: bool,
+}
+
+/// The commands for the OBS thread, with one lane per `ObsTaskPriority`.
+#[cfg(feature = "enable_runtime")]
+#[derive(Default)]
+pub(crate) struct ObsCommandQueue {
+    lanes: Mutex<ObsCommandLanes>,
+    available: Condvar,
+}
+
+#[cfg(feature = "enable_runtime")]
+impl ObsCommandQueue {
+    fn lock(&self) -> std::sync::MutexGuard<'_, ObsCommandLanes> {
+        self.lanes
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn push(&self, priority: ObsTaskPriority, command: ObsCommand) -> Result<(), ObsError> {
+        let mut lanes = self.lock();
+        if lanes.closed {
+            return Err(ObsError::RuntimeChannelError(
+                "Failed to send command to OBS thread".to_string(),
+            ));
+        }
+
+        match priority {
+            ObsTaskPriority::RenderCritical => lanes.render_critical.push_back(command),
+            ObsTaskPriority::Background => lanes.background.push_back(command),
+        }
+        drop(lanes);
+
+        self.available.notify_one();
+        Ok(())
+    }
+
+    /// Blocks until a command is available, render-critical commands first.
+    /// Returns `None` once the queue has been closed.
+    fn pop(&self) -> Option<ObsCommand> {
+        let mut lanes = self.lock();
+        loop {
+            if lanes.closed {
+                return None;
+            }
+
+            if let Some(command) = lanes.render_critical.pop_front() {
+                return Some(command);
+            }
+
+            if let Some(command) = lanes.background.pop_front() {
+                return Some(command);
+            }
+
+            lanes = self
+                .available
+                .wait(lanes)
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+        }
+    }
+
+    /// Stops accepting commands and drops the queued ones, so their callers get an error
+    /// instead of waiting forever.
+    fn close(&self) {
+        let mut lanes = self.lock();
+        lanes.closed = true;
+        let pending = (
+            std::mem::take(&mut lanes.render_critical),
+            std::mem::take(&mut lanes.background),
+        );
+        drop(lanes);
+
+        drop(pending);
+    }
+}
+
+#[cfg(feature = "enable_runtime")]
+impl Debug for ObsCommandQueue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let lanes = self.lock();
+        f.debug_struct("ObsCommandQueue")
+            .field("render_critical", &lanes.render_critical.len())
+            .field("background", &lanes.background.len())
+            .field("closed", &lanes.closed)
+            .finish()
+    }
+}
+
+/// Closes the command queue once the OBS thread exits, even if it panicked.
+#[cfg(feature = "enable_runtime")]
+pub(crate) struct ObsCommandQueueCloser(pub(crate) Arc<ObsCommandQueue>);
+
+#[cfg(feature = "enable_runtime")]
+impl Drop for ObsCommandQueueCloser {
+    fn drop(&mut self) {
+        self.0.close();
+    }
+}
+
 /// Core runtime that manages the OBS thread
 ///
 /// This struct represents the runtime environment for OBS operations.
@@ -93,11 +345,11 @@ enum ObsCommand {
 #[derive(Debug, Clone)]
 pub struct ObsRuntime {
     #[cfg(feature = "enable_runtime")]
-    command_sender: Arc<Sender<ObsCommand>>,
+    pub(crate) commands: Arc<ObsCommandQueue>,
     #[cfg(feature = "enable_runtime")]
-    queued_commands: Arc<AtomicUsize>,
-    thread_id: std::thread::ThreadId,
-    _guard: Arc<_ObsRuntimeGuard>,
+    pub(crate) queued_commands: Arc<AtomicUsize>,
+    pub(crate) thread_id: std::thread::ThreadId,
+    pub(crate) _guard: Arc<_ObsRuntimeGuard>,
 
     #[cfg(not(feature = "enable_runtime"))]
     _platform_specific: Option<Rc<PlatformSpecificGuard>>,
@@ -164,6 +416,22 @@ impl ObsRuntime {
     pub(crate) fn startup(
         mut options: StartupInfo,
     ) -> Result<(ObsRuntime, ObsModules, StartupInfo), ObsError> {
+        // A previous context may still be shutting down, which has to finish first
+        #[cfg(feature = "enable_runtime")]
+        {
+            let previous = SHUTTING_DOWN_THREAD
+                .lock()
+                .map_err(|_e| ObsError::MutexFailure)?
+                .take();
+
+            if let Some(previous) = previous {
+                log::trace!("Waiting for the previous OBS thread to shut down");
+                if previous.join().is_err() {
+                    log::error!("The previous OBS thread panicked during shutdown");
+                }
+            }
+        }
+
         // Check if OBS is already running on another thread
         let obs_id = OBS_THREAD_ID.lock().map_err(|_e| ObsError::MutexFailure)?;
         if obs_id.is_some() {
@@ -182,6 +450,8 @@ impl ObsRuntime {
     /// Creates the OBS thread and performs core initialization.
     #[cfg(not(feature = "enable_runtime"))]
     fn init(info: StartupInfo) -> Result<(ObsRuntime, ObsModules, StartupInfo), ObsError> {
+        #[cfg(feature = "debug_pointers")]
+        crate::unsafe_send::debug::mark_obs_thread();
         let (startup, mut modules, platform_specific) = unsafe { Self::initialize_inner(info)? };
 
         let runtime = Self {
@@ -201,15 +471,22 @@ impl ObsRuntime {
     fn init(info: StartupInfo) -> Result<(ObsRuntime, ObsModules, StartupInfo), ObsError> {
         static RUNTIME_THREAD_NAME: &str = "libobs-wrapper-obs-runtime";
 
-        let (command_sender, command_receiver) = channel();
+        let commands = Arc::new(ObsCommandQueue::default());
         let (init_tx, init_rx) = oneshot::channel();
         let queued_commands = Arc::new(AtomicUsize::new(0));
+        let running_command: RunningCommand = Arc::new(Mutex::new(None));
+        let shutdown_watchdog = info.shutdown_watchdog;
 
+        let commands_clone = commands.clone();
         let queued_commands_clone = queued_commands.clone();
+        let running_command_clone = running_command.clone();
         let handle = std::thread::Builder::new()
             .name(RUNTIME_THREAD_NAME.to_string())
             .spawn(move || {
                 log::trace!("Starting OBS thread");
+                #[cfg(feature = "debug_pointers")]
+                crate::unsafe_send::debug::mark_obs_thread();
+                let _closer = ObsCommandQueueCloser(commands_clone.clone());
 
                 let res = unsafe {
                     // Safety: This is safe to can because we are in the dedicated OBS thread.
@@ -225,21 +502,19 @@ impl ObsRuntime {
                             log::error!("Failed to send initialization signal: {:?}", err);
                         }
 
-                        // Process commands until termination
-                        while let Ok(command) = command_receiver.recv() {
-                            match command {
-                                ObsCommand::Execute(func, result_sender) => {
-                                    let result = func();
-                                    if let Some(result_sender) = result_sender {
-                                        let _ = result_sender.send(result);
-                                    }
-
-                                    queued_commands_clone.fetch_sub(1, Ordering::SeqCst);
-                                }
-                                ObsCommand::Terminate => break,
-                            }
-                        }
-
+                        Self::process_commands(
+                            &commands_clone,
+                            &queued_commands_clone,
+                            &running_command_clone,
+                        );
+
+                        set_running_command(
+                            &running_command_clone,
+                            Some(ObsCommandInfo {
+                                closure: "ObsRuntime::shutdown_inner",
+                                location: Location::caller(),
+                            }),
+                        );
                         let r = unsafe {
                             // Safety: We are in the OBS thread, so it's safe to call shutdown here.
                             Self::shutdown_inner()
@@ -267,14 +542,15 @@ impl ObsRuntime {
 
         let thread_id = handle.thread().id();
         let handle = Arc::new(Mutex::new(Some(handle)));
-        let command_sender = Arc::new(command_sender);
         let runtime = Self {
-            command_sender: command_sender.clone(),
+            commands: commands.clone(),
             thread_id,
             queued_commands,
             _guard: Arc::new(_ObsRuntimeGuard {
                 handle,
-                command_sender,
+                commands,
+                running_command,
+                shutdown_watchdog,
             }),
         };
 
@@ -282,6 +558,38 @@ impl ObsRuntime {
         Ok((runtime, m.0, info))
     }
 
+    /// Executes the commands of the queue on the current thread until the runtime terminates.
+    #[cfg(feature = "enable_runtime")]
+    pub(crate) fn process_commands(
+        commands: &ObsCommandQueue,
+        queued_commands: &AtomicUsize,
+        running_command: &RunningCommand,
+    ) {
+        while let Some(command) = commands.pop() {
+            match command {
+                ObsCommand::Execute(func, result_sender, command_info) => {
+                    set_running_command(running_command, Some(command_info));
+                    let result = func();
+                    set_running_command(running_command, None);
+
+                    if let Some(result_sender) = result_sender {
+                        let _ = result_sender.send(result);
+                    }
+
+                    queued_commands.fetch_sub(1, Ordering::SeqCst);
+                }
+                ObsCommand::Terminate => break,
+            }
+        }
+    }
+
+    /// Returns whether the current thread is the OBS thread. Operations that are run from the
+    /// OBS thread itself (e.g. `run_with_obs!` in another `run_with_obs!` closure) are
+    /// executed inline instead of being queued, which would deadlock.
+    pub fn is_within_runtime(&self) -> bool {
+        thread::current().id() == self.thread_id
+    }
+
     /// Executes an operation on the OBS thread *without* blocking. This method *will not wait* for the result.
     ///
     /// # Parameters
@@ -305,13 +613,30 @@ impl ObsRuntime {
     /// }
     /// ```
     #[cfg(feature = "enable_runtime")]
+    #[track_caller]
     pub fn run_with_obs_no_block<F>(&self, operation: F) -> Result<(), ObsError>
     where
         F: FnOnce() + Send + 'static,
     {
-        let is_within_runtime = std::thread::current().id() == self.thread_id;
+        self.run_with_obs_no_block_priority(ObsTaskPriority::RenderCritical, operation)
+    }
+
+    /// Like `run_with_obs_no_block`, but queues the operation in the lane of the given priority.
+    /// Note that operations of different lanes may be executed in a different order than they were queued.
+    #[cfg(feature = "enable_runtime")]
+    #[track_caller]
+    pub fn run_with_obs_no_block_priority<F>(
+        &self,
+        priority: ObsTaskPriority,
+        operation: F,
+    ) -> Result<(), ObsError>
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        #[cfg(feature = "tracing")]
+        let operation = traced(priority, operation);
 
-        if is_within_runtime {
+        if self.is_within_runtime() {
             operation();
 
             return Ok(());
@@ -327,13 +652,10 @@ impl ObsRuntime {
             Box::new(())
         };
 
-        self.command_sender
-            .send(ObsCommand::Execute(Box::new(wrapper), None))
-            .map_err(|_| {
-                ObsError::RuntimeChannelError("Failed to send command to OBS thread".to_string())
-            })?;
-
-        Ok(())
+        self.commands.push(
+            priority,
+            ObsCommand::Execute(Box::new(wrapper), None, ObsCommandInfo::new::<F>()),
+        )
     }
 
     /// Because you have the `enable_runtime` feature disabled, this is a no-op function and will still block. This is just so the run_with_obs macro works.
@@ -346,20 +668,45 @@ impl ObsRuntime {
         self.run_with_obs_result(operation)
     }
 
+    /// Same as `run_with_obs_no_block`, the priority is ignored because there is no queue without the runtime.
+    #[cfg(not(feature = "enable_runtime"))]
+    pub fn run_with_obs_no_block_priority<F>(
+        &self,
+        _priority: ObsTaskPriority,
+        operation: F,
+    ) -> Result<(), ObsError>
+    where
+        F: FnOnce() + 'static,
+    {
+        self.run_with_obs_no_block(operation)
+    }
+
     /// No-Op function, as you have the runtime disabled. This is just so the run_with_obs macro still works
     #[cfg(not(feature = "enable_runtime"))]
     pub fn run_with_obs_result<F, T>(&self, operation: F) -> Result<T, ObsError>
     where
         F: FnOnce() -> T,
     {
-        let is_within_runtime = std::thread::current().id() == self.thread_id;
-        if !is_within_runtime {
+        if !self.is_within_runtime() {
             return Err(ObsError::RuntimeOutsideThread);
         }
 
         Ok(operation())
     }
 
+    /// Same as `run_with_obs_result`, the priority is ignored because there is no queue without the runtime.
+    #[cfg(not(feature = "enable_runtime"))]
+    pub fn run_with_obs_priority<F, T>(
+        &self,
+        _priority: ObsTaskPriority,
+        operation: F,
+    ) -> Result<T, ObsError>
+    where
+        F: FnOnce() -> T,
+    {
+        self.run_with_obs_result(operation)
+    }
+
     /// Executes an operation on the OBS thread, waits for the call to finish and returns a result
     ///
     /// This method dispatches a task to the OBS thread and blocks and waits for the result.
@@ -387,13 +734,33 @@ impl ObsRuntime {
     /// }
     /// ```
     #[cfg(feature = "enable_runtime")]
+    #[track_caller]
     pub fn run_with_obs_result<F, T>(&self, operation: F) -> Result<T, ObsError>
     where
         F: FnOnce() -> T + Send + 'static,
         T: Send + 'static,
     {
-        let is_within_runtime = std::thread::current().id() == self.thread_id;
-        if is_within_runtime {
+        self.run_with_obs_priority(ObsTaskPriority::RenderCritical, operation)
+    }
+
+    /// Like `run_with_obs_result`, but queues the operation in the lane of the given priority.
+    /// Use `ObsTaskPriority::Background` for long running operations, so they do not delay
+    /// render-critical ones. This is what `run_with_obs_background!` does.
+    #[cfg(feature = "enable_runtime")]
+    #[track_caller]
+    pub fn run_with_obs_priority<F, T>(
+        &self,
+        priority: ObsTaskPriority,
+        operation: F,
+    ) -> Result<T, ObsError>
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        #[cfg(feature = "tracing")]
+        let operation = traced(priority, operation);
+
+        if self.is_within_runtime() {
             let result = operation();
             return Ok(result);
         }
@@ -410,11 +777,10 @@ impl ObsRuntime {
             log::warn!("More than 50 queued commands. Try to batch them together.");
         }
 
-        self.command_sender
-            .send(ObsCommand::Execute(Box::new(wrapper), Some(tx)))
-            .map_err(|_| {
-                ObsError::RuntimeChannelError("Failed to send command to OBS thread".to_string())
-            })?;
+        self.commands.push(
+            priority,
+            ObsCommand::Execute(Box::new(wrapper), Some(tx), ObsCommandInfo::new::<F>()),
+        )?;
 
         let result = rx.recv().map_err(|_| {
             ObsError::RuntimeChannelError("OBS thread dropped the response channel".to_string())
@@ -430,6 +796,57 @@ impl ObsRuntime {
         Ok(res)
     }
 
+    /// Executes multiple wrapper operations with a single dispatch onto the OBS thread.
+    ///
+    /// Every wrapper method called in the closure runs inline on the OBS thread instead of
+    /// being dispatched on its own, so e.g. building a scene with many sources only waits
+    /// for the OBS thread once. Nested `run_with_obs!` calls and batches are run inline as
+    /// well. The closure blocks the OBS thread while it runs, so it must not wait for other
+    /// threads that use the runtime, as they can't be served until the closure returns.
+    ///
+    /// If the closure panics, the panic is caught on the OBS thread and returned as
+    /// `ObsError::InvocationError`, the runtime stays usable.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use libobs_wrapper::runtime::ObsRuntime;
+    /// use libobs_wrapper::scenes::{ObsSceneRef, SceneItemExtSceneTrait};
+    /// use libobs_wrapper::utils::{ObsError, SourceInfo};
+    ///
+    /// fn example(runtime: &ObsRuntime, mut scene: ObsSceneRef, sources: Vec<SourceInfo>) -> Result<(), ObsError> {
+    ///     runtime.batch(move |_runtime| {
+    ///         // Each source is created and added directly on the OBS thread
+    ///         for info in sources {
+    ///             scene.add_and_create_source(info)?;
+    ///         }
+    ///
+    ///         Ok(())
+    ///     })?
+    /// }
+    /// ```
+    #[cfg(feature = "enable_runtime")]
+    #[track_caller]
+    pub fn batch<F, T>(&self, operation: F) -> Result<T, ObsError>
+    where
+        F: FnOnce(&ObsRuntime) -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        // The caller keeps its runtime alive, so this clone is never the last one
+        // and can be dropped on the OBS thread.
+        let runtime = self.clone();
+        self.run_with_obs_result(move || run_batch(operation, &runtime))?
+    }
+
+    /// Executes the closure directly, as all operations already run on the current thread without the runtime.
+    #[cfg(not(feature = "enable_runtime"))]
+    pub fn batch<F, T>(&self, operation: F) -> Result<T, ObsError>
+    where
+        F: FnOnce(&ObsRuntime) -> T,
+    {
+        self.run_with_obs_result(|| run_batch(operation, self))?
+    }
+
     /// Initializes the libobs context and prepares it for recording.
     ///
     /// This method handles core OBS initialization including:
@@ -450,7 +867,7 @@ impl ObsRuntime {
     #[allow(unknown_lints)]
     #[allow(ensure_obs_call_in_runtime)]
     unsafe fn initialize_inner(
-        mut info: StartupInfo,
+        info: StartupInfo,
     ) -> Result<(StartupInfo, ObsModules, Option<Rc<PlatformSpecificGuard>>), ObsError> {
         // Checks that there are no other threads
         // using libobs using a static Mutex.
@@ -479,16 +896,51 @@ impl ObsRuntime {
         // other thread can use libobs while
         // the current thread is using it.
         *mutex_value = Some(thread::current().id());
+        drop(mutex_value);
+
+        let res = unsafe {
+            // Safety: We are in the OBS thread and own the OBS thread id.
+            Self::startup_obs(info)
+        };
+
+        if res.is_err() {
+            // Release everything a partial startup has set up, so that
+            // a new context can be created in this process afterwards.
+            unsafe {
+                // Safety: We are in the OBS thread, so it's safe to call this here.
+                Self::release_global_state();
+            }
+        }
+
+        res
+    }
 
+    /// Performs the actual startup of libobs, see `initialize_inner`.
+    ///
+    /// # Safety
+    /// Must only be called by `initialize_inner` on the OBS thread.
+    #[allow(unknown_lints)]
+    #[allow(ensure_obs_call_in_runtime)]
+    unsafe fn startup_obs(
+        mut info: StartupInfo,
+    ) -> Result<(StartupInfo, ObsModules, Option<Rc<PlatformSpecificGuard>>), ObsError> {
         // Install DLL blocklist hook here
 
+        // The crash handler can only be initialized once per process,
+        // contexts created after a shutdown reuse it.
         #[cfg(windows)]
-        unsafe {
-            // Safety: We are in the OBS thread, so it's safe to call this here.
-            libobs::obs_init_win32_crash_handler();
+        {
+            static WIN32_CRASH_HANDLER: std::sync::Once = std::sync::Once::new();
+            WIN32_CRASH_HANDLER.call_once(|| unsafe {
+                // Safety: We are in the OBS thread, so it's safe to call this here.
+                libobs::obs_init_win32_crash_handler();
+            });
         }
 
         // Set logger, load debug privileges and crash handler
+        if let Some(crash_handler) = info.crash_handler.take() {
+            crash_handler::set_crash_handler(crash_handler);
+        }
         unsafe {
             // Safety: We are in the OBS thread, so it's safe to call this here.
             libobs::base_set_crash_handler(Some(main_crash_handler), std::ptr::null_mut());
@@ -507,14 +959,24 @@ impl ObsRuntime {
 
         *log_callback = info.logger.take().expect("Logger can never be null");
         drop(log_callback);
+        set_log_history_size(info.log_history_size);
+
+        // The locale is used by modules to translate their
+        // properties and by sources formatting localized text.
+        let locale_str = ObsString::new(info.locale.as_str());
+
+        // The profiler has to run before startup, so the startup itself is measured as well
+        #[cfg(feature = "profiler")]
+        let name_store = unsafe {
+            // Safety: We are in the OBS thread, before obs_startup
+            crate::stats::start_profiler()
+        };
+        #[cfg(not(feature = "profiler"))]
+        let name_store = ptr::null_mut();
 
-        // Locale will only be used internally by
-        // libobs for logging purposes, making it
-        // unnecessary to support other languages.
-        let locale_str = ObsString::new("en-US");
         let startup_status = unsafe {
             // Safety: All pointers are valid here.
-            libobs::obs_startup(locale_str.as_ptr().0, ptr::null(), ptr::null_mut())
+            libobs::obs_startup(locale_str.as_ptr().0, ptr::null(), name_store)
         };
 
         let version = unsafe { libobs::obs_get_version_string() };
@@ -552,11 +1014,38 @@ impl ObsRuntime {
             return Err(ObsError::Failure);
         }
 
+        let disabled_after_crash = match info.previous_crashed_session() {
+            Some(crashed) if info.safe_mode => crashed.modules,
+            Some(crashed) => {
+                internal_log_global(
+                    ObsLogLevel::Warning,
+                    format!(
+                        "The previous session did not shut down cleanly (active third-party modules: {}), consider enabling safe mode",
+                        crashed.modules.join(", ")
+                    ),
+                );
+                Vec::new()
+            }
+            None => Vec::new(),
+        };
+
         let mut obs_modules = unsafe {
             // Safety: This is running in the OBS thread, so it's safe to call this here.
-            ObsModules::add_paths(&info.startup_paths)
+            ObsModules::add_paths(&info, &disabled_after_crash)
         };
 
+        let loading_modules = find_third_party_modules(&info)
+            .into_iter()
+            .filter(|m| !disabled_after_crash.contains(m))
+            .collect::<Vec<_>>();
+        if let Err(e) = session::write_session(
+            &info.session_state_path,
+            SessionPhase::LoadingModules,
+            &loading_modules,
+        ) {
+            log::warn!("Failed to write session state: {}", e);
+        }
+
         // Note that audio is meant to only be reset
         // once. See the link below for information.
         //
@@ -566,6 +1055,8 @@ impl ObsRuntime {
             libobs::obs_reset_audio2(info.obs_audio_info.as_ptr().0);
         }
 
+        info.obs_video_info.validate()?;
+
         // Resets the video context. Note that this
         // is similar to Self::reset_video, but it
         // does not call that function because the
@@ -596,6 +1087,24 @@ impl ObsRuntime {
             obs_modules.load_modules();
         }
 
+        let loaded_modules = unsafe {
+            // Safety: This is running in the OBS thread and the modules have been loaded.
+            loaded_third_party_modules()
+        };
+        if let Err(e) = session::write_session(
+            &info.session_state_path,
+            SessionPhase::Running,
+            &loaded_modules,
+        ) {
+            log::warn!("Failed to write session state: {}", e);
+        }
+
+        let all_modules = unsafe {
+            // Safety: This is running in the OBS thread and the modules have been loaded.
+            loaded_module_names()
+        };
+        crash_handler::report::set_startup_state(version_str, all_modules);
+
         internal_log_global(
             ObsLogLevel::Info,
             "==== Startup complete ===============================================".to_string(),
@@ -674,15 +1183,43 @@ impl ObsRuntime {
 
         unsafe {
             // Safety: We are in the OBS thread, so it's safe to call this here.
+            Self::release_global_state();
+        }
+
+        Ok(())
+    }
+
+    /// Resets the process-wide state of libobs and this crate, so
+    /// a new context can be created after the current one has been shut down.
+    ///
+    /// # Safety
+    /// Must be called on the OBS thread, after `obs_shutdown` or after a failed startup.
+    #[allow(unknown_lints)]
+    #[allow(ensure_obs_call_in_runtime)]
+    unsafe fn release_global_state() {
+        unsafe {
+            // Safety: We are in the OBS thread. If the startup failed after
+            // obs_startup, the core has to be shut down before it can be started again.
+            if libobs::obs_initialized() {
+                libobs::obs_shutdown();
+            }
+
+            #[cfg(feature = "profiler")]
+            crate::stats::stop_profiler();
+
             // Clean up log and crash handler
             libobs::base_set_crash_handler(None, std::ptr::null_mut());
             libobs::base_set_log_handler(None, std::ptr::null_mut());
         }
 
-        let mut mutex_value = OBS_THREAD_ID.lock().map_err(|_e| ObsError::MutexFailure)?;
+        session::end_session();
+        crash_handler::report::clear_state();
 
-        *mutex_value = None;
-        Ok(())
+        // Releasing the thread id even if the lock has been poisoned, otherwise no
+        // context could ever be created again in this process.
+        *OBS_THREAD_ID
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = None;
     }
 
     #[cfg(target_os = "linux")]
@@ -714,18 +1251,88 @@ impl ObsRuntime {
 pub struct _ObsRuntimeGuard {
     /// Thread handle for the OBS thread
     #[cfg(feature = "enable_runtime")]
-    #[cfg_attr(
-        all(
-            feature = "no_blocking_drops",
-            not(feature = "__test_environment"),
-            not(test)
-        ),
-        allow(dead_code)
-    )]
-    handle: Arc<Mutex<Option<JoinHandle<()>>>>,
-    /// Sender channel for the OBS thread
+    pub(crate) handle: Arc<Mutex<Option<JoinHandle<()>>>>,
+    /// Command queue of the OBS thread
     #[cfg(feature = "enable_runtime")]
-    command_sender: Arc<Sender<ObsCommand>>,
+    pub(crate) commands: Arc<ObsCommandQueue>,
+    #[cfg(feature = "enable_runtime")]
+    pub(crate) running_command: RunningCommand,
+    #[cfg(feature = "enable_runtime")]
+    pub(crate) shutdown_watchdog: Option<ObsShutdownWatchdog>,
+}
+
+/// Waits for the OBS thread to finish. If a watchdog is given and the thread has not finished
+/// within its timeout, the operation it is stuck in is logged and the action of the watchdog is taken.
+///
+/// Returns `false` if the thread has been detached.
+#[cfg(feature = "enable_runtime")]
+pub(crate) fn join_obs_thread(
+    handle: JoinHandle<()>,
+    watchdog: Option<ObsShutdownWatchdog>,
+    running_command: &RunningCommand,
+) -> bool {
+    if let Some(watchdog) = watchdog {
+        let deadline = Instant::now() + watchdog.timeout;
+        while !handle.is_finished() && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(10));
+        }
+
+        if !handle.is_finished() {
+            let stuck = match *running_command
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+            {
+                Some((command, started)) => {
+                    format!("stuck in {} for {:?}", command, started.elapsed())
+                }
+                None => "no operation is running".to_string(),
+            };
+
+            let msg = format!(
+                "OBS thread did not shut down within {:?} ({}), {}",
+                watchdog.timeout,
+                stuck,
+                match watchdog.action {
+                    ObsShutdownTimeoutAction::Wait => "waiting for it",
+                    ObsShutdownTimeoutAction::Detach => "detaching it",
+                    ObsShutdownTimeoutAction::Abort => "aborting",
+                }
+            );
+
+            // The hung thread might hold the logger, so it is not waited for
+            match LOGGER.try_lock() {
+                Ok(mut logger) => logger.log(ObsLogLevel::Error, msg),
+                Err(_) => log::error!("{}", msg),
+            }
+
+            match watchdog.action {
+                ObsShutdownTimeoutAction::Wait => {}
+                ObsShutdownTimeoutAction::Detach => return false,
+                ObsShutdownTimeoutAction::Abort => std::process::abort(),
+            }
+        }
+    }
+
+    handle.join().expect("Failed to join OBS thread");
+    true
+}
+
+/// Runs the closure of `ObsRuntime::batch`. A panic would unwind the OBS thread and take the
+/// runtime down with it, so it is turned into an error instead.
+fn run_batch<F, T>(operation: F, runtime: &ObsRuntime) -> Result<T, ObsError>
+where
+    F: FnOnce(&ObsRuntime) -> T,
+{
+    std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| operation(runtime))).map_err(|e| {
+        let message = e
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| e.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "Unknown panic".to_string());
+
+        log::error!("Batch on the OBS thread panicked: {}", message);
+        ObsError::InvocationError(format!("The batch panicked: {}", message))
+    })
 }
 
 #[cfg(feature = "enable_runtime")]
@@ -735,7 +1342,10 @@ impl Drop for _ObsRuntimeGuard {
         log::trace!("Dropping ObsRuntime and shutting down OBS thread");
         // Theoretically the queued_commands is zero and should be increased but because
         // we are shutting down, we don't care about that.
-        let r = self.command_sender.send(ObsCommand::Terminate);
+        // Queued in the background lane, so every command that has been queued before is executed first.
+        let r = self
+            .commands
+            .push(ObsTaskPriority::Background, ObsCommand::Terminate);
 
         if thread::panicking() {
             return;
@@ -759,7 +1369,20 @@ impl Drop for _ObsRuntimeGuard {
                 let mut handle = handle.unwrap();
                 let handle = handle.take().expect("Handle can not be empty");
 
-                handle.join().expect("Failed to join OBS thread");
+                join_obs_thread(handle, self.shutdown_watchdog, &self.running_command);
+            }
+        }
+
+        #[cfg(all(
+            feature = "no_blocking_drops",
+            not(test),
+            not(feature = "__test_environment")
+        ))]
+        {
+            // Not waiting here, but the next context has to wait for the shutdown to finish
+            let handle = self.handle.lock().ok().and_then(|mut h| h.take());
+            if let (Some(handle), Ok(mut shutting_down)) = (handle, SHUTTING_DOWN_THREAD.lock()) {
+                *shutting_down = Some(handle);
             }
         }
     }
@@ -0,0 +1,162 @@
+#[cfg(all(test, feature = "enable_runtime"))]
+mod tests {
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::mpsc::channel;
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+    use std::time::Duration;
+
+    use super::super::runtime::{
+        join_obs_thread, set_running_command, ObsCommandInfo, ObsCommandQueue,
+        ObsCommandQueueCloser, ObsRuntime, ObsShutdownTimeoutAction, ObsShutdownWatchdog,
+        ObsTaskPriority, RunningCommand, _ObsRuntimeGuard,
+    };
+    use crate::utils::ObsError;
+
+    /// A runtime whose thread only executes the queued commands, without starting OBS.
+    fn runtime_without_obs() -> ObsRuntime {
+        let commands = Arc::new(ObsCommandQueue::default());
+        let queued_commands = Arc::new(AtomicUsize::new(0));
+        let running_command: RunningCommand = Arc::new(Mutex::new(None));
+
+        let (thread_commands, thread_queued, thread_running) = (
+            commands.clone(),
+            queued_commands.clone(),
+            running_command.clone(),
+        );
+        let handle = thread::spawn(move || {
+            let _closer = ObsCommandQueueCloser(thread_commands.clone());
+            ObsRuntime::process_commands(&thread_commands, &thread_queued, &thread_running);
+        });
+
+        ObsRuntime {
+            commands: commands.clone(),
+            queued_commands,
+            thread_id: handle.thread().id(),
+            _guard: Arc::new(_ObsRuntimeGuard {
+                handle: Arc::new(Mutex::new(Some(handle))),
+                commands,
+                running_command,
+                shutdown_watchdog: None,
+            }),
+        }
+    }
+
+    #[test]
+    fn nested_calls_run_inline() {
+        let runtime = runtime_without_obs();
+        assert!(!runtime.is_within_runtime());
+
+        let inner = runtime.clone();
+        let result = runtime
+            .run_with_obs_result(move || {
+                assert!(inner.is_within_runtime());
+
+                let nested = inner.clone();
+                inner
+                    .run_with_obs_priority(ObsTaskPriority::Background, move || {
+                        nested.run_with_obs_result(|| 42).unwrap()
+                    })
+                    .unwrap()
+            })
+            .unwrap();
+
+        assert_eq!(result, 42);
+    }
+
+    #[test]
+    fn batch_runs_nested_calls_inline() {
+        let runtime = runtime_without_obs();
+
+        let result = runtime
+            .batch(|runtime| {
+                assert!(runtime.is_within_runtime());
+
+                let nested = runtime.clone();
+                let value = runtime.run_with_obs_result(|| 40).unwrap();
+                let batched = runtime
+                    .batch(move |inner| {
+                        assert!(inner.is_within_runtime());
+                        nested.run_with_obs_result(|| 2).unwrap()
+                    })
+                    .unwrap();
+
+                value + batched
+            })
+            .unwrap();
+
+        assert_eq!(result, 42);
+    }
+
+    #[test]
+    fn batch_panic_keeps_runtime_alive() {
+        let runtime = runtime_without_obs();
+
+        let result = runtime.batch(|_runtime| -> i32 { panic!("batch failed") });
+        match result {
+            Err(ObsError::InvocationError(message)) => assert!(message.contains("batch failed")),
+            other => panic!("Expected InvocationError, got {:?}", other),
+        }
+
+        // The OBS thread survived the panic and still executes commands
+        assert_eq!(runtime.run_with_obs_result(|| 42).unwrap(), 42);
+        assert_eq!(runtime.batch(|_runtime| 42).unwrap(), 42);
+    }
+
+    #[test]
+    fn render_critical_commands_run_first() {
+        let runtime = runtime_without_obs();
+        let order = Arc::new(Mutex::new(Vec::new()));
+
+        // Keeps the OBS thread busy until all commands are queued
+        let (release_tx, release_rx) = channel::<()>();
+        runtime
+            .run_with_obs_no_block(move || {
+                let _ = release_rx.recv();
+            })
+            .unwrap();
+
+        for (priority, name) in [
+            (ObsTaskPriority::Background, "background 1"),
+            (ObsTaskPriority::RenderCritical, "critical 1"),
+            (ObsTaskPriority::Background, "background 2"),
+            (ObsTaskPriority::RenderCritical, "critical 2"),
+        ] {
+            let order = order.clone();
+            runtime
+                .run_with_obs_no_block_priority(priority, move || {
+                    order.lock().unwrap().push(name);
+                })
+                .unwrap();
+        }
+
+        release_tx.send(()).unwrap();
+        runtime
+            .run_with_obs_priority(ObsTaskPriority::Background, || ())
+            .unwrap();
+
+        assert_eq!(
+            *order.lock().unwrap(),
+            ["critical 1", "critical 2", "background 1", "background 2"]
+        );
+    }
+
+    #[test]
+    fn watchdog_detaches_hung_thread() {
+        let (tx, rx) = channel::<()>();
+        let handle = thread::spawn(move || {
+            let _ = rx.recv();
+        });
+
+        let running: RunningCommand = Arc::new(Mutex::new(None));
+        set_running_command(&running, Some(ObsCommandInfo::new::<fn()>()));
+
+        let watchdog =
+            ObsShutdownWatchdog::new(Duration::from_millis(50), ObsShutdownTimeoutAction::Detach);
+        assert!(!join_obs_thread(handle, Some(watchdog), &running));
+        drop(tx);
+
+        let handle = thread::spawn(|| {});
+        assert!(join_obs_thread(handle, Some(watchdog), &running));
+    }
+}
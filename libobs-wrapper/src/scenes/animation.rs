@@ -0,0 +1,383 @@
+//! Animates the position, scale and rotation of scene items on the graphics tick of OBS,
+//! e.g. for overlays that slide in or zoom, without updating the scene item from another thread every frame.
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Condvar, Mutex,
+    },
+    time::Duration,
+};
+
+use libobs::obs_scene_item;
+
+use crate::{
+    graphics::{ObsTickCallback, Vec2},
+    run_with_obs,
+    scenes::SceneItemTrait,
+    unsafe_send::{Sendable, SmartPointerSendable},
+    utils::ObsError,
+};
+
+/// The curve an animation follows from its start to its end.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ObsEasing {
+    Linear,
+    /// Starts slowly and accelerates (cubic)
+    EaseIn,
+    /// Starts fast and decelerates (cubic), suited for elements that enter the screen
+    #[default]
+    EaseOut,
+    /// Accelerates until the middle and decelerates afterwards (cubic)
+    EaseInOut,
+    /// Like `EaseOut`, but overshoots the target slightly before settling
+    EaseOutBack,
+}
+
+impl ObsEasing {
+    /// Maps the progress of the animation (from 0 to 1) to the progress of the animated values.
+    pub fn apply(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            ObsEasing::Linear => t,
+            ObsEasing::EaseIn => t * t * t,
+            ObsEasing::EaseOut => 1.0 - (1.0 - t).powi(3),
+            ObsEasing::EaseInOut => {
+                if t < 0.5 {
+                    4.0 * t * t * t
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+                }
+            }
+            ObsEasing::EaseOutBack => {
+                const OVERSHOOT: f32 = 1.70158;
+                1.0 + (OVERSHOOT + 1.0) * (t - 1.0).powi(3) + OVERSHOOT * (t - 1.0).powi(2)
+            }
+        }
+    }
+}
+
+fn lerp(from: f32, to: f32, k: f32) -> f32 {
+    from + (to - from) * k
+}
+
+pub(super) fn lerp_vec2(from: Vec2, to: Vec2, k: f32) -> Vec2 {
+    Vec2::new(lerp(*from.x(), *to.x(), k), lerp(*from.y(), *to.y(), k))
+}
+
+/// The values of a scene item that are animated, `None` if the value is not changed.
+#[derive(Debug, Clone, Copy, Default)]
+struct AnimatedValues {
+    position: Option<Vec2>,
+    scale: Option<Vec2>,
+    rotation: Option<f32>,
+}
+
+/// Whether an animation has finished, shared between the tick callback and the handles.
+#[derive(Debug, Default)]
+struct AnimationStatus {
+    finished: Mutex<bool>,
+    changed: Condvar,
+    cancelled: AtomicBool,
+}
+
+impl AnimationStatus {
+    fn finish(&self) {
+        *self
+            .finished
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = true;
+        self.changed.notify_all();
+    }
+
+    fn is_finished(&self) -> bool {
+        *self
+            .finished
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+struct AnimationState {
+    scene_item: Sendable<*mut obs_scene_item>,
+    from: AnimatedValues,
+    to: AnimatedValues,
+    duration: f32,
+    elapsed: f32,
+    easing: ObsEasing,
+    status: Arc<AnimationStatus>,
+}
+
+impl AnimationState {
+    /// # Safety
+    /// The scene item must be valid.
+    unsafe fn apply(&self, k: f32) {
+        let scene_item = self.scene_item.0;
+        if let (Some(from), Some(to)) = (self.from.position, self.to.position) {
+            let position: libobs::vec2 = lerp_vec2(from, to, k).into();
+            libobs::obs_sceneitem_set_pos(scene_item, &position);
+        }
+
+        if let (Some(from), Some(to)) = (self.from.scale, self.to.scale) {
+            let scale: libobs::vec2 = lerp_vec2(from, to, k).into();
+            libobs::obs_sceneitem_set_scale(scene_item, &scale);
+        }
+
+        if let (Some(from), Some(to)) = (self.from.rotation, self.to.rotation) {
+            libobs::obs_sceneitem_set_rot(scene_item, lerp(from, to, k));
+        }
+    }
+
+    /// Advances the animation, called on every graphics tick.
+    ///
+    /// # Safety
+    /// The scene item must be valid.
+    unsafe fn tick(&mut self, seconds: f32) {
+        if self.status.is_finished() {
+            return;
+        }
+
+        if self.status.cancelled.load(Ordering::SeqCst) {
+            self.status.finish();
+            return;
+        }
+
+        self.elapsed += seconds;
+        let progress = if self.duration > 0.0 {
+            self.elapsed / self.duration
+        } else {
+            1.0
+        };
+
+        // The end values are set exactly, regardless of the easing
+        let k = if progress >= 1.0 {
+            1.0
+        } else {
+            self.easing.apply(progress)
+        };
+
+        self.apply(k);
+
+        if progress >= 1.0 {
+            self.status.finish();
+        }
+    }
+}
+
+/// Builds an animation of the position, scale and/or rotation of a scene item.
+///
+/// The values are interpolated on every graphics tick of OBS, so the animation is as smooth as the
+/// video output and does not depend on the thread of the caller. Values without a start value are
+/// animated from the current value of the scene item.
+///
+/// ```no_run
+/// use std::time::Duration;
+/// use libobs_wrapper::graphics::Vec2;
+/// use libobs_wrapper::scenes::{ObsEasing, SceneItemTrait, TransformAnimator};
+/// use libobs_wrapper::utils::ObsError;
+///
+/// fn slide_in(item: &impl SceneItemTrait) -> Result<(), ObsError> {
+///     let animation = TransformAnimator::new(Duration::from_millis(400))
+///         .from_position(Vec2::new(-500.0, 50.0))
+///         .position(Vec2::new(50.0, 50.0))
+///         .easing(ObsEasing::EaseOutBack)
+///         .start(item)?;
+///
+///     animation.wait();
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct TransformAnimator {
+    duration: Duration,
+    easing: ObsEasing,
+    from: AnimatedValues,
+    to: AnimatedValues,
+}
+
+impl TransformAnimator {
+    pub fn new(duration: Duration) -> Self {
+        Self {
+            duration,
+            easing: ObsEasing::default(),
+            from: AnimatedValues::default(),
+            to: AnimatedValues::default(),
+        }
+    }
+
+    /// Sets the easing curve, defaults to `ObsEasing::EaseOut`.
+    pub fn easing(mut self, easing: ObsEasing) -> Self {
+        self.easing = easing;
+        self
+    }
+
+    /// Animates the position to the given value.
+    pub fn position(mut self, position: Vec2) -> Self {
+        self.to.position = Some(position);
+        self
+    }
+
+    /// Starts the position animation at the given value instead of the current position.
+    pub fn from_position(mut self, position: Vec2) -> Self {
+        self.from.position = Some(position);
+        self
+    }
+
+    /// Animates the scale to the given value.
+    pub fn scale(mut self, scale: Vec2) -> Self {
+        self.to.scale = Some(scale);
+        self
+    }
+
+    /// Starts the scale animation at the given value instead of the current scale.
+    pub fn from_scale(mut self, scale: Vec2) -> Self {
+        self.from.scale = Some(scale);
+        self
+    }
+
+    /// Animates the rotation to the given value in degrees.
+    pub fn rotation(mut self, degrees: f32) -> Self {
+        self.to.rotation = Some(degrees);
+        self
+    }
+
+    /// Starts the rotation animation at the given value in degrees instead of the current rotation.
+    pub fn from_rotation(mut self, degrees: f32) -> Self {
+        self.from.rotation = Some(degrees);
+        self
+    }
+
+    /// Starts the animation on the given scene item. The start values are applied immediately.
+    ///
+    /// The animation keeps running until it is finished or cancelled, or until every handle to it has been dropped.
+    pub fn start<T: SceneItemTrait + ?Sized>(
+        self,
+        item: &T,
+    ) -> Result<ObsTransformAnimation, ObsError> {
+        if self.to.position.is_none() && self.to.scale.is_none() && self.to.rotation.is_none() {
+            return Err(ObsError::InvalidOperation(
+                "The animation does not change the position, scale or rotation".into(),
+            ));
+        }
+
+        let runtime = item.runtime();
+        let scene_item = item.as_ptr().clone();
+        let status = Arc::new(AnimationStatus::default());
+
+        let TransformAnimator {
+            duration,
+            easing,
+            from,
+            to,
+        } = self;
+        let callback_status = status.clone();
+
+        let mut state = run_with_obs!(
+            runtime,
+            (scene_item, callback_status),
+            move |token| unsafe {
+                // Safety: The scene item is valid because of the smart pointer
+                let item_ptr = scene_item.get_ptr(token);
+                let from = AnimatedValues {
+                    position: to.position.map(|_| {
+                        from.position.unwrap_or_else(|| {
+                            let mut position: libobs::vec2 = std::mem::zeroed();
+                            libobs::obs_sceneitem_get_pos(item_ptr, &mut position);
+                            Vec2::from(position)
+                        })
+                    }),
+                    scale: to.scale.map(|_| {
+                        from.scale.unwrap_or_else(|| {
+                            let mut scale: libobs::vec2 = std::mem::zeroed();
+                            libobs::obs_sceneitem_get_scale(item_ptr, &mut scale);
+                            Vec2::from(scale)
+                        })
+                    }),
+                    rotation: to.rotation.map(|_| {
+                        from.rotation
+                            .unwrap_or_else(|| libobs::obs_sceneitem_get_rot(item_ptr))
+                    }),
+                };
+
+                let state = AnimationState {
+                    scene_item: Sendable(item_ptr),
+                    from,
+                    to,
+                    duration: duration.as_secs_f32(),
+                    elapsed: 0.0,
+                    easing,
+                    status: callback_status,
+                };
+                state.apply(0.0);
+
+                state
+            }
+        )?;
+
+        let callback = ObsTickCallback::new(runtime, move |seconds| unsafe {
+            // Safety: The scene item is kept alive by the handle until the callback has been removed
+            state.tick(seconds)
+        })?;
+
+        Ok(ObsTransformAnimation {
+            _callback: Arc::new(callback),
+            _scene_item: scene_item,
+            status,
+        })
+    }
+}
+
+/// A handle to a running animation, see `TransformAnimator`.
+///
+/// The animation is stopped once every clone of this handle has been dropped, the scene item keeps
+/// the values it had at that time.
+#[derive(Debug, Clone)]
+pub struct ObsTransformAnimation {
+    // Removes the callback before the scene item can be released
+    _callback: Arc<ObsTickCallback>,
+    _scene_item: SmartPointerSendable<*mut obs_scene_item>,
+    status: Arc<AnimationStatus>,
+}
+
+impl ObsTransformAnimation {
+    /// Returns whether the animation has reached its end or has been cancelled.
+    pub fn is_finished(&self) -> bool {
+        self.status.is_finished()
+    }
+
+    /// Stops the animation on the next tick, the scene item keeps its current values.
+    pub fn cancel(&self) {
+        self.status.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    /// Blocks until the animation has finished or has been cancelled.
+    pub fn wait(&self) {
+        let finished = self
+            .status
+            .finished
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        let _finished = self
+            .status
+            .changed
+            .wait_while(finished, |finished| !*finished)
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+    }
+
+    /// Like `wait`, but returns `false` if the animation hasn't finished within the timeout.
+    pub fn wait_timeout(&self, timeout: Duration) -> bool {
+        let finished = self
+            .status
+            .finished
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        let (finished, _) = self
+            .status
+            .changed
+            .wait_timeout_while(finished, timeout, |finished| !*finished)
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        *finished
+    }
+}
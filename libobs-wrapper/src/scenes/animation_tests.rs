@@ -0,0 +1,28 @@
+#[cfg(test)]
+mod tests {
+    use super::super::animation::{lerp_vec2, ObsEasing};
+    use crate::graphics::Vec2;
+
+    #[test]
+    fn test_easing_curves() {
+        for easing in [
+            ObsEasing::Linear,
+            ObsEasing::EaseIn,
+            ObsEasing::EaseOut,
+            ObsEasing::EaseInOut,
+            ObsEasing::EaseOutBack,
+        ] {
+            assert!(easing.apply(0.0).abs() < 1e-6, "{:?}", easing);
+            assert!((easing.apply(1.0) - 1.0).abs() < 1e-6, "{:?}", easing);
+        }
+
+        assert_eq!(ObsEasing::Linear.apply(0.25), 0.25);
+        assert!(ObsEasing::EaseIn.apply(0.5) < 0.5);
+        assert!(ObsEasing::EaseOut.apply(0.5) > 0.5);
+        assert!((ObsEasing::EaseInOut.apply(0.5) - 0.5).abs() < 1e-6);
+        assert!(ObsEasing::EaseOutBack.apply(0.8) > 1.0);
+
+        let position = lerp_vec2(Vec2::new(0.0, 10.0), Vec2::new(100.0, 20.0), 0.5);
+        assert_eq!((*position.x(), *position.y()), (50.0, 15.0));
+    }
+}
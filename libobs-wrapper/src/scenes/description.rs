@@ -0,0 +1,432 @@
+use std::{collections::HashSet, ffi::CStr, sync::Arc};
+
+use libobs::{obs_data_array_t, obs_data_item_t, obs_data_t};
+
+use crate::{
+    data::{ObsData, ObsDataPointers},
+    graphics::Vec2,
+    run_with_obs,
+    scenes::{hit_test::xy, ObsSceneRef, SceneItemExtSceneTrait, SceneItemTrait},
+    sources::{ObsSourceRef, ObsSourceTrait},
+    utils::ObsError,
+};
+
+/// Transforms are considered equal if they don't differ by more than this value.
+const TRANSFORM_EPSILON: f32 = 1e-4;
+
+/// A declarative model of the sources of a scene.
+///
+/// Use [`ObsSceneRef::diff`] to compare it with the live scene and [`ObsSceneRef::apply`]
+/// to bring the scene into the described state. With the `serde` feature enabled, the
+/// description can be loaded from / saved to any serde format.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SceneDescription {
+    pub sources: Vec<SceneSourceDescription>,
+}
+
+/// A single source within a [`SceneDescription`], identified by its name.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SceneSourceDescription {
+    pub name: String,
+    /// The id of the source type, e.g. `image_source`
+    pub id: String,
+    /// The settings of the source as JSON. Only the given keys are compared and updated,
+    /// all other settings are left untouched.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub settings: Option<String>,
+    /// The transform of the scene item, `None` leaves it as is
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub transform: Option<SceneTransformDescription>,
+    /// Whether the scene item is visible, `None` leaves it as is
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub visible: Option<bool>,
+}
+
+impl SceneSourceDescription {
+    pub fn new(name: impl Into<String>, id: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            id: id.into(),
+            settings: None,
+            transform: None,
+            visible: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SceneTransformDescription {
+    pub pos: (f32, f32),
+    pub scale: (f32, f32),
+    /// Rotation in degrees
+    pub rot: f32,
+}
+
+impl SceneTransformDescription {
+    fn approx_eq(&self, other: &Self) -> bool {
+        let values = [
+            (self.pos.0, other.pos.0),
+            (self.pos.1, other.pos.1),
+            (self.scale.0, other.scale.0),
+            (self.scale.1, other.scale.1),
+            (self.rot, other.rot),
+        ];
+
+        values
+            .iter()
+            .all(|(a, b)| (a - b).abs() <= TRANSFORM_EPSILON)
+    }
+}
+
+/// A single change that is needed to bring a scene into the described state.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SceneOperation {
+    /// The source does not exist in the scene yet and has to be created
+    Create(SceneSourceDescription),
+    /// The source exists, only the fields that are `Some` have to be changed
+    Update {
+        name: String,
+        settings: Option<String>,
+        transform: Option<SceneTransformDescription>,
+        visible: Option<bool>,
+    },
+    /// The source is not part of the description and has to be removed from the scene
+    Remove { name: String },
+}
+
+type AttachedSource = Arc<Box<dyn ObsSourceTrait>>;
+
+/// The state of a source that is currently attached to the scene.
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct LiveSource {
+    pub(super) name: String,
+    pub(super) id: String,
+    pub(super) transform: Option<SceneTransformDescription>,
+    pub(super) visible: bool,
+}
+
+/// Computes the operations that turn `live` into `desired`.
+///
+/// Removals come first, so sources whose type changed can be recreated with the same name.
+/// `settings_changed` is called with the index of the live source and the desired settings.
+pub(super) fn plan(
+    live: &[LiveSource],
+    desired: &SceneDescription,
+    mut settings_changed: impl FnMut(usize, &str) -> Result<bool, ObsError>,
+) -> Result<Vec<SceneOperation>, ObsError> {
+    let mut names = HashSet::new();
+    for source in &desired.sources {
+        if !names.insert(source.name.as_str()) {
+            return Err(ObsError::InvalidOperation(format!(
+                "Source '{}' is described more than once",
+                source.name
+            )));
+        }
+    }
+
+    let mut removals = Vec::new();
+    let mut changes = Vec::new();
+    for source in live {
+        let target = desired.sources.iter().find(|s| s.name == source.name);
+        if target.is_none_or(|t| t.id != source.id) {
+            removals.push(SceneOperation::Remove {
+                name: source.name.clone(),
+            });
+        }
+    }
+
+    for target in &desired.sources {
+        let Some((index, source)) = live
+            .iter()
+            .enumerate()
+            .find(|(_, s)| s.name == target.name && s.id == target.id)
+        else {
+            changes.push(SceneOperation::Create(target.clone()));
+            continue;
+        };
+
+        let settings = match &target.settings {
+            Some(settings) if settings_changed(index, settings)? => Some(settings.clone()),
+            _ => None,
+        };
+        let transform = target
+            .transform
+            .filter(|t| source.transform.is_none_or(|live| !live.approx_eq(t)));
+        let visible = target.visible.filter(|v| *v != source.visible);
+
+        if settings.is_some() || transform.is_some() || visible.is_some() {
+            changes.push(SceneOperation::Update {
+                name: target.name.clone(),
+                settings,
+                transform,
+                visible,
+            });
+        }
+    }
+
+    removals.append(&mut changes);
+    Ok(removals)
+}
+
+impl ObsSceneRef {
+    /// Returns the sources attached through this `ObsSceneRef`, together with the state
+    /// of their first scene item.
+    pub(super) fn live_sources(&self) -> Result<Vec<(AttachedSource, LiveSource)>, ObsError> {
+        let sources = self
+            .attached_scene_items
+            .read()
+            .map_err(|e| ObsError::LockError(format!("{:?}", e)))?
+            .iter()
+            .map(|(source, items)| (source.clone(), items.first().cloned()))
+            .collect::<Vec<_>>();
+
+        let mut live = Vec::with_capacity(sources.len());
+        for (source, item) in sources {
+            let (transform, visible) = match item {
+                Some(item) => {
+                    let info = item.get_transform_info()?;
+                    let item_ptr = item.as_ptr();
+                    let visible = run_with_obs!(self.runtime, (item_ptr), move |token| unsafe {
+                        // Safety: The scene item is valid because of the smart pointer
+                        libobs::obs_sceneitem_visible(item_ptr.get_ptr(token))
+                    })?;
+
+                    let transform = SceneTransformDescription {
+                        pos: xy(info.0.pos),
+                        scale: xy(info.0.scale),
+                        rot: info.0.rot,
+                    };
+                    (Some(transform), visible)
+                }
+                None => (None, false),
+            };
+
+            let state = LiveSource {
+                name: source.name().to_string(),
+                id: source.id().to_string(),
+                transform,
+                visible,
+            };
+            live.push((source, state));
+        }
+
+        Ok(live)
+    }
+
+    /// Compares the sources attached through this `ObsSceneRef` with the given description
+    /// and returns the minimal operations needed to match it, without changing anything.
+    ///
+    /// Sources are matched by name. If the type of a source differs, it is removed and created again.
+    pub fn diff(&self, description: &SceneDescription) -> Result<Vec<SceneOperation>, ObsError> {
+        let (sources, live): (Vec<_>, Vec<_>) = self.live_sources()?.into_iter().unzip();
+
+        plan(&live, description, |index, settings| {
+            let live_settings = sources[index].settings()?.as_ptr();
+            let desired = ObsData::from_json(settings, self.runtime.clone())?.as_ptr();
+
+            run_with_obs!(
+                self.runtime,
+                (live_settings, desired),
+                move |token| unsafe {
+                    // Safety: Both data objects are kept alive by their smart pointers
+                    !data_contains(live_settings.get_ptr(token), desired.get_ptr(token))
+                }
+            )
+        })
+    }
+
+    /// Brings the scene into the described state by creating, updating and removing sources.
+    ///
+    /// Returns the operations that were applied. Sources that were not added through this
+    /// `ObsSceneRef` are not known to it and therefore left untouched.
+    pub fn apply(
+        &mut self,
+        description: &SceneDescription,
+    ) -> Result<Vec<SceneOperation>, ObsError> {
+        let operations = self.diff(description)?;
+
+        for operation in &operations {
+            match operation {
+                SceneOperation::Remove { name } => {
+                    self.attached_scene_items
+                        .write()
+                        .map_err(|e| ObsError::LockError(format!("{:?}", e)))?
+                        .retain(|source, _| source.name() != name.as_str());
+                }
+                SceneOperation::Create(source) => {
+                    let settings = source
+                        .settings
+                        .as_ref()
+                        .map(|json| ObsData::from_json(json, self.runtime.clone()))
+                        .transpose()?
+                        .map(|data| data.into_immutable());
+
+                    let created = ObsSourceRef::new(
+                        source.id.as_str(),
+                        source.name.as_str(),
+                        settings,
+                        None,
+                        self.runtime.clone(),
+                    )?;
+
+                    let item = self.add_source(created)?;
+                    apply_item_state(&item, source.transform, source.visible)?;
+                }
+                SceneOperation::Update {
+                    name,
+                    settings,
+                    transform,
+                    visible,
+                } => {
+                    let items = self
+                        .attached_scene_items
+                        .read()
+                        .map_err(|e| ObsError::LockError(format!("{:?}", e)))?
+                        .iter()
+                        .find(|(source, _)| source.name() == name.as_str())
+                        .map(|(source, items)| (source.clone(), items.clone()));
+
+                    let Some((source, items)) = items else {
+                        continue;
+                    };
+
+                    if let Some(settings) = settings {
+                        source
+                            .update_settings(ObsData::from_json(settings, self.runtime.clone())?)?;
+                    }
+
+                    for item in items {
+                        apply_item_state(&**item, *transform, *visible)?;
+                    }
+                }
+            }
+        }
+
+        Ok(operations)
+    }
+}
+
+fn apply_item_state(
+    item: &dyn SceneItemTrait,
+    transform: Option<SceneTransformDescription>,
+    visible: Option<bool>,
+) -> Result<(), ObsError> {
+    if let Some(transform) = transform {
+        let mut info = item.get_transform_info()?;
+        info.0.pos = Vec2::new(transform.pos.0, transform.pos.1).into();
+        info.0.scale = Vec2::new(transform.scale.0, transform.scale.1).into();
+        info.0.rot = transform.rot;
+
+        item.set_transform_info(&info)?;
+    }
+
+    if let Some(visible) = visible {
+        let item_ptr = item.as_ptr().clone();
+        run_with_obs!(item.runtime(), (item_ptr), move |token| unsafe {
+            // Safety: The scene item is valid because of the smart pointer
+            libobs::obs_sceneitem_set_visible(item_ptr.get_ptr(token), visible);
+        })?;
+    }
+
+    Ok(())
+}
+
+/// Returns true if every value of `needle` is set to the same value in `haystack`.
+///
+/// # Safety
+/// Both pointers must be valid and this must be called in the OBS runtime.
+unsafe fn data_contains(haystack: *mut obs_data_t, needle: *mut obs_data_t) -> bool {
+    let mut item = libobs::obs_data_first(needle);
+    while !item.is_null() {
+        let other = libobs::obs_data_item_byname(haystack, libobs::obs_data_item_get_name(item));
+        let equal = !other.is_null() && item_equals(other, item);
+        libobs::obs_data_item_release(&mut { other });
+
+        if !equal {
+            libobs::obs_data_item_release(&mut item);
+            return false;
+        }
+
+        libobs::obs_data_item_next(&mut item);
+    }
+
+    true
+}
+
+/// # Safety
+/// Both items must be valid and this must be called in the OBS runtime.
+unsafe fn item_equals(a: *mut obs_data_item_t, b: *mut obs_data_item_t) -> bool {
+    let item_type = libobs::obs_data_item_gettype(a);
+    if item_type != libobs::obs_data_item_gettype(b) {
+        return false;
+    }
+
+    match item_type {
+        libobs::obs_data_type_OBS_DATA_STRING => {
+            let a = libobs::obs_data_item_get_string(a);
+            let b = libobs::obs_data_item_get_string(b);
+            match (a.is_null(), b.is_null()) {
+                (false, false) => CStr::from_ptr(a) == CStr::from_ptr(b),
+                (a_null, b_null) => a_null == b_null,
+            }
+        }
+        libobs::obs_data_type_OBS_DATA_NUMBER => {
+            libobs::obs_data_item_get_double(a) == libobs::obs_data_item_get_double(b)
+        }
+        libobs::obs_data_type_OBS_DATA_BOOLEAN => {
+            libobs::obs_data_item_get_bool(a) == libobs::obs_data_item_get_bool(b)
+        }
+        libobs::obs_data_type_OBS_DATA_OBJECT => {
+            let a = libobs::obs_data_item_get_obj(a);
+            let b = libobs::obs_data_item_get_obj(b);
+            let equal = data_equals(a, b);
+            libobs::obs_data_release(a);
+            libobs::obs_data_release(b);
+            equal
+        }
+        libobs::obs_data_type_OBS_DATA_ARRAY => {
+            let a = libobs::obs_data_item_get_array(a);
+            let b = libobs::obs_data_item_get_array(b);
+            let equal = array_equals(a, b);
+            libobs::obs_data_array_release(a);
+            libobs::obs_data_array_release(b);
+            equal
+        }
+        _ => true,
+    }
+}
+
+/// # Safety
+/// Both pointers must be null or valid and this must be called in the OBS runtime.
+unsafe fn data_equals(a: *mut obs_data_t, b: *mut obs_data_t) -> bool {
+    if a.is_null() || b.is_null() {
+        return a.is_null() == b.is_null();
+    }
+
+    data_contains(a, b) && data_contains(b, a)
+}
+
+/// # Safety
+/// Both pointers must be null or valid and this must be called in the OBS runtime.
+unsafe fn array_equals(a: *mut obs_data_array_t, b: *mut obs_data_array_t) -> bool {
+    if a.is_null() || b.is_null() {
+        return a.is_null() == b.is_null();
+    }
+
+    let count = libobs::obs_data_array_count(a);
+    if count != libobs::obs_data_array_count(b) {
+        return false;
+    }
+
+    (0..count).all(|index| {
+        let a = libobs::obs_data_array_item(a, index);
+        let b = libobs::obs_data_array_item(b, index);
+        let equal = data_equals(a, b);
+        libobs::obs_data_release(a);
+        libobs::obs_data_release(b);
+        equal
+    })
+}
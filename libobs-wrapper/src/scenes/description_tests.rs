@@ -0,0 +1,95 @@
+#[cfg(test)]
+mod tests {
+    use super::super::description::{
+        plan, LiveSource, SceneDescription, SceneOperation, SceneSourceDescription,
+        SceneTransformDescription,
+    };
+
+    fn live(name: &str, id: &str, pos: (f32, f32)) -> LiveSource {
+        LiveSource {
+            name: name.to_string(),
+            id: id.to_string(),
+            transform: Some(SceneTransformDescription {
+                pos,
+                scale: (1.0, 1.0),
+                rot: 0.0,
+            }),
+            visible: true,
+        }
+    }
+
+    #[test]
+    fn plan_computes_minimal_operations() {
+        let live = vec![
+            live("camera", "v4l2_input", (0.0, 0.0)),
+            live("logo", "image_source", (10.0, 10.0)),
+            live("old", "color_source", (0.0, 0.0)),
+        ];
+
+        let mut camera = SceneSourceDescription::new("camera", "v4l2_input");
+        camera.transform = Some(SceneTransformDescription {
+            pos: (100.0, 0.0),
+            scale: (1.0, 1.0),
+            rot: 0.0,
+        });
+        camera.visible = Some(true);
+
+        let mut logo = SceneSourceDescription::new("logo", "image_source");
+        logo.settings = Some(r#"{"file": "logo.png"}"#.to_string());
+
+        let text = SceneSourceDescription::new("text", "text_ft2_source_v2");
+
+        let description = SceneDescription {
+            sources: vec![camera, logo, text.clone()],
+        };
+
+        let operations = plan(&live, &description, |index, _| Ok(index == 0)).unwrap();
+        assert_eq!(
+            operations,
+            vec![
+                SceneOperation::Remove {
+                    name: "old".to_string()
+                },
+                SceneOperation::Update {
+                    name: "camera".to_string(),
+                    settings: None,
+                    transform: Some(SceneTransformDescription {
+                        pos: (100.0, 0.0),
+                        scale: (1.0, 1.0),
+                        rot: 0.0,
+                    }),
+                    visible: None,
+                },
+                SceneOperation::Create(text),
+            ]
+        );
+    }
+
+    #[test]
+    fn plan_recreates_sources_with_changed_type() {
+        let live = vec![live("background", "color_source", (0.0, 0.0))];
+        let background = SceneSourceDescription::new("background", "image_source");
+        let description = SceneDescription {
+            sources: vec![background.clone()],
+        };
+
+        let operations = plan(&live, &description, |_, _| Ok(false)).unwrap();
+        assert_eq!(
+            operations,
+            vec![
+                SceneOperation::Remove {
+                    name: "background".to_string()
+                },
+                SceneOperation::Create(background),
+            ]
+        );
+
+        let duplicated = SceneDescription {
+            sources: vec![
+                description.sources[0].clone(),
+                description.sources[0].clone(),
+            ],
+        };
+        assert!(plan(&live, &duplicated, |_, _| Ok(false)).is_err());
+    }
+}
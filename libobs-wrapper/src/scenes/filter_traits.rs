@@ -25,10 +25,10 @@ impl ObsSceneExtFilter for ObsSceneRef {
             ObsError::LockError("Failed to acquire write lock on attached filters".into())
         })?;
 
-        run_with_obs!(self.runtime, (source_ptr, filter_ptr), move || {
+        run_with_obs!(self.runtime, (source_ptr, filter_ptr), move |token| {
             unsafe {
                 // Safety: Both source_ptr and filter_ptr are valid because of SmartPointers
-                libobs::obs_source_filter_add(source_ptr.0, filter_ptr.get_ptr());
+                libobs::obs_source_filter_add(source_ptr.0, filter_ptr.get_ptr(token));
             };
         })?;
 
@@ -56,7 +56,7 @@ impl ObsSceneExtFilter for ObsSceneRef {
             })?
             .retain(|f| {
                 // Keep everything except this one filter
-                f.get_inner().as_ptr().get_ptr() != filter_ref.as_ptr().get_ptr()
+                !f.get_inner().as_ptr().ptr_eq(&filter_ref.as_ptr())
             });
         Ok(())
     }
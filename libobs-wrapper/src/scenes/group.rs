@@ -0,0 +1,224 @@
+use std::{ffi::c_void, sync::Arc};
+
+use libobs::{obs_scene_item, obs_scene_t, obs_sceneitem_t};
+
+use crate::{
+    data::ImmutableObsData,
+    impl_obs_drop, obs_string, run_with_obs,
+    runtime::ObsRuntime,
+    scenes::{ObsSceneRef, SceneItemTrait},
+    sources::{ObsSourceRef, ObsSourceTrait},
+    unsafe_send::{Sendable, SmartPointerSendable},
+    utils::{ObsDropGuard, ObsError, ObsString},
+};
+
+#[derive(Debug)]
+struct _ObsSceneGroupDropGuard {
+    group: Sendable<*mut obs_scene_item>,
+    runtime: ObsRuntime,
+}
+
+impl ObsDropGuard for _ObsSceneGroupDropGuard {}
+
+impl_obs_drop!(_ObsSceneGroupDropGuard, (group), move || unsafe {
+    // Safety: The group is valid until it is removed here. The items are moved back into the
+    // parent scene first, because `obs_sceneitem_group_ungroup` would recreate them and the
+    // scene items that are still referenced by other structs would be freed with the group.
+    for item in group_items(group.0) {
+        libobs::obs_sceneitem_group_remove_item(group.0, item);
+    }
+
+    libobs::obs_sceneitem_remove(group.0);
+});
+
+unsafe extern "C" fn collect_group_item(
+    _scene: *mut obs_scene_t,
+    item: *mut obs_sceneitem_t,
+    param: *mut c_void,
+) -> bool {
+    let items = &mut *(param as *mut Vec<*mut obs_sceneitem_t>);
+    items.push(item);
+    true
+}
+
+/// Returns the scene items that are currently in the given group.
+///
+/// # Safety
+/// The group must be valid and this must be called in the OBS runtime.
+unsafe fn group_items(group: *mut obs_sceneitem_t) -> Vec<*mut obs_sceneitem_t> {
+    let mut items = Vec::new();
+    libobs::obs_sceneitem_group_enum_items(
+        group,
+        Some(collect_group_item),
+        &mut items as *mut _ as *mut c_void,
+    );
+
+    items
+}
+
+#[derive(Debug, Clone)]
+/// A group of scene items that can be moved, scaled and rotated as a unit,
+/// using the methods of the `SceneItemTrait`.
+///
+/// The group is stored in the scene it was created in, so it is only removed
+/// by `ObsSceneRef::remove_group` or once the scene is dropped. Items in the
+/// group are moved back into the scene when the group is removed.
+pub struct ObsSceneGroupRef {
+    // Drop the group item first...
+    group_ptr: SmartPointerSendable<*mut obs_scene_item>,
+    name: ObsString,
+    group_source: ObsSourceRef,
+    runtime: ObsRuntime,
+    // ...and then the scene, see `ObsSceneItemRef` on why only the pointer is kept
+    scene_ptr: SmartPointerSendable<*mut libobs::obs_scene>,
+}
+
+impl ObsSceneGroupRef {
+    pub(super) fn new(scene: &ObsSceneRef, name: ObsString) -> Result<Self, ObsError> {
+        let runtime = scene.runtime.clone();
+        let scene_ptr = scene.as_ptr();
+
+        let (group_ptr, group_source_ptr) =
+            run_with_obs!(runtime, (scene_ptr, name), move |token| unsafe {
+                // Safety: The scene is valid because of the smart pointer and the name is kept in scope
+                let group =
+                    libobs::obs_scene_add_group2(scene_ptr.get_ptr(token), name.as_ptr().0, true);
+                if group.is_null() {
+                    return Err(ObsError::NullPointer(None).in_function("obs_scene_add_group2"));
+                }
+
+                // The source is owned by the group item, so we need our own reference for the wrapper
+                let source = libobs::obs_source_get_ref(libobs::obs_sceneitem_get_source(group));
+                Ok((Sendable(group), Sendable(source)))
+            })??;
+
+        let drop_guard = _ObsSceneGroupDropGuard {
+            group: group_ptr.clone(),
+            runtime: runtime.clone(),
+        };
+        let group_ptr = SmartPointerSendable::new(group_ptr.0, Arc::new(drop_guard));
+
+        let group_source = ObsSourceRef::from_created(
+            group_source_ptr,
+            obs_string!("group"),
+            name.clone(),
+            ImmutableObsData::new(&runtime)?,
+            runtime.clone(),
+        )?;
+
+        Ok(Self {
+            group_ptr,
+            name,
+            group_source,
+            runtime,
+            scene_ptr: scene.as_ptr(),
+        })
+    }
+
+    pub fn name(&self) -> ObsString {
+        self.name.clone()
+    }
+
+    /// Moves the given scene item of the same scene into this group. Its transform is
+    /// adjusted, so the item stays at the same place on the canvas.
+    pub fn add_item<K: SceneItemTrait + ?Sized>(&self, item: &K) -> Result<(), ObsError> {
+        let group_ptr = self.group_ptr.clone();
+        let scene_ptr = self.scene_ptr.clone();
+        let item_ptr = item.as_ptr().clone();
+
+        run_with_obs!(
+            self.runtime,
+            (group_ptr, scene_ptr, item_ptr),
+            move |token| {
+                unsafe {
+                    // Safety: All pointers are valid because of the smart pointers
+                    let parent = libobs::obs_sceneitem_get_scene(item_ptr.get_ptr(token));
+                    if parent != scene_ptr.get_ptr(token) {
+                        return Err(ObsError::InvalidOperation(
+                        "Only items that are directly in the scene of the group can be added to it"
+                            .into(),
+                    ));
+                    }
+
+                    libobs::obs_sceneitem_group_add_item(
+                        group_ptr.get_ptr(token),
+                        item_ptr.get_ptr(token),
+                    );
+                }
+
+                Ok(())
+            }
+        )?
+    }
+
+    /// Moves the given scene item out of this group back into the scene.
+    pub fn remove_item<K: SceneItemTrait + ?Sized>(&self, item: &K) -> Result<(), ObsError> {
+        if !self.contains_item(item)? {
+            return Err(ObsError::InvalidOperation(
+                "The scene item is not part of this group".into(),
+            ));
+        }
+
+        let group_ptr = self.group_ptr.clone();
+        let item_ptr = item.as_ptr().clone();
+        run_with_obs!(self.runtime, (group_ptr, item_ptr), move |token| unsafe {
+            // Safety: Both pointers are valid because of the smart pointers
+            libobs::obs_sceneitem_group_remove_item(
+                group_ptr.get_ptr(token),
+                item_ptr.get_ptr(token),
+            );
+        })
+    }
+
+    /// Returns whether the given scene item is part of this group.
+    pub fn contains_item<K: SceneItemTrait + ?Sized>(&self, item: &K) -> Result<bool, ObsError> {
+        let group_ptr = self.group_ptr.clone();
+        let scene_ptr = self.scene_ptr.clone();
+        let item_ptr = item.as_ptr().clone();
+
+        run_with_obs!(
+            self.runtime,
+            (group_ptr, scene_ptr, item_ptr),
+            move |token| unsafe {
+                // Safety: All pointers are valid because of the smart pointers
+                libobs::obs_sceneitem_get_group(scene_ptr.get_ptr(token), item_ptr.get_ptr(token))
+                    == group_ptr.get_ptr(token)
+            }
+        )
+    }
+
+    /// Returns the number of scene items in this group.
+    pub fn item_count(&self) -> Result<usize, ObsError> {
+        let group_ptr = self.group_ptr.clone();
+        run_with_obs!(self.runtime, (group_ptr), move |token| unsafe {
+            // Safety: The group is valid because of the smart pointer
+            group_items(group_ptr.get_ptr(token)).len()
+        })
+    }
+}
+
+impl SceneItemTrait for ObsSceneGroupRef {
+    fn as_ptr(&self) -> &SmartPointerSendable<*mut obs_scene_item> {
+        &self.group_ptr
+    }
+
+    fn runtime(&self) -> ObsRuntime {
+        self.runtime.clone()
+    }
+
+    fn inner_source_dyn(&self) -> &dyn ObsSourceTrait {
+        &self.group_source
+    }
+
+    fn inner_source_dyn_mut(&mut self) -> &mut dyn ObsSourceTrait {
+        &mut self.group_source
+    }
+}
+
+impl PartialEq for ObsSceneGroupRef {
+    fn eq(&self, other: &Self) -> bool {
+        self.group_ptr.ptr_eq(&other.group_ptr)
+    }
+}
+
+impl Eq for ObsSceneGroupRef {}
@@ -0,0 +1,91 @@
+use libobs::{obs_sceneitem_t, obs_transform_info};
+
+use crate::enums::ObsBoundsType;
+
+/// The on-canvas box of a scene item, as drawn by OBS for the selection outline.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct ItemBox {
+    pub pos: (f32, f32),
+    /// Signed size of the box, negative if the item is flipped.
+    pub size: (f32, f32),
+    /// Point inside the box (in box space) that is placed at `pos`.
+    pub origin: (f32, f32),
+    /// Rotation in degrees, clockwise as in OBS.
+    pub rot: f32,
+}
+
+impl ItemBox {
+    /// Computes the box from the transform info of an item and the cropped size of its source.
+    pub fn from_info(info: &obs_transform_info, base_width: f32, base_height: f32) -> Self {
+        let scale = xy(info.scale);
+        let size = if info.bounds_type == ObsBoundsType::None as libobs::obs_bounds_type {
+            (base_width * scale.0, base_height * scale.1)
+        } else {
+            xy(info.bounds)
+        };
+
+        let align = |len: f32, start: u32, end: u32| {
+            if info.alignment & start != 0 {
+                0.0
+            } else if info.alignment & end != 0 {
+                len
+            } else {
+                len / 2.0
+            }
+        };
+
+        ItemBox {
+            pos: xy(info.pos),
+            size,
+            origin: (
+                align(size.0, libobs::OBS_ALIGN_LEFT, libobs::OBS_ALIGN_RIGHT),
+                align(size.1, libobs::OBS_ALIGN_TOP, libobs::OBS_ALIGN_BOTTOM),
+            ),
+            rot: info.rot,
+        }
+    }
+
+    /// Whether the given canvas coordinate lies inside this box.
+    pub fn contains(&self, x: f32, y: f32) -> bool {
+        let (dx, dy) = (x - self.pos.0, y - self.pos.1);
+        let (sin, cos) = (-self.rot).to_radians().sin_cos();
+
+        let local_x = dx * cos - dy * sin + self.origin.0;
+        let local_y = dx * sin + dy * cos + self.origin.1;
+
+        let in_range = |v: f32, len: f32| v >= len.min(0.0) && v <= len.max(0.0);
+        in_range(local_x, self.size.0) && in_range(local_y, self.size.1)
+    }
+}
+
+pub(super) fn xy(v: libobs::vec2) -> (f32, f32) {
+    // Safety: libobs::vec2 is a C representation with a known layout, the __bindgen_anon_1 must be set
+    let inner = unsafe { v.__bindgen_anon_1.__bindgen_anon_1 };
+    (inner.x, inner.y)
+}
+
+/// Reads the box of the given scene item, returns `None` if the item is hidden.
+///
+/// # Safety
+/// Must be called on the OBS thread with a valid scene item pointer.
+pub(crate) unsafe fn visible_item_box(item: *mut obs_sceneitem_t) -> Option<ItemBox> {
+    if !libobs::obs_sceneitem_visible(item) {
+        return None;
+    }
+
+    let mut info: obs_transform_info = std::mem::zeroed();
+    libobs::obs_sceneitem_get_info2(item, &mut info);
+
+    let mut crop: libobs::obs_sceneitem_crop = std::mem::zeroed();
+    libobs::obs_sceneitem_get_crop(item, &mut crop);
+
+    let source = libobs::obs_sceneitem_get_source(item);
+    let width = libobs::obs_source_get_width(source) as i64 - (crop.left + crop.right) as i64;
+    let height = libobs::obs_source_get_height(source) as i64 - (crop.top + crop.bottom) as i64;
+
+    Some(ItemBox::from_info(
+        &info,
+        width.max(0) as f32,
+        height.max(0) as f32,
+    ))
+}
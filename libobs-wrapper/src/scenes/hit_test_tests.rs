@@ -0,0 +1,47 @@
+#[cfg(test)]
+mod tests {
+    use libobs::obs_transform_info;
+
+    use super::super::hit_test::ItemBox;
+    use crate::{enums::ObsBoundsType, graphics::Vec2};
+
+    fn info(pos: (f32, f32), scale: (f32, f32), rot: f32, alignment: u32) -> obs_transform_info {
+        let mut info: obs_transform_info = unsafe { std::mem::zeroed() };
+        info.pos = Vec2::new(pos.0, pos.1).into();
+        info.scale = Vec2::new(scale.0, scale.1).into();
+        info.rot = rot;
+        info.alignment = alignment;
+        info.bounds_type = ObsBoundsType::None as libobs::obs_bounds_type;
+        info
+    }
+
+    #[test]
+    fn top_left_aligned_box() {
+        let top_left = libobs::OBS_ALIGN_LEFT | libobs::OBS_ALIGN_TOP;
+        let b = ItemBox::from_info(
+            &info((100.0, 50.0), (2.0, 1.0), 0.0, top_left),
+            100.0,
+            100.0,
+        );
+
+        assert!(b.contains(100.0, 50.0));
+        assert!(b.contains(299.0, 149.0));
+        assert!(!b.contains(99.0, 60.0));
+        assert!(!b.contains(301.0, 60.0));
+    }
+
+    #[test]
+    fn rotated_and_flipped_boxes() {
+        let center = libobs::OBS_ALIGN_CENTER;
+        // A 200x20 bar rotated by 90 degrees becomes a 20x200 bar around its center.
+        let b = ItemBox::from_info(&info((0.0, 0.0), (1.0, 1.0), 90.0, center), 200.0, 20.0);
+        assert!(b.contains(0.0, 90.0));
+        assert!(!b.contains(90.0, 0.0));
+
+        let top_left = libobs::OBS_ALIGN_LEFT | libobs::OBS_ALIGN_TOP;
+        let flipped =
+            ItemBox::from_info(&info((100.0, 0.0), (-1.0, 1.0), 0.0, top_left), 50.0, 50.0);
+        assert!(flipped.contains(60.0, 10.0));
+        assert!(!flipped.contains(110.0, 10.0));
+    }
+}
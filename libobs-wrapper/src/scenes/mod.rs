@@ -6,6 +6,27 @@
 mod transform_info;
 pub use transform_info::*;
 
+mod animation;
+#[cfg(test)]
+mod animation_tests;
+pub use animation::*;
+
+mod description;
+#[cfg(test)]
+mod description_tests;
+pub use description::*;
+
+mod template;
+#[cfg(test)]
+mod template_tests;
+pub use template::*;
+
+mod group;
+pub use group::ObsSceneGroupRef;
+
+mod hit_test;
+#[cfg(test)]
+mod hit_test_tests;
 mod scene_drop_guards;
 mod scene_item;
 
@@ -23,7 +44,7 @@ use libobs::{obs_scene_t, obs_source_t};
 use crate::macros::impl_eq_of_ptr;
 use crate::scenes::scene_drop_guards::_SceneDropGuard;
 use crate::sources::{ObsFilterGuardPair, ObsSourceTrait};
-use crate::unsafe_send::SmartPointerSendable;
+use crate::unsafe_send::{ObsThreadToken, SmartPointerSendable};
 use crate::utils::{GeneralTraitHashMap, ObsDropGuard};
 use crate::{
     impl_signal_manager, run_with_obs,
@@ -43,6 +64,7 @@ pub struct ObsSceneRef {
     attached_scene_items:
         GeneralTraitHashMap<dyn ObsSourceTrait, Vec<Arc<Box<dyn SceneItemTrait + 'static>>>>,
     attached_filters: Arc<RwLock<Vec<ObsFilterGuardPair>>>,
+    attached_groups: Arc<RwLock<Vec<ObsSceneGroupRef>>>,
     runtime: ObsRuntime,
     signals: Arc<ObsSceneSignals>,
     scene: SmartPointerSendable<*mut obs_scene_t>,
@@ -60,7 +82,7 @@ impl ObsSceneRef {
                 libobs::obs_scene_create(name_ptr.0)
             };
             if scene_ptr.is_null() {
-                return Err(ObsError::NullPointer(None));
+                return Err(ObsError::NullPointer(None).in_function("obs_scene_create"));
             }
 
             let source_ptr = unsafe {
@@ -73,7 +95,7 @@ impl ObsSceneRef {
                     // Safety: scene_ptr is valid because we just created it and its not null.
                     libobs::obs_scene_release(scene_ptr);
                 }
-                return Err(ObsError::NullPointer(None));
+                return Err(ObsError::NullPointer(None).in_function("obs_scene_get_source"));
             }
 
             Ok(Sendable(scene_ptr))
@@ -88,6 +110,7 @@ impl ObsSceneRef {
             scene,
             attached_scene_items: Arc::new(RwLock::new(HashMap::new())),
             attached_filters: Arc::new(RwLock::new(Vec::new())),
+            attached_groups: Arc::new(RwLock::new(Vec::new())),
             runtime,
             signals,
         })
@@ -138,14 +161,62 @@ impl ObsSceneRef {
     /// Gets the underlying source pointer of this scene, which is used internally when setting it to a channel.
     pub fn get_scene_source_ptr(&self) -> Result<Sendable<*mut obs_source_t>, ObsError> {
         let scene_ptr = self.scene.clone();
-        run_with_obs!(self.runtime, (scene_ptr), move || {
+        run_with_obs!(self.runtime, (scene_ptr), move |token| {
             unsafe {
                 // Safety: We are in the runtime and the scene ptr must be valid because we are using a smart pointer
-                Sendable(libobs::obs_scene_get_source(scene_ptr.get_ptr()))
+                Sendable(libobs::obs_scene_get_source(scene_ptr.get_ptr(token)))
             }
         })
     }
 
+    /// The number of clones of this scene, including the one stored in the context.
+    pub(crate) fn handle_count(&self) -> usize {
+        Arc::strong_count(&self.attached_scene_items)
+    }
+
+    /// Returns whether the scene is rendered anywhere, see `obs_source_showing`.
+    pub(crate) fn is_showing(&self) -> Result<bool, ObsError> {
+        let scene_ptr = self.scene.clone();
+        run_with_obs!(self.runtime, (scene_ptr), move |token| unsafe {
+            // Safety: The scene is valid because of the smart pointer
+            libobs::obs_source_showing(libobs::obs_scene_get_source(scene_ptr.get_ptr(token)))
+        })
+    }
+
+    /// Returns the names of the sources that are kept for this scene, but whose scene items
+    /// have all been removed from the scene in OBS.
+    pub(crate) fn detached_sources(&self) -> Result<Vec<String>, ObsError> {
+        let tracked = self
+            .attached_scene_items
+            .read()
+            .map_err(|e| ObsError::LockError(format!("{:?}", e)))?
+            .iter()
+            .map(|(source, items)| {
+                let items = items
+                    .iter()
+                    .map(|item| item.as_ptr().clone())
+                    .collect::<Vec<_>>();
+                (source.name().to_string(), items)
+            })
+            .collect::<Vec<_>>();
+
+        let tracked = Sendable(tracked);
+        run_with_obs!(self.runtime, (tracked), move |token| {
+            tracked
+                .0
+                .into_iter()
+                .filter(|(_, items)| {
+                    items.iter().all(|item| unsafe {
+                        // Safety: The item is valid because of the smart pointer. Removed items
+                        // are detached from their scene, so their parent is null.
+                        libobs::obs_sceneitem_get_scene(item.get_ptr(token)).is_null()
+                    })
+                })
+                .map(|(name, _)| name)
+                .collect::<Vec<_>>()
+        })
+    }
+
     pub fn as_ptr(&self) -> SmartPointerSendable<*mut obs_scene_t> {
         self.scene.clone()
     }
@@ -154,14 +225,103 @@ impl ObsSceneRef {
         self.name.clone()
     }
 
+    /// Creates a new, empty group in this scene. Use `ObsSceneGroupRef::add_item`
+    /// to move scene items into it.
+    pub fn add_group<T: Into<ObsString>>(&self, name: T) -> Result<ObsSceneGroupRef, ObsError> {
+        let group = ObsSceneGroupRef::new(self, name.into())?;
+
+        self.attached_groups
+            .write()
+            .map_err(|_| ObsError::LockError("Failed to acquire write lock on groups".into()))?
+            .push(group.clone());
+
+        Ok(group)
+    }
+
+    /// Returns every group that was created through this scene.
+    pub fn get_groups(&self) -> Result<Vec<ObsSceneGroupRef>, ObsError> {
+        let groups = self
+            .attached_groups
+            .read()
+            .map_err(|_| ObsError::LockError("Failed to acquire read lock on groups".into()))?
+            .clone();
+
+        Ok(groups)
+    }
+
+    /// Removes the group from this scene once every clone of it is dropped.
+    /// The items of the group are moved back into the scene.
+    pub fn remove_group(&self, group: &ObsSceneGroupRef) -> Result<(), ObsError> {
+        self.attached_groups
+            .write()
+            .map_err(|_| ObsError::LockError("Failed to acquire write lock on groups".into()))?
+            .retain(|g| g != group);
+
+        Ok(())
+    }
+
+    /// Returns the topmost visible scene item that lies under the given canvas
+    /// (base resolution) coordinate, or `None` if there is no item at that point.
+    ///
+    /// Only items added through this `ObsSceneRef` are considered. Each item is
+    /// tested against its transformed box (position, scale, rotation, alignment,
+    /// bounds and crop), which is the same box OBS draws when selecting an item.
+    pub fn find_item_at(
+        &self,
+        base_x: f32,
+        base_y: f32,
+    ) -> Result<Option<Arc<Box<dyn SceneItemTrait>>>, ObsError> {
+        let items = self
+            .attached_scene_items
+            .read()
+            .map_err(|e| ObsError::LockError(format!("{:?}", e)))?
+            .values()
+            .flatten()
+            .cloned()
+            .collect::<Vec<_>>();
+
+        if items.is_empty() {
+            return Ok(None);
+        }
+
+        let item_ptrs = items
+            .iter()
+            .map(|item| item.as_ptr().clone())
+            .collect::<Vec<_>>();
+        let hit = run_with_obs!(self.runtime, (item_ptrs), move |token| {
+            let mut topmost: Option<(i32, usize)> = None;
+            for (index, item) in item_ptrs.iter().enumerate() {
+                let item = item.get_ptr(token);
+                unsafe {
+                    // Safety: The items are kept alive by the scene, and we are in the runtime
+                    let Some(item_box) = hit_test::visible_item_box(item) else {
+                        continue;
+                    };
+                    if !item_box.contains(base_x, base_y) {
+                        continue;
+                    }
+
+                    let order = libobs::obs_sceneitem_get_order_position(item);
+                    if topmost.is_none_or(|(top_order, _)| order > top_order) {
+                        topmost = Some((order, index));
+                    }
+                }
+            }
+
+            topmost.map(|(_, index)| index)
+        })?;
+
+        Ok(hit.map(|index| items[index].clone()))
+    }
+
     pub fn signals(&self) -> Arc<ObsSceneSignals> {
         self.signals.clone()
     }
 }
 
-impl_signal_manager!(|scene_ptr: SmartPointerSendable<*mut obs_scene_t>| unsafe {
+impl_signal_manager!(|scene_ptr: SmartPointerSendable<*mut obs_scene_t>, token: ObsThreadToken| unsafe {
     // Safety: This is a smart pointer, so it is fine
-    let source_ptr = libobs::obs_scene_get_source(scene_ptr.get_ptr());
+    let source_ptr = libobs::obs_scene_get_source(scene_ptr.get_ptr(token));
 
     libobs::obs_source_get_signal_handler(source_ptr)
 }, ObsSceneSignals for *mut obs_scene_t, [
@@ -6,9 +6,12 @@ pub use traits::SceneItemExtSceneTrait;
 use std::{fmt::Debug, hash::Hash, sync::Arc};
 
 use libobs::{obs_scene_item, obs_transform_info, obs_video_info};
+use num_traits::FromPrimitive;
 
 use crate::{
-    enums::ObsBoundsType,
+    enums::{
+        ObsAlignment, ObsBlendingMethod, ObsBlendingType, ObsBoundsType, ObsScaleType, OsEnumType,
+    },
     graphics::Vec2,
     impl_obs_drop,
     macros::trait_with_optional_send_sync,
@@ -63,14 +66,14 @@ impl<T: ObsSourceTrait + Clone> ObsSceneItemRef<T> {
         let scene_ptr = scene.as_ptr();
         let source_ptr = source.as_ptr();
 
-        let scene_item_ptr = run_with_obs!(runtime, (scene_ptr, source_ptr), move || {
+        let scene_item_ptr = run_with_obs!(runtime, (scene_ptr, source_ptr), move |token| {
             let ptr = unsafe {
                 // Safety: The pointers are valid as they are safe pointers
-                libobs::obs_scene_add(scene_ptr.get_ptr(), source_ptr.get_ptr())
+                libobs::obs_scene_add(scene_ptr.get_ptr(token), source_ptr.get_ptr(token))
             };
 
             if ptr.is_null() {
-                Err(ObsError::NullPointer(None))
+                Err(ObsError::NullPointer(None).in_function("obs_scene_add"))
             } else {
                 Ok(Sendable(ptr))
             }
@@ -102,14 +105,14 @@ trait_with_optional_send_sync! {
         /// Gets the transform info of the given source in this scene.
         fn get_transform_info(&self) -> Result<ObsTransformInfo, ObsError> {
             let self_ptr = self.as_ptr();
-            let item_info = run_with_obs!(self.runtime(), (self_ptr), move || {
+            let item_info = run_with_obs!(self.runtime(), (self_ptr), move |token| {
                 let mut item_info: obs_transform_info = unsafe {
                     // Safety: this is safe to call because we are filling a struct with zeros
                     std::mem::zeroed()
                 };
                 unsafe {
                     // Safety: Fill the transform info struct with the data
-                    libobs::obs_sceneitem_get_info2(self_ptr.get_ptr(), &mut item_info)
+                    libobs::obs_sceneitem_get_info2(self_ptr.get_ptr(token), &mut item_info)
                 };
 
                 ObsTransformInfo(item_info)
@@ -121,13 +124,13 @@ trait_with_optional_send_sync! {
         /// Gets the position of the given source in this scene.
         fn get_source_position(&self) -> Result<Vec2, ObsError> {
             let self_ptr = self.as_ptr();
-            let position = run_with_obs!(self.runtime(), (self_ptr), move || {
+            let position = run_with_obs!(self.runtime(), (self_ptr), move |token| {
                 let main_pos = unsafe {
                     // Safety: this is safe to call because we a filling a struct with zeros
                     let mut main_pos: libobs::vec2 = std::mem::zeroed();
 
                     // Safety: Fill the vec2 struct with the position data
-                    libobs::obs_sceneitem_get_pos(self_ptr.get_ptr(), &mut main_pos);
+                    libobs::obs_sceneitem_get_pos(self_ptr.get_ptr(token), &mut main_pos);
 
                     main_pos
                 };
@@ -141,13 +144,13 @@ trait_with_optional_send_sync! {
         /// Gets the scale of the given source in this scene.
         fn get_source_scale(&self) -> Result<Vec2, ObsError> {
             let self_ptr = self.as_ptr();
-            let scale = run_with_obs!(self.runtime(), (self_ptr), move || {
+            let scale = run_with_obs!(self.runtime(), (self_ptr), move |token| {
                 let main_pos = unsafe {
                     // Safety: this is safe to call because we a filling a struct with zeros
                     let mut main_pos: libobs::vec2 = std::mem::zeroed();
 
                     // Safety: Fill the vec2 struct with the scale data, this using the correct size
-                    libobs::obs_sceneitem_get_scale(self_ptr.get_ptr(), &mut main_pos);
+                    libobs::obs_sceneitem_get_scale(self_ptr.get_ptr(token), &mut main_pos);
 
                     main_pos
                 };
@@ -162,12 +165,12 @@ trait_with_optional_send_sync! {
         fn set_source_position(&self, position: Vec2) -> Result<(), ObsError> {
             let self_ptr = self.as_ptr();
 
-            run_with_obs!(self.runtime(), (self_ptr), move || {
+            run_with_obs!(self.runtime(), (self_ptr), move |token| {
                 let position: libobs::vec2 = position.into();
 
                 unsafe {
                     // Safety: The pointer is valid as it is a safe pointer
-                    libobs::obs_sceneitem_set_pos(self_ptr.get_ptr(), &position);
+                    libobs::obs_sceneitem_set_pos(self_ptr.get_ptr(token), &position);
                 }
             })?;
 
@@ -180,12 +183,12 @@ trait_with_optional_send_sync! {
             let item_info = Sendable(info.clone());
             let self_ptr = self.as_ptr();
 
-            run_with_obs!(self.runtime(), (self_ptr, item_info), move || {
+            run_with_obs!(self.runtime(), (self_ptr, item_info), move |token| {
                 let item_info = item_info.0 .0;
 
                 unsafe {
                     // Safety: The pointers are valid as they are safe pointers
-                    libobs::obs_sceneitem_set_info2(self_ptr.get_ptr(), &item_info);
+                    libobs::obs_sceneitem_set_info2(self_ptr.get_ptr(token), &item_info);
                 }
             })?;
 
@@ -199,9 +202,9 @@ trait_with_optional_send_sync! {
         fn fit_source_to_screen(&self) -> Result<bool, ObsError> {
             let self_ptr = self.as_ptr();
             let is_locked = {
-                run_with_obs!(self.runtime(), (self_ptr), move || unsafe {
+                run_with_obs!(self.runtime(), (self_ptr), move |token| unsafe {
                     // Safety: The pointer is valid as it is a safe pointer
-                    libobs::obs_sceneitem_locked(self_ptr.get_ptr())
+                    libobs::obs_sceneitem_locked(self_ptr.get_ptr(token))
                 })?
             };
 
@@ -230,10 +233,10 @@ trait_with_optional_send_sync! {
                 }
             })??;
 
-            let bounds_crop = run_with_obs!(self.runtime(), (self_ptr), move || {
+            let bounds_crop = run_with_obs!(self.runtime(), (self_ptr), move |token| {
                 unsafe {
                     // Safety: The pointer is valid as it is a safe pointer
-                    libobs::obs_sceneitem_get_bounds_crop(self_ptr.get_ptr())
+                    libobs::obs_sceneitem_get_bounds_crop(self_ptr.get_ptr(token))
                 }
             })?;
 
@@ -251,17 +254,169 @@ trait_with_optional_send_sync! {
         fn set_source_scale(&self, scale: Vec2) -> Result<(), ObsError> {
             let self_ptr = self.as_ptr();
 
-            run_with_obs!(self.runtime(), (self_ptr), move || {
+            run_with_obs!(self.runtime(), (self_ptr), move |token| {
                 let scale: libobs::vec2 = scale.into();
 
                 unsafe {
                     // Safety: The pointer is valid as it is a safe pointer
-                    libobs::obs_sceneitem_set_scale(self_ptr.get_ptr(), &scale);
+                    libobs::obs_sceneitem_set_scale(self_ptr.get_ptr(token), &scale);
                 }
             })?;
 
             Ok(())
         }
+
+        /// Gets the rotation of the given source in this scene in degrees.
+        fn get_rotation(&self) -> Result<f32, ObsError> {
+            let self_ptr = self.as_ptr();
+            run_with_obs!(self.runtime(), (self_ptr), move |token| unsafe {
+                // Safety: The pointer is valid as it is a safe pointer
+                libobs::obs_sceneitem_get_rot(self_ptr.get_ptr(token))
+            })
+        }
+
+        /// Sets the rotation of the given source in this scene in degrees, clockwise around
+        /// the alignment point.
+        fn set_rotation(&self, degrees: f32) -> Result<(), ObsError> {
+            let self_ptr = self.as_ptr();
+            run_with_obs!(self.runtime(), (self_ptr), move |token| unsafe {
+                // Safety: The pointer is valid as it is a safe pointer
+                libobs::obs_sceneitem_set_rot(self_ptr.get_ptr(token), degrees);
+            })
+        }
+
+        /// Gets the alignment of the given source in this scene, which is the point the
+        /// position refers to and the source is rotated around.
+        fn get_alignment(&self) -> Result<ObsAlignment, ObsError> {
+            let self_ptr = self.as_ptr();
+            let alignment = run_with_obs!(self.runtime(), (self_ptr), move |token| unsafe {
+                // Safety: The pointer is valid as it is a safe pointer
+                libobs::obs_sceneitem_get_alignment(self_ptr.get_ptr(token))
+            })?;
+
+            Ok(ObsAlignment::from_bits_truncate(alignment))
+        }
+
+        /// Sets the alignment of the given source in this scene.
+        /// Use alignment constants like so: `ObsAlignment::LEFT | ObsAlignment::TOP`
+        fn set_alignment(&self, alignment: ObsAlignment) -> Result<(), ObsError> {
+            let self_ptr = self.as_ptr();
+            let alignment = alignment.bits();
+            run_with_obs!(self.runtime(), (self_ptr), move |token| unsafe {
+                // Safety: The pointer is valid as it is a safe pointer
+                libobs::obs_sceneitem_set_alignment(self_ptr.get_ptr(token), alignment);
+            })
+        }
+
+        /// Gets the size of the bounding box of the given source in this scene and how the
+        /// source is fitted into it.
+        fn get_bounds(&self) -> Result<(Vec2, ObsBoundsType), ObsError> {
+            let self_ptr = self.as_ptr();
+            let (bounds, bounds_type) = run_with_obs!(self.runtime(), (self_ptr), move |token| {
+                unsafe {
+                    // Safety: this is safe to call because we a filling a struct with zeros
+                    let mut bounds: libobs::vec2 = std::mem::zeroed();
+
+                    // Safety: The pointer is valid as it is a safe pointer
+                    libobs::obs_sceneitem_get_bounds(self_ptr.get_ptr(token), &mut bounds);
+                    let bounds_type = libobs::obs_sceneitem_get_bounds_type(self_ptr.get_ptr(token));
+
+                    (Vec2::from(bounds), bounds_type)
+                }
+            })?;
+
+            let bounds_type = ObsBoundsType::from_i64(bounds_type as i64).ok_or_else(|| {
+                ObsError::Unexpected(format!("Unknown bounds type {}", bounds_type))
+            })?;
+
+            Ok((bounds, bounds_type))
+        }
+
+        /// Sets the size of the bounding box of the given source in this scene and how the
+        /// source is fitted into it. `ObsBoundsType::None` disables the bounding box.
+        fn set_bounds(&self, bounds: Vec2, bounds_type: ObsBoundsType) -> Result<(), ObsError> {
+            let self_ptr = self.as_ptr();
+            let bounds_type = bounds_type as OsEnumType;
+            run_with_obs!(self.runtime(), (self_ptr), move |token| {
+                let bounds: libobs::vec2 = bounds.into();
+
+                unsafe {
+                    // Safety: The pointer is valid as it is a safe pointer
+                    libobs::obs_sceneitem_set_bounds_type(self_ptr.get_ptr(token), bounds_type);
+                    libobs::obs_sceneitem_set_bounds(self_ptr.get_ptr(token), &bounds);
+                }
+            })
+        }
+
+        /// Sets the algorithm used to scale the source in this scene,
+        /// `ObsScaleType::Disable` uses the default scaling of the scene.
+        fn set_scale_filter(&self, filter: ObsScaleType) -> Result<(), ObsError> {
+            let self_ptr = self.as_ptr();
+            let filter = filter as OsEnumType;
+
+            run_with_obs!(self.runtime(), (self_ptr), move |token| unsafe {
+                // Safety: The pointer is valid as it is a safe pointer
+                libobs::obs_sceneitem_set_scale_filter(self_ptr.get_ptr(token), filter);
+            })
+        }
+
+        /// Gets the algorithm used to scale the source in this scene.
+        fn get_scale_filter(&self) -> Result<ObsScaleType, ObsError> {
+            let self_ptr = self.as_ptr();
+            let filter = run_with_obs!(self.runtime(), (self_ptr), move |token| unsafe {
+                // Safety: The pointer is valid as it is a safe pointer
+                libobs::obs_sceneitem_get_scale_filter(self_ptr.get_ptr(token))
+            })?;
+
+            ObsScaleType::from_i64(filter as i64)
+                .ok_or_else(|| ObsError::Unexpected(format!("Unknown scale filter {}", filter)))
+        }
+
+        /// Sets how this item is blended with the items below it.
+        fn set_blending_mode(&self, mode: ObsBlendingType) -> Result<(), ObsError> {
+            let self_ptr = self.as_ptr();
+            let mode = mode as OsEnumType;
+
+            run_with_obs!(self.runtime(), (self_ptr), move |token| unsafe {
+                // Safety: The pointer is valid as it is a safe pointer
+                libobs::obs_sceneitem_set_blending_mode(self_ptr.get_ptr(token), mode);
+            })
+        }
+
+        /// Gets how this item is blended with the items below it.
+        fn get_blending_mode(&self) -> Result<ObsBlendingType, ObsError> {
+            let self_ptr = self.as_ptr();
+            let mode = run_with_obs!(self.runtime(), (self_ptr), move |token| unsafe {
+                // Safety: The pointer is valid as it is a safe pointer
+                libobs::obs_sceneitem_get_blending_mode(self_ptr.get_ptr(token))
+            })?;
+
+            ObsBlendingType::from_i64(mode as i64)
+                .ok_or_else(|| ObsError::Unexpected(format!("Unknown blending mode {}", mode)))
+        }
+
+        /// Sets in which color space this item is blended.
+        fn set_blending_method(&self, method: ObsBlendingMethod) -> Result<(), ObsError> {
+            let self_ptr = self.as_ptr();
+            let method = method as OsEnumType;
+
+            run_with_obs!(self.runtime(), (self_ptr), move |token| unsafe {
+                // Safety: The pointer is valid as it is a safe pointer
+                libobs::obs_sceneitem_set_blending_method(self_ptr.get_ptr(token), method);
+            })
+        }
+
+        /// Gets in which color space this item is blended.
+        fn get_blending_method(&self) -> Result<ObsBlendingMethod, ObsError> {
+            let self_ptr = self.as_ptr();
+            let method = run_with_obs!(self.runtime(), (self_ptr), move |token| unsafe {
+                // Safety: The pointer is valid as it is a safe pointer
+                libobs::obs_sceneitem_get_blending_method(self_ptr.get_ptr(token))
+            })?;
+
+            ObsBlendingMethod::from_i64(method as i64)
+                .ok_or_else(|| ObsError::Unexpected(format!("Unknown blending method {}", method)))
+        }
     }
 }
 
@@ -303,7 +458,7 @@ where
 
 impl<T: ObsSourceTrait + Clone> PartialEq for ObsSceneItemRef<T> {
     fn eq(&self, other: &Self) -> bool {
-        self.scene_item_ptr.get_ptr() == other.scene_item_ptr.get_ptr()
+        self.scene_item_ptr.ptr_eq(&other.scene_item_ptr)
     }
 }
 
@@ -311,6 +466,7 @@ impl<T: ObsSourceTrait + Clone> Eq for ObsSceneItemRef<T> {}
 
 impl<T: ObsSourceTrait + Clone> Hash for ObsSceneItemRef<T> {
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-        self.scene_item_ptr.get_ptr().hash(state);
+        // Safety: The pointer is only hashed
+        unsafe { self.scene_item_ptr.get_ptr_unchecked() }.hash(state);
     }
 }
@@ -90,14 +90,14 @@ impl SceneItemExtSceneTrait for ObsSceneRef {
         &mut self,
         source: T,
     ) -> Result<(), ObsError> {
-        let source_ptr = source.as_ptr().get_ptr();
+        let source_ptr = source.as_ptr();
 
         self.attached_scene_items
             .write()
             .map_err(|e| ObsError::LockError(format!("{:?}", e)))?
             .retain(|s, _| {
                 //TODO: Maybe find a better way to utilize the HashMap's capabilities here
-                s.as_ptr().get_ptr() != source_ptr
+                !s.as_ptr().ptr_eq(&source_ptr)
             });
 
         Ok(())
@@ -112,7 +112,7 @@ impl SceneItemExtSceneTrait for ObsSceneRef {
         guard.retain(|_, items| {
             items.retain(|item| {
                 // Keep everything except this one scene item
-                item.as_ptr().get_ptr() != scene_item.as_ptr().get_ptr()
+                !item.as_ptr().ptr_eq(scene_item.as_ptr())
             });
             // Remove the entry if no items remain
             !items.is_empty()
@@ -142,7 +142,7 @@ impl SceneItemExtSceneTrait for ObsSceneRef {
         let res = guard
             .iter()
             .find_map(|(s, scene_item_pointers)| {
-                if s.as_ptr().get_ptr() == source.as_ptr().get_ptr() {
+                if s.as_ptr().ptr_eq(&source.as_ptr()) {
                     Some(scene_item_pointers.clone())
                 } else {
                     None
@@ -0,0 +1,307 @@
+use std::{collections::HashMap, ffi::CStr, path::Path};
+
+use libobs::obs_data_t;
+
+use crate::{
+    data::{ObsData, ObsDataGetters, ObsDataPointers},
+    run_with_obs,
+    scenes::{ObsSceneRef, SceneDescription, SceneOperation, SceneSourceDescription},
+    utils::{ObsError, ObsString},
+};
+
+/// Placeholder for the base directory of a template, see [`ObsSceneRef::export_template`].
+pub const SCENE_TEMPLATE_BASE_DIR: &str = "${base}";
+
+/// Settings that contain the id of a monitor, e.g. of the monitor capture on Windows or the
+/// screen capture on macOS.
+const MONITOR_KEYS: &[&str] = &["monitor_id", "display_uuid"];
+
+/// Settings that identify a window or an application, e.g. `title:class:executable` of the
+/// window and game capture on Windows.
+const WINDOW_KEYS: &[&str] = &["window", "capture_window", "application"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ScenePlaceholderKind {
+    /// An absolute path outside of the base directory
+    File,
+    Monitor,
+    Window,
+}
+
+/// A machine-specific value that has been replaced with `${name}` in the settings of a template.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ScenePlaceholder {
+    /// The name of the placeholder, e.g. `file1`
+    pub name: String,
+    pub kind: ScenePlaceholderKind,
+    /// The value on the machine the template has been exported on
+    pub original: String,
+    /// The names of the sources that use this placeholder
+    pub sources: Vec<String>,
+}
+
+impl ScenePlaceholder {
+    /// The text that replaces the value in the settings, e.g. `${file1}`
+    pub fn token(&self) -> String {
+        format!("${{{}}}", self.name)
+    }
+}
+
+/// A [`SceneDescription`] that can be shared between machines, created by
+/// [`ObsSceneRef::export_template`] and applied with [`ObsSceneRef::import_template`].
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SceneTemplate {
+    pub description: SceneDescription,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub placeholders: Vec<ScenePlaceholder>,
+}
+
+/// Collects the placeholders while the settings of the sources are rewritten.
+#[derive(Debug, Default)]
+pub(super) struct PlaceholderRegistry {
+    base_dir: Option<String>,
+    pub(super) placeholders: Vec<ScenePlaceholder>,
+}
+
+impl PlaceholderRegistry {
+    pub(super) fn new(base_dir: Option<&Path>) -> Self {
+        Self {
+            base_dir: base_dir.map(|dir| normalize_path(&dir.to_string_lossy())),
+            placeholders: vec![],
+        }
+    }
+
+    /// Returns the portable replacement of a setting, `None` if the value can be kept.
+    pub(super) fn rewrite(&mut self, source: &str, key: &str, value: &str) -> Option<String> {
+        let kind = if MONITOR_KEYS.contains(&key) {
+            ScenePlaceholderKind::Monitor
+        } else if WINDOW_KEYS.contains(&key) {
+            ScenePlaceholderKind::Window
+        } else if is_absolute_path(value) {
+            if let Some(relative) = self.relative_to_base(value) {
+                return Some(format!("{}/{}", SCENE_TEMPLATE_BASE_DIR, relative));
+            }
+
+            ScenePlaceholderKind::File
+        } else {
+            return None;
+        };
+
+        if value.is_empty() {
+            return None;
+        }
+
+        let existing = self
+            .placeholders
+            .iter_mut()
+            .find(|p| p.kind == kind && p.original == value);
+
+        let placeholder = match existing {
+            Some(placeholder) => placeholder,
+            None => {
+                let prefix = match kind {
+                    ScenePlaceholderKind::File => "file",
+                    ScenePlaceholderKind::Monitor => "monitor",
+                    ScenePlaceholderKind::Window => "window",
+                };
+                let count = self.placeholders.iter().filter(|p| p.kind == kind).count();
+
+                self.placeholders.push(ScenePlaceholder {
+                    name: format!("{}{}", prefix, count + 1),
+                    kind,
+                    original: value.to_string(),
+                    sources: vec![],
+                });
+                self.placeholders.last_mut().unwrap()
+            }
+        };
+
+        if !placeholder.sources.iter().any(|s| s == source) {
+            placeholder.sources.push(source.to_string());
+        }
+
+        Some(placeholder.token())
+    }
+
+    fn relative_to_base(&self, value: &str) -> Option<String> {
+        let base = self.base_dir.as_ref()?;
+        let value = normalize_path(value);
+
+        let relative = value.strip_prefix(base.as_str())?.strip_prefix('/')?;
+        Some(relative.to_string())
+    }
+}
+
+fn normalize_path(path: &str) -> String {
+    path.replace('\\', "/").trim_end_matches('/').to_string()
+}
+
+/// Checks for Unix, Windows drive and UNC paths, independent of the current platform.
+fn is_absolute_path(value: &str) -> bool {
+    let bytes = value.as_bytes();
+    let unix = bytes.len() > 1 && bytes[0] == b'/' && bytes[1] != b'/';
+    let drive = bytes.len() > 2
+        && bytes[0].is_ascii_alphabetic()
+        && bytes[1] == b':'
+        && (bytes[2] == b'\\' || bytes[2] == b'/');
+    let unc = value.starts_with("\\\\");
+
+    unix || drive || unc
+}
+
+/// Replaces every token in `value`, `None` if nothing has been replaced.
+pub(super) fn resolve_tokens(value: &str, values: &HashMap<String, String>) -> Option<String> {
+    let mut resolved = value.to_string();
+    for (token, replacement) in values {
+        resolved = resolved.replace(token.as_str(), replacement);
+    }
+
+    (resolved != value).then_some(resolved)
+}
+
+/// Calls `rewrite` with the key and value of every string setting, including those in nested
+/// objects and arrays, and stores the returned values.
+///
+/// # Safety
+/// The pointer must be valid and this must be called in the OBS runtime.
+#[allow(unknown_lints)]
+#[allow(ensure_obs_call_in_runtime)]
+unsafe fn rewrite_strings(
+    data: *mut obs_data_t,
+    rewrite: &mut dyn FnMut(&str, &str) -> Option<String>,
+) {
+    // Setting a value can reallocate the item, so the changes are stored after iterating
+    let mut changes = vec![];
+    let mut item = libobs::obs_data_first(data);
+    while !item.is_null() {
+        match libobs::obs_data_item_gettype(item) {
+            libobs::obs_data_type_OBS_DATA_STRING if libobs::obs_data_item_has_user_value(item) => {
+                let key = CStr::from_ptr(libobs::obs_data_item_get_name(item)).to_string_lossy();
+                let value = libobs::obs_data_item_get_string(item);
+                if !value.is_null() {
+                    let value = CStr::from_ptr(value).to_string_lossy();
+                    if let Some(new_value) = rewrite(&key, &value) {
+                        changes.push((ObsString::from(key.as_ref()), ObsString::from(new_value)));
+                    }
+                }
+            }
+            libobs::obs_data_type_OBS_DATA_OBJECT => {
+                let obj = libobs::obs_data_item_get_obj(item);
+                if !obj.is_null() {
+                    rewrite_strings(obj, rewrite);
+                    libobs::obs_data_release(obj);
+                }
+            }
+            libobs::obs_data_type_OBS_DATA_ARRAY => {
+                let array = libobs::obs_data_item_get_array(item);
+                if !array.is_null() {
+                    for index in 0..libobs::obs_data_array_count(array) {
+                        let obj = libobs::obs_data_array_item(array, index);
+                        if !obj.is_null() {
+                            rewrite_strings(obj, rewrite);
+                            libobs::obs_data_release(obj);
+                        }
+                    }
+
+                    libobs::obs_data_array_release(array);
+                }
+            }
+            _ => {}
+        }
+
+        libobs::obs_data_item_next(&mut item);
+    }
+
+    for (key, value) in changes {
+        libobs::obs_data_set_string(data, key.as_ptr().0, value.as_ptr().0);
+    }
+}
+
+impl ObsSceneRef {
+    /// Exports the sources attached through this `ObsSceneRef` as a template that can be
+    /// imported on another machine.
+    ///
+    /// Absolute paths within `base_dir` are stored relative to it, e.g. `${base}/images/logo.png`.
+    /// Other absolute paths, monitor ids and window identifiers are replaced with placeholders,
+    /// which are resolved when the template is imported.
+    pub fn export_template(&self, base_dir: Option<&Path>) -> Result<SceneTemplate, ObsError> {
+        let mut registry = PlaceholderRegistry::new(base_dir);
+        let mut sources = vec![];
+
+        for (source, live) in self.live_sources()? {
+            let settings = source.settings()?.to_mutable()?;
+            let settings_ptr = settings.as_ptr();
+            let name = live.name.clone();
+
+            registry = run_with_obs!(self.runtime, (settings_ptr), move |token| unsafe {
+                // Safety: The settings are kept alive by the smart pointer
+                let mut registry = registry;
+                rewrite_strings(settings_ptr.get_ptr(token), &mut |key, value| {
+                    registry.rewrite(&name, key, value)
+                });
+
+                registry
+            })?;
+
+            let mut description = SceneSourceDescription::new(live.name, live.id);
+            description.settings = Some(settings.get_json()?);
+            description.transform = live.transform;
+            description.visible = Some(live.visible);
+            sources.push(description);
+        }
+
+        Ok(SceneTemplate {
+            description: SceneDescription { sources },
+            placeholders: registry.placeholders,
+        })
+    }
+
+    /// Applies a template created by [`ObsSceneRef::export_template`] to this scene, see
+    /// [`ObsSceneRef::apply`].
+    ///
+    /// `${base}` is replaced with `base_dir`. `resolve` is called once for every other
+    /// placeholder and returns the value on this machine, e.g. after asking the user to pick
+    /// a monitor. If it returns `None`, the original value of the exporting machine is used.
+    pub fn import_template(
+        &mut self,
+        template: &SceneTemplate,
+        base_dir: &Path,
+        mut resolve: impl FnMut(&ScenePlaceholder) -> Option<String>,
+    ) -> Result<Vec<SceneOperation>, ObsError> {
+        let mut values = HashMap::new();
+        values.insert(
+            SCENE_TEMPLATE_BASE_DIR.to_string(),
+            normalize_path(&base_dir.to_string_lossy()),
+        );
+
+        for placeholder in &template.placeholders {
+            let value = resolve(placeholder).unwrap_or_else(|| placeholder.original.clone());
+            values.insert(placeholder.token(), value);
+        }
+
+        let mut description = template.description.clone();
+        for source in description.sources.iter_mut() {
+            let Some(json) = &source.settings else {
+                continue;
+            };
+
+            let settings = ObsData::from_json(json, self.runtime.clone())?;
+            let settings_ptr = settings.as_ptr();
+            let values = values.clone();
+
+            run_with_obs!(self.runtime, (settings_ptr), move |token| unsafe {
+                // Safety: The settings are kept alive by the smart pointer
+                rewrite_strings(settings_ptr.get_ptr(token), &mut |_, value| {
+                    resolve_tokens(value, &values)
+                });
+            })?;
+
+            source.settings = Some(settings.get_json()?);
+        }
+
+        self.apply(&description)
+    }
+}
@@ -0,0 +1,58 @@
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, path::Path};
+
+    use super::super::template::{resolve_tokens, PlaceholderRegistry};
+
+    #[test]
+    fn registry_rewrites_machine_specific_values() {
+        let mut registry = PlaceholderRegistry::new(Some(Path::new("C:\\Users\\me\\stream")));
+
+        assert_eq!(
+            registry.rewrite("logo", "file", "C:\\Users\\me\\stream\\images\\logo.png"),
+            Some("${base}/images/logo.png".to_string())
+        );
+        assert_eq!(
+            registry.rewrite("music", "local_file", "D:/music/intro.mp3"),
+            Some("${file1}".to_string())
+        );
+        assert_eq!(
+            registry.rewrite("screen", "monitor_id", "\\\\?\\DISPLAY#GSM5B08"),
+            Some("${monitor1}".to_string())
+        );
+        assert_eq!(
+            registry.rewrite("game", "window", "Game:UnityWndClass:game.exe"),
+            Some("${window1}".to_string())
+        );
+        assert_eq!(
+            registry.rewrite("music 2", "local_file", "D:/music/intro.mp3"),
+            Some("${file1}".to_string())
+        );
+        assert_eq!(registry.rewrite("text", "text", "Hello"), None);
+        assert_eq!(
+            registry.rewrite("browser", "url", "https://example.com/a"),
+            None
+        );
+
+        assert_eq!(registry.placeholders.len(), 3);
+        assert_eq!(registry.placeholders[0].sources, ["music", "music 2"]);
+    }
+
+    #[test]
+    fn tokens_are_resolved() {
+        let values = HashMap::from([
+            ("${base}".to_string(), "/home/me/stream".to_string()),
+            ("${file1}".to_string(), "/home/me/intro.mp3".to_string()),
+        ]);
+
+        assert_eq!(
+            resolve_tokens("${base}/images/logo.png", &values),
+            Some("/home/me/stream/images/logo.png".to_string())
+        );
+        assert_eq!(
+            resolve_tokens("${file1}", &values),
+            Some("/home/me/intro.mp3".to_string())
+        );
+        assert_eq!(resolve_tokens("Hello", &values), None);
+    }
+}
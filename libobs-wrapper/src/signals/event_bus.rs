@@ -0,0 +1,484 @@
+use std::{
+    collections::HashSet,
+    ffi::{c_void, CStr},
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use libobs::{calldata_t, obs_source_t};
+use tokio::sync::broadcast;
+
+use crate::{
+    data::output::ObsOutputTrait,
+    run_with_obs,
+    runtime::ObsRuntime,
+    signals::{ObsRawSignalConnection, ObsSignalReceiver},
+    unsafe_send::Sendable,
+    utils::ObsError,
+};
+
+/// The number of events that are buffered per receiver of an `ObsEventBus` until the oldest ones are dropped.
+pub const DEFAULT_EVENT_CAPACITY: usize = 256;
+
+/// An event of an `ObsEventBus`. Objects are identified by their name, as the event may be
+/// received after the object has already been destroyed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ObsEvent {
+    SourceCreated {
+        name: String,
+        id: String,
+    },
+    /// The source is about to be destroyed, because its last reference has been released
+    SourceDestroyed {
+        name: String,
+    },
+    /// The source has been removed by `obs_source_remove`, e.g. when it was deleted by the user
+    SourceRemoved {
+        name: String,
+    },
+    SourceRenamed {
+        old_name: String,
+        new_name: String,
+    },
+    SceneItemAdded {
+        scene: String,
+        source: String,
+        item_id: i64,
+    },
+    SceneItemRemoved {
+        scene: String,
+        source: String,
+        item_id: i64,
+    },
+    OutputStarted {
+        output: String,
+    },
+    /// The output stopped with the given code, which is `0` if it stopped successfully
+    OutputStopped {
+        output: String,
+        code: i64,
+    },
+    OutputPaused {
+        output: String,
+    },
+    OutputUnpaused {
+        output: String,
+    },
+    OutputReconnecting {
+        output: String,
+    },
+    OutputReconnected {
+        output: String,
+    },
+}
+
+impl ObsEvent {
+    /// Returns the name of the source the event is about, which is the source of the item for scene item events.
+    pub fn source_name(&self) -> Option<&str> {
+        match self {
+            ObsEvent::SourceCreated { name, .. }
+            | ObsEvent::SourceDestroyed { name }
+            | ObsEvent::SourceRemoved { name } => Some(name),
+            ObsEvent::SourceRenamed { new_name, .. } => Some(new_name),
+            ObsEvent::SceneItemAdded { source, .. } | ObsEvent::SceneItemRemoved { source, .. } => {
+                Some(source)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Reads a pointer from the calldata, null if it doesn't exist.
+///
+/// # Safety
+/// The calldata must be valid.
+unsafe fn calldata_ptr<T>(calldata: *mut calldata_t, name: &CStr) -> *mut T {
+    let mut ptr = std::ptr::null_mut::<T>();
+    libobs::calldata_get_data(
+        calldata,
+        name.as_ptr(),
+        &mut ptr as *mut *mut T as *mut c_void,
+        std::mem::size_of::<*mut T>(),
+    );
+    ptr
+}
+
+/// # Safety
+/// The calldata must be valid.
+unsafe fn calldata_int(calldata: *mut calldata_t, name: &CStr) -> i64 {
+    let mut value = 0i64;
+    libobs::calldata_get_data(
+        calldata,
+        name.as_ptr(),
+        &mut value as *mut i64 as *mut c_void,
+        std::mem::size_of::<i64>(),
+    );
+    value
+}
+
+/// # Safety
+/// The calldata must be valid.
+unsafe fn calldata_string(calldata: *mut calldata_t, name: &CStr) -> String {
+    let mut value = std::ptr::null();
+    if !libobs::calldata_get_string(calldata, name.as_ptr(), &mut value) || value.is_null() {
+        return String::new();
+    }
+
+    CStr::from_ptr(value).to_string_lossy().into_owned()
+}
+
+/// # Safety
+/// The string must be null or a valid C string.
+unsafe fn to_string(value: *const std::ffi::c_char) -> String {
+    if value.is_null() {
+        return String::new();
+    }
+
+    CStr::from_ptr(value).to_string_lossy().into_owned()
+}
+
+/// # Safety
+/// The source must be null or valid.
+unsafe fn source_name(source: *mut obs_source_t) -> String {
+    if source.is_null() {
+        return String::new();
+    }
+
+    to_string(libobs::obs_source_get_name(source))
+}
+
+/// Creates the event of an output signal from the name of the output and the calldata.
+type OutputEventFn = fn(String, *mut calldata_t) -> ObsEvent;
+
+#[derive(Debug, Default)]
+struct SceneConnections {
+    /// Set once the bus is dropped, so no new scenes are connected
+    closed: bool,
+    /// The scene sources whose item signals are connected
+    scenes: HashSet<usize>,
+}
+
+#[derive(Debug)]
+struct EventBusShared {
+    sender: broadcast::Sender<ObsEvent>,
+    scenes: Mutex<SceneConnections>,
+}
+
+impl EventBusShared {
+    fn send(&self, event: ObsEvent) {
+        // Sending only fails if nobody is subscribed
+        let _ = self.sender.send(event);
+    }
+
+    fn lock_scenes(&self) -> MutexGuard<'_, SceneConnections> {
+        self.scenes
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Connects the item signals of the source if it is a scene.
+    ///
+    /// # Safety
+    /// The source must be valid and this struct must outlive the connection, see `disconnect_scene`.
+    unsafe fn connect_scene(&self, source: *mut obs_source_t) {
+        if libobs::obs_scene_from_source(source).is_null() {
+            return;
+        }
+
+        let mut connections = self.lock_scenes();
+        if connections.closed || !connections.scenes.insert(source as usize) {
+            return;
+        }
+
+        let handler = libobs::obs_source_get_signal_handler(source);
+        let param = self as *const Self as *mut c_void;
+        libobs::signal_handler_connect(handler, c"item_add".as_ptr(), Some(item_add), param);
+        libobs::signal_handler_connect(handler, c"item_remove".as_ptr(), Some(item_remove), param);
+    }
+
+    /// # Safety
+    /// The source must be valid and its signal handler must not have been destroyed yet.
+    unsafe fn disconnect_scene(&self, source: *mut obs_source_t) {
+        let mut connections = self.lock_scenes();
+        if connections.scenes.remove(&(source as usize)) {
+            disconnect_item_signals(self, source);
+        }
+    }
+}
+
+/// # Safety
+/// The item signals of the scene must have been connected with this bus.
+unsafe fn disconnect_item_signals(shared: &EventBusShared, source: *mut obs_source_t) {
+    let handler = libobs::obs_source_get_signal_handler(source);
+    let param = shared as *const EventBusShared as *mut c_void;
+    libobs::signal_handler_disconnect(handler, c"item_add".as_ptr(), Some(item_add), param);
+    libobs::signal_handler_disconnect(handler, c"item_remove".as_ptr(), Some(item_remove), param);
+}
+
+/// # Safety
+/// The calldata must be the calldata of an `item_add` or `item_remove` signal.
+unsafe fn item_event(calldata: *mut calldata_t) -> (String, String, i64) {
+    let scene = calldata_ptr::<libobs::obs_scene_t>(calldata, c"scene");
+    let item = calldata_ptr::<libobs::obs_sceneitem_t>(calldata, c"item");
+
+    let scene = if scene.is_null() {
+        String::new()
+    } else {
+        source_name(libobs::obs_scene_get_source(scene))
+    };
+
+    if item.is_null() {
+        return (scene, String::new(), 0);
+    }
+
+    (
+        scene,
+        source_name(libobs::obs_sceneitem_get_source(item)),
+        libobs::obs_sceneitem_get_id(item),
+    )
+}
+
+#[allow(unknown_lints)]
+#[allow(ensure_obs_call_in_runtime)]
+/// # Safety
+/// Only called by OBS with the `EventBusShared` the signal has been connected with.
+unsafe extern "C" fn item_add(param: *mut c_void, calldata: *mut calldata_t) {
+    let shared = &*(param as *const EventBusShared);
+    let (scene, source, item_id) = item_event(calldata);
+    shared.send(ObsEvent::SceneItemAdded {
+        scene,
+        source,
+        item_id,
+    });
+}
+
+#[allow(unknown_lints)]
+#[allow(ensure_obs_call_in_runtime)]
+/// # Safety
+/// Only called by OBS with the `EventBusShared` the signal has been connected with.
+unsafe extern "C" fn item_remove(param: *mut c_void, calldata: *mut calldata_t) {
+    let shared = &*(param as *const EventBusShared);
+    let (scene, source, item_id) = item_event(calldata);
+    shared.send(ObsEvent::SceneItemRemoved {
+        scene,
+        source,
+        item_id,
+    });
+}
+
+#[allow(unknown_lints)]
+#[allow(ensure_obs_call_in_runtime)]
+/// # Safety
+/// Only called by `obs_enum_scenes` with the `EventBusShared` of the bus.
+unsafe extern "C" fn connect_existing_scene(param: *mut c_void, source: *mut obs_source_t) -> bool {
+    let shared = &*(param as *const EventBusShared);
+    shared.connect_scene(source);
+    true
+}
+
+/// A single stream of the most important events of every object, instead of subscribing to the
+/// signal manager of each object on its own. This simplifies keeping e.g. the state of a GUI in sync.
+///
+/// Source events are received for every public source, scene item events for every scene.
+/// Output events are only received for the outputs of the context at the time the bus was created
+/// and outputs added with `watch_output`.
+///
+/// ```no_run
+/// # use libobs_wrapper::{context::ObsContext, signals::ObsEvent};
+/// # fn example(context: &ObsContext) -> Result<(), libobs_wrapper::utils::ObsError> {
+/// let bus = context.event_bus()?;
+/// let mut events = bus.subscribe();
+///
+/// while let Ok(event) = events.blocking_recv_signal() {
+///     if let ObsEvent::SourceRenamed { old_name, new_name } = event {
+///         println!("{} is now called {}", old_name, new_name);
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct ObsEventBus {
+    connections: Mutex<Vec<ObsRawSignalConnection>>,
+    shared: Arc<EventBusShared>,
+    runtime: ObsRuntime,
+}
+
+impl ObsEventBus {
+    pub(crate) fn new(runtime: ObsRuntime) -> Result<Self, ObsError> {
+        let (sender, _) = broadcast::channel(DEFAULT_EVENT_CAPACITY);
+        let shared = Arc::new(EventBusShared {
+            sender,
+            scenes: Mutex::new(SceneConnections::default()),
+        });
+
+        let bus = Self {
+            connections: Mutex::new(Vec::new()),
+            shared,
+            runtime,
+        };
+
+        bus.connect_global("source_create", |shared, calldata| unsafe {
+            // Safety: The calldata and its source are valid while the signal is emitted
+            let source = calldata_ptr::<obs_source_t>(calldata, c"source");
+            if source.is_null() {
+                return;
+            }
+
+            shared.connect_scene(source);
+            shared.send(ObsEvent::SourceCreated {
+                name: source_name(source),
+                id: to_string(libobs::obs_source_get_id(source)),
+            });
+        })?;
+        bus.connect_global("source_destroy", |shared, calldata| unsafe {
+            // Safety: The calldata and its source are valid while the signal is emitted
+            let source = calldata_ptr::<obs_source_t>(calldata, c"source");
+            if source.is_null() {
+                return;
+            }
+
+            shared.disconnect_scene(source);
+            shared.send(ObsEvent::SourceDestroyed {
+                name: source_name(source),
+            });
+        })?;
+        bus.connect_global("source_remove", |shared, calldata| unsafe {
+            // Safety: The calldata and its source are valid while the signal is emitted
+            let source = calldata_ptr::<obs_source_t>(calldata, c"source");
+            shared.send(ObsEvent::SourceRemoved {
+                name: source_name(source),
+            });
+        })?;
+        bus.connect_global("source_rename", |shared, calldata| unsafe {
+            // Safety: The calldata and its source are valid while the signal is emitted
+            shared.send(ObsEvent::SourceRenamed {
+                old_name: calldata_string(calldata, c"prev_name"),
+                new_name: calldata_string(calldata, c"new_name"),
+            });
+        })?;
+
+        // Scenes created from now on are connected by `source_create`, the set prevents connecting them twice
+        let shared_ptr = Sendable(Arc::as_ptr(&bus.shared) as *mut c_void);
+        run_with_obs!(bus.runtime, (shared_ptr), move || unsafe {
+            // Safety: The shared data outlives every connection, they are removed when the bus is dropped
+            libobs::obs_enum_scenes(Some(connect_existing_scene), shared_ptr.0);
+        })?;
+
+        Ok(bus)
+    }
+
+    /// Connects a signal of the global signal handler of OBS.
+    ///
+    /// The callback is only called with valid calldata by the signal handler.
+    fn connect_global(
+        &self,
+        signal: &str,
+        callback: unsafe fn(&EventBusShared, *mut calldata_t),
+    ) -> Result<(), ObsError> {
+        let handler = run_with_obs!(self.runtime, move || unsafe {
+            // Safety: The global signal handler lives as long as OBS
+            Sendable(libobs::obs_get_signal_handler())
+        })?;
+
+        let shared = self.shared.clone();
+        let connection = ObsRawSignalConnection::connect(
+            handler,
+            signal,
+            Box::new(move |calldata| unsafe {
+                // Safety: The calldata is valid while the signal is emitted
+                callback(&shared, calldata.0)
+            }),
+            Box::new(()),
+            self.runtime.clone(),
+        )?;
+
+        self.lock_connections().push(connection);
+        Ok(())
+    }
+
+    fn lock_connections(&self) -> MutexGuard<'_, Vec<ObsRawSignalConnection>> {
+        self.connections
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Receives every event emitted from now on.
+    pub fn subscribe(&self) -> ObsSignalReceiver<ObsEvent> {
+        ObsSignalReceiver::new(self.shared.sender.subscribe(), "event_bus")
+    }
+
+    /// Adds the state changes of this output to the bus. The output is kept alive until the bus is dropped.
+    pub fn watch_output<T: ObsOutputTrait + ?Sized>(&self, output: &T) -> Result<(), ObsError> {
+        let output_ptr = output.as_ptr();
+        let name = output.name().to_string();
+
+        let handler_ptr = output_ptr.clone();
+        let handler = run_with_obs!(self.runtime, (handler_ptr), move |token| unsafe {
+            // Safety: The output is valid because of the smart pointer
+            Sendable(libobs::obs_output_get_signal_handler(
+                handler_ptr.get_ptr(token),
+            ))
+        })?;
+
+        let signals: [(&str, OutputEventFn); 6] = [
+            ("start", |output, _| ObsEvent::OutputStarted { output }),
+            ("stop", |output, calldata| ObsEvent::OutputStopped {
+                output,
+                code: unsafe {
+                    // Safety: The calldata is valid while the signal is emitted
+                    calldata_int(calldata, c"code")
+                },
+            }),
+            ("pause", |output, _| ObsEvent::OutputPaused { output }),
+            ("unpause", |output, _| ObsEvent::OutputUnpaused { output }),
+            ("reconnect", |output, _| ObsEvent::OutputReconnecting {
+                output,
+            }),
+            ("reconnect_success", |output, _| {
+                ObsEvent::OutputReconnected { output }
+            }),
+        ];
+
+        let mut connections = Vec::with_capacity(signals.len());
+        for (signal, to_event) in signals {
+            let shared = self.shared.clone();
+            let name = name.clone();
+            connections.push(ObsRawSignalConnection::connect(
+                handler.clone(),
+                signal,
+                Box::new(move |calldata| shared.send(to_event(name.clone(), calldata.0))),
+                Box::new(output_ptr.clone()),
+                self.runtime.clone(),
+            )?);
+        }
+
+        self.lock_connections().extend(connections);
+        Ok(())
+    }
+}
+
+impl Drop for ObsEventBus {
+    fn drop(&mut self) {
+        // The scenes are disconnected while the `source_destroy` handler is still connected,
+        // so none of them can be destroyed in the meantime
+        let shared_ptr = Sendable(Arc::as_ptr(&self.shared) as *mut EventBusShared);
+        let res = run_with_obs!(self.runtime, (shared_ptr), move || unsafe {
+            // Safety: The shared data is kept alive by the bus
+            let shared = &*shared_ptr.0;
+            let mut connections = shared.lock_scenes();
+            connections.closed = true;
+            for source in connections.scenes.drain() {
+                // Safety: Scenes are removed from the set before they are destroyed
+                disconnect_item_signals(shared, source as *mut obs_source_t);
+            }
+        });
+
+        if let Err(e) = res {
+            // The scenes might still be connected, so the shared data has to be leaked
+            log::warn!("Failed to disconnect the scenes of the event bus: {:?}", e);
+            std::mem::forget(self.shared.clone());
+        }
+
+        self.lock_connections().clear();
+    }
+}
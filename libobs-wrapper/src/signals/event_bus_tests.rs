@@ -0,0 +1,24 @@
+#[cfg(test)]
+mod tests {
+    use super::super::event_bus::ObsEvent;
+
+    #[test]
+    fn test_source_name() {
+        let renamed = ObsEvent::SourceRenamed {
+            old_name: "Old".to_string(),
+            new_name: "New".to_string(),
+        };
+        let item = ObsEvent::SceneItemAdded {
+            scene: "Scene".to_string(),
+            source: "Camera".to_string(),
+            item_id: 1,
+        };
+        let output = ObsEvent::OutputStarted {
+            output: "recording".to_string(),
+        };
+
+        assert_eq!(renamed.source_name(), Some("New"));
+        assert_eq!(item.source_name(), Some("Camera"));
+        assert_eq!(output.source_name(), None);
+    }
+}
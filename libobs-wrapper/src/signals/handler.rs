@@ -29,7 +29,7 @@ macro_rules! __signals_impl_primitive_handler {
     ($field_name: ident, String) => {
         move |__internal_calldata|  {
             let mut $field_name = std::ptr::null_mut();
-            let obs_str = $crate::utils::ObsString::new(stringify!($field_name));
+            let obs_str = $crate::obs_string!(stringify!($field_name));
             let success = libobs::calldata_get_string(
                 __internal_calldata,
                 obs_str.as_ptr().0,
@@ -55,7 +55,7 @@ macro_rules! __signals_impl_primitive_handler {
     (__inner, $field_name: ident, $field_type: ty) => {
         move |__internal_calldata| {
             let mut $field_name = std::mem::zeroed::<$field_type>();
-            let obs_str = $crate::utils::ObsString::new(stringify!($field_name));
+            let obs_str = $crate::obs_string!(stringify!($field_name));
             let success = libobs::calldata_get_data(
                 __internal_calldata,
                 obs_str.as_ptr().0,
@@ -75,7 +75,7 @@ macro_rules! __signals_impl_primitive_handler {
     (__ptr, $field_name: ident, $field_type: ty) => {
         move |__internal_calldata| {
             let mut $field_name = std::mem::zeroed::<$field_type>();
-            let obs_str = $crate::utils::ObsString::new(stringify!($field_name));
+            let obs_str = $crate::obs_string!(stringify!($field_name));
             let success = libobs::calldata_get_data(
                 __internal_calldata,
                 obs_str.as_ptr().0,
@@ -113,9 +113,6 @@ macro_rules! __signals_impl_signal {
     ($ptr: ty, $signal_name: literal, $field_name: ident: $gen_type:ty) => {
         paste::paste! {
             type [<__Private $signal_name:camel Type >] = $gen_type;
-            lazy_static::lazy_static! {
-                static ref [<$signal_name:snake:upper _SENDERS>]: std::sync::Arc<std::sync::RwLock<std::collections::HashMap<usize, tokio::sync::broadcast::Sender<$gen_type>>>> = std::sync::Arc::new(std::sync::RwLock::new(std::collections::HashMap::new()));
-            }
 
             #[allow(unknown_lints)]
             #[allow(ensure_obs_call_in_runtime)]
@@ -132,9 +129,6 @@ macro_rules! __signals_impl_signal {
     ($ptr: ty, $signal_name: literal, ) => {
         paste::paste! {
             type [<__Private $signal_name:camel Type >] = ();
-            lazy_static::lazy_static! {
-                static ref [<$signal_name:snake:upper _SENDERS>]: std::sync::Arc<std::sync::RwLock<std::collections::HashMap<usize, tokio::sync::broadcast::Sender<()>>>> = std::sync::Arc::new(std::sync::RwLock::new(std::collections::HashMap::new()));
-            }
 
             /// # Safety
             /// You must make sure that the calldata pointer is valid and this is running on the OBS runtime.
@@ -167,9 +161,6 @@ macro_rules! __signals_impl_signal {
     }) => {
         paste::paste! {
             type [<__Private $signal_name:camel Type >] = $name;
-            lazy_static::lazy_static! {
-                static ref [<$signal_name:snake:upper _SENDERS>]: std::sync::Arc<std::sync::RwLock<std::collections::HashMap<usize, tokio::sync::broadcast::Sender<$name>>>> = std::sync::Arc::new(std::sync::RwLock::new(std::collections::HashMap::new()));
-            }
 
             #[derive(Debug, Clone)]
             pub struct $name {
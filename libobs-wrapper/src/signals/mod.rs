@@ -1,8 +1,18 @@
 //! Signals can be emitted by sources attached to a scene. You may implement your own signal manager
 //! by using the `impl_signal_manager` macro, but you'll need to make sure that you know which signals are emitted and what structure they have.
+mod event_bus;
+#[cfg(test)]
+mod event_bus_tests;
 mod handler;
+mod raw;
+mod receiver;
+#[cfg(test)]
+mod receiver_tests;
 mod traits;
 
+pub use event_bus::{ObsEvent, ObsEventBus, DEFAULT_EVENT_CAPACITY};
+pub use raw::ObsRawSignalConnection;
+pub use receiver::*;
 pub use traits::*;
 
 /// Generates a signal manager for OBS objects that can emit signals.
@@ -10,14 +20,15 @@ pub use traits::*;
 /// This macro creates a complete signal management system including:
 /// - Signal handler functions that interface with OBS's C API
 /// - A manager struct that maintains signal subscriptions
-/// - Methods to subscribe to signals via `tokio::sync::broadcast` channels
+/// - Methods to subscribe to signals via `ObsSignalReceiver`s
 /// - Automatic cleanup on drop
 ///
 /// # Parameters
 ///
-/// * `$handler_getter` - A closure that takes a `SmartPointerSendable<$ptr>` and returns the raw signal handler pointer.
-///   The closure should have an explicit type annotation for the parameter and typically contains an unsafe block.
-///   Example: `|scene_ptr: SmartPointerSendable<*mut obs_scene_t>| unsafe { libobs::obs_scene_get_signal_handler(scene_ptr.get_ptr()) }`
+/// * `$handler_getter` - A closure that takes a `SmartPointerSendable<$ptr>` and the `ObsThreadToken` of the OBS thread
+///   and returns the raw signal handler pointer.
+///   The closure should have explicit type annotations for the parameters and typically contains an unsafe block.
+///   Example: `|scene_ptr: SmartPointerSendable<*mut obs_scene_t>, token: ObsThreadToken| unsafe { libobs::obs_scene_get_signal_handler(scene_ptr.get_ptr(token)) }`
 ///
 /// * `$name` - The identifier for the generated signal manager struct.
 ///
@@ -33,9 +44,12 @@ pub use traits::*;
 ///
 /// The macro generates:
 /// - A `$name` struct that manages all signal subscriptions for a single object instance
-/// - `on_<signal_name>()` methods that return `broadcast::Receiver` for each signal
+/// - `on_<signal_name>()` methods that return an `ObsSignalReceiver` for each signal
+/// - `set_<signal_name>_capacity()` methods to change how many signals are buffered per receiver
+/// - A `connect_raw()` method to listen to signals that are not declared in the macro
 /// - Automatic signal handler registration and cleanup
-/// - Thread-safe signal dispatching using `tokio::sync::broadcast`
+/// - Thread-safe signal dispatching using `tokio::sync::broadcast`, every manager owns its own
+///   channels, so receivers only get the signals of the object they subscribed to
 ///
 /// # Signal Data Types
 ///
@@ -56,8 +70,8 @@ pub use traits::*;
 ///
 /// ```ignore
 /// impl_signal_manager!(
-///     |scene_ptr: SmartPointerSendable<*mut obs_scene_t>| unsafe {
-///         let source_ptr = libobs::obs_scene_get_source(scene_ptr.get_ptr());
+///     |scene_ptr: SmartPointerSendable<*mut obs_scene_t>, token: ObsThreadToken| unsafe {
+///         let source_ptr = libobs::obs_scene_get_source(scene_ptr.get_ptr(token));
 ///         libobs::obs_source_get_signal_handler(source_ptr)
 ///     },
 ///     ObsSceneSignals for *mut obs_scene_t,
@@ -109,8 +123,11 @@ pub use traits::*;
 /// let mut rx = signals.on_refresh()?;
 ///
 /// tokio::spawn(async move {
-///     while let Ok(_) = rx.recv().await {
-///         println!("Scene refreshed!");
+///     while let Ok(event) = rx.recv().await {
+///         match event {
+///             ObsSignalEvent::Signal(_) => println!("Scene refreshed!"),
+///             ObsSignalEvent::Lagged(count) => println!("Missed {} refreshes", count),
+///         }
 ///     }
 /// });
 /// ```
@@ -122,10 +139,15 @@ macro_rules! impl_signal_manager {
         paste::paste! {
             $($crate::__signals_impl_signal!($ptr, $signal_name, $($inner_def)*);)*
 
-            $(
-            extern "C" fn [< $signal_name:snake _handler>](obj_ptr_key: *mut std::ffi::c_void, __internal_calldata: *mut libobs::calldata_t) {
-                let obj_ptr_key = obj_ptr_key as usize;
+            /// The senders of every signal, the address of this struct is passed to the
+            /// signal handlers, so each manager only dispatches to its own receivers.
+            #[derive(Debug)]
+            struct [<$name Senders>] {
+                $([<$signal_name:snake>]: std::sync::RwLock<tokio::sync::broadcast::Sender<[<__Private $signal_name:camel Type >]>>,)*
+            }
 
+            $(
+            unsafe extern "C" fn [< $signal_name:snake _handler>](senders: *mut std::ffi::c_void, __internal_calldata: *mut libobs::calldata_t) {
                 #[allow(unused_unsafe)]
                 let res = unsafe {
                     // Safety: We are in the runtime and the calldata pointer is valid because OBS is calling this function
@@ -136,22 +158,12 @@ macro_rules! impl_signal_manager {
                     return;
                 }
 
-                let res = res.unwrap();
-                let senders = [<$signal_name:snake:upper _SENDERS>].read();
-                if let Err(e) = senders {
-                    log::warn!("Failed to acquire read lock for signal {}: {}", stringify!($signal_name), e);
-                    return;
-                }
-
-                let senders = senders.unwrap();
-                let senders = senders.get(&obj_ptr_key);
-                if senders.is_none() {
-                    log::warn!("No sender found for signal {}", stringify!($signal_name));
-                    return;
-                }
-
-                let senders = senders.unwrap();
-                let _ = senders.send(res);
+                // Safety: The senders are only freed after every handler has been disconnected
+                let senders = unsafe { &*(senders as *const [<$name Senders>]) };
+                let sender = senders.[<$signal_name:snake>]
+                    .read()
+                    .unwrap_or_else(|poisoned| poisoned.into_inner());
+                let _ = sender.send(res.unwrap());
             })*
 
             /// This signal manager must be within an `Arc` if you want to clone it.
@@ -159,43 +171,33 @@ macro_rules! impl_signal_manager {
             pub struct $name {
                 runtime: $crate::runtime::ObsRuntime,
                 pointer: $crate::unsafe_send::SmartPointerSendable<$ptr>,
+                /// Owned by this manager, freed once the handlers are disconnected
+                senders: std::mem::ManuallyDrop<Box<[<$name Senders>]>>,
             }
 
             impl $name {
-                fn smart_ptr_to_key(ptr: &$crate::unsafe_send::SmartPointerSendable<$ptr>) -> usize {
-                    ptr.get_ptr() as usize
-                }
-
                 pub(crate) fn new(smart_ptr: &$crate::unsafe_send::SmartPointerSendable<$ptr>, runtime: $crate::runtime::ObsRuntime) -> Result<Self, $crate::utils::ObsError> {
-                    use $crate::utils::ObsString;
                     let smart_ptr = smart_ptr.clone();
-                    let smart_ptr_as_key = Self::smart_ptr_to_key(&smart_ptr);
 
-                    $(
-                        let senders = [<$signal_name:snake:upper _SENDERS>].clone();
-                        let senders = senders.write();
-                        if senders.is_err() {
-                            return Err($crate::utils::ObsError::LockError("Failed to acquire write lock for signal senders".to_string()));
-                        }
-
-                        let (tx, [<_ $signal_name:snake _rx>]) = tokio::sync::broadcast::channel(16);
-                        let mut senders = senders.unwrap();
-                        // Its fine since we are just using the pointer as key
-                        senders.insert(smart_ptr_as_key.clone(), tx);
-                    )*
+                    let senders = Box::new([<$name Senders>] {
+                        $([<$signal_name:snake>]: std::sync::RwLock::new(
+                            tokio::sync::broadcast::channel($crate::signals::DEFAULT_SIGNAL_CAPACITY).0
+                        ),)*
+                    });
+                    let senders_ptr = $crate::unsafe_send::Sendable(&*senders as *const [<$name Senders>] as *mut std::ffi::c_void);
 
-                    $crate::run_with_obs!(runtime, (smart_ptr_as_key, smart_ptr), move || {
-                            let handler = ($handler_getter)(smart_ptr);
+                    $crate::run_with_obs!(runtime, (senders_ptr, smart_ptr), move |token| {
+                            #[allow(unused_variables)]
+                            let handler = ($handler_getter)(smart_ptr, token);
                             $(
-                                let signal = ObsString::new($signal_name);
+                                let signal = $crate::obs_string!($signal_name);
                                 unsafe {
-                                    // Safety: We know that the handler must exist, the signal is still in scope, so the ptr to that is valid as well and we are just using the raw_ptr as key in the handler function.
+                                    // Safety: We know that the handler must exist, the signal is still in scope, so the ptr to that is valid as well and the senders live until the handler is disconnected.
                                     libobs::signal_handler_connect(
                                         handler,
                                         signal.as_ptr().0,
                                         Some([< $signal_name:snake _handler>]),
-                                        // We are just casting it back to a usize in the handler function
-                                        smart_ptr_as_key as *mut std::ffi::c_void,
+                                        senders_ptr.0,
                                     );
                                 };
                             )*
@@ -203,27 +205,62 @@ macro_rules! impl_signal_manager {
 
                     Ok(Self {
                         pointer: smart_ptr,
-                        runtime
+                        runtime,
+                        senders: std::mem::ManuallyDrop::new(senders),
                     })
                 }
 
                 $(
                     $(#[$attr])*
-                    pub fn [<on_ $signal_name:snake>](&self) -> Result<tokio::sync::broadcast::Receiver<[<__Private $signal_name:camel Type >]>, $crate::utils::ObsError> {
-                        let handlers = [<$signal_name:snake:upper _SENDERS>].read();
-                        if handlers.is_err() {
-                            return Err($crate::utils::ObsError::LockError("Failed to acquire read lock for signal senders".to_string()));
+                    pub fn [<on_ $signal_name:snake>](&self) -> Result<$crate::signals::ObsSignalReceiver<[<__Private $signal_name:camel Type >]>, $crate::utils::ObsError> {
+                        let receiver = self.senders.[<$signal_name:snake>]
+                            .read()
+                            .map_err(|e| $crate::utils::ObsError::LockError(e.to_string()))?
+                            .subscribe();
+
+                        Ok($crate::signals::ObsSignalReceiver::new(receiver, $signal_name))
+                    }
+
+                    #[doc = concat!("Sets how many `", $signal_name, "` signals are buffered until the oldest ones are dropped, ")]
+                    #[doc = concat!("defaults to `DEFAULT_SIGNAL_CAPACITY`. Receivers that have been created before are closed.")]
+                    pub fn [<set_ $signal_name:snake _capacity>](&self, capacity: usize) -> Result<(), $crate::utils::ObsError> {
+                        if capacity == 0 {
+                            return Err($crate::utils::ObsError::InvalidOperation(
+                                "The signal capacity must be greater than 0".into(),
+                            ));
                         }
 
-                        let handlers = handlers.unwrap();
-                        let handler_key = Self::smart_ptr_to_key(&self.pointer);
-                        let rx = handlers.get(&handler_key)
-                            .ok_or_else(|| $crate::utils::ObsError::NoSenderError)?
-                            .subscribe();
+                        *self.senders.[<$signal_name:snake>]
+                            .write()
+                            .map_err(|e| $crate::utils::ObsError::LockError(e.to_string()))? =
+                            tokio::sync::broadcast::channel(capacity).0;
 
-                        Ok(rx)
+                        Ok(())
                     }
                 )*
+
+                /// Connects a callback to a signal that is not declared in this manager.
+                ///
+                /// The callback receives the raw calldata of the signal, which is only valid during the call,
+                /// and is invoked on whatever thread OBS emits the signal on. It is disconnected once the
+                /// returned connection is dropped.
+                pub fn connect_raw<F>(&self, signal_name: &str, callback: F) -> Result<$crate::signals::ObsRawSignalConnection, $crate::utils::ObsError>
+                where
+                    F: Fn($crate::unsafe_send::Sendable<*mut libobs::calldata_t>) + Send + Sync + 'static,
+                {
+                    let ptr = self.pointer.clone();
+                    let handler = $crate::run_with_obs!(self.runtime, (ptr), move |token| {
+                        $crate::unsafe_send::Sendable(($handler_getter)(ptr, token))
+                    })?;
+
+                    $crate::signals::ObsRawSignalConnection::connect(
+                        handler,
+                        signal_name,
+                        Box::new(callback),
+                        Box::new(self.pointer.clone()),
+                        self.runtime.clone(),
+                    )
+                }
             }
 
             impl Drop for $name {
@@ -233,46 +270,38 @@ macro_rules! impl_signal_manager {
                     #[allow(unused_variables)]
                     let ptr = self.pointer.clone();
                     #[allow(unused_variables)]
+                    let senders_ptr = $crate::unsafe_send::Sendable(&**self.senders as *const [<$name Senders>] as *mut std::ffi::c_void);
+                    #[allow(unused_variables)]
                     let runtime = self.runtime.clone();
 
                     //TODO make this non blocking
-                    let future = $crate::run_with_obs!(runtime, (ptr), move || {
+                    let res = $crate::run_with_obs!(runtime, (ptr, senders_ptr), move |token| {
                         #[allow(unused_variables)]
-                        let handler = ($handler_getter)(ptr.clone());
+                        let handler = ($handler_getter)(ptr.clone(), token);
                         $(
-                            let signal = $crate::utils::ObsString::new($signal_name);
+                            let signal = $crate::obs_string!($signal_name);
                             unsafe {
                                 // Safety: We are in the runtime, the signal string is allocated, we still have the drop guard as ptr in this scope so the handler is valid.
                                 libobs::signal_handler_disconnect(
                                     handler,
                                     signal.as_ptr().0,
                                     Some([< $signal_name:snake _handler>]),
-                                    ptr.get_ptr() as *mut std::ffi::c_void,
+                                    senders_ptr.0,
                                 );
                             }
                         )*
                     });
 
-                    let r = {
-                        $(
-                            let handlers = [<$signal_name:snake:upper _SENDERS>].write();
-                            if handlers.is_err() {
-                                log::warn!("Failed to acquire write lock for signal {} senders during drop", stringify!($signal_name));
-                                return;
-                            }
-
-                            let mut handlers = handlers.unwrap();
-                            handlers.remove(&Self::smart_ptr_to_key(&self.pointer));
-                        )*
-
-                        future
-                    };
-
-                    if std::thread::panicking() {
-                        return;
+                    match res {
+                        Ok(()) => unsafe {
+                            // Safety: Every handler has been disconnected, so the senders can't be accessed anymore
+                            std::mem::ManuallyDrop::drop(&mut self.senders);
+                        },
+                        Err(e) => {
+                            // The handlers might still be connected, so we have to leak the senders
+                            log::warn!("Failed to disconnect signal manager {}: {:?}", stringify!($name), e);
+                        }
                     }
-
-                    r.unwrap();
                 }
             }
         }
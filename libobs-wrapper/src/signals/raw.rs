@@ -0,0 +1,124 @@
+use std::{any::Any, ffi::c_void, fmt::Debug, mem::ManuallyDrop};
+
+use libobs::{calldata_t, signal_handler_t};
+
+use crate::{
+    run_with_obs,
+    runtime::ObsRuntime,
+    unsafe_send::Sendable,
+    utils::{ObsError, ObsString},
+};
+
+type RawSignalCallback = Box<dyn Fn(Sendable<*mut calldata_t>) + Send + Sync>;
+
+unsafe extern "C" fn raw_signal_handler(param: *mut c_void, calldata: *mut calldata_t) {
+    // Safety: The callback is only freed after this handler has been disconnected
+    let callback = &*(param as *const RawSignalCallback);
+    callback(Sendable(calldata));
+}
+
+/// A callback connected to a signal by name using `connect_raw` of a signal manager.
+///
+/// The callback is disconnected once this connection is dropped.
+pub struct ObsRawSignalConnection {
+    handler: Sendable<*mut signal_handler_t>,
+    signal: ObsString,
+    callback: ManuallyDrop<Box<RawSignalCallback>>,
+    runtime: ObsRuntime,
+    /// Keeps the object that owns the signal handler alive
+    _keep_alive: Box<dyn Any + Send + Sync>,
+}
+
+impl ObsRawSignalConnection {
+    /// Used by `impl_signal_manager`, use `connect_raw` of a signal manager instead.
+    #[doc(hidden)]
+    pub fn connect(
+        handler: Sendable<*mut signal_handler_t>,
+        signal: &str,
+        callback: RawSignalCallback,
+        keep_alive: Box<dyn Any + Send + Sync>,
+        runtime: ObsRuntime,
+    ) -> Result<Self, ObsError> {
+        if handler.0.is_null() {
+            return Err(ObsError::NullPointer(Some(
+                "The object has no signal handler".into(),
+            )));
+        }
+
+        let signal = ObsString::new(signal);
+        let callback = Box::new(callback);
+        let callback_ptr = Sendable(&*callback as *const RawSignalCallback as *mut c_void);
+
+        let handler_ptr = handler.clone();
+        let signal_name = signal.clone();
+        run_with_obs!(
+            runtime,
+            (handler_ptr, signal_name, callback_ptr),
+            move || unsafe {
+                // Safety: The handler is kept alive by the owner of the signal manager and the
+                // callback lives until it is disconnected again.
+                libobs::signal_handler_connect(
+                    handler_ptr.0,
+                    signal_name.as_ptr().0,
+                    Some(raw_signal_handler),
+                    callback_ptr.0,
+                );
+            }
+        )?;
+
+        Ok(Self {
+            handler,
+            signal,
+            callback: ManuallyDrop::new(callback),
+            runtime,
+            _keep_alive: keep_alive,
+        })
+    }
+
+    /// The name of the signal this callback is connected to.
+    pub fn signal(&self) -> ObsString {
+        self.signal.clone()
+    }
+}
+
+impl Debug for ObsRawSignalConnection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ObsRawSignalConnection")
+            .field("handler", &self.handler)
+            .field("signal", &self.signal)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Drop for ObsRawSignalConnection {
+    fn drop(&mut self) {
+        let handler = self.handler.clone();
+        let signal = self.signal.clone();
+        let callback_ptr = Sendable(&**self.callback as *const RawSignalCallback as *mut c_void);
+
+        let res = run_with_obs!(
+            self.runtime,
+            (handler, signal, callback_ptr),
+            move || unsafe {
+                // Safety: The handler is still kept alive by this connection
+                libobs::signal_handler_disconnect(
+                    handler.0,
+                    signal.as_ptr().0,
+                    Some(raw_signal_handler),
+                    callback_ptr.0,
+                );
+            }
+        );
+
+        match res {
+            Ok(()) => unsafe {
+                // Safety: The handler has been disconnected, so the callback can't be called anymore
+                ManuallyDrop::drop(&mut self.callback);
+            },
+            Err(e) => {
+                // The handler might still be connected, so we have to leak the callback
+                log::warn!("Failed to disconnect raw signal {:?}: {:?}", self.signal, e);
+            }
+        }
+    }
+}
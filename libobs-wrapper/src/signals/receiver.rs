@@ -0,0 +1,143 @@
+use std::time::{Duration, Instant};
+
+use tokio::sync::broadcast::{
+    self,
+    error::{RecvError, TryRecvError},
+};
+
+use crate::utils::ObsError;
+
+/// The number of signals that are buffered per signal until the oldest ones are dropped,
+/// unless a different capacity is set on the signal manager.
+pub const DEFAULT_SIGNAL_CAPACITY: usize = 16;
+
+/// An event received by an `ObsSignalReceiver`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ObsSignalEvent<T> {
+    Signal(T),
+    /// The receiver fell behind and the given number of signals have been dropped. Raise the
+    /// capacity of the signal on the signal manager if this happens for high-frequency signals.
+    Lagged(u64),
+}
+
+impl<T> ObsSignalEvent<T> {
+    /// Returns the signal, or `None` if signals have been dropped.
+    pub fn into_signal(self) -> Option<T> {
+        match self {
+            ObsSignalEvent::Signal(signal) => Some(signal),
+            ObsSignalEvent::Lagged(_) => None,
+        }
+    }
+}
+
+/// Receives the signals of a signal manager, returned by its `on_<signal>` methods.
+///
+/// Every receiver gets every signal that is emitted after it has been created. Once more
+/// signals are buffered than the capacity of the signal allows, the oldest ones are dropped
+/// and the receiver gets an `ObsSignalEvent::Lagged` with the number of dropped signals.
+#[derive(Debug)]
+pub struct ObsSignalReceiver<T> {
+    receiver: broadcast::Receiver<T>,
+    signal: &'static str,
+}
+
+impl<T: Clone> ObsSignalReceiver<T> {
+    #[doc(hidden)]
+    pub fn new(receiver: broadcast::Receiver<T>, signal: &'static str) -> Self {
+        Self { receiver, signal }
+    }
+
+    fn map_result(&self, result: Result<T, RecvError>) -> Result<ObsSignalEvent<T>, ObsError> {
+        match result {
+            Ok(signal) => Ok(ObsSignalEvent::Signal(signal)),
+            Err(RecvError::Lagged(count)) => Ok(ObsSignalEvent::Lagged(count)),
+            Err(RecvError::Closed) => Err(ObsError::NoSenderError),
+        }
+    }
+
+    fn log_lagged(&self, count: u64) {
+        log::warn!(
+            "Dropped {} '{}' signals because the receiver fell behind",
+            count,
+            self.signal
+        );
+    }
+
+    /// Waits for the next event. Returns `ObsError::NoSenderError` once the signal manager has been dropped.
+    pub async fn recv(&mut self) -> Result<ObsSignalEvent<T>, ObsError> {
+        let result = self.receiver.recv().await;
+        self.map_result(result)
+    }
+
+    /// Blocks until the next event. This must not be called in an async context.
+    pub fn blocking_recv(&mut self) -> Result<ObsSignalEvent<T>, ObsError> {
+        let result = self.receiver.blocking_recv();
+        self.map_result(result)
+    }
+
+    /// Returns the next event if one is available.
+    pub fn try_recv(&mut self) -> Result<Option<ObsSignalEvent<T>>, ObsError> {
+        match self.receiver.try_recv() {
+            Ok(signal) => Ok(Some(ObsSignalEvent::Signal(signal))),
+            Err(TryRecvError::Empty) => Ok(None),
+            Err(TryRecvError::Lagged(count)) => Ok(Some(ObsSignalEvent::Lagged(count))),
+            Err(TryRecvError::Closed) => Err(ObsError::NoSenderError),
+        }
+    }
+
+    /// Waits for the next signal. Dropped signals are logged as a warning and skipped.
+    pub async fn recv_signal(&mut self) -> Result<T, ObsError> {
+        loop {
+            match self.recv().await? {
+                ObsSignalEvent::Signal(signal) => return Ok(signal),
+                ObsSignalEvent::Lagged(count) => self.log_lagged(count),
+            }
+        }
+    }
+
+    /// Blocks until the next signal. Dropped signals are logged as a warning and skipped.
+    pub fn blocking_recv_signal(&mut self) -> Result<T, ObsError> {
+        loop {
+            match self.blocking_recv()? {
+                ObsSignalEvent::Signal(signal) => return Ok(signal),
+                ObsSignalEvent::Lagged(count) => self.log_lagged(count),
+            }
+        }
+    }
+
+    /// Blocks until the next signal or until the timeout elapsed, in which case `None` is returned.
+    /// Dropped signals are logged as a warning and skipped. This must not be called in an async context.
+    pub fn blocking_recv_signal_timeout(
+        &mut self,
+        timeout: Duration,
+    ) -> Result<Option<T>, ObsError> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            match self.try_recv()? {
+                Some(ObsSignalEvent::Signal(signal)) => return Ok(Some(signal)),
+                Some(ObsSignalEvent::Lagged(count)) => self.log_lagged(count),
+                None => {
+                    let now = Instant::now();
+                    if now >= deadline {
+                        return Ok(None);
+                    }
+
+                    std::thread::sleep((deadline - now).min(Duration::from_millis(10)));
+                }
+            }
+        }
+    }
+
+    /// Creates a new receiver for the same signal, which only gets signals emitted from now on.
+    pub fn resubscribe(&self) -> Self {
+        Self {
+            receiver: self.receiver.resubscribe(),
+            signal: self.signal,
+        }
+    }
+
+    /// Returns the underlying `tokio` receiver.
+    pub fn into_inner(self) -> broadcast::Receiver<T> {
+        self.receiver
+    }
+}
@@ -0,0 +1,57 @@
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use tokio::sync::broadcast;
+
+    use super::super::receiver::{ObsSignalEvent, ObsSignalReceiver};
+    use crate::utils::ObsError;
+
+    #[test]
+    fn lagged_receiver_reports_dropped_signals() {
+        let (sender, receiver) = broadcast::channel(2);
+        let mut receiver = ObsSignalReceiver::new(receiver, "test");
+
+        for i in 0..5 {
+            sender.send(i).unwrap();
+        }
+
+        assert_eq!(
+            receiver.try_recv().unwrap(),
+            Some(ObsSignalEvent::Lagged(3))
+        );
+        assert_eq!(receiver.blocking_recv_signal().unwrap(), 3);
+        assert_eq!(
+            receiver.try_recv().unwrap(),
+            Some(ObsSignalEvent::Signal(4))
+        );
+        assert_eq!(receiver.try_recv().unwrap(), None);
+
+        drop(sender);
+        assert!(matches!(receiver.try_recv(), Err(ObsError::NoSenderError)));
+    }
+
+    #[test]
+    fn recv_with_timeout() {
+        let (sender, receiver) = broadcast::channel(2);
+        let mut receiver = ObsSignalReceiver::new(receiver, "test");
+
+        let timeout = Duration::from_millis(20);
+        assert_eq!(
+            receiver.blocking_recv_signal_timeout(timeout).unwrap(),
+            None
+        );
+
+        sender.send(1).unwrap();
+        assert_eq!(
+            receiver.blocking_recv_signal_timeout(timeout).unwrap(),
+            Some(1)
+        );
+
+        drop(sender);
+        assert!(matches!(
+            receiver.blocking_recv_signal_timeout(timeout),
+            Err(ObsError::NoSenderError)
+        ));
+    }
+}
@@ -0,0 +1,407 @@
+//! A source that outputs video frames and audio pushed from Rust, e.g. decoded frames of a
+//! network stream, the output of a game engine or generated content.
+use std::ffi::{c_char, c_void, CString};
+
+use crate::{
+    data::object::ObsObjectTrait,
+    enums::{
+        ObsAudioFormat, ObsColorspace, ObsSpeakerLayout, ObsVideoFormat, ObsVideoRange, OsEnumType,
+    },
+    forward_obs_object_impl, forward_obs_source_impl,
+    macros::impl_eq_of_ptr,
+    run_with_obs,
+    runtime::ObsRuntime,
+    sources::ObsSourceRef,
+    utils::{ObsError, ObsString},
+};
+
+/// The id of the source type used by [`ObsBufferSource`].
+pub const BUFFER_SOURCE_ID: &str = "libobs_rs_buffer_source";
+const BUFFER_SOURCE_NAME: &str = "Buffer Source";
+
+/// One plane of a video frame, `linesize` is the number of bytes of a row including padding.
+#[derive(Debug, Clone, Copy)]
+pub struct ObsFramePlane<'a> {
+    pub data: &'a [u8],
+    pub linesize: u32,
+}
+
+/// A raw video frame that is pushed into an [`ObsBufferSource`]. OBS copies the frame, so the
+/// data only has to live until the frame is pushed.
+#[derive(Debug, Clone)]
+pub struct ObsVideoFrame<'a> {
+    pub format: ObsVideoFormat,
+    pub width: u32,
+    pub height: u32,
+    /// The planes of the frame, e.g. one for BGRA, two for NV12 and three for I420
+    pub planes: Vec<ObsFramePlane<'a>>,
+    /// The presentation time in nanoseconds. Any monotonic clock can be used, e.g. the time
+    /// since the stream started, as OBS syncs the first timestamp to its own clock.
+    pub timestamp: u64,
+    /// Only used for YUV formats
+    pub colorspace: ObsColorspace,
+    /// Only used for YUV formats
+    pub range: ObsVideoRange,
+    /// Whether the frame is upside down
+    pub flip: bool,
+}
+
+impl<'a> ObsVideoFrame<'a> {
+    pub fn new(
+        format: ObsVideoFormat,
+        width: u32,
+        height: u32,
+        planes: Vec<ObsFramePlane<'a>>,
+        timestamp: u64,
+    ) -> Self {
+        Self {
+            format,
+            width,
+            height,
+            planes,
+            timestamp,
+            colorspace: ObsColorspace::Default,
+            range: ObsVideoRange::Default,
+            flip: false,
+        }
+    }
+
+    /// Checks that the planes match the format and hold every row, as OBS reads them without
+    /// knowing their length.
+    pub(super) fn validate(&self) -> Result<(), ObsError> {
+        let expected = video_plane_count(self.format).ok_or_else(|| {
+            ObsError::InvalidOperation(format!(
+                "Frames of format {:?} can not be pushed",
+                self.format
+            ))
+        })?;
+
+        if self.planes.len() != expected {
+            return Err(ObsError::InvalidOperation(format!(
+                "Frames of format {:?} need {} planes, got {}",
+                self.format,
+                expected,
+                self.planes.len()
+            )));
+        }
+
+        for (index, plane) in self.planes.iter().enumerate() {
+            let rows = video_plane_rows(self.format, self.height, index);
+            if (plane.data.len() as u64) < plane.linesize as u64 * rows as u64 {
+                return Err(ObsError::InvalidOperation(format!(
+                    "Plane {} holds {} bytes, but needs {} rows of {} bytes",
+                    index,
+                    plane.data.len(),
+                    rows,
+                    plane.linesize
+                )));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Raw audio that is pushed into an [`ObsBufferSource`]. OBS copies the audio, so the data
+/// only has to live until it is pushed.
+#[derive(Debug, Clone)]
+pub struct ObsAudioFrame<'a> {
+    pub format: ObsAudioFormat,
+    pub speakers: ObsSpeakerLayout,
+    pub samples_per_sec: u32,
+    /// The number of samples per channel
+    pub frames: u32,
+    /// One plane with interleaved samples, or one plane per channel for planar formats
+    pub planes: Vec<&'a [u8]>,
+    /// The presentation time in nanoseconds, on the same clock as the video frames
+    pub timestamp: u64,
+}
+
+impl ObsAudioFrame<'_> {
+    pub(super) fn validate(&self) -> Result<(), ObsError> {
+        let (Some(sample_size), Some(channels)) = (
+            audio_sample_size(self.format),
+            speaker_channels(self.speakers),
+        ) else {
+            return Err(ObsError::InvalidOperation(format!(
+                "Audio with format {:?} and speakers {:?} can not be pushed",
+                self.format, self.speakers
+            )));
+        };
+
+        let (expected, plane_len) = if is_planar(self.format) {
+            (channels, sample_size * self.frames as usize)
+        } else {
+            (1, sample_size * channels * self.frames as usize)
+        };
+
+        if self.planes.len() != expected {
+            return Err(ObsError::InvalidOperation(format!(
+                "Audio of format {:?} needs {} planes, got {}",
+                self.format,
+                expected,
+                self.planes.len()
+            )));
+        }
+
+        if let Some((index, plane)) = self
+            .planes
+            .iter()
+            .enumerate()
+            .find(|(_, plane)| plane.len() < plane_len)
+        {
+            return Err(ObsError::InvalidOperation(format!(
+                "Plane {} holds {} bytes, but needs {}",
+                index,
+                plane.len(),
+                plane_len
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+/// The number of planes of a video format, `None` if the format can not be pushed.
+fn video_plane_count(format: ObsVideoFormat) -> Option<usize> {
+    use ObsVideoFormat::*;
+
+    match format {
+        NONE => None,
+        NV12 | P010 | P216 | P416 => Some(2),
+        I420 | I422 | I444 | I010 | I210 | I412 => Some(3),
+        I40A | I42A | YUVA | YA2L => Some(4),
+        _ => Some(1),
+    }
+}
+
+/// The number of rows of a plane, the chroma planes of 4:2:0 formats have half the height.
+fn video_plane_rows(format: ObsVideoFormat, height: u32, plane: usize) -> u32 {
+    use ObsVideoFormat::*;
+
+    let is_chroma = plane == 1 || plane == 2;
+    match format {
+        NV12 | P010 | I420 | I010 | I40A if is_chroma => height.div_ceil(2),
+        _ => height,
+    }
+}
+
+fn audio_sample_size(format: ObsAudioFormat) -> Option<usize> {
+    use ObsAudioFormat::*;
+
+    match format {
+        Unknown => None,
+        U8Bit | U8BitPlanar => Some(1),
+        I16Bit | I16BitPlanar => Some(2),
+        I32Bit | I32BitPlanar | Float | FloatPlanar => Some(4),
+    }
+}
+
+fn is_planar(format: ObsAudioFormat) -> bool {
+    use ObsAudioFormat::*;
+
+    matches!(
+        format,
+        U8BitPlanar | I16BitPlanar | I32BitPlanar | FloatPlanar
+    )
+}
+
+fn speaker_channels(speakers: ObsSpeakerLayout) -> Option<usize> {
+    use ObsSpeakerLayout::*;
+
+    match speakers {
+        Unknown => None,
+        Mono => Some(1),
+        Stereo => Some(2),
+        S2Point1 => Some(3),
+        S4Point0 => Some(4),
+        S4Point1 => Some(5),
+        S5Point1 => Some(6),
+        S7Point1 => Some(8),
+    }
+}
+
+/// A source that outputs the video frames and audio pushed into it, like a capture card.
+///
+/// Frames can be pushed from any thread. OBS buffers them and shows each frame at its
+/// timestamp, use [`set_unbuffered`](Self::set_unbuffered) to show frames as soon as they
+/// are pushed instead.
+#[derive(Debug, Clone)]
+pub struct ObsBufferSource {
+    source: ObsSourceRef,
+}
+
+impl ObsBufferSource {
+    /// Creates a new buffer source, see `ObsContext::buffer_source`.
+    pub fn new<K: Into<ObsString> + Sync + Send>(
+        name: K,
+        runtime: ObsRuntime,
+    ) -> Result<Self, ObsError> {
+        register_buffer_source(&runtime)?;
+
+        let source = ObsSourceRef::new(BUFFER_SOURCE_ID, name, None, None, runtime)?;
+        Ok(Self { source })
+    }
+
+    /// Pushes a video frame, replacing the current one once its timestamp is reached.
+    #[allow(unknown_lints)]
+    #[allow(ensure_obs_call_in_runtime)]
+    pub fn push_video(&self, frame: &ObsVideoFrame) -> Result<(), ObsError> {
+        frame.validate()?;
+
+        unsafe {
+            // Safety: Every field is a number, a bool or a null pointer, so zero is a valid value
+            let mut obs_frame = std::mem::zeroed::<libobs::obs_source_frame>();
+            obs_frame.width = frame.width;
+            obs_frame.height = frame.height;
+            obs_frame.timestamp = frame.timestamp;
+            obs_frame.format = frame.format as OsEnumType;
+            obs_frame.full_range = frame.range == ObsVideoRange::Full;
+            obs_frame.flip = frame.flip;
+
+            for (index, plane) in frame.planes.iter().enumerate() {
+                obs_frame.data[index] = plane.data.as_ptr() as *mut u8;
+                obs_frame.linesize[index] = plane.linesize;
+            }
+
+            // Safety: The arrays have the sizes libobs expects. RGB formats don't need the
+            // parameters, for them the call fails and the zeroed values are kept.
+            libobs::video_format_get_parameters_for_format(
+                frame.colorspace as OsEnumType,
+                frame.range as OsEnumType,
+                obs_frame.format,
+                obs_frame.color_matrix.as_mut_ptr(),
+                obs_frame.color_range_min.as_mut_ptr(),
+                obs_frame.color_range_max.as_mut_ptr(),
+            );
+
+            // Safety: The planes were validated to hold every row. libobs copies the frame and
+            // synchronizes access to the source itself, so this can be called from any thread.
+            libobs::obs_source_output_video(self.source.as_ptr().get_ptr_unchecked(), &obs_frame);
+        }
+
+        Ok(())
+    }
+
+    /// Removes the current frame, so the source is transparent until the next frame is pushed.
+    #[allow(unknown_lints)]
+    #[allow(ensure_obs_call_in_runtime)]
+    pub fn clear_video(&self) {
+        unsafe {
+            // Safety: A null frame clears the source, this can be called from any thread
+            libobs::obs_source_output_video(
+                self.source.as_ptr().get_ptr_unchecked(),
+                std::ptr::null(),
+            );
+        }
+    }
+
+    /// Pushes audio, which is mixed into the audio of OBS at its timestamp.
+    #[allow(unknown_lints)]
+    #[allow(ensure_obs_call_in_runtime)]
+    pub fn push_audio(&self, audio: &ObsAudioFrame) -> Result<(), ObsError> {
+        audio.validate()?;
+
+        unsafe {
+            // Safety: Every field is a number or a null pointer, so zero is a valid value
+            let mut obs_audio = std::mem::zeroed::<libobs::obs_source_audio>();
+            obs_audio.frames = audio.frames;
+            obs_audio.speakers = audio.speakers as OsEnumType;
+            obs_audio.format = audio.format as OsEnumType;
+            obs_audio.samples_per_sec = audio.samples_per_sec;
+            obs_audio.timestamp = audio.timestamp;
+
+            for (index, plane) in audio.planes.iter().enumerate() {
+                obs_audio.data[index] = plane.as_ptr();
+            }
+
+            // Safety: The planes were validated to hold every sample. libobs copies the audio
+            // and synchronizes access to the source itself, so this can be called from any thread.
+            libobs::obs_source_output_audio(self.source.as_ptr().get_ptr_unchecked(), &obs_audio);
+        }
+
+        Ok(())
+    }
+
+    /// Shows frames as soon as they are pushed instead of at their timestamp.
+    /// This lowers the latency, but frames may be shown unevenly.
+    pub fn set_unbuffered(&self, unbuffered: bool) -> Result<(), ObsError> {
+        let source_ptr = self.source.as_ptr();
+        run_with_obs!(self.source.runtime(), (source_ptr), move |token| unsafe {
+            // Safety: The pointer is valid because of the smart pointer
+            libobs::obs_source_set_async_unbuffered(source_ptr.get_ptr(token), unbuffered);
+        })
+    }
+
+    pub fn unbuffered(&self) -> Result<bool, ObsError> {
+        let source_ptr = self.source.as_ptr();
+        run_with_obs!(self.source.runtime(), (source_ptr), move |token| unsafe {
+            // Safety: The pointer is valid because of the smart pointer
+            libobs::obs_source_async_unbuffered(source_ptr.get_ptr(token))
+        })
+    }
+}
+
+forward_obs_object_impl!(ObsBufferSource, source, *mut libobs::obs_source_t);
+forward_obs_source_impl!(ObsBufferSource, source);
+
+impl_eq_of_ptr!(ObsBufferSource);
+
+#[allow(unknown_lints)]
+#[allow(ensure_obs_call_in_runtime)]
+/// # Safety
+/// Only called by OBS with the type data set in `register_buffer_source`, which is never freed.
+unsafe extern "C" fn get_name(type_data: *mut c_void) -> *const c_char {
+    type_data as *const c_char
+}
+
+#[allow(unknown_lints)]
+#[allow(ensure_obs_call_in_runtime)]
+/// # Safety
+/// Only called by OBS when a buffer source is created. The source has no state of its own,
+/// but OBS treats a null pointer as failure, so the source itself is returned.
+unsafe extern "C" fn create(
+    _settings: *mut libobs::obs_data_t,
+    source: *mut libobs::obs_source_t,
+) -> *mut c_void {
+    source as *mut c_void
+}
+
+#[allow(unknown_lints)]
+#[allow(ensure_obs_call_in_runtime)]
+/// # Safety
+/// Only called by OBS with the data returned by `create`, which isn't owned by the source.
+unsafe extern "C" fn destroy(_data: *mut c_void) {}
+
+/// Registers the buffer source type, unless it is registered already. Registered types are
+/// removed when OBS shuts down, so this is checked every time a buffer source is created.
+fn register_buffer_source(runtime: &ObsRuntime) -> Result<(), ObsError> {
+    run_with_obs!(runtime, move || unsafe {
+        let id = CString::new(BUFFER_SOURCE_ID).map_err(|_| ObsError::StringConversionError)?;
+
+        // Safety: We are on the runtime thread and the id is a valid C string
+        if !libobs::obs_source_get_display_name(id.as_ptr()).is_null() {
+            return Ok(());
+        }
+
+        let name = CString::new(BUFFER_SOURCE_NAME).map_err(|_| ObsError::StringConversionError)?;
+
+        // Safety: Every field of the info is either a pointer or an optional callback, so zero is a valid value
+        let mut info = std::mem::zeroed::<libobs::obs_source_info>();
+
+        // OBS keeps the id and the name for as long as the type is registered
+        info.id = id.into_raw();
+        info.type_data = name.into_raw() as *mut c_void;
+        info.type_ = libobs::obs_source_type_OBS_SOURCE_TYPE_INPUT;
+        info.output_flags = libobs::OBS_SOURCE_ASYNC_VIDEO
+            | libobs::OBS_SOURCE_AUDIO
+            | libobs::OBS_SOURCE_DO_NOT_DUPLICATE;
+        info.get_name = Some(get_name);
+        info.create = Some(create);
+        info.destroy = Some(destroy);
+
+        // Safety: OBS copies the info, the callbacks are static functions
+        libobs::obs_register_source_s(&info, std::mem::size_of::<libobs::obs_source_info>());
+        Ok(())
+    })?
+}
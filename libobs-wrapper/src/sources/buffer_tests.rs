@@ -0,0 +1,53 @@
+#[cfg(test)]
+mod tests {
+    use super::super::buffer::{ObsAudioFrame, ObsFramePlane, ObsVideoFrame};
+    use crate::enums::{ObsAudioFormat, ObsSpeakerLayout, ObsVideoFormat};
+
+    #[test]
+    fn nv12_chroma_plane_has_half_height() {
+        let luma = [0u8; 4 * 3];
+        let chroma = [0u8; 4 * 2];
+        let planes = vec![
+            ObsFramePlane {
+                data: &luma,
+                linesize: 4,
+            },
+            ObsFramePlane {
+                data: &chroma,
+                linesize: 4,
+            },
+        ];
+
+        let frame = ObsVideoFrame::new(ObsVideoFormat::NV12, 4, 3, planes.clone(), 0);
+        assert!(frame.validate().is_ok());
+
+        let frame = ObsVideoFrame::new(ObsVideoFormat::NV12, 4, 4, planes, 0);
+        assert!(frame.validate().is_err());
+
+        let frame = ObsVideoFrame::new(ObsVideoFormat::I420, 4, 3, vec![], 0);
+        assert!(frame.validate().is_err());
+    }
+
+    #[test]
+    fn audio_planes_match_the_layout() {
+        let samples = [0u8; 2 * 4 * 16];
+        let mut audio = ObsAudioFrame {
+            format: ObsAudioFormat::Float,
+            speakers: ObsSpeakerLayout::Stereo,
+            samples_per_sec: 48000,
+            frames: 16,
+            planes: vec![&samples],
+            timestamp: 0,
+        };
+        assert!(audio.validate().is_ok());
+
+        audio.format = ObsAudioFormat::FloatPlanar;
+        assert!(audio.validate().is_err());
+
+        audio.planes = vec![&samples[..64], &samples[64..]];
+        assert!(audio.validate().is_ok());
+
+        audio.frames = 17;
+        assert!(audio.validate().is_err());
+    }
+}
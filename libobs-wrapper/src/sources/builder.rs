@@ -14,6 +14,9 @@ pub trait ObsSourceBuilder: ObsObjectBuilder {
 
     /// Both items are returned: the source and the scene item it was added as.
     /// You can safely drop these items, they are stored within the scene if you don't need them.
+    ///
+    /// The source is built and added in two dispatches onto the OBS thread, use
+    /// `add_to_scene_batched` if the builder can be sent to the OBS thread.
     fn add_to_scene(self, scene: &mut ObsSceneRef) -> Result<ObsSceneItemRef<Self::T>, ObsError>
     where
         Self: Sized,
@@ -22,4 +25,22 @@ pub trait ObsSourceBuilder: ObsObjectBuilder {
 
         scene.add_source(source.clone())
     }
+
+    /// Same as `add_to_scene`, but the source is built and added in a single dispatch onto
+    /// the OBS thread, see `ObsRuntime::batch`. Builders that override `add_to_scene` keep
+    /// their behavior.
+    fn add_to_scene_batched(
+        self,
+        scene: &mut ObsSceneRef,
+    ) -> Result<ObsSceneItemRef<Self::T>, ObsError>
+    where
+        Self: Sized + Send + 'static,
+        Self::T: Send,
+    {
+        let mut scene = scene.clone();
+        let runtime = self.runtime().clone();
+
+        // Every dispatch of `add_to_scene` runs inline on the OBS thread
+        runtime.batch(move |_runtime| self.add_to_scene(&mut scene))?
+    }
 }
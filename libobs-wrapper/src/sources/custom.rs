@@ -0,0 +1,269 @@
+//! Source types that are implemented in Rust and registered with `obs_register_source_s`.
+use std::{
+    ffi::{c_char, c_void, CStr, CString},
+    marker::PhantomData,
+    panic::AssertUnwindSafe,
+    sync::{Mutex, MutexGuard},
+};
+
+use crate::{graphics::GraphicsContext, run_with_obs, runtime::ObsRuntime, utils::ObsError};
+
+/// Read-only access to the settings of a custom source while one of its callbacks is running.
+///
+/// The values are read directly from the `obs_data_t` OBS passed to the callback, as the callbacks
+/// may run on the graphics thread, where dispatching to the runtime could deadlock.
+pub struct CustomSourceSettings<'a> {
+    data: *mut libobs::obs_data_t,
+    _callback: PhantomData<&'a ()>,
+}
+
+impl CustomSourceSettings<'_> {
+    /// # Safety
+    /// The data must be valid for as long as this struct is alive.
+    unsafe fn new(data: *mut libobs::obs_data_t) -> Self {
+        Self {
+            data,
+            _callback: PhantomData,
+        }
+    }
+
+    /// Runs `f` with the key as C string, if the settings exist and contain the key.
+    fn with_key<T>(&self, key: &str, f: impl FnOnce(*const c_char) -> T) -> Option<T> {
+        if self.data.is_null() {
+            return None;
+        }
+
+        let key = CString::new(key).ok()?;
+        let has_value = unsafe {
+            // Safety: The data is valid while the callback runs, see `new`
+            libobs::obs_data_has_user_value(self.data, key.as_ptr())
+                || libobs::obs_data_has_default_value(self.data, key.as_ptr())
+        };
+
+        has_value.then(|| f(key.as_ptr()))
+    }
+
+    pub fn get_string(&self, key: &str) -> Option<String> {
+        self.with_key(key, |key| unsafe {
+            // Safety: The data is valid while the callback runs and the returned string is owned by it
+            let value = libobs::obs_data_get_string(self.data, key);
+            if value.is_null() {
+                return None;
+            }
+
+            Some(CStr::from_ptr(value).to_string_lossy().into_owned())
+        })
+        .flatten()
+    }
+
+    pub fn get_int(&self, key: &str) -> Option<i64> {
+        self.with_key(key, |key| unsafe {
+            // Safety: The data is valid while the callback runs
+            libobs::obs_data_get_int(self.data, key)
+        })
+    }
+
+    pub fn get_double(&self, key: &str) -> Option<f64> {
+        self.with_key(key, |key| unsafe {
+            // Safety: The data is valid while the callback runs
+            libobs::obs_data_get_double(self.data, key)
+        })
+    }
+
+    pub fn get_bool(&self, key: &str) -> Option<bool> {
+        self.with_key(key, |key| unsafe {
+            // Safety: The data is valid while the callback runs
+            libobs::obs_data_get_bool(self.data, key)
+        })
+    }
+}
+
+/// A video source type that is implemented in Rust, see `ObsContext::register_source`.
+///
+/// Once registered, sources of this type are created like any other source, using `ID` as the id,
+/// e.g. with `ObsSourceRef::new`. OBS calls the callbacks from the runtime and the graphics thread,
+/// so the source is kept behind a mutex and only one callback runs at a time.
+/// A panicking callback is logged and otherwise ignored.
+pub trait CustomSourceDef: Send + Sized + 'static {
+    /// The unique id of the source type
+    const ID: &'static str;
+    /// The display name of the source type
+    const NAME: &'static str;
+
+    /// Creates the source when a source of this type is created, with the settings it is created with.
+    fn create(settings: &CustomSourceSettings) -> Self;
+
+    /// Called before the source is dropped, when the last reference to the OBS source is released.
+    fn destroy(&mut self) {}
+
+    /// Called whenever the settings of the source are updated.
+    fn update(&mut self, _settings: &CustomSourceSettings) {}
+
+    /// Called on every tick of the graphics thread with the seconds elapsed since the last tick.
+    fn video_tick(&mut self, _seconds: f32) {}
+
+    /// Draws the source. The `GraphicsContext` uses the size of the source as canvas size.
+    fn video_render(&mut self, ctx: &mut GraphicsContext);
+
+    fn get_width(&self) -> u32;
+
+    fn get_height(&self) -> u32;
+}
+
+pub(super) type SourceData<T> = Mutex<T>;
+
+/// Runs `f` with the locked source, returns `None` if there is no source or `f` panicked.
+///
+/// # Safety
+/// The data must be null or have been created by `create_source::<T>` and not been destroyed yet.
+pub(super) unsafe fn with_source<T: CustomSourceDef, R>(
+    data: *mut c_void,
+    callback: &str,
+    f: impl FnOnce(&mut T) -> R,
+) -> Option<R> {
+    if data.is_null() {
+        return None;
+    }
+
+    let source = &*(data as *const SourceData<T>);
+    let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+        let mut source: MutexGuard<T> = source
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        f(&mut source)
+    }));
+
+    match result {
+        Ok(result) => Some(result),
+        Err(_) => {
+            log::error!("{} callback of custom source {} panicked", callback, T::ID);
+            None
+        }
+    }
+}
+
+#[allow(unknown_lints)]
+#[allow(ensure_obs_call_in_runtime)]
+/// # Safety
+/// Only called by OBS with the type data set in `register_custom_source`, which is never freed.
+unsafe extern "C" fn get_name(type_data: *mut c_void) -> *const c_char {
+    type_data as *const c_char
+}
+
+#[allow(unknown_lints)]
+#[allow(ensure_obs_call_in_runtime)]
+/// # Safety
+/// Only called by OBS with the settings of the source that is created.
+unsafe extern "C" fn create_source<T: CustomSourceDef>(
+    settings: *mut libobs::obs_data_t,
+    _source: *mut libobs::obs_source_t,
+) -> *mut c_void {
+    let settings = CustomSourceSettings::new(settings);
+    match std::panic::catch_unwind(AssertUnwindSafe(|| T::create(&settings))) {
+        Ok(source) => Box::into_raw(Box::new(SourceData::new(source))) as *mut c_void,
+        Err(_) => {
+            log::error!("create callback of custom source {} panicked", T::ID);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+#[allow(unknown_lints)]
+#[allow(ensure_obs_call_in_runtime)]
+/// # Safety
+/// Only called by OBS once with the data returned by `create_source::<T>`, which isn't used afterwards.
+pub(super) unsafe extern "C" fn destroy_source<T: CustomSourceDef>(data: *mut c_void) {
+    with_source::<T, _>(data, "destroy", |source| source.destroy());
+    if !data.is_null() {
+        drop(Box::from_raw(data as *mut SourceData<T>));
+    }
+}
+
+#[allow(unknown_lints)]
+#[allow(ensure_obs_call_in_runtime)]
+/// # Safety
+/// Only called by OBS with the data returned by `create_source::<T>`.
+unsafe extern "C" fn update_source<T: CustomSourceDef>(
+    data: *mut c_void,
+    settings: *mut libobs::obs_data_t,
+) {
+    let settings = CustomSourceSettings::new(settings);
+    with_source::<T, _>(data, "update", |source| source.update(&settings));
+}
+
+#[allow(unknown_lints)]
+#[allow(ensure_obs_call_in_runtime)]
+/// # Safety
+/// Only called by OBS on the graphics thread with the data returned by `create_source::<T>`.
+unsafe extern "C" fn video_tick<T: CustomSourceDef>(data: *mut c_void, seconds: f32) {
+    with_source::<T, _>(data, "video_tick", |source| source.video_tick(seconds));
+}
+
+#[allow(unknown_lints)]
+#[allow(ensure_obs_call_in_runtime)]
+/// # Safety
+/// Only called by OBS on the graphics thread with the data returned by `create_source::<T>`,
+/// while the projection matches the size of the source.
+unsafe extern "C" fn video_render<T: CustomSourceDef>(
+    data: *mut c_void,
+    _effect: *mut libobs::gs_effect_t,
+) {
+    with_source::<T, _>(data, "video_render", |source| {
+        let (width, height) = (source.get_width(), source.get_height());
+        let mut ctx = GraphicsContext::new(width, height, width, height);
+        source.video_render(&mut ctx);
+    });
+}
+
+#[allow(unknown_lints)]
+#[allow(ensure_obs_call_in_runtime)]
+/// # Safety
+/// Only called by OBS with the data returned by `create_source::<T>`.
+pub(super) unsafe extern "C" fn get_width<T: CustomSourceDef>(data: *mut c_void) -> u32 {
+    with_source::<T, _>(data, "get_width", |source| source.get_width()).unwrap_or(0)
+}
+
+#[allow(unknown_lints)]
+#[allow(ensure_obs_call_in_runtime)]
+/// # Safety
+/// Only called by OBS with the data returned by `create_source::<T>`.
+unsafe extern "C" fn get_height<T: CustomSourceDef>(data: *mut c_void) -> u32 {
+    with_source::<T, _>(data, "get_height", |source| source.get_height()).unwrap_or(0)
+}
+
+/// Registers the source type with OBS. Source types can't be unregistered, so they stay
+/// available until OBS is shut down.
+pub(crate) fn register_custom_source<T: CustomSourceDef>(
+    runtime: &ObsRuntime,
+) -> Result<(), ObsError> {
+    let id = CString::new(T::ID).map_err(|_| ObsError::StringConversionError)?;
+    let name = CString::new(T::NAME).map_err(|_| ObsError::StringConversionError)?;
+
+    run_with_obs!(runtime, move || unsafe {
+        // Safety: We are on the runtime thread and the id is a valid C string
+        if !libobs::obs_source_get_display_name(id.as_ptr()).is_null() {
+            return Err(ObsError::NameAlreadyInUse(T::ID.to_string()));
+        }
+
+        // Safety: Every field of the info is either a pointer or an optional callback, so zero is a valid value
+        let mut info = std::mem::zeroed::<libobs::obs_source_info>();
+
+        // OBS keeps the id and the name for as long as the type is registered, which is forever
+        info.id = id.into_raw();
+        info.type_data = name.into_raw() as *mut c_void;
+        info.type_ = libobs::obs_source_type_OBS_SOURCE_TYPE_INPUT;
+        info.output_flags = libobs::OBS_SOURCE_VIDEO | libobs::OBS_SOURCE_CUSTOM_DRAW;
+        info.get_name = Some(get_name);
+        info.create = Some(create_source::<T>);
+        info.destroy = Some(destroy_source::<T>);
+        info.update = Some(update_source::<T>);
+        info.video_tick = Some(video_tick::<T>);
+        info.video_render = Some(video_render::<T>);
+        info.get_width = Some(get_width::<T>);
+        info.get_height = Some(get_height::<T>);
+
+        // Safety: OBS copies the info, the callbacks are static functions
+        libobs::obs_register_source_s(&info, std::mem::size_of::<libobs::obs_source_info>());
+        Ok(())
+    })?
+}
@@ -0,0 +1,52 @@
+#[cfg(test)]
+mod tests {
+    use std::ffi::c_void;
+
+    use super::super::custom::{
+        destroy_source, get_width, with_source, CustomSourceDef, CustomSourceSettings, SourceData,
+    };
+    use crate::graphics::GraphicsContext;
+
+    struct Counter(u32);
+
+    impl CustomSourceDef for Counter {
+        const ID: &'static str = "test_counter_source";
+        const NAME: &'static str = "Counter";
+
+        fn create(_settings: &CustomSourceSettings) -> Self {
+            Counter(0)
+        }
+
+        fn video_render(&mut self, _ctx: &mut GraphicsContext) {}
+
+        fn get_width(&self) -> u32 {
+            self.0
+        }
+
+        fn get_height(&self) -> u32 {
+            self.0
+        }
+    }
+
+    #[test]
+    fn test_with_source_survives_panics() {
+        let data = Box::into_raw(Box::new(SourceData::new(Counter(0)))) as *mut c_void;
+
+        unsafe {
+            assert_eq!(
+                with_source::<Counter, _>(std::ptr::null_mut(), "test", |c| c.0),
+                None
+            );
+            assert_eq!(
+                with_source::<Counter, _>(data, "test", |c| -> u32 {
+                    c.0 = 3;
+                    panic!("render failed")
+                }),
+                None
+            );
+            assert_eq!(get_width::<Counter>(data), 3);
+
+            destroy_source::<Counter>(data);
+        }
+    }
+}
@@ -30,6 +30,11 @@ impl ObsFilterRef {
         let inner = ObsSourceRef::new(id, name, settings, hotkey_data, runtime)?;
         Ok(Self { inner })
     }
+
+    /// The number of clones of this filter, including the ones held by the sources it is applied to.
+    pub(crate) fn handle_count(&self) -> usize {
+        self.inner.handle_count()
+    }
 }
 
 #[derive(Debug)]
@@ -58,10 +63,14 @@ impl _ObsRemoveFilterOnDrop {
 }
 
 impl ObsDropGuard for _ObsRemoveFilterOnDrop {}
-impl_obs_drop!(_ObsRemoveFilterOnDrop, (source, filter), move || unsafe {
-    // Safety: This is safe because both pointers still exist because of the SmartPointers.
-    libobs::obs_source_filter_remove(source.get_ptr(), filter.get_ptr());
-});
+impl_obs_drop!(
+    _ObsRemoveFilterOnDrop,
+    (source, filter),
+    move |token| unsafe {
+        // Safety: This is safe because both pointers still exist because of the SmartPointers.
+        libobs::obs_source_filter_remove(source.get_ptr(token), filter.get_ptr(token));
+    }
+);
 
 forward_obs_object_impl!(ObsFilterRef, inner, *mut libobs::obs_source_t);
 forward_obs_source_impl!(ObsFilterRef, inner);
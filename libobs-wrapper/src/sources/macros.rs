@@ -18,6 +18,12 @@ macro_rules! forward_obs_source_impl {
             ) -> Result<(), $crate::utils::ObsError> {
                 self.$var_name.apply_filter(filter)
             }
+            fn remove_filter(
+                &self,
+                filter: &$crate::sources::ObsFilterRef,
+            ) -> Result<(), $crate::utils::ObsError> {
+                self.$var_name.remove_filter(filter)
+            }
         }
 
         impl $struct_name {
@@ -12,6 +12,17 @@ mod macros;
 mod filter;
 pub use filter::*;
 
+mod custom;
+#[cfg(test)]
+mod custom_tests;
+pub(crate) use custom::register_custom_source;
+pub use custom::{CustomSourceDef, CustomSourceSettings};
+
+mod buffer;
+#[cfg(test)]
+mod buffer_tests;
+pub use buffer::*;
+
 use libobs::obs_source_t;
 
 use crate::{
@@ -21,7 +32,7 @@ use crate::{
     },
     impl_obs_drop, impl_signal_manager, run_with_obs,
     runtime::ObsRuntime,
-    unsafe_send::{Sendable, SmartPointerSendable},
+    unsafe_send::{ObsThreadToken, Sendable, SmartPointerSendable},
     utils::{ObsDropGuard, ObsError, ObsString, SourceInfo},
 };
 
@@ -34,7 +45,7 @@ pub struct ObsSourceRef {
     signal_manager: Arc<ObsSourceSignals>,
 
     id: ObsString,
-    name: ObsString,
+    name: Arc<RwLock<ObsString>>,
     settings: Arc<RwLock<ImmutableObsData>>,
     hotkey_data: Arc<RwLock<ImmutableObsData>>,
 
@@ -78,12 +89,12 @@ impl ObsSourceRef {
         let source_ptr = run_with_obs!(
             runtime,
             (hotkey_data_ptr, settings_ptr, id, name),
-            move || {
+            move |token| {
                 let id_ptr = id.as_ptr().0;
                 let name_ptr = name.as_ptr().0;
 
                 let settings_raw_ptr = match settings_ptr {
-                    Some(s) => s.get_ptr(),
+                    Some(s) => s.get_ptr(token),
                     None => std::ptr::null_mut(),
                 };
 
@@ -93,18 +104,29 @@ impl ObsSourceRef {
                         id_ptr,
                         name_ptr,
                         settings_raw_ptr,
-                        hotkey_data_ptr.get_ptr(),
+                        hotkey_data_ptr.get_ptr(token),
                     )
                 };
 
                 if source_ptr.is_null() {
-                    Err(ObsError::NullPointer(None))
+                    Err(ObsError::NullPointer(None).in_function("obs_source_create"))
                 } else {
                     Ok(Sendable(source_ptr))
                 }
             }
         )??;
 
+        Self::from_created(source_ptr, id, name, hotkey_data, runtime)
+    }
+
+    /// Wraps a newly created source, taking ownership of its reference.
+    pub(crate) fn from_created(
+        source_ptr: Sendable<*mut obs_source_t>,
+        id: ObsString,
+        name: ObsString,
+        hotkey_data: ImmutableObsData,
+        runtime: ObsRuntime,
+    ) -> Result<Self, ObsError> {
         let source_ptr = SmartPointerSendable::new(
             source_ptr.0,
             Arc::new(_ObsSourceGuard {
@@ -115,10 +137,10 @@ impl ObsSourceRef {
 
         // Getting default settings if none were provided
         let settings = {
-            let default_settings_ptr = run_with_obs!(runtime, (source_ptr), move || {
+            let default_settings_ptr = run_with_obs!(runtime, (source_ptr), move |token| {
                 unsafe {
                     // Safety: This safe to call because we are using a smart pointer and the source pointer must not be dropped.
-                    Sendable(libobs::obs_source_get_settings(source_ptr.get_ptr()))
+                    Sendable(libobs::obs_source_get_settings(source_ptr.get_ptr(token)))
                 }
             })?;
 
@@ -129,7 +151,7 @@ impl ObsSourceRef {
         Ok(Self {
             source: source_ptr.clone(),
             id,
-            name,
+            name: Arc::new(RwLock::new(name)),
             settings: Arc::new(RwLock::new(settings)),
             hotkey_data: Arc::new(RwLock::new(hotkey_data)),
             attached_filters: Arc::new(RwLock::new(Vec::new())),
@@ -137,6 +159,164 @@ impl ObsSourceRef {
             signal_manager: Arc::new(signals),
         })
     }
+
+    /// Wraps the source with the given name that already exists in OBS, e.g. because it
+    /// was created by a loaded scene collection or another plugin, using `obs_get_source_by_name`.
+    ///
+    /// The wrapper holds its own reference, so the source is kept alive until it is dropped.
+    /// Returns `None` if there is no source with this name.
+    pub fn new_from_existing<T: Into<ObsString> + Sync + Send>(
+        name: T,
+        runtime: ObsRuntime,
+    ) -> Result<Option<Self>, ObsError> {
+        let name = name.into();
+        let source_ptr = run_with_obs!(runtime, (name), move || unsafe {
+            // Safety: The name pointer is valid because name is kept in scope
+            Sendable(libobs::obs_get_source_by_name(name.as_ptr().0))
+        })?;
+
+        if source_ptr.0.is_null() {
+            return Ok(None);
+        }
+
+        Self::from_existing_ptr(source_ptr, runtime).map(Some)
+    }
+
+    /// Wraps an existing source, taking ownership of the reference the caller has added.
+    pub(crate) fn from_existing_ptr(
+        source_ptr: Sendable<*mut obs_source_t>,
+        runtime: ObsRuntime,
+    ) -> Result<Self, ObsError> {
+        let ptr = source_ptr.clone();
+        let (id, name) = run_with_obs!(runtime, (ptr), move || unsafe {
+            // Safety: The caller holds a reference to the source, so it is valid
+            let id = libobs::obs_source_get_id(ptr.0);
+            let name = libobs::obs_source_get_name(ptr.0);
+
+            let to_string = |s: *const std::os::raw::c_char| {
+                if s.is_null() {
+                    String::new()
+                } else {
+                    std::ffi::CStr::from_ptr(s).to_string_lossy().into_owned()
+                }
+            };
+
+            (to_string(id), to_string(name))
+        })?;
+
+        let hotkey_data = ImmutableObsData::new(&runtime)?;
+        Self::from_created(
+            source_ptr,
+            ObsString::new(&id),
+            ObsString::new(&name),
+            hotkey_data,
+            runtime,
+        )
+    }
+
+    /// The number of clones of this source, e.g. held by the context, the user or a filter guard.
+    pub(crate) fn handle_count(&self) -> usize {
+        Arc::strong_count(&self.name)
+    }
+
+    /// Wraps every public source that exists in OBS, using `obs_enum_sources`.
+    /// Scenes and private sources are not included.
+    pub fn existing_sources(runtime: ObsRuntime) -> Result<Vec<Self>, ObsError> {
+        let source_ptrs = run_with_obs!(runtime, move || unsafe {
+            let mut sources = Vec::<*mut obs_source_t>::new();
+            // Safety: The callback only pushes into the vector, which outlives the call
+            libobs::obs_enum_sources(
+                Some(collect_source),
+                &mut sources as *mut _ as *mut std::ffi::c_void,
+            );
+
+            sources.into_iter().map(Sendable).collect::<Vec<_>>()
+        })?;
+
+        source_ptrs
+            .into_iter()
+            .map(|ptr| Self::from_existing_ptr(ptr, runtime.clone()))
+            .collect()
+    }
+
+    /// Creates a deep copy of the given source with its own settings and filters,
+    /// using `obs_source_duplicate`.
+    ///
+    /// Private sources are not added to the global source list, so they can't be found by name
+    /// and their name doesn't need to be unique. This is useful for projector-style previews.
+    ///
+    /// Returns an `InvalidOperation` error if the type of the source doesn't support duplication.
+    pub fn duplicate_from<T: ObsSourceTrait + ?Sized>(
+        source: &T,
+        new_name: &str,
+        private: bool,
+    ) -> Result<Self, ObsError> {
+        let runtime = source.runtime().clone();
+        let new_name = ObsString::new(new_name);
+        let source_ptr = source.as_ptr();
+
+        let duplicate_ptr = run_with_obs!(runtime, (source_ptr, new_name), move |token| {
+            if !private {
+                let existing = unsafe {
+                    // Safety: The name pointer is valid because new_name is kept in scope
+                    libobs::obs_get_source_by_name(new_name.as_ptr().0)
+                };
+
+                if !existing.is_null() {
+                    unsafe {
+                        // Safety: obs_get_source_by_name added a reference, which we have to release again
+                        libobs::obs_source_release(existing);
+                    }
+                    return Err(ObsError::NameAlreadyInUse(new_name.to_string()));
+                }
+            }
+
+            let duplicate = unsafe {
+                // Safety: The source is valid because of the smart pointer and the name is kept in scope
+                libobs::obs_source_duplicate(
+                    source_ptr.get_ptr(token),
+                    new_name.as_ptr().0,
+                    private,
+                )
+            };
+
+            if duplicate.is_null() {
+                return Err(ObsError::NullPointer(None).in_function("obs_source_duplicate"));
+            }
+
+            // Sources that can't be duplicated just return a new reference to themselves
+            if duplicate == source_ptr.get_ptr(token) {
+                unsafe {
+                    // Safety: obs_source_duplicate added a reference, which we have to release again
+                    libobs::obs_source_release(duplicate);
+                }
+                return Err(ObsError::InvalidOperation(
+                    "This type of source can't be duplicated".into(),
+                ));
+            }
+
+            Ok(Sendable(duplicate))
+        })??;
+
+        let hotkey_data = ImmutableObsData::new(&runtime)?;
+        Self::from_created(duplicate_ptr, source.id(), new_name, hotkey_data, runtime)
+    }
+}
+
+unsafe extern "C" fn collect_source(
+    param: *mut std::ffi::c_void,
+    source: *mut obs_source_t,
+) -> bool {
+    let sources = &mut *(param as *mut Vec<*mut obs_source_t>);
+
+    // Our own reference, which is owned by the wrapper afterwards. Sources that are
+    // being destroyed return null.
+    let source = libobs::obs_source_get_ref(source);
+    if !source.is_null() {
+        sources.push(source);
+    }
+
+    true
 }
 
 impl ObsObjectTraitPrivate for ObsSourceRef {
@@ -192,8 +372,57 @@ impl ObsObjectTrait<*mut libobs::obs_source_t> for ObsSourceRef {
         self.id.clone()
     }
 
+    /// Returns the current name of this source, including renames that were not done
+    /// through this crate.
     fn name(&self) -> ObsString {
-        self.name.clone()
+        let source_ptr = self.as_ptr();
+        let current = run_with_obs!(self.runtime, (source_ptr), move |token| unsafe {
+            // Safety: The source pointer is valid because of the smart pointer, the name is copied
+            // before the closure returns
+            optional_source_name(libobs::obs_source_get_name(source_ptr.get_ptr(token)))
+        });
+
+        let mut cached = self
+            .name
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Ok(Some(current)) = current {
+            if cached.to_string() != current {
+                *cached = ObsString::new(current);
+            }
+        }
+
+        cached.clone()
+    }
+
+    /// Renames this source by using `obs_source_set_name`.
+    /// Fails with `ObsError::NameAlreadyInUse` if another source already has the name.
+    fn set_name(&self, new_name: &str) -> Result<(), ObsError> {
+        let new_name = ObsString::new(new_name);
+        let source_ptr = self.as_ptr();
+        let name = run_with_obs!(self.runtime, (source_ptr, new_name), move |token| unsafe {
+            // Safety: The source pointer is valid because of the smart pointer and the name is kept in scope
+            let source = source_ptr.get_ptr(token);
+            let existing = libobs::obs_get_source_by_name(new_name.as_ptr().0);
+            if !existing.is_null() {
+                // obs_get_source_by_name added a reference, which we have to release again
+                libobs::obs_source_release(existing);
+                if existing != source {
+                    return Err(ObsError::NameAlreadyInUse(new_name.to_string()));
+                }
+            }
+
+            libobs::obs_source_set_name(source, new_name.as_ptr().0);
+            Ok(optional_source_name(libobs::obs_source_get_name(source)))
+        })??;
+
+        *self
+            .name
+            .write()
+            .map_err(|_| ObsError::LockError("Failed to acquire write lock on name".into()))? =
+            ObsString::new(name.unwrap_or_default());
+
+        Ok(())
     }
 
     fn update_settings(&self, settings: crate::data::ObsData) -> Result<(), ObsError> {
@@ -228,17 +457,21 @@ impl ObsSourceTrait for ObsSourceRef {
 
         let has_filter = guard
             .iter()
-            .any(|f| f.get_inner().as_ptr().get_ptr() == filter.as_ptr().get_ptr());
+            .any(|f| f.get_inner().as_ptr().ptr_eq(&filter.as_ptr()));
 
         if has_filter {
             return Err(ObsError::FilterAlreadyApplied);
         }
 
-        run_with_obs!(self.runtime(), (source_ptr, filter_ptr), move || unsafe {
-            // Safety: Both pointers are valid because of the smart pointers.
-            libobs::obs_source_filter_add(source_ptr.get_ptr(), filter_ptr.get_ptr());
-            Ok(())
-        })??;
+        run_with_obs!(
+            self.runtime(),
+            (source_ptr, filter_ptr),
+            move |token| unsafe {
+                // Safety: Both pointers are valid because of the smart pointers.
+                libobs::obs_source_filter_add(source_ptr.get_ptr(token), filter_ptr.get_ptr(token));
+                Ok(())
+            }
+        )??;
 
         let runtime = self.runtime().clone();
         let drop_guard = _ObsRemoveFilterOnDrop::new(self.as_ptr(), filter.as_ptr(), None, runtime);
@@ -250,11 +483,46 @@ impl ObsSourceTrait for ObsSourceRef {
 
         Ok(())
     }
+
+    fn remove_filter(&self, filter: &ObsFilterRef) -> Result<(), ObsError> {
+        let mut guard = self.attached_filters.write().map_err(|_| {
+            ObsError::LockError("Failed to acquire write lock on attached filters".into())
+        })?;
+
+        let Some(index) = guard
+            .iter()
+            .position(|f| f.get_inner().as_ptr().ptr_eq(&filter.as_ptr()))
+        else {
+            return Err(ObsError::InvalidOperation(
+                "The filter is not applied to this source".into(),
+            ));
+        };
+
+        let source_ptr = self.as_ptr();
+        let filter_ptr = filter.as_ptr();
+        run_with_obs!(
+            self.runtime(),
+            (source_ptr, filter_ptr),
+            move |token| unsafe {
+                // Safety: Both pointers are valid because of the smart pointers.
+                libobs::obs_source_filter_remove(
+                    source_ptr.get_ptr(token),
+                    filter_ptr.get_ptr(token),
+                );
+            }
+        )?;
+
+        // Clones of the pair returned by `get_active_filters` may keep the drop guard alive,
+        // so the filter has been removed explicitly above
+        guard.remove(index);
+
+        Ok(())
+    }
 }
 
-impl_signal_manager!(|ptr: SmartPointerSendable<*mut libobs::obs_source_t>| unsafe {
+impl_signal_manager!(|ptr: SmartPointerSendable<*mut libobs::obs_source_t>, token: ObsThreadToken| unsafe {
     // Safety: We are using a smart pointer, so it is fine
-    libobs::obs_source_get_signal_handler(ptr.get_ptr())
+    libobs::obs_source_get_signal_handler(ptr.get_ptr(token))
 }, ObsSourceSignals for *mut libobs::obs_source_t, [
     "destroy": {},
     "remove": {},
@@ -338,3 +606,16 @@ impl_obs_drop!(_ObsSourceGuard, (source), move || unsafe {
     // Safety: We are in the runtime and the pointer is valid because of the drop guard
     libobs::obs_source_release(source.0);
 });
+
+/// Safety: The pointer must be null or point to a valid C string
+unsafe fn optional_source_name(name: *const std::os::raw::c_char) -> Option<String> {
+    if name.is_null() {
+        return None;
+    }
+
+    Some(
+        std::ffi::CStr::from_ptr(name)
+            .to_string_lossy()
+            .into_owned(),
+    )
+}
@@ -1,10 +1,16 @@
 use crate::{
-    data::object::ObsObjectTrait,
+    data::{object::ObsObjectTrait, properties::ObsPropertiesWatcher},
+    enums::{
+        ObsDeinterlaceFieldOrder, ObsDeinterlaceMode, ObsSourceFlags, ObsSourceOutputFlags,
+        OsEnumType,
+    },
     macros::impl_eq_of_ptr,
-    sources::{ObsFilterRef, ObsSourceSignals, _ObsRemoveFilterOnDrop},
+    run_with_obs,
+    sources::{_ObsRemoveFilterOnDrop, ObsFilterRef, ObsSourceRef, ObsSourceSignals},
     utils::ObsError,
 };
-use std::sync::Arc;
+use num_traits::FromPrimitive;
+use std::{sync::Arc, time::Duration};
 
 #[derive(Debug, Clone)]
 pub struct ObsFilterGuardPair {
@@ -35,6 +41,155 @@ pub trait ObsSourceTrait: ObsObjectTrait<*mut libobs::obs_source_t> {
 
     fn get_active_filters(&self) -> Result<Vec<ObsFilterGuardPair>, ObsError>;
     fn apply_filter(&self, filter: &ObsFilterRef) -> Result<(), ObsError>;
+    /// Removes a filter that has been applied with `apply_filter` from this source.
+    fn remove_filter(&self, filter: &ObsFilterRef) -> Result<(), ObsError>;
+
+    /// Enables or disables this source. A disabled source is neither rendered nor plays audio,
+    /// but stays in every scene it has been added to, so it can be enabled again later on.
+    fn set_enabled(&self, enabled: bool) -> Result<(), ObsError> {
+        let source_ptr = self.as_ptr();
+        run_with_obs!(self.runtime(), (source_ptr), move |token| unsafe {
+            // Safety: The pointer is valid because of the smart pointer
+            libobs::obs_source_set_enabled(source_ptr.get_ptr(token), enabled);
+        })
+    }
+
+    fn enabled(&self) -> Result<bool, ObsError> {
+        let source_ptr = self.as_ptr();
+        run_with_obs!(self.runtime(), (source_ptr), move |token| unsafe {
+            // Safety: The pointer is valid because of the smart pointer
+            libobs::obs_source_enabled(source_ptr.get_ptr(token))
+        })
+    }
+
+    /// Sets whether the audio of this source is active. Sources whose audio is inactive are
+    /// hidden from the mixer, which is useful for sources that should only be displayed.
+    fn set_audio_active(&self, active: bool) -> Result<(), ObsError> {
+        let source_ptr = self.as_ptr();
+        run_with_obs!(self.runtime(), (source_ptr), move |token| unsafe {
+            // Safety: The pointer is valid because of the smart pointer
+            libobs::obs_source_set_audio_active(source_ptr.get_ptr(token), active);
+        })
+    }
+
+    fn audio_active(&self) -> Result<bool, ObsError> {
+        let source_ptr = self.as_ptr();
+        run_with_obs!(self.runtime(), (source_ptr), move |token| unsafe {
+            // Safety: The pointer is valid because of the smart pointer
+            libobs::obs_source_audio_active(source_ptr.get_ptr(token))
+        })
+    }
+
+    /// Mutes or unmutes the audio of this source.
+    fn set_muted(&self, muted: bool) -> Result<(), ObsError> {
+        let source_ptr = self.as_ptr();
+        run_with_obs!(self.runtime(), (source_ptr), move |token| unsafe {
+            // Safety: The pointer is valid because of the smart pointer
+            libobs::obs_source_set_muted(source_ptr.get_ptr(token), muted);
+        })
+    }
+
+    fn muted(&self) -> Result<bool, ObsError> {
+        let source_ptr = self.as_ptr();
+        run_with_obs!(self.runtime(), (source_ptr), move |token| unsafe {
+            // Safety: The pointer is valid because of the smart pointer
+            libobs::obs_source_muted(source_ptr.get_ptr(token))
+        })
+    }
+
+    /// Replaces the flags of this source.
+    fn set_flags(&self, flags: ObsSourceFlags) -> Result<(), ObsError> {
+        let source_ptr = self.as_ptr();
+        let flags = flags.bits();
+        run_with_obs!(self.runtime(), (source_ptr), move |token| unsafe {
+            // Safety: The pointer is valid because of the smart pointer
+            libobs::obs_source_set_flags(source_ptr.get_ptr(token), flags);
+        })
+    }
+
+    /// Returns the flags of this source, unknown flags are dropped.
+    fn flags(&self) -> Result<ObsSourceFlags, ObsError> {
+        let source_ptr = self.as_ptr();
+        let flags = run_with_obs!(self.runtime(), (source_ptr), move |token| unsafe {
+            // Safety: The pointer is valid because of the smart pointer
+            libobs::obs_source_get_flags(source_ptr.get_ptr(token))
+        })?;
+
+        Ok(ObsSourceFlags::from_bits_truncate(flags))
+    }
+
+    /// Returns the capabilities of the type of this source, e.g. whether it outputs audio or
+    /// can be interacted with. Unknown flags are dropped.
+    fn output_flags(&self) -> Result<ObsSourceOutputFlags, ObsError> {
+        let source_ptr = self.as_ptr();
+        let flags = run_with_obs!(self.runtime(), (source_ptr), move |token| unsafe {
+            // Safety: The pointer is valid because of the smart pointer
+            libobs::obs_source_get_output_flags(source_ptr.get_ptr(token))
+        })?;
+
+        Ok(ObsSourceOutputFlags::from_bits_truncate(flags))
+    }
+
+    /// Sets how interlaced video of this source is deinterlaced.
+    /// This only has an effect on async video sources, e.g. capture cards or media sources.
+    fn set_deinterlace_mode(&self, mode: ObsDeinterlaceMode) -> Result<(), ObsError> {
+        let source_ptr = self.as_ptr();
+        let mode = mode as OsEnumType;
+        run_with_obs!(self.runtime(), (source_ptr), move |token| unsafe {
+            // Safety: The pointer is valid because of the smart pointer
+            libobs::obs_source_set_deinterlace_mode(source_ptr.get_ptr(token), mode);
+        })
+    }
+
+    fn deinterlace_mode(&self) -> Result<ObsDeinterlaceMode, ObsError> {
+        let source_ptr = self.as_ptr();
+        let mode = run_with_obs!(self.runtime(), (source_ptr), move |token| unsafe {
+            // Safety: The pointer is valid because of the smart pointer
+            libobs::obs_source_get_deinterlace_mode(source_ptr.get_ptr(token))
+        })?;
+
+        ObsDeinterlaceMode::from_i64(mode as i64)
+            .ok_or_else(|| ObsError::Unexpected(format!("Unknown deinterlace mode {}", mode)))
+    }
+
+    /// Sets which field of the interlaced video of this source comes first.
+    fn set_deinterlace_field_order(&self, order: ObsDeinterlaceFieldOrder) -> Result<(), ObsError> {
+        let source_ptr = self.as_ptr();
+        let order = order as OsEnumType;
+        run_with_obs!(self.runtime(), (source_ptr), move |token| unsafe {
+            // Safety: The pointer is valid because of the smart pointer
+            libobs::obs_source_set_deinterlace_field_order(source_ptr.get_ptr(token), order);
+        })
+    }
+
+    fn deinterlace_field_order(&self) -> Result<ObsDeinterlaceFieldOrder, ObsError> {
+        let source_ptr = self.as_ptr();
+        let order = run_with_obs!(self.runtime(), (source_ptr), move |token| unsafe {
+            // Safety: The pointer is valid because of the smart pointer
+            libobs::obs_source_get_deinterlace_field_order(source_ptr.get_ptr(token))
+        })?;
+
+        ObsDeinterlaceFieldOrder::from_i64(order as i64).ok_or_else(|| {
+            ObsError::Unexpected(format!("Unknown deinterlace field order {}", order))
+        })
+    }
+
+    /// Sends the properties of this source whenever they change, see [`ObsPropertiesWatcher`].
+    fn watch_properties(&self, debounce: Duration) -> Result<ObsPropertiesWatcher, ObsError>
+    where
+        Self: Clone + Send + Sized + 'static,
+    {
+        ObsPropertiesWatcher::new(self, debounce)
+    }
+
+    /// Creates a deep copy of this source with its own settings and filters,
+    /// see [`ObsSourceRef::duplicate_from`].
+    fn duplicate(&self, new_name: &str, private: bool) -> Result<ObsSourceRef, ObsError>
+    where
+        Self: Sized,
+    {
+        ObsSourceRef::duplicate_from(self, new_name, private)
+    }
 }
 
 impl_eq_of_ptr!(dyn ObsSourceTrait);
@@ -0,0 +1,237 @@
+use std::{
+    collections::VecDeque,
+    sync::mpsc::{self, RecvTimeoutError},
+    time::{Duration, Instant},
+};
+
+use tokio::sync::broadcast;
+
+use crate::{runtime::ObsRuntime, stats::ObsFrameStats, utils::ObsError};
+
+/// The frame counter an alarm is watching.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ObsFrameMetric {
+    /// Frames missed due to rendering lag, compared to the total rendered frames
+    RenderLagged,
+    /// Frames skipped due to encoding lag, compared to the total frames sent to the encoders
+    EncoderSkipped,
+}
+
+/// Fires when the share of dropped frames of a metric exceeds a threshold over a time window,
+/// e.g. "encoder skipped frames > 5% over 10s".
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ObsFrameDropAlarm {
+    metric: ObsFrameMetric,
+    threshold: f64,
+    window: Duration,
+}
+
+impl ObsFrameDropAlarm {
+    /// Creates a new alarm, `threshold_percent` is clamped to 0 - 100.
+    pub fn new(metric: ObsFrameMetric, threshold_percent: f64, window: Duration) -> Self {
+        Self {
+            metric,
+            threshold: threshold_percent.clamp(0.0, 100.0) / 100.0,
+            window,
+        }
+    }
+
+    pub fn metric(&self) -> ObsFrameMetric {
+        self.metric
+    }
+
+    pub fn threshold_percent(&self) -> f64 {
+        self.threshold * 100.0
+    }
+
+    pub fn window(&self) -> Duration {
+        self.window
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ObsFrameDropAlarmState {
+    /// The threshold was exceeded
+    Triggered,
+    /// The alarm was triggered before and the dropped frames are below the threshold again
+    Cleared,
+}
+
+/// Sent by the `ObsStatsMonitor` whenever an alarm changes its state.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ObsFrameDropEvent {
+    pub alarm: ObsFrameDropAlarm,
+    pub state: ObsFrameDropAlarmState,
+    /// Frames dropped within the alarm window
+    pub dropped_frames: u32,
+    /// Total frames within the alarm window
+    pub total_frames: u32,
+}
+
+impl ObsFrameDropEvent {
+    /// Share of dropped frames within the alarm window in percent.
+    pub fn dropped_percent(&self) -> f64 {
+        if self.total_frames == 0 {
+            return 0.0;
+        }
+
+        self.dropped_frames as f64 / self.total_frames as f64 * 100.0
+    }
+}
+
+/// Polls the frame counters of OBS in the background and evaluates the given alarms.
+///
+/// Events are only sent when an alarm changes its state, so a consistently overloaded encoder
+/// results in a single `Triggered` event until it recovers. The monitor stops once it is dropped.
+#[derive(Debug)]
+pub struct ObsStatsMonitor {
+    events: broadcast::Sender<ObsFrameDropEvent>,
+    _stop: mpsc::Sender<()>,
+}
+
+impl ObsStatsMonitor {
+    /// Starts monitoring the frame counters every `poll_interval`.
+    pub fn new(
+        runtime: ObsRuntime,
+        alarms: Vec<ObsFrameDropAlarm>,
+        poll_interval: Duration,
+    ) -> Result<Self, ObsError> {
+        let (events, _) = broadcast::channel(16);
+        let (stop_tx, stop_rx) = mpsc::channel::<()>();
+
+        let max_window = alarms.iter().map(|a| a.window).max().unwrap_or_default();
+
+        let sender = events.clone();
+        std::thread::Builder::new()
+            .name("libobs-wrapper-stats".to_string())
+            .spawn(move || {
+                let mut history = StatsHistory::default();
+                let mut trackers = alarms
+                    .into_iter()
+                    .map(AlarmTracker::new)
+                    .collect::<Vec<_>>();
+
+                // The loop ends as soon as the monitor is dropped, as the stop sender disconnects.
+                while let Err(RecvTimeoutError::Timeout) = stop_rx.recv_timeout(poll_interval) {
+                    let stats = match ObsFrameStats::read(&runtime) {
+                        Ok(stats) => stats,
+                        Err(e) => {
+                            log::debug!("Stopping stats monitor, could not read stats: {:?}", e);
+                            break;
+                        }
+                    };
+
+                    history.push(Instant::now(), stats, max_window);
+                    for tracker in trackers.iter_mut() {
+                        if let Some(event) = tracker.evaluate(&history) {
+                            // Sending only fails if there are no receivers, which is fine
+                            let _ = sender.send(event);
+                        }
+                    }
+                }
+            })
+            .map_err(|e| {
+                ObsError::InvalidOperation(format!("Failed to spawn stats monitor thread: {}", e))
+            })?;
+
+        Ok(Self {
+            events,
+            _stop: stop_tx,
+        })
+    }
+
+    /// Subscribes to the alarm events of this monitor.
+    pub fn subscribe(&self) -> broadcast::Receiver<ObsFrameDropEvent> {
+        self.events.subscribe()
+    }
+}
+
+#[derive(Debug, Default)]
+pub(super) struct StatsHistory {
+    samples: VecDeque<(Instant, ObsFrameStats)>,
+}
+
+impl StatsHistory {
+    pub(super) fn push(&mut self, at: Instant, stats: ObsFrameStats, max_window: Duration) {
+        // The counters start over when the video pipeline is reset
+        let was_reset = self.samples.back().is_some_and(|(_, last)| {
+            stats.rendered_frames < last.rendered_frames || stats.output_frames < last.output_frames
+        });
+        if was_reset {
+            self.samples.clear();
+        }
+
+        self.samples.push_back((at, stats));
+
+        // Keep a single sample that is older than the largest window as baseline
+        while self
+            .samples
+            .get(1)
+            .is_some_and(|(t, _)| at.duration_since(*t) >= max_window)
+        {
+            self.samples.pop_front();
+        }
+    }
+
+    /// Returns the (dropped, total) frames of the metric over the given window,
+    /// `None` if the history does not span the whole window yet.
+    pub(super) fn window_counters(
+        &self,
+        metric: ObsFrameMetric,
+        window: Duration,
+    ) -> Option<(u32, u32)> {
+        let (now, latest) = self.samples.back()?;
+        let (_, baseline) = self
+            .samples
+            .iter()
+            .rev()
+            .find(|(t, _)| now.duration_since(*t) >= window)?;
+
+        let (dropped_now, total_now) = latest.counters(metric);
+        let (dropped_then, total_then) = baseline.counters(metric);
+
+        Some((
+            dropped_now.saturating_sub(dropped_then),
+            total_now.saturating_sub(total_then),
+        ))
+    }
+}
+
+#[derive(Debug)]
+pub(super) struct AlarmTracker {
+    alarm: ObsFrameDropAlarm,
+    active: bool,
+}
+
+impl AlarmTracker {
+    pub(super) fn new(alarm: ObsFrameDropAlarm) -> Self {
+        Self {
+            alarm,
+            active: false,
+        }
+    }
+
+    pub(super) fn evaluate(&mut self, history: &StatsHistory) -> Option<ObsFrameDropEvent> {
+        let (dropped, total) = history.window_counters(self.alarm.metric, self.alarm.window)?;
+        if total == 0 {
+            return None;
+        }
+
+        let exceeded = dropped as f64 / total as f64 > self.alarm.threshold;
+        if exceeded == self.active {
+            return None;
+        }
+
+        self.active = exceeded;
+        Some(ObsFrameDropEvent {
+            alarm: self.alarm,
+            state: if exceeded {
+                ObsFrameDropAlarmState::Triggered
+            } else {
+                ObsFrameDropAlarmState::Cleared
+            },
+            dropped_frames: dropped,
+            total_frames: total,
+        })
+    }
+}
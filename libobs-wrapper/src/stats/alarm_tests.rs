@@ -0,0 +1,74 @@
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::super::{
+        alarm::{AlarmTracker, StatsHistory},
+        ObsFrameDropAlarm, ObsFrameDropAlarmState, ObsFrameMetric, ObsFrameStats,
+    };
+
+    fn stats(output_frames: u32, skipped_frames: u32) -> ObsFrameStats {
+        ObsFrameStats {
+            output_frames,
+            skipped_frames,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn alarm_triggers_and_clears_over_window() {
+        let alarm =
+            ObsFrameDropAlarm::new(ObsFrameMetric::EncoderSkipped, 5.0, Duration::from_secs(10));
+        let mut tracker = AlarmTracker::new(alarm);
+        let mut history = StatsHistory::default();
+        let start = Instant::now();
+
+        // 60 fps, 10% skipped, but the window is not full yet
+        for second in 0..10u32 {
+            let at = start + Duration::from_secs(second as u64);
+            history.push(at, stats(second * 60, second * 6), alarm.window());
+            assert_eq!(tracker.evaluate(&history), None);
+        }
+
+        history.push(
+            start + Duration::from_secs(10),
+            stats(600, 60),
+            alarm.window(),
+        );
+        let event = tracker.evaluate(&history).unwrap();
+        assert_eq!(event.state, ObsFrameDropAlarmState::Triggered);
+        assert_eq!((event.dropped_frames, event.total_frames), (60, 600));
+
+        // Still overloaded, no new event
+        history.push(
+            start + Duration::from_secs(11),
+            stats(660, 66),
+            alarm.window(),
+        );
+        assert_eq!(tracker.evaluate(&history), None);
+
+        // No frames skipped for a whole window
+        history.push(
+            start + Duration::from_secs(21),
+            stats(1260, 66),
+            alarm.window(),
+        );
+        let event = tracker.evaluate(&history).unwrap();
+        assert_eq!(event.state, ObsFrameDropAlarmState::Cleared);
+    }
+
+    #[test]
+    fn history_restarts_after_counter_reset() {
+        let window = Duration::from_secs(1);
+        let mut history = StatsHistory::default();
+        let start = Instant::now();
+
+        history.push(start, stats(600, 60), window);
+        history.push(start + window, stats(10, 0), window);
+
+        assert_eq!(
+            history.window_counters(ObsFrameMetric::EncoderSkipped, window),
+            None
+        );
+    }
+}
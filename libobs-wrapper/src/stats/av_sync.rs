@@ -0,0 +1,550 @@
+//! Measures the offset between audio and video by injecting a flash and a beep at the same
+//! timestamp and detecting both in the raw video and audio that are passed to the encoders.
+
+use std::{
+    ffi::c_void,
+    sync::{
+        mpsc::{self, RecvTimeoutError},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use crate::{
+    enums::{ObsAudioFormat, ObsBoundsType, ObsSpeakerLayout, ObsVideoFormat},
+    graphics::Vec2,
+    impl_obs_drop, run_with_obs,
+    runtime::ObsRuntime,
+    scenes::{ObsSceneItemRef, ObsSceneRef, SceneItemExtSceneTrait, SceneItemTrait},
+    sources::{ObsAudioFrame, ObsBufferSource, ObsFramePlane, ObsVideoFrame},
+    unsafe_send::Sendable,
+    utils::{ObsDropGuard, ObsError, ObsString},
+};
+
+/// Size of the frames pushed into the probe source, it is stretched to the canvas anyway
+const PROBE_SIZE: u32 = 16;
+/// Sample rate of the generated beep, OBS resamples it to its own rate
+const PROBE_SAMPLE_RATE: u32 = 48000;
+/// Length of every chunk of generated audio
+const PROBE_AUDIO_CHUNK: u32 = PROBE_SAMPLE_RATE / 100;
+
+/// Size the raw video is scaled down to before its brightness is measured
+const DETECT_WIDTH: u32 = 32;
+const DETECT_HEIGHT: u32 = 18;
+/// Difference between the darkest and brightest frame (0 - 255) before flashes are detected
+const MIN_FLASH_CONTRAST: f32 = 32.0;
+/// Amplitude from which on a sample is part of a beep
+const BEEP_THRESHOLD: f32 = 0.1;
+/// How many onsets are kept, older ones are discarded
+const MAX_ONSETS: usize = 1024;
+
+/// Configuration of an [`ObsAvSyncProbe`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ObsAvSyncProbeConfig {
+    /// Time between two flashes
+    pub interval: Duration,
+    /// How long every flash and beep lasts, has to be shorter than half of the interval
+    pub pulse: Duration,
+    /// Frequency of the beep in Hz
+    pub frequency: f32,
+    /// The audio mixer the beep is detected in
+    pub mixer: usize,
+}
+
+impl Default for ObsAvSyncProbeConfig {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(1),
+            pulse: Duration::from_millis(100),
+            frequency: 1000.0,
+            mixer: 0,
+        }
+    }
+}
+
+/// The offsets measured by an [`ObsAvSyncProbe`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ObsAvSyncReport {
+    /// Offset of every detected beep to its flash in nanoseconds, oldest first.
+    /// Positive values mean that the audio is late.
+    pub offsets: Vec<i64>,
+}
+
+impl ObsAvSyncReport {
+    /// Mean offset in nanoseconds, `None` if nothing has been detected yet.
+    pub fn mean_offset(&self) -> Option<i64> {
+        if self.offsets.is_empty() {
+            return None;
+        }
+
+        let sum: i128 = self.offsets.iter().map(|offset| *offset as i128).sum();
+        Some((sum / self.offsets.len() as i128) as i64)
+    }
+
+    /// How much the offset changed between the first and the last measurement in nanoseconds,
+    /// `None` if less than two offsets have been measured.
+    pub fn drift(&self) -> Option<i64> {
+        match (self.offsets.first(), self.offsets.last()) {
+            (Some(first), Some(last)) if self.offsets.len() > 1 => Some(last - first),
+            _ => None,
+        }
+    }
+
+    /// The value for `obs_source_set_sync_offset` that cancels out the mean offset,
+    /// for sources that go through the same pipeline.
+    pub fn suggested_sync_offset(&self) -> Option<i64> {
+        self.mean_offset().map(|offset| -offset)
+    }
+}
+
+/// Injects a white flash and a beep at the same timestamp every interval and measures how far
+/// apart they are in the raw video and audio that are passed to the encoders. These are the
+/// timestamps the encoded packets get, so the offset is the one of the recording or stream.
+///
+/// Add the [`source`](Self::source) to a scene that is otherwise static, e.g. with
+/// [`add_to_scene`](Self::add_to_scene), as the flashes are detected by the brightness of the
+/// whole canvas. The probe stops once it is dropped.
+#[derive(Debug)]
+pub struct ObsAvSyncProbe {
+    source: ObsBufferSource,
+    detector: Arc<Mutex<AvSyncDetector>>,
+    runtime: ObsRuntime,
+    _stop: mpsc::Sender<()>,
+    _guard: _ObsAvSyncProbeDropGuard,
+}
+
+impl ObsAvSyncProbe {
+    pub fn new<K: Into<ObsString> + Send + Sync>(
+        name: K,
+        config: ObsAvSyncProbeConfig,
+        runtime: ObsRuntime,
+    ) -> Result<Self, ObsError> {
+        if config.interval.is_zero()
+            || config.pulse.is_zero()
+            || config.pulse * 2 >= config.interval
+        {
+            return Err(ObsError::InvalidOperation(format!(
+                "The pulse ({:?}) has to be shorter than half of the interval ({:?})",
+                config.pulse, config.interval
+            )));
+        }
+
+        let source = ObsBufferSource::new(name, runtime.clone())?;
+        let detector = Arc::new(Mutex::new(AvSyncDetector::new(config.interval)));
+
+        let data = Box::new(ProbeCallbackData {
+            detector: detector.clone(),
+            samples_per_sec: 0,
+        });
+        let data = Sendable(Box::into_raw(data));
+        let mixer = config.mixer;
+
+        let added = run_with_obs!(runtime, (data), move || unsafe {
+            // Safety: We are providing a valid pointer to be filled
+            let mut oai = std::mem::zeroed::<libobs::obs_audio_info>();
+            if !libobs::obs_get_audio_info(&mut oai) {
+                return Err(ObsError::NullPointer(Some(
+                    "Failed to get audio info".to_string(),
+                )));
+            }
+
+            // Safety: The data is not shared with OBS yet
+            (*data.0).samples_per_sec = oai.samples_per_sec;
+
+            let video_conversion = libobs::video_scale_info {
+                format: ObsVideoFormat::RGBA as _,
+                width: DETECT_WIDTH,
+                height: DETECT_HEIGHT,
+                range: libobs::video_range_type_VIDEO_RANGE_FULL,
+                colorspace: libobs::video_colorspace_VIDEO_CS_DEFAULT,
+            };
+            let audio_conversion = libobs::audio_convert_info {
+                samples_per_sec: oai.samples_per_sec,
+                format: ObsAudioFormat::Float as _,
+                speakers: ObsSpeakerLayout::Mono as _,
+                allow_clipping: false,
+            };
+
+            // Safety: The data is freed after both callbacks have been removed in the drop guard
+            libobs::obs_add_raw_video_callback(
+                &video_conversion,
+                Some(raw_video_callback),
+                data.0 as *mut c_void,
+            );
+            libobs::obs_add_raw_audio_callback(
+                mixer,
+                &audio_conversion,
+                Some(raw_audio_callback),
+                data.0 as *mut c_void,
+            );
+
+            Ok(())
+        })
+        .and_then(|result| result);
+
+        if let Err(e) = added {
+            unsafe {
+                // Safety: The callbacks have not been added, so the data is not used by OBS
+                drop(Box::from_raw(data.0));
+            }
+            return Err(e);
+        }
+
+        let guard = _ObsAvSyncProbeDropGuard {
+            data,
+            mixer,
+            runtime: runtime.clone(),
+        };
+
+        let (stop_tx, stop_rx) = mpsc::channel::<()>();
+        let generator = source.clone();
+        std::thread::Builder::new()
+            .name("libobs-wrapper-av-sync".to_string())
+            .spawn(move || generate_pulses(generator, config, stop_rx))
+            .map_err(|e| {
+                ObsError::InvalidOperation(format!("Failed to spawn A/V sync probe thread: {}", e))
+            })?;
+
+        Ok(Self {
+            source,
+            detector,
+            runtime,
+            _stop: stop_tx,
+            _guard: guard,
+        })
+    }
+
+    /// The source that shows the flashes and plays the beeps.
+    pub fn source(&self) -> &ObsBufferSource {
+        &self.source
+    }
+
+    /// Adds the source to the scene and stretches it over the whole canvas.
+    pub fn add_to_scene(
+        &self,
+        scene: &mut ObsSceneRef,
+    ) -> Result<ObsSceneItemRef<ObsBufferSource>, ObsError> {
+        let (width, height) = run_with_obs!(self.runtime, move || unsafe {
+            // Safety: We are providing a valid pointer to be filled
+            let mut ovi = std::mem::zeroed::<libobs::obs_video_info>();
+            if !libobs::obs_get_video_info(&mut ovi) {
+                return Err(ObsError::NullPointer(Some(
+                    "Failed to get video info".to_string(),
+                )));
+            }
+
+            Ok((ovi.base_width, ovi.base_height))
+        })??;
+
+        let item = scene.add_source(self.source.clone())?;
+        item.set_bounds(
+            Vec2::new(width as f32, height as f32),
+            ObsBoundsType::Stretch,
+        )?;
+
+        Ok(item)
+    }
+
+    /// Returns the offsets that have been measured so far.
+    pub fn report(&self) -> ObsAvSyncReport {
+        self.detector
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .report()
+    }
+
+    /// Discards every measurement, e.g. after a sync offset has been changed.
+    pub fn reset(&self) {
+        self.detector
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clear();
+    }
+}
+
+struct ProbeCallbackData {
+    detector: Arc<Mutex<AvSyncDetector>>,
+    samples_per_sec: u32,
+}
+
+#[derive(Debug)]
+struct _ObsAvSyncProbeDropGuard {
+    data: Sendable<*mut ProbeCallbackData>,
+    mixer: usize,
+    runtime: ObsRuntime,
+}
+
+impl ObsDropGuard for _ObsAvSyncProbeDropGuard {}
+
+impl_obs_drop!(_ObsAvSyncProbeDropGuard, (data, mixer), move || unsafe {
+    // Safety: Both callbacks are removed before their data is freed, so it can't be accessed afterwards.
+    libobs::obs_remove_raw_video_callback(Some(raw_video_callback), data.0 as *mut c_void);
+    libobs::obs_remove_raw_audio_callback(mixer, Some(raw_audio_callback), data.0 as *mut c_void);
+    drop(Box::from_raw(data.0));
+});
+
+#[allow(unknown_lints)]
+#[allow(ensure_obs_call_in_runtime)]
+/// # Safety
+/// Only called by OBS on the video thread with the data registered in `ObsAvSyncProbe::new`
+/// and a RGBA frame of `DETECT_WIDTH`x`DETECT_HEIGHT`.
+unsafe extern "C" fn raw_video_callback(param: *mut c_void, frame: *mut libobs::video_data) {
+    if frame.is_null() || (*frame).data[0].is_null() {
+        return;
+    }
+
+    let data = &*(param as *const ProbeCallbackData);
+    let frame = &*frame;
+    let linesize = frame.linesize[0] as usize;
+
+    // Safety: The plane holds `linesize` bytes for every row
+    let pixels = std::slice::from_raw_parts(frame.data[0], linesize * DETECT_HEIGHT as usize);
+    let luma = mean_luma(pixels, linesize, DETECT_WIDTH, DETECT_HEIGHT);
+
+    data.detector
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .push_video(frame.timestamp, luma);
+}
+
+#[allow(unknown_lints)]
+#[allow(ensure_obs_call_in_runtime)]
+/// # Safety
+/// Only called by OBS on the audio thread with the data registered in `ObsAvSyncProbe::new`
+/// and mono float samples.
+unsafe extern "C" fn raw_audio_callback(
+    param: *mut c_void,
+    _mix_idx: usize,
+    audio: *mut libobs::audio_data,
+) {
+    if audio.is_null() || (*audio).data[0].is_null() {
+        return;
+    }
+
+    let data = &*(param as *const ProbeCallbackData);
+    let audio = &*audio;
+
+    // Safety: The mono plane holds `frames` float samples
+    let samples = std::slice::from_raw_parts(audio.data[0] as *const f32, audio.frames as usize);
+
+    data.detector
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .push_audio(audio.timestamp, data.samples_per_sec, samples);
+}
+
+/// Pushes a flash and a beep into the source every interval until the probe is dropped.
+///
+/// Video frames are only pushed when the flash starts or ends, so their timestamps match the
+/// beeps exactly. Audio is pushed in chunks of 10ms.
+fn generate_pulses(
+    source: ObsBufferSource,
+    config: ObsAvSyncProbeConfig,
+    stop_rx: mpsc::Receiver<()>,
+) {
+    let interval = config.interval.as_nanos() as u64;
+    let pulse = config.pulse.as_nanos() as u64;
+
+    let black = vec![0u8; (PROBE_SIZE * PROBE_SIZE * 4) as usize];
+    let white = vec![255u8; (PROBE_SIZE * PROBE_SIZE * 4) as usize];
+
+    let start = Instant::now();
+    let mut flash_on = false;
+    let mut next_flash_event = 0u64;
+    let mut audio_samples = 0u64;
+    let mut audio = vec![0f32; PROBE_AUDIO_CHUNK as usize];
+
+    loop {
+        let now = start.elapsed().as_nanos() as u64;
+
+        while next_flash_event <= now {
+            flash_on = !flash_on;
+            let pixels = if flash_on { &white } else { &black };
+            let frame = ObsVideoFrame::new(
+                ObsVideoFormat::BGRA,
+                PROBE_SIZE,
+                PROBE_SIZE,
+                vec![ObsFramePlane {
+                    data: pixels,
+                    linesize: PROBE_SIZE * 4,
+                }],
+                next_flash_event,
+            );
+
+            if let Err(e) = source.push_video(&frame) {
+                log::warn!("Failed to push A/V sync frame: {:?}", e);
+            }
+
+            next_flash_event = if flash_on {
+                next_flash_event + pulse
+            } else {
+                next_flash_event - pulse + interval
+            };
+        }
+
+        let mut next_audio = sample_timestamp(audio_samples);
+        while next_audio <= now {
+            for (index, sample) in audio.iter_mut().enumerate() {
+                let timestamp = sample_timestamp(audio_samples + index as u64);
+                let phase = timestamp % interval;
+                *sample = if phase < pulse {
+                    let seconds = phase as f32 / 1_000_000_000.0;
+                    (seconds * config.frequency * std::f32::consts::TAU).sin() * 0.5
+                } else {
+                    0.0
+                };
+            }
+
+            let bytes: Vec<u8> = audio
+                .iter()
+                .flat_map(|sample| sample.to_ne_bytes())
+                .collect();
+            let frame = ObsAudioFrame {
+                format: ObsAudioFormat::Float,
+                speakers: ObsSpeakerLayout::Mono,
+                samples_per_sec: PROBE_SAMPLE_RATE,
+                frames: PROBE_AUDIO_CHUNK,
+                planes: vec![&bytes],
+                timestamp: next_audio,
+            };
+
+            if let Err(e) = source.push_audio(&frame) {
+                log::warn!("Failed to push A/V sync audio: {:?}", e);
+            }
+
+            audio_samples += PROBE_AUDIO_CHUNK as u64;
+            next_audio = sample_timestamp(audio_samples);
+        }
+
+        let wait = next_flash_event.min(next_audio).saturating_sub(now);
+        // The loop ends as soon as the probe is dropped, as the stop sender disconnects.
+        if let Err(RecvTimeoutError::Disconnected) | Ok(()) =
+            stop_rx.recv_timeout(Duration::from_nanos(wait))
+        {
+            break;
+        }
+    }
+}
+
+fn sample_timestamp(sample: u64) -> u64 {
+    sample * 1_000_000_000 / PROBE_SAMPLE_RATE as u64
+}
+
+/// Mean BT.601 luma (0 - 255) of a RGBA image.
+fn mean_luma(pixels: &[u8], linesize: usize, width: u32, height: u32) -> f32 {
+    let mut sum = 0u64;
+    for row in 0..height as usize {
+        let start = row * linesize;
+        for pixel in pixels[start..start + width as usize * 4].chunks_exact(4) {
+            sum += (pixel[0] as u64 * 299 + pixel[1] as u64 * 587 + pixel[2] as u64 * 114) / 1000;
+        }
+    }
+
+    sum as f32 / (width as u64 * height as u64) as f32
+}
+
+/// Finds the starts of flashes and beeps and pairs them up.
+#[derive(Debug)]
+pub(super) struct AvSyncDetector {
+    /// Flashes and beeps further apart than this are not paired
+    max_offset: u64,
+    darkest: Option<f32>,
+    brightest: Option<f32>,
+    flash_on: bool,
+    /// Timestamp of the last sample that was part of a beep
+    last_loud: Option<u64>,
+    pub(super) video_onsets: Vec<u64>,
+    pub(super) audio_onsets: Vec<u64>,
+}
+
+impl AvSyncDetector {
+    pub(super) fn new(interval: Duration) -> Self {
+        Self {
+            max_offset: interval.as_nanos() as u64 / 2,
+            darkest: None,
+            brightest: None,
+            flash_on: false,
+            last_loud: None,
+            video_onsets: Vec::new(),
+            audio_onsets: Vec::new(),
+        }
+    }
+
+    fn clear(&mut self) {
+        self.video_onsets.clear();
+        self.audio_onsets.clear();
+    }
+
+    pub(super) fn push_video(&mut self, timestamp: u64, luma: f32) {
+        let darkest = self.darkest.map_or(luma, |darkest| darkest.min(luma));
+        let brightest = self.brightest.map_or(luma, |brightest| brightest.max(luma));
+        self.darkest = Some(darkest);
+        self.brightest = Some(brightest);
+
+        let contrast = brightest - darkest;
+        let flash_on = contrast >= MIN_FLASH_CONTRAST && luma > darkest + contrast / 2.0;
+        if flash_on && !self.flash_on {
+            push_onset(&mut self.video_onsets, timestamp);
+        }
+
+        self.flash_on = flash_on;
+    }
+
+    pub(super) fn push_audio(&mut self, timestamp: u64, samples_per_sec: u32, samples: &[f32]) {
+        if samples_per_sec == 0 {
+            return;
+        }
+
+        // A beep has to be quieter for a quarter of the pairing window before the next one starts
+        let rearm = self.max_offset / 2;
+        for (index, sample) in samples.iter().enumerate() {
+            if sample.abs() < BEEP_THRESHOLD {
+                continue;
+            }
+
+            let at = timestamp + index as u64 * 1_000_000_000 / samples_per_sec as u64;
+            if self
+                .last_loud
+                .is_none_or(|last| at.saturating_sub(last) > rearm)
+            {
+                push_onset(&mut self.audio_onsets, at);
+            }
+
+            self.last_loud = Some(at);
+        }
+    }
+
+    fn report(&self) -> ObsAvSyncReport {
+        ObsAvSyncReport {
+            offsets: pair_onsets(&self.video_onsets, &self.audio_onsets, self.max_offset),
+        }
+    }
+}
+
+fn push_onset(onsets: &mut Vec<u64>, timestamp: u64) {
+    if onsets.len() >= MAX_ONSETS {
+        onsets.remove(0);
+    }
+
+    onsets.push(timestamp);
+}
+
+/// Pairs every audio onset with the closest video onset and returns `audio - video` for every
+/// pair that is at most `max_offset` apart. Both lists have to be sorted.
+pub(super) fn pair_onsets(video: &[u64], audio: &[u64], max_offset: u64) -> Vec<i64> {
+    audio
+        .iter()
+        .filter_map(|&audio| {
+            let index = video.partition_point(|&video| video < audio);
+            let before = index.checked_sub(1).map(|i| video[i]);
+            let after = video.get(index).copied();
+
+            [before, after]
+                .into_iter()
+                .flatten()
+                .map(|video| audio as i64 - video as i64)
+                .filter(|offset| offset.unsigned_abs() <= max_offset)
+                .min_by_key(|offset| offset.unsigned_abs())
+        })
+        .collect()
+}
@@ -0,0 +1,88 @@
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::super::{
+        av_sync::{pair_onsets, AvSyncDetector},
+        ObsAvSyncReport,
+    };
+
+    const MS: u64 = 1_000_000;
+
+    #[test]
+    fn test_flashes_are_detected_once() {
+        let mut detector = AvSyncDetector::new(Duration::from_secs(1));
+
+        for frame in 0..180u64 {
+            let timestamp = frame * 1_000_000_000 / 60;
+            // Flashes for 6 frames every second
+            let luma = if frame % 60 < 6 { 235.0 } else { 16.0 };
+            detector.push_video(timestamp, luma);
+        }
+
+        // The first flash is the first frame, before any contrast is known
+        assert_eq!(detector.video_onsets, vec![1000 * MS, 2000 * MS]);
+    }
+
+    #[test]
+    fn test_low_contrast_is_ignored() {
+        let mut detector = AvSyncDetector::new(Duration::from_secs(1));
+        for frame in 0..120u64 {
+            let luma = if frame % 2 == 0 { 100.0 } else { 110.0 };
+            detector.push_video(frame * 16 * MS, luma);
+        }
+
+        assert!(detector.video_onsets.is_empty());
+    }
+
+    #[test]
+    fn test_beeps_are_detected_at_the_first_loud_sample() {
+        let mut detector = AvSyncDetector::new(Duration::from_secs(1));
+        let rate = 48000;
+
+        // 3 seconds in chunks of 10ms, beeping for 100ms every second starting at 40ms
+        let mut sample = 0u64;
+        while sample < 3 * rate {
+            let chunk: Vec<f32> = (sample..sample + 480)
+                .map(|s| {
+                    let ms = s * 1000 / rate % 1000;
+                    if (40..140).contains(&ms) && s % 2 == 0 {
+                        0.5
+                    } else {
+                        0.0
+                    }
+                })
+                .collect();
+
+            detector.push_audio(sample * 1_000_000_000 / rate, rate as u32, &chunk);
+            sample += 480;
+        }
+
+        assert_eq!(detector.audio_onsets, vec![40 * MS, 1040 * MS, 2040 * MS]);
+    }
+
+    #[test]
+    fn test_onsets_are_paired_with_the_closest_flash() {
+        let video = [1000 * MS, 2000 * MS, 3000 * MS];
+        // Late, early, and one without a flash nearby
+        let audio = [1020 * MS, 1990 * MS, 4600 * MS];
+
+        let offsets = pair_onsets(&video, &audio, 500 * MS);
+        assert_eq!(offsets, vec![20 * MS as i64, -10 * MS as i64]);
+    }
+
+    #[test]
+    fn test_report_statistics() {
+        let report = ObsAvSyncReport {
+            offsets: vec![10 * MS as i64, 20 * MS as i64, 30 * MS as i64],
+        };
+
+        assert_eq!(report.mean_offset(), Some(20 * MS as i64));
+        assert_eq!(report.drift(), Some(20 * MS as i64));
+        assert_eq!(report.suggested_sync_offset(), Some(-20 * (MS as i64)));
+
+        let empty = ObsAvSyncReport::default();
+        assert_eq!(empty.mean_offset(), None);
+        assert_eq!(empty.drift(), None);
+    }
+}
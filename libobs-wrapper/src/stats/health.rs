@@ -0,0 +1,270 @@
+use std::{
+    collections::VecDeque,
+    sync::mpsc::{self, RecvTimeoutError},
+    time::{Duration, Instant},
+};
+
+use tokio::sync::broadcast;
+
+use crate::{data::output::ObsOutputTrait, run_with_obs, utils::ObsError};
+
+/// The health of a stream, from the congestion of the connection and the frames the
+/// output had to drop because of it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ObsStreamHealth {
+    Good,
+    /// The connection can barely keep up, e.g. to show "your connection is unstable"
+    Unstable,
+    /// The connection can not keep up and viewers will notice it
+    Bad,
+}
+
+/// Thresholds of the [`ObsStreamHealthMonitor`]. A stream is as unhealthy as the worse of
+/// its congestion and its share of dropped frames over `window`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ObsStreamHealthThresholds {
+    /// Congestion (0.0 - 1.0) from which on the stream is unstable
+    pub unstable_congestion: f32,
+    /// Congestion (0.0 - 1.0) from which on the stream is bad
+    pub bad_congestion: f32,
+    /// Share of dropped frames in percent from which on the stream is unstable
+    pub unstable_dropped_percent: f64,
+    /// Share of dropped frames in percent from which on the stream is bad
+    pub bad_dropped_percent: f64,
+    /// Time window the dropped frames are counted over
+    pub window: Duration,
+}
+
+impl Default for ObsStreamHealthThresholds {
+    fn default() -> Self {
+        Self {
+            unstable_congestion: 0.25,
+            bad_congestion: 0.75,
+            unstable_dropped_percent: 1.0,
+            bad_dropped_percent: 5.0,
+            window: Duration::from_secs(10),
+        }
+    }
+}
+
+impl ObsStreamHealthThresholds {
+    /// Returns the health of a stream with the given congestion and share of dropped frames.
+    pub fn evaluate(&self, congestion: f32, dropped_percent: f64) -> ObsStreamHealth {
+        let by_congestion = if congestion >= self.bad_congestion {
+            ObsStreamHealth::Bad
+        } else if congestion >= self.unstable_congestion {
+            ObsStreamHealth::Unstable
+        } else {
+            ObsStreamHealth::Good
+        };
+
+        let by_dropped = if dropped_percent >= self.bad_dropped_percent {
+            ObsStreamHealth::Bad
+        } else if dropped_percent >= self.unstable_dropped_percent {
+            ObsStreamHealth::Unstable
+        } else {
+            ObsStreamHealth::Good
+        };
+
+        by_congestion.max(by_dropped)
+    }
+}
+
+/// Sent by the [`ObsStreamHealthMonitor`] whenever the health of the stream changes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ObsStreamHealthEvent {
+    pub health: ObsStreamHealth,
+    /// Congestion of the output (0.0 - 1.0)
+    pub congestion: f32,
+    /// Frames dropped within the window of the thresholds
+    pub dropped_frames: u32,
+    /// Total frames within the window of the thresholds
+    pub total_frames: u32,
+}
+
+impl ObsStreamHealthEvent {
+    /// Share of dropped frames within the window in percent.
+    pub fn dropped_percent(&self) -> f64 {
+        if self.total_frames == 0 {
+            return 0.0;
+        }
+
+        self.dropped_frames as f64 / self.total_frames as f64 * 100.0
+    }
+}
+
+/// Polls the congestion and the dropped frames of a streaming output in the background.
+///
+/// Events are only sent when the health changes, so a consistently congested connection
+/// results in a single event until it recovers. Once the output stops, the health is reset to
+/// [`ObsStreamHealth::Good`]. The monitor keeps the output alive and stops once it is dropped.
+#[derive(Debug)]
+pub struct ObsStreamHealthMonitor {
+    events: broadcast::Sender<ObsStreamHealthEvent>,
+    _stop: mpsc::Sender<()>,
+}
+
+impl ObsStreamHealthMonitor {
+    /// Starts monitoring the output every `poll_interval`.
+    pub fn new<T: ObsOutputTrait + ?Sized>(
+        output: &T,
+        thresholds: ObsStreamHealthThresholds,
+        poll_interval: Duration,
+    ) -> Result<Self, ObsError> {
+        let output_ptr = output.as_ptr();
+        let runtime = output.runtime().clone();
+
+        let (events, _) = broadcast::channel(16);
+        let (stop_tx, stop_rx) = mpsc::channel::<()>();
+
+        let sender = events.clone();
+        std::thread::Builder::new()
+            .name("libobs-wrapper-stream-health".to_string())
+            .spawn(move || {
+                let mut tracker = HealthTracker::new(thresholds);
+
+                // The loop ends as soon as the monitor is dropped, as the stop sender disconnects.
+                while let Err(RecvTimeoutError::Timeout) = stop_rx.recv_timeout(poll_interval) {
+                    let sample = run_with_obs!(runtime, (output_ptr), move |token| unsafe {
+                        // Safety: output_ptr is valid because of SmartPointer
+                        let output = output_ptr.get_ptr(token);
+                        if !libobs::obs_output_active(output) {
+                            return None;
+                        }
+
+                        Some(HealthSample {
+                            congestion: libobs::obs_output_get_congestion(output),
+                            total_frames: libobs::obs_output_get_total_frames(output).max(0) as u32,
+                            dropped_frames: libobs::obs_output_get_frames_dropped(output).max(0)
+                                as u32,
+                        })
+                    });
+
+                    let event = match sample {
+                        Ok(Some(sample)) => tracker.push(Instant::now(), sample),
+                        Ok(None) => tracker.reset(),
+                        Err(e) => {
+                            log::debug!("Stopping stream health monitor: {:?}", e);
+                            break;
+                        }
+                    };
+
+                    if let Some(event) = event {
+                        // Sending only fails if there are no receivers, which is fine
+                        let _ = sender.send(event);
+                    }
+                }
+            })
+            .map_err(|e| {
+                ObsError::InvalidOperation(format!(
+                    "Failed to spawn stream health monitor thread: {}",
+                    e
+                ))
+            })?;
+
+        Ok(Self {
+            events,
+            _stop: stop_tx,
+        })
+    }
+
+    /// Subscribes to the health events of this monitor.
+    pub fn subscribe(&self) -> broadcast::Receiver<ObsStreamHealthEvent> {
+        self.events.subscribe()
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub(super) struct HealthSample {
+    pub(super) congestion: f32,
+    pub(super) total_frames: u32,
+    pub(super) dropped_frames: u32,
+}
+
+#[derive(Debug)]
+pub(super) struct HealthTracker {
+    thresholds: ObsStreamHealthThresholds,
+    samples: VecDeque<(Instant, HealthSample)>,
+    health: ObsStreamHealth,
+}
+
+impl HealthTracker {
+    pub(super) fn new(thresholds: ObsStreamHealthThresholds) -> Self {
+        Self {
+            thresholds,
+            samples: VecDeque::new(),
+            health: ObsStreamHealth::Good,
+        }
+    }
+
+    /// Forgets the samples of the last run of the output.
+    pub(super) fn reset(&mut self) -> Option<ObsStreamHealthEvent> {
+        self.samples.clear();
+        self.transition(ObsStreamHealth::Good, HealthSample::default(), (0, 0))
+    }
+
+    pub(super) fn push(
+        &mut self,
+        at: Instant,
+        sample: HealthSample,
+    ) -> Option<ObsStreamHealthEvent> {
+        // The counters start at zero again when the output is restarted
+        let was_reset = self
+            .samples
+            .back()
+            .is_some_and(|(_, last)| sample.total_frames < last.total_frames);
+        if was_reset {
+            self.samples.clear();
+        }
+
+        self.samples.push_back((at, sample));
+
+        // Keep a single sample that is older than the window as baseline
+        while self
+            .samples
+            .get(1)
+            .is_some_and(|(t, _)| at.duration_since(*t) >= self.thresholds.window)
+        {
+            self.samples.pop_front();
+        }
+
+        // Dropped frames are only counted once the history spans the whole window
+        let (dropped, total) = match self.samples.front() {
+            Some((t, baseline)) if at.duration_since(*t) >= self.thresholds.window => (
+                sample
+                    .dropped_frames
+                    .saturating_sub(baseline.dropped_frames),
+                sample.total_frames.saturating_sub(baseline.total_frames),
+            ),
+            _ => (0, 0),
+        };
+
+        let dropped_percent = if total == 0 {
+            0.0
+        } else {
+            dropped as f64 / total as f64 * 100.0
+        };
+
+        let health = self.thresholds.evaluate(sample.congestion, dropped_percent);
+        self.transition(health, sample, (dropped, total))
+    }
+
+    fn transition(
+        &mut self,
+        health: ObsStreamHealth,
+        sample: HealthSample,
+        (dropped_frames, total_frames): (u32, u32),
+    ) -> Option<ObsStreamHealthEvent> {
+        if health == self.health {
+            return None;
+        }
+
+        self.health = health;
+        Some(ObsStreamHealthEvent {
+            health,
+            congestion: sample.congestion,
+            dropped_frames,
+            total_frames,
+        })
+    }
+}
@@ -0,0 +1,91 @@
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::super::{
+        health::{HealthSample, HealthTracker},
+        ObsStreamHealth, ObsStreamHealthThresholds,
+    };
+
+    fn sample(congestion: f32, total_frames: u32, dropped_frames: u32) -> HealthSample {
+        HealthSample {
+            congestion,
+            total_frames,
+            dropped_frames,
+        }
+    }
+
+    #[test]
+    fn congestion_changes_health_right_away() {
+        let mut tracker = HealthTracker::new(ObsStreamHealthThresholds::default());
+        let start = Instant::now();
+
+        assert_eq!(tracker.push(start, sample(0.1, 60, 0)), None);
+
+        let event = tracker
+            .push(start + Duration::from_secs(1), sample(0.5, 120, 0))
+            .expect("unstable event");
+        assert_eq!(event.health, ObsStreamHealth::Unstable);
+
+        // No event while the health stays the same
+        assert_eq!(
+            tracker.push(start + Duration::from_secs(2), sample(0.6, 180, 0)),
+            None
+        );
+
+        let event = tracker
+            .push(start + Duration::from_secs(3), sample(0.9, 240, 0))
+            .expect("bad event");
+        assert_eq!(event.health, ObsStreamHealth::Bad);
+
+        let event = tracker
+            .push(start + Duration::from_secs(4), sample(0.0, 300, 0))
+            .expect("recovered event");
+        assert_eq!(event.health, ObsStreamHealth::Good);
+    }
+
+    #[test]
+    fn dropped_frames_are_counted_over_window() {
+        let mut tracker = HealthTracker::new(ObsStreamHealthThresholds::default());
+        let start = Instant::now();
+
+        // 60 fps with 3% dropped, but the window is not full yet
+        for second in 0..10u32 {
+            let at = start + Duration::from_secs(second as u64);
+            assert_eq!(
+                tracker.push(at, sample(0.0, second * 60, second * 60 * 3 / 100)),
+                None
+            );
+        }
+
+        let event = tracker
+            .push(start + Duration::from_secs(10), sample(0.0, 600, 18))
+            .expect("unstable event");
+        assert_eq!(event.health, ObsStreamHealth::Unstable);
+        assert_eq!(event.total_frames, 600);
+        assert_eq!(event.dropped_frames, 18);
+
+        // No more drops, the window slides past them
+        let mut last = None;
+        for second in 11..=20u32 {
+            let at = start + Duration::from_secs(second as u64);
+            if let Some(event) = tracker.push(at, sample(0.0, second * 60, 18)) {
+                last = Some(event);
+            }
+        }
+        assert_eq!(last.map(|e| e.health), Some(ObsStreamHealth::Good));
+    }
+
+    #[test]
+    fn reset_reports_recovery_once() {
+        let mut tracker = HealthTracker::new(ObsStreamHealthThresholds::default());
+        let start = Instant::now();
+
+        assert!(tracker.push(start, sample(0.8, 60, 0)).is_some());
+        assert_eq!(
+            tracker.reset().map(|e| e.health),
+            Some(ObsStreamHealth::Good)
+        );
+        assert_eq!(tracker.reset(), None);
+    }
+}
@@ -0,0 +1,87 @@
+//! Frame statistics of the OBS pipeline.
+//!
+//! [`ObsFrameStats`] is a snapshot of the global frame counters, which can be read with
+//! `ObsContext::frame_stats`. Instead of polling these counters yourself, you can create an
+//! [`ObsStatsMonitor`] with a set of [`ObsFrameDropAlarm`]s, which notifies you whenever
+//! too many frames are dropped over a given time window. An [`ObsEncoderWatchdog`] goes one
+//! step further and lowers the quality of an overloaded encoder automatically.
+//! For streams, an [`ObsStreamHealthMonitor`] reports when the connection becomes unstable.
+//! An [`ObsAvSyncProbe`] measures the offset between audio and video of the encoded output.
+
+mod alarm;
+#[cfg(test)]
+mod alarm_tests;
+pub use alarm::*;
+
+mod watchdog;
+#[cfg(test)]
+mod watchdog_tests;
+pub use watchdog::*;
+
+mod health;
+#[cfg(test)]
+mod health_tests;
+pub use health::*;
+
+mod av_sync;
+#[cfg(test)]
+mod av_sync_tests;
+pub use av_sync::*;
+
+#[cfg(feature = "profiler")]
+mod profiler;
+#[cfg(all(test, feature = "profiler"))]
+mod profiler_tests;
+#[cfg(feature = "profiler")]
+pub(crate) use profiler::{start_profiler, stop_profiler};
+#[cfg(feature = "profiler")]
+pub use profiler::{ObsProfilerEntry, ObsProfilerSnapshot};
+
+use crate::{run_with_obs, runtime::ObsRuntime, utils::ObsError};
+
+/// Snapshot of the global frame counters of libobs.
+///
+/// The counters are cumulative since the video pipeline was last reset.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ObsFrameStats {
+    /// Total frames rendered by the graphics thread
+    pub rendered_frames: u32,
+    /// Frames that were not rendered in time, because the GPU or the graphics thread lagged behind
+    pub lagged_frames: u32,
+    /// Total frames passed to the video output (the encoders)
+    pub output_frames: u32,
+    /// Frames skipped by the video output, because encoding took too long
+    pub skipped_frames: u32,
+}
+
+impl ObsFrameStats {
+    pub(crate) fn read(runtime: &ObsRuntime) -> Result<Self, ObsError> {
+        run_with_obs!(runtime, move || unsafe {
+            // Safety: We are in the runtime, the video output is checked for null
+            let video = libobs::obs_get_video();
+            let (output_frames, skipped_frames) = if video.is_null() {
+                (0, 0)
+            } else {
+                (
+                    libobs::video_output_get_total_frames(video),
+                    libobs::video_output_get_skipped_frames(video),
+                )
+            };
+
+            ObsFrameStats {
+                rendered_frames: libobs::obs_get_total_frames(),
+                lagged_frames: libobs::obs_get_lagged_frames(),
+                output_frames,
+                skipped_frames,
+            }
+        })
+    }
+
+    /// Returns the (dropped, total) counters of the given metric.
+    pub fn counters(&self, metric: ObsFrameMetric) -> (u32, u32) {
+        match metric {
+            ObsFrameMetric::RenderLagged => (self.lagged_frames, self.rendered_frames),
+            ObsFrameMetric::EncoderSkipped => (self.skipped_frames, self.output_frames),
+        }
+    }
+}
@@ -0,0 +1,187 @@
+//! Snapshots of the internal profiler of libobs, which is started with the context if the
+//! `profiler` feature is enabled.
+//!
+//! libobs measures its render loop, the encoders and the tick and render calls of every source.
+//! [`ObsProfilerSnapshot`] captures the tree of these measurements, see `ObsContext::profiler_snapshot`.
+use std::{
+    ffi::{c_void, CStr},
+    sync::Mutex,
+    time::Duration,
+};
+
+use lazy_static::lazy_static;
+use libobs::{profiler_name_store_t, profiler_snapshot_entry_t, profiler_time_entries_t};
+
+use crate::{run_with_obs, runtime::ObsRuntime, unsafe_send::Sendable, utils::ObsError};
+
+lazy_static! {
+    static ref NAME_STORE: Mutex<Option<Sendable<*mut profiler_name_store_t>>> = Mutex::new(None);
+}
+
+/// A measured section of the profiler (e.g. `obs_video_thread` or the tick of a source) and the sections called within.
+///
+/// All times are the wall clock time a single call took, with a resolution of microseconds.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ObsProfilerEntry {
+    pub name: String,
+    /// How often the section has been called
+    pub calls: u64,
+    pub min_time: Duration,
+    pub max_time: Duration,
+    pub average_time: Duration,
+    /// The interval this section is expected to be called in, e.g. the frame time for the render loop
+    pub expected_time_between_calls: Option<Duration>,
+    pub min_time_between_calls: Duration,
+    pub max_time_between_calls: Duration,
+    pub children: Vec<ObsProfilerEntry>,
+}
+
+impl ObsProfilerEntry {
+    /// Returns this entry or the first entry below it with the given name.
+    pub fn find(&self, name: &str) -> Option<&ObsProfilerEntry> {
+        if self.name == name {
+            return Some(self);
+        }
+
+        self.children.iter().find_map(|child| child.find(name))
+    }
+}
+
+/// The tree of the measurements of the profiler at the time the snapshot was taken.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ObsProfilerSnapshot {
+    pub roots: Vec<ObsProfilerEntry>,
+}
+
+impl ObsProfilerSnapshot {
+    /// Returns the first entry with the given name, searching depth-first.
+    pub fn find(&self, name: &str) -> Option<&ObsProfilerEntry> {
+        self.roots.iter().find_map(|root| root.find(name))
+    }
+
+    pub(crate) fn take(runtime: &ObsRuntime) -> Result<Self, ObsError> {
+        run_with_obs!(runtime, move || unsafe {
+            // Safety: The snapshot is only used within this call and freed afterwards
+            let snapshot = libobs::profile_snapshot_create();
+            let mut roots = Vec::new();
+            libobs::profiler_snapshot_enumerate_roots(
+                snapshot,
+                Some(collect_entry),
+                &mut roots as *mut _ as *mut c_void,
+            );
+            libobs::profile_snapshot_free(snapshot);
+
+            ObsProfilerSnapshot { roots }
+        })
+    }
+}
+
+fn micros(time: u64) -> Duration {
+    Duration::from_micros(time)
+}
+
+/// # Safety
+/// The times must be valid for the duration of this call.
+unsafe fn average_time(times: *mut profiler_time_entries_t) -> Duration {
+    if times.is_null() {
+        return Duration::ZERO;
+    }
+
+    let times = (*times).__bindgen_anon_1;
+    if times.array.is_null() || times.num == 0 {
+        return Duration::ZERO;
+    }
+
+    let (total, count) = std::slice::from_raw_parts(times.array, times.num)
+        .iter()
+        .fold((0u128, 0u128), |(total, count), entry| {
+            (
+                total + entry.time_delta as u128 * entry.count as u128,
+                count + entry.count as u128,
+            )
+        });
+
+    if count == 0 {
+        return Duration::ZERO;
+    }
+
+    micros((total / count) as u64)
+}
+
+unsafe extern "C" fn collect_entry(
+    param: *mut c_void,
+    entry: *mut profiler_snapshot_entry_t,
+) -> bool {
+    let entries = &mut *(param as *mut Vec<ObsProfilerEntry>);
+
+    let name = libobs::profiler_snapshot_entry_name(entry);
+    let name = if name.is_null() {
+        String::new()
+    } else {
+        CStr::from_ptr(name).to_string_lossy().into_owned()
+    };
+
+    let mut children = Vec::new();
+    libobs::profiler_snapshot_enumerate_children(
+        entry,
+        Some(collect_entry),
+        &mut children as *mut _ as *mut c_void,
+    );
+
+    let expected = libobs::profiler_snapshot_entry_expected_time_between_calls(entry);
+    entries.push(ObsProfilerEntry {
+        name,
+        calls: libobs::profiler_snapshot_entry_overall_count(entry),
+        min_time: micros(libobs::profiler_snapshot_entry_min_time(entry)),
+        max_time: micros(libobs::profiler_snapshot_entry_max_time(entry)),
+        average_time: average_time(libobs::profiler_snapshot_entry_times(entry)),
+        expected_time_between_calls: (expected != 0).then(|| micros(expected)),
+        min_time_between_calls: micros(libobs::profiler_snapshot_entry_min_time_between_calls(
+            entry,
+        )),
+        max_time_between_calls: micros(libobs::profiler_snapshot_entry_max_time_between_calls(
+            entry,
+        )),
+        children,
+    });
+
+    true
+}
+
+/// Starts the profiler and returns the name store that has to be passed to `obs_startup`.
+///
+/// # Safety
+/// Must be called on the OBS thread before `obs_startup`.
+#[allow(unknown_lints)]
+#[allow(ensure_obs_call_in_runtime)]
+pub(crate) unsafe fn start_profiler() -> *mut profiler_name_store_t {
+    libobs::profiler_start();
+    let store = libobs::profiler_name_store_create();
+
+    *NAME_STORE
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(Sendable(store));
+
+    store
+}
+
+/// Stops the profiler and frees its measurements, in the same order OBS Studio does.
+///
+/// # Safety
+/// Must be called on the OBS thread after `obs_shutdown`.
+#[allow(unknown_lints)]
+#[allow(ensure_obs_call_in_runtime)]
+pub(crate) unsafe fn stop_profiler() {
+    let store = NAME_STORE
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .take();
+
+    if let Some(store) = store {
+        libobs::profiler_stop();
+        libobs::profiler_free();
+        libobs::profiler_name_store_free(store.0);
+    }
+}
@@ -0,0 +1,22 @@
+#[cfg(test)]
+mod tests {
+    use super::super::{ObsProfilerEntry, ObsProfilerSnapshot};
+
+    #[test]
+    fn test_find_entry() {
+        let snapshot = ObsProfilerSnapshot {
+            roots: vec![ObsProfilerEntry {
+                name: "obs_video_thread(16.67 ms)".into(),
+                children: vec![ObsProfilerEntry {
+                    name: "tick_sources".into(),
+                    calls: 3,
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }],
+        };
+
+        assert_eq!(snapshot.find("tick_sources").map(|e| e.calls), Some(3));
+        assert!(snapshot.find("output_frame").is_none());
+    }
+}
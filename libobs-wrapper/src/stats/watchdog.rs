@@ -0,0 +1,267 @@
+use std::{
+    sync::{
+        mpsc::{self, RecvTimeoutError},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use tokio::sync::broadcast;
+
+use crate::{
+    data::{object::ObsObjectTrait, ObsData, ObsDataGetters, ObsDataSetters},
+    encoders::{video::ObsVideoEncoder, ObsEncoderTrait},
+    runtime::ObsRuntime,
+    stats::{alarm::StatsHistory, ObsFrameDropAlarm, ObsFrameStats},
+    utils::ObsError,
+};
+
+/// A way to reduce the load of an overloaded encoder, applied by the [`ObsEncoderWatchdog`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum ObsEncoderMitigation {
+    /// Multiplies the `bitrate` setting (in kbps) with `factor`, but never goes below `min_bitrate`.
+    /// Applied while the encoder is running if it supports
+    /// [`ObsEncoderCaps::DYN_BITRATE`](crate::encoders::ObsEncoderCaps::DYN_BITRATE).
+    LowerBitrate { factor: f64, min_bitrate: i64 },
+    /// Steps through `presets`, which are ordered from slowest to fastest, e.g.
+    /// `key: "preset"` with `["medium", "fast", "veryfast"]` for x264.
+    FasterPreset { key: String, presets: Vec<String> },
+    /// Multiplies the encoded size with `factor`, but never goes below `min_height`.
+    /// libobs can only rescale inactive encoders, so this is skipped while the encoder
+    /// is running and takes effect when the output is started the next time.
+    Downscale { factor: f64, min_height: u32 },
+}
+
+#[derive(Debug, Clone)]
+pub enum ObsEncoderWatchdogAction {
+    BitrateLowered {
+        from: i64,
+        to: i64,
+    },
+    PresetChanged {
+        from: String,
+        to: String,
+    },
+    Downscaled {
+        width: u32,
+        height: u32,
+    },
+    /// The encoder is still overloaded, but every mitigation has reached its limit.
+    /// Sent once until the encoder recovers.
+    Exhausted,
+    /// Applying a mitigation failed, the watchdog tries again on the next overload.
+    Failed(ObsError),
+}
+
+/// Sent by the [`ObsEncoderWatchdog`] whenever it reacted to an overload.
+#[derive(Debug, Clone)]
+pub struct ObsEncoderWatchdogEvent {
+    pub action: ObsEncoderWatchdogAction,
+    /// Frames dropped within the alarm window that caused the action
+    pub dropped_frames: u32,
+    /// Total frames within the alarm window that caused the action
+    pub total_frames: u32,
+}
+
+/// Watches the frame counters like an [`ObsStatsMonitor`](crate::stats::ObsStatsMonitor) and
+/// degrades the quality of a video encoder whenever the alarm is exceeded, similar to the
+/// dynamic bitrate of OBS.
+///
+/// Mitigations are tried in order, the first one that can still lower the load is applied.
+/// After each action the watchdog waits for a whole alarm window before judging its effect.
+/// The watchdog keeps the encoder alive and stops once it is dropped.
+#[derive(Debug)]
+pub struct ObsEncoderWatchdog {
+    events: broadcast::Sender<ObsEncoderWatchdogEvent>,
+    _stop: mpsc::Sender<()>,
+}
+
+impl ObsEncoderWatchdog {
+    /// Starts watching the frame counters every `poll_interval`.
+    pub fn new(
+        runtime: ObsRuntime,
+        encoder: Arc<ObsVideoEncoder>,
+        alarm: ObsFrameDropAlarm,
+        mitigations: Vec<ObsEncoderMitigation>,
+        poll_interval: Duration,
+    ) -> Result<Self, ObsError> {
+        let (events, _) = broadcast::channel(16);
+        let (stop_tx, stop_rx) = mpsc::channel::<()>();
+
+        let sender = events.clone();
+        std::thread::Builder::new()
+            .name("libobs-wrapper-encoder-watchdog".to_string())
+            .spawn(move || {
+                let mut history = StatsHistory::default();
+                let mut exhausted = false;
+
+                // The loop ends as soon as the watchdog is dropped, as the stop sender disconnects.
+                while let Err(RecvTimeoutError::Timeout) = stop_rx.recv_timeout(poll_interval) {
+                    let stats = match ObsFrameStats::read(&runtime) {
+                        Ok(stats) => stats,
+                        Err(e) => {
+                            log::debug!("Stopping encoder watchdog, could not read stats: {:?}", e);
+                            break;
+                        }
+                    };
+
+                    history.push(Instant::now(), stats, alarm.window());
+                    let Some((dropped, total)) =
+                        history.window_counters(alarm.metric(), alarm.window())
+                    else {
+                        continue;
+                    };
+
+                    let overloaded = total > 0
+                        && dropped as f64 / total as f64 * 100.0 > alarm.threshold_percent();
+                    if !overloaded {
+                        exhausted = false;
+                        continue;
+                    }
+
+                    let action = match apply_next(&encoder, &mitigations) {
+                        Some(action) => action,
+                        None if exhausted => continue,
+                        None => {
+                            exhausted = true;
+                            ObsEncoderWatchdogAction::Exhausted
+                        }
+                    };
+
+                    log::info!(
+                        "Encoder overloaded ({} of {} frames dropped): {:?}",
+                        dropped,
+                        total,
+                        action
+                    );
+
+                    // Sending only fails if there are no receivers, which is fine
+                    let _ = sender.send(ObsEncoderWatchdogEvent {
+                        action,
+                        dropped_frames: dropped,
+                        total_frames: total,
+                    });
+
+                    // Judge the effect of the action over a whole window of new frames
+                    history = StatsHistory::default();
+                }
+            })
+            .map_err(|e| {
+                ObsError::InvalidOperation(format!(
+                    "Failed to spawn encoder watchdog thread: {}",
+                    e
+                ))
+            })?;
+
+        Ok(Self {
+            events,
+            _stop: stop_tx,
+        })
+    }
+
+    /// Subscribes to the actions of this watchdog.
+    pub fn subscribe(&self) -> broadcast::Receiver<ObsEncoderWatchdogEvent> {
+        self.events.subscribe()
+    }
+}
+
+/// Applies the first mitigation that can still lower the load, `None` if all are exhausted.
+fn apply_next(
+    encoder: &ObsVideoEncoder,
+    mitigations: &[ObsEncoderMitigation],
+) -> Option<ObsEncoderWatchdogAction> {
+    for mitigation in mitigations {
+        match apply(encoder, mitigation) {
+            Ok(Some(action)) => return Some(action),
+            Ok(None) => continue,
+            Err(e) => return Some(ObsEncoderWatchdogAction::Failed(e)),
+        }
+    }
+
+    None
+}
+
+fn apply(
+    encoder: &ObsVideoEncoder,
+    mitigation: &ObsEncoderMitigation,
+) -> Result<Option<ObsEncoderWatchdogAction>, ObsError> {
+    match mitigation {
+        ObsEncoderMitigation::LowerBitrate {
+            factor,
+            min_bitrate,
+        } => {
+            let Some(from) = encoder.settings()?.get_int("bitrate")? else {
+                return Ok(None);
+            };
+            let Some(to) = next_bitrate(from, *factor, *min_bitrate) else {
+                return Ok(None);
+            };
+
+            let mut settings = ObsData::new(encoder.runtime.clone())?;
+            settings.set_int("bitrate", to)?;
+            encoder.update_active_settings(settings)?;
+
+            Ok(Some(ObsEncoderWatchdogAction::BitrateLowered { from, to }))
+        }
+        ObsEncoderMitigation::FasterPreset { key, presets } => {
+            let Some(from) = encoder.settings()?.get_string(key.as_str())? else {
+                return Ok(None);
+            };
+            let Some(to) = next_preset(&from, presets) else {
+                return Ok(None);
+            };
+
+            let mut settings = ObsData::new(encoder.runtime.clone())?;
+            settings.set_string(key.as_str(), to)?;
+            encoder.update_active_settings(settings)?;
+
+            Ok(Some(ObsEncoderWatchdogAction::PresetChanged {
+                from,
+                to: to.to_string(),
+            }))
+        }
+        ObsEncoderMitigation::Downscale { factor, min_height } => {
+            if encoder.is_active()? {
+                return Ok(None);
+            }
+
+            let size = (encoder.width()?, encoder.height()?);
+            let Some((width, height)) = scaled_size(size, *factor, *min_height) else {
+                return Ok(None);
+            };
+
+            encoder.set_scaled_size(width, height)?;
+            Ok(Some(ObsEncoderWatchdogAction::Downscaled { width, height }))
+        }
+    }
+}
+
+pub(super) fn next_bitrate(current: i64, factor: f64, min_bitrate: i64) -> Option<i64> {
+    let next = ((current as f64 * factor) as i64).max(min_bitrate);
+    (next < current).then_some(next)
+}
+
+/// The preset after the current one, `None` if it is the fastest or unknown.
+pub(super) fn next_preset<'a>(current: &str, presets: &'a [String]) -> Option<&'a str> {
+    let index = presets.iter().position(|p| p == current)?;
+    presets.get(index + 1).map(|p| p.as_str())
+}
+
+/// Scales the size with the factor, keeping the aspect ratio and even dimensions.
+pub(super) fn scaled_size(
+    (width, height): (u32, u32),
+    factor: f64,
+    min_height: u32,
+) -> Option<(u32, u32)> {
+    if width == 0 || height == 0 {
+        return None;
+    }
+
+    let next_height = ((height as f64 * factor) as u32).max(min_height) & !1;
+    if next_height == 0 || next_height >= height {
+        return None;
+    }
+
+    let next_width = ((width as f64 * next_height as f64 / height as f64) as u32) & !1;
+    Some((next_width, next_height))
+}
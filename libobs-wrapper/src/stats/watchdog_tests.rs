@@ -0,0 +1,27 @@
+#[cfg(test)]
+mod tests {
+    use super::super::watchdog::{next_bitrate, next_preset, scaled_size};
+
+    #[test]
+    fn bitrate_stops_at_minimum() {
+        assert_eq!(next_bitrate(6000, 0.8, 2500), Some(4800));
+        assert_eq!(next_bitrate(3000, 0.8, 2500), Some(2500));
+        assert_eq!(next_bitrate(2500, 0.8, 2500), None);
+    }
+
+    #[test]
+    fn preset_steps_to_faster() {
+        let presets = ["medium", "fast", "veryfast"].map(String::from);
+        assert_eq!(next_preset("medium", &presets), Some("fast"));
+        assert_eq!(next_preset("veryfast", &presets), None);
+        assert_eq!(next_preset("slow", &presets), None);
+    }
+
+    #[test]
+    fn downscale_keeps_aspect_ratio() {
+        assert_eq!(scaled_size((1920, 1080), 0.75, 480), Some((1440, 810)));
+        assert_eq!(scaled_size((1280, 720), 0.5, 480), Some((852, 480)));
+        assert_eq!(scaled_size((854, 480), 0.5, 480), None);
+        assert_eq!(scaled_size((0, 0), 0.5, 480), None);
+    }
+}
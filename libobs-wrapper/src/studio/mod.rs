@@ -0,0 +1,278 @@
+//! Studio mode, the preview / program workflow of OBS Studio.
+//!
+//! The program scene is rendered on an output channel through a transition source, so every
+//! output sees the transition between scenes. The preview scene is rendered on a separate
+//! [`ObsViewRef`] that can be shown to the operator before it goes live using
+//! [`StudioModeController::transition_to_program`].
+
+use std::{
+    sync::{Arc, RwLock},
+    time::Duration,
+};
+
+use libobs::obs_source_t;
+use tokio::sync::broadcast;
+
+use crate::{
+    data::object::ObsObjectTrait,
+    impl_obs_drop, run_with_obs,
+    runtime::ObsRuntime,
+    scenes::ObsSceneRef,
+    sources::ObsSourceRef,
+    unsafe_send::SmartPointerSendable,
+    utils::{ObsDropGuard, ObsError, ObsString},
+    view::ObsViewRef,
+};
+
+/// Sent by the [`StudioModeController`] whenever the preview or program scene changes.
+///
+/// The start and end of transitions are emitted by the transition source itself, see
+/// `StudioModeController::transition` and its `on_transition_start` / `on_transition_stop` signals.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StudioModeEvent {
+    /// A new scene is shown in the preview
+    PreviewSceneChanged(ObsString),
+    /// A new scene is live. If it was started with a transition, the transition may still be running.
+    ProgramSceneChanged(ObsString),
+}
+
+#[derive(Debug, Default)]
+struct StudioModeState {
+    preview: Option<ObsSceneRef>,
+    program: Option<ObsSceneRef>,
+}
+
+#[derive(Debug, Clone)]
+pub struct StudioModeController {
+    /// Removes the transition from the output channel first
+    _channel_guard: Arc<_StudioChannelDropGuard>,
+    transition: ObsSourceRef,
+    preview_view: ObsViewRef,
+    state: Arc<RwLock<StudioModeState>>,
+    events: broadcast::Sender<StudioModeEvent>,
+    channel: u32,
+    runtime: ObsRuntime,
+}
+
+impl StudioModeController {
+    /// Creates a new transition of the given type (e.g. `fade_transition` or `cut_transition`)
+    /// and renders it on the given output channel, replacing whatever was set on that channel.
+    pub fn new<T: Into<ObsString> + Sync + Send>(
+        transition_id: T,
+        channel: u32,
+        runtime: ObsRuntime,
+    ) -> Result<Self, ObsError> {
+        if channel >= libobs::MAX_CHANNELS {
+            return Err(ObsError::InvalidOperation(format!(
+                "Channel {} is out of bounds (max {})",
+                channel,
+                libobs::MAX_CHANNELS - 1
+            )));
+        }
+
+        let transition = ObsSourceRef::new(
+            transition_id,
+            format!("Studio Mode Transition (channel {})", channel),
+            None,
+            None,
+            runtime.clone(),
+        )?;
+
+        let transition_ptr = transition.as_ptr();
+        run_with_obs!(runtime, (transition_ptr), move |token| {
+            unsafe {
+                // Safety: The transition is valid because of the smart pointer and we are in the runtime
+                let source_type = libobs::obs_source_get_type(transition_ptr.get_ptr(token));
+                if source_type != libobs::obs_source_type_OBS_SOURCE_TYPE_TRANSITION {
+                    return Err(ObsError::InvalidOperation(
+                        "The given source id is not a transition".into(),
+                    ));
+                }
+
+                let mut ovi: libobs::obs_video_info = std::mem::zeroed();
+                if libobs::obs_get_video_info(&mut ovi) {
+                    libobs::obs_transition_set_size(
+                        transition_ptr.get_ptr(token),
+                        ovi.base_width,
+                        ovi.base_height,
+                    );
+                }
+
+                libobs::obs_set_output_source(channel, transition_ptr.get_ptr(token));
+            }
+
+            Ok(())
+        })??;
+
+        let channel_guard = _StudioChannelDropGuard {
+            transition: transition.as_ptr(),
+            channel,
+            runtime: runtime.clone(),
+        };
+
+        let (events, _) = broadcast::channel(16);
+        Ok(Self {
+            _channel_guard: Arc::new(channel_guard),
+            transition,
+            preview_view: ObsViewRef::new(runtime.clone())?,
+            state: Arc::new(RwLock::new(StudioModeState::default())),
+            events,
+            channel,
+            runtime,
+        })
+    }
+
+    /// Shows the given scene in the preview, without changing what is live.
+    pub fn set_preview_scene(&self, scene: &ObsSceneRef) -> Result<(), ObsError> {
+        self.preview_view.set_scene(0, scene)?;
+
+        self.state
+            .write()
+            .map_err(|_| {
+                ObsError::LockError("Failed to acquire write lock on studio mode state".into())
+            })?
+            .preview = Some(scene.clone());
+
+        // Sending only fails if there are no receivers, which is fine
+        let _ = self
+            .events
+            .send(StudioModeEvent::PreviewSceneChanged(scene.name()));
+        Ok(())
+    }
+
+    /// Cuts to the given scene immediately, without a transition.
+    pub fn set_program_scene(&self, scene: &ObsSceneRef) -> Result<(), ObsError> {
+        let transition_ptr = self.transition.as_ptr();
+        let scene_source_ptr = scene.get_scene_source_ptr()?;
+        run_with_obs!(
+            self.runtime,
+            (transition_ptr, scene_source_ptr),
+            move |token| unsafe {
+                // Safety: The transition is valid because of the smart pointer and the scene is
+                // kept alive in the state of this controller.
+                libobs::obs_transition_set(transition_ptr.get_ptr(token), scene_source_ptr.0);
+            }
+        )?;
+
+        self.set_program_state(scene)
+    }
+
+    /// Transitions from the current program scene to the preview scene over the given duration.
+    /// The preview keeps showing the same scene afterwards, just like in OBS Studio.
+    ///
+    /// Fails if there is no preview scene or another transition is still running.
+    pub fn transition_to_program(&self, duration: Duration) -> Result<(), ObsError> {
+        let preview = self
+            .preview_scene()?
+            .ok_or_else(|| ObsError::InvalidOperation("There is no preview scene set".into()))?;
+
+        let transition_ptr = self.transition.as_ptr();
+        let scene_source_ptr = preview.get_scene_source_ptr()?;
+        let duration_ms = duration.as_millis().min(u32::MAX as u128) as u32;
+        let started = run_with_obs!(
+            self.runtime,
+            (transition_ptr, scene_source_ptr),
+            move |token| unsafe {
+                // Safety: The transition is valid because of the smart pointer and the scene is
+                // kept alive in the state of this controller.
+                libobs::obs_transition_start(
+                    transition_ptr.get_ptr(token),
+                    libobs::obs_transition_mode_OBS_TRANSITION_MODE_AUTO,
+                    duration_ms,
+                    scene_source_ptr.0,
+                )
+            }
+        )?;
+
+        if !started {
+            return Err(ObsError::InvalidOperation(
+                "Could not start the transition, another transition might still be running".into(),
+            ));
+        }
+
+        self.set_program_state(&preview)
+    }
+
+    fn set_program_state(&self, scene: &ObsSceneRef) -> Result<(), ObsError> {
+        self.state
+            .write()
+            .map_err(|_| {
+                ObsError::LockError("Failed to acquire write lock on studio mode state".into())
+            })?
+            .program = Some(scene.clone());
+
+        // Sending only fails if there are no receivers, which is fine
+        let _ = self
+            .events
+            .send(StudioModeEvent::ProgramSceneChanged(scene.name()));
+        Ok(())
+    }
+
+    pub fn preview_scene(&self) -> Result<Option<ObsSceneRef>, ObsError> {
+        Ok(self
+            .state
+            .read()
+            .map_err(|_| {
+                ObsError::LockError("Failed to acquire read lock on studio mode state".into())
+            })?
+            .preview
+            .clone())
+    }
+
+    pub fn program_scene(&self) -> Result<Option<ObsSceneRef>, ObsError> {
+        Ok(self
+            .state
+            .read()
+            .map_err(|_| {
+                ObsError::LockError("Failed to acquire read lock on studio mode state".into())
+            })?
+            .program
+            .clone())
+    }
+
+    /// Returns the transition source that is rendered on the program channel.
+    pub fn transition(&self) -> &ObsSourceRef {
+        &self.transition
+    }
+
+    /// Returns the view the preview scene is rendered on. Add it with `ObsViewRef::add`
+    /// to encode or display the preview.
+    pub fn preview_view(&self) -> &ObsViewRef {
+        &self.preview_view
+    }
+
+    /// The output channel the program is rendered on.
+    pub fn channel(&self) -> u32 {
+        self.channel
+    }
+
+    /// Subscribes to preview and program scene changes.
+    pub fn subscribe(&self) -> broadcast::Receiver<StudioModeEvent> {
+        self.events.subscribe()
+    }
+}
+
+#[derive(Debug)]
+struct _StudioChannelDropGuard {
+    transition: SmartPointerSendable<*mut obs_source_t>,
+    channel: u32,
+    runtime: ObsRuntime,
+}
+
+impl ObsDropGuard for _StudioChannelDropGuard {}
+
+impl_obs_drop!(
+    _StudioChannelDropGuard,
+    (transition, channel),
+    move |token| unsafe {
+        // Safety: We are in the runtime and the transition is valid because of the smart pointer.
+        // The channel is only cleared if nobody replaced the transition in the meantime.
+        let current = libobs::obs_get_output_source(channel);
+        if current == transition.get_ptr(token) {
+            libobs::obs_set_output_source(channel, std::ptr::null_mut());
+        }
+        libobs::obs_source_release(current);
+
+        libobs::obs_transition_clear(transition.get_ptr(token));
+    }
+);
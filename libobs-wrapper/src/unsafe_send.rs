@@ -3,10 +3,40 @@
 //! This module provides wrapper types that allow non-Send types to be sent
 //! across thread boundaries. Use with caution - these are unsafe by design.
 
-use std::{hash::Hash, sync::Arc};
+use std::{hash::Hash, marker::PhantomData, sync::Arc};
 
 use crate::utils::ObsDropGuard;
 
+/// Proof that the current code runs on the OBS thread, which is required to read the pointer
+/// of a `SmartPointerSendable`.
+///
+/// `run_with_obs!` hands out a token to operations that take an argument
+/// (`move |token| unsafe { ... }`). Tokens can not be sent to other threads, so reading a
+/// pointer outside of the runtime fails to compile instead of being caught by a lint.
+///
+/// ```compile_fail
+/// fn assert_send<T: Send>() {}
+/// assert_send::<libobs_wrapper::unsafe_send::ObsThreadToken>();
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct ObsThreadToken {
+    _not_send: PhantomData<*const ()>,
+}
+
+impl ObsThreadToken {
+    /// Creates a token for the current thread, used by `run_with_obs!`.
+    ///
+    /// # Safety
+    /// Must only be called on the OBS thread or in callbacks libobs invokes on its own
+    /// threads, e.g. the graphics thread.
+    #[doc(hidden)]
+    pub unsafe fn new_unchecked() -> Self {
+        Self {
+            _not_send: PhantomData,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Sendable<T>(pub T);
 
@@ -34,17 +64,46 @@ unsafe impl<T: PartialEq> Sync for SendableComp<T> {}
 pub struct SmartPointerSendable<T: Clone> {
     ptr: T,
     drop_guard: Arc<dyn ObsDropGuard>,
+    #[cfg(feature = "debug_pointers")]
+    _tracker: Arc<debug::PointerTracker>,
 }
 
 impl<T: Clone> SmartPointerSendable<T> {
     pub fn new(ptr: T, drop_guard: Arc<dyn ObsDropGuard>) -> Self {
-        Self { ptr, drop_guard }
+        Self {
+            ptr,
+            drop_guard,
+            #[cfg(feature = "debug_pointers")]
+            _tracker: debug::PointerTracker::new::<T>(),
+        }
+    }
+
+    /// Returns the pointer, the token ensures that this happens on the OBS thread.
+    pub fn get_ptr(&self, _token: ObsThreadToken) -> T {
+        self.ptr.clone()
     }
 
-    pub fn get_ptr(&self) -> T {
+    /// Returns the pointer without proving that this happens on the OBS thread,
+    /// e.g. to compare or hash it.
+    ///
+    /// # Safety
+    /// The pointer must only be passed to libobs on the OBS thread.
+    #[cfg_attr(feature = "debug_pointers", track_caller)]
+    pub unsafe fn get_ptr_unchecked(&self) -> T {
+        #[cfg(feature = "debug_pointers")]
+        debug::check_access(std::panic::Location::caller());
+
         self.ptr.clone()
     }
 
+    /// Whether both smart pointers point to the same object.
+    pub fn ptr_eq(&self, other: &Self) -> bool
+    where
+        T: PartialEq,
+    {
+        self.ptr == other.ptr
+    }
+
     pub fn into_comp(self) -> SmartPointerSendableComp<T> {
         SmartPointerSendableComp::new(self.ptr, self.drop_guard)
     }
@@ -95,7 +154,8 @@ impl<T: Clone> SmartPointerSendableComp<T> {
         }
     }
 
-    pub fn get_ptr(&self) -> T {
+    /// Returns the pointer, the token ensures that this happens on the OBS thread.
+    pub fn get_ptr(&self, _token: ObsThreadToken) -> T {
         self.ptr.clone()
     }
 }
@@ -104,3 +164,133 @@ impl<T: Clone> SmartPointerSendableComp<T> {
 unsafe impl<T: Clone> Send for SmartPointerSendableComp<T> {}
 #[cfg(feature = "enable_runtime")]
 unsafe impl<T: Clone> Sync for SmartPointerSendableComp<T> {}
+
+#[cfg(feature = "debug_pointers")]
+pub mod debug {
+    //! Diagnostics for `SmartPointerSendable`, enabled by the `debug_pointers` feature.
+    //!
+    //! Every `SmartPointerSendable` records where it has been created, and a warning is logged
+    //! once per call site when its pointer is read with `get_ptr_unchecked` outside of the OBS
+    //! thread, which usually means that a libobs call is missing a `run_with_obs!`. Callbacks that
+    //! libobs runs on its own threads (e.g. the graphics thread) are reported as well, so check
+    //! each site before fixing it.
+    //!
+    //! `ObsContext::shutdown` logs every pointer that is still alive afterwards, as these keep
+    //! OBS from shutting down. `Sendable` is a plain tuple struct and is not tracked.
+
+    use std::{
+        backtrace::Backtrace,
+        cell::Cell,
+        collections::{HashMap, HashSet},
+        panic::Location,
+        sync::{
+            atomic::{AtomicU64, Ordering},
+            Arc, Mutex,
+        },
+    };
+
+    use lazy_static::lazy_static;
+
+    lazy_static! {
+        pub(crate) static ref LIVE_POINTERS: Mutex<HashMap<u64, ObsLivePointer>> =
+            Mutex::new(HashMap::new());
+        static ref REPORTED_ACCESSES: Mutex<HashSet<&'static Location<'static>>> =
+            Mutex::new(HashSet::new());
+    }
+
+    static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+    thread_local! {
+        static IS_OBS_THREAD: Cell<bool> = const { Cell::new(false) };
+    }
+
+    /// A `SmartPointerSendable` that has not been dropped yet.
+    #[derive(Debug, Clone)]
+    pub struct ObsLivePointer {
+        /// The type of the pointer, e.g. `*mut obs_source`
+        pub type_name: &'static str,
+        /// Where the pointer has been created
+        pub created_at: Arc<Backtrace>,
+    }
+
+    /// Registered while the `SmartPointerSendable` or one of its clones is alive.
+    #[derive(Debug)]
+    pub(crate) struct PointerTracker {
+        pub(crate) id: u64,
+    }
+
+    impl PointerTracker {
+        pub(crate) fn new<T>() -> Arc<Self> {
+            let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+            let pointer = ObsLivePointer {
+                type_name: std::any::type_name::<T>(),
+                created_at: Arc::new(Backtrace::force_capture()),
+            };
+
+            LIVE_POINTERS
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+                .insert(id, pointer);
+
+            Arc::new(Self { id })
+        }
+    }
+
+    impl Drop for PointerTracker {
+        fn drop(&mut self) {
+            LIVE_POINTERS
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+                .remove(&self.id);
+        }
+    }
+
+    /// Marks the current thread as the OBS thread, pointers may be read freely on it.
+    pub(crate) fn mark_obs_thread() {
+        IS_OBS_THREAD.with(|is_obs_thread| is_obs_thread.set(true));
+    }
+
+    pub(crate) fn check_access(location: &'static Location<'static>) {
+        if IS_OBS_THREAD.with(Cell::get) {
+            return;
+        }
+
+        let first_access = REPORTED_ACCESSES
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .insert(location);
+
+        if first_access {
+            log::warn!(
+                "Pointer of a SmartPointerSendable read outside of the OBS thread at {}, is a run_with_obs! missing?",
+                location
+            );
+        }
+    }
+
+    /// Returns every `SmartPointerSendable` that is still alive.
+    pub fn live_pointers() -> Vec<ObsLivePointer> {
+        LIVE_POINTERS
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .values()
+            .cloned()
+            .collect()
+    }
+
+    /// Logs every `SmartPointerSendable` that is still alive with the place it was created at.
+    pub(crate) fn report_live_pointers() {
+        let pointers = live_pointers();
+        if pointers.is_empty() {
+            return;
+        }
+
+        log::warn!(
+            "{} pointers are still alive after the context has been shut down:",
+            pointers.len()
+        );
+        for pointer in pointers {
+            log::warn!("{} created at:\n{}", pointer.type_name, pointer.created_at);
+        }
+    }
+}
@@ -0,0 +1,21 @@
+#[cfg(test)]
+mod tests {
+    use crate::unsafe_send::debug::{ObsLivePointer, PointerTracker, LIVE_POINTERS};
+
+    #[test]
+    fn tracker_is_removed_once_dropped() {
+        let tracker = PointerTracker::new::<*mut u8>();
+        let is_live = |id| {
+            LIVE_POINTERS
+                .lock()
+                .unwrap()
+                .get(&id)
+                .map(|p: &ObsLivePointer| p.type_name)
+        };
+
+        assert_eq!(is_live(tracker.id), Some("*mut u8"));
+        let id = tracker.id;
+        drop(tracker);
+        assert_eq!(is_live(id), None);
+    }
+}
@@ -1,5 +1,5 @@
 use std::{
-    ffi::{c_char, CStr},
+    ffi::{c_char, c_void, CStr},
     mem::MaybeUninit,
     pin::Pin,
     sync::Arc,
@@ -93,6 +93,42 @@ impl CalldataWrapper {
         Ok(value)
     }
 
+    /// Extracts a boolean for the given key from the calldata.
+    pub fn get_bool<T: Into<ObsString>>(&mut self, key: T) -> Result<bool, ObsError> {
+        let key: ObsString = key.into();
+        let self_ptr = unsafe {
+            // Safety: We won't modify the calldata, so it's safe to get a mutable pointer here.
+            self.as_mut_ptr()
+        };
+
+        let _drop_guard = self._drop_guard.clone(); // Ensure runtime is valid during the call
+        let value = run_with_obs!(
+            self.runtime.clone(),
+            (_drop_guard, self_ptr, key),
+            move || {
+                let mut value = false;
+                let ok = unsafe {
+                    // Safety: self_ptr and key are valid pointers and the size matches the bool we write into.
+                    libobs::calldata_get_data(
+                        self_ptr.0,
+                        key.as_ptr().0,
+                        &mut value as *mut bool as *mut c_void,
+                        std::mem::size_of::<bool>(),
+                    )
+                };
+                if !ok {
+                    return Err(ObsError::Unexpected(format!(
+                        "Calldata bool {key} not found."
+                    )));
+                }
+
+                Ok(value)
+            }
+        )??;
+
+        Ok(value)
+    }
+
     //TODO implement calldata get_data type but I think this is hard to safely do this
 }
 
@@ -0,0 +1,103 @@
+//! Lightweight descriptions of the outputs, encoders and services that exist in OBS,
+//! including the ones that have not been created by this wrapper.
+use std::ffi::{c_char, c_void, CStr};
+
+use libobs::{obs_encoder_t, obs_output_t, obs_service_t};
+use num_traits::FromPrimitive;
+
+use crate::enums::ObsEncoderType;
+
+/// An output that exists in OBS, see `ObsContext::enumerate_outputs`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ObsOutputSummary {
+    pub id: String,
+    pub name: String,
+    pub active: bool,
+}
+
+/// An encoder that exists in OBS, see `ObsContext::enumerate_encoders`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ObsEncoderSummary {
+    pub id: String,
+    pub name: String,
+    pub encoder_type: Option<ObsEncoderType>,
+    pub active: bool,
+}
+
+/// A service that exists in OBS, see `ObsContext::enumerate_services`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ObsServiceSummary {
+    pub id: String,
+    pub name: String,
+}
+
+unsafe fn ptr_to_string(ptr: *const c_char) -> String {
+    if ptr.is_null() {
+        return String::new();
+    }
+
+    CStr::from_ptr(ptr).to_string_lossy().into_owned()
+}
+
+unsafe extern "C" fn collect_output(param: *mut c_void, output: *mut obs_output_t) -> bool {
+    let outputs = &mut *(param as *mut Vec<ObsOutputSummary>);
+    outputs.push(ObsOutputSummary {
+        id: ptr_to_string(libobs::obs_output_get_id(output)),
+        name: ptr_to_string(libobs::obs_output_get_name(output)),
+        active: libobs::obs_output_active(output),
+    });
+
+    true
+}
+
+unsafe extern "C" fn collect_encoder(param: *mut c_void, encoder: *mut obs_encoder_t) -> bool {
+    let encoders = &mut *(param as *mut Vec<ObsEncoderSummary>);
+    encoders.push(ObsEncoderSummary {
+        id: ptr_to_string(libobs::obs_encoder_get_id(encoder)),
+        name: ptr_to_string(libobs::obs_encoder_get_name(encoder)),
+        encoder_type: ObsEncoderType::from_i64(libobs::obs_encoder_get_type(encoder) as i64),
+        active: libobs::obs_encoder_active(encoder),
+    });
+
+    true
+}
+
+unsafe extern "C" fn collect_service(param: *mut c_void, service: *mut obs_service_t) -> bool {
+    let services = &mut *(param as *mut Vec<ObsServiceSummary>);
+    services.push(ObsServiceSummary {
+        id: ptr_to_string(libobs::obs_service_get_id(service)),
+        name: ptr_to_string(libobs::obs_service_get_name(service)),
+    });
+
+    true
+}
+
+/// # Safety
+/// Must be called in the OBS runtime.
+pub(crate) unsafe fn enumerate_outputs() -> Vec<ObsOutputSummary> {
+    let mut outputs = Vec::new();
+    libobs::obs_enum_outputs(Some(collect_output), &mut outputs as *mut _ as *mut c_void);
+    outputs
+}
+
+/// # Safety
+/// Must be called in the OBS runtime.
+pub(crate) unsafe fn enumerate_encoders() -> Vec<ObsEncoderSummary> {
+    let mut encoders = Vec::new();
+    libobs::obs_enum_encoders(
+        Some(collect_encoder),
+        &mut encoders as *mut _ as *mut c_void,
+    );
+    encoders
+}
+
+/// # Safety
+/// Must be called in the OBS runtime.
+pub(crate) unsafe fn enumerate_services() -> Vec<ObsServiceSummary> {
+    let mut services = Vec::new();
+    libobs::obs_enum_services(
+        Some(collect_service),
+        &mut services as *mut _ as *mut c_void,
+    );
+    services
+}
@@ -1,7 +1,99 @@
-use std::fmt::Display;
+use std::{error::Error, fmt::Display, panic::Location, sync::Arc};
 
 use crate::enums::ObsResetVideoStatus;
 
+/// A cloneable handle to the underlying error that caused an `ObsError`.
+///
+/// This is returned by `std::error::Error::source` so downstream crates (`anyhow`, `eyre`, ...)
+/// can walk the full causal chain. Two sources are considered equal if they format to the same message.
+#[derive(Clone, Debug)]
+pub struct ObsErrorSource(Arc<dyn Error + Send + Sync + 'static>);
+
+impl ObsErrorSource {
+    pub fn new<E: Error + Send + Sync + 'static>(error: E) -> Self {
+        Self(Arc::new(error))
+    }
+
+    /// Returns the wrapped error.
+    pub fn inner(&self) -> &(dyn Error + Send + Sync + 'static) {
+        self.0.as_ref()
+    }
+}
+
+impl Display for ObsErrorSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl PartialEq for ObsErrorSource {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0) || self.0.to_string() == other.0.to_string()
+    }
+}
+
+impl Eq for ObsErrorSource {}
+
+impl<E: Error + Send + Sync + 'static> From<E> for ObsErrorSource {
+    fn from(error: E) -> Self {
+        Self::new(error)
+    }
+}
+
+/// A stable classification of an `ObsError`, see `ObsError::code`.
+///
+/// Unlike the messages of the errors, the codes and their numeric values do not change between releases,
+/// so they can be matched on or be passed to other languages and telemetry.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u32)]
+pub enum ObsErrorCode {
+    /// `obs_startup` failed or libobs could not be loaded
+    Startup = 1,
+    /// The runtime or its thread is in an invalid state
+    Runtime = 2,
+    ResetVideo = 3,
+    ResetAudio = 4,
+    /// An OBS function returned a null pointer
+    NullPointer = 5,
+    Output = 6,
+    NotFound = 7,
+    InvalidOperation = 8,
+    Conversion = 9,
+    Display = 10,
+    Native = 11,
+    Json = 12,
+    Signal = 13,
+    Encoder = 14,
+    Lock = 15,
+    Platform = 16,
+    Io = 17,
+    Filter = 18,
+    NameAlreadyInUse = 19,
+    Module = 20,
+    Unexpected = 21,
+    Serialization = 22,
+}
+
+impl ObsErrorCode {
+    /// Returns the numeric value of this code.
+    pub fn value(self) -> u32 {
+        self as u32
+    }
+}
+
+/// An error together with the OBS function that failed and where it has been raised,
+/// see `ObsError::in_function`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ObsCallError {
+    /// The name of the OBS function that failed, e.g. `obs_source_create`
+    pub function: &'static str,
+    /// Where in this crate the error has been raised
+    pub location: &'static Location<'static>,
+    /// The lines OBS logged right before the error, if they have been attached
+    pub log_excerpt: Option<String>,
+    pub error: ObsError,
+}
+
 /// Error type for OBS function calls.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum ObsError {
@@ -21,6 +113,10 @@ pub enum ObsError {
     ResetVideoFailureGraphicsModule,
     /// Unable to reset video because some outputs were still active.
     ResetVideoFailureOutputActive,
+    /// Unable to reset audio.
+    ResetAudioFailure,
+    /// Unable to reset audio because some outputs were still active.
+    ResetAudioFailureOutputActive,
     /// The function returned a null pointer, often indicating
     /// an error with creating the object of the requested
     /// pointer.
@@ -36,15 +132,21 @@ pub enum ObsError {
     /// Error converting a string between Rust and OBS
     StringConversionError,
 
-    /// Native error from the Windows API when creating a display
+    /// Error when creating a display
     DisplayCreationError(String),
 
+    /// Native error from the OS API (for example the Windows API when managing the preview window)
+    NativeError(ObsErrorSource),
+
     OutputSaveBufferFailure(String),
 
     /// The obs thread couldn't be called
     InvocationError(String),
 
-    JsonParseError,
+    /// Failed to parse JSON data, with the underlying error if there is one
+    JsonParseError(Option<ObsErrorSource>),
+    /// Failed to serialize data, e.g. a profile to JSON or TOML
+    SerializationError(ObsErrorSource),
     /// Couldn't get the sender of the signal
     NoSenderError,
     NoAvailableEncoders,
@@ -59,7 +161,7 @@ pub enum ObsError {
     PlatformInitError(String),
 
     /// Error reading/writing to a file
-    IoError(String),
+    IoError(ObsErrorSource),
 
     /// Failed to get data from signal calldata
     SignalDataError(String),
@@ -77,6 +179,152 @@ pub enum ObsError {
 
     /// A filter was already applied to a source
     FilterAlreadyApplied,
+
+    /// An object with the given name exists already
+    NameAlreadyInUse(String),
+
+    /// A module could not be found, opened or initialized
+    ModuleLoadFailure(String),
+
+    /// The given error was raised by an OBS function. The message of the wrapped error is kept as is.
+    Call(Box<ObsCallError>),
+}
+
+impl ObsError {
+    /// Attaches the name of the failing OBS function and the location of the caller to this error.
+    ///
+    /// Errors that already have a function attached are returned unchanged.
+    #[track_caller]
+    pub fn in_function(self, function: &'static str) -> Self {
+        if let ObsError::Call(_) = self {
+            return self;
+        }
+
+        ObsError::Call(Box::new(ObsCallError {
+            function,
+            location: Location::caller(),
+            log_excerpt: None,
+            error: self,
+        }))
+    }
+
+    /// Attaches an excerpt of the OBS log to this error, e.g. the lines logged by a failing `obs_source_create`.
+    #[track_caller]
+    pub fn with_log_excerpt<S: Into<String>>(self, excerpt: S) -> Self {
+        match self {
+            ObsError::Call(mut call) => {
+                call.log_excerpt = Some(excerpt.into());
+                ObsError::Call(call)
+            }
+            error => ObsError::Call(Box::new(ObsCallError {
+                function: "",
+                location: Location::caller(),
+                log_excerpt: Some(excerpt.into()),
+                error,
+            })),
+        }
+    }
+
+    /// Returns the underlying error, without the function and location that may be attached to it.
+    pub fn kind(&self) -> &ObsError {
+        match self {
+            ObsError::Call(call) => call.error.kind(),
+            error => error,
+        }
+    }
+
+    /// Returns the name of the OBS function that failed, if it is known.
+    pub fn function(&self) -> Option<&'static str> {
+        match self {
+            ObsError::Call(call) if !call.function.is_empty() => Some(call.function),
+            _ => None,
+        }
+    }
+
+    /// Returns where in this crate the error has been raised, if it is known.
+    pub fn location(&self) -> Option<&'static Location<'static>> {
+        match self {
+            ObsError::Call(call) => Some(call.location),
+            _ => None,
+        }
+    }
+
+    /// Returns the excerpt of the OBS log attached to this error.
+    pub fn log_excerpt(&self) -> Option<&str> {
+        match self {
+            ObsError::Call(call) => call.log_excerpt.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Returns the stable code of this error.
+    pub fn code(&self) -> ObsErrorCode {
+        match self.kind() {
+            ObsError::Failure | ObsError::InvalidDll => ObsErrorCode::Startup,
+            ObsError::MutexFailure
+            | ObsError::ThreadFailure
+            | ObsError::InvocationError(_)
+            | ObsError::RuntimeChannelError(_)
+            | ObsError::RuntimeOutsideThread => ObsErrorCode::Runtime,
+            ObsError::ResetVideoFailure(_)
+            | ObsError::ResetVideoFailureGraphicsModule
+            | ObsError::ResetVideoFailureOutputActive => ObsErrorCode::ResetVideo,
+            ObsError::ResetAudioFailure | ObsError::ResetAudioFailureOutputActive => {
+                ObsErrorCode::ResetAudio
+            }
+            ObsError::NullPointer(_) => ObsErrorCode::NullPointer,
+            ObsError::OutputAlreadyActive
+            | ObsError::OutputStartFailure(_)
+            | ObsError::OutputStopFailure(_)
+            | ObsError::OutputPauseFailure(_)
+            | ObsError::OutputSaveBufferFailure(_) => ObsErrorCode::Output,
+            ObsError::OutputNotFound
+            | ObsError::SourceNotFound
+            | ObsError::SourceNotAvailable(_) => ObsErrorCode::NotFound,
+            ObsError::InvalidOperation(_) => ObsErrorCode::InvalidOperation,
+            ObsError::StringConversionError | ObsError::EnumConversionError(_) => {
+                ObsErrorCode::Conversion
+            }
+            ObsError::DisplayCreationError(_) => ObsErrorCode::Display,
+            ObsError::NativeError(_) => ObsErrorCode::Native,
+            ObsError::JsonParseError(_) => ObsErrorCode::Json,
+            ObsError::SerializationError(_) => ObsErrorCode::Serialization,
+            ObsError::NoSenderError | ObsError::SignalDataError(_) => ObsErrorCode::Signal,
+            ObsError::NoAvailableEncoders | ObsError::EncoderActive => ObsErrorCode::Encoder,
+            ObsError::LockError(_) => ObsErrorCode::Lock,
+            ObsError::PlatformInitError(_) => ObsErrorCode::Platform,
+            ObsError::IoError(_) => ObsErrorCode::Io,
+            ObsError::FilterAlreadyApplied => ObsErrorCode::Filter,
+            ObsError::NameAlreadyInUse(_) => ObsErrorCode::NameAlreadyInUse,
+            ObsError::ModuleLoadFailure(_) => ObsErrorCode::Module,
+            ObsError::Unexpected(_) => ObsErrorCode::Unexpected,
+            // `kind` never returns a call error
+            ObsError::Call(call) => call.error.code(),
+        }
+    }
+
+    /// Returns whether the application can keep using the context after this error,
+    /// e.g. by retrying with different settings or another encoder.
+    ///
+    /// Errors that indicate a broken runtime, a poisoned lock or a failed startup are not recoverable,
+    /// the context should be recreated (or the application restarted) instead.
+    pub fn is_recoverable(&self) -> bool {
+        !matches!(
+            self.kind(),
+            ObsError::Failure
+                | ObsError::InvalidDll
+                | ObsError::MutexFailure
+                | ObsError::ThreadFailure
+                | ObsError::ResetVideoFailureGraphicsModule
+                | ObsError::InvocationError(_)
+                | ObsError::NoSenderError
+                | ObsError::LockError(_)
+                | ObsError::Unexpected(_)
+                | ObsError::PlatformInitError(_)
+                | ObsError::RuntimeChannelError(_)
+                | ObsError::RuntimeOutsideThread
+        )
+    }
 }
 
 #[cfg_attr(coverage_nightly, coverage(off))]
@@ -91,17 +339,21 @@ impl Display for ObsError {
             ObsError::ResetVideoFailure(status) => write!(f, "Could not reset obs video. Status: {:?}", status),
             ObsError::ResetVideoFailureGraphicsModule => write!(f, "Unable to reset video because the program attempted to change the graphics module. This is a bug!"),
             ObsError::ResetVideoFailureOutputActive => write!(f, "Unable to reset video because some outputs were still active."),
+            ObsError::ResetAudioFailure => write!(f, "Could not reset obs audio."),
+            ObsError::ResetAudioFailureOutputActive => write!(f, "Unable to reset audio because some outputs were still active."),
             ObsError::NullPointer(e) => write!(f, "The function returned a null pointer, often indicating an error with creating the object of the requested pointer. Details: {:?}", e),
             ObsError::OutputAlreadyActive => write!(f, "Output is already active."),
             ObsError::OutputStartFailure(s) => write!(f, "Output failed to start. Error is {:?}", s),
             ObsError::OutputStopFailure(s) => write!(f, "Output failed to stop. Error is {:?}", s),
             ObsError::OutputNotFound => write!(f, "Output not found."),
-            ObsError::DisplayCreationError(e) => write!(f, "Native error from the Windows API when creating a display: {:?}", e),
+            ObsError::DisplayCreationError(e) => write!(f, "Error when creating a display: {:?}", e),
+            ObsError::NativeError(e) => write!(f, "Native error from the OS API: {}", e),
             ObsError::OutputSaveBufferFailure(e) => write!(f, "Couldn't save output buffer: {:?}", e),
             ObsError::SourceNotFound => write!(f, "Source not found."),
             ObsError::SourceNotAvailable(source_name) => write!(f, "Source {} is not available. See logs or similar to check why.", source_name),
             ObsError::InvocationError(e) => write!(f, "The obs thread couldn't be called: {:?}", e),
-            ObsError::JsonParseError => write!(f, "Failed to parse JSON data."),
+            ObsError::JsonParseError(_) => write!(f, "Failed to parse JSON data."),
+            ObsError::SerializationError(e) => write!(f, "Failed to serialize data: {}", e),
             ObsError::NoSenderError => write!(f, "Couldn't get the sender of the signal."),
             ObsError::NoAvailableEncoders => write!(f, "No available encoders found."),
             ObsError::OutputPauseFailure(s) => write!(f, "Output failed to pause. Error is {:?}", s),
@@ -121,8 +373,26 @@ impl Display for ObsError {
             #[cfg(not(feature="enable_runtime"))]
             ObsError::RuntimeOutsideThread => write!(f, "Attempted to call a OBS runtime function from outside the OBS thread. Make sure that you do not use any OBS struct from a different thread than the one where the ObsContext was initialized. THIS BUG WILL CAUSE MEMORY CORRUPTION OR DEADLOCKS!"),
             ObsError::FilterAlreadyApplied => write!(f, "Filter was applied already."),
+            ObsError::NameAlreadyInUse(name) => write!(f, "The name {} is already in use.", name),
+            ObsError::ModuleLoadFailure(e) => write!(f, "Failed to load module: {}", e),
+            // The prefix has been written already
+            ObsError::Call(call) => {
+                let message = call.error.to_string();
+                write!(f, "{}", message.strip_prefix("OBS Error: ").unwrap_or(&message))
+            }
         }
     }
 }
 
-impl std::error::Error for ObsError {}
+impl Error for ObsError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ObsError::NativeError(e)
+            | ObsError::IoError(e)
+            | ObsError::JsonParseError(Some(e))
+            | ObsError::SerializationError(e) => Some(e.inner()),
+            ObsError::Call(call) => call.error.source(),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,54 @@
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+
+    use super::super::error::{ObsError, ObsErrorCode};
+
+    #[test]
+    fn test_io_error_source_is_kept() {
+        let io = std::io::Error::new(std::io::ErrorKind::NotFound, "missing log dir");
+        let err = ObsError::IoError(io.into());
+
+        let source = err.source().expect("IoError should expose its source");
+        assert_eq!(source.to_string(), "missing log dir");
+        assert_eq!(err.clone(), err);
+    }
+
+    #[test]
+    fn test_serialization_error() {
+        let err = ObsError::SerializationError(std::fmt::Error.into());
+
+        assert_eq!(err.code(), ObsErrorCode::Serialization);
+        assert_ne!(err.code(), ObsError::JsonParseError(None).code());
+        assert!(err.source().is_some());
+        assert!(err.is_recoverable());
+    }
+
+    #[test]
+    fn test_error_without_source() {
+        assert!(ObsError::JsonParseError(None).source().is_none());
+        assert!(ObsError::OutputNotFound.source().is_none());
+    }
+
+    #[test]
+    fn test_call_error_keeps_message_and_code() {
+        let plain = ObsError::NullPointer(None);
+        let err = plain.clone().in_function("obs_source_create");
+
+        assert_eq!(err.to_string(), plain.to_string());
+        assert_eq!(err.kind(), &plain);
+        assert_eq!(err.code(), ObsErrorCode::NullPointer);
+        assert_eq!(err.function(), Some("obs_source_create"));
+        assert_eq!(err.location().map(|l| l.file()), Some(file!()));
+        assert!(err.is_recoverable());
+
+        let err = err.with_log_excerpt("error: Source 'x' not found");
+        assert_eq!(err.function(), Some("obs_source_create"));
+        assert_eq!(err.log_excerpt(), Some("error: Source 'x' not found"));
+
+        assert!(!ObsError::LockError("poisoned".into())
+            .in_function("obs_output_start")
+            .is_recoverable());
+        assert_eq!(ObsErrorCode::Startup.value(), 1);
+    }
+}
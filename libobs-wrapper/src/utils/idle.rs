@@ -0,0 +1,165 @@
+//! Lowers the frame rate of the video pipeline while nothing is displayed or recorded,
+//! see `StartupInfo::set_idle_frame_rate`.
+//!
+//! libobs renders the scenes every frame, even if no output or display uses the result.
+//! There is no way to pause the graphics thread, so the video is reset with the idle frame
+//! rate instead and reset back before a display, output or view is created.
+//!
+//! A reset frees the `video_t` of the main canvas and of every view. Outputs, their encoders
+//! and views keep the pointer they were created with, so the video is only lowered while the
+//! context has none of them. Once an output exists, the video keeps its real frame rate.
+
+use std::sync::{Arc, Mutex, MutexGuard, RwLock, Weak};
+
+use libobs::video_output;
+
+use crate::{
+    data::video::ObsVideoInfo, enums::ObsResetVideoStatus, run_with_obs, runtime::ObsRuntime,
+    unsafe_send::Sendable, utils::ObsError,
+};
+
+/// Handle of a view created by the context, the view is alive as long as this can be upgraded.
+pub(crate) type ObsViewLiveness = Weak<RwLock<Option<Sendable<*mut video_output>>>>;
+
+#[derive(Debug)]
+struct IdleVideo {
+    /// The video info OBS runs with while something is displayed or recorded
+    active: ObsVideoInfo,
+    idle: ObsVideoInfo,
+    is_idle: bool,
+    displays: usize,
+    views: Vec<ObsViewLiveness>,
+}
+
+impl IdleVideo {
+    fn reset(&self, runtime: &ObsRuntime, idle: bool) -> Result<(), ObsError> {
+        let ovi = if idle { &self.idle } else { &self.active };
+        let vid_ptr = Sendable(ovi.as_ptr());
+        let status = run_with_obs!(runtime, (vid_ptr), move || unsafe {
+            // Safety: The video info is kept alive by the locked state
+            libobs::obs_reset_video(vid_ptr.0)
+        })?;
+
+        match num_traits::FromPrimitive::from_i32(status) {
+            Some(ObsResetVideoStatus::Success) => Ok(()),
+            Some(status) => Err(ObsError::ResetVideoFailure(status)),
+            None => Err(ObsError::ResetVideoFailure(ObsResetVideoStatus::Failure)),
+        }
+    }
+}
+
+/// Idle state of a context, shared between its clones.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ObsIdleVideo(Arc<Mutex<Option<IdleVideo>>>);
+
+impl ObsIdleVideo {
+    fn lock(&self) -> MutexGuard<'_, Option<IdleVideo>> {
+        self.0
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Enables idle rendering with the given frame rate, `active` is the video info OBS was
+    /// started with.
+    pub(crate) fn enable(&self, active: &ObsVideoInfo, fps_num: u32, fps_den: u32) {
+        *self.lock() = Some(IdleVideo {
+            active: active.with_frame_rate(active.get_fps_num(), active.get_fps_den()),
+            idle: active.with_frame_rate(fps_num, fps_den),
+            is_idle: false,
+            displays: 0,
+            views: Vec::new(),
+        });
+    }
+
+    pub(crate) fn disable(&self) {
+        *self.lock() = None;
+    }
+
+    pub(crate) fn is_idle(&self) -> bool {
+        self.lock().as_ref().is_some_and(|state| state.is_idle)
+    }
+
+    /// Replaces the video info OBS runs with while something is displayed or recorded,
+    /// after it has been reset by `ObsContext::reset_video`.
+    pub(crate) fn video_reset(&self, active: &ObsVideoInfo) {
+        if let Some(state) = self.lock().as_mut() {
+            let (fps_num, fps_den) = (state.idle.get_fps_num(), state.idle.get_fps_den());
+            state.active = active.with_frame_rate(active.get_fps_num(), active.get_fps_den());
+            state.idle = active.with_frame_rate(fps_num, fps_den);
+            state.is_idle = false;
+        }
+    }
+
+    pub(crate) fn display_added(&self) {
+        if let Some(state) = self.lock().as_mut() {
+            state.displays += 1;
+        }
+    }
+
+    pub(crate) fn display_removed(&self) {
+        if let Some(state) = self.lock().as_mut() {
+            state.displays = state.displays.saturating_sub(1);
+        }
+    }
+
+    /// Keeps the video awake while the view is alive. The video has to be woken up before
+    /// the view is created.
+    pub(crate) fn view_added(&self, view: ObsViewLiveness) {
+        if let Some(state) = self.lock().as_mut() {
+            state.views.retain(|view| view.strong_count() > 0);
+            state.views.push(view);
+        }
+    }
+
+    /// Restores the frame rate of the video if it is idle. Must be called before anything
+    /// that keeps the video of OBS is created, e.g. an output or a view.
+    pub(crate) fn wake(&self, runtime: &ObsRuntime) -> Result<(), ObsError> {
+        let mut guard = self.lock();
+        let Some(state) = guard.as_mut().filter(|state| state.is_idle) else {
+            return Ok(());
+        };
+
+        log::debug!("Waking up video rendering");
+        state.reset(runtime, false)?;
+        state.is_idle = false;
+
+        Ok(())
+    }
+
+    /// Lowers the frame rate of the video if idle rendering is enabled and the context has
+    /// no display, output or view. Returns whether the video is idle afterwards.
+    pub(crate) fn try_idle(
+        &self,
+        runtime: &ObsRuntime,
+        has_outputs: bool,
+    ) -> Result<bool, ObsError> {
+        let mut guard = self.lock();
+        let Some(state) = guard.as_mut() else {
+            return Ok(false);
+        };
+
+        if state.is_idle {
+            return Ok(true);
+        }
+
+        state.views.retain(|view| view.strong_count() > 0);
+        if has_outputs || state.displays > 0 || !state.views.is_empty() {
+            return Ok(false);
+        }
+
+        // Outputs created directly through libobs
+        let video_active = run_with_obs!(runtime, move || unsafe {
+            // Safety: We are in the runtime, this just reads the global state of the video output
+            libobs::obs_video_active()
+        })?;
+        if video_active {
+            return Ok(false);
+        }
+
+        log::debug!("No display, output or view left, lowering the frame rate");
+        state.reset(runtime, true)?;
+        state.is_idle = true;
+
+        Ok(true)
+    }
+}
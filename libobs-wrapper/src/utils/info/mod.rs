@@ -1,4 +1,8 @@
+mod profile;
+#[cfg(test)]
+mod profile_tests;
 mod startup;
+pub use profile::*;
 pub use startup::*;
 
 use crate::data::{ImmutableObsData, ObsData};
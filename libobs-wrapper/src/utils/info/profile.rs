@@ -0,0 +1,351 @@
+use std::collections::BTreeMap;
+
+use crate::{
+    data::{audio::ObsAudioInfo, video::ObsVideoInfo, ObsData},
+    enums::{ObsSamplesPerSecond, ObsSpeakerLayout},
+    runtime::ObsRuntime,
+    utils::{ObsError, VideoEncoderInfo},
+};
+
+/// A preset of the video, audio, encoder and output settings, like a profile of OBS Studio.
+///
+/// Apply it with `ObsContext::apply_profile` and create outputs with its encoders with
+/// `ObsContext::profile_output`. With the `serde` feature enabled, the profile can be
+/// loaded from / saved to any serde format, see `Self::from_json` and `Self::from_toml`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ObsProfile {
+    pub name: String,
+    pub video: ObsProfileVideo,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub audio: ObsProfileAudio,
+    /// The video encoder of outputs created with `ObsContext::profile_output`
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub video_encoder: Option<ObsProfileEncoder>,
+    /// The audio encoder of outputs created with `ObsContext::profile_output`
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub audio_encoder: Option<ObsProfileEncoder>,
+    /// The settings of outputs created with `ObsContext::profile_output`,
+    /// e.g. the path of a recording
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub output_settings: ObsProfileSettings,
+}
+
+impl ObsProfile {
+    pub fn new(name: impl Into<String>, video: ObsProfileVideo) -> Self {
+        Self {
+            name: name.into(),
+            video,
+            audio: ObsProfileAudio::default(),
+            video_encoder: None,
+            audio_encoder: None,
+            output_settings: ObsProfileSettings::new(),
+        }
+    }
+
+    pub fn with_audio(mut self, audio: ObsProfileAudio) -> Self {
+        self.audio = audio;
+        self
+    }
+
+    pub fn with_video_encoder(mut self, encoder: ObsProfileEncoder) -> Self {
+        self.video_encoder = Some(encoder);
+        self
+    }
+
+    pub fn with_audio_encoder(mut self, encoder: ObsProfileEncoder) -> Self {
+        self.audio_encoder = Some(encoder);
+        self
+    }
+
+    pub fn with_output_setting(
+        mut self,
+        key: impl Into<String>,
+        value: impl Into<ObsProfileValue>,
+    ) -> Self {
+        self.output_settings.insert(key.into(), value.into());
+        self
+    }
+}
+
+#[cfg(feature = "serde")]
+impl ObsProfile {
+    pub fn to_json(&self) -> Result<String, ObsError> {
+        serde_json::to_string_pretty(self).map_err(|e| ObsError::SerializationError(e.into()))
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, ObsError> {
+        serde_json::from_str(json).map_err(|e| ObsError::JsonParseError(Some(e.into())))
+    }
+
+    pub fn to_toml(&self) -> Result<String, ObsError> {
+        toml::to_string_pretty(self).map_err(|e| ObsError::SerializationError(e.into()))
+    }
+
+    pub fn from_toml(toml: &str) -> Result<Self, ObsError> {
+        toml::from_str(toml)
+            .map_err(|e| ObsError::InvalidOperation(format!("Failed to parse profile TOML: {}", e)))
+    }
+}
+
+/// A value of the encoder or output settings of an [`ObsProfile`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(untagged)
+)]
+pub enum ObsProfileValue {
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    String(String),
+}
+
+impl From<bool> for ObsProfileValue {
+    fn from(value: bool) -> Self {
+        Self::Bool(value)
+    }
+}
+
+impl From<i32> for ObsProfileValue {
+    fn from(value: i32) -> Self {
+        Self::Int(value.into())
+    }
+}
+
+impl From<u32> for ObsProfileValue {
+    fn from(value: u32) -> Self {
+        Self::Int(value.into())
+    }
+}
+
+impl From<i64> for ObsProfileValue {
+    fn from(value: i64) -> Self {
+        Self::Int(value)
+    }
+}
+
+impl From<f64> for ObsProfileValue {
+    fn from(value: f64) -> Self {
+        Self::Float(value)
+    }
+}
+
+impl From<&str> for ObsProfileValue {
+    fn from(value: &str) -> Self {
+        Self::String(value.to_string())
+    }
+}
+
+impl From<String> for ObsProfileValue {
+    fn from(value: String) -> Self {
+        Self::String(value)
+    }
+}
+
+/// The settings of an encoder or output of an [`ObsProfile`] by their key, e.g. `bitrate`.
+pub type ObsProfileSettings = BTreeMap<String, ObsProfileValue>;
+
+/// Converts the settings to `ObsData`, `None` if there are no settings.
+pub(crate) fn settings_to_data(
+    settings: &ObsProfileSettings,
+    runtime: &ObsRuntime,
+) -> Result<Option<ObsData>, ObsError> {
+    if settings.is_empty() {
+        return Ok(None);
+    }
+
+    let mut data = ObsData::new(runtime.clone())?;
+    let mut updater = data.bulk_update();
+    for (key, value) in settings {
+        match value {
+            ObsProfileValue::Bool(value) => updater.set_bool_ref(key.as_str(), *value),
+            ObsProfileValue::Int(value) => updater.set_int_ref(key.as_str(), *value),
+            ObsProfileValue::Float(value) => updater.set_double_ref(key.as_str(), *value),
+            ObsProfileValue::String(value) => updater.set_string_ref(key.as_str(), value.as_str()),
+        }
+    }
+    updater.apply()?;
+
+    Ok(Some(data))
+}
+
+/// The canvas and frame rate of an [`ObsProfile`]. The graphics module, format and
+/// colorspace of the running context are kept.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ObsProfileVideo {
+    pub base_width: u32,
+    pub base_height: u32,
+    pub output_width: u32,
+    pub output_height: u32,
+    pub fps_num: u32,
+    pub fps_den: u32,
+}
+
+impl ObsProfileVideo {
+    /// A canvas that is rendered and encoded at the same size.
+    pub fn new(width: u32, height: u32, fps_num: u32, fps_den: u32) -> Self {
+        Self {
+            base_width: width,
+            base_height: height,
+            output_width: width,
+            output_height: height,
+            fps_num,
+            fps_den,
+        }
+    }
+
+    pub fn with_output_size(mut self, width: u32, height: u32) -> Self {
+        self.output_width = width;
+        self.output_height = height;
+        self
+    }
+
+    fn validate(&self) -> Result<(), ObsError> {
+        let sizes = [
+            self.base_width,
+            self.base_height,
+            self.output_width,
+            self.output_height,
+        ];
+
+        if sizes.contains(&0) || self.fps_num == 0 || self.fps_den == 0 {
+            return Err(ObsError::InvalidOperation(format!(
+                "Invalid video profile {:?}, sizes and frame rate must not be zero",
+                self
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Returns whether the video info already uses the canvas and frame rate of this profile.
+    pub(crate) fn matches(&self, ovi: &ObsVideoInfo) -> bool {
+        self.base_width == ovi.get_base_width()
+            && self.base_height == ovi.get_base_height()
+            && self.output_width == ovi.get_output_width()
+            && self.output_height == ovi.get_output_height()
+            && self.fps_num == ovi.get_fps_num()
+            && self.fps_den == ovi.get_fps_den()
+    }
+
+    /// Returns a copy of the current video info with the canvas and frame rate of this profile.
+    pub(crate) fn to_video_info(self, current: &ObsVideoInfo) -> Result<ObsVideoInfo, ObsError> {
+        self.validate()?;
+
+        Ok(current.with_canvas(
+            (self.base_width, self.base_height),
+            (self.output_width, self.output_height),
+            (self.fps_num, self.fps_den),
+        ))
+    }
+}
+
+/// The audio settings of an [`ObsProfile`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ObsProfileAudio {
+    /// 44100 or 48000
+    pub samples_per_sec: u32,
+    /// 1 (mono) - 8 (7.1), 7 channels are not supported by libobs
+    pub channels: u32,
+    /// Zero uses the default of libobs
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub max_buffering_ms: u32,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub fixed_buffering: bool,
+}
+
+impl Default for ObsProfileAudio {
+    fn default() -> Self {
+        Self {
+            samples_per_sec: 48000,
+            channels: 2,
+            max_buffering_ms: 0,
+            fixed_buffering: false,
+        }
+    }
+}
+
+impl ObsProfileAudio {
+    pub fn to_audio_info(&self) -> Result<ObsAudioInfo, ObsError> {
+        let samples_per_sec = match self.samples_per_sec {
+            44100 => ObsSamplesPerSecond::F44100,
+            48000 => ObsSamplesPerSecond::F48000,
+            rate => {
+                return Err(ObsError::InvalidOperation(format!(
+                    "Unsupported sample rate {}, use 44100 or 48000",
+                    rate
+                )))
+            }
+        };
+
+        let speakers = match self.channels {
+            1 => ObsSpeakerLayout::Mono,
+            2 => ObsSpeakerLayout::Stereo,
+            3 => ObsSpeakerLayout::S2Point1,
+            4 => ObsSpeakerLayout::S4Point0,
+            5 => ObsSpeakerLayout::S4Point1,
+            6 => ObsSpeakerLayout::S5Point1,
+            8 => ObsSpeakerLayout::S7Point1,
+            channels => {
+                return Err(ObsError::InvalidOperation(format!(
+                    "Unsupported number of audio channels {}",
+                    channels
+                )))
+            }
+        };
+
+        crate::data::audio::ObsAudioInfoBuilder::new()
+            .samples_per_second(samples_per_sec)
+            .speakers(speakers)
+            .max_buffering_ms(self.max_buffering_ms)
+            .fixed_buffering(self.fixed_buffering)
+            .build()
+    }
+}
+
+/// An encoder of an [`ObsProfile`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ObsProfileEncoder {
+    /// The id of the encoder, e.g. `obs_x264` or `ffmpeg_aac`
+    pub id: String,
+    /// The settings of the encoder, e.g. the bitrate
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub settings: ObsProfileSettings,
+}
+
+impl ObsProfileEncoder {
+    pub fn new(id: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            settings: ObsProfileSettings::new(),
+        }
+    }
+
+    pub fn with_setting(
+        mut self,
+        key: impl Into<String>,
+        value: impl Into<ObsProfileValue>,
+    ) -> Self {
+        self.settings.insert(key.into(), value.into());
+        self
+    }
+
+    /// Creates the info of an encoder with the given name, the same type is used for audio encoders.
+    pub(crate) fn to_info(
+        &self,
+        name: String,
+        runtime: &ObsRuntime,
+    ) -> Result<VideoEncoderInfo, ObsError> {
+        Ok(VideoEncoderInfo::new(
+            self.id.as_str(),
+            name,
+            settings_to_data(&self.settings, runtime)?,
+            None,
+        ))
+    }
+}
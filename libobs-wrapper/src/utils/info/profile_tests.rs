@@ -0,0 +1,96 @@
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "serde")]
+    use super::super::profile::{ObsProfile, ObsProfileEncoder};
+    use super::super::profile::{ObsProfileAudio, ObsProfileVideo};
+    use crate::data::video::ObsVideoInfoBuilder;
+    use crate::enums::{ObsSamplesPerSecond, ObsSpeakerLayout};
+
+    #[test]
+    fn test_audio_profile_conversion() {
+        let info = ObsProfileAudio {
+            samples_per_sec: 44100,
+            channels: 6,
+            max_buffering_ms: 100,
+            fixed_buffering: true,
+        }
+        .to_audio_info()
+        .unwrap();
+
+        assert_eq!(info.samples_per_second(), ObsSamplesPerSecond::F44100);
+        assert_eq!(info.speakers(), ObsSpeakerLayout::S5Point1);
+        assert_eq!(info.max_buffering_ms(), 100);
+        assert!(info.fixed_buffering());
+
+        let invalid_rate = ObsProfileAudio {
+            samples_per_sec: 96000,
+            ..Default::default()
+        };
+        assert!(invalid_rate.to_audio_info().is_err());
+
+        let invalid_channels = ObsProfileAudio {
+            channels: 7,
+            ..Default::default()
+        };
+        assert!(invalid_channels.to_audio_info().is_err());
+    }
+
+    #[test]
+    fn test_video_profile_keeps_the_rest_of_the_video_info() {
+        let current = ObsVideoInfoBuilder::new()
+            .base_width(1920)
+            .base_height(1080)
+            .build();
+
+        let profile = ObsProfileVideo::new(1280, 720, 60, 1).with_output_size(854, 480);
+        assert!(!profile.matches(&current));
+
+        let ovi = profile.to_video_info(&current).unwrap();
+        assert!(profile.matches(&ovi));
+        assert_eq!(ovi.graphics_module(), current.graphics_module());
+        assert_eq!(ovi.get_output_format(), current.get_output_format());
+
+        let invalid = ObsProfileVideo::new(0, 720, 60, 1);
+        assert!(invalid.to_video_info(&current).is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    fn test_profile() -> ObsProfile {
+        ObsProfile::new("Recording", ObsProfileVideo::new(1920, 1080, 60, 1))
+            .with_audio(ObsProfileAudio {
+                samples_per_sec: 44100,
+                ..Default::default()
+            })
+            .with_video_encoder(
+                ObsProfileEncoder::new("obs_x264")
+                    .with_setting("bitrate", 6000)
+                    .with_setting("rate_control", "CBR")
+                    .with_setting("crf", 23.5),
+            )
+            .with_audio_encoder(ObsProfileEncoder::new("ffmpeg_aac").with_setting("bitrate", 160))
+            .with_output_setting("path", "recording.mkv")
+            .with_output_setting("split_file", true)
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_profile_json_roundtrip() {
+        let profile = test_profile();
+        let json = profile.to_json().unwrap();
+        assert_eq!(ObsProfile::from_json(&json).unwrap(), profile);
+
+        assert!(ObsProfile::from_json("{").is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_profile_toml_roundtrip() {
+        let profile = test_profile();
+        let toml = profile.to_toml().unwrap();
+        assert_eq!(ObsProfile::from_toml(&toml).unwrap(), profile);
+
+        let minimal = ObsProfile::new("Minimal", ObsProfileVideo::new(1280, 720, 30, 1));
+        let toml = minimal.to_toml().unwrap();
+        assert_eq!(ObsProfile::from_toml(&toml).unwrap(), minimal);
+    }
+}
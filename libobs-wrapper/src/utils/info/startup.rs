@@ -1,8 +1,12 @@
+use std::path::PathBuf;
+
 use crate::{
     context::ObsContext,
+    crash_handler::{session, ObsCrashHandler, ObsCrashedSession},
     data::{audio::ObsAudioInfo, video::ObsVideoInfo},
-    logger::{ConsoleLogger, ObsLogger},
-    utils::{initialization::NixDisplay, ObsError, ObsPath, ObsString},
+    logger::{ConsoleLogger, ObsLogger, DEFAULT_LOG_HISTORY_SIZE},
+    runtime::ObsShutdownWatchdog,
+    utils::{initialization::NixDisplay, ObsError, ObsModuleSearchPath, ObsPath, ObsString},
 };
 
 /// Contains information to start a libobs context.
@@ -22,8 +26,18 @@ pub struct StartupInfo {
     pub(crate) obs_audio_info: ObsAudioInfo,
     // Option because logger is taken when creating
     pub(crate) logger: Option<Box<dyn ObsLogger + Sync + Send>>,
+    pub(crate) log_history_size: usize,
+    // Taken on startup, the default crash handler is kept if this is `None`
+    pub(crate) crash_handler: Option<Box<dyn ObsCrashHandler + Sync + Send>>,
     pub(crate) start_glib_loop: bool,
     pub(crate) nix_display: Option<NixDisplay>,
+    pub(crate) locale: String,
+    pub(crate) safe_mode: bool,
+    pub(crate) session_state_path: PathBuf,
+    pub(crate) module_paths: Vec<ObsModuleSearchPath>,
+    pub(crate) disabled_modules: Vec<String>,
+    pub(crate) shutdown_watchdog: Option<ObsShutdownWatchdog>,
+    pub(crate) idle_frame_rate: Option<(u32, u32)>,
 }
 
 impl StartupInfo {
@@ -47,12 +61,118 @@ impl StartupInfo {
         &self.obs_video_info
     }
 
+    /// Sets the audio settings libobs is started with, see `ObsAudioInfoBuilder`.
+    /// Defaults to 44.1 kHz stereo.
+    pub fn set_audio_info(mut self, oai: ObsAudioInfo) -> Self {
+        self.obs_audio_info = oai;
+        self
+    }
+
+    #[cfg_attr(coverage_nightly, coverage(off))]
+    pub fn get_audio_info(&self) -> &ObsAudioInfo {
+        &self.obs_audio_info
+    }
+
     #[cfg_attr(coverage_nightly, coverage(off))]
     pub fn set_logger(mut self, logger: Box<dyn ObsLogger + Sync + Send>) -> Self {
         self.logger = Some(logger);
         self
     }
 
+    /// Sets how many of the last log lines of libobs are kept, defaults to `DEFAULT_LOG_HISTORY_SIZE`.
+    /// The lines are attached to errors like `ObsError::OutputStartFailure`, see `ObsError::log_excerpt`
+    /// and `logger::recent_log_lines`. Set this to 0 to disable the history.
+    pub fn set_log_history_size(mut self, size: usize) -> Self {
+        self.log_history_size = size;
+        self
+    }
+
+    /// Replaces the crash handler that is called if libobs crashes, e.g. with a
+    /// `FileCrashHandler` that writes crash reports to disk. There is only one crash
+    /// handler per process, so it is kept until another context sets a new one.
+    pub fn set_crash_handler(mut self, handler: Box<dyn ObsCrashHandler + Sync + Send>) -> Self {
+        self.crash_handler = Some(handler);
+        self
+    }
+
+    /// Sets the locale libobs is started with (e.g. `de-DE`), defaults to `en-US`.
+    /// The locale is used by modules to translate their properties.
+    pub fn set_locale(mut self, locale: impl Into<String>) -> Self {
+        self.locale = locale.into();
+        self
+    }
+
+    pub fn get_locale(&self) -> &str {
+        &self.locale
+    }
+
+    /// Enables safe mode, which mirrors the safe mode of OBS Studio.
+    ///
+    /// If the previous session crashed, the third-party modules that were loading or active
+    /// at that time are not loaded. They are reported as `ObsModuleIssue::DisabledAfterCrash`
+    /// in the diagnostics of the modules. Nothing is disabled if the previous session shut down cleanly.
+    pub fn safe_mode(mut self) -> Self {
+        self.safe_mode = true;
+        self
+    }
+
+    /// Sets the file used to detect whether the previous session crashed.
+    /// Defaults to a file in the temp directory; set this to a per-application location
+    /// if multiple applications using libobs run on the same machine.
+    pub fn set_session_state_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.session_state_path = path.into();
+        self
+    }
+
+    /// Returns the previous session if it did not shut down cleanly.
+    pub fn previous_crashed_session(&self) -> Option<ObsCrashedSession> {
+        session::read_previous_session(&self.session_state_path)
+    }
+
+    /// Adds another directory modules are loaded from on startup, in addition to the plugin
+    /// paths of the `StartupPaths`. Use this to ship your own or third-party plugins alongside
+    /// your application. Like the startup paths, `%module%` is replaced with the module name.
+    pub fn add_module_path(mut self, bin_path: ObsPath, data_path: ObsPath) -> Self {
+        self.module_paths
+            .push(ObsModuleSearchPath::new(bin_path, data_path));
+        self
+    }
+
+    /// Prevents the module with the given name (e.g. `obs-browser`) from being loaded on startup.
+    pub fn disable_module(mut self, module: impl Into<String>) -> Self {
+        self.disabled_modules.push(module.into());
+        self
+    }
+
+    /// Limits how long dropping the context waits for the OBS thread to shut down.
+    /// Without a watchdog, the drop blocks until the shutdown is done, even if a plugin deadlocks.
+    pub fn set_shutdown_watchdog(mut self, watchdog: ObsShutdownWatchdog) -> Self {
+        self.shutdown_watchdog = Some(watchdog);
+        self
+    }
+
+    pub fn get_shutdown_watchdog(&self) -> Option<ObsShutdownWatchdog> {
+        self.shutdown_watchdog
+    }
+
+    /// Lowers the frame rate of the video to `fps_num / fps_den` (e.g. 1 / 1) while the context has
+    /// no display, output or view, to save GPU time while the application waits in the background.
+    ///
+    /// libobs can't pause rendering, so the video is reset with the lower frame rate instead.
+    /// It is reset back automatically before a display, output or view is created,
+    /// `ObsContext::wake_rendering` does the same on demand, e.g. before taking a screenshot.
+    /// Outputs and views keep the video they were created with, so once the context has one
+    /// the video stays at its real frame rate. Outputs, encoders and views that are created
+    /// without the context must not exist while the video is idle.
+    pub fn set_idle_frame_rate(mut self, fps_num: u32, fps_den: u32) -> Self {
+        self.idle_frame_rate = Some((fps_num, fps_den));
+        self
+    }
+
+    pub fn get_idle_frame_rate(&self) -> Option<(u32, u32)> {
+        self.idle_frame_rate
+    }
+
     #[cfg_attr(coverage_nightly, coverage(off))]
     pub fn set_start_glib_loop(mut self, start: bool) -> Self {
         self.start_glib_loop = start;
@@ -89,8 +209,17 @@ impl Default for StartupInfo {
             obs_video_info: ObsVideoInfo::default(),
             obs_audio_info: ObsAudioInfo::default(),
             logger: Some(Box::new(ConsoleLogger::new())),
+            log_history_size: DEFAULT_LOG_HISTORY_SIZE,
+            crash_handler: None,
             start_glib_loop: true,
             nix_display: None,
+            locale: "en-US".to_string(),
+            safe_mode: false,
+            session_state_path: session::default_session_state_path(),
+            module_paths: Vec::new(),
+            disabled_modules: Vec::new(),
+            shutdown_watchdog: None,
+            idle_frame_rate: None,
         }
     }
 }
@@ -0,0 +1,67 @@
+use std::fmt::{self, Display};
+
+/// Why an object has been reported by `ObsContext::leak_report`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ObsLeakKind {
+    /// The scene is only held by the context and is not shown anywhere
+    /// (not set to a channel, nested in a shown scene or rendered by a display or view).
+    UnusedScene,
+    /// The source is still kept by the wrapper for the given scene, but all its scene items
+    /// have been removed from the scene in OBS, e.g. by a plugin or the raw API.
+    DetachedSource { scene: String },
+    /// The filter is only held by the context and is not applied to any source or scene.
+    UnusedFilter,
+}
+
+/// An object that is kept alive by the wrapper, but is most likely not used anymore.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ObsLeakEntry {
+    pub name: String,
+    pub kind: ObsLeakKind,
+}
+
+/// The result of `ObsContext::leak_report`.
+///
+/// The report is a heuristic to find scene and source lifetime bugs, an object that is
+/// reported here may still be used on purpose, e.g. a scene that is only shown later on.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ObsLeakReport {
+    pub entries: Vec<ObsLeakEntry>,
+}
+
+impl ObsLeakReport {
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl Display for ObsLeakReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.entries.is_empty() {
+            return writeln!(f, "No leaked objects found");
+        }
+
+        writeln!(f, "{} possibly leaked objects:", self.entries.len())?;
+        for entry in &self.entries {
+            match &entry.kind {
+                ObsLeakKind::UnusedScene => writeln!(
+                    f,
+                    "  scene '{}' is only held by the context and not shown",
+                    entry.name
+                )?,
+                ObsLeakKind::DetachedSource { scene } => writeln!(
+                    f,
+                    "  source '{}' is kept for scene '{}', but is no longer in it",
+                    entry.name, scene
+                )?,
+                ObsLeakKind::UnusedFilter => writeln!(
+                    f,
+                    "  filter '{}' is only held by the context and not applied",
+                    entry.name
+                )?,
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,32 @@
+#[cfg(test)]
+mod tests {
+    use super::super::leak_report::{ObsLeakEntry, ObsLeakKind, ObsLeakReport};
+
+    #[test]
+    fn test_leak_report_display() {
+        assert_eq!(
+            ObsLeakReport::default().to_string(),
+            "No leaked objects found\n"
+        );
+
+        let report = ObsLeakReport {
+            entries: vec![
+                ObsLeakEntry {
+                    name: "Old scene".into(),
+                    kind: ObsLeakKind::UnusedScene,
+                },
+                ObsLeakEntry {
+                    name: "Camera".into(),
+                    kind: ObsLeakKind::DetachedSource {
+                        scene: "Main".into(),
+                    },
+                },
+            ],
+        };
+
+        assert_eq!(
+            report.to_string(),
+            "2 possibly leaked objects:\n  scene 'Old scene' is only held by the context and not shown\n  source 'Camera' is kept for scene 'Main', but is no longer in it\n"
+        );
+    }
+}
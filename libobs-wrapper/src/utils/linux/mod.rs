@@ -1,15 +1,77 @@
 //! Contains linux specific bindings to x11 and wayland
 
-use std::{fs, os::raw::c_char};
+use std::{
+    fs,
+    os::raw::{c_char, c_int, c_uint, c_ulong, c_void},
+};
+
+/// Opaque `struct wl_interface`, only used by pointer
+#[repr(C)]
+pub(crate) struct WlInterface {
+    _private: [u8; 0],
+}
 
 extern "C" {
     // X11 functions
     pub(crate) fn XOpenDisplay(display_name: *const c_char) -> *mut std::os::raw::c_void;
     pub(crate) fn XCloseDisplay(display: *mut std::os::raw::c_void) -> i32;
+    pub(crate) fn XCreateSimpleWindow(
+        display: *mut c_void,
+        parent: c_ulong,
+        x: c_int,
+        y: c_int,
+        width: c_uint,
+        height: c_uint,
+        border_width: c_uint,
+        border: c_ulong,
+        background: c_ulong,
+    ) -> c_ulong;
+    pub(crate) fn XDestroyWindow(display: *mut c_void, window: c_ulong) -> c_int;
+    pub(crate) fn XMapWindow(display: *mut c_void, window: c_ulong) -> c_int;
+    pub(crate) fn XUnmapWindow(display: *mut c_void, window: c_ulong) -> c_int;
+    pub(crate) fn XMoveWindow(display: *mut c_void, window: c_ulong, x: c_int, y: c_int) -> c_int;
+    pub(crate) fn XResizeWindow(
+        display: *mut c_void,
+        window: c_ulong,
+        width: c_uint,
+        height: c_uint,
+    ) -> c_int;
+    pub(crate) fn XRaiseWindow(display: *mut c_void, window: c_ulong) -> c_int;
+    pub(crate) fn XLowerWindow(display: *mut c_void, window: c_ulong) -> c_int;
+    pub(crate) fn XFlush(display: *mut c_void) -> c_int;
 
     // Wayland functions
     pub(crate) fn wl_display_connect(name: *const c_char) -> *mut std::os::raw::c_void;
     pub(crate) fn wl_display_disconnect(display: *mut std::os::raw::c_void);
+    pub(crate) fn wl_display_create_queue(display: *mut c_void) -> *mut c_void;
+    pub(crate) fn wl_display_roundtrip_queue(display: *mut c_void, queue: *mut c_void) -> c_int;
+    pub(crate) fn wl_display_flush(display: *mut c_void) -> c_int;
+    pub(crate) fn wl_event_queue_destroy(queue: *mut c_void);
+    pub(crate) fn wl_proxy_create_wrapper(proxy: *mut c_void) -> *mut c_void;
+    pub(crate) fn wl_proxy_wrapper_destroy(wrapper: *mut c_void);
+    pub(crate) fn wl_proxy_set_queue(proxy: *mut c_void, queue: *mut c_void);
+    pub(crate) fn wl_proxy_add_listener(
+        proxy: *mut c_void,
+        implementation: *mut c_void,
+        data: *mut c_void,
+    ) -> c_int;
+    pub(crate) fn wl_proxy_get_version(proxy: *mut c_void) -> u32;
+    pub(crate) fn wl_proxy_destroy(proxy: *mut c_void);
+    pub(crate) fn wl_proxy_marshal_flags(
+        proxy: *mut c_void,
+        opcode: u32,
+        interface: *const WlInterface,
+        version: u32,
+        flags: u32,
+        ...
+    ) -> *mut c_void;
+
+    pub(crate) static wl_registry_interface: WlInterface;
+    pub(crate) static wl_compositor_interface: WlInterface;
+    pub(crate) static wl_subcompositor_interface: WlInterface;
+    pub(crate) static wl_surface_interface: WlInterface;
+    pub(crate) static wl_subsurface_interface: WlInterface;
+    pub(crate) static wl_region_interface: WlInterface;
 }
 #[derive(Debug)]
 pub struct LinuxGlibLoop {
@@ -0,0 +1,148 @@
+//! Contains macOS specific bindings to the Objective-C runtime and libdispatch
+
+use std::{
+    ffi::CStr,
+    os::raw::{c_char, c_int, c_void},
+};
+
+pub(crate) type Id = *mut c_void;
+pub(crate) type Sel = *const c_void;
+pub(crate) type CGFloat = f64;
+
+#[cfg(target_arch = "aarch64")]
+pub(crate) type ObjcBool = bool;
+#[cfg(not(target_arch = "aarch64"))]
+pub(crate) type ObjcBool = i8;
+
+#[cfg(target_arch = "aarch64")]
+pub(crate) const NO: ObjcBool = false;
+#[cfg(not(target_arch = "aarch64"))]
+pub(crate) const NO: ObjcBool = 0;
+
+#[cfg(target_arch = "aarch64")]
+pub(crate) const YES: ObjcBool = true;
+#[cfg(not(target_arch = "aarch64"))]
+pub(crate) const YES: ObjcBool = 1;
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub(crate) struct NSPoint {
+    pub x: CGFloat,
+    pub y: CGFloat,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub(crate) struct NSSize {
+    pub width: CGFloat,
+    pub height: CGFloat,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub(crate) struct NSRect {
+    pub origin: NSPoint,
+    pub size: NSSize,
+}
+
+/// Opaque `struct dispatch_queue_s`, only used by pointer
+#[repr(C)]
+pub(crate) struct DispatchQueue {
+    _private: [u8; 0],
+}
+
+extern "C" {
+    pub(crate) fn objc_getClass(name: *const c_char) -> Id;
+    pub(crate) fn sel_registerName(name: *const c_char) -> Sel;
+    /// Must be cast to the exact signature of the method before calling it, see [`msg_send`].
+    pub(crate) fn objc_msgSend();
+    #[cfg(target_arch = "x86_64")]
+    fn objc_msgSend_stret();
+
+    static _dispatch_main_q: DispatchQueue;
+    fn dispatch_sync_f(
+        queue: *const DispatchQueue,
+        context: *mut c_void,
+        work: unsafe extern "C" fn(*mut c_void),
+    );
+    fn pthread_main_np() -> c_int;
+}
+
+/// Sends an Objective-C message, e.g. `msg_send![(); view, c"setHidden:", YES => ObjcBool]`.
+///
+/// Methods returning structs larger than 16 bytes need `objc_msgSend_stret` on x86_64
+/// (see [`msg_send_rect`]), everything else goes through `objc_msgSend`.
+macro_rules! msg_send {
+    [$ret:ty; $obj:expr, $sel:expr $(, $arg:expr => $ty:ty)* $(,)?] => {{
+        let send: unsafe extern "C" fn(
+            $crate::utils::macos::Id,
+            $crate::utils::macos::Sel
+            $(, $ty)*
+        ) -> $ret = std::mem::transmute(
+            $crate::utils::macos::objc_msgSend as unsafe extern "C" fn(),
+        );
+        send($obj, $crate::utils::macos::sel($sel) $(, $arg)*)
+    }};
+}
+pub(crate) use msg_send;
+
+/// # Safety
+/// The name must be the name of a selector, no checks are done.
+pub(crate) unsafe fn sel(name: &CStr) -> Sel {
+    sel_registerName(name.as_ptr())
+}
+
+/// # Safety
+/// The class must be registered with the Objective-C runtime.
+pub(crate) unsafe fn class(name: &CStr) -> Id {
+    objc_getClass(name.as_ptr())
+}
+
+/// Sends a message without arguments that returns an `NSRect`, e.g. `bounds`.
+///
+/// # Safety
+/// The object must be valid and respond to the selector with an `NSRect`.
+pub(crate) unsafe fn msg_send_rect(obj: Id, name: &CStr) -> NSRect {
+    #[cfg(target_arch = "x86_64")]
+    let send: unsafe extern "C" fn(Id, Sel) -> NSRect =
+        std::mem::transmute(objc_msgSend_stret as unsafe extern "C" fn());
+    #[cfg(not(target_arch = "x86_64"))]
+    let send: unsafe extern "C" fn(Id, Sel) -> NSRect =
+        std::mem::transmute(objc_msgSend as unsafe extern "C" fn());
+
+    send(obj, sel(name))
+}
+
+/// Runs `f` on the main thread, which AppKit requires for every call on views.
+/// Blocks until `f` returned, so the main thread must not wait on the calling thread.
+pub(crate) fn run_on_main<R, F: FnOnce() -> R>(f: F) -> R {
+    unsafe extern "C" fn trampoline<R, F: FnOnce() -> R>(context: *mut c_void) {
+        let state = &mut *(context as *mut (Option<F>, Option<R>));
+        if let Some(f) = state.0.take() {
+            state.1 = Some(f());
+        }
+    }
+
+    let is_main = unsafe {
+        // Safety: Only checks the current thread
+        pthread_main_np() != 0
+    };
+    if is_main {
+        return f();
+    }
+
+    let mut state: (Option<F>, Option<R>) = (Some(f), None);
+    unsafe {
+        // Safety: `dispatch_sync_f` only returns after the trampoline ran, so the state is
+        // still alive while it is accessed.
+        dispatch_sync_f(
+            &_dispatch_main_q,
+            &mut state as *mut (Option<F>, Option<R>) as *mut c_void,
+            trampoline::<R, F>,
+        );
+    }
+
+    state
+        .1
+        .expect("dispatch_sync_f runs the function before returning")
+}
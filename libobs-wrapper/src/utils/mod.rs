@@ -1,4 +1,6 @@
 mod error;
+#[cfg(test)]
+mod error_tests;
 mod info;
 pub(crate) mod initialization;
 mod obs_string;
@@ -7,6 +9,9 @@ mod path;
 #[cfg(target_os = "linux")]
 pub(crate) mod linux;
 
+#[cfg(target_os = "macos")]
+pub(crate) mod macos;
+
 #[cfg(test)]
 mod obs_string_tests;
 
@@ -14,9 +19,23 @@ mod obs_string_tests;
 mod path_tests;
 
 mod modules;
+#[cfg(test)]
+mod modules_tests;
 
 mod calldata;
 
+mod enumerate;
+
+mod leak_report;
+#[cfg(test)]
+mod leak_report_tests;
+
+pub(crate) mod idle;
+
+mod version;
+#[cfg(test)]
+mod version_tests;
+
 use std::{
     collections::HashMap,
     fmt::Debug,
@@ -24,14 +43,24 @@ use std::{
 };
 
 pub use calldata::*;
+pub(crate) use enumerate::{enumerate_encoders, enumerate_outputs, enumerate_services};
+pub use enumerate::{ObsEncoderSummary, ObsOutputSummary, ObsServiceSummary};
 pub use error::*;
 pub use info::*;
 pub use initialization::NixDisplay;
 #[cfg(any(target_os = "linux", doc, feature = "__test_environment"))]
 pub use initialization::PlatformType;
-pub use modules::ObsModules;
+pub use leak_report::*;
+pub(crate) use modules::{
+    find_third_party_modules, loaded_module_names, loaded_third_party_modules,
+};
+pub use modules::{
+    ObsModuleInfo, ObsModuleIssue, ObsModuleLoadReport, ObsModuleLoadStatus, ObsModuleSearchPath,
+    ObsModules,
+};
 pub use obs_string::*;
 pub use path::*;
+pub use version::{ObsCapabilities, ObsSemver, ObsVersionInfo};
 
 pub const ENCODER_HIDE_FLAGS: u32 =
     libobs::OBS_ENCODER_CAP_DEPRECATED | libobs::OBS_ENCODER_CAP_INTERNAL;
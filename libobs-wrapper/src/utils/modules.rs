@@ -1,19 +1,135 @@
 use std::{
-    ffi::{CStr, CString},
-    fmt::Debug,
+    ffi::{c_char, CStr, CString},
+    fmt::{Debug, Display},
+    path::{Path, PathBuf},
 };
 
 use crate::{
-    context::ObsContext, enums::ObsLogLevel, logger::internal_log_global, run_with_obs,
-    runtime::ObsRuntime, unsafe_send::Sendable, utils::StartupPaths,
+    context::ObsContext,
+    enums::ObsLogLevel,
+    logger::{internal_log_global, start_log_capture, stop_log_capture},
+    run_with_obs,
+    runtime::ObsRuntime,
+    unsafe_send::Sendable,
+    utils::{ObsError, ObsPath, ObsString, StartupInfo, StartupPaths},
 };
-use libobs::obs_module_failure_info;
+use libobs::{obs_module_failure_info, obs_module_t};
+
+/// A problem with the module setup that was found before or while loading modules.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ObsModuleIssue {
+    /// The libobs data directory does not exist. libobs will fail to find its effects and locale files.
+    MissingLibobsData(PathBuf),
+    /// The plugin binary directory does not exist, so no module can be loaded.
+    MissingPluginBinaries(PathBuf),
+    /// A module binary was found, but its data directory does not exist.
+    /// This is a common symptom of a broken install and usually makes the module fail to load.
+    MissingModuleData { module: String, path: PathBuf },
+    /// libobs reported that the module failed to load.
+    LoadFailed(String),
+    /// The module was not loaded because safe mode is enabled and the previous session crashed while it was active.
+    DisabledAfterCrash(String),
+}
+
+impl Display for ObsModuleIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ObsModuleIssue::MissingLibobsData(path) => {
+                write!(f, "libobs data directory {} does not exist", path.display())
+            }
+            ObsModuleIssue::MissingPluginBinaries(path) => {
+                write!(f, "plugin bin directory {} does not exist", path.display())
+            }
+            ObsModuleIssue::MissingModuleData { module, path } => write!(
+                f,
+                "data directory {} of module {} does not exist",
+                path.display(),
+                module
+            ),
+            ObsModuleIssue::LoadFailed(module) => write!(f, "module {} failed to load", module),
+            ObsModuleIssue::DisabledAfterCrash(module) => write!(
+                f,
+                "module {} was disabled because the previous session crashed",
+                module
+            ),
+        }
+    }
+}
+
+/// An additional directory modules are loaded from, see `StartupInfo::add_module_path`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ObsModuleSearchPath {
+    bin_path: ObsString,
+    data_path: ObsString,
+}
+
+impl ObsModuleSearchPath {
+    pub fn new(bin_path: ObsPath, data_path: ObsPath) -> Self {
+        Self {
+            bin_path: bin_path.build(),
+            data_path: data_path.build(),
+        }
+    }
+
+    pub fn bin_path(&self) -> &ObsString {
+        &self.bin_path
+    }
+
+    pub fn data_path(&self) -> &ObsString {
+        &self.data_path
+    }
+}
+
+/// A module that is loaded by libobs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ObsModuleInfo {
+    /// The name of the module binary without its extension, e.g. `obs-ffmpeg`
+    pub module: String,
+    /// The display name the module reports, if it has one
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub author: Option<String>,
+    pub binary_path: Option<PathBuf>,
+    pub data_path: Option<PathBuf>,
+}
+
+/// Whether a module was loaded on startup, see [`ObsModules::load_report`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ObsModuleLoadStatus {
+    Loaded(ObsModuleInfo),
+    /// The module was not loaded because it was disabled by default,
+    /// using `StartupInfo::disable_module` or by safe mode.
+    Disabled,
+    /// The module was built for a newer version of libobs than the one that is running.
+    IncompatibleVersion,
+    /// The module failed to load, for example because a dependency of the binary
+    /// (like the Visual C++ runtime) is missing. `reason` is the message libobs logged, if there is one.
+    Failed {
+        reason: Option<String>,
+    },
+    /// The module was found, but libobs did not try to load it.
+    NotLoaded,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ObsModuleLoadReport {
+    /// The name of the module binary without its extension, e.g. `obs-ffmpeg`
+    pub module: String,
+    pub status: ObsModuleLoadStatus,
+}
 
 pub struct ObsModules {
     paths: StartupPaths,
+    module_paths: Vec<ObsModuleSearchPath>,
+    disabled_modules: Vec<String>,
+    /// Every module that was excluded from loading on startup
+    skipped_modules: Vec<String>,
+    /// The modules that failed to load on startup and why
+    failed_modules: Vec<(String, ObsModuleLoadStatus)>,
 
     /// A pointer to the module failure info structure.
     info: Option<Sendable<obs_module_failure_info>>,
+    diagnostics: Vec<ObsModuleIssue>,
     pub(crate) runtime: Option<ObsRuntime>,
 }
 
@@ -21,7 +137,10 @@ impl Debug for ObsModules {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("ObsModules")
             .field("paths", &self.paths)
+            .field("module_paths", &self.module_paths)
+            .field("disabled_modules", &self.disabled_modules)
             .field("info", &"(internal obs_module_failure_info)")
+            .field("diagnostics", &self.diagnostics)
             .finish()
     }
 }
@@ -42,11 +161,226 @@ static SAFE_MODULES: &str = concat!(
     "mac-virtualcam|coreaudio-encoder|aja"
 );
 
+// Modules shipped with OBS that are not part of `SAFE_MODULES`. Every other module is considered third-party.
+static FIRST_PARTY_MODULES: &str = concat!(
+    "obs-browser|obs-text|obs-webrtc|nv-filters|enc-amf|image-source|",
+    "win-capture|win-dshow|win-wasapi|win-mf|mac-virtualcam"
+);
+
+/// Returns true if the module is not shipped with OBS itself.
+pub(crate) fn is_third_party_module(module: &str) -> bool {
+    !SAFE_MODULES
+        .split('|')
+        .chain(FIRST_PARTY_MODULES.split('|'))
+        .any(|m| m == module)
+}
+
 impl ObsModules {
+    /// The paths the modules were loaded from.
+    pub fn paths(&self) -> &StartupPaths {
+        &self.paths
+    }
+
+    /// The resolved directory libobs loads its own data (effects, locale) from.
+    pub fn libobs_data_path(&self) -> PathBuf {
+        PathBuf::from(self.paths.libobs_data_path().to_string())
+    }
+
+    /// The resolved directory the binary of the given module is loaded from.
+    pub fn plugin_bin_path(&self, module: &str) -> PathBuf {
+        resolve_module_path(self.paths.plugin_bin_path(), module)
+    }
+
+    /// The resolved data directory of the given module.
+    pub fn plugin_data_path(&self, module: &str) -> PathBuf {
+        resolve_module_path(self.paths.plugin_data_path(), module)
+    }
+
+    /// Every issue that was found while validating the paths and loading the modules.
+    /// This is empty if everything was set up correctly.
+    pub fn diagnostics(&self) -> &[ObsModuleIssue] {
+        &self.diagnostics
+    }
+
+    /// Checks that the libobs data directory, the plugin bin directory and the data directory
+    /// of every module found in the plugin bin directory exist.
+    ///
+    /// This does not need a running OBS context, so it can be used to check an installation
+    /// (for example from a CLI) before starting up.
+    pub fn validate_paths(paths: &StartupPaths) -> Vec<ObsModuleIssue> {
+        let mut issues = Vec::new();
+
+        let libobs_data = PathBuf::from(paths.libobs_data_path().to_string());
+        if !libobs_data.is_dir() {
+            issues.push(ObsModuleIssue::MissingLibobsData(libobs_data));
+        }
+
+        validate_module_path(
+            paths.plugin_bin_path(),
+            paths.plugin_data_path(),
+            &mut issues,
+        );
+
+        issues
+    }
+
+    /// The additional module paths that were set using `StartupInfo::add_module_path`.
+    pub fn module_paths(&self) -> &[ObsModuleSearchPath] {
+        &self.module_paths
+    }
+
+    /// The modules that were disabled using `StartupInfo::disable_module`.
+    pub fn disabled_modules(&self) -> &[String] {
+        &self.disabled_modules
+    }
+
+    /// Returns every module that is currently loaded, with the metadata it reports.
+    pub fn loaded_modules(&self) -> Result<Vec<ObsModuleInfo>, ObsError> {
+        let runtime = self.runtime()?;
+        run_with_obs!(runtime, move || unsafe {
+            // Safety: We are in the runtime
+            enum_modules()
+                .into_iter()
+                .map(|module| module_info(module))
+                .collect::<Vec<_>>()
+        })
+    }
+
+    /// Loads the module with the given name (e.g. `obs-browser`) from the plugin paths of the
+    /// `StartupPaths` or the additional module paths, after OBS has been started.
+    ///
+    /// Returns the already loaded module if it was loaded before. Note that libobs only calls
+    /// the `post_load` callback of modules loaded on startup.
+    pub fn load_module(&self, module: &str) -> Result<ObsModuleInfo, ObsError> {
+        if let Some(info) = self
+            .loaded_modules()?
+            .into_iter()
+            .find(|info| info.module == module)
+        {
+            return Ok(info);
+        }
+
+        let search_paths =
+            std::iter::once((self.paths.plugin_bin_path(), self.paths.plugin_data_path())).chain(
+                self.module_paths
+                    .iter()
+                    .map(|p| (p.bin_path(), p.data_path())),
+            );
+
+        for (bin_path, data_path) in search_paths {
+            let found = find_modules(bin_path)
+                .map(|modules| modules.iter().any(|m| m == module))
+                .unwrap_or(false);
+
+            if found {
+                return self.load_module_from(
+                    resolve_module_path(bin_path, module).join(module),
+                    resolve_module_path(data_path, module),
+                );
+            }
+        }
+
+        Err(ObsError::ModuleLoadFailure(format!(
+            "Module {} was not found in any module path",
+            module
+        )))
+    }
+
+    /// Loads a module from the given binary and data directory after OBS has been started.
+    /// The extension of the binary can be omitted, the one of the current platform is used then.
+    ///
+    /// Returns the already loaded module if a module with the same binary name was loaded before.
+    pub fn load_module_from<P: AsRef<Path>, D: AsRef<Path>>(
+        &self,
+        bin_path: P,
+        data_path: D,
+    ) -> Result<ObsModuleInfo, ObsError> {
+        let bin_path = bin_path.as_ref();
+        let bin: ObsString = bin_path.to_string_lossy().as_ref().into();
+        let data: ObsString = data_path.as_ref().to_string_lossy().as_ref().into();
+        let display_path = bin_path.display().to_string();
+        let file_stem = bin_path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string());
+
+        let runtime = self.runtime()?;
+        run_with_obs!(runtime, (bin, data), move || unsafe {
+            // Safety: We are in the runtime and both strings are kept in scope
+            let loaded = enum_modules()
+                .into_iter()
+                .find(|module| file_stem.is_some() && module_file_stem(*module) == file_stem);
+            if let Some(module) = loaded {
+                return Ok(module_info(module));
+            }
+
+            let mut module = std::ptr::null_mut();
+            let code = libobs::obs_open_module(&mut module, bin.as_ptr().0, data.as_ptr().0);
+            if code != libobs::MODULE_SUCCESS as i32 || module.is_null() {
+                return Err(ObsError::ModuleLoadFailure(format!(
+                    "{} could not be opened: {}",
+                    display_path,
+                    module_error_message(code)
+                )));
+            }
+
+            if !libobs::obs_init_module(module) {
+                return Err(ObsError::ModuleLoadFailure(format!(
+                    "{} failed to initialize",
+                    display_path
+                )));
+            }
+
+            Ok(module_info(module))
+        })?
+    }
+
+    /// Returns the status of every module that was found in the module paths or is loaded,
+    /// so applications can tell users why a source type is missing.
+    pub fn load_report(&self) -> Result<Vec<ObsModuleLoadReport>, ObsError> {
+        let loaded = self.loaded_modules()?;
+
+        let mut modules = std::iter::once(self.paths.plugin_bin_path())
+            .chain(self.module_paths.iter().map(|p| p.bin_path()))
+            .flat_map(|bin_path| find_modules(bin_path).unwrap_or_default())
+            .chain(loaded.iter().map(|info| info.module.clone()))
+            .chain(self.failed_modules.iter().map(|(module, _)| module.clone()))
+            .collect::<Vec<_>>();
+        modules.sort_unstable();
+        modules.dedup();
+
+        let report = modules
+            .into_iter()
+            .map(|module| {
+                let status = if let Some(info) = loaded.iter().find(|info| info.module == module) {
+                    ObsModuleLoadStatus::Loaded(info.clone())
+                } else if let Some((_, status)) =
+                    self.failed_modules.iter().find(|(m, _)| *m == module)
+                {
+                    status.clone()
+                } else if self.skipped_modules.contains(&module) {
+                    ObsModuleLoadStatus::Disabled
+                } else {
+                    ObsModuleLoadStatus::NotLoaded
+                };
+
+                ObsModuleLoadReport { module, status }
+            })
+            .collect();
+
+        Ok(report)
+    }
+
+    fn runtime(&self) -> Result<ObsRuntime, ObsError> {
+        self.runtime.clone().ok_or_else(|| {
+            ObsError::InvalidOperation("The modules have not been loaded yet".into())
+        })
+    }
+
     /// Safety: ALWAYS CALL THIS IN THE OBS RUNTIME CONTEXT
     #[allow(unknown_lints)]
     #[allow(ensure_obs_call_in_runtime)]
-    pub(crate) unsafe fn add_paths(paths: &StartupPaths) -> Self {
+    pub(crate) unsafe fn add_paths(info: &StartupInfo, disabled_after_crash: &[String]) -> Self {
+        let paths = &info.startup_paths;
         internal_log_global(
             ObsLogLevel::Info,
             "[libobs-wrapper]: Adding module paths:".to_string(),
@@ -73,11 +407,44 @@ impl ObsModules {
             ),
         );
 
+        let mut diagnostics = Self::validate_paths(paths);
+        for module_path in &info.module_paths {
+            internal_log_global(
+                ObsLogLevel::Info,
+                format!(
+                    "[libobs-wrapper]:   additional module path: {} (data: {})",
+                    module_path.bin_path(),
+                    module_path.data_path()
+                ),
+            );
+            validate_module_path(
+                module_path.bin_path(),
+                module_path.data_path(),
+                &mut diagnostics,
+            );
+        }
+        diagnostics.extend(
+            disabled_after_crash
+                .iter()
+                .cloned()
+                .map(ObsModuleIssue::DisabledAfterCrash),
+        );
+
+        for issue in &diagnostics {
+            internal_log_global(ObsLogLevel::Warning, format!("[libobs-wrapper]: {}", issue));
+        }
+
         libobs::obs_add_data_path(paths.libobs_data_path().as_ptr().0);
         libobs::obs_add_module_path(
             paths.plugin_bin_path().as_ptr().0,
             paths.plugin_data_path().as_ptr().0,
         );
+        for module_path in &info.module_paths {
+            libobs::obs_add_module_path(
+                module_path.bin_path().as_ptr().0,
+                module_path.data_path().as_ptr().0,
+            );
+        }
 
         #[allow(unused_mut)]
         let mut disabled_plugins = vec!["obs-websocket", "frontend-tools"];
@@ -87,6 +454,11 @@ impl ObsModules {
             disabled_plugins.extend(&["decklink-output-ui", "decklink-captions", "decklink"]);
         }
 
+        // Third-party modules are never part of the safe modules, so they only have to be disabled explicitly
+        disabled_plugins.extend(disabled_after_crash.iter().map(String::as_str));
+        disabled_plugins.extend(info.disabled_modules.iter().map(String::as_str));
+        let skipped_modules = disabled_plugins.iter().map(|m| m.to_string()).collect();
+
         let version = ObsContext::get_version_global().unwrap_or_default();
         let version_parts: Vec<&str> = version.split('.').collect();
         let major = version_parts
@@ -141,7 +513,12 @@ impl ObsModules {
 
         Self {
             paths: paths.clone(),
+            module_paths: info.module_paths.clone(),
+            disabled_modules: info.disabled_modules.clone(),
+            skipped_modules,
+            failed_modules: Vec::new(),
             info: None,
+            diagnostics,
             runtime: None,
         }
     }
@@ -155,7 +532,10 @@ impl ObsModules {
             ObsLogLevel::Info,
             "---------------------------------".to_string(),
         );
+        // The failure info only contains the names of the modules, the reason is only logged
+        start_log_capture();
         libobs::obs_load_all_modules2(&mut failure_info);
+        let load_logs = stop_log_capture();
         internal_log_global(
             ObsLogLevel::Info,
             "---------------------------------".to_string(),
@@ -169,6 +549,25 @@ impl ObsModules {
         self.info = Some(Sendable(failure_info));
 
         self.log_if_failed();
+        self.collect_failed_modules(&load_logs);
+    }
+
+    #[allow(unknown_lints)]
+    #[allow(ensure_obs_call_in_runtime)]
+    unsafe fn collect_failed_modules(&mut self, load_logs: &[(ObsLogLevel, String)]) {
+        let Some(info) = self.info.as_ref() else {
+            return;
+        };
+
+        let info = &info.0;
+        for i in 0..info.count {
+            let module = info.failed_modules.add(i);
+            let plugin_name = CStr::from_ptr(*module).to_string_lossy().to_string();
+            self.failed_modules
+                .push((plugin_name.clone(), failure_status(&plugin_name, load_logs)));
+            self.diagnostics
+                .push(ObsModuleIssue::LoadFailed(plugin_name));
+        }
     }
 
     #[cfg_attr(coverage_nightly, coverage(off))]
@@ -194,6 +593,192 @@ impl ObsModules {
     }
 }
 
+/// Checks that the given plugin bin directory and the data directory of every module in it exist.
+fn validate_module_path(
+    bin_path: &ObsString,
+    data_path: &ObsString,
+    issues: &mut Vec<ObsModuleIssue>,
+) {
+    let modules = match find_modules(bin_path) {
+        Ok(modules) => modules,
+        Err(bin_dir) => {
+            issues.push(ObsModuleIssue::MissingPluginBinaries(bin_dir));
+            return;
+        }
+    };
+
+    for module in modules {
+        let module_data_path = resolve_module_path(data_path, &module);
+        if !module_data_path.is_dir() {
+            issues.push(ObsModuleIssue::MissingModuleData {
+                module,
+                path: module_data_path,
+            });
+        }
+    }
+}
+
+/// Returns the names of all modules found in the given plugin bin directory,
+/// or the bin directory if it can not be read.
+fn find_modules(bin_path: &ObsString) -> Result<Vec<String>, PathBuf> {
+    let bin_path = bin_path.to_string();
+    // If the module name is part of the bin path, every module has its own directory
+    let (bin_dir, per_module_dir) = match bin_path.split_once("%module%") {
+        Some((dir, _)) => (PathBuf::from(dir), true),
+        None => (PathBuf::from(&bin_path), false),
+    };
+
+    let entries = std::fs::read_dir(&bin_dir).map_err(|_| bin_dir)?;
+
+    let mut modules = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| module_name_from_path(&entry.path(), per_module_dir))
+        .collect::<Vec<_>>();
+    modules.sort_unstable();
+    modules.dedup();
+
+    Ok(modules)
+}
+
+/// Returns the third-party modules found in the plugin bin directory and the additional module paths.
+pub(crate) fn find_third_party_modules(info: &StartupInfo) -> Vec<String> {
+    std::iter::once(info.startup_paths.plugin_bin_path())
+        .chain(info.module_paths.iter().map(|p| p.bin_path()))
+        .flat_map(|bin_path| find_modules(bin_path).unwrap_or_default())
+        .filter(|m| is_third_party_module(m) && !info.disabled_modules.contains(m))
+        .collect()
+}
+
+/// Returns the names of all modules that are currently loaded.
+///
+/// Safety: ALWAYS CALL THIS IN THE OBS RUNTIME CONTEXT
+#[allow(unknown_lints)]
+#[allow(ensure_obs_call_in_runtime)]
+pub(crate) unsafe fn loaded_module_names() -> Vec<String> {
+    enum_modules()
+        .into_iter()
+        .filter_map(|module| module_file_stem(module))
+        .collect()
+}
+
+/// Returns the third-party modules that are currently loaded.
+///
+/// Safety: ALWAYS CALL THIS IN THE OBS RUNTIME CONTEXT
+#[allow(unknown_lints)]
+#[allow(ensure_obs_call_in_runtime)]
+pub(crate) unsafe fn loaded_third_party_modules() -> Vec<String> {
+    loaded_module_names()
+        .into_iter()
+        .filter(|name| is_third_party_module(name))
+        .collect()
+}
+
+/// Safety: ALWAYS CALL THIS IN THE OBS RUNTIME CONTEXT
+#[allow(unknown_lints)]
+#[allow(ensure_obs_call_in_runtime)]
+unsafe fn enum_modules() -> Vec<*mut obs_module_t> {
+    unsafe extern "C" fn enum_module(param: *mut std::os::raw::c_void, module: *mut obs_module_t) {
+        let modules = &mut *(param as *mut Vec<*mut obs_module_t>);
+        modules.push(module);
+    }
+
+    let mut modules = Vec::<*mut obs_module_t>::new();
+    libobs::obs_enum_modules(Some(enum_module), &mut modules as *mut _ as *mut _);
+
+    modules
+}
+
+/// Returns the name of the module binary without its extension.
+///
+/// Safety: The module must be valid and this must be called in the OBS runtime
+#[allow(unknown_lints)]
+#[allow(ensure_obs_call_in_runtime)]
+unsafe fn module_file_stem(module: *mut obs_module_t) -> Option<String> {
+    let file_name = optional_string(libobs::obs_get_module_file_name(module))?;
+    Path::new(&file_name)
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+}
+
+/// Safety: The module must be valid and this must be called in the OBS runtime
+#[allow(unknown_lints)]
+#[allow(ensure_obs_call_in_runtime)]
+unsafe fn module_info(module: *mut obs_module_t) -> ObsModuleInfo {
+    ObsModuleInfo {
+        module: module_file_stem(module).unwrap_or_default(),
+        name: optional_string(libobs::obs_get_module_name(module)),
+        description: optional_string(libobs::obs_get_module_description(module)),
+        author: optional_string(libobs::obs_get_module_author(module)),
+        binary_path: optional_string(libobs::obs_get_module_binary_path(module)).map(PathBuf::from),
+        data_path: optional_string(libobs::obs_get_module_data_path(module)).map(PathBuf::from),
+    }
+}
+
+unsafe fn optional_string(ptr: *const c_char) -> Option<String> {
+    if ptr.is_null() {
+        return None;
+    }
+
+    Some(CStr::from_ptr(ptr).to_string_lossy().to_string())
+}
+
+/// Finds out why the given module failed to load, using the messages libobs logged while loading it.
+pub(super) fn failure_status(
+    module: &str,
+    load_logs: &[(ObsLogLevel, String)],
+) -> ObsModuleLoadStatus {
+    let mut messages = load_logs
+        .iter()
+        .filter(|(level, msg)| {
+            matches!(level, ObsLogLevel::Error | ObsLogLevel::Warning) && msg.contains(module)
+        })
+        .map(|(_, msg)| msg);
+
+    if messages
+        .clone()
+        .any(|msg| msg.contains("newer libobs") || msg.contains("incompatible"))
+    {
+        return ObsModuleLoadStatus::IncompatibleVersion;
+    }
+
+    // The first message is the most specific one, e.g. the error of the OS loader
+    ObsModuleLoadStatus::Failed {
+        reason: messages.next().cloned(),
+    }
+}
+
+pub(super) fn module_error_message(code: i32) -> &'static str {
+    match code {
+        libobs::MODULE_FILE_NOT_FOUND => "the file could not be opened or is missing dependencies",
+        libobs::MODULE_MISSING_EXPORTS => "the module is missing required exports",
+        libobs::MODULE_INCOMPATIBLE_VER => {
+            "the module was built for an incompatible version of OBS"
+        }
+        libobs::MODULE_HARDCODED_SKIP => "the module is disabled",
+        _ => "unknown error",
+    }
+}
+
+fn resolve_module_path(path: &ObsString, module: &str) -> PathBuf {
+    PathBuf::from(path.to_string().replace("%module%", module))
+}
+
+fn module_name_from_path(path: &Path, per_module_dir: bool) -> Option<String> {
+    let file_name = path.file_name()?.to_str()?;
+    if per_module_dir && path.is_dir() {
+        // macOS bundles are called `<module>.plugin`
+        let name = file_name.strip_suffix(".plugin").unwrap_or(file_name);
+        return Some(name.to_string());
+    }
+
+    let extension = path.extension()?.to_str()?;
+    if !matches!(extension, "dll" | "so" | "dylib") {
+        return None;
+    }
+
+    path.file_stem()?.to_str().map(|s| s.to_string())
+}
+
 impl Drop for ObsModules {
     fn drop(&mut self) {
         log::trace!("Dropping ObsModules and removing module paths...");
@@ -0,0 +1,98 @@
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::super::modules::{
+        failure_status, module_error_message, ObsModuleIssue, ObsModuleLoadStatus, ObsModules,
+    };
+    use super::super::{ObsPath, StartupPaths};
+    use crate::enums::ObsLogLevel;
+
+    #[test]
+    fn test_validate_paths_reports_missing_module_data() {
+        let root = std::env::temp_dir().join(format!("libobs-modules-test-{}", std::process::id()));
+        let bin = root.join("obs-plugins");
+        let data = root.join("data");
+        fs::create_dir_all(&bin).unwrap();
+        fs::create_dir_all(data.join("libobs")).unwrap();
+        fs::create_dir_all(data.join("obs-plugins/obs-ffmpeg")).unwrap();
+        fs::write(bin.join("obs-ffmpeg.dll"), b"").unwrap();
+        fs::write(bin.join("obs-x264.dll"), b"").unwrap();
+        fs::write(bin.join("readme.txt"), b"").unwrap();
+
+        let paths = StartupPaths::new(
+            ObsPath::new(data.join("libobs").to_str().unwrap()),
+            ObsPath::new(bin.to_str().unwrap()),
+            ObsPath::new(data.join("obs-plugins/%module%").to_str().unwrap()),
+        );
+
+        let issues = ObsModules::validate_paths(&paths);
+        fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(issues.len(), 1);
+        assert!(matches!(
+            &issues[0],
+            ObsModuleIssue::MissingModuleData { module, .. } if module == "obs-x264"
+        ));
+    }
+
+    #[test]
+    fn test_validate_paths_missing_directories() {
+        let paths = StartupPaths::new(
+            ObsPath::new("/this/path/does/not/exist/libobs"),
+            ObsPath::new("/this/path/does/not/exist/obs-plugins"),
+            ObsPath::new("/this/path/does/not/exist/data/%module%"),
+        );
+
+        let issues = ObsModules::validate_paths(&paths);
+        assert!(matches!(issues[0], ObsModuleIssue::MissingLibobsData(_)));
+        assert!(matches!(
+            issues[1],
+            ObsModuleIssue::MissingPluginBinaries(_)
+        ));
+    }
+
+    #[test]
+    fn test_module_error_message() {
+        assert_eq!(
+            module_error_message(libobs::MODULE_INCOMPATIBLE_VER),
+            "the module was built for an incompatible version of OBS"
+        );
+        assert_eq!(module_error_message(-100), "unknown error");
+    }
+
+    #[test]
+    fn test_failure_status_from_logs() {
+        let logs = vec![
+            (ObsLogLevel::Info, "Loading module: obs-foo.so".to_string()),
+            (
+                ObsLogLevel::Warning,
+                "LoadLibrary failed for 'obs-foo.dll': The specified module could not be found. (126)"
+                    .to_string(),
+            ),
+            (ObsLogLevel::Warning, "Module 'obs-foo.dll' not loaded".to_string()),
+            (
+                ObsLogLevel::Warning,
+                "Module 'obs-bar.dll' compiled with newer libobs 33.0".to_string(),
+            ),
+        ];
+
+        assert_eq!(
+            failure_status("obs-foo", &logs),
+            ObsModuleLoadStatus::Failed {
+                reason: Some(
+                    "LoadLibrary failed for 'obs-foo.dll': The specified module could not be found. (126)"
+                        .to_string()
+                )
+            }
+        );
+        assert_eq!(
+            failure_status("obs-bar", &logs),
+            ObsModuleLoadStatus::IncompatibleVersion
+        );
+        assert_eq!(
+            failure_status("obs-baz", &logs),
+            ObsModuleLoadStatus::Failed { reason: None }
+        );
+    }
+}
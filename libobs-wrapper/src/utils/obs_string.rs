@@ -4,12 +4,21 @@
 //! The core type `ObsString` wraps C-compatible strings in a memory-safe way,
 //! ensuring proper lifetime management and UTF-8 validation.
 
-use std::ffi::CString;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::ffi::{CStr, CString};
 use std::fmt;
 use std::os::raw::c_char;
+use std::sync::{Arc, Mutex};
+
+use lazy_static::lazy_static;
 
 use crate::unsafe_send::Sendable;
 
+lazy_static! {
+    static ref INTERNED: Mutex<HashMap<Box<str>, Arc<CStr>>> = Mutex::new(HashMap::new());
+}
+
 /// String wrapper for OBS function calls.
 ///
 /// `ObsString` provides safe interaction with OBS C API functions that require
@@ -36,10 +45,21 @@ use crate::unsafe_send::Sendable;
 ///     // Pass ptr.0 to OBS functions
 /// }
 /// ```
-#[derive(Clone, Default, Debug, PartialEq, Eq, PartialOrd, Ord)]
+///
+/// Strings that are known at compile time, like the ids of source types or the names of
+/// signals, can be created without allocating with [`ObsString::from_static`] or the
+/// [`obs_string!`](crate::obs_string) macro. Ids that are only known at runtime but used over
+/// and over again can be shared with [`ObsString::interned`].
+#[derive(Clone)]
 pub struct ObsString {
-    /// The underlying C string representation
-    c_string: CString,
+    c_string: ObsStringInner,
+}
+
+#[derive(Clone)]
+enum ObsStringInner {
+    Owned(CString),
+    Static(&'static CStr),
+    Interned(Arc<CStr>),
 }
 
 impl ObsString {
@@ -57,8 +77,72 @@ impl ObsString {
     /// ```
     pub fn new<S: AsRef<str>>(s: S) -> Self {
         let s = s.as_ref().replace("\0", "");
+        Self::from_c_string(CString::new(s).unwrap())
+    }
+
+    fn from_c_string(c_string: CString) -> Self {
+        Self {
+            c_string: ObsStringInner::Owned(c_string),
+        }
+    }
+
+    /// Creates an `ObsString` that borrows a static C string, without allocating or copying.
+    /// Can be used in constants.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use libobs_wrapper::utils::ObsString;
+    ///
+    /// const SOURCE_ID: ObsString = ObsString::from_static(c"color_source");
+    /// assert_eq!(SOURCE_ID, "color_source");
+    /// ```
+    pub const fn from_static(s: &'static CStr) -> Self {
         Self {
-            c_string: CString::new(s).unwrap(),
+            c_string: ObsStringInner::Static(s),
+        }
+    }
+
+    /// Returns the interned `ObsString` of the given string. The string is only allocated
+    /// the first time, afterwards every call and every clone shares the same allocation.
+    ///
+    /// Interned strings are never freed, so only intern a bounded set of strings like ids.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use libobs_wrapper::utils::ObsString;
+    ///
+    /// let a = ObsString::interned("ffmpeg_muxer");
+    /// let b = ObsString::interned("ffmpeg_muxer");
+    /// assert_eq!(a.as_ptr().0, b.as_ptr().0);
+    /// ```
+    pub fn interned<S: AsRef<str>>(s: S) -> Self {
+        let s = s.as_ref();
+        let mut interned = INTERNED
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        let c_string = match interned.get(s) {
+            Some(c_string) => c_string.clone(),
+            None => {
+                let c_string: Arc<CStr> = CString::new(s.replace("\0", "")).unwrap().into();
+                interned.insert(s.into(), c_string.clone());
+                c_string
+            }
+        };
+
+        Self {
+            c_string: ObsStringInner::Interned(c_string),
+        }
+    }
+
+    /// Returns the underlying C string.
+    pub fn as_c_str(&self) -> &CStr {
+        match &self.c_string {
+            ObsStringInner::Owned(c_string) => c_string,
+            ObsStringInner::Static(c_string) => c_string,
+            ObsStringInner::Interned(c_string) => c_string,
         }
     }
 
@@ -77,9 +161,66 @@ impl ObsString {
     /// // Use ptr.0 in OBS API calls
     /// ```
     pub fn as_ptr(&self) -> Sendable<*const c_char> {
-        Sendable(self.c_string.as_ptr())
+        Sendable(self.as_c_str().as_ptr())
+    }
+}
+
+/// Creates an [`ObsString`] from a string literal at compile time, without allocating.
+///
+/// # Examples
+///
+/// ```
+/// use libobs_wrapper::{obs_string, utils::ObsString};
+///
+/// let id: ObsString = obs_string!("ffmpeg_muxer");
+/// assert_eq!(id, "ffmpeg_muxer");
+/// ```
+#[macro_export]
+macro_rules! obs_string {
+    ($($s:tt)+) => {
+        $crate::utils::ObsString::from_static(
+            const {
+                match std::ffi::CStr::from_bytes_with_nul(concat!($($s)+, "\0").as_bytes()) {
+                    Ok(s) => s,
+                    Err(_) => panic!("ObsString literals must not contain NUL bytes"),
+                }
+            },
+        )
+    };
+}
+
+impl Default for ObsString {
+    fn default() -> Self {
+        Self::from_static(c"")
+    }
+}
+
+impl fmt::Debug for ObsString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ObsString").field(&self.as_c_str()).finish()
     }
 }
+
+impl PartialEq for ObsString {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_c_str() == other.as_c_str()
+    }
+}
+
+impl Eq for ObsString {}
+
+impl PartialOrd for ObsString {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ObsString {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_c_str().cmp(other.as_c_str())
+    }
+}
+
 impl fmt::Display for ObsString {
     /// Converts the `ObsString` back to a Rust `String` for display.
     ///
@@ -92,7 +233,7 @@ impl fmt::Display for ObsString {
     /// assert_eq!(format!("{}", obs_string), "Hello");
     /// ```
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.c_string.to_string_lossy())
+        write!(f, "{}", self.as_c_str().to_string_lossy())
     }
 }
 
@@ -108,9 +249,7 @@ impl From<&str> for ObsString {
     /// ```
     fn from(value: &str) -> Self {
         let value = value.replace("\0", "");
-        Self {
-            c_string: CString::new(value).unwrap(),
-        }
+        Self::from_c_string(CString::new(value).unwrap())
     }
 }
 
@@ -129,9 +268,7 @@ impl From<Vec<u8>> for ObsString {
     /// ```
     fn from(mut value: Vec<u8>) -> Self {
         value.retain(|&c| c != 0);
-        Self {
-            c_string: CString::new(value).unwrap(),
-        }
+        Self::from_c_string(CString::new(value).unwrap())
     }
 }
 
@@ -148,9 +285,7 @@ impl From<String> for ObsString {
     /// ```
     fn from(value: String) -> Self {
         let value = value.replace("\0", "");
-        Self {
-            c_string: CString::new(value).unwrap(),
-        }
+        Self::from_c_string(CString::new(value).unwrap())
     }
 }
 
@@ -166,6 +301,6 @@ impl PartialEq<&str> for ObsString {
     /// assert_eq!(obs_string, "Hello");
     /// ```
     fn eq(&self, other: &&str) -> bool {
-        self.c_string.to_str() == Ok(*other)
+        self.as_c_str().to_str() == Ok(*other)
     }
 }
@@ -113,4 +113,38 @@ mod tests {
         let obs_string = ObsString::new("!@#$%^&*()");
         assert_eq!(format!("{}", obs_string), "!@#$%^&*()");
     }
+
+    #[test]
+    fn test_obs_string_from_static() {
+        const ID: ObsString = ObsString::from_static(c"static_id");
+        assert_eq!(ID, "static_id");
+        assert_eq!(ID.as_ptr().0, c"static_id".as_ptr());
+    }
+
+    #[test]
+    fn test_obs_string_macro() {
+        let obs_string = crate::obs_string!("macro_id");
+        assert_eq!(obs_string, ObsString::new("macro_id"));
+
+        let obs_string = crate::obs_string!(stringify!(field_name));
+        assert_eq!(format!("{}", obs_string), "field_name");
+    }
+
+    #[test]
+    fn test_obs_string_interned_shares_allocation() {
+        let obs_string1 = ObsString::interned("interned_id");
+        let obs_string2 = ObsString::interned(String::from("interned_id"));
+        assert_eq!(obs_string1.as_ptr().0, obs_string2.as_ptr().0);
+        assert_eq!(obs_string1.clone().as_ptr().0, obs_string1.as_ptr().0);
+        assert_eq!(obs_string1, ObsString::new("interned_id"));
+    }
+
+    #[test]
+    fn test_obs_string_ord_across_kinds() {
+        let owned = ObsString::new("bbb");
+        let borrowed = ObsString::from_static(c"aaa");
+        let interned = ObsString::interned("ccc");
+        assert!(borrowed < owned);
+        assert!(owned < interned);
+    }
 }
@@ -3,6 +3,8 @@ use std::{
     path::{Path, PathBuf},
 };
 
+use chrono::{DateTime, Local};
+
 use super::ObsString;
 
 /// Builds into an `ObsString` that represents a path used
@@ -48,6 +50,100 @@ impl ObsPath {
         obs_path.push(path_str)
     }
 
+    /// Creates a new `ObsPath` with `path_str` appended
+    /// to the home directory of the current user.
+    ///
+    /// Returns `None` if the home directory is unknown.
+    pub fn from_home(path_str: &str) -> Option<Self> {
+        let path = home_dir()?;
+        Some(Self { path }.push(path_str))
+    }
+
+    /// Creates a new `ObsPath` with `path_str` appended
+    /// to the data directory of the platform, that is
+    /// `%APPDATA%` on Windows, `~/Library/Application Support`
+    /// on macOS and `$XDG_DATA_HOME` (or `~/.local/share`) on Linux.
+    ///
+    /// Returns `None` if the data directory is unknown.
+    pub fn from_data_dir(path_str: &str) -> Option<Self> {
+        let path = data_dir()?;
+        Some(Self { path }.push(path_str))
+    }
+
+    /// Replaces a leading `~` with the home directory
+    /// of the current user. The path is left as is if
+    /// it doesn't start with `~` or the home directory
+    /// is unknown.
+    pub fn expand_home(self) -> Self {
+        let Ok(rest) = self.path.strip_prefix("~") else {
+            return self;
+        };
+
+        match home_dir() {
+            Some(home) => Self {
+                path: home.join(rest),
+            },
+            None => self,
+        }
+    }
+
+    /// Replaces the date and time placeholders in the
+    /// file name with the current local time, see
+    /// `ObsPath::expand_template_at`.
+    pub fn expand_template(self) -> Self {
+        self.expand_template_at(&Local::now())
+    }
+
+    /// Replaces the date and time placeholders in the
+    /// file name with the given time. The placeholders
+    /// used by OBS Studio and the replay buffer are
+    /// supported (`%CCYY`, `%YY`, `%MM`, `%DD`, `%hh`,
+    /// `%mm`, `%ss`), as well as the common strftime
+    /// specifiers like `%Y` or `%H`. `%%` is a literal
+    /// percent sign, unknown placeholders are kept.
+    pub fn expand_template_at(mut self, time: &DateTime<Local>) -> Self {
+        let Some(file_name) = self.path.file_name().and_then(|name| name.to_str()) else {
+            return self;
+        };
+
+        let file_name = format_template(file_name, time);
+        self.path.set_file_name(file_name);
+        self
+    }
+
+    /// Returns a path that doesn't exist yet, so
+    /// existing recordings are not overwritten. If the
+    /// path already exists, a counter is appended to
+    /// the file name, e.g. `recording (2).mp4`.
+    pub fn next_available(self) -> Self {
+        if !self.path.exists() {
+            return self;
+        }
+
+        let stem = self
+            .path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let extension = self
+            .path
+            .extension()
+            .map(|extension| format!(".{}", extension.to_string_lossy()))
+            .unwrap_or_default();
+
+        let mut counter = 2;
+        loop {
+            let candidate = self
+                .path
+                .with_file_name(format!("{} ({}){}", stem, counter, extension));
+            if !candidate.exists() {
+                return Self { path: candidate };
+            }
+
+            counter += 1;
+        }
+    }
+
     /// Modifies the path to point to the path
     /// `path_str` appended to the current path which
     /// `ObsPath` is pointing to.
@@ -104,3 +200,76 @@ impl From<ObsPath> for PathBuf {
         val.path
     }
 }
+
+fn home_dir() -> Option<PathBuf> {
+    #[cfg(target_os = "windows")]
+    let home = env::var_os("USERPROFILE");
+    #[cfg(not(target_os = "windows"))]
+    let home = env::var_os("HOME");
+
+    home.filter(|home| !home.is_empty()).map(PathBuf::from)
+}
+
+fn data_dir() -> Option<PathBuf> {
+    #[cfg(target_os = "windows")]
+    return env::var_os("APPDATA")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from);
+
+    #[cfg(target_os = "macos")]
+    return home_dir().map(|home| home.join("Library/Application Support"));
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    return env::var_os("XDG_DATA_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| home_dir().map(|home| home.join(".local/share")));
+}
+
+/// Replaces the placeholders of a file name template, see `ObsPath::expand_template_at`.
+pub(crate) fn format_template(template: &str, time: &DateTime<Local>) -> String {
+    // Longest placeholders first, so `%MM` is not read as `%M`
+    const OBS_PLACEHOLDERS: [(&str, &str); 7] = [
+        ("CCYY", "%Y"),
+        ("YY", "%y"),
+        ("MM", "%m"),
+        ("DD", "%d"),
+        ("hh", "%H"),
+        ("mm", "%M"),
+        ("ss", "%S"),
+    ];
+    const STRFTIME_SPECIFIERS: &str = "aAbBdHIjmMpSyYzZ";
+
+    let mut result = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(idx) = rest.find('%') {
+        result.push_str(&rest[..idx]);
+        rest = &rest[idx + 1..];
+
+        if let Some(after) = rest.strip_prefix('%') {
+            result.push('%');
+            rest = after;
+            continue;
+        }
+
+        if let Some((placeholder, format)) = OBS_PLACEHOLDERS
+            .iter()
+            .find(|(placeholder, _)| rest.starts_with(placeholder))
+        {
+            result.push_str(&time.format(format).to_string());
+            rest = &rest[placeholder.len()..];
+            continue;
+        }
+
+        match rest.chars().next() {
+            Some(c) if STRFTIME_SPECIFIERS.contains(c) => {
+                result.push_str(&time.format(&format!("%{}", c)).to_string());
+                rest = &rest[c.len_utf8()..];
+            }
+            _ => result.push('%'),
+        }
+    }
+
+    result.push_str(rest);
+    result
+}
@@ -1,6 +1,8 @@
 #[cfg(test)]
 mod tests {
-    use super::super::{ObsPath, ObsString};
+    use chrono::{Local, TimeZone};
+
+    use super::super::{path::format_template, ObsPath, ObsString};
 
     #[test]
     fn test_obs_path_new() {
@@ -144,4 +146,50 @@ mod tests {
         let path2 = ObsPath::new("C:/bbb");
         assert!(path1 < path2);
     }
+
+    #[test]
+    fn test_format_template_obs_placeholders() {
+        let time = Local.with_ymd_and_hms(2024, 3, 7, 9, 5, 2).unwrap();
+        assert_eq!(
+            format_template("%CCYY-%MM-%DD %hh-%mm-%ss", &time),
+            "2024-03-07 09-05-02"
+        );
+        assert_eq!(
+            format_template("Replay %YY%MM%DD.mp4", &time),
+            "Replay 240307.mp4"
+        );
+    }
+
+    #[test]
+    fn test_format_template_strftime_and_escapes() {
+        let time = Local.with_ymd_and_hms(2024, 3, 7, 9, 5, 2).unwrap();
+        assert_eq!(format_template("%Y_%H%M%S", &time), "2024_090502");
+        assert_eq!(format_template("100%% %q done", &time), "100% %q done");
+        assert_eq!(format_template("trailing %", &time), "trailing %");
+    }
+
+    #[test]
+    fn test_obs_path_expand_template() {
+        let time = Local.with_ymd_and_hms(2024, 3, 7, 9, 5, 2).unwrap();
+        let path = ObsPath::new("C:/%CCYY/%CCYY-%MM-%DD.mp4").expand_template_at(&time);
+        // Only the file name is expanded
+        assert_eq!(format!("{}", path.build()), "C:/%CCYY/2024-03-07.mp4");
+    }
+
+    #[test]
+    fn test_obs_path_next_available() {
+        let dir = std::env::temp_dir().join(format!("obs_path_test_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let file = dir.join("recording.mp4");
+        let path = ObsPath::new(file.to_str().unwrap());
+        assert_eq!(path.clone().next_available(), path);
+
+        std::fs::write(&file, b"").unwrap();
+        std::fs::write(dir.join("recording (2).mp4"), b"").unwrap();
+        let next = path.next_available().build();
+        assert!(format!("{}", next).ends_with("recording (3).mp4"));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
 }
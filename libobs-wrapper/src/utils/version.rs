@@ -0,0 +1,178 @@
+//! The version of the loaded libobs and the features it supports, so applications can
+//! gate features without probing encoder or output lists themselves.
+
+use std::{
+    ffi::{c_char, CStr},
+    fmt,
+};
+
+use crate::{
+    enums::{ObsEncoderType, OsEnumType},
+    utils::{loaded_module_names, ENCODER_HIDE_FLAGS},
+};
+
+/// The first libobs version that can render and encode HDR
+const HDR_MIN_VERSION: ObsSemver = ObsSemver::new(28, 0, 0);
+
+/// The output type registered by the virtual camera plugins of every platform
+const VIRTUALCAM_OUTPUT_ID: &str = "virtualcam_output";
+
+const WEBSOCKET_MODULE: &str = "obs-websocket";
+
+/// A `major.minor.patch` version of libobs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ObsSemver {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
+
+impl ObsSemver {
+    pub const fn new(major: u32, minor: u32, patch: u32) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+
+    /// Unpacks a version as returned by `obs_get_version`, `(major << 24) | (minor << 16) | patch`.
+    pub fn from_packed(version: u32) -> Self {
+        Self::new(version >> 24, (version >> 16) & 0xFF, version & 0xFFFF)
+    }
+
+    pub fn is_at_least(&self, major: u32, minor: u32, patch: u32) -> bool {
+        *self >= Self::new(major, minor, patch)
+    }
+}
+
+impl fmt::Display for ObsSemver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// Features of the running libobs that depend on its version, the hardware or the loaded plugins.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ObsCapabilities {
+    /// libobs can render HDR and a video encoder that can encode it (HEVC or AV1) is available
+    pub hdr: bool,
+    /// The ids of the available AV1 video encoders, e.g. `ffmpeg_svt_av1`
+    pub av1_encoders: Vec<String>,
+    /// A virtual camera output is registered
+    pub virtualcam: bool,
+    /// The obs-websocket plugin is loaded
+    pub websocket: bool,
+}
+
+impl ObsCapabilities {
+    pub fn has_av1(&self) -> bool {
+        !self.av1_encoders.is_empty()
+    }
+
+    /// Derives the capabilities from the video encoders as `(id, codec)`, the output types
+    /// and the names of the loaded modules.
+    pub(super) fn from_parts(
+        version: ObsSemver,
+        video_encoders: &[(String, String)],
+        output_types: &[String],
+        modules: &[String],
+    ) -> Self {
+        let mut av1_encoders = video_encoders
+            .iter()
+            .filter(|(_, codec)| codec == "av1")
+            .map(|(id, _)| id.clone())
+            .collect::<Vec<_>>();
+        av1_encoders.sort_unstable();
+
+        let hdr_encoder = video_encoders
+            .iter()
+            .any(|(_, codec)| codec == "hevc" || codec == "av1");
+
+        Self {
+            hdr: version >= HDR_MIN_VERSION && hdr_encoder,
+            av1_encoders,
+            virtualcam: output_types.iter().any(|id| id == VIRTUALCAM_OUTPUT_ID),
+            websocket: modules.iter().any(|name| name == WEBSOCKET_MODULE),
+        }
+    }
+}
+
+/// The version of the loaded libobs with its capabilities, see `ObsContext::version`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ObsVersionInfo {
+    /// The full version string, which may contain a suffix like `-rc1`
+    pub version: String,
+    pub semver: ObsSemver,
+    pub capabilities: ObsCapabilities,
+}
+
+impl ObsVersionInfo {
+    /// Reads the version and collects the capabilities.
+    ///
+    /// # Safety
+    /// Must be called in the OBS runtime.
+    pub(crate) unsafe fn collect() -> Self {
+        let version = ptr_to_string(libobs::obs_get_version_string()).unwrap_or_default();
+        let semver = ObsSemver::from_packed(libobs::obs_get_version());
+
+        let video_encoders = enum_types(|idx, id| libobs::obs_enum_encoder_types(idx, id))
+            .into_iter()
+            .filter_map(|(id, ptr)| {
+                let is_hidden = libobs::obs_get_encoder_caps(ptr) & ENCODER_HIDE_FLAGS != 0;
+                let is_video =
+                    libobs::obs_get_encoder_type(ptr) == ObsEncoderType::Video as OsEnumType;
+                if is_hidden || !is_video {
+                    return None;
+                }
+
+                let codec = ptr_to_string(libobs::obs_get_encoder_codec(ptr))?;
+                Some((id, codec))
+            })
+            .collect::<Vec<_>>();
+
+        let output_types = enum_types(|idx, id| libobs::obs_enum_output_types(idx, id))
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect::<Vec<_>>();
+
+        let capabilities = ObsCapabilities::from_parts(
+            semver,
+            &video_encoders,
+            &output_types,
+            &loaded_module_names(),
+        );
+
+        Self {
+            version,
+            semver,
+            capabilities,
+        }
+    }
+}
+
+unsafe fn ptr_to_string(ptr: *const c_char) -> Option<String> {
+    if ptr.is_null() {
+        return None;
+    }
+
+    Some(CStr::from_ptr(ptr).to_string_lossy().into_owned())
+}
+
+/// Collects the ids of an `obs_enum_*_types` function, with the pointers libobs returned.
+unsafe fn enum_types<F>(mut enum_fn: F) -> Vec<(String, *const c_char)>
+where
+    F: FnMut(usize, *mut *const c_char) -> bool,
+{
+    let mut types = Vec::new();
+    let mut idx = 0;
+    let mut ptr: *const c_char = std::ptr::null();
+    while enum_fn(idx, &mut ptr) {
+        idx += 1;
+        if let Some(id) = ptr_to_string(ptr) {
+            types.push((id, ptr));
+        }
+    }
+
+    types
+}
@@ -0,0 +1,45 @@
+#[cfg(test)]
+mod tests {
+    use super::super::version::{ObsCapabilities, ObsSemver};
+
+    #[test]
+    fn test_semver_from_packed() {
+        let version = ObsSemver::from_packed((32 << 24) | (1 << 16) | 2);
+        assert_eq!(version, ObsSemver::new(32, 1, 2));
+        assert_eq!(version.to_string(), "32.1.2");
+
+        assert!(version.is_at_least(32, 0, 4));
+        assert!(version.is_at_least(32, 1, 2));
+        assert!(!version.is_at_least(32, 2, 0));
+        assert!(!version.is_at_least(33, 0, 0));
+    }
+
+    #[test]
+    fn test_capabilities_from_parts() {
+        let encoders = [
+            ("obs_x264".to_string(), "h264".to_string()),
+            ("ffmpeg_svt_av1".to_string(), "av1".to_string()),
+            ("ffmpeg_aom_av1".to_string(), "av1".to_string()),
+        ];
+        let outputs = ["ffmpeg_muxer".to_string(), "virtualcam_output".to_string()];
+        let modules = ["obs-ffmpeg".to_string(), "obs-websocket".to_string()];
+
+        let caps =
+            ObsCapabilities::from_parts(ObsSemver::new(32, 0, 2), &encoders, &outputs, &modules);
+        assert!(caps.hdr);
+        assert!(caps.has_av1());
+        assert_eq!(caps.av1_encoders, ["ffmpeg_aom_av1", "ffmpeg_svt_av1"]);
+        assert!(caps.virtualcam);
+        assert!(caps.websocket);
+
+        let old = ObsCapabilities::from_parts(ObsSemver::new(27, 2, 4), &encoders, &[], &[]);
+        assert!(!old.hdr);
+        assert!(!old.virtualcam);
+        assert!(!old.websocket);
+
+        let h264_only =
+            ObsCapabilities::from_parts(ObsSemver::new(32, 0, 2), &encoders[..1], &[], &[]);
+        assert!(!h264_only.hdr);
+        assert!(!h264_only.has_av1());
+    }
+}
@@ -0,0 +1,308 @@
+//! Views are separate canvases with their own output channels, similar to the
+//! multiple canvases of OBS Studio.
+//!
+//! By default every output renders the global channels set by `ObsSceneRef::add_and_set`.
+//! A view has its own 64 channels and, once added with [`ObsViewRef::add`], its own
+//! video mix with a separate resolution and frame rate. Assign it to an output using
+//! `ObsOutputTrait::set_view` to record or stream a different composition than the one
+//! shown in your preview.
+
+use std::{
+    collections::HashMap,
+    ptr,
+    sync::{Arc, RwLock, RwLockReadGuard, Weak},
+};
+
+use libobs::{obs_view_t, video_output};
+
+use crate::{
+    data::{object::ObsObjectTrait, video::ObsVideoInfo},
+    encoders::video::ObsVideoEncoder,
+    impl_obs_drop, run_with_obs,
+    runtime::ObsRuntime,
+    scenes::ObsSceneRef,
+    sources::ObsSourceTrait,
+    unsafe_send::{Sendable, SmartPointerSendable},
+    utils::{idle::ObsViewLiveness, ObsDropGuard, ObsError},
+};
+
+/// Keeps whatever is assigned to a channel of the view alive.
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+enum ObsViewChannel {
+    Source(Arc<Box<dyn ObsSourceTrait>>),
+    Scene(ObsSceneRef),
+}
+
+#[derive(Debug, Clone)]
+pub struct ObsViewRef {
+    view: SmartPointerSendable<*mut obs_view_t>,
+    video: Arc<RwLock<Option<Sendable<*mut video_output>>>>,
+    /// Video encoders that have been started with the video of this view
+    encoders: Arc<RwLock<Vec<Weak<ObsVideoEncoder>>>>,
+    channels: Arc<RwLock<HashMap<u32, ObsViewChannel>>>,
+    runtime: ObsRuntime,
+}
+
+impl ObsViewRef {
+    pub fn new(runtime: ObsRuntime) -> Result<Self, ObsError> {
+        let view = run_with_obs!(runtime, move || {
+            let view = unsafe {
+                // Safety: We are in the runtime
+                libobs::obs_view_create()
+            };
+
+            if view.is_null() {
+                return Err(ObsError::NullPointer(None).in_function("obs_view_create"));
+            }
+
+            Ok(Sendable(view))
+        })??;
+
+        let drop_guard = Arc::new(_ObsViewDropGuard {
+            view: view.clone(),
+            runtime: runtime.clone(),
+        });
+
+        Ok(Self {
+            view: SmartPointerSendable::new(view.0, drop_guard),
+            video: Arc::new(RwLock::new(None)),
+            encoders: Arc::new(RwLock::new(Vec::new())),
+            channels: Arc::new(RwLock::new(HashMap::new())),
+            runtime,
+        })
+    }
+
+    /// Sets the source that is rendered on the given channel of this view.
+    pub fn set_source<T: ObsSourceTrait + Clone + 'static>(
+        &self,
+        channel: u32,
+        source: &T,
+    ) -> Result<(), ObsError> {
+        let source_ptr = source.as_ptr();
+        self.set_channel(
+            channel,
+            // Safety: The pointer is only passed to libobs in the runtime by `set_channel`
+            Sendable(unsafe { source_ptr.get_ptr_unchecked() }),
+            Some(ObsViewChannel::Source(Arc::new(Box::new(source.clone())))),
+        )
+    }
+
+    /// Sets the scene that is rendered on the given channel of this view.
+    pub fn set_scene(&self, channel: u32, scene: &ObsSceneRef) -> Result<(), ObsError> {
+        let source_ptr = scene.get_scene_source_ptr()?;
+        self.set_channel(
+            channel,
+            source_ptr,
+            Some(ObsViewChannel::Scene(scene.clone())),
+        )
+    }
+
+    /// Removes whatever is rendered on the given channel of this view.
+    pub fn clear_channel(&self, channel: u32) -> Result<(), ObsError> {
+        self.set_channel(channel, Sendable(ptr::null_mut()), None)
+    }
+
+    fn set_channel(
+        &self,
+        channel: u32,
+        source_ptr: Sendable<*mut libobs::obs_source_t>,
+        entry: Option<ObsViewChannel>,
+    ) -> Result<(), ObsError> {
+        if channel >= libobs::MAX_CHANNELS {
+            return Err(ObsError::InvalidOperation(format!(
+                "Channel {} is out of range, there are only {} channels",
+                channel,
+                libobs::MAX_CHANNELS
+            )));
+        }
+
+        let view_ptr = self.view.clone();
+        run_with_obs!(self.runtime, (view_ptr, source_ptr), move |token| unsafe {
+            // Safety: The view is valid because of the smart pointer, the source is kept
+            // alive by the caller and the view holds its own reference to it afterwards.
+            libobs::obs_view_set_source(view_ptr.get_ptr(token), channel, source_ptr.0);
+        })?;
+
+        let mut channels = self
+            .channels
+            .write()
+            .map_err(|_| ObsError::LockError("Failed to acquire write lock on channels".into()))?;
+
+        match entry {
+            Some(entry) => channels.insert(channel, entry),
+            None => channels.remove(&channel),
+        };
+
+        Ok(())
+    }
+
+    /// Creates a video mix for this view with the given video info, so outputs can
+    /// encode this view instead of the main canvas.
+    ///
+    /// The graphics module of the video info is ignored, as it can only be set on startup.
+    pub fn add(&self, ovi: &ObsVideoInfo) -> Result<(), ObsError> {
+        let mut video = self
+            .video
+            .write()
+            .map_err(|_| ObsError::LockError("Failed to acquire write lock on video".into()))?;
+
+        if video.is_some() {
+            return Err(ObsError::InvalidOperation(
+                "The view has already been added, remove it first".into(),
+            ));
+        }
+
+        let view_ptr = self.view.clone();
+        let ovi_ptr = Sendable(ovi.as_ptr());
+        let video_ptr = run_with_obs!(self.runtime, (view_ptr, ovi_ptr), move |token| unsafe {
+            // Safety: The view is valid because of the smart pointer and the video info
+            // is still in scope, libobs copies it into the mix.
+            Sendable(libobs::obs_view_add2(view_ptr.get_ptr(token), ovi_ptr.0))
+        })?;
+
+        if video_ptr.0.is_null() {
+            return Err(ObsError::NullPointer(Some(
+                "Failed to create the video mix of the view".into(),
+            )));
+        }
+
+        video.replace(video_ptr);
+        Ok(())
+    }
+
+    /// Removes the video mix of this view again.
+    ///
+    /// Fails while an output that encodes this view is active. The video encoders of stopped
+    /// outputs are switched back to the main video, so none of them keeps the removed video.
+    pub fn remove(&self) -> Result<(), ObsError> {
+        let mut video = self
+            .video
+            .write()
+            .map_err(|_| ObsError::LockError("Failed to acquire write lock on video".into()))?;
+
+        if video.is_none() {
+            return Ok(());
+        }
+
+        let encoders = {
+            let mut encoders = self.encoders.write().map_err(|_| {
+                ObsError::LockError("Failed to acquire write lock on encoders".into())
+            })?;
+            encoders.retain(|encoder| encoder.strong_count() > 0);
+            encoders
+                .iter()
+                .filter_map(Weak::upgrade)
+                .map(|encoder| encoder.as_ptr())
+                .collect::<Vec<_>>()
+        };
+
+        let view_ptr = self.view.clone();
+        let removed = run_with_obs!(self.runtime, (view_ptr, encoders), move |token| unsafe {
+            // Safety: The view and the encoders are valid because of the smart pointers. The
+            // active check and the removal run in one call, so no output can start in between.
+            if encoders
+                .iter()
+                .any(|encoder| libobs::obs_encoder_active(encoder.get_ptr(token)))
+            {
+                return false;
+            }
+
+            for encoder in encoders.iter() {
+                libobs::obs_encoder_set_video(encoder.get_ptr(token), libobs::obs_get_video());
+            }
+
+            libobs::obs_view_remove(view_ptr.get_ptr(token));
+            true
+        })?;
+
+        if !removed {
+            return Err(ObsError::InvalidOperation(
+                "An output encoding this view is still active, stop it first".into(),
+            ));
+        }
+
+        video.take();
+        self.encoders
+            .write()
+            .map_err(|_| ObsError::LockError("Failed to acquire write lock on encoders".into()))?
+            .clear();
+
+        Ok(())
+    }
+
+    /// Locks the video of this view, it can't be removed while the guard is held.
+    pub(crate) fn video_lock(
+        &self,
+    ) -> Result<RwLockReadGuard<'_, Option<Sendable<*mut video_output>>>, ObsError> {
+        self.video
+            .read()
+            .map_err(|_| ObsError::LockError("Failed to acquire read lock on video".into()))
+    }
+
+    /// Remembers the video encoders that an output started with the video of this view,
+    /// so `Self::remove` can check that none of them is still encoding.
+    pub(crate) fn register_encoders(
+        &self,
+        started: &[Arc<ObsVideoEncoder>],
+    ) -> Result<(), ObsError> {
+        let mut encoders = self
+            .encoders
+            .write()
+            .map_err(|_| ObsError::LockError("Failed to acquire write lock on encoders".into()))?;
+
+        encoders.retain(|encoder| encoder.strong_count() > 0);
+        for encoder in started {
+            let encoder = Arc::downgrade(encoder);
+            if !encoders.iter().any(|e| e.ptr_eq(&encoder)) {
+                encoders.push(encoder);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns whether the view has a video mix, see [`ObsViewRef::add`].
+    pub fn is_added(&self) -> Result<bool, ObsError> {
+        Ok(self
+            .video
+            .read()
+            .map_err(|_| ObsError::LockError("Failed to acquire read lock on video".into()))?
+            .is_some())
+    }
+
+    /// Returns a pointer to the video output of this view, `None` if it hasn't been added.
+    ///
+    /// # Safety
+    /// The pointer is only valid until the view is removed. Only use this pointer if you REALLY know what you are doing.
+    pub unsafe fn get_video_ptr(&self) -> Result<Option<Sendable<*mut video_output>>, ObsError> {
+        Ok(self
+            .video
+            .read()
+            .map_err(|_| ObsError::LockError("Failed to acquire read lock on video".into()))?
+            .clone())
+    }
+
+    pub fn as_ptr(&self) -> SmartPointerSendable<*mut obs_view_t> {
+        self.view.clone()
+    }
+
+    pub(crate) fn liveness(&self) -> ObsViewLiveness {
+        Arc::downgrade(&self.video)
+    }
+}
+
+#[derive(Debug)]
+struct _ObsViewDropGuard {
+    view: Sendable<*mut obs_view_t>,
+    runtime: ObsRuntime,
+}
+
+impl ObsDropGuard for _ObsViewDropGuard {}
+
+impl_obs_drop!(_ObsViewDropGuard, (view), move || unsafe {
+    // Safety: The view is valid until it is destroyed here. Removing a view
+    // without a mix is a no-op, destroying it releases the channel sources.
+    libobs::obs_view_remove(view.0);
+    libobs::obs_view_destroy(view.0);
+});